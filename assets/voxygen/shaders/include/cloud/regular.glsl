@@ -57,6 +57,10 @@ vec4 cloud_at(vec3 pos, float dist, out vec3 emission) {
         ;
     }
 
+    // Fog and falling rain or snow thicken the air close to the ground
+    float weather_fog = (weather.w * 0.01 + (weather.y + weather.z) * 0.002) * clamp(1.0 - (pos.z - alt) / 500.0, 0.0, 1.0);
+    mist = max(mist, weather_fog);
+
     //vec2 cloud_attr = get_cloud_heights(wind_pos.xy);
     float sun_access = 0.0;
     float moon_access = 0.0;
@@ -19,6 +19,11 @@ layout(std140, set = 0, binding = 0) uniform u_globals {
     uvec4 medium;
     ivec4 select_pos;
     vec4 gamma_exposure;
+    // x - cloud cover
+    // y - rain
+    // z - snow
+    // w - fog
+    vec4 weather;
     float ambiance;
     // 0 - FirstPerson
     // 1 - ThirdPerson
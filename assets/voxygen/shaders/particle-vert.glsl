@@ -72,6 +72,7 @@ const int WATER = 30;
 const int ICE_SPIKES = 31;
 const int DRIP = 32;
 const int TORNADO = 33;
+const int RAIN = 34;
 
 // meters per second squared (acceleration)
 const float earth_gravity = 9.807;
@@ -553,6 +554,14 @@ void main() {
                 spin_in_axis(vec3(rand6, rand7, rand8), percent() * 10 + 3 * rand9)
             );
             break;
+        case RAIN:
+            attr = Attr(
+                inst_dir * percent(),
+                vec3(0.25, 0.25, 4.0),
+                vec4(0.5, 0.6, 0.8, 1),
+                spin_in_axis(vec3(1,0,0),0)
+            );
+            break;
         default:
             attr = Attr(
                 linear_motion(
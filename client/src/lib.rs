@@ -20,20 +20,24 @@ use crate::addr::ConnectionArgs;
 use byteorder::{ByteOrder, LittleEndian};
 use common::{
     character::{CharacterId, CharacterItem},
+    combat_log::CombatLogEntry,
     comp::{
         self,
         chat::{KillSource, KillType},
         group,
         invite::{InviteKind, InviteResponse},
+        item::ItemDesc,
         skills::Skill,
-        slot::Slot,
-        ChatMode, ControlAction, ControlEvent, Controller, ControllerInputs, GroupManip, InputKind,
-        InventoryAction, InventoryEvent, InventoryUpdateEvent, UtteranceKind,
+        slot::{InvSlotId, Slot},
+        BuildManip, ChatMode, ControlAction, ControlEvent, Controller, ControllerInputs,
+        GroupManip, GuildInfo, GuildManip, InputKind, InventoryAction, InventoryEvent,
+        InventoryUpdateEvent, MailManip, StorageManip, UtteranceKind,
     },
     event::{EventBus, LocalEvent},
     grid::Grid,
+    mail::{Mail, MailId},
     outcome::Outcome,
-    recipe::RecipeBook,
+    recipe::{default_repair_recipe_book, RecipeBook},
     resources::{PlayerEntity, TimeOfDay},
     terrain::{
         block::Block, map::MapConfig, neighbors, BiomeKind, SitesKind, SpriteKind, TerrainChunk,
@@ -42,6 +46,7 @@ use common::{
     trade::{PendingTrade, SitePrices, TradeAction, TradeId, TradeResult},
     uid::{Uid, UidAllocator},
     vol::RectVolSize,
+    weather::{Weather, WeatherGrid},
 };
 use common_base::{prof_span, span};
 use common_net::{
@@ -95,6 +100,7 @@ pub enum Event {
     Notification(Notification),
     SetViewDistance(u32),
     Outcome(Outcome),
+    CombatLog(CombatLogEntry),
     CharacterCreated(CharacterId),
     CharacterError(String),
 }
@@ -166,6 +172,14 @@ pub struct Client {
     pending_invites: HashSet<Uid>,
     // The pending trade the client is involved in, and it's id
     pending_trade: Option<(TradeId, PendingTrade, Option<SitePrices>)>,
+    // The contents of the storage container or bank the client has open
+    storage: Option<comp::Inventory>,
+    // The letters waiting for the client's character
+    mail: Vec<Mail>,
+    // The mailbox the client has open
+    mailbox: Option<Vec3<i32>>,
+    // The guild the client's character belongs to
+    guild: Option<GuildInfo>,
 
     network: Option<Network>,
     participant: Option<Participant>,
@@ -308,6 +322,11 @@ impl Client {
                         ))
                     })?;
                 let map_size = map_size_lg.chunks();
+                // The server only sends the weather around the player, which is put into
+                // a grid covering the whole world
+                state
+                    .ecs_mut()
+                    .insert(WeatherGrid::new(map_size.map(u32::from)));
                 let max_height = world_map.max_height;
                 let sea_level = world_map.sea_level;
                 let rgba = world_map.rgba;
@@ -675,6 +694,10 @@ impl Client {
             group_members: HashMap::new(),
             pending_invites: HashSet::new(),
             pending_trade: None,
+            storage: None,
+            mail: Vec::new(),
+            mailbox: None,
+            guild: None,
 
             network: Some(network),
             participant: Some(participant),
@@ -802,9 +825,8 @@ impl Client {
                     | ClientGeneral::RequestSiteInfo(_)
                     | ClientGeneral::UnlockSkillGroup(_)
                     | ClientGeneral::RequestPlayerPhysics { .. }
-                    | ClientGeneral::RequestLossyTerrainCompression { .. } => {
-                        &mut self.in_game_stream
-                    },
+                    | ClientGeneral::RequestLossyTerrainCompression { .. }
+                    | ClientGeneral::RequestCombatLog { .. } => &mut self.in_game_stream,
                     //Only in game, terrain
                     ClientGeneral::TerrainChunkRequest { .. } => &mut self.terrain_stream,
                     //Always possible
@@ -830,6 +852,10 @@ impl Client {
         })
     }
 
+    pub fn request_combat_log(&mut self, enabled: bool) {
+        self.send_msg(ClientGeneral::RequestCombatLog { enabled })
+    }
+
     fn send_msg<S>(&mut self, msg: S)
     where
         S: Into<ClientMsg>,
@@ -1016,18 +1042,25 @@ impl Client {
             .unwrap_or((false, None))
     }
 
+    /// Crafts the recipe `amount` times in a row. Recipes that take time are
+    /// queued, and only go on while the player stays at the crafting
+    /// station.
     pub fn craft_recipe(
         &mut self,
         recipe: &str,
         craft_sprite: Option<(Vec3<i32>, SpriteKind)>,
+        amount: u32,
+        improve: bool,
     ) -> bool {
         let (can_craft, required_sprite) = self.can_craft_recipe(recipe);
         let has_sprite = required_sprite.map_or(true, |s| Some(s) == craft_sprite.map(|(_, s)| s));
-        if can_craft && has_sprite {
+        if can_craft && has_sprite && amount > 0 {
             self.send_msg(ClientGeneral::ControlEvent(ControlEvent::InventoryEvent(
                 InventoryEvent::CraftRecipe {
                     recipe: recipe.to_string(),
                     craft_sprite: craft_sprite.map(|(pos, _)| pos),
+                    amount,
+                    improve,
                 },
             )));
             true
@@ -1036,6 +1069,81 @@ impl Client {
         }
     }
 
+    /// Stops every craft in the crafting queue
+    pub fn cancel_crafting(&mut self) {
+        self.send_msg(ClientGeneral::ControlEvent(ControlEvent::InventoryEvent(
+            InventoryEvent::CancelCrafting,
+        )));
+    }
+
+    /// The crafts the player is working on, if any
+    pub fn crafting_queue(&self) -> Option<comp::CraftingQueue> {
+        self.state
+            .ecs()
+            .read_storage::<comp::CraftingQueue>()
+            .get(self.entity())
+            .filter(|queue| !queue.is_empty())
+            .cloned()
+    }
+
+    /// Find the first worn out item, equipped or in the inventory, that can be
+    /// repaired with the given crafting station and the materials on hand
+    pub fn repairable_item(&self, craft_sprite: Option<SpriteKind>) -> Option<Slot> {
+        let inventories = self.inventories();
+        let inv = inventories.get(self.entity())?;
+        let repair_book = default_repair_recipe_book().read();
+        let can_repair = |item: &comp::Item| {
+            item.durability().map_or(false, |(cur, max)| cur < max)
+                && repair_book.get(item).map_or(false, |recipe| {
+                    recipe
+                        .craft_sprite
+                        .map_or(true, |s| Some(s) == craft_sprite)
+                        && inv.contains_inputs(recipe.inputs()).is_ok()
+                })
+        };
+
+        inv.equipped_items_with_slot()
+            .find(|(_, item)| can_repair(item))
+            .map(|(slot, _)| Slot::Equip(slot))
+            .or_else(|| {
+                inv.slots_with_id()
+                    .find(|(_, slot)| slot.as_ref().map_or(false, |item| can_repair(item)))
+                    .map(|(id, _)| Slot::Inventory(id))
+            })
+    }
+
+    pub fn repair_item(
+        &mut self,
+        slot: Slot,
+        craft_sprite: Option<(Vec3<i32>, SpriteKind)>,
+    ) -> bool {
+        if self.repairable_item(craft_sprite.map(|(_, s)| s)).is_some() {
+            self.send_msg(ClientGeneral::ControlEvent(ControlEvent::InventoryEvent(
+                InventoryEvent::RepairItem {
+                    slot,
+                    craft_sprite: craft_sprite.map(|(pos, _)| pos),
+                },
+            )));
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Sockets one of the gems in the `gem` slot into the item in `slot`
+    pub fn socket_gem(&mut self, gem: InvSlotId, slot: Slot) -> bool {
+        let can_socket = self
+            .inventories()
+            .get(self.entity())
+            .map_or(false, |inv| inv.can_socket(gem, slot));
+        if can_socket {
+            self.send_msg(ClientGeneral::ControlEvent(ControlEvent::InventoryEvent(
+                InventoryEvent::SocketGem { gem, slot },
+            )));
+        }
+        can_socket
+    }
+
     fn update_available_recipes(&mut self) {
         self.available_recipes = self
             .recipe_book
@@ -1096,6 +1204,86 @@ impl Client {
         &self.pending_trade
     }
 
+    pub fn storage(&self) -> Option<&comp::Inventory> { self.storage.as_ref() }
+
+    /// Opens the bank of the character at the strongbox at the given position
+    pub fn open_bank(&mut self, pos: Vec3<i32>) {
+        self.send_msg(ClientGeneral::ControlEvent(ControlEvent::StorageManip(
+            StorageManip::OpenBank(pos),
+        )));
+    }
+
+    pub fn close_storage(&mut self) {
+        self.send_msg(ClientGeneral::ControlEvent(ControlEvent::StorageManip(
+            StorageManip::Close,
+        )));
+    }
+
+    /// Moves an item from the inventory into the open storage
+    pub fn deposit_item(&mut self, slot: InvSlotId) {
+        self.send_msg(ClientGeneral::ControlEvent(ControlEvent::StorageManip(
+            StorageManip::Deposit(slot),
+        )));
+    }
+
+    /// Moves an item from the open storage into the inventory
+    pub fn withdraw_item(&mut self, slot: InvSlotId) {
+        self.send_msg(ClientGeneral::ControlEvent(ControlEvent::StorageManip(
+            StorageManip::Withdraw(slot),
+        )));
+    }
+
+    pub fn mail(&self) -> &[Mail] { &self.mail }
+
+    /// The position of the mailbox the client has open
+    pub fn mailbox(&self) -> Option<Vec3<i32>> { self.mailbox }
+
+    pub fn open_mailbox(&mut self, pos: Vec3<i32>) { self.mailbox = Some(pos); }
+
+    pub fn close_mailbox(&mut self) { self.mailbox = None; }
+
+    /// Sends a letter from the open mailbox, attaching the given amounts of the
+    /// items in the inventory
+    pub fn send_mail(
+        &mut self,
+        recipient: String,
+        message: String,
+        attachments: Vec<(InvSlotId, u32)>,
+    ) {
+        if let Some(mailbox) = self.mailbox {
+            self.send_msg(ClientGeneral::ControlEvent(ControlEvent::MailManip(
+                MailManip::Send {
+                    mailbox,
+                    recipient,
+                    message,
+                    attachments,
+                },
+            )));
+        }
+    }
+
+    /// Collects a letter and its attachments at the open mailbox
+    pub fn claim_mail(&mut self, mail: MailId) {
+        if let Some(mailbox) = self.mailbox {
+            self.send_msg(ClientGeneral::ControlEvent(ControlEvent::MailManip(
+                MailManip::Claim { mailbox, mail },
+            )));
+        }
+    }
+
+    pub fn guild(&self) -> Option<&GuildInfo> { self.guild.as_ref() }
+
+    /// Respecs a skill group, or saves, deletes or switches to a preset of
+    /// skills and equipment
+    pub fn build_manip(&mut self, manip: BuildManip) {
+        self.send_msg(ClientGeneral::ControlEvent(ControlEvent::BuildManip(manip)));
+    }
+
+    /// Founds a guild, or changes the guild the client's character belongs to
+    pub fn guild_manip(&mut self, manip: GuildManip) {
+        self.send_msg(ClientGeneral::ControlEvent(ControlEvent::GuildManip(manip)));
+    }
+
     pub fn send_invite(&mut self, invitee: Uid, kind: InviteKind) {
         self.send_msg(ClientGeneral::ControlEvent(ControlEvent::InitiateInvite(
             invitee, kind,
@@ -1327,6 +1515,18 @@ impl Client {
         }
     }
 
+    /// The weather at the player's position, as last sent by the server.
+    pub fn current_weather(&self) -> Weather {
+        self.position()
+            .map(|pos| {
+                self.state
+                    .ecs()
+                    .read_resource::<WeatherGrid>()
+                    .get_interpolated(pos.xy())
+            })
+            .unwrap_or_default()
+    }
+
     pub fn current_site(&self) -> SitesKind {
         let mut player_alt = 0.0;
         if let Some(position) = self.current::<comp::Pos>() {
@@ -1503,6 +1703,17 @@ impl Client {
             }
         }
 
+        // Crafting progress is only sent when a craft starts or finishes, so move it
+        // along in between
+        if let Some(crafting_queue) = self
+            .state
+            .ecs()
+            .write_storage::<comp::CraftingQueue>()
+            .get_mut(self.entity())
+        {
+            crafting_queue.predict(dt.as_secs_f32());
+        }
+
         // 4) Tick the client's LocalState
         self.state.tick(
             dt,
@@ -1706,11 +1917,27 @@ impl Client {
                     );
                 }
             },
+            ServerGeneral::PlayerListUpdate(PlayerListUpdate::GuildTag(uid, tag)) => {
+                if let Some(character) = self
+                    .player_list
+                    .get_mut(&uid)
+                    .and_then(|player_info| player_info.character.as_mut())
+                {
+                    character.guild_tag = tag;
+                } else {
+                    warn!(
+                        "Received msg to update guild tag for uid {}, but they were not in the \
+                         list or have no character.",
+                        uid
+                    );
+                }
+            },
             ServerGeneral::PlayerListUpdate(PlayerListUpdate::LevelChange(uid, next_level)) => {
                 if let Some(player_info) = self.player_list.get_mut(&uid) {
                     player_info.character = match &player_info.character {
                         Some(character) => Some(msg::CharacterInfo {
                             name: character.name.to_string(),
+                            guild_tag: character.guild_tag.clone(),
                         }),
                         None => {
                             warn!(
@@ -1918,6 +2145,10 @@ impl Client {
             // Cleanup for when the client goes back to the `presence = None`
             ServerGeneral::ExitInGameSuccess => {
                 self.presence = None;
+                self.storage = None;
+                self.mail.clear();
+                self.mailbox = None;
+                self.guild = None;
                 self.clean_state();
             },
             ServerGeneral::InventoryUpdate(inventory, event) => {
@@ -1949,6 +2180,55 @@ impl Client {
 
                 frontend_events.push(Event::InventoryUpdated(event));
             },
+            ServerGeneral::QuestUpdate(quest_log) => {
+                let entity = self.entity();
+                if let Err(e) = self
+                    .state
+                    .ecs_mut()
+                    .write_storage()
+                    .insert(entity, quest_log)
+                {
+                    warn!(
+                        ?e,
+                        "Received a quest update for client entity, but this entity was not \
+                         found... this may be a bug."
+                    );
+                }
+            },
+            ServerGeneral::CraftingUpdate(crafting_queue) => {
+                let entity = self.entity();
+                if let Err(e) = self
+                    .state
+                    .ecs_mut()
+                    .write_storage()
+                    .insert(entity, crafting_queue)
+                {
+                    warn!(
+                        ?e,
+                        "Received a crafting update for client entity, but this entity was not \
+                         found... this may be a bug."
+                    );
+                }
+            },
+            ServerGeneral::PresetUpdate(presets) => {
+                let entity = self.entity();
+                if let Err(e) = self.state.ecs_mut().write_storage().insert(entity, presets) {
+                    warn!(
+                        ?e,
+                        "Received a preset update for client entity, but this entity was not \
+                         found... this may be a bug."
+                    );
+                }
+            },
+            ServerGeneral::StorageUpdate(storage) => {
+                self.storage = storage;
+            },
+            ServerGeneral::MailUpdate(mail) => {
+                self.mail = mail;
+            },
+            ServerGeneral::GuildUpdate(guild) => {
+                self.guild = guild;
+            },
             ServerGeneral::SetViewDistance(vd) => {
                 self.view_distance = Some(vd);
                 frontend_events.push(Event::SetViewDistance(vd));
@@ -1956,6 +2236,9 @@ impl Client {
             ServerGeneral::Outcomes(outcomes) => {
                 frontend_events.extend(outcomes.into_iter().map(Event::Outcome))
             },
+            ServerGeneral::CombatLog(entries) => {
+                frontend_events.extend(entries.into_iter().map(Event::CombatLog))
+            },
             ServerGeneral::Knockback(impulse) => {
                 self.state
                     .ecs()
@@ -1980,6 +2263,14 @@ impl Client {
                     rich.economy = Some(economy);
                 }
             },
+            ServerGeneral::WeatherUpdate(weather) => {
+                if let Some(window) = weather.decompress() {
+                    self.state
+                        .ecs_mut()
+                        .write_resource::<WeatherGrid>()
+                        .apply_window(&window);
+                }
+            },
             _ => unreachable!("Not a in_game message"),
         }
         Ok(())
@@ -2307,6 +2598,7 @@ impl Client {
             comp::ChatType::CommandInfo => message.to_string(),
             comp::ChatType::FactionMeta(_) => message.to_string(),
             comp::ChatType::GroupMeta(_) => message.to_string(),
+            comp::ChatType::GuildMeta(_) => message.to_string(),
             comp::ChatType::Kill(kill_source, victim) => {
                 // Default message formats if no localized message string is set by hud
                 // Needed for cli clients that don't set localization info
@@ -2418,6 +2710,14 @@ impl Client {
             comp::ChatType::Say(uid) => message_format(uid, message, None),
             comp::ChatType::Group(uid, s) => message_format(uid, message, Some(s)),
             comp::ChatType::Faction(uid, s) => message_format(uid, message, Some(s)),
+            comp::ChatType::Guild(uid, guild) => message_format(
+                uid,
+                message,
+                self.guild
+                    .as_ref()
+                    .filter(|info| info.id == *guild)
+                    .map(|info| &info.tag),
+            ),
             comp::ChatType::Region(uid) => message_format(uid, message, None),
             comp::ChatType::World(uid) => message_format(uid, message, None),
             // NPCs can't talk. Should be filtered by hud/mod.rs for voxygen and should be filtered
@@ -2433,7 +2733,7 @@ impl Client {
                     format!("From [{}]: {}", from_alias, message)
                 }
             },
-            comp::ChatType::Meta => message.to_string(),
+            comp::ChatType::Meta | comp::ChatType::CombatLog => message.to_string(),
         }
     }
 }
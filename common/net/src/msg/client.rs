@@ -88,6 +88,10 @@ pub enum ClientGeneral {
     RequestLossyTerrainCompression {
         lossy_terrain_compression: bool,
     },
+    /// Asks the server to send the combat log of the player and their group
+    RequestCombatLog {
+        enabled: bool,
+    },
 }
 
 impl ClientMsg {
@@ -126,7 +130,8 @@ impl ClientMsg {
                         | ClientGeneral::RequestSiteInfo(_)
                         | ClientGeneral::UnlockSkillGroup(_)
                         | ClientGeneral::RequestPlayerPhysics { .. }
-                        | ClientGeneral::RequestLossyTerrainCompression { .. } => {
+                        | ClientGeneral::RequestLossyTerrainCompression { .. }
+                        | ClientGeneral::RequestCombatLog { .. } => {
                             c_type == ClientType::Game && presence.is_some()
                         },
                         //Always possible
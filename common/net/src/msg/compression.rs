@@ -67,6 +67,30 @@ impl<T: for<'a> Deserialize<'a>> CompressedData<T> {
     }
 }
 
+impl<T> CompressedData<T> {
+    /// Raw representation of the compressed data, e.g. for storing it on disk
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.data.len() + 1);
+        bytes.push(self.compressed as u8);
+        bytes.extend_from_slice(&self.data);
+        bytes
+    }
+
+    /// Inverse of [`CompressedData::to_bytes`]
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let (compressed, data) = bytes.split_first()?;
+        Some(CompressedData {
+            data: data.to_vec(),
+            compressed: match compressed {
+                0 => false,
+                1 => true,
+                _ => return None,
+            },
+            _phantom: PhantomData,
+        })
+    }
+}
+
 /// Formula for packing voxel data into a 2d array
 pub trait PackingFormula: Copy {
     fn dimensions(&self, dims: Vec3<u32>) -> (u32, u32);
@@ -5,13 +5,16 @@ use super::{
 use crate::sync;
 use common::{
     character::{self, CharacterItem},
+    combat_log::CombatLogEntry,
     comp::{self, invite::InviteKind, item::MaterialStatManifest},
+    mail::Mail,
     outcome::Outcome,
     recipe::RecipeBook,
     resources::TimeOfDay,
     terrain::{Block, TerrainChunk, TerrainChunkMeta, TerrainChunkSize},
     trade::{PendingTrade, SitePrices, TradeId, TradeResult},
     uid::Uid,
+    weather::WeatherWindow,
 };
 use hashbrown::HashMap;
 use serde::{Deserialize, Serialize};
@@ -151,8 +154,22 @@ pub enum ServerGeneral {
     /// from an ingame state
     ExitInGameSuccess,
     InventoryUpdate(comp::Inventory, comp::InventoryUpdateEvent),
+    /// The quests of the client's character
+    QuestUpdate(comp::QuestLog),
+    /// The crafts the client's character is working on
+    CraftingUpdate(comp::CraftingQueue),
+    /// The skill and equipment presets of the client's character
+    PresetUpdate(comp::LoadoutPresets),
+    /// The contents of the storage the client has open, or `None` once it is
+    /// closed
+    StorageUpdate(Option<comp::Inventory>),
+    /// The letters waiting for the client's character
+    MailUpdate(Vec<Mail>),
+    /// The guild of the client's character, or `None` if they are not in one
+    GuildUpdate(Option<comp::GuildInfo>),
     SetViewDistance(u32),
     Outcomes(Vec<Outcome>),
+    CombatLog(Vec<CombatLogEntry>),
     Knockback(Vec3<f32>),
     // Ingame related AND terrain stream
     TerrainChunkUpdate {
@@ -179,6 +196,8 @@ pub enum ServerGeneral {
     FinishedTrade(TradeResult),
     /// Economic information about sites
     SiteEconomy(EconomyInfo),
+    /// The current weather around the client
+    WeatherUpdate(CompressedData<WeatherWindow>),
 }
 
 impl ServerGeneral {
@@ -204,6 +223,9 @@ pub enum PlayerListUpdate {
     Moderator(Uid, bool),
     Remove(Uid),
     Alias(Uid, String),
+    /// The tag of the guild a player's character joined, or `None` if they
+    /// left it
+    GuildTag(Uid, Option<String>),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -217,6 +239,7 @@ pub struct PlayerInfo {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CharacterInfo {
     pub name: String,
+    pub guild_tag: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -229,6 +252,33 @@ pub enum InviteAnswer {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Notification {
     WaypointSaved,
+    /// A quest was completed, with the title of the quest
+    QuestComplete(String),
+    /// A quest was failed, with the title of the quest
+    QuestFailed(String),
+    /// An equipped item wore out, with the name of the item
+    ItemBroken(String),
+    /// Where the house of the player is, how many days are left until its
+    /// lease runs out, and after how many days offline it goes back on the
+    /// market
+    HouseInfo {
+        pos: Vec2<i32>,
+        lease_days_left: Option<u64>,
+        evict_after_days: Option<u32>,
+    },
+    /// The player has no house, with the price of one
+    NoHouse {
+        price: u32,
+    },
+    /// The player can't own a house, e.g. because they are spectating
+    CannotOwnHouse,
+    /// The house of the player became their waypoint
+    HouseWaypointSaved,
+    /// The player gave up their house
+    HouseAbandoned,
+    /// The house of the player went back on the market, because they were
+    /// away for too long or their lease ran out
+    HouseLost,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -280,14 +330,22 @@ impl ServerMsg {
                         | ServerGeneral::InviteComplete { .. }
                         | ServerGeneral::ExitInGameSuccess
                         | ServerGeneral::InventoryUpdate(_, _)
+                        | ServerGeneral::QuestUpdate(_)
+                        | ServerGeneral::CraftingUpdate(_)
+                        | ServerGeneral::PresetUpdate(_)
+                        | ServerGeneral::StorageUpdate(_)
+                        | ServerGeneral::MailUpdate(_)
+                        | ServerGeneral::GuildUpdate(_)
                         | ServerGeneral::TerrainChunkUpdate { .. }
                         | ServerGeneral::TerrainBlockUpdates(_)
                         | ServerGeneral::SetViewDistance(_)
                         | ServerGeneral::Outcomes(_)
+                        | ServerGeneral::CombatLog(_)
                         | ServerGeneral::Knockback(_)
                         | ServerGeneral::UpdatePendingTrade(_, _, _)
                         | ServerGeneral::FinishedTrade(_)
-                        | ServerGeneral::SiteEconomy(_) => {
+                        | ServerGeneral::SiteEconomy(_)
+                        | ServerGeneral::WeatherUpdate(_) => {
                             c_type == ClientType::Game && presence.is_some()
                         },
                         // Always possible
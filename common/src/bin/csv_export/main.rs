@@ -161,6 +161,7 @@ fn get_tool_kind(kind: &ToolKind) -> String {
         ToolKind::Debug => "Debug".to_string(),
         ToolKind::Farming => "Farming".to_string(),
         ToolKind::Pick => "Pick".to_string(),
+        ToolKind::Fishing => "Fishing".to_string(),
         ToolKind::Natural => "Natural".to_string(),
         ToolKind::Empty => "Empty".to_string(),
     }
@@ -235,6 +236,7 @@ fn loot_table(loot_table: &str) -> Result<(), Box<dyn Error>> {
         "Item",
         "Lower Amount",
         "Upper Amount",
+        "Modifier Pool",
     ])?;
 
     let loot_table = "common.loot_tables.".to_owned() + loot_table;
@@ -253,16 +255,20 @@ fn loot_table(loot_table: &str) -> Result<(), Box<dyn Error>> {
         .to_string();
 
         match item {
-            LootSpec::Item(item) => wtr.write_record(&[&chance, "Item", item, "", ""])?,
+            LootSpec::Item(item) => wtr.write_record(&[&chance, "Item", item, "", "", ""])?,
             LootSpec::ItemQuantity(item, lower, upper) => wtr.write_record(&[
                 &chance,
                 "Item",
                 item,
                 &lower.to_string(),
                 &upper.to_string(),
+                "",
             ])?,
             LootSpec::LootTable(table) => {
-                wtr.write_record(&[&chance, "LootTable", table, "", ""])?
+                wtr.write_record(&[&chance, "LootTable", table, "", "", ""])?
+            },
+            LootSpec::ModifiedItem(item, pool) => {
+                wtr.write_record(&[&chance, "Item", item, "", "", pool])?
             },
         }
     }
@@ -423,7 +423,16 @@ fn loot_table(loot_table: &str) -> Result<(), Box<dyn Error>> {
     for ref record in rdr.records().flatten() {
         let item = match record.get(headers["Kind"]).expect("No loot specifier") {
             "Item" => {
-                if let (Some(Ok(lower)), Some(Ok(upper))) = (
+                if let Some(pool) = headers
+                    .get("Modifier Pool")
+                    .and_then(|i| record.get(*i))
+                    .filter(|pool| !pool.is_empty())
+                {
+                    LootSpec::ModifiedItem(
+                        record.get(headers["Item"]).expect("No item").to_string(),
+                        pool.to_string(),
+                    )
+                } else if let (Some(Ok(lower)), Some(Ok(upper))) = (
                     record.get(headers["Lower Amount"]).map(|a| a.parse()),
                     record.get(headers["Upper Amount"]).map(|a| a.parse()),
                 ) {
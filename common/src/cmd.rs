@@ -55,6 +55,7 @@ pub enum ChatCommand {
     DebugColumn,
     DisconnectAllPlayers,
     DropAll,
+    Duel,
     Dummy,
     Explosion,
     Faction,
@@ -65,9 +66,21 @@ pub enum ChatCommand {
     GroupKick,
     GroupLeave,
     GroupPromote,
+    Guild,
+    GuildCreate,
+    GuildDemote,
+    GuildDisband,
+    GuildInvite,
+    GuildKick,
+    GuildLeave,
+    GuildPromote,
     Health,
     Help,
     Home,
+    House,
+    HouseAbandon,
+    HouseBuy,
+    HouseWaypoint,
     JoinFaction,
     Jump,
     Kick,
@@ -76,12 +89,15 @@ pub enum ChatCommand {
     Kit,
     Lantern,
     Light,
+    MailLog,
     MakeBlock,
     MakeSprite,
     Motd,
     Object,
     PermitBuild,
+    Pet,
     Players,
+    Pvp,
     Region,
     RemoveLights,
     RevokeBuild,
@@ -103,6 +119,7 @@ pub enum ChatCommand {
     Waypoint,
     Whitelist,
     Wiring,
+    Work,
     World,
 }
 
@@ -130,6 +147,12 @@ lazy_static! {
     static ref SKILL_TREES: Vec<String> = vec!["general", "sword", "axe", "hammer", "bow", "staff", "sceptre", "mining"]
         .iter()
         .map(|s| s.to_string())
+        .chain(
+            comp::skill_tree::DATA_SKILL_TREES
+                .sorted_groups()
+                .into_iter()
+                .map(|group| group.id().to_string()),
+        )
         .collect();
     /// TODO: Make this use hot-reloading
     static ref ENTITIES: Vec<String> = {
@@ -173,6 +196,14 @@ lazy_static! {
         .iter()
         .map(|o| o.to_string().to_string())
         .collect();
+    static ref PET_COMMANDS: Vec<String> = vec!["follow", "stay", "attack"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    static ref ZONE_KINDS: Vec<String> = vec!["safe", "pvp", "no_pvp"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
     static ref TIMES: Vec<String> = vec![
         "midnight", "night", "dawn", "morning", "day", "noon", "dusk"
     ]
@@ -357,6 +388,11 @@ impl ChatCommand {
                 "Drops all your items on the ground",
                 Some(Moderator),
             ),
+            ChatCommand::Duel => cmd(
+                vec![PlayerName(Required)],
+                "Challenge a player to a duel",
+                None,
+            ),
             ChatCommand::Dummy => cmd(vec![], "Spawns a training dummy", Some(Admin)),
             ChatCommand::Explosion => cmd(
                 vec![Float("radius", 5.0, Required)],
@@ -402,6 +438,34 @@ impl ChatCommand {
                 "Promote a player to group leader",
                 None,
             ),
+            ChatCommand::Guild => cmd(vec![Message(Optional)], "Send messages to your guild", None),
+            ChatCommand::GuildCreate => cmd(
+                vec![Any("tag", Required), Message(Required)],
+                "Found a guild with the given tag and name",
+                None,
+            ),
+            ChatCommand::GuildDemote => cmd(
+                vec![Any("character", Required)],
+                "Demote an officer of your guild to member",
+                None,
+            ),
+            ChatCommand::GuildDisband => cmd(vec![], "Disband your guild", None),
+            ChatCommand::GuildInvite => cmd(
+                vec![PlayerName(Required)],
+                "Invite a player to join your guild",
+                None,
+            ),
+            ChatCommand::GuildKick => cmd(
+                vec![Any("character", Required)],
+                "Remove a character from your guild",
+                None,
+            ),
+            ChatCommand::GuildLeave => cmd(vec![], "Leave your guild", None),
+            ChatCommand::GuildPromote => cmd(
+                vec![Any("character", Required)],
+                "Promote a member of your guild. Promoting an officer makes them the leader",
+                None,
+            ),
             ChatCommand::Health => cmd(
                 vec![Integer("hp", 100, Required)],
                 "Set your current health",
@@ -413,6 +477,14 @@ impl ChatCommand {
                 None,
             ),
             ChatCommand::Home => cmd(vec![], "Return to the home town", None),
+            ChatCommand::House => cmd(vec![], "Show where your house is", None),
+            ChatCommand::HouseAbandon => cmd(
+                vec![],
+                "Give up your house, it can then be bought by someone else",
+                None,
+            ),
+            ChatCommand::HouseBuy => cmd(vec![], "Buy a house from the closest NPC", None),
+            ChatCommand::HouseWaypoint => cmd(vec![], "Make your house your waypoint", None),
             ChatCommand::JoinFaction => ChatCommandData::new(
                 vec![Any("faction", Optional)],
                 "Join/leave the specified faction",
@@ -462,6 +534,12 @@ impl ChatCommand {
                 "Spawn entity with light",
                 Some(Admin),
             ),
+            ChatCommand::MailLog => cmd(
+                vec![Any("character", Optional)],
+                "Lists recent letters that were sent, returned or collected, optionally only \
+                 those of a character",
+                Some(Moderator),
+            ),
             ChatCommand::MakeBlock => cmd(
                 vec![Enum("block", BLOCK_KINDS.clone(), Required)],
                 "Make a block at your location",
@@ -483,7 +561,18 @@ impl ChatCommand {
                 "Grants player a bounded box they can build in",
                 Some(Admin),
             ),
+            ChatCommand::Pet => cmd(
+                vec![Enum("command", PET_COMMANDS.clone(), Required)],
+                "Tell your pets to follow you, stay where they are, or attack what you are \
+                 looking at",
+                None,
+            ),
             ChatCommand::Players => cmd(vec![], "Lists players currently online", None),
+            ChatCommand::Pvp => cmd(
+                vec![Boolean("enabled", "true".to_string(), Optional)],
+                "Turn PvP on or off for your character, if the server lets players choose",
+                None,
+            ),
             ChatCommand::RemoveLights => cmd(
                 vec![Float("radius", 20.0, Optional)],
                 "Removes all lights spawned by players",
@@ -505,8 +594,11 @@ impl ChatCommand {
                 None,
             ),
             ChatCommand::Safezone => cmd(
-                vec![Float("range", 100.0, Optional)],
-                "Creates a safezone",
+                vec![
+                    Float("range", 100.0, Optional),
+                    Enum("kind", ZONE_KINDS.clone(), Optional),
+                ],
+                "Creates a safezone, or a zone where players can or can't fight each other",
                 Some(Moderator),
             ),
             ChatCommand::Say => cmd(
@@ -589,6 +681,7 @@ impl ChatCommand {
                 Some(Admin),
             ),
             ChatCommand::Wiring => cmd(vec![], "Create wiring element", Some(Admin)),
+            ChatCommand::Work => cmd(vec![], "Ask the closest NPC for work", None),
             ChatCommand::Whitelist => cmd(
                 vec![Any("add/remove", Required), Any("username", Required)],
                 "Adds/removes username to whitelist",
@@ -618,6 +711,7 @@ impl ChatCommand {
             ChatCommand::DebugColumn => "debug_column",
             ChatCommand::DisconnectAllPlayers => "disconnect_all_players",
             ChatCommand::DropAll => "dropall",
+            ChatCommand::Duel => "duel",
             ChatCommand::Dummy => "dummy",
             ChatCommand::Explosion => "explosion",
             ChatCommand::Faction => "faction",
@@ -628,10 +722,22 @@ impl ChatCommand {
             ChatCommand::GroupKick => "group_kick",
             ChatCommand::GroupPromote => "group_promote",
             ChatCommand::GroupLeave => "group_leave",
+            ChatCommand::Guild => "guild",
+            ChatCommand::GuildCreate => "guild_create",
+            ChatCommand::GuildDemote => "guild_demote",
+            ChatCommand::GuildDisband => "guild_disband",
+            ChatCommand::GuildInvite => "guild_invite",
+            ChatCommand::GuildKick => "guild_kick",
+            ChatCommand::GuildLeave => "guild_leave",
+            ChatCommand::GuildPromote => "guild_promote",
             ChatCommand::Health => "health",
             ChatCommand::JoinFaction => "join_faction",
             ChatCommand::Help => "help",
             ChatCommand::Home => "home",
+            ChatCommand::House => "house",
+            ChatCommand::HouseAbandon => "house_abandon",
+            ChatCommand::HouseBuy => "house_buy",
+            ChatCommand::HouseWaypoint => "house_waypoint",
             ChatCommand::Jump => "jump",
             ChatCommand::Kick => "kick",
             ChatCommand::Kill => "kill",
@@ -639,12 +745,15 @@ impl ChatCommand {
             ChatCommand::KillNpcs => "kill_npcs",
             ChatCommand::Lantern => "lantern",
             ChatCommand::Light => "light",
+            ChatCommand::MailLog => "mail_log",
             ChatCommand::MakeBlock => "make_block",
             ChatCommand::MakeSprite => "make_sprite",
             ChatCommand::Motd => "motd",
             ChatCommand::Object => "object",
             ChatCommand::PermitBuild => "permit_build",
+            ChatCommand::Pet => "pet",
             ChatCommand::Players => "players",
+            ChatCommand::Pvp => "pvp",
             ChatCommand::Region => "region",
             ChatCommand::RemoveLights => "remove_lights",
             ChatCommand::RevokeBuild => "revoke_build",
@@ -665,6 +774,7 @@ impl ChatCommand {
             ChatCommand::Version => "version",
             ChatCommand::Waypoint => "waypoint",
             ChatCommand::Wiring => "wiring",
+            ChatCommand::Work => "work",
             ChatCommand::Whitelist => "whitelist",
             ChatCommand::World => "world",
         }
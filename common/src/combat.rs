@@ -1,24 +1,26 @@
 use crate::comp::buff::{Buff, BuffChange, BuffData, BuffKind, BuffSource};
 #[cfg(not(target_arch = "wasm32"))]
 use crate::{
+    combat_log::CombatLogKind,
     comp::{
         inventory::{
             item::{
                 armor::Protection,
                 tool::{self, Tool, ToolKind},
-                Item, ItemDesc, ItemKind, MaterialStatManifest,
+                Item, ItemDesc, ItemKind, ItemModifier, MaterialStatManifest,
             },
-            slot::EquipSlot,
+            slot::{ArmorSlot, EquipSlot},
         },
         poise::PoiseChange,
         skills::SkillGroupKind,
-        Body, CharacterState, Combo, Energy, EnergyChange, EnergySource, Health, HealthChange,
-        HealthSource, Inventory, Ori, SkillSet, Stats,
+        Alignment, Body, CharacterState, Combo, Duel, Energy, EnergyChange, EnergySource, Health,
+        HealthChange, HealthSource, Inventory, Ori, Player, Pos, PvpFlag, PvpZone, PvpZoneKind,
+        SkillSet, Stats,
     },
     event::ServerEvent,
     outcome::Outcome,
     states::utils::StageSection,
-    uid::Uid,
+    uid::{Uid, UidAllocator},
     util::Dir,
 };
 
@@ -28,7 +30,7 @@ use rand::{thread_rng, Rng};
 use serde::{Deserialize, Serialize};
 
 #[cfg(not(target_arch = "wasm32"))]
-use specs::Entity as EcsEntity;
+use specs::{saveload::MarkerAllocator, Entity as EcsEntity, Join, ReadStorage};
 #[cfg(not(target_arch = "wasm32"))]
 use std::time::Duration;
 #[cfg(not(target_arch = "wasm32"))] use vek::*;
@@ -71,6 +73,145 @@ pub struct TargetInfo<'a> {
     pub char_state: Option<&'a CharacterState>,
 }
 
+/// Whether players can hurt each other outside of duels and PvP zones
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PvpMode {
+    /// Players can never hurt each other
+    Pve,
+    /// Players can always hurt each other
+    Pvp,
+    /// Players can hurt each other if both of them turned PvP on
+    OptIn,
+}
+
+impl Default for PvpMode {
+    fn default() -> Self { PvpMode::Pvp }
+}
+
+/// Decides whether one entity is allowed to harm another, from the PvP mode of
+/// the server, the PvP flags of the players, their duels and the PvP zones
+/// they stand in. Only fights between players are restricted, which includes
+/// the pets and minions of players, as they fight on behalf of their owner.
+#[cfg(not(target_arch = "wasm32"))]
+pub struct HarmPolicy<'a, 'b> {
+    pub mode: PvpMode,
+    pub time: f64,
+    pub players: &'a ReadStorage<'b, Player>,
+    pub pvp_flags: &'a ReadStorage<'b, PvpFlag>,
+    pub duels: &'a ReadStorage<'b, Duel>,
+    pub pvp_zones: &'a ReadStorage<'b, PvpZone>,
+    pub positions: &'a ReadStorage<'b, Pos>,
+    pub uids: &'a ReadStorage<'b, Uid>,
+    pub alignments: &'a ReadStorage<'b, Alignment>,
+    pub uid_allocator: &'a UidAllocator,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl<'a, 'b> HarmPolicy<'a, 'b> {
+    pub fn may_harm(&self, attacker: Option<EcsEntity>, target: EcsEntity) -> bool {
+        let attacker = match attacker {
+            Some(attacker) if attacker != target => attacker,
+            _ => return true,
+        };
+        let (attacker_owner, target_owner) = (self.owner_of(attacker), self.owner_of(target));
+        if attacker_owner == target_owner
+            || !self.players.contains(attacker_owner)
+            || !self.players.contains(target_owner)
+        {
+            return true;
+        }
+
+        if self.in_duel(attacker_owner, target_owner) && self.in_duel(target_owner, attacker_owner)
+        {
+            return true;
+        }
+
+        // The zones the fight happens in matter, not the ones the owners are in
+        match self.zone_kind(attacker).max(self.zone_kind(target)) {
+            Some(PvpZoneKind::NoPvp) => false,
+            Some(PvpZoneKind::Pvp) => true,
+            None => match self.mode {
+                PvpMode::Pve => false,
+                PvpMode::Pvp => true,
+                PvpMode::OptIn => {
+                    let flagged = |entity| self.pvp_flags.get(entity).map_or(false, |f| f.enabled);
+                    flagged(attacker_owner) && flagged(target_owner)
+                },
+            },
+        }
+    }
+
+    /// The entity that the given entity fights on behalf of, which is the
+    /// owner of pets and minions and the entity itself otherwise
+    fn owner_of(&self, entity: EcsEntity) -> EcsEntity {
+        match self.alignments.get(entity) {
+            Some(Alignment::Owned(owner)) if self.uids.get(entity) != Some(owner) => self
+                .uid_allocator
+                .retrieve_entity_internal(owner.0)
+                .unwrap_or(entity),
+            _ => entity,
+        }
+    }
+
+    fn in_duel(&self, entity: EcsEntity, opponent: EcsEntity) -> bool {
+        self.duels
+            .get(entity)
+            .zip(self.uids.get(opponent))
+            .map_or(false, |(duel, uid)| {
+                duel.opponent == *uid && duel.is_active(self.time)
+            })
+    }
+
+    /// The strictest PvP zone the entity is standing in, if any
+    fn zone_kind(&self, entity: EcsEntity) -> Option<PvpZoneKind> {
+        let pos = self.positions.get(entity)?;
+        (self.positions, self.pvp_zones)
+            .join()
+            .filter(|(zone_pos, zone)| zone_pos.0.distance_squared(pos.0) <= zone.radius.powi(2))
+            .map(|(_, zone)| zone.kind)
+            .max()
+    }
+}
+
+/// The equipment that wears down when its owner lands a hit
+#[cfg(not(target_arch = "wasm32"))]
+const WEAPON_SLOTS: [EquipSlot; 2] = [EquipSlot::ActiveMainhand, EquipSlot::ActiveOffhand];
+
+/// The equipment that wears down when its owner is hit
+#[cfg(not(target_arch = "wasm32"))]
+const ARMOR_SLOTS: [EquipSlot; 7] = [
+    EquipSlot::Armor(ArmorSlot::Head),
+    EquipSlot::Armor(ArmorSlot::Shoulders),
+    EquipSlot::Armor(ArmorSlot::Chest),
+    EquipSlot::Armor(ArmorSlot::Hands),
+    EquipSlot::Armor(ArmorSlot::Belt),
+    EquipSlot::Armor(ArmorSlot::Legs),
+    EquipSlot::Armor(ArmorSlot::Feet),
+];
+
+/// Emits an event to wear down the items in the given slots, if any of them
+/// can wear out
+#[cfg(not(target_arch = "wasm32"))]
+fn wear_equipment(
+    entity: EcsEntity,
+    inventory: Option<&Inventory>,
+    slots: &[EquipSlot],
+    emit: &mut impl FnMut(ServerEvent),
+) {
+    let slots = slots
+        .iter()
+        .copied()
+        .filter(|slot| {
+            inventory
+                .and_then(|inv| inv.equipped(*slot))
+                .map_or(false, |item| item.durability().is_some())
+        })
+        .collect::<Vec<_>>();
+    if !slots.is_empty() {
+        emit(ServerEvent::WearEquipment { entity, slots });
+    }
+}
+
 #[cfg(not(target_arch = "wasm32"))]
 #[derive(Clone, Debug, Serialize, Deserialize)] // TODO: Yeet clone derive
 pub struct Attack {
@@ -166,6 +307,9 @@ impl Attack {
         target: TargetInfo,
         dir: Dir,
         target_dodging: bool,
+        // Whether the attacker is allowed to harm the target, see `HarmPolicy`. Attacks that
+        // aren't allowed to harm only apply their effects meant for the attacker's group.
+        may_harm: bool,
         // Currently just modifies damage, maybe look into modifying strength of other effects?
         strength_modifier: f32,
         attack_source: AttackSource,
@@ -173,11 +317,33 @@ impl Attack {
         mut emit_outcome: impl FnMut(Outcome),
     ) {
         let is_crit = thread_rng().gen::<f32>() < self.crit_chance;
+        let attacker_inventory = attacker.and_then(|a| a.inventory);
+        let weapon = attacker_inventory.and_then(|inv| inv.equipped(EquipSlot::ActiveMainhand));
+        // Broken weapons don't hit as hard. Attacks don't record which hand they were
+        // made with, so a broken weapon in either active hand weakens them (armour is
+        // handled in `Damage::compute_damage_reduction`).
+        let strength_modifier = strength_modifier
+            * attacker_inventory.map_or(1.0, |inv| {
+                WEAPON_SLOTS
+                    .iter()
+                    .filter_map(|slot| inv.equipped(*slot))
+                    .map(Item::effectiveness)
+                    .product::<f32>()
+            });
+        // The modifiers of the weapon add their effects to every attack that deals
+        // damage
+        let weapon_effects = weapon.map_or_else(Vec::new, |item| {
+            item.all_modifiers()
+                .into_iter()
+                .filter_map(ItemModifier::attack_effect)
+                .collect()
+        });
         let mut accumulated_damage = 0.0;
         for damage in self
             .damages
             .iter()
             .filter(|d| d.target.map_or(true, |t| t == target_group))
+            .filter(|d| may_harm || d.target == Some(GroupTarget::InGroup))
             .filter(|d| !(matches!(d.target, Some(GroupTarget::OutOfGroup)) && target_dodging))
         {
             let damage_reduction = Attack::compute_damage_reduction(
@@ -202,6 +368,24 @@ impl Attack {
                     entity: target.entity,
                     change,
                 });
+                let unmitigated = damage.damage.calculate_health_change(
+                    0.0,
+                    attacker.map(|a| a.uid),
+                    is_crit,
+                    self.crit_multiplier,
+                    strength_modifier,
+                );
+                emit(ServerEvent::CombatLog {
+                    source: attacker.map(|a| a.uid),
+                    target: target.entity,
+                    kind: CombatLogKind::Damage {
+                        source: damage.damage.source,
+                        kind: Some(damage.damage.kind),
+                        crit: is_crit,
+                        amount: (-change.amount).max(0) as u32,
+                        mitigated: (change.amount - unmitigated.amount).max(0) as u32,
+                    },
+                });
                 for effect in damage.effects.iter() {
                     match effect {
                         CombatEffect::Knockback(kb) => {
@@ -288,10 +472,25 @@ impl Attack {
                 }
             }
         }
+        // Landing a hit wears down the weapons of the attacker and the armour of the
+        // target
+        if accumulated_damage > 0.0 {
+            if let Some(attacker) = attacker {
+                wear_equipment(
+                    attacker.entity,
+                    attacker.inventory,
+                    &WEAPON_SLOTS,
+                    &mut emit,
+                );
+            }
+            wear_equipment(target.entity, target.inventory, &ARMOR_SLOTS, &mut emit);
+        }
         for effect in self
             .effects
             .iter()
+            .chain(weapon_effects.iter())
             .filter(|e| e.target.map_or(true, |t| t == target_group))
+            .filter(|e| may_harm || e.target == Some(GroupTarget::InGroup))
             .filter(|e| !(matches!(e.target, Some(GroupTarget::OutOfGroup)) && target_dodging))
         {
             if effect.requirements.iter().all(|req| match req {
@@ -543,13 +742,13 @@ impl Damage {
                 .equipped_items()
                 .filter_map(|item| {
                     if let ItemKind::Armor(armor) = &item.kind() {
-                        Some(armor.protection())
+                        Some((armor.protection(), item.effectiveness()))
                     } else {
                         None
                     }
                 })
-                .map(|protection| match protection {
-                    Protection::Normal(protection) => Some(protection),
+                .map(|(protection, effectiveness)| match protection {
+                    Protection::Normal(protection) => Some(protection * effectiveness),
                     Protection::Invincible => None,
                 })
                 .sum::<Option<f32>>();
@@ -820,7 +1019,8 @@ pub fn weapon_rating<T: ItemDesc>(item: &T, msm: &MaterialStatManifest) -> f32 {
     const POISE_WEIGHT: f32 = 1.0;
 
     if let ItemKind::Tool(tool) = item.kind() {
-        let stats = tool::Stats::from((msm, item.components(), tool));
+        let stats =
+            tool::Stats::from((msm, item.components(), tool)).with_modifiers(&item.all_modifiers());
 
         // TODO: Look into changing the 0.5 to reflect armor later maybe?
         // Since it is only for weapon though, it probably makes sense to leave
@@ -961,3 +1161,144 @@ pub fn compute_max_energy_mod(energy: &Energy, inventory: Option<&Inventory>) ->
     // correctly.
     energy_increase as f32 / energy.base_max() as f32
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use specs::{Builder, World, WorldExt};
+
+    fn world() -> World {
+        let mut world = World::new();
+        world.register::<Uid>();
+        world.register::<Player>();
+        world.register::<Alignment>();
+        world.register::<PvpFlag>();
+        world.register::<Duel>();
+        world.register::<PvpZone>();
+        world.register::<Pos>();
+        world.insert(UidAllocator::new());
+        world
+    }
+
+    fn spawn(world: &mut World, player: bool, owner: Option<Uid>) -> (EcsEntity, Uid) {
+        let mut builder = world.create_entity().with(Pos(Vec3::zero()));
+        if player {
+            builder = builder.with(Player::new("Player".to_owned(), uuid::Uuid::nil()));
+        }
+        let entity = builder.build();
+        let uid = world
+            .write_resource::<UidAllocator>()
+            .allocate(entity, None);
+        world.write_storage::<Uid>().insert(entity, uid).unwrap();
+        // Like players, entities that are not owned by anyone else own themselves
+        let alignment = Alignment::Owned(owner.unwrap_or(uid));
+        world
+            .write_storage::<Alignment>()
+            .insert(entity, alignment)
+            .unwrap();
+        (entity, uid)
+    }
+
+    fn may_harm(world: &World, mode: PvpMode, attacker: EcsEntity, target: EcsEntity) -> bool {
+        HarmPolicy {
+            mode,
+            time: 0.0,
+            players: &world.read_storage(),
+            pvp_flags: &world.read_storage(),
+            duels: &world.read_storage(),
+            pvp_zones: &world.read_storage(),
+            positions: &world.read_storage(),
+            uids: &world.read_storage(),
+            alignments: &world.read_storage(),
+            uid_allocator: &world.read_resource(),
+        }
+        .may_harm(Some(attacker), target)
+    }
+
+    #[test]
+    fn pvp_modes_restrict_players() {
+        let mut world = world();
+        let (a, _) = spawn(&mut world, true, None);
+        let (b, _) = spawn(&mut world, true, None);
+
+        assert!(!may_harm(&world, PvpMode::Pve, a, b));
+        assert!(may_harm(&world, PvpMode::Pvp, a, b));
+        assert!(!may_harm(&world, PvpMode::OptIn, a, b));
+
+        let flag = PvpFlag {
+            enabled: true,
+            changed_at: 0.0,
+        };
+        world.write_storage::<PvpFlag>().insert(a, flag).unwrap();
+        assert!(!may_harm(&world, PvpMode::OptIn, a, b));
+        world.write_storage::<PvpFlag>().insert(b, flag).unwrap();
+        assert!(may_harm(&world, PvpMode::OptIn, a, b));
+    }
+
+    #[test]
+    fn npcs_are_not_restricted() {
+        let mut world = world();
+        let (player, _) = spawn(&mut world, true, None);
+        let (npc, _) = spawn(&mut world, false, None);
+
+        assert!(may_harm(&world, PvpMode::Pve, player, npc));
+        assert!(may_harm(&world, PvpMode::Pve, npc, player));
+    }
+
+    #[test]
+    fn pets_fight_on_behalf_of_their_owner() {
+        let mut world = world();
+        let (a, a_uid) = spawn(&mut world, true, None);
+        let (b, _) = spawn(&mut world, true, None);
+        let (pet, _) = spawn(&mut world, false, Some(a_uid));
+
+        assert!(!may_harm(&world, PvpMode::Pve, pet, b));
+        assert!(!may_harm(&world, PvpMode::Pve, b, pet));
+        assert!(may_harm(&world, PvpMode::Pvp, pet, b));
+        // Friendly fire isn't a matter of PvP
+        assert!(may_harm(&world, PvpMode::Pve, pet, a));
+    }
+
+    #[test]
+    fn duels_allow_harm() {
+        let mut world = world();
+        let (a, a_uid) = spawn(&mut world, true, None);
+        let (b, b_uid) = spawn(&mut world, true, None);
+        let (pet, _) = spawn(&mut world, false, Some(a_uid));
+
+        let duel = |opponent| Duel {
+            opponent,
+            ends_at: 1.0,
+        };
+        world
+            .write_storage::<Duel>()
+            .insert(a, duel(b_uid))
+            .unwrap();
+        assert!(!may_harm(&world, PvpMode::Pve, a, b));
+        world
+            .write_storage::<Duel>()
+            .insert(b, duel(a_uid))
+            .unwrap();
+        assert!(may_harm(&world, PvpMode::Pve, a, b));
+        assert!(may_harm(&world, PvpMode::Pve, pet, b));
+    }
+
+    #[test]
+    fn zones_override_the_pvp_mode() {
+        let mut world = world();
+        let (a, _) = spawn(&mut world, true, None);
+        let (b, _) = spawn(&mut world, true, None);
+        let (zone, _) = spawn(&mut world, false, None);
+
+        let set_zone = |kind| {
+            world
+                .write_storage::<PvpZone>()
+                .insert(zone, PvpZone { kind, radius: 10.0 })
+                .unwrap();
+        };
+        set_zone(PvpZoneKind::Pvp);
+        assert!(may_harm(&world, PvpMode::Pve, a, b));
+        set_zone(PvpZoneKind::NoPvp);
+        assert!(!may_harm(&world, PvpMode::Pvp, a, b));
+    }
+}
@@ -0,0 +1,57 @@
+use crate::{
+    combat::{DamageKind, DamageSource},
+    comp::BuffKind,
+    states::utils::AbilityInfo,
+    uid::Uid,
+};
+use serde::{Deserialize, Serialize};
+
+/// A single line of the combat log. The server only sends these to players who
+/// asked for them, and only about themselves and their group.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CombatLogEntry {
+    pub time: f64,
+    /// Who caused it, if anyone
+    pub source: Option<Uid>,
+    pub target: Uid,
+    /// The ability the source was using when this happened, if any
+    pub ability: Option<AbilityInfo>,
+    pub kind: CombatLogKind,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum CombatLogKind {
+    Damage {
+        source: DamageSource,
+        /// None for damage that doesn't come from an attack, e.g. bleeding
+        kind: Option<DamageKind>,
+        crit: bool,
+        /// Damage dealt after armour, blocking and other reductions
+        amount: u32,
+        /// Damage prevented by armour, blocking and other reductions
+        mitigated: u32,
+    },
+    Heal {
+        amount: u32,
+    },
+    BuffApplied(BuffKind),
+    BuffRemoved(BuffKind),
+}
+
+impl CombatLogKind {
+    /// Damage dealt, for damage meters
+    pub fn damage(&self) -> u32 {
+        match self {
+            CombatLogKind::Damage { amount, .. } => *amount,
+            _ => 0,
+        }
+    }
+
+    /// Health restored, for healing meters
+    pub fn healing(&self) -> u32 {
+        match self {
+            CombatLogKind::Heal { amount } => *amount,
+            _ => 0,
+        }
+    }
+}
@@ -3,7 +3,8 @@ use crate::{
     combat::{self, CombatEffect, DamageKind, Knockback},
     comp::{
         self, aura, beam, buff, inventory::item::tool::ToolKind, projectile::ProjectileConstructor,
-        skills, Body, CharacterState, EnergySource, LightEmitter, StateUpdate,
+        skill_tree::AbilityField, skills, Body, CharacterState, EnergySource, LightEmitter,
+        StateUpdate,
     },
     states::{
         behavior::JoinData,
@@ -303,6 +304,12 @@ pub enum CharacterAbility {
         summon_distance: (f32, f32),
         sparseness: f64,
     },
+    Fishing {
+        cast_duration: f32,
+        bite_window: f32,
+        reel_duration: f32,
+        range: f32,
+    },
 }
 
 impl Default for CharacterAbility {
@@ -375,7 +382,8 @@ impl CharacterAbility {
             | CharacterAbility::BasicBeam { .. }
             | CharacterAbility::Blink { .. }
             | CharacterAbility::BasicSummon { .. }
-            | CharacterAbility::SpriteSummon { .. } => true,
+            | CharacterAbility::SpriteSummon { .. }
+            | CharacterAbility::Fishing { .. } => true,
         }
     }
 
@@ -648,6 +656,14 @@ impl CharacterAbility {
                 *cast_duration /= speed;
                 *recover_duration /= speed;
             },
+            Fishing {
+                ref mut cast_duration,
+                ref mut reel_duration,
+                ..
+            } => {
+                *cast_duration /= speed;
+                *reel_duration /= speed;
+            },
         }
         self
     }
@@ -680,7 +696,8 @@ impl CharacterAbility {
             | ComboMelee { .. }
             | Blink { .. }
             | BasicSummon { .. }
-            | SpriteSummon { .. } => 0,
+            | SpriteSummon { .. }
+            | Fishing { .. } => 0,
         }
     }
 
@@ -1241,8 +1258,181 @@ impl CharacterAbility {
             },
             Some(_) => {},
         }
+        // Skills declared in the data skill trees
+        for (skill, level) in skillset.skills.iter() {
+            if let Skill::Data(skill) = skill {
+                for modifier in skill.def().modifiers.iter().filter(|m| m.tool == tool) {
+                    if let Some(field) = self.field_mut(modifier.field) {
+                        *field *= modifier.multiplier.powi(level.unwrap_or(1).into());
+                    }
+                }
+            }
+        }
         self
     }
+
+    /// Returns the field of the ability that a data skill modifier scales, if
+    /// the ability has it
+    pub fn field_mut(&mut self, field: AbilityField) -> Option<&mut f32> {
+        use CharacterAbility::*;
+        match field {
+            AbilityField::EnergyCost => match self {
+                BasicMelee { energy_cost, .. }
+                | BasicRanged { energy_cost, .. }
+                | RepeaterRanged { energy_cost, .. }
+                | DashMelee { energy_cost, .. }
+                | Roll { energy_cost, .. }
+                | LeapMelee { energy_cost, .. }
+                | SpinMelee { energy_cost, .. }
+                | ChargedMelee { energy_cost, .. }
+                | ChargedRanged { energy_cost, .. }
+                | Shockwave { energy_cost, .. }
+                | HealingBeam { energy_cost, .. }
+                | BasicAura { energy_cost, .. }
+                | BasicBlock { energy_cost, .. }
+                | SelfBuff { energy_cost, .. } => Some(energy_cost),
+                _ => None,
+            },
+            AbilityField::BaseDamage => match self {
+                BasicMelee { base_damage, .. }
+                | DashMelee { base_damage, .. }
+                | LeapMelee { base_damage, .. }
+                | SpinMelee { base_damage, .. } => Some(base_damage),
+                _ => None,
+            },
+            AbilityField::Range => match self {
+                BasicMelee { range, .. }
+                | DashMelee { range, .. }
+                | LeapMelee { range, .. }
+                | SpinMelee { range, .. }
+                | ChargedMelee { range, .. }
+                | BasicBeam { range, .. }
+                | BasicAura { range, .. }
+                | HealingBeam { range, .. } => Some(range),
+                _ => None,
+            },
+            AbilityField::BuildupDuration => match self {
+                BasicMelee {
+                    buildup_duration, ..
+                }
+                | BasicRanged {
+                    buildup_duration, ..
+                }
+                | RepeaterRanged {
+                    buildup_duration, ..
+                }
+                | DashMelee {
+                    buildup_duration, ..
+                }
+                | BasicBlock {
+                    buildup_duration, ..
+                }
+                | Roll {
+                    buildup_duration, ..
+                }
+                | LeapMelee {
+                    buildup_duration, ..
+                }
+                | SpinMelee {
+                    buildup_duration, ..
+                }
+                | ChargedRanged {
+                    buildup_duration, ..
+                }
+                | Shockwave {
+                    buildup_duration, ..
+                }
+                | BasicBeam {
+                    buildup_duration, ..
+                }
+                | BasicAura {
+                    buildup_duration, ..
+                }
+                | HealingBeam {
+                    buildup_duration, ..
+                }
+                | Blink {
+                    buildup_duration, ..
+                }
+                | BasicSummon {
+                    buildup_duration, ..
+                }
+                | SelfBuff {
+                    buildup_duration, ..
+                }
+                | SpriteSummon {
+                    buildup_duration, ..
+                } => Some(buildup_duration),
+                _ => None,
+            },
+            AbilityField::SwingDuration => match self {
+                BasicMelee { swing_duration, .. }
+                | DashMelee { swing_duration, .. }
+                | LeapMelee { swing_duration, .. }
+                | SpinMelee { swing_duration, .. }
+                | ChargedMelee { swing_duration, .. }
+                | Shockwave { swing_duration, .. } => Some(swing_duration),
+                _ => None,
+            },
+            AbilityField::RecoverDuration => match self {
+                BasicMelee {
+                    recover_duration, ..
+                }
+                | BasicRanged {
+                    recover_duration, ..
+                }
+                | RepeaterRanged {
+                    recover_duration, ..
+                }
+                | DashMelee {
+                    recover_duration, ..
+                }
+                | BasicBlock {
+                    recover_duration, ..
+                }
+                | Roll {
+                    recover_duration, ..
+                }
+                | LeapMelee {
+                    recover_duration, ..
+                }
+                | SpinMelee {
+                    recover_duration, ..
+                }
+                | ChargedMelee {
+                    recover_duration, ..
+                }
+                | ChargedRanged {
+                    recover_duration, ..
+                }
+                | Shockwave {
+                    recover_duration, ..
+                }
+                | BasicBeam {
+                    recover_duration, ..
+                }
+                | BasicAura {
+                    recover_duration, ..
+                }
+                | HealingBeam {
+                    recover_duration, ..
+                }
+                | Blink {
+                    recover_duration, ..
+                }
+                | BasicSummon {
+                    recover_duration, ..
+                }
+                | SelfBuff {
+                    recover_duration, ..
+                }
+                | SpriteSummon {
+                    recover_duration, ..
+                } => Some(recover_duration),
+                _ => None,
+            },
+        }
+    }
 }
 
 impl From<(&CharacterAbility, AbilityInfo)> for CharacterState {
@@ -1827,6 +2017,26 @@ impl From<(&CharacterAbility, AbilityInfo)> for CharacterState {
                 stage_section: StageSection::Buildup,
                 achieved_radius: summon_distance.0.floor() as i32 - 1,
             }),
+            CharacterAbility::Fishing {
+                cast_duration,
+                bite_window,
+                reel_duration,
+                range,
+            } => CharacterState::Fishing(fishing::Data {
+                static_data: fishing::StaticData {
+                    cast_duration: Duration::from_secs_f32(*cast_duration),
+                    bite_window: Duration::from_secs_f32(*bite_window),
+                    reel_duration: Duration::from_secs_f32(*reel_duration),
+                    range: *range,
+                    ability_info,
+                },
+                timer: Duration::default(),
+                stage_section: StageSection::Buildup,
+                bobber: None,
+                bite: None,
+                input_released: false,
+                hooked: false,
+            }),
         }
     }
 }
@@ -331,6 +331,10 @@ pub struct ActionState {
     pub counter: f32,
     pub condition: bool,
     pub int_counter: u8,
+    /// The behaviour of a data-driven tactic that is currently being performed
+    pub behaviour: Option<usize>,
+    /// When each behaviour of a data-driven tactic was last started
+    pub behaviour_started: Vec<f64>,
 }
 
 impl Agent {
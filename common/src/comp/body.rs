@@ -50,6 +50,32 @@ make_case_elim!(
     }
 );
 
+/// How a creature behaves while it carries a rider
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct MountStats {
+    /// Where the rider sits, relative to the position of the mount and rotated
+    /// with it
+    pub saddle_offset: Vec3<f32>,
+    /// Multiplier for the movement speed of the mount while ridden
+    pub speed: f32,
+    /// Multiplier for the jump strength of the mount while ridden
+    pub jump: f32,
+    /// Multiplier for the flight thrust of the mount while ridden, only
+    /// relevant for bodies that can fly
+    pub flight: f32,
+}
+
+impl MountStats {
+    const fn new(saddle_offset: Vec3<f32>, speed: f32, jump: f32, flight: f32) -> Self {
+        Self {
+            saddle_offset,
+            speed,
+            jump,
+            flight,
+        }
+    }
+}
+
 /// Data representing data generic to the body together with per-species data.
 ///
 /// NOTE: Deliberately don't (yet?) implement serialize.
@@ -723,7 +749,67 @@ impl Body {
         match self {
             Body::Ship(ship::Body::DefaultAirship) => Vec3::from([0.0, 0.0, 10.0]),
             Body::Ship(ship::Body::AirBalloon) => Vec3::from([0.0, 0.0, 5.0]),
-            _ => Vec3::unit_z(),
+            _ => self
+                .mount_stats()
+                .map_or(Vec3::unit_z(), |stats| stats.saddle_offset),
+        }
+    }
+
+    /// Returns the mount stats if a player can ride a creature with this body
+    pub fn mount_stats(&self) -> Option<MountStats> {
+        match self {
+            Body::QuadrupedMedium(body) => match body.species {
+                quadruped_medium::Species::Horse | quadruped_medium::Species::Kelpie => {
+                    Some(MountStats::new(Vec3::new(0.0, -0.2, 2.0), 1.6, 1.2, 1.0))
+                },
+                quadruped_medium::Species::Zebra | quadruped_medium::Species::Antelope => {
+                    Some(MountStats::new(Vec3::new(0.0, -0.2, 1.8), 1.5, 1.2, 1.0))
+                },
+                quadruped_medium::Species::Deer => {
+                    Some(MountStats::new(Vec3::new(0.0, -0.2, 1.8), 1.4, 1.3, 1.0))
+                },
+                quadruped_medium::Species::Donkey | quadruped_medium::Species::Mouflon => {
+                    Some(MountStats::new(Vec3::new(0.0, -0.2, 1.6), 1.2, 1.0, 1.0))
+                },
+                quadruped_medium::Species::Camel => {
+                    Some(MountStats::new(Vec3::new(0.0, 0.0, 2.4), 1.3, 0.8, 1.0))
+                },
+                quadruped_medium::Species::Tuskram
+                | quadruped_medium::Species::Highland
+                | quadruped_medium::Species::Cattle => {
+                    Some(MountStats::new(Vec3::new(0.0, -0.2, 2.0), 1.1, 0.8, 1.0))
+                },
+                quadruped_medium::Species::Yak | quadruped_medium::Species::Moose => {
+                    Some(MountStats::new(Vec3::new(0.0, -0.2, 2.4), 1.2, 0.9, 1.0))
+                },
+                _ => None,
+            },
+            Body::Theropod(body) => match body.species {
+                theropod::Species::Sandraptor
+                | theropod::Species::Snowraptor
+                | theropod::Species::Woodraptor => {
+                    Some(MountStats::new(Vec3::new(0.0, 0.0, 2.0), 1.5, 1.4, 1.0))
+                },
+                _ => None,
+            },
+            Body::BirdLarge(body) => match body.species {
+                bird_large::Species::Roc => {
+                    Some(MountStats::new(Vec3::new(0.0, 0.0, 3.0), 1.0, 1.0, 1.5))
+                },
+                _ => None,
+            },
+            Body::Humanoid(_)
+            | Body::QuadrupedSmall(_)
+            | Body::BirdMedium(_)
+            | Body::FishMedium(_)
+            | Body::Dragon(_)
+            | Body::FishSmall(_)
+            | Body::BipedLarge(_)
+            | Body::BipedSmall(_)
+            | Body::Object(_)
+            | Body::Golem(_)
+            | Body::QuadrupedLow(_)
+            | Body::Ship(_) => None,
         }
     }
 }
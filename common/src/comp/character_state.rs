@@ -2,7 +2,11 @@ use crate::{
     combat::Attack,
     comp::{tool::ToolKind, Density, Energy, InputAttr, InputKind, Ori, Pos, Vel},
     event::{LocalEvent, ServerEvent},
-    states::{behavior::JoinData, utils::StageSection, *},
+    states::{
+        behavior::JoinData,
+        utils::{AbilityInfo, StageSection},
+        *,
+    },
 };
 use serde::{Deserialize, Serialize};
 use specs::{Component, DerefFlaggedStorage, VecStorage};
@@ -105,6 +109,8 @@ pub enum CharacterState {
     SelfBuff(self_buff::Data),
     /// Creates sprites around the caster
     SpriteSummon(sprite_summon::Data),
+    /// Casts a fishing line into water, waits for a bite and reels it in
+    Fishing(fishing::Data),
 }
 
 impl CharacterState {
@@ -130,6 +136,7 @@ impl CharacterState {
                 | CharacterState::Blink(_)
                 | CharacterState::BasicSummon(_)
                 | CharacterState::SpriteSummon(_)
+                | CharacterState::Fishing(_)
         )
     }
 
@@ -215,6 +222,44 @@ impl CharacterState {
             || matches!(self, CharacterState::Roll(s) if s.stage_section == StageSection::Movement)
     }
 
+    /// The ability this state was entered through, if any
+    pub fn ability_info(&self) -> Option<AbilityInfo> {
+        match self {
+            CharacterState::BasicBlock(data) => Some(data.static_data.ability_info),
+            CharacterState::Roll(data) => Some(data.static_data.ability_info),
+            CharacterState::BasicMelee(data) => Some(data.static_data.ability_info),
+            CharacterState::BasicRanged(data) => Some(data.static_data.ability_info),
+            CharacterState::Boost(data) => Some(data.static_data.ability_info),
+            CharacterState::DashMelee(data) => Some(data.static_data.ability_info),
+            CharacterState::ComboMelee(data) => Some(data.static_data.ability_info),
+            CharacterState::LeapMelee(data) => Some(data.static_data.ability_info),
+            CharacterState::SpinMelee(data) => Some(data.static_data.ability_info),
+            CharacterState::ChargedRanged(data) => Some(data.static_data.ability_info),
+            CharacterState::ChargedMelee(data) => Some(data.static_data.ability_info),
+            CharacterState::RepeaterRanged(data) => Some(data.static_data.ability_info),
+            CharacterState::Shockwave(data) => Some(data.static_data.ability_info),
+            CharacterState::BasicBeam(data) => Some(data.static_data.ability_info),
+            CharacterState::BasicAura(data) => Some(data.static_data.ability_info),
+            CharacterState::HealingBeam(data) => Some(data.static_data.ability_info),
+            CharacterState::Blink(data) => Some(data.static_data.ability_info),
+            CharacterState::BasicSummon(data) => Some(data.static_data.ability_info),
+            CharacterState::SelfBuff(data) => Some(data.static_data.ability_info),
+            CharacterState::SpriteSummon(data) => Some(data.static_data.ability_info),
+            CharacterState::Fishing(data) => Some(data.static_data.ability_info),
+            CharacterState::Idle
+            | CharacterState::Climb(_)
+            | CharacterState::Sit
+            | CharacterState::Dance
+            | CharacterState::Talk
+            | CharacterState::Sneak
+            | CharacterState::Glide(_)
+            | CharacterState::GlideWield
+            | CharacterState::Stunned(_)
+            | CharacterState::Equipping(_)
+            | CharacterState::Wielding => None,
+        }
+    }
+
     /// Compares for shallow equality (does not check internal struct equality)
     pub fn same_variant(&self, other: &Self) -> bool {
         // Check if state is the same without looking at the inner data
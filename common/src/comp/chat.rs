@@ -1,5 +1,5 @@
 use crate::{
-    comp::{group::Group, BuffKind},
+    comp::{group::Group, guild::GuildId, BuffKind},
     uid::Uid,
 };
 use serde::{Deserialize, Serialize};
@@ -21,6 +21,8 @@ pub enum ChatMode {
     Group(Group),
     /// Talk to your faction
     Faction(String),
+    /// Talk to the members of your guild
+    Guild(GuildId),
     /// Talk to every player on the server
     World,
 }
@@ -38,6 +40,7 @@ impl ChatMode {
             ChatMode::Region => ChatType::Region(from),
             ChatMode::Group(group) => ChatType::Group(from, *group),
             ChatMode::Faction(faction) => ChatType::Faction(from, faction.clone()),
+            ChatMode::Guild(guild) => ChatType::Guild(from, *guild),
             ChatMode::World => ChatType::World(from),
         };
         UnresolvedChatMsg { chat_type, message }
@@ -90,6 +93,8 @@ pub enum ChatType<G> {
     GroupMeta(G),
     /// Server notifications to a faction, such as player join/leave
     FactionMeta(String),
+    /// Server notifications to a guild, such as members joining or leaving
+    GuildMeta(GuildId),
     /// One-on-one chat (from, to)
     Tell(Uid, Uid),
     /// Chat with nearby players
@@ -98,6 +103,8 @@ pub enum ChatType<G> {
     Group(Uid, G),
     /// Factional chat
     Faction(Uid, String),
+    /// Guild chat
+    Guild(Uid, GuildId),
     /// Regional chat
     Region(Uid),
     /// World chat
@@ -113,6 +120,9 @@ pub enum ChatType<G> {
     NpcTell(Uid, Uid, u16),
     /// Anything else
     Meta,
+    /// Lines of the combat log. These are created by the client from the
+    /// combat log stream and never sent by the server.
+    CombatLog,
 }
 
 impl<G> ChatType<G> {
@@ -165,18 +175,21 @@ impl<G> GenericChatMsg<G> {
             ChatType::CommandInfo => ChatType::CommandInfo,
             ChatType::CommandError => ChatType::CommandError,
             ChatType::FactionMeta(a) => ChatType::FactionMeta(a),
+            ChatType::GuildMeta(a) => ChatType::GuildMeta(a),
             ChatType::GroupMeta(g) => ChatType::GroupMeta(f(g)),
             ChatType::Kill(a, b) => ChatType::Kill(a, b),
             ChatType::Tell(a, b) => ChatType::Tell(a, b),
             ChatType::Say(a) => ChatType::Say(a),
             ChatType::Group(a, g) => ChatType::Group(a, f(g)),
             ChatType::Faction(a, b) => ChatType::Faction(a, b),
+            ChatType::Guild(a, b) => ChatType::Guild(a, b),
             ChatType::Region(a) => ChatType::Region(a),
             ChatType::World(a) => ChatType::World(a),
             ChatType::Npc(a, b) => ChatType::Npc(a, b),
             ChatType::NpcSay(a, b) => ChatType::NpcSay(a, b),
             ChatType::NpcTell(a, b, c) => ChatType::NpcTell(a, b, c),
             ChatType::Meta => ChatType::Meta,
+            ChatType::CombatLog => ChatType::CombatLog,
         };
 
         GenericChatMsg {
@@ -212,18 +225,21 @@ impl<G> GenericChatMsg<G> {
             ChatType::CommandInfo => SpeechBubbleType::None,
             ChatType::CommandError => SpeechBubbleType::None,
             ChatType::FactionMeta(_) => SpeechBubbleType::None,
+            ChatType::GuildMeta(_) => SpeechBubbleType::None,
             ChatType::GroupMeta(_) => SpeechBubbleType::None,
             ChatType::Kill(_, _) => SpeechBubbleType::None,
             ChatType::Tell(_u, _) => SpeechBubbleType::Tell,
             ChatType::Say(_u) => SpeechBubbleType::Say,
             ChatType::Group(_u, _s) => SpeechBubbleType::Group,
             ChatType::Faction(_u, _s) => SpeechBubbleType::Faction,
+            ChatType::Guild(_u, _g) => SpeechBubbleType::Guild,
             ChatType::Region(_u) => SpeechBubbleType::Region,
             ChatType::World(_u) => SpeechBubbleType::World,
             ChatType::Npc(_u, _r) => SpeechBubbleType::None,
             ChatType::NpcSay(_u, _r) => SpeechBubbleType::Say,
             ChatType::NpcTell(_f, _t, _) => SpeechBubbleType::Say,
             ChatType::Meta => SpeechBubbleType::None,
+            ChatType::CombatLog => SpeechBubbleType::None,
         }
     }
 
@@ -234,18 +250,21 @@ impl<G> GenericChatMsg<G> {
             ChatType::CommandInfo => None,
             ChatType::CommandError => None,
             ChatType::FactionMeta(_) => None,
+            ChatType::GuildMeta(_) => None,
             ChatType::GroupMeta(_) => None,
             ChatType::Kill(_, _) => None,
             ChatType::Tell(u, _t) => Some(*u),
             ChatType::Say(u) => Some(*u),
             ChatType::Group(u, _s) => Some(*u),
             ChatType::Faction(u, _s) => Some(*u),
+            ChatType::Guild(u, _g) => Some(*u),
             ChatType::Region(u) => Some(*u),
             ChatType::World(u) => Some(*u),
             ChatType::Npc(u, _r) => Some(*u),
             ChatType::NpcSay(u, _r) => Some(*u),
             ChatType::NpcTell(u, _t, _r) => Some(*u),
             ChatType::Meta => None,
+            ChatType::CombatLog => None,
         }
     }
 }
@@ -282,6 +301,7 @@ pub enum SpeechBubbleType {
     Region,
     Group,
     Faction,
+    Guild,
     World,
     // For NPCs
     Quest, // TODO not implemented
@@ -1,9 +1,13 @@
 use crate::{
+    character::CharacterId,
     comp::{
+        crafting::QueuedCraft,
         inventory::slot::{EquipSlot, InvSlotId, Slot},
         invite::{InviteKind, InviteResponse},
+        skills::SkillGroupKind,
         BuffKind,
     },
+    mail::MailId,
     trade::{TradeAction, TradeId},
     uid::Uid,
     util::Dir,
@@ -26,6 +30,19 @@ pub enum InventoryEvent {
     CraftRecipe {
         recipe: String,
         craft_sprite: Option<Vec3<i32>>,
+        /// How many times in a row to craft the recipe
+        amount: u32,
+        /// Whether to use the optional inputs of the recipe
+        improve: bool,
+    },
+    CancelCrafting,
+    RepairItem {
+        slot: Slot,
+        craft_sprite: Option<Vec3<i32>>,
+    },
+    SocketGem {
+        gem: InvSlotId,
+        slot: Slot,
     },
 }
 
@@ -50,7 +67,23 @@ pub enum InventoryManip {
     CraftRecipe {
         recipe: String,
         craft_sprite: Option<Vec3<i32>>,
+        /// How many times in a row to craft the recipe
+        amount: u32,
+        /// Whether to use the optional inputs of the recipe
+        improve: bool,
+    },
+    CancelCrafting,
+    RepairItem {
+        slot: Slot,
+        craft_sprite: Option<Vec3<i32>>,
     },
+    SocketGem {
+        gem: InvSlotId,
+        slot: Slot,
+    },
+    /// A queued craft is done, sent by the server when it finished working on
+    /// it
+    FinishCraft(QueuedCraft),
 }
 
 impl From<InventoryAction> for InventoryManip {
@@ -81,10 +114,19 @@ impl From<InventoryEvent> for InventoryManip {
             InventoryEvent::CraftRecipe {
                 recipe,
                 craft_sprite,
+                amount,
+                improve,
             } => Self::CraftRecipe {
                 recipe,
                 craft_sprite,
+                amount,
+                improve,
+            },
+            InventoryEvent::CancelCrafting => Self::CancelCrafting,
+            InventoryEvent::RepairItem { slot, craft_sprite } => {
+                Self::RepairItem { slot, craft_sprite }
             },
+            InventoryEvent::SocketGem { gem, slot } => Self::SocketGem { gem, slot },
         }
     }
 }
@@ -96,6 +138,59 @@ pub enum GroupManip {
     AssignLeader(Uid),
 }
 
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum GuildManip {
+    /// Found a new guild, becoming its leader
+    Create {
+        name: String,
+        tag: String,
+    },
+    Leave,
+    /// Members are addressed by character, as they may be offline
+    Kick(CharacterId),
+    Promote(CharacterId),
+    Demote(CharacterId),
+    Disband,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum StorageManip {
+    /// Open the player's bank at the strongbox at this position
+    OpenBank(Vec3<i32>),
+    Close,
+    /// Move an item from the player's inventory into the open storage
+    Deposit(InvSlotId),
+    /// Move an item from the open storage into the player's inventory
+    Withdraw(InvSlotId),
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum MailManip {
+    /// Send a letter from the mailbox at the given position to the character
+    /// with the given name, attaching the given amounts of the items in the
+    /// player's inventory
+    Send {
+        mailbox: Vec3<i32>,
+        recipient: String,
+        message: String,
+        attachments: Vec<(InvSlotId, u32)>,
+    },
+    /// Collect a letter and its attachments at the mailbox at the given
+    /// position
+    Claim { mailbox: Vec3<i32>, mail: MailId },
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum BuildManip {
+    /// Refund every skill in a skill group, which may cost an item
+    Respec(SkillGroupKind),
+    /// Save the current skills and equipment under the given name
+    SavePreset(String),
+    DeletePreset(String),
+    /// Switch to the skills and equipment of the preset with the given name
+    ApplyPreset(String),
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum UtteranceKind {
     Calm,
@@ -121,6 +216,10 @@ pub enum ControlEvent {
     Unmount,
     InventoryEvent(InventoryEvent),
     GroupManip(GroupManip),
+    GuildManip(GuildManip),
+    StorageManip(StorageManip),
+    MailManip(MailManip),
+    BuildManip(BuildManip),
     RemoveBuff(BuffKind),
     Respawn,
     Utterance(UtteranceKind),
@@ -246,6 +345,27 @@ impl Controller {
     pub fn clear_events(&mut self) { self.events.clear(); }
 
     pub fn push_event(&mut self, event: ControlEvent) { self.events.push(event); }
+
+    /// The controller of a mount carrying a rider with this controller. Only
+    /// movement is passed on to the mount, abilities are used by the rider
+    /// from the saddle.
+    pub fn for_mount(&self) -> Self {
+        Self {
+            inputs: ControllerInputs {
+                move_dir: self.inputs.move_dir,
+                move_z: self.inputs.move_z,
+                look_dir: self.inputs.look_dir,
+                ..Default::default()
+            },
+            queued_inputs: self
+                .queued_inputs
+                .iter()
+                .filter(|(input, _)| matches!(input, InputKind::Jump | InputKind::Fly))
+                .map(|(input, attr)| (*input, *attr))
+                .collect(),
+            ..Default::default()
+        }
+    }
 }
 
 impl Component for Controller {
@@ -268,3 +388,49 @@ pub struct Mounting(pub Uid);
 impl Component for Mounting {
     type Storage = DerefFlaggedStorage<Self, IdvStorage<Self>>;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mounts_only_get_movement_inputs() {
+        let attr = InputAttr {
+            select_pos: None,
+            target_entity: None,
+        };
+        let rider = Controller {
+            inputs: ControllerInputs {
+                move_dir: Vec2::new(0.0, 1.0),
+                move_z: 0.5,
+                look_dir: Dir::new(Vec3::unit_x()),
+                select_pos: Some(Vec3::zero()),
+                strafing: true,
+                ..Default::default()
+            },
+            queued_inputs: vec![
+                (InputKind::Jump, attr),
+                (InputKind::Fly, attr),
+                (InputKind::Primary, attr),
+                (InputKind::Roll, attr),
+            ]
+            .into_iter()
+            .collect(),
+            events: vec![ControlEvent::Unmount],
+            actions: vec![ControlAction::Wield],
+        };
+
+        let mount = rider.for_mount();
+        assert_eq!(mount.inputs.move_dir, rider.inputs.move_dir);
+        assert_eq!(mount.inputs.move_z, rider.inputs.move_z);
+        assert_eq!(mount.inputs.look_dir, rider.inputs.look_dir);
+        assert_eq!(mount.inputs.select_pos, None);
+        assert!(!mount.inputs.strafing);
+        assert_eq!(
+            mount.queued_inputs.keys().copied().collect::<Vec<_>>(),
+            vec![InputKind::Jump, InputKind::Fly]
+        );
+        assert!(mount.events.is_empty());
+        assert!(mount.actions.is_empty());
+    }
+}
@@ -0,0 +1,147 @@
+use serde::{Deserialize, Serialize};
+use specs::Component;
+use specs_idvs::IdvStorage;
+use std::collections::VecDeque;
+use vek::*;
+
+/// The most crafts that can be waiting in a crafting queue at once
+pub const MAX_QUEUED_CRAFTS: u32 = 50;
+
+/// A recipe waiting to be crafted, possibly several times in a row
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct QueuedCraft {
+    pub recipe: String,
+    /// How many more times the recipe is to be crafted
+    pub amount: u32,
+    /// Where the crafting station the recipe needs is
+    pub craft_sprite: Option<Vec3<i32>>,
+    /// Whether to use up the optional inputs of the recipe to improve the
+    /// quality of what is crafted
+    pub improve: bool,
+    /// How many seconds crafting the recipe once takes
+    pub craft_time: f32,
+}
+
+/// The recipes an entity is crafting, one after the other. Crafting goes on
+/// for as long as the entity stays near the crafting station and has the
+/// ingredients.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct CraftingQueue {
+    crafts: VecDeque<QueuedCraft>,
+    /// How many seconds have been spent on the current craft
+    progress: f32,
+}
+
+impl CraftingQueue {
+    /// The craft being worked on
+    pub fn current(&self) -> Option<&QueuedCraft> { self.crafts.front() }
+
+    pub fn crafts(&self) -> impl ExactSizeIterator<Item = &QueuedCraft> { self.crafts.iter() }
+
+    pub fn is_empty(&self) -> bool { self.crafts.is_empty() }
+
+    /// How many crafts are left in the queue, counting each time a recipe is
+    /// crafted
+    pub fn remaining(&self) -> u32 { self.crafts.iter().map(|craft| craft.amount).sum() }
+
+    /// How far along the current craft is, from 0 to 1
+    pub fn progress(&self) -> f32 {
+        self.current().map_or(0.0, |craft| {
+            if craft.craft_time > 0.0 {
+                (self.progress / craft.craft_time).min(1.0)
+            } else {
+                1.0
+            }
+        })
+    }
+
+    /// Adds a craft to the back of the queue, crafting it fewer times if the
+    /// queue would otherwise hold too many crafts. Returns whether anything
+    /// was queued.
+    pub fn push(&mut self, mut craft: QueuedCraft) -> bool {
+        craft.amount = craft
+            .amount
+            .min(MAX_QUEUED_CRAFTS.saturating_sub(self.remaining()));
+        if craft.amount == 0 {
+            return false;
+        }
+        self.crafts.push_back(craft);
+        true
+    }
+
+    /// Works on the current craft for `dt` seconds, returning a single craft
+    /// of its recipe when it is done
+    pub fn tick(&mut self, dt: f32) -> Option<QueuedCraft> {
+        let craft = self.crafts.front_mut()?;
+        self.progress += dt;
+        if self.progress < craft.craft_time {
+            return None;
+        }
+
+        self.progress = 0.0;
+        let done = QueuedCraft {
+            amount: 1,
+            ..craft.clone()
+        };
+        craft.amount = craft.amount.saturating_sub(1);
+        if craft.amount == 0 {
+            self.crafts.pop_front();
+        }
+        Some(done)
+    }
+
+    /// Moves the current craft along without ever finishing it, for clients to
+    /// show progress between updates from the server
+    pub fn predict(&mut self, dt: f32) {
+        if let Some(craft) = self.crafts.front() {
+            self.progress = (self.progress + dt).min(craft.craft_time);
+        }
+    }
+
+    /// Stops crafting everything in the queue
+    pub fn clear(&mut self) {
+        self.crafts.clear();
+        self.progress = 0.0;
+    }
+}
+
+impl Component for CraftingQueue {
+    type Storage = IdvStorage<Self>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn craft(amount: u32) -> QueuedCraft {
+        QueuedCraft {
+            recipe: "velorite_frag".to_owned(),
+            amount,
+            craft_sprite: None,
+            improve: false,
+            craft_time: 2.0,
+        }
+    }
+
+    #[test]
+    fn crafts_finish_one_at_a_time() {
+        let mut queue = CraftingQueue::default();
+        assert!(queue.push(craft(2)));
+        assert_eq!(queue.tick(1.0), None);
+        assert_eq!(queue.progress(), 0.5);
+        assert_eq!(queue.tick(1.0), Some(craft(1)));
+        assert_eq!(queue.remaining(), 1);
+        assert_eq!(queue.tick(2.0), Some(craft(1)));
+        assert!(queue.is_empty());
+        assert_eq!(queue.tick(2.0), None);
+    }
+
+    #[test]
+    fn queue_is_capped() {
+        let mut queue = CraftingQueue::default();
+        assert!(queue.push(craft(MAX_QUEUED_CRAFTS - 1)));
+        assert!(queue.push(craft(5)));
+        assert_eq!(queue.remaining(), MAX_QUEUED_CRAFTS);
+        assert!(!queue.push(craft(1)));
+    }
+}
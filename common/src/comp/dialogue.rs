@@ -1,3 +1,4 @@
+use serde::{Deserialize, Serialize};
 use vek::{Vec2, Vec3};
 
 use super::Item;
@@ -58,10 +59,14 @@ pub enum Subject {
     /// So either it'll tell you something to do, or just say that he got
     /// nothing
     Work,
+    /// Asking to buy a house
+    /// NPC will sell you the closest house that has no owner, if you can pay
+    /// for it
+    House,
 }
 
 /// Context of why a NPC has a specific mood (good, neutral, bad, ...)
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum MoodContext {
     /// The weather is good, sunny, appeasing, etc...
     GoodWeather,
@@ -81,7 +86,7 @@ pub enum MoodContext {
 
 // Note: You can add in-between states if needed
 /// NPC mood status indicator
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum MoodState {
     /// The NPC is happy!
     Good(MoodContext),
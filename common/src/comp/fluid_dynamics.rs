@@ -4,6 +4,7 @@ use super::{Density, Ori, Vel};
 use crate::{
     consts::{AIR_DENSITY, LAVA_DENSITY, WATER_DENSITY},
     util::{Dir, Plane, Projection},
+    weather::Weather,
 };
 use serde::{Deserialize, Serialize};
 use std::f32::consts::PI;
@@ -109,6 +110,22 @@ impl Fluid {
             _ => None,
         }
     }
+
+    /// Air moved by the given weather: the wind, along with thermals rising
+    /// beneath clouds and the downdrafts of falling rain or snow.
+    pub fn with_weather(self, weather: &Weather) -> Self {
+        match self {
+            Self::Air { elevation, .. } => {
+                let precipitation = weather.precipitation();
+                let updraft = weather.cloud * (1.0 - precipitation) * 2.0 - precipitation * 1.5;
+                Self::Air {
+                    vel: Vel(weather.wind.with_z(updraft)),
+                    elevation,
+                }
+            },
+            liquid => liquid,
+        }
+    }
 }
 
 impl Default for Fluid {
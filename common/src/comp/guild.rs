@@ -0,0 +1,92 @@
+use crate::character::CharacterId;
+use serde::{Deserialize, Serialize};
+use specs::Component;
+use specs_idvs::IdvStorage;
+
+pub type GuildId = u64;
+
+/// The longest name a guild can have, in characters
+pub const MAX_GUILD_NAME_LENGTH: usize = 32;
+/// The shortest and longest tags a guild can have, in characters. The tag is
+/// shown next to the names of the guild's members.
+pub const MIN_GUILD_TAG_LENGTH: usize = 2;
+pub const MAX_GUILD_TAG_LENGTH: usize = 5;
+
+/// The rank of a member within their guild, which decides what they are
+/// allowed to do. Ranks are ordered from lowest to highest.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum GuildRank {
+    Member,
+    Officer,
+    Leader,
+}
+
+impl GuildRank {
+    pub fn can_invite(self) -> bool { self >= GuildRank::Officer }
+
+    /// Members can only be removed by someone of a higher rank
+    pub fn can_kick(self, target: GuildRank) -> bool { self.can_invite() && self > target }
+
+    /// Only the leader can change ranks. Promoting an officer hands over the
+    /// leadership of the guild.
+    pub fn can_promote(self, target: GuildRank) -> bool {
+        self == GuildRank::Leader && target < GuildRank::Leader
+    }
+
+    pub fn can_demote(self, target: GuildRank) -> bool {
+        self == GuildRank::Leader && target == GuildRank::Officer
+    }
+
+    pub fn can_disband(self) -> bool { self == GuildRank::Leader }
+
+    pub fn promoted(self) -> Self {
+        match self {
+            GuildRank::Member => GuildRank::Officer,
+            GuildRank::Officer | GuildRank::Leader => GuildRank::Leader,
+        }
+    }
+
+    pub fn demoted(self) -> Self {
+        match self {
+            GuildRank::Member | GuildRank::Officer => GuildRank::Member,
+            GuildRank::Leader => GuildRank::Officer,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            GuildRank::Member => "member",
+            GuildRank::Officer => "officer",
+            GuildRank::Leader => "leader",
+        }
+    }
+}
+
+/// The guild a character belongs to. Guilds themselves are kept by the
+/// server, this only marks their members so that guild chat can find them.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Guild(pub GuildId);
+
+impl Component for Guild {
+    type Storage = IdvStorage<Self>;
+}
+
+/// A member of a guild, as shown in the guild panel
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GuildMember {
+    pub character_id: CharacterId,
+    pub name: String,
+    pub rank: GuildRank,
+    pub online: bool,
+}
+
+/// What a member knows about their own guild
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GuildInfo {
+    pub id: GuildId,
+    pub name: String,
+    pub tag: String,
+    /// The rank of the member the information was sent to
+    pub rank: GuildRank,
+    pub members: Vec<GuildMember>,
+}
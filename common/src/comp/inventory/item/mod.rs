@@ -1,8 +1,10 @@
 pub mod armor;
+pub mod modifier;
 pub mod modular;
 pub mod tool;
 
 // Reexports
+pub use modifier::{GemManifest, ItemModifier, ModifierPool};
 pub use modular::{ModularComponent, ModularComponentKind, ModularComponentTag};
 pub use tool::{AbilitySet, AbilitySpec, Hands, MaterialStatManifest, Tool, ToolKind};
 
@@ -13,6 +15,7 @@ use crate::{
         CharacterAbility,
     },
     effect::Effect,
+    farming::CropManifest,
     lottery::{LootSpec, Lottery},
     recipe::RecipeInput,
     terrain::{Block, SpriteKind},
@@ -53,6 +56,10 @@ pub enum Reagent {
 pub enum Utility {
     Coins,
     Collar,
+    StorageChest,
+    /// Planted on farmland where you stand, the crop is looked up by the item
+    /// definition id
+    Seeds,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -85,6 +92,27 @@ pub enum Quality {
     Debug,     // Red
 }
 
+impl Quality {
+    /// The qualities that an item can be improved through, from worst to best
+    const IMPROVABLE: [Quality; 6] = [
+        Quality::Low,
+        Quality::Common,
+        Quality::Moderate,
+        Quality::High,
+        Quality::Epic,
+        Quality::Legendary,
+    ];
+
+    /// The quality the given number of steps above this one, never going past
+    /// legendary. Artifact and debug qualities can't be improved.
+    pub fn improved(self, steps: u8) -> Self {
+        match Self::IMPROVABLE.iter().position(|quality| *quality == self) {
+            Some(i) => Self::IMPROVABLE[(i + steps as usize).min(Self::IMPROVABLE.len() - 1)],
+            None => self,
+        }
+    }
+}
+
 pub trait TagExampleInfo {
     fn name(&self) -> &'static str;
     /// What item to show in the crafting hud if the player has nothing with the
@@ -288,6 +316,9 @@ pub struct CreateDatabaseItemId {
     item_id: Arc<ItemId>,
 }*/
 
+/// How effective a broken item still is compared to an intact one
+pub const BROKEN_ITEM_EFFECTIVENESS: f32 = 0.5;
+
 /// NOTE: Do not call `Item::clone` without consulting the core devs!  It only
 /// exists due to being required for message serialization at the moment, and
 /// should not be used for any other purpose.
@@ -328,6 +359,23 @@ pub struct Item {
     /// The slots for items that this item has
     slots: Vec<InvSlot>,
     item_config: Option<Box<ItemConfig>>,
+    /// How much of its durability the item has lost through use, only
+    /// meaningful if the item definition gives it a durability
+    #[serde(default)]
+    durability_lost: u32,
+    /// How many steps of quality the item is above its definition, such as
+    /// when it was crafted with optional ingredients. Stackable items never
+    /// have a quality bonus.
+    #[serde(default)]
+    quality_bonus: u8,
+    /// Modifiers rolled for the item when it dropped. Stackable items never
+    /// have modifiers.
+    #[serde(default)]
+    modifiers: Vec<ItemModifier>,
+    /// The gems socketed into the item by item definition id, one entry for
+    /// each socket its definition gives it
+    #[serde(default)]
+    sockets: Vec<Option<String>>,
 }
 
 // Custom serialization for ItemDef, we only want to send the item_definition_id
@@ -380,6 +428,13 @@ pub struct ItemDef {
     /// Used to specify a custom ability set for a weapon. Leave None (or don't
     /// include field in ItemDef) to use default ability set for weapon kind.
     pub ability_spec: Option<AbilitySpec>,
+    /// How many hits the item can take before it breaks. Leave None (or don't
+    /// include field in ItemDef) for items that never wear out.
+    #[serde(default)]
+    pub durability: Option<u32>,
+    /// How many gems can be socketed into the item
+    #[serde(default)]
+    pub sockets: u8,
 }
 
 impl PartialEq for ItemDef {
@@ -404,13 +459,15 @@ impl TryFrom<(&Item, &AbilityMap, &MaterialStatManifest)> for ItemConfig {
         (item, ability_map, msm): (&Item, &AbilityMap, &MaterialStatManifest),
     ) -> Result<Self, Self::Error> {
         if let ItemKind::Tool(tool) = &item.kind {
+            let modifiers = item.all_modifiers();
             // If no custom ability set is specified, fall back to abilityset of tool kind.
             let tool_default = ability_map
                 .get_ability_set(&AbilitySpec::Tool(tool.kind))
                 .cloned();
             let abilities = if let Some(set_key) = item.ability_spec() {
                 if let Some(set) = ability_map.get_ability_set(set_key) {
-                    set.clone().modified_by_tool(&tool, msm, &item.components)
+                    set.clone()
+                        .modified_by_tool(&tool, msm, &item.components, &modifiers)
                 } else {
                     error!(
                         "Custom ability set: {:?} references non-existent set, falling back to \
@@ -420,7 +477,7 @@ impl TryFrom<(&Item, &AbilityMap, &MaterialStatManifest)> for ItemConfig {
                     tool_default.unwrap_or_default()
                 }
             } else if let Some(set) = tool_default {
-                set.modified_by_tool(&tool, msm, &item.components)
+                set.modified_by_tool(&tool, msm, &item.components, &modifiers)
             } else {
                 error!(
                     "No ability set defined for tool: {:?}, falling back to default ability set.",
@@ -485,6 +542,8 @@ impl ItemDef {
             tags,
             slots,
             ability_spec: None,
+            durability: None,
+            sockets: 0,
         }
     }
 }
@@ -526,6 +585,8 @@ impl assets::Compound for ItemDef {
             tags,
             slots,
             ability_spec,
+            durability,
+            sockets,
         } = raw;
 
         // Some commands like /give_item provide the asset specifier separated with \
@@ -543,6 +604,8 @@ impl assets::Compound for ItemDef {
             tags,
             slots,
             ability_spec,
+            durability,
+            sockets,
         })
     }
 }
@@ -558,6 +621,10 @@ struct RawItemDef {
     #[serde(default)]
     slots: u16,
     ability_spec: Option<AbilitySpec>,
+    #[serde(default)]
+    durability: Option<u32>,
+    #[serde(default)]
+    sockets: u8,
 }
 
 impl assets::Asset for RawItemDef {
@@ -614,8 +681,12 @@ impl Item {
             amount: NonZeroU32::new(1).unwrap(),
             components,
             slots: vec![None; inner_item.slots as usize],
+            sockets: vec![None; inner_item.sockets as usize],
             item_def: inner_item,
             item_config: None,
+            durability_lost: 0,
+            quality_bonus: 0,
+            modifiers: Vec::new(),
         };
         item.update_item_config(ability_map, msm);
         item
@@ -659,6 +730,11 @@ impl Item {
             "`new_item` has the same `item_def` and as an invariant, \
              self.set_amount(self.amount()) should always succeed.",
         );
+        new_item.durability_lost = self.durability_lost;
+        new_item.quality_bonus = self.quality_bonus;
+        new_item.modifiers = self.modifiers.clone();
+        new_item.sockets = self.sockets.clone();
+        new_item.update_item_config(ability_map, msm);
         new_item.slots_mut().iter_mut().zip(self.slots()).for_each(
             |(new_item_slot, old_item_slot)| {
                 *new_item_slot = old_item_slot
@@ -744,6 +820,44 @@ impl Item {
         self.update_item_config(ability_map, msm);
     }
 
+    /// How much durability the item has lost, used for persistence
+    pub fn durability_lost(&self) -> u32 { self.durability_lost }
+
+    pub fn set_durability_lost(&mut self, durability_lost: u32) {
+        self.durability_lost = self
+            .item_def
+            .durability
+            .map_or(0, |max| durability_lost.min(max));
+    }
+
+    /// Whether the item has worn out completely
+    pub fn is_broken(&self) -> bool {
+        self.item_def
+            .durability
+            .map_or(false, |max| self.durability_lost >= max)
+    }
+
+    /// Wears the item down by the given amount, returning true if this broke
+    /// the item
+    pub fn wear(&mut self, amount: u32) -> bool {
+        let was_broken = self.is_broken();
+        self.set_durability_lost(self.durability_lost.saturating_add(amount));
+        !was_broken && self.is_broken()
+    }
+
+    /// Restores the full durability of the item
+    pub fn repair(&mut self) { self.durability_lost = 0; }
+
+    /// How well the item still does its job, broken items are only partly
+    /// effective
+    pub fn effectiveness(&self) -> f32 {
+        if self.is_broken() {
+            BROKEN_ITEM_EFFECTIVENESS
+        } else {
+            1.0
+        }
+    }
+
     fn update_item_config(&mut self, ability_map: &AbilityMap, msm: &MaterialStatManifest) {
         if let Ok(item_config) = ItemConfig::try_from((&*self, ability_map, msm)) {
             self.item_config = Some(Box::new(item_config));
@@ -780,7 +894,78 @@ impl Item {
     /// and if !self.is_stackable(), self.max_amount() = 1.
     pub fn max_amount(&self) -> u32 { if self.is_stackable() { u32::MAX } else { 1 } }
 
-    pub fn quality(&self) -> Quality { self.item_def.quality }
+    pub fn quality(&self) -> Quality { self.item_def.quality.improved(self.quality_bonus) }
+
+    /// How many steps of quality the item is above its definition, used for
+    /// persistence
+    pub fn quality_bonus(&self) -> u8 { self.quality_bonus }
+
+    pub fn set_quality_bonus(&mut self, quality_bonus: u8) {
+        self.quality_bonus = if self.is_stackable() {
+            0
+        } else {
+            quality_bonus
+        };
+    }
+
+    /// The modifiers rolled for the item, without those of its gems
+    pub fn modifiers(&self) -> &[ItemModifier] { &self.modifiers }
+
+    pub fn set_modifiers(
+        &mut self,
+        modifiers: Vec<ItemModifier>,
+        ability_map: &AbilityMap,
+        msm: &MaterialStatManifest,
+    ) {
+        self.modifiers = if self.is_stackable() {
+            Vec::new()
+        } else {
+            modifiers
+        };
+        // modifiers change the stats, so recalculate the ItemConfig
+        self.update_item_config(ability_map, msm);
+    }
+
+    /// The gems socketed into the item, by item definition id
+    pub fn sockets(&self) -> &[Option<String>] { &self.sockets }
+
+    pub fn has_free_socket(&self) -> bool { self.sockets.iter().any(Option::is_none) }
+
+    /// Puts the given gem into the first free socket of the item, returning
+    /// whether there was a free socket for it
+    pub fn socket_gem(
+        &mut self,
+        gem: &str,
+        ability_map: &AbilityMap,
+        msm: &MaterialStatManifest,
+    ) -> bool {
+        if GemManifest::load().read().modifier(gem).is_none() {
+            return false;
+        }
+        match self.sockets.iter_mut().find(|socket| socket.is_none()) {
+            Some(socket) => {
+                *socket = Some(gem.to_owned());
+                self.update_item_config(ability_map, msm);
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Restores the gems socketed into the item, used for persistence. Gems
+    /// that don't fit into the sockets of the item are lost.
+    pub fn set_sockets(
+        &mut self,
+        sockets: Vec<Option<String>>,
+        ability_map: &AbilityMap,
+        msm: &MaterialStatManifest,
+    ) {
+        self.sockets
+            .iter_mut()
+            .zip(sockets)
+            .for_each(|(socket, gem)| *socket = gem);
+        self.update_item_config(ability_map, msm);
+    }
 
     pub fn components(&self) -> &[Item] { &self.components }
 
@@ -870,10 +1055,25 @@ impl Item {
             | SpriteKind::ChestBuried) => {
                 return Item::from_container(container);
             },
-            _ => return None,
+            // Crops, whether planted or generated with the world
+            crop => return Item::from_crop(crop),
         }))
     }
 
+    fn from_crop(crop: SpriteKind) -> Option<Item> {
+        let harvest = CropManifest::load()
+            .read()
+            .crop_grown_as(crop)?
+            .harvest
+            .clone();
+        Some(
+            Lottery::<LootSpec>::load_expect(&harvest)
+                .read()
+                .choose()
+                .to_item(),
+        )
+    }
+
     fn from_container(container: SpriteKind) -> Option<Item> {
         let chosen;
         match container {
@@ -943,11 +1143,15 @@ pub trait ItemDesc {
     fn description(&self) -> &str;
     fn name(&self) -> &str;
     fn kind(&self) -> &ItemKind;
-    fn quality(&self) -> &Quality;
+    fn quality(&self) -> Quality;
     fn num_slots(&self) -> u16;
     fn item_definition_id(&self) -> &str;
     fn components(&self) -> &[Item];
     fn tags(&self) -> &[ItemTag];
+    /// The remaining and maximum durability, for items that wear out
+    fn durability(&self) -> Option<(u32, u32)>;
+    fn modifiers(&self) -> &[ItemModifier];
+    fn sockets(&self) -> &[Option<String>];
 
     fn tool(&self) -> Option<&Tool> {
         if let ItemKind::Tool(tool) = self.kind() {
@@ -956,6 +1160,22 @@ pub trait ItemDesc {
             None
         }
     }
+
+    /// The modifiers rolled for the item followed by those of the gems
+    /// socketed into it
+    fn all_modifiers(&self) -> Vec<ItemModifier> {
+        let mut modifiers = self.modifiers().to_vec();
+        if self.sockets().iter().any(Option::is_some) {
+            let gems = GemManifest::load().read();
+            modifiers.extend(
+                self.sockets()
+                    .iter()
+                    .flatten()
+                    .filter_map(|gem| gems.modifier(gem)),
+            );
+        }
+        modifiers
+    }
 }
 
 impl ItemDesc for Item {
@@ -965,7 +1185,7 @@ impl ItemDesc for Item {
 
     fn kind(&self) -> &ItemKind { &self.item_def.kind }
 
-    fn quality(&self) -> &Quality { &self.item_def.quality }
+    fn quality(&self) -> Quality { self.item_def.quality.improved(self.quality_bonus) }
 
     fn num_slots(&self) -> u16 { self.item_def.slots }
 
@@ -974,6 +1194,16 @@ impl ItemDesc for Item {
     fn components(&self) -> &[Item] { &self.components }
 
     fn tags(&self) -> &[ItemTag] { &self.item_def.tags }
+
+    fn durability(&self) -> Option<(u32, u32)> {
+        self.item_def
+            .durability
+            .map(|max| (max.saturating_sub(self.durability_lost), max))
+    }
+
+    fn modifiers(&self) -> &[ItemModifier] { &self.modifiers }
+
+    fn sockets(&self) -> &[Option<String>] { &self.sockets }
 }
 
 impl ItemDesc for ItemDef {
@@ -983,7 +1213,7 @@ impl ItemDesc for ItemDef {
 
     fn kind(&self) -> &ItemKind { &self.kind }
 
-    fn quality(&self) -> &Quality { &self.quality }
+    fn quality(&self) -> Quality { self.quality }
 
     fn num_slots(&self) -> u16 { self.slots }
 
@@ -992,6 +1222,12 @@ impl ItemDesc for ItemDef {
     fn components(&self) -> &[Item] { &[] }
 
     fn tags(&self) -> &[ItemTag] { &self.tags }
+
+    fn durability(&self) -> Option<(u32, u32)> { self.durability.map(|max| (max, max)) }
+
+    fn modifiers(&self) -> &[ItemModifier] { &[] }
+
+    fn sockets(&self) -> &[Option<String>] { &[] }
 }
 
 impl Component for Item {
@@ -1012,7 +1248,7 @@ impl<'a, T: ItemDesc + ?Sized> ItemDesc for &'a T {
 
     fn kind(&self) -> &ItemKind { (*self).kind() }
 
-    fn quality(&self) -> &Quality { (*self).quality() }
+    fn quality(&self) -> Quality { (*self).quality() }
 
     fn num_slots(&self) -> u16 { (*self).num_slots() }
 
@@ -1021,6 +1257,12 @@ impl<'a, T: ItemDesc + ?Sized> ItemDesc for &'a T {
     fn components(&self) -> &[Item] { (*self).components() }
 
     fn tags(&self) -> &[ItemTag] { (*self).tags() }
+
+    fn durability(&self) -> Option<(u32, u32)> { (*self).durability() }
+
+    fn modifiers(&self) -> &[ItemModifier] { (*self).modifiers() }
+
+    fn sockets(&self) -> &[Option<String>] { (*self).sockets() }
 }
 
 #[cfg(test)]
@@ -0,0 +1,153 @@
+use crate::{
+    assets::{self, AssetExt, AssetHandle},
+    combat::{AttackEffect, CombatEffect, CombatRequirement, GroupTarget},
+    lottery::Lottery,
+};
+use hashbrown::HashMap;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Asset specifier of the gems that can be socketed into items
+pub const GEM_MANIFEST: &str = "common.item_modifiers.gems";
+
+/// A bonus that a single item has on top of its definition, either rolled
+/// when the item dropped or given by a gem socketed into it. Only the
+/// modifiers of the weapon in the main hand take effect.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum ItemModifier {
+    /// Multiplies the power of the weapon by 1 + the value
+    Power(f32),
+    /// Multiplies the speed of the weapon by 1 + the value
+    Speed(f32),
+    /// Added to the critical chance of the weapon
+    CritChance(f32),
+    /// Energy the wielder gains whenever the weapon deals damage
+    EnergyReward(f32),
+    /// An effect on whatever the weapon deals damage to, such as burning it
+    OnHit(CombatEffect),
+}
+
+impl ItemModifier {
+    /// The effect the modifier adds to every attack that deals damage, if any
+    pub fn attack_effect(self) -> Option<AttackEffect> {
+        let effect = match self {
+            ItemModifier::EnergyReward(energy) => CombatEffect::EnergyReward(energy),
+            ItemModifier::OnHit(effect) => effect,
+            ItemModifier::Power(_) | ItemModifier::Speed(_) | ItemModifier::CritChance(_) => {
+                return None;
+            },
+        };
+        Some(
+            AttackEffect::new(Some(GroupTarget::OutOfGroup), effect)
+                .with_requirement(CombatRequirement::AnyDamage),
+        )
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct RawModifierPool {
+    rolls: (u32, u32),
+    modifiers: Vec<(f32, ItemModifier)>,
+}
+
+/// A pool of modifiers that loot tables roll items with
+#[derive(Clone, Debug)]
+pub struct ModifierPool {
+    /// The fewest and most modifiers an item rolls from the pool
+    rolls: (u32, u32),
+    modifiers: Lottery<ItemModifier>,
+}
+
+impl assets::Asset for ModifierPool {
+    type Loader = assets::LoadFrom<RawModifierPool, assets::RonLoader>;
+
+    const EXTENSION: &'static str = "ron";
+}
+
+impl From<RawModifierPool> for ModifierPool {
+    fn from(raw: RawModifierPool) -> Self {
+        Self {
+            rolls: raw.rolls,
+            modifiers: Lottery::from(raw.modifiers),
+        }
+    }
+}
+
+impl ModifierPool {
+    /// Rolls the modifiers for a single item, the same modifier can be rolled
+    /// more than once
+    pub fn roll(&self, rng: &mut impl Rng) -> Vec<ItemModifier> {
+        let (lower, upper) = self.rolls;
+        let rolls = rng.gen_range(lower..=upper.max(lower));
+        (0..rolls)
+            .map(|_| *self.modifiers.choose_seeded(rng.gen()))
+            .collect()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &ItemModifier> {
+        self.modifiers.iter().map(|(_, modifier)| modifier)
+    }
+}
+
+/// The modifier each gem gives the item it is socketed into, by the item
+/// definition id of the gem
+#[derive(Clone, Debug, Deserialize)]
+pub struct GemManifest(pub HashMap<String, ItemModifier>);
+
+impl assets::Asset for GemManifest {
+    type Loader = assets::RonLoader;
+
+    const EXTENSION: &'static str = "ron";
+}
+
+impl GemManifest {
+    pub fn load() -> AssetHandle<Self> { Self::load_expect(GEM_MANIFEST) }
+
+    /// The modifier the given gem gives, if it is a gem at all
+    pub fn modifier(&self, gem: &str) -> Option<ItemModifier> { self.0.get(gem).copied() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::comp::item::{ItemDef, Quality};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_all_gems_load() {
+        for gem in GemManifest::load().read().0.keys() {
+            let item_def = Arc::<ItemDef>::load_expect_cloned(gem);
+            assert!(item_def.is_stackable());
+            assert!(!matches!(item_def.quality, Quality::Debug));
+        }
+    }
+
+    #[test]
+    fn test_rolls_stay_in_range() {
+        let pool = ModifierPool::from(RawModifierPool {
+            rolls: (1, 2),
+            modifiers: vec![
+                (1.0, ItemModifier::Power(0.1)),
+                (1.0, ItemModifier::Speed(0.1)),
+            ],
+        });
+        let mut rng = rand::thread_rng();
+        for _ in 0..20 {
+            let modifiers = pool.roll(&mut rng);
+            assert!((1..=2).contains(&modifiers.len()));
+            assert!(modifiers.iter().all(|m| pool.iter().any(|p| p == m)));
+        }
+    }
+
+    #[test]
+    fn test_only_effects_apply_to_attacks() {
+        assert!(ItemModifier::Power(0.1).attack_effect().is_none());
+        assert!(ItemModifier::CritChance(0.1).attack_effect().is_none());
+        assert_eq!(
+            ItemModifier::EnergyReward(20.0)
+                .attack_effect()
+                .map(|effect| *effect.effect()),
+            Some(CombatEffect::EnergyReward(20.0))
+        );
+    }
+}
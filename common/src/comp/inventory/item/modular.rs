@@ -49,6 +49,7 @@ impl TagExampleInfo for ModularComponentTag {
                 ToolKind::Debug => "debug damage component",
                 ToolKind::Farming => "farming damage component",
                 ToolKind::Pick => "pickaxe head",
+                ToolKind::Fishing => "fishing damage component",
                 ToolKind::Natural => "natural damage component",
                 ToolKind::Empty => "empty damage component",
             },
@@ -67,6 +68,7 @@ impl TagExampleInfo for ModularComponentTag {
                 ToolKind::Debug => "debug held component",
                 ToolKind::Farming => "farming held component",
                 ToolKind::Pick => "pickaxe handle",
+                ToolKind::Fishing => "fishing held component",
                 ToolKind::Empty => "empty held component",
             },
         }
@@ -88,6 +90,7 @@ impl TagExampleInfo for ModularComponentTag {
                 ToolKind::Debug => "common.items.tag_examples.modular.damage.debug",
                 ToolKind::Farming => "common.items.tag_examples.modular.damage.farming",
                 ToolKind::Pick => "common.items.tag_examples.modular.damage.pick",
+                ToolKind::Fishing => "common.items.tag_examples.modular.damage.fishing",
                 ToolKind::Empty => "common.items.tag_examples.modular.damage.empty",
             },
             ModularComponentKind::Held => match self.toolkind {
@@ -104,6 +107,7 @@ impl TagExampleInfo for ModularComponentTag {
                 ToolKind::Debug => "common.items.tag_examples.modular.held.debug",
                 ToolKind::Farming => "common.items.tag_examples.modular.held.farming",
                 ToolKind::Pick => "common.items.tag_examples.modular.held.pick",
+                ToolKind::Fishing => "common.items.tag_examples.modular.held.fishing",
                 ToolKind::Empty => "common.items.tag_examples.modular.held.empty",
             },
         }
@@ -188,6 +192,8 @@ fn make_component_def(
         tags: vec![ItemTag::ModularComponent(tag)],
         slots: 0,
         ability_spec: None,
+        durability: None,
+        sockets: 0,
     };
     (identifier, item)
 }
@@ -211,6 +217,8 @@ fn make_weapon_def(toolkind: ToolKind) -> (String, RawItemDef) {
         tags: Vec::new(),
         slots: 0,
         ability_spec: None,
+        durability: None,
+        sockets: 0,
     };
     (identifier, item)
 }
@@ -229,6 +237,8 @@ fn make_recipe_def(identifier: String, toolkind: ToolKind) -> RawRecipe {
         output,
         inputs,
         craft_sprite: None,
+        craft_time: 0.0,
+        optional_inputs: Vec::new(),
     }
 }
 
@@ -264,6 +274,8 @@ fn make_tagexample_def(
         tags: vec![ItemTag::ModularComponent(tag)],
         slots: 0,
         ability_spec: None,
+        durability: None,
+        sockets: 0,
     };
     (identifier, item)
 }
@@ -3,7 +3,11 @@
 
 use crate::{
     assets::{self, Asset, AssetExt},
-    comp::{item::ItemKind, skills::Skill, CharacterAbility, Item},
+    comp::{
+        item::{ItemKind, ItemModifier},
+        skills::Skill,
+        CharacterAbility, Item,
+    },
 };
 use hashbrown::HashMap;
 use serde::{Deserialize, Serialize};
@@ -27,6 +31,7 @@ pub enum ToolKind {
     Debug,
     Farming,
     Pick,
+    Fishing,
     /// This is an placeholder item, it is used by non-humanoid npcs to attack
     Empty,
 }
@@ -47,6 +52,7 @@ impl ToolKind {
             ToolKind::Debug => "debug",
             ToolKind::Farming => "farming",
             ToolKind::Pick => "pickaxe",
+            ToolKind::Fishing => "fishing",
             ToolKind::Empty => "empty",
         }
     }
@@ -99,6 +105,19 @@ impl Stats {
         self.speed = self.speed.max(0.1);
         self
     }
+
+    /// Applies the modifiers of an item that change its stats
+    pub fn with_modifiers(mut self, modifiers: &[ItemModifier]) -> Stats {
+        for modifier in modifiers {
+            match modifier {
+                ItemModifier::Power(power) => self.power *= 1.0 + power,
+                ItemModifier::Speed(speed) => self.speed *= 1.0 + speed,
+                ItemModifier::CritChance(crit_chance) => self.crit_chance += crit_chance,
+                ItemModifier::EnergyReward(_) | ItemModifier::OnHit(_) => {},
+            }
+        }
+        self
+    }
 }
 
 impl Asset for Stats {
@@ -326,8 +345,9 @@ impl AbilitySet<CharacterAbility> {
         tool: &Tool,
         msm: &MaterialStatManifest,
         components: &[Item],
+        modifiers: &[ItemModifier],
     ) -> Self {
-        let stats = Stats::from((msm, components, tool));
+        let stats = Stats::from((msm, components, tool)).with_modifiers(modifiers);
         self.map(|a| a.adjusted_by_stats(stats.power, stats.poise_strength, stats.speed))
     }
 }
@@ -100,6 +100,15 @@ impl Loadout {
         self.slot(equip_slot).and_then(|x| x.slot.as_ref())
     }
 
+    /// Returns a mutable reference to the item (if any) equipped in the given
+    /// EquipSlot
+    pub(super) fn equipped_mut(&mut self, equip_slot: EquipSlot) -> Option<&mut Item> {
+        self.slots
+            .iter_mut()
+            .find(|loadout_slot| loadout_slot.equip_slot == equip_slot)
+            .and_then(|x| x.slot.as_mut())
+    }
+
     fn slot(&self, equip_slot: EquipSlot) -> Option<&LoadoutSlot> {
         self.slots
             .iter()
@@ -311,6 +320,12 @@ impl Loadout {
         self.slots.iter().filter_map(|x| x.slot.as_ref())
     }
 
+    pub(super) fn items_with_slot(&self) -> impl Iterator<Item = (EquipSlot, &Item)> {
+        self.slots
+            .iter()
+            .filter_map(|x| x.slot.as_ref().map(|item| (x.equip_slot, item)))
+    }
+
     /// Checks that a slot can hold a given item
     pub(super) fn slot_can_hold(
         &self,
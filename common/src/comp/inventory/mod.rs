@@ -9,7 +9,10 @@ use vek::Vec3;
 use crate::{
     comp::{
         inventory::{
-            item::{tool::AbilityMap, ItemDef, ItemKind, MaterialStatManifest, TagExampleInfo},
+            item::{
+                tool::AbilityMap, GemManifest, ItemDef, ItemKind, MaterialStatManifest,
+                TagExampleInfo,
+            },
             loadout::Loadout,
             slot::{EquipSlot, Slot, SlotError},
         },
@@ -91,6 +94,16 @@ impl Inventory {
         }
     }
 
+    /// Creates an inventory with no loadout and the given number of slots,
+    /// for containers that hold items but are never equipped from
+    pub fn new_with_capacity(slots: usize) -> Inventory {
+        Inventory {
+            next_sort_order: InventorySortOrder::Name,
+            loadout: LoadoutBuilder::new().build(),
+            slots: vec![None; slots],
+        }
+    }
+
     /// Total number of slots in in the inventory.
     pub fn capacity(&self) -> usize { self.slots().count() }
 
@@ -342,6 +355,28 @@ impl Inventory {
         self.loadout.equipped(equip_slot)
     }
 
+    /// Get the item in a slot, whether it is carried or equipped
+    pub fn get_slot(&self, slot: Slot) -> Option<&Item> {
+        match slot {
+            Slot::Inventory(inv_slot_id) => self.get(inv_slot_id),
+            Slot::Equip(equip_slot) => self.equipped(equip_slot),
+        }
+    }
+
+    /// Mutably get the item in a slot, whether it is carried or equipped
+    pub fn get_slot_mut(&mut self, slot: Slot) -> Option<&mut Item> {
+        match slot {
+            Slot::Inventory(inv_slot_id) => self.get_mut(inv_slot_id),
+            Slot::Equip(equip_slot) => self.equipped_mut(equip_slot),
+        }
+    }
+
+    /// Returns a mutable reference to the item (if any) equipped in the given
+    /// EquipSlot
+    pub fn equipped_mut(&mut self, equip_slot: EquipSlot) -> Option<&mut Item> {
+        self.loadout.equipped_mut(equip_slot)
+    }
+
     pub fn loadout_items_with_persistence_key(
         &self,
     ) -> impl Iterator<Item = (&str, Option<&Item>)> {
@@ -402,6 +437,42 @@ impl Inventory {
         }
     }
 
+    /// Whether the item in the `gem` slot is a gem that can be socketed into
+    /// the item in the `target` slot
+    pub fn can_socket(&self, gem: InvSlotId, target: Slot) -> bool {
+        target != Slot::Inventory(gem)
+            && self.get(gem).map_or(false, |gem| {
+                GemManifest::load()
+                    .read()
+                    .modifier(gem.item_definition_id())
+                    .is_some()
+            })
+            && self
+                .get_slot(target)
+                .map_or(false, |item| item.has_free_socket())
+    }
+
+    /// Sockets one of the gems in the `gem` slot into the item in the `target`
+    /// slot, returning whether it was socketed
+    pub fn socket_gem(
+        &mut self,
+        gem: InvSlotId,
+        target: Slot,
+        ability_map: &AbilityMap,
+        msm: &MaterialStatManifest,
+    ) -> bool {
+        if !self.can_socket(gem, target) {
+            return false;
+        }
+        let gem = match self.take(gem, ability_map, msm) {
+            Some(gem) => gem,
+            None => return false,
+        };
+        self.get_slot_mut(target).map_or(false, |item| {
+            item.socket_gem(gem.item_definition_id(), ability_map, msm)
+        })
+    }
+
     /// Takes half of the items from a slot in the inventory
     pub fn take_half(
         &mut self,
@@ -444,6 +515,32 @@ impl Inventory {
             .sum()
     }
 
+    /// Remove the given amount of a particular item, taking from as many stacks
+    /// as needed. Nothing is removed if there are fewer items than that in the
+    /// inventory; returns whether the items were removed.
+    pub fn remove_item_amount(&mut self, item_def: &ItemDef, amount: u32) -> bool {
+        if self.item_count(item_def) < u64::from(amount) {
+            return false;
+        }
+        let mut remaining = amount;
+        for slot in self.slots_mut() {
+            if remaining == 0 {
+                break;
+            }
+            if let Some(item) = slot.as_mut().filter(|it| it.is_same_item_def(item_def)) {
+                if item.amount() > remaining {
+                    // Can't fail, since the stack holds more than is being removed
+                    let _ = item.decrease_amount(remaining);
+                    remaining = 0;
+                } else {
+                    remaining -= item.amount();
+                    *slot = None;
+                }
+            }
+        }
+        true
+    }
+
     /// Determine whether the inventory contains the ingredients for a recipe.
     /// If it does, return a vector of numbers, where is number corresponds
     /// to an inventory slot, along with the number of items that need
@@ -452,11 +549,20 @@ impl Inventory {
     pub fn contains_ingredients<'a>(
         &self,
         recipe: &'a Recipe,
+    ) -> Result<HashMap<InvSlotId, u32>, Vec<(&'a RecipeInput, u32)>> {
+        self.contains_inputs(recipe.inputs())
+    }
+
+    /// Like `contains_ingredients`, but for any list of recipe inputs such as
+    /// the materials needed for a repair
+    pub fn contains_inputs<'a>(
+        &self,
+        inputs: impl Iterator<Item = (&'a RecipeInput, u32)>,
     ) -> Result<HashMap<InvSlotId, u32>, Vec<(&'a RecipeInput, u32)>> {
         let mut slot_claims = HashMap::<InvSlotId, u32>::new();
         let mut missing = Vec::<(&RecipeInput, u32)>::new();
 
-        for (input, mut needed) in recipe.inputs() {
+        for (input, mut needed) in inputs {
             let mut contains_any = false;
 
             for (inv_slot_id, slot) in self.slots_with_id() {
@@ -537,6 +643,11 @@ impl Inventory {
 
     pub fn equipped_items(&self) -> impl Iterator<Item = &Item> { self.loadout.items() }
 
+    /// Returns all equipped items along with the slot they are equipped in
+    pub fn equipped_items_with_slot(&self) -> impl Iterator<Item = (EquipSlot, &Item)> {
+        self.loadout.items_with_slot()
+    }
+
     /// Replaces the loadout item (if any) in the given EquipSlot with the
     /// provided item, returning the item that was previously in the slot.
     pub fn replace_loadout_item(
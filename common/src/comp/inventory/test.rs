@@ -498,3 +498,148 @@ fn fill_inv_slots(inv: &mut Inventory, items: u16) {
         inv.push(boots.duplicate(ability_map, msm)).unwrap();
     }
 }
+
+#[test]
+fn remove_item_amount_takes_from_stacks() {
+    use crate::assets::AssetExt;
+    use std::sync::Arc;
+
+    let mut inv = Inventory::new_empty();
+    let mut apples = Item::new_from_asset_expect("common.items.food.apple");
+    apples.set_amount(5).unwrap();
+    inv.push(apples).unwrap();
+    let apple_def = Arc::<ItemDef>::load_expect_cloned("common.items.food.apple");
+
+    assert!(!inv.remove_item_amount(&apple_def, 6));
+    assert_eq!(inv.item_count(&apple_def), 5);
+    assert!(inv.remove_item_amount(&apple_def, 4));
+    assert_eq!(inv.item_count(&apple_def), 1);
+    assert!(inv.remove_item_amount(&apple_def, 1));
+    assert_eq!(inv.populated_slots(), 0);
+}
+
+#[test]
+fn socket_gem_uses_up_one_gem() {
+    let msm = &MaterialStatManifest::default();
+    let ability_map = &AbilityMap::default();
+    let mut inv = Inventory::new_empty();
+    inv.push(Item::new_from_asset_expect(
+        "common.items.weapons.hammer_1h.orichalcum-0",
+    ))
+    .unwrap();
+    let mut rubies = Item::new_from_asset_expect("common.items.mineral.gem.ruby");
+    rubies.set_amount(2).unwrap();
+    inv.push(rubies).unwrap();
+    let slot_of = |inv: &Inventory, id: &str| {
+        inv.slots_with_id()
+            .find(|(_, slot)| {
+                slot.as_ref()
+                    .map_or(false, |item| item.item_definition_id() == id)
+            })
+            .map(|(slot, _)| slot)
+            .unwrap()
+    };
+    let hammer = Slot::Inventory(slot_of(&inv, "common.items.weapons.hammer_1h.orichalcum-0"));
+    let gem = slot_of(&inv, "common.items.mineral.gem.ruby");
+
+    assert!(!inv.socket_gem(gem, Slot::Inventory(gem), ability_map, msm));
+    assert!(inv.socket_gem(gem, hammer, ability_map, msm));
+    assert_eq!(inv.get(gem).map(Item::amount), Some(1));
+    assert_eq!(inv.get_slot(hammer).unwrap().sockets(), &[Some(
+        "common.items.mineral.gem.ruby".to_owned()
+    )]);
+    // The only socket of the hammer is taken now
+    assert!(!inv.socket_gem(gem, hammer, ability_map, msm));
+    assert_eq!(inv.get(gem).map(Item::amount), Some(1));
+}
+
+/// Wearing an item down should break it exactly once, and repairing it should
+/// restore its effectiveness.
+#[test]
+fn wear_and_repair() {
+    let mut item = Item::new_from_asset_expect("common.items.weapons.hammer_1h.iron-1");
+    assert!(!item.wear(199));
+    assert!((item.effectiveness() - 1.0).abs() < f32::EPSILON);
+
+    assert!(item.wear(1));
+    assert!(item.is_broken());
+    assert!((item.effectiveness() - item::BROKEN_ITEM_EFFECTIVENESS).abs() < f32::EPSILON);
+
+    // An item that is already broken doesn't break again
+    assert!(!item.wear(1));
+    assert_eq!(item.durability_lost(), 200);
+
+    item.repair();
+    assert!(!item.is_broken());
+    assert_eq!(item.durability_lost(), 0);
+    assert!((item.effectiveness() - 1.0).abs() < f32::EPSILON);
+}
+
+/// Items without durability should never wear out.
+#[test]
+fn wear_without_durability() {
+    let mut item = Item::new_from_asset_expect("common.items.debug.admin_stick");
+    assert!(!item.wear(u32::MAX));
+    assert!(!item.is_broken());
+    assert_eq!(item.durability_lost(), 0);
+    assert!((item.effectiveness() - 1.0).abs() < f32::EPSILON);
+}
+
+fn inv_with_worn_hammer() -> Inventory {
+    let mut hammer = Item::new_from_asset_expect("common.items.weapons.hammer_1h.iron-1");
+    hammer.wear(200);
+    let mut inv = Inventory::new_empty();
+    inv.try_equip(hammer).unwrap();
+    inv
+}
+
+/// Repairing should use up the materials of the recipe, but not its tools.
+#[test]
+fn repair_recipe_uses_materials() {
+    let msm = &MaterialStatManifest::default();
+    let ability_map = &AbilityMap::default();
+    let book = crate::recipe::default_repair_recipe_book().read();
+    let slot = Slot::Equip(EquipSlot::ActiveMainhand);
+    let mut inv = inv_with_worn_hammer();
+    inv.push(Item::new_from_asset_expect(
+        "common.items.mineral.ingot.iron",
+    ))
+    .unwrap();
+    inv.push(Item::new_from_asset_expect(
+        "common.items.tool.craftsman_hammer",
+    ))
+    .unwrap();
+
+    let recipe = book.get(inv.get_slot(slot).unwrap()).unwrap();
+    assert!(recipe.perform(&mut inv, slot, ability_map, msm).is_ok());
+    assert!(!inv.get_slot(slot).unwrap().is_broken());
+    assert_eq!(inv.populated_slots(), 1);
+}
+
+/// Repairing without the materials, or an item that isn't worn, should fail
+/// without changing the inventory.
+#[test]
+fn repair_recipe_fails() {
+    let msm = &MaterialStatManifest::default();
+    let ability_map = &AbilityMap::default();
+    let book = crate::recipe::default_repair_recipe_book().read();
+    let slot = Slot::Equip(EquipSlot::ActiveMainhand);
+    let mut inv = inv_with_worn_hammer();
+    inv.push(Item::new_from_asset_expect(
+        "common.items.tool.craftsman_hammer",
+    ))
+    .unwrap();
+
+    let recipe = book.get(inv.get_slot(slot).unwrap()).unwrap();
+    assert!(recipe.perform(&mut inv, slot, ability_map, msm).is_err());
+    assert!(inv.get_slot(slot).unwrap().is_broken());
+    assert_eq!(inv.populated_slots(), 1);
+
+    inv.get_slot_mut(slot).unwrap().repair();
+    inv.push(Item::new_from_asset_expect(
+        "common.items.mineral.ingot.iron",
+    ))
+    .unwrap();
+    assert!(recipe.perform(&mut inv, slot, ability_map, msm).is_err());
+    assert_eq!(inv.populated_slots(), 2);
+}
@@ -96,7 +96,9 @@ impl From<Vec<(f32, LootSpec)>> for ProbabilityFile {
             content: content
                 .into_iter()
                 .flat_map(|(p0, loot)| match loot {
-                    LootSpec::Item(asset) => vec![(p0, asset)].into_iter(),
+                    LootSpec::Item(asset) | LootSpec::ModifiedItem(asset, _) => {
+                        vec![(p0, asset)].into_iter()
+                    },
                     LootSpec::ItemQuantity(asset, a, b) => {
                         vec![(p0 * (a + b) as f32 / 2.0, asset)].into_iter()
                     },
@@ -13,11 +13,14 @@ pub mod compass;
 #[cfg(not(target_arch = "wasm32"))]
 mod controller;
 #[cfg(not(target_arch = "wasm32"))]
+pub mod crafting;
+#[cfg(not(target_arch = "wasm32"))]
 pub mod dialogue;
 #[cfg(not(target_arch = "wasm32"))] mod energy;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod fluid_dynamics;
 #[cfg(not(target_arch = "wasm32"))] pub mod group;
+#[cfg(not(target_arch = "wasm32"))] pub mod guild;
 mod health;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod home_chunk;
@@ -30,17 +33,26 @@ pub mod invite;
 #[cfg(not(target_arch = "wasm32"))] mod location;
 #[cfg(not(target_arch = "wasm32"))] mod misc;
 #[cfg(not(target_arch = "wasm32"))] pub mod ori;
+#[cfg(not(target_arch = "wasm32"))] pub mod pet;
 #[cfg(not(target_arch = "wasm32"))] mod phys;
 #[cfg(not(target_arch = "wasm32"))] mod player;
 #[cfg(not(target_arch = "wasm32"))] pub mod poise;
 #[cfg(not(target_arch = "wasm32"))]
+pub mod preset;
+#[cfg(not(target_arch = "wasm32"))]
 pub mod projectile;
+#[cfg(not(target_arch = "wasm32"))] pub mod pvp;
+#[cfg(not(target_arch = "wasm32"))] pub mod quest;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod shockwave;
 #[cfg(not(target_arch = "wasm32"))]
+pub mod skill_tree;
+#[cfg(not(target_arch = "wasm32"))]
 pub mod skills;
 #[cfg(not(target_arch = "wasm32"))] mod stats;
 #[cfg(not(target_arch = "wasm32"))]
+pub mod storage;
+#[cfg(not(target_arch = "wasm32"))]
 pub mod visual;
 
 // Reexports
@@ -54,7 +66,7 @@ pub use self::{
     body::{
         biped_large, biped_small, bird_large, bird_medium, dragon, fish_medium, fish_small, golem,
         humanoid, object, quadruped_low, quadruped_medium, quadruped_small, ship, theropod,
-        AllBodies, Body, BodyData,
+        AllBodies, Body, BodyData, MountStats,
     },
     buff::{
         Buff, BuffCategory, BuffChange, BuffData, BuffEffect, BuffId, BuffKind, BuffSource, Buffs,
@@ -66,13 +78,15 @@ pub use self::{
     },
     combo::Combo,
     controller::{
-        Climb, ControlAction, ControlEvent, Controller, ControllerInputs, GroupManip, InputAttr,
-        InputKind, InventoryAction, InventoryEvent, InventoryManip, MountState, Mounting,
-        UtteranceKind,
+        BuildManip, Climb, ControlAction, ControlEvent, Controller, ControllerInputs, GroupManip,
+        GuildManip, InputAttr, InputKind, InventoryAction, InventoryEvent, InventoryManip,
+        MailManip, MountState, Mounting, StorageManip, UtteranceKind,
     },
+    crafting::CraftingQueue,
     energy::{Energy, EnergyChange, EnergySource},
     fluid_dynamics::Fluid,
     group::Group,
+    guild::{Guild, GuildId, GuildInfo, GuildRank},
     home_chunk::HomeChunk,
     inputs::CanBuild,
     inventory::{
@@ -83,6 +97,7 @@ pub use self::{
     location::{Waypoint, WaypointArea},
     misc::Object,
     ori::Ori,
+    pet::{Pet, PetCommand},
     phys::{
         Collider, Density, ForceUpdate, Mass, PhysicsState, Pos, PosVelDefer, PreviousPhysCache,
         Scale, Sticky, Vel,
@@ -90,10 +105,14 @@ pub use self::{
     player::DisconnectReason,
     player::Player,
     poise::{Poise, PoiseChange, PoiseSource, PoiseState},
+    preset::{LoadoutPreset, LoadoutPresets},
     projectile::{Projectile, ProjectileConstructor},
+    pvp::{Duel, PvpFlag, PvpZone, PvpZoneKind},
+    quest::QuestLog,
     shockwave::{Shockwave, ShockwaveHitEntities},
     skills::{Skill, SkillGroup, SkillGroupKind, SkillSet},
     stats::Stats,
+    storage::{Bank, Storage, StorageTarget},
     visual::{LightAnimation, LightEmitter},
 };
 
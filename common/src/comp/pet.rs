@@ -0,0 +1,157 @@
+use crate::{
+    comp::{body::Body, Alignment, Stats},
+    uid::Uid,
+};
+use serde::{Deserialize, Serialize};
+use specs::Component;
+use specs_idvs::IdvStorage;
+use vek::*;
+
+/// The most pets a character can have at once
+pub const MAX_PETS: usize = 3;
+
+/// Marks a creature that was tamed by a player character. Unlike summons,
+/// pets are saved with the character of their owner and come back with it
+/// when it logs in.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Pet {
+    pub command: PetCommand,
+}
+
+/// What the owner of a pet last told it to do
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum PetCommand {
+    /// Stay close to the owner and defend them
+    Follow,
+    /// Stay at the given position until told to follow again
+    Stay(Vec3<f32>),
+}
+
+impl Default for PetCommand {
+    fn default() -> Self { PetCommand::Follow }
+}
+
+impl Component for Pet {
+    type Storage = IdvStorage<Self>;
+}
+
+/// What is remembered of a pet while its owner is offline
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PetPersistenceData {
+    pub name: String,
+    pub body: Body,
+}
+
+impl PetPersistenceData {
+    pub fn new(stats: &Stats, body: Body) -> Self {
+        Self {
+            name: stats.name.clone(),
+            body,
+        }
+    }
+}
+
+/// Whether a wild creature with this body can be tamed with a collar
+pub fn is_tameable(body: &Body) -> bool {
+    match body {
+        Body::QuadrupedSmall(_)
+        | Body::QuadrupedMedium(_)
+        | Body::QuadrupedLow(_)
+        | Body::BirdMedium(_) => true,
+        // Larger creatures can only be tamed if they can also be ridden
+        Body::BirdLarge(_) | Body::Theropod(_) => body.mount_stats().is_some(),
+        Body::Humanoid(_)
+        | Body::BipedSmall(_)
+        | Body::BipedLarge(_)
+        | Body::FishSmall(_)
+        | Body::FishMedium(_)
+        | Body::Dragon(_)
+        | Body::Golem(_)
+        | Body::Object(_)
+        | Body::Ship(_) => false,
+    }
+}
+
+/// Whether the given character may ride a mount with this body and alignment.
+/// Anyone can board a ship, but creatures only carry the one who tamed them.
+pub fn can_ride(mount: &Body, mount_alignment: Option<&Alignment>, rider: Uid) -> bool {
+    match mount {
+        Body::Ship(_) => true,
+        body if body.mount_stats().is_some() => {
+            matches!(mount_alignment, Some(Alignment::Owned(owner)) if *owner == rider)
+        },
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::comp::body::{bird_large, humanoid, quadruped_medium, ship, theropod};
+
+    fn horse() -> Body {
+        Body::QuadrupedMedium(quadruped_medium::Body::random_with(
+            &mut rand::thread_rng(),
+            &quadruped_medium::Species::Horse,
+        ))
+    }
+
+    #[test]
+    fn rideable_creatures_can_be_tamed() {
+        let mut rng = rand::thread_rng();
+        let bodies = quadruped_medium::ALL_SPECIES
+            .iter()
+            .map(|s| Body::QuadrupedMedium(quadruped_medium::Body::random_with(&mut rng, s)))
+            .chain(
+                theropod::ALL_SPECIES
+                    .iter()
+                    .map(|s| Body::Theropod(theropod::Body::random_with(&mut rng, s))),
+            )
+            .chain(
+                bird_large::ALL_SPECIES
+                    .iter()
+                    .map(|s| Body::BirdLarge(bird_large::Body::random_with(&mut rng, s))),
+            )
+            .collect::<Vec<_>>();
+        for body in bodies {
+            if let Some(stats) = body.mount_stats() {
+                assert!(is_tameable(&body), "{:?} can be ridden but not tamed", body);
+                assert!(stats.saddle_offset.z > 0.0);
+                assert!(stats.speed > 0.0 && stats.jump > 0.0 && stats.flight > 0.0);
+                assert_eq!(body.mounting_offset(), stats.saddle_offset);
+            }
+        }
+        assert!(horse().mount_stats().is_some());
+        assert!(
+            Body::Humanoid(humanoid::Body::random())
+                .mount_stats()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn only_owners_ride_their_creatures() {
+        let owner = Uid(1);
+        let stranger = Uid(2);
+        assert!(can_ride(&horse(), Some(&Alignment::Owned(owner)), owner));
+        assert!(!can_ride(
+            &horse(),
+            Some(&Alignment::Owned(owner)),
+            stranger
+        ));
+        assert!(!can_ride(&horse(), Some(&Alignment::Wild), owner));
+        assert!(!can_ride(&horse(), None, owner));
+        // Anyone can board a ship
+        assert!(can_ride(
+            &Body::Ship(ship::Body::DefaultAirship),
+            None,
+            stranger
+        ));
+        // Creatures that can't be ridden carry nobody, not even their owner
+        assert!(!can_ride(
+            &Body::Humanoid(humanoid::Body::random()),
+            Some(&Alignment::Owned(owner)),
+            owner
+        ));
+    }
+}
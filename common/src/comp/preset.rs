@@ -0,0 +1,113 @@
+use crate::comp::{inventory::slot::EquipSlot, skills::Skill, Inventory, SkillSet};
+use serde::{Deserialize, Serialize};
+use specs::Component;
+use specs_idvs::IdvStorage;
+
+/// The most presets a character can keep
+pub const MAX_PRESETS: usize = 5;
+pub const MAX_PRESET_NAME_LENGTH: usize = 24;
+/// How long after being hurt a character counts as being in combat, and can't
+/// switch presets
+pub const COMBAT_COOLDOWN: f64 = 10.0;
+
+/// A named build of skills and equipment that a character can switch to
+/// outside of combat
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LoadoutPreset {
+    pub name: String,
+    pub skills: Vec<(Skill, Option<u16>)>,
+    /// The item definition id of the item in each equipped slot
+    pub equipment: Vec<(EquipSlot, String)>,
+}
+
+impl LoadoutPreset {
+    /// Takes a snapshot of the current skills and equipment of a character
+    pub fn new(name: String, skill_set: &SkillSet, inventory: &Inventory) -> Self {
+        Self {
+            name,
+            skills: skill_set
+                .skills
+                .iter()
+                .map(|(skill, level)| (*skill, *level))
+                .collect(),
+            equipment: inventory
+                .equipped_items_with_slot()
+                .map(|(slot, item)| (slot, item.item_definition_id().to_owned()))
+                .collect(),
+        }
+    }
+}
+
+/// The presets a character has saved
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct LoadoutPresets {
+    pub presets: Vec<LoadoutPreset>,
+}
+
+impl LoadoutPresets {
+    pub fn get(&self, name: &str) -> Option<&LoadoutPreset> {
+        self.presets.iter().find(|preset| preset.name == name)
+    }
+
+    /// Saves a preset, replacing any preset with the same name. Returns the
+    /// preset back if there is no room for it.
+    pub fn save(&mut self, preset: LoadoutPreset) -> Result<(), LoadoutPreset> {
+        if let Some(existing) = self.presets.iter_mut().find(|p| p.name == preset.name) {
+            *existing = preset;
+        } else if self.presets.len() < MAX_PRESETS {
+            self.presets.push(preset);
+        } else {
+            return Err(preset);
+        }
+        Ok(())
+    }
+
+    /// Returns whether there was a preset with that name
+    pub fn remove(&mut self, name: &str) -> bool {
+        let len = self.presets.len();
+        self.presets.retain(|preset| preset.name != name);
+        self.presets.len() != len
+    }
+}
+
+impl Component for LoadoutPresets {
+    type Storage = IdvStorage<Self>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn preset(name: &str) -> LoadoutPreset {
+        LoadoutPreset {
+            name: name.to_owned(),
+            skills: Vec::new(),
+            equipment: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn save_replaces_preset_with_same_name() {
+        let mut presets = LoadoutPresets::default();
+        assert!(presets.save(preset("tank")).is_ok());
+        let mut tank = preset("tank");
+        tank.equipment.push((
+            EquipSlot::Lantern,
+            "common.items.lantern.black_0".to_owned(),
+        ));
+        assert!(presets.save(tank.clone()).is_ok());
+        assert_eq!(presets.presets, vec![tank]);
+    }
+
+    #[test]
+    fn save_respects_limit() {
+        let mut presets = LoadoutPresets::default();
+        for i in 0..MAX_PRESETS {
+            assert!(presets.save(preset(&i.to_string())).is_ok());
+        }
+        assert!(presets.save(preset("one too many")).is_err());
+        assert!(presets.remove("0"));
+        assert!(!presets.remove("0"));
+        assert!(presets.save(preset("one too many")).is_ok());
+    }
+}
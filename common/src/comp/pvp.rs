@@ -0,0 +1,69 @@
+use crate::uid::Uid;
+use serde::{Deserialize, Serialize};
+use specs::Component;
+use specs_idvs::IdvStorage;
+
+/// How long a player has to wait between turning PvP on or off, in seconds, so
+/// that it can't be used to escape from a fight
+pub const PVP_FLAG_COOLDOWN: f64 = 60.0;
+/// How long a duel lasts at most, in seconds
+pub const DUEL_DURATION: f64 = 180.0;
+
+/// Whether a player wants to fight other players, on servers that let players
+/// choose
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PvpFlag {
+    pub enabled: bool,
+    /// The time at which the flag was last changed
+    pub changed_at: f64,
+}
+
+impl PvpFlag {
+    pub fn can_toggle(&self, time: f64) -> bool { time - self.changed_at >= PVP_FLAG_COOLDOWN }
+
+    /// The time left until the flag can be changed again, in seconds
+    pub fn cooldown_left(&self, time: f64) -> f64 {
+        (self.changed_at + PVP_FLAG_COOLDOWN - time).max(0.0)
+    }
+}
+
+impl Component for PvpFlag {
+    type Storage = IdvStorage<Self>;
+}
+
+/// A fight two players agreed to. Duelling players can hurt each other
+/// regardless of the PvP rules until the duel ends.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Duel {
+    pub opponent: Uid,
+    pub ends_at: f64,
+}
+
+impl Duel {
+    pub fn is_active(&self, time: f64) -> bool { time < self.ends_at }
+}
+
+impl Component for Duel {
+    type Storage = IdvStorage<Self>;
+}
+
+/// The rules of a PvP zone. Where zones overlap, the stricter one applies.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum PvpZoneKind {
+    /// Players can always hurt each other
+    Pvp,
+    /// Players can never hurt each other, except in duels
+    NoPvp,
+}
+
+/// An area around an entity in which the PvP rules of the server are
+/// overridden
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PvpZone {
+    pub kind: PvpZoneKind,
+    pub radius: f32,
+}
+
+impl Component for PvpZone {
+    type Storage = IdvStorage<Self>;
+}
@@ -0,0 +1,276 @@
+use crate::{
+    assets::{self, AssetExt, AssetHandle},
+    comp::{
+        biped_large, biped_small, bird_medium, item::ItemDef, quadruped_low, quadruped_medium,
+        quadruped_small, theropod, Body,
+    },
+    lottery::Lottery,
+    uid::Uid,
+};
+use serde::{Deserialize, Serialize};
+use specs::Component;
+use specs_idvs::IdvStorage;
+use std::sync::Arc;
+use vek::*;
+
+/// The most quests a character can have active at the same time
+pub const MAX_ACTIVE_QUESTS: usize = 5;
+
+/// Asset specifier of the lottery that quest givers draw templates from
+pub const QUEST_POOL: &str = "common.quests.pool";
+
+/// How close to the centre of a site one has to be for it to count as reached
+pub const DESTINATION_RADIUS: f32 = 64.0;
+
+/// What a character has to do to complete a quest
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Objective {
+    /// Collect the given amount of an item, which is handed over once there
+    /// are enough of it
+    Fetch { item: String, amount: u32 },
+    /// Kill the given number of creatures
+    Kill { target: KillTarget, amount: u32 },
+    /// Accompany the quest giver to a nearby site
+    Escort,
+    /// Carry the given amount of an item to a nearby site, where it is handed
+    /// over
+    Deliver { item: String, amount: u32 },
+}
+
+impl Objective {
+    /// The progress at which the objective is complete
+    pub fn required(&self) -> u32 {
+        match self {
+            Objective::Fetch { amount, .. } | Objective::Kill { amount, .. } => *amount,
+            Objective::Escort | Objective::Deliver { .. } => 1,
+        }
+    }
+
+    /// Whether the quest giver has to name a site to travel to
+    pub fn needs_destination(&self) -> bool {
+        matches!(self, Objective::Escort | Objective::Deliver { .. })
+    }
+}
+
+/// The kinds of creature that kill objectives can ask for
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum KillTarget {
+    QuadrupedSmall(quadruped_small::Species),
+    QuadrupedMedium(quadruped_medium::Species),
+    QuadrupedLow(quadruped_low::Species),
+    BirdMedium(bird_medium::Species),
+    BipedSmall(biped_small::Species),
+    BipedLarge(biped_large::Species),
+    Theropod(theropod::Species),
+}
+
+impl KillTarget {
+    pub fn matches(&self, body: &Body) -> bool {
+        match (self, body) {
+            (KillTarget::QuadrupedSmall(s), Body::QuadrupedSmall(b)) => *s == b.species,
+            (KillTarget::QuadrupedMedium(s), Body::QuadrupedMedium(b)) => *s == b.species,
+            (KillTarget::QuadrupedLow(s), Body::QuadrupedLow(b)) => *s == b.species,
+            (KillTarget::BirdMedium(s), Body::BirdMedium(b)) => *s == b.species,
+            (KillTarget::BipedSmall(s), Body::BipedSmall(b)) => *s == b.species,
+            (KillTarget::BipedLarge(s), Body::BipedLarge(b)) => *s == b.species,
+            (KillTarget::Theropod(s), Body::Theropod(b)) => *s == b.species,
+            _ => false,
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            KillTarget::QuadrupedSmall(s) => format!("{:?}", s),
+            KillTarget::QuadrupedMedium(s) => format!("{:?}", s),
+            KillTarget::QuadrupedLow(s) => format!("{:?}", s),
+            KillTarget::BirdMedium(s) => format!("{:?}", s),
+            KillTarget::BipedSmall(s) => format!("{:?}", s),
+            KillTarget::BipedLarge(s) => format!("{:?}", s),
+            KillTarget::Theropod(s) => format!("{:?}", s),
+        }
+    }
+}
+
+/// What a character receives for completing a quest
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Reward {
+    /// Experience, shared between the general skill tree and the trees of the
+    /// equipped weapons
+    #[serde(default)]
+    pub exp: u32,
+    /// Items, as pairs of asset specifier and amount
+    #[serde(default)]
+    pub items: Vec<(String, u32)>,
+}
+
+/// A kind of quest that NPCs can hand out, loaded from
+/// `assets/common/quests`.
+///
+/// The title and description may contain the placeholders `{amount}`,
+/// `{item}`, `{target}` and `{site}`, which are filled in from the objective
+/// and the destination of each quest.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct QuestTemplate {
+    pub title: String,
+    pub description: String,
+    pub objective: Objective,
+    #[serde(default)]
+    pub reward: Reward,
+}
+
+impl assets::Asset for QuestTemplate {
+    type Loader = assets::RonLoader;
+
+    const EXTENSION: &'static str = "ron";
+}
+
+impl QuestTemplate {
+    /// Draw a random template from the quest pool, returning its specifier
+    pub fn choose() -> String {
+        Lottery::<String>::load_expect(QUEST_POOL)
+            .read()
+            .choose()
+            .clone()
+    }
+}
+
+/// A site that a quest asks the character to travel to
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Destination {
+    pub name: String,
+    pub wpos: Vec2<i32>,
+}
+
+/// A quest that a character has accepted
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Quest {
+    /// Asset specifier of the [`QuestTemplate`]
+    pub template: String,
+    /// Name of the NPC that gave out the quest
+    pub giver: String,
+    pub destination: Option<Destination>,
+    /// The NPC being escorted, for escort quests
+    pub escortee: Option<Uid>,
+    pub progress: u32,
+}
+
+impl Quest {
+    pub fn template(&self) -> AssetHandle<QuestTemplate> {
+        QuestTemplate::load_expect(&self.template)
+    }
+
+    pub fn objective(&self) -> Objective { self.template().read().objective.clone() }
+
+    pub fn is_complete(&self) -> bool { self.progress >= self.objective().required() }
+
+    pub fn title(&self) -> String { self.fill_placeholders(&self.template().read().title) }
+
+    pub fn description(&self) -> String {
+        self.fill_placeholders(&self.template().read().description)
+    }
+
+    fn fill_placeholders(&self, text: &str) -> String {
+        let (amount, item, target) = match self.objective() {
+            Objective::Fetch { item, amount } | Objective::Deliver { item, amount } => (
+                amount,
+                Arc::<ItemDef>::load_expect_cloned(&item).name.clone(),
+                String::new(),
+            ),
+            Objective::Kill { target, amount } => (amount, String::new(), target.name()),
+            Objective::Escort => (1, String::new(), String::new()),
+        };
+        text.replace("{amount}", &amount.to_string())
+            .replace("{item}", &item)
+            .replace("{target}", &target)
+            .replace(
+                "{site}",
+                self.destination.as_ref().map_or("", |dest| &dest.name),
+            )
+    }
+}
+
+/// The quests of a character
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct QuestLog {
+    pub active: Vec<Quest>,
+    /// How many quests the character has completed
+    pub completed: u32,
+}
+
+impl QuestLog {
+    pub fn is_full(&self) -> bool { self.active.len() >= MAX_ACTIVE_QUESTS }
+
+    pub fn has_quest(&self, template: &str) -> bool {
+        self.active.iter().any(|quest| quest.template == template)
+    }
+
+    /// Count a kill towards all kill objectives that ask for the body that was
+    /// killed. Returns whether any quest progressed.
+    pub fn count_kill(&mut self, body: &Body) -> bool {
+        let mut progressed = false;
+        for quest in self.active.iter_mut() {
+            if let Objective::Kill { target, amount } = quest.objective() {
+                if target.matches(body) && quest.progress < amount {
+                    quest.progress += 1;
+                    progressed = true;
+                }
+            }
+        }
+        progressed
+    }
+}
+
+impl Component for QuestLog {
+    type Storage = IdvStorage<Self>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_all_quest_templates_load() {
+        let pool = Lottery::<String>::load_expect(QUEST_POOL);
+        for (_, template) in pool.read().iter() {
+            let quest = Quest {
+                template: template.clone(),
+                giver: "Tester".to_string(),
+                destination: None,
+                escortee: None,
+                progress: 0,
+            };
+            assert!(quest.objective().required() > 0);
+            for (item, _) in quest.template().read().reward.items.iter() {
+                Arc::<ItemDef>::load_expect(item);
+            }
+            // Item names are looked up while filling in the placeholders
+            quest.description();
+        }
+    }
+
+    #[test]
+    fn test_kills_only_count_matching_species() {
+        let mut log = QuestLog {
+            active: vec![Quest {
+                template: "common.quests.cull_wolves".to_string(),
+                giver: "Tester".to_string(),
+                destination: None,
+                escortee: None,
+                progress: 0,
+            }],
+            completed: 0,
+        };
+        let wolf = Body::QuadrupedMedium(quadruped_medium::Body::random_with(
+            &mut rand::thread_rng(),
+            &quadruped_medium::Species::Wolf,
+        ));
+        let bear = Body::QuadrupedMedium(quadruped_medium::Body::random_with(
+            &mut rand::thread_rng(),
+            &quadruped_medium::Species::Bear,
+        ));
+
+        assert!(!log.count_kill(&bear));
+        assert!(log.count_kill(&wolf));
+        assert_eq!(log.active[0].progress, 1);
+    }
+}
@@ -0,0 +1,260 @@
+use crate::{
+    assets::{self, Asset, AssetExt},
+    comp::item::tool::ToolKind,
+};
+use hashbrown::{HashMap, HashSet};
+use lazy_static::lazy_static;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use tracing::warn;
+
+/// Experience given to each data skill group that levels from crafting, every
+/// time something is crafted
+pub const CRAFTING_EXPERIENCE: i32 = 10;
+
+/// What a data skill group gains experience from
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExperienceSource {
+    /// Killing enemies, shared with the general and weapon skill groups
+    Combat,
+    /// Mining blocks with a tool
+    Mining,
+    /// Crafting any recipe
+    Crafting,
+}
+
+/// A field of a `CharacterAbility` that a data skill can scale
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AbilityField {
+    EnergyCost,
+    BaseDamage,
+    Range,
+    BuildupDuration,
+    SwingDuration,
+    RecoverDuration,
+}
+
+/// Multiplies a field of the abilities of a tool by `multiplier` for each level
+/// of the skill
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AbilityModifier {
+    /// The tool whose abilities are changed, `None` changes the roll
+    pub tool: Option<ToolKind>,
+    pub field: AbilityField,
+    pub multiplier: f32,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct RawSkillGroup {
+    title: String,
+    #[serde(default)]
+    experience: Vec<ExperienceSource>,
+    skills: Vec<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct RawSkill {
+    title: String,
+    desc: String,
+    #[serde(default)]
+    max_level: Option<u16>,
+    #[serde(default)]
+    prerequisites: HashMap<String, Option<u16>>,
+    #[serde(default)]
+    modifiers: Vec<AbilityModifier>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct DataSkillTreeManifest {
+    groups: HashMap<String, RawSkillGroup>,
+    skills: HashMap<String, RawSkill>,
+}
+
+impl Asset for DataSkillTreeManifest {
+    type Loader = assets::RonLoader;
+
+    const EXTENSION: &'static str = "ron";
+}
+
+pub struct DataSkillGroupDef {
+    /// Localization key of the name of the group
+    pub title: String,
+    pub experience: Vec<ExperienceSource>,
+    pub skills: Vec<DataSkill>,
+}
+
+pub struct DataSkillDef {
+    /// Localization key of the name of the skill
+    pub title: String,
+    /// Localization key of the description of the skill
+    pub desc: String,
+    pub max_level: Option<u16>,
+    pub prerequisites: Vec<(DataSkill, Option<u16>)>,
+    pub modifiers: Vec<AbilityModifier>,
+}
+
+/// The skill groups and skills declared in
+/// `common.skill_trees.data_skill_trees`, keyed by their ids
+pub struct DataSkillTrees {
+    pub groups: HashMap<&'static str, DataSkillGroupDef>,
+    pub skills: HashMap<&'static str, DataSkillDef>,
+}
+
+impl DataSkillTrees {
+    fn from_manifest(manifest: DataSkillTreeManifest) -> Self {
+        // Ids are interned for the lifetime of the program so that skills stay
+        // `Copy` like the ones declared in code
+        fn intern(id: &str) -> &'static str { Box::leak(id.to_owned().into_boxed_str()) }
+
+        let skill_ids = manifest
+            .skills
+            .keys()
+            .map(|id| (id.as_str(), intern(id)))
+            .collect::<HashMap<_, _>>();
+        let lookup = |id: &str, context: &str| {
+            let skill = skill_ids.get(id).map(|id| DataSkill(*id));
+            if skill.is_none() {
+                warn!(?id, ?context, "Unknown skill in data skill trees");
+            }
+            skill
+        };
+
+        let mut grouped = HashSet::new();
+        let groups = manifest
+            .groups
+            .iter()
+            .map(|(id, group)| {
+                let skills = group
+                    .skills
+                    .iter()
+                    .filter_map(|skill| lookup(skill.as_str(), id.as_str()))
+                    .filter(|skill| {
+                        let first = grouped.insert(*skill);
+                        if !first {
+                            warn!(?skill, "Data skill is in more than one skill group");
+                        }
+                        first
+                    })
+                    .collect();
+                (intern(id), DataSkillGroupDef {
+                    title: group.title.clone(),
+                    experience: group.experience.clone(),
+                    skills,
+                })
+            })
+            .collect();
+        let skills = manifest
+            .skills
+            .iter()
+            .map(|(id, skill)| {
+                (skill_ids[id.as_str()], DataSkillDef {
+                    title: skill.title.clone(),
+                    desc: skill.desc.clone(),
+                    max_level: skill.max_level,
+                    prerequisites: skill
+                        .prerequisites
+                        .iter()
+                        .filter_map(|(prerequisite, level)| {
+                            lookup(prerequisite.as_str(), id.as_str()).map(|skill| (skill, *level))
+                        })
+                        .collect(),
+                    modifiers: skill.modifiers.clone(),
+                })
+            })
+            .collect();
+
+        Self { groups, skills }
+    }
+
+    /// The skill groups in a stable order, for display
+    pub fn sorted_groups(&self) -> Vec<DataSkillGroup> {
+        let mut groups = self
+            .groups
+            .keys()
+            .map(|id| DataSkillGroup(*id))
+            .collect::<Vec<_>>();
+        groups.sort_by_key(|group| group.0);
+        groups
+    }
+
+    /// The skill groups that gain experience from a source
+    pub fn groups_gaining(
+        &self,
+        source: ExperienceSource,
+    ) -> impl Iterator<Item = DataSkillGroup> + '_ {
+        self.groups
+            .iter()
+            .filter(move |(_, group)| group.experience.contains(&source))
+            .map(|(id, _)| DataSkillGroup(*id))
+    }
+}
+
+lazy_static! {
+    pub static ref DATA_SKILL_TREES: DataSkillTrees = DataSkillTrees::from_manifest(
+        DataSkillTreeManifest::load_expect_cloned("common.skill_trees.data_skill_trees")
+    );
+}
+
+/// A skill declared in the data skill trees, identified by its id
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DataSkill(&'static str);
+
+impl DataSkill {
+    /// Returns `None` if no skill with that id is declared
+    pub fn from_id(id: &str) -> Option<Self> {
+        DATA_SKILL_TREES
+            .skills
+            .get_key_value(id)
+            .map(|(id, _)| Self(*id))
+    }
+
+    pub fn id(self) -> &'static str { self.0 }
+
+    pub fn def(self) -> &'static DataSkillDef { &DATA_SKILL_TREES.skills[self.0] }
+}
+
+/// A skill group declared in the data skill trees, identified by its id
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DataSkillGroup(&'static str);
+
+impl DataSkillGroup {
+    /// Returns `None` if no skill group with that id is declared
+    pub fn from_id(id: &str) -> Option<Self> {
+        DATA_SKILL_TREES
+            .groups
+            .get_key_value(id)
+            .map(|(id, _)| Self(*id))
+    }
+
+    pub fn id(self) -> &'static str { self.0 }
+
+    pub fn def(self) -> &'static DataSkillGroupDef { &DATA_SKILL_TREES.groups[self.0] }
+}
+
+macro_rules! impl_id_traits {
+    ($name:ident) => {
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "{}({:?})", stringify!($name), self.0)
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(self.0)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let id = String::deserialize(deserializer)?;
+                Self::from_id(&id).ok_or_else(|| {
+                    de::Error::custom(format!("Unknown {} id: {}", stringify!($name), id))
+                })
+            }
+        }
+    };
+}
+
+impl_id_traits!(DataSkill);
+impl_id_traits!(DataSkillGroup);
@@ -1,6 +1,9 @@
 use crate::{
     assets::{self, Asset, AssetExt},
-    comp::item::tool::ToolKind,
+    comp::{
+        item::tool::ToolKind,
+        skill_tree::{DataSkill, DataSkillGroup, ExperienceSource, DATA_SKILL_TREES},
+    },
 };
 use hashbrown::{HashMap, HashSet};
 use lazy_static::lazy_static;
@@ -42,14 +45,32 @@ impl Asset for SkillPrerequisitesMap {
     const EXTENSION: &'static str = "ron";
 }
 
+/// Loads the skill groups declared in code along with the ones declared in the
+/// data skill trees
+fn load_skill_tree_map() -> HashMap<SkillGroupKind, HashSet<Skill>> {
+    let mut map =
+        SkillTreeMap::load_expect_cloned("common.skill_trees.skills_skill-groups_manifest").0;
+    map.extend(DATA_SKILL_TREES.groups.keys().map(|id| {
+        let group = DataSkillGroup::from_id(id).expect("Data skill groups are keyed by id");
+        (
+            SkillGroupKind::Data(group),
+            group
+                .def()
+                .skills
+                .iter()
+                .map(|skill| Skill::Data(*skill))
+                .collect(),
+        )
+    }));
+    map
+}
+
 lazy_static! {
     // Determines the skills that comprise each skill group - this data is used to determine
     // which of a player's skill groups a particular skill should be added to when a skill unlock
     // is requested.
     pub static ref SKILL_GROUP_DEFS: HashMap<SkillGroupKind, SkillGroupDef> = {
-        let map = SkillTreeMap::load_expect_cloned(
-            "common.skill_trees.skills_skill-groups_manifest",
-        ).0;
+        let map = load_skill_tree_map();
         map.iter().map(|(sgk, skills)|
             (*sgk, SkillGroupDef { skills: skills.clone(),
                 total_skill_point_cost: skills
@@ -71,22 +92,36 @@ lazy_static! {
     };
     // Creates a hashmap for the reverse lookup of skill groups from a skill
     pub static ref SKILL_GROUP_LOOKUP: HashMap<Skill, SkillGroupKind> = {
-        let map = SkillTreeMap::load_expect_cloned(
-            "common.skill_trees.skills_skill-groups_manifest",
-        ).0;
+        let map = load_skill_tree_map();
         map.iter().map(|(sgk, skills)| skills.into_iter().map(move |s| (*s, *sgk))).flatten().collect()
     };
     // Loads the maximum level that a skill can obtain
     pub static ref SKILL_MAX_LEVEL: HashMap<Skill, Option<u16>> = {
-        SkillLevelMap::load_expect_cloned(
+        let mut map = SkillLevelMap::load_expect_cloned(
             "common.skill_trees.skill_max_levels",
-        ).0
+        ).0;
+        map.extend(DATA_SKILL_TREES.skills.keys().filter_map(|id| {
+            let skill = DataSkill::from_id(id)?;
+            Some((Skill::Data(skill), skill.def().max_level))
+        }));
+        map
     };
     // Loads the prerequisite skills for a particular skill
     pub static ref SKILL_PREREQUISITES: HashMap<Skill, HashMap<Skill, Option<u16>>> = {
-        SkillPrerequisitesMap::load_expect_cloned(
+        let mut map = SkillPrerequisitesMap::load_expect_cloned(
             "common.skill_trees.skill_prerequisites",
-        ).0
+        ).0;
+        map.extend(DATA_SKILL_TREES.skills.keys().filter_map(|id| {
+            let skill = DataSkill::from_id(id)?;
+            let prerequisites = skill
+                .def()
+                .prerequisites
+                .iter()
+                .map(|(prerequisite, level)| (Skill::Data(*prerequisite), *level))
+                .collect::<HashMap<_, _>>();
+            (!prerequisites.is_empty()).then(|| (Skill::Data(skill), prerequisites))
+        }));
+        map
     };
 }
 
@@ -108,6 +143,8 @@ pub enum Skill {
     Climb(ClimbSkill),
     Swim(SwimSkill),
     Pick(MiningSkill),
+    /// A skill declared in the data skill trees
+    Data(DataSkill),
 }
 
 pub enum SkillError {
@@ -275,6 +312,8 @@ pub enum MiningSkill {
 pub enum SkillGroupKind {
     General,
     Weapon(ToolKind),
+    /// A skill group declared in the data skill trees
+    Data(DataSkillGroup),
 }
 
 impl SkillGroupKind {
@@ -439,7 +478,7 @@ impl SkillSet {
     }
 
     /// Removes a skill from a player and refunds 1 skill point in the relevant
-    /// skill group.
+    /// skill group. Returns whether the skill was refunded.
     ///
     /// ```
     /// use veloren_common::comp::skills::{GeneralSkill, Skill, SkillGroupKind, SkillSet};
@@ -452,7 +491,7 @@ impl SkillSet {
     ///
     /// assert_eq!(skillset.skills.len(), 0);
     /// ```
-    pub fn refund_skill(&mut self, skill: Skill) {
+    pub fn refund_skill(&mut self, skill: Skill) -> bool {
         if let Ok(level) = self.skill_level(skill) {
             if let Some(skill_group_kind) = skill.skill_group_kind() {
                 if let Some(mut skill_group) = self.skill_group_mut(skill_group_kind) {
@@ -462,6 +501,7 @@ impl SkillSet {
                     } else {
                         self.skills.remove(&skill);
                     }
+                    return true;
                 } else {
                     warn!("Tried to refund skill for a skill group that player does not have");
                 }
@@ -474,6 +514,84 @@ impl SkillSet {
         } else {
             warn!("Tried to refund skill that has not been unlocked");
         }
+        false
+    }
+
+    /// Checks if there are any skills in a skill group that a respec would
+    /// refund. Skills that unlock other skill groups are kept by a respec.
+    pub fn can_respec_skill_group(&self, skill_group_kind: SkillGroupKind) -> bool {
+        self.skills.keys().any(|skill| {
+            skill.skill_group_kind() == Some(skill_group_kind)
+                && !matches!(skill, Skill::UnlockGroup(_))
+        })
+    }
+
+    /// Refunds every skill in a skill group at once, except for those that
+    /// unlock other skill groups.
+    ///
+    /// ```
+    /// use veloren_common::comp::skills::{GeneralSkill, Skill, SkillGroupKind, SkillSet};
+    ///
+    /// let mut skillset = SkillSet::default();
+    /// skillset.add_skill_points(SkillGroupKind::General, 2);
+    /// skillset.unlock_skill(Skill::General(GeneralSkill::HealthIncrease));
+    /// skillset.unlock_skill(Skill::General(GeneralSkill::HealthIncrease));
+    ///
+    /// skillset.respec_skill_group(SkillGroupKind::General);
+    ///
+    /// assert_eq!(skillset.skills.len(), 0);
+    /// assert_eq!(skillset.available_sp(SkillGroupKind::General), 2);
+    /// ```
+    pub fn respec_skill_group(&mut self, skill_group_kind: SkillGroupKind) {
+        let skills = self
+            .skills
+            .keys()
+            .filter(|skill| {
+                skill.skill_group_kind() == Some(skill_group_kind)
+                    && !matches!(skill, Skill::UnlockGroup(_))
+            })
+            .copied()
+            .collect::<Vec<_>>();
+        for skill in skills {
+            // Skills that can't be refunded are kept, rather than retried forever
+            while self.has_skill(skill) && self.refund_skill(skill) {}
+        }
+        // Skills such as health increase may have been refunded
+        self.modify_health = true;
+        self.modify_energy = true;
+    }
+
+    /// Respecs every skill group, then unlocks the given skills up to the
+    /// given levels, as far as skill points allow. The skills may be in any
+    /// order, they are unlocked once their prerequisites are.
+    pub fn apply_skills(&mut self, skills: &[(Skill, Option<u16>)]) {
+        let skill_group_kinds = self
+            .skill_groups
+            .iter()
+            .map(|skill_group| skill_group.skill_group_kind)
+            .collect::<Vec<_>>();
+        for skill_group_kind in skill_group_kinds {
+            self.respec_skill_group(skill_group_kind);
+        }
+
+        let mut progressed = true;
+        while progressed {
+            progressed = false;
+            for (skill, level) in skills {
+                let reached = match self.skill_level(*skill) {
+                    Ok(current) => current >= *level,
+                    Err(_) => false,
+                };
+                if !reached
+                    && self.prerequisites_met(*skill)
+                    && self.sufficient_skill_points(*skill)
+                {
+                    let before = self.skill_level(*skill).ok();
+                    self.unlock_skill(*skill);
+                    progressed |= self.skill_level(*skill).ok() != before;
+                }
+            }
+        }
     }
 
     /// Adds skill points to a skill group as long as the player has that skill
@@ -520,6 +638,22 @@ impl SkillSet {
             .any(|x| x.skill_group_kind == skill_group_kind)
     }
 
+    /// Returns the data skill groups that gain experience from a source,
+    /// unlocking the ones the skill set doesn't have yet since every character
+    /// has access to them
+    pub fn unlock_data_skill_groups(&mut self, source: ExperienceSource) -> Vec<SkillGroupKind> {
+        DATA_SKILL_TREES
+            .groups_gaining(source)
+            .map(|group| {
+                let skill_group_kind = SkillGroupKind::Data(group);
+                if !self.contains_skill_group(skill_group_kind) {
+                    self.unlock_skill_group(skill_group_kind);
+                }
+                skill_group_kind
+            })
+            .collect()
+    }
+
     /// Adds/subtracts experience to the skill group within an entity's skill
     /// set
     pub fn change_experience(&mut self, skill_group_kind: SkillGroupKind, amount: i32) {
@@ -689,6 +823,19 @@ mod tests {
         assert_eq!(skillset.skills.get(&Skill::Axe(AxeSkill::UnlockLeap)), None);
     }
 
+    #[test]
+    fn test_respec_keeps_unrefundable_skills() {
+        let mut skillset = SkillSet::default();
+        // The axe skill group was never unlocked, so the skill can't be refunded
+        skillset
+            .skills
+            .insert(Skill::Axe(AxeSkill::UnlockLeap), None);
+
+        skillset.respec_skill_group(SkillGroupKind::Weapon(ToolKind::Axe));
+
+        assert!(skillset.has_skill(Skill::Axe(AxeSkill::UnlockLeap)));
+    }
+
     #[test]
     fn test_unlock_skillgroup() {
         let mut skillset = SkillSet::default();
@@ -733,4 +880,63 @@ mod tests {
 
         assert_eq!(skillset.skill_groups[2].available_sp, 1);
     }
+
+    #[test]
+    fn test_respec_skill_group() {
+        let mut skillset = SkillSet::default();
+        skillset.unlock_skill_group(SkillGroupKind::Weapon(ToolKind::Axe));
+        skillset.add_skill_points(SkillGroupKind::Weapon(ToolKind::Axe), 2);
+        skillset.unlock_skill(Skill::Axe(AxeSkill::UnlockLeap));
+        skillset.unlock_skill(Skill::Axe(AxeSkill::LDamage));
+
+        assert!(skillset.can_respec_skill_group(SkillGroupKind::Weapon(ToolKind::Axe)));
+
+        skillset.respec_skill_group(SkillGroupKind::Weapon(ToolKind::Axe));
+
+        assert_eq!(skillset.skill_groups[2].available_sp, 2);
+        assert_eq!(skillset.skills.len(), 0);
+        assert!(!skillset.can_respec_skill_group(SkillGroupKind::Weapon(ToolKind::Axe)));
+    }
+
+    #[test]
+    fn test_apply_skills() {
+        let mut skillset = SkillSet::default();
+        skillset.unlock_skill_group(SkillGroupKind::Weapon(ToolKind::Axe));
+        skillset.add_skill_points(SkillGroupKind::Weapon(ToolKind::Axe), 2);
+        skillset.unlock_skill(Skill::Axe(AxeSkill::UnlockLeap));
+
+        // Prerequisites come after the skills that need them
+        skillset.apply_skills(&[
+            (Skill::Axe(AxeSkill::LDamage), Some(1)),
+            (Skill::Axe(AxeSkill::UnlockLeap), None),
+        ]);
+
+        assert_eq!(skillset.skill_groups[2].available_sp, 0);
+        assert!(skillset.has_skill(Skill::Axe(AxeSkill::UnlockLeap)));
+        assert!(skillset.has_skill(Skill::Axe(AxeSkill::LDamage)));
+    }
+
+    #[test]
+    fn test_data_skill_groups() {
+        let mut skillset = SkillSet::default();
+        let gathering = SkillGroupKind::Data(DataSkillGroup::from_id("gathering").unwrap());
+        let swift_pick = Skill::Data(DataSkill::from_id("swift_pick").unwrap());
+        let long_reach = Skill::Data(DataSkill::from_id("long_reach").unwrap());
+
+        assert!(!skillset.contains_skill_group(gathering));
+        assert!(
+            skillset
+                .unlock_data_skill_groups(ExperienceSource::Mining)
+                .contains(&gathering)
+        );
+        assert!(skillset.contains_skill_group(gathering));
+        assert_eq!(long_reach.skill_group_kind(), Some(gathering));
+
+        skillset.add_skill_points(gathering, 2);
+        skillset.unlock_skill(long_reach);
+        assert!(!skillset.has_skill(long_reach));
+        skillset.unlock_skill(swift_pick);
+        skillset.unlock_skill(long_reach);
+        assert!(skillset.has_skill(long_reach));
+    }
 }
@@ -0,0 +1,49 @@
+use crate::{comp::Inventory, uid::Uid};
+use serde::{Deserialize, Serialize};
+use specs::Component;
+use specs_idvs::IdvStorage;
+use vek::*;
+
+/// The number of slots in a storage container placed in the world
+pub const CONTAINER_SLOTS: usize = 27;
+/// The number of slots in a character's bank
+pub const BANK_SLOTS: usize = 36;
+
+/// Marks a storage container in the world that any player can put items in
+/// and take items out of. The server keeps the contents under the block
+/// position the container was placed at, so they outlive the entity when its
+/// chunk is unloaded.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Storage {
+    pub pos: Vec3<i32>,
+}
+
+impl Component for Storage {
+    type Storage = IdvStorage<Self>;
+}
+
+/// The items a character keeps in the bank. The same bank can be reached from
+/// the strongbox of any settlement.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Bank(pub Inventory);
+
+impl Default for Bank {
+    fn default() -> Self { Self(Inventory::new_with_capacity(BANK_SLOTS)) }
+}
+
+impl Component for Bank {
+    type Storage = IdvStorage<Self>;
+}
+
+/// The storage a player currently has open
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum StorageTarget {
+    /// A storage container entity
+    Container(Uid),
+    /// The player's own bank, opened at the strongbox at this position
+    Bank(Vec3<i32>),
+}
+
+impl Component for StorageTarget {
+    type Storage = IdvStorage<Self>;
+}
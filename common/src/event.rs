@@ -1,5 +1,6 @@
 use crate::{
     character::CharacterId,
+    combat_log::CombatLogKind,
     comp::{
         self,
         agent::Sound,
@@ -58,6 +59,10 @@ pub enum ServerEvent {
     },
     InventoryManip(EcsEntity, comp::InventoryManip),
     GroupManip(EcsEntity, comp::GroupManip),
+    GuildManip(EcsEntity, comp::GuildManip),
+    StorageManip(EcsEntity, comp::StorageManip),
+    MailManip(EcsEntity, comp::MailManip),
+    BuildManip(EcsEntity, comp::BuildManip),
     Respawn(EcsEntity),
     Shoot {
         entity: EcsEntity,
@@ -108,6 +113,10 @@ pub enum ServerEvent {
             comp::SkillSet,
             comp::Inventory,
             Option<comp::Waypoint>,
+            comp::QuestLog,
+            Vec<comp::pet::PetPersistenceData>,
+            comp::Bank,
+            comp::LoadoutPresets,
         ),
     },
     ExitIngame {
@@ -138,6 +147,9 @@ pub enum ServerEvent {
         rtsim_entity: Option<RtSimEntity>,
     },
     CreateWaypoint(Vec3<f32>),
+    /// Spawns the storage container kept at this block position, creating an
+    /// empty one if there is none yet
+    CreateStorage(Vec3<i32>),
     ClientDisconnect(EcsEntity, DisconnectReason),
     ClientDisconnectWithoutPersistence(EcsEntity),
     ChunkRequest(EcsEntity, Vec2<i32>),
@@ -160,6 +172,17 @@ pub enum ServerEvent {
         entity: EcsEntity,
         change: i32,
     },
+    /// Records something that happened to `target` in the combat log
+    CombatLog {
+        source: Option<Uid>,
+        target: EcsEntity,
+        kind: CombatLogKind,
+    },
+    /// Wears down the items equipped in the given slots of an entity
+    WearEquipment {
+        entity: EcsEntity,
+        slots: Vec<comp::slot::EquipSlot>,
+    },
     RequestSiteInfo {
         entity: EcsEntity,
         id: SiteId,
@@ -186,6 +209,26 @@ pub enum ServerEvent {
         pos: Vec3<i32>,
         sprite: SpriteKind,
     },
+    /// An NPC offers a quest to the player it is talking to
+    OfferQuest {
+        giver: EcsEntity,
+        player: EcsEntity,
+    },
+    /// The objective of a quest has been met, hand out the reward
+    CompleteQuest {
+        entity: EcsEntity,
+        template: String,
+    },
+    /// An NPC sells a house to the player it is talking to
+    SellHouse {
+        seller: EcsEntity,
+        buyer: EcsEntity,
+    },
+    /// A fish was reeled in from where the fishing line landed
+    CatchFish {
+        entity: EcsEntity,
+        pos: Vec3<f32>,
+    },
 }
 
 pub struct EventBus<E> {
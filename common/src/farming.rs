@@ -0,0 +1,136 @@
+use crate::{
+    assets::{self, AssetExt, AssetHandle},
+    terrain::{Block, BlockKind, SpriteKind},
+};
+use hashbrown::HashMap;
+use serde::Deserialize;
+use vek::*;
+
+/// Asset specifier of the crops that can be planted
+pub const CROP_MANIFEST: &str = "common.farming.crops";
+
+/// A crop that grows from seeds planted on farmland
+#[derive(Clone, Debug, Deserialize)]
+pub struct CropDef {
+    /// Item definition id of the seeds that plant the crop
+    pub seeds: String,
+    /// The sprite of each stage of growth, ending with the fully grown crop
+    pub stages: Vec<SpriteKind>,
+    /// How many in-game seconds the crop spends in each stage before growing
+    /// into the next
+    pub stage_duration: f64,
+    /// Loot table of what harvesting the fully grown crop yields
+    pub harvest: String,
+}
+
+impl CropDef {
+    /// The stage of growth a crop is in after growing for `growth` in-game
+    /// seconds
+    pub fn stage(&self, growth: f64) -> usize {
+        ((growth / self.stage_duration).max(0.0) as usize).min(self.stages.len().saturating_sub(1))
+    }
+
+    /// The sprite of a crop after growing for `growth` in-game seconds
+    pub fn sprite(&self, growth: f64) -> SpriteKind {
+        self.stages
+            .get(self.stage(growth))
+            .copied()
+            .unwrap_or(SpriteKind::Empty)
+    }
+
+    /// Whether the crop can be harvested after growing for `growth` in-game
+    /// seconds
+    pub fn is_grown(&self, growth: f64) -> bool { self.stage(growth) + 1 >= self.stages.len() }
+}
+
+/// Every crop that can be planted, by id
+#[derive(Clone, Debug, Deserialize)]
+pub struct CropManifest(pub HashMap<String, CropDef>);
+
+impl assets::Asset for CropManifest {
+    type Loader = assets::RonLoader;
+
+    const EXTENSION: &'static str = "ron";
+}
+
+impl CropManifest {
+    pub fn load() -> AssetHandle<Self> { Self::load_expect(CROP_MANIFEST) }
+
+    /// The crop that the given seeds plant
+    pub fn crop_from_seeds(&self, seeds: &str) -> Option<(&str, &CropDef)> {
+        self.0
+            .iter()
+            .find(|(_, crop)| crop.seeds == seeds)
+            .map(|(id, crop)| (id.as_str(), crop))
+    }
+
+    /// The crop that has the given sprite once it is fully grown
+    pub fn crop_grown_as(&self, sprite: SpriteKind) -> Option<&CropDef> {
+        self.0
+            .values()
+            .find(|crop| crop.stages.last() == Some(&sprite))
+    }
+}
+
+/// The tilled earth that farm fields are made of, its shade varying with the
+/// seed of the field
+pub fn tilled_earth(seed: u32) -> Block {
+    Block::new(BlockKind::Earth, Rgb::new(40, 5 + (seed % 32) as u8, 0))
+}
+
+/// Whether seeds can be planted on top of a block, which is only the case for
+/// the tilled earth of farm fields
+pub fn is_farmland(block: &Block) -> bool {
+    block.kind() == BlockKind::Earth
+        && block.get_color().map_or(false, |col| {
+            col.r == 40 && col.b == 0 && (5..37).contains(&col.g)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        comp::item::ItemDef,
+        lottery::{LootSpec, Lottery},
+    };
+    use std::sync::Arc;
+
+    #[test]
+    fn test_all_crops_load() {
+        for crop in CropManifest::load().read().0.values() {
+            assert!(!crop.stages.is_empty());
+            assert!(crop.stage_duration > 0.0);
+            Arc::<ItemDef>::load_expect(&crop.seeds);
+            Lottery::<LootSpec>::load_expect(&crop.harvest);
+        }
+    }
+
+    #[test]
+    fn test_crops_stop_growing_when_grown() {
+        let crop = CropDef {
+            seeds: String::new(),
+            stages: vec![SpriteKind::Seedling, SpriteKind::Sprout, SpriteKind::Carrot],
+            stage_duration: 100.0,
+            harvest: String::new(),
+        };
+        assert_eq!(crop.sprite(0.0), SpriteKind::Seedling);
+        assert_eq!(crop.sprite(150.0), SpriteKind::Sprout);
+        assert!(!crop.is_grown(199.0));
+        assert_eq!(crop.sprite(1000.0), SpriteKind::Carrot);
+        assert!(crop.is_grown(200.0));
+    }
+
+    #[test]
+    fn test_only_tilled_earth_is_farmland() {
+        assert!((0..64).all(|seed| is_farmland(&tilled_earth(seed))));
+        assert!(!is_farmland(&Block::new(
+            BlockKind::Earth,
+            Rgb::new(100, 60, 30)
+        )));
+        assert!(!is_farmland(&Block::new(
+            BlockKind::Grass,
+            Rgb::new(40, 20, 0)
+        )));
+    }
+}
@@ -45,6 +45,7 @@ impl assets::Asset for EntityConfig {
 pub struct EntityInfo {
     pub pos: Vec3<f32>,
     pub is_waypoint: bool, // Edge case, overrides everything else
+    pub is_storage: bool,  // Same as above
     pub is_giant: bool,
     pub has_agency: bool,
     pub alignment: Alignment,
@@ -71,6 +72,7 @@ impl EntityInfo {
         Self {
             pos,
             is_waypoint: false,
+            is_storage: false,
             is_giant: false,
             has_agency: true,
             alignment: Alignment::Wild,
@@ -173,6 +175,11 @@ impl EntityInfo {
         self
     }
 
+    pub fn into_storage(mut self) -> Self {
+        self.is_storage = true;
+        self
+    }
+
     pub fn into_giant(mut self) -> Self {
         self.is_giant = true;
         self
@@ -31,6 +31,7 @@ pub mod character;
 #[cfg(not(target_arch = "wasm32"))] pub mod clock;
 #[cfg(not(target_arch = "wasm32"))] pub mod cmd;
 pub mod combat;
+pub mod combat_log;
 pub mod comp;
 pub mod consts;
 #[cfg(not(target_arch = "wasm32"))] pub mod depot;
@@ -40,12 +41,15 @@ pub mod effect;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod explosion;
 #[cfg(not(target_arch = "wasm32"))]
+pub mod farming;
+#[cfg(not(target_arch = "wasm32"))]
 pub mod figure;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod generation;
 #[cfg(not(target_arch = "wasm32"))] pub mod grid;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod lottery;
+#[cfg(not(target_arch = "wasm32"))] pub mod mail;
 #[cfg(not(target_arch = "wasm32"))] pub mod npc;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod outcome;
@@ -76,6 +80,8 @@ pub mod uid;
 #[cfg(not(target_arch = "wasm32"))] pub mod vol;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod volumes;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod weather;
 
 #[cfg(not(target_arch = "wasm32"))]
 pub use cached_spatial_grid::CachedSpatialGrid;
@@ -28,7 +28,10 @@
 
 use crate::{
     assets::{self, AssetExt},
-    comp::Item,
+    comp::{
+        item::{tool::AbilityMap, MaterialStatManifest, ModifierPool},
+        Item,
+    },
 };
 use rand::prelude::*;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
@@ -84,6 +87,8 @@ pub enum LootSpec {
     ItemQuantity(String, u32, u32),
     /// Loot table
     LootTable(String),
+    /// Asset specifier, modifier pool to roll the modifiers of the item from
+    ModifiedItem(String, String),
 }
 
 impl LootSpec {
@@ -104,6 +109,19 @@ impl LootSpec {
                 .read()
                 .choose()
                 .to_item(),
+            Self::ModifiedItem(item, pool) => {
+                let mut item = Item::new_from_asset_expect(&item);
+                let modifiers = ModifierPool::load_expect(&pool)
+                    .read()
+                    .roll(&mut thread_rng());
+                // TODO: Get msm and ability_map less hackily
+                item.set_modifiers(
+                    modifiers,
+                    &AbilityMap::default(),
+                    &MaterialStatManifest::default(),
+                );
+                item
+            },
         }
     }
 }
@@ -161,6 +179,15 @@ mod tests {
                         let loot_table = Lottery::<LootSpec>::load_expect_cloned(&loot_table);
                         validate_table_contents(loot_table);
                     },
+                    LootSpec::ModifiedItem(item, pool) => {
+                        let item = Item::new_from_asset_expect(&item);
+                        assert!(
+                            !item.is_stackable(),
+                            "Stackable items can't have modifiers: {}",
+                            item.item_definition_id()
+                        );
+                        ModifierPool::load_expect(&pool);
+                    },
                 }
             }
         }
@@ -0,0 +1,43 @@
+use crate::{character::CharacterId, comp::Inventory};
+use serde::{Deserialize, Serialize};
+
+/// The number of item stacks that can be attached to a letter
+pub const MAX_ATTACHMENTS: usize = 8;
+/// The longest message a letter can hold, in characters
+pub const MAX_MESSAGE_LENGTH: usize = 500;
+/// How long a letter waits to be collected before it is returned to its
+/// sender, in seconds
+pub const MAIL_EXPIRY_SECS: u64 = 14 * 24 * 60 * 60;
+
+pub type MailId = u64;
+
+/// A letter sent from one character to another, along with any items
+/// attached to it. Letters are kept by the server until the recipient
+/// collects them at a mailbox.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Mail {
+    pub id: MailId,
+    pub sender: CharacterId,
+    pub sender_alias: String,
+    pub recipient: CharacterId,
+    pub recipient_alias: String,
+    pub message: String,
+    pub attachments: Inventory,
+    /// When the letter was sent (or returned), in seconds since the unix epoch
+    pub sent_at: u64,
+    /// Whether the letter was not collected in time and went back to its
+    /// sender. The recipient is then the original sender.
+    pub returned: bool,
+}
+
+impl Mail {
+    /// Returned letters stay with their sender until collected
+    pub fn expires_at(&self) -> Option<u64> {
+        (!self.returned).then(|| self.sent_at + MAIL_EXPIRY_SECS)
+    }
+
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.expires_at()
+            .map_or(false, |expires_at| now >= expires_at)
+    }
+}
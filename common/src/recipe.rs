@@ -1,14 +1,18 @@
 use crate::{
     assets::{self, AssetExt, AssetHandle},
     comp::{
-        item::{modular, tool::AbilityMap, ItemDef, ItemTag, MaterialStatManifest},
+        item::{
+            modular, tool::AbilityMap, ItemDef, ItemDesc, ItemKind, ItemTag, MaterialStatManifest,
+            ToolKind,
+        },
+        slot::Slot,
         Inventory, Item,
     },
     terrain::SpriteKind,
 };
 use hashbrown::HashMap;
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum RecipeInput {
@@ -21,34 +25,57 @@ pub struct Recipe {
     pub output: (Arc<ItemDef>, u32),
     pub inputs: Vec<(RecipeInput, u32)>,
     pub craft_sprite: Option<SpriteKind>,
+    /// How long crafting the recipe once takes, crafts that take no time are
+    /// done right away instead of being queued
+    pub craft_time: Duration,
+    /// Ingredients that can be added on top of the inputs to improve the
+    /// quality of what is crafted
+    pub optional_inputs: Vec<(RecipeInput, u32)>,
 }
 
 #[allow(clippy::type_complexity)]
 impl Recipe {
-    /// Perform a recipe, returning a list of missing items on failure
+    /// Perform a recipe, returning a list of missing items on failure. When
+    /// `improve` is set and the inventory also holds the optional inputs, they
+    /// are used up too and what is crafted is one step of quality better.
     pub fn perform(
         &self,
         inv: &mut Inventory,
+        improve: bool,
         ability_map: &AbilityMap,
         msm: &MaterialStatManifest,
     ) -> Result<Option<(Item, u32)>, Vec<(&RecipeInput, u32)>> {
         // Get ingredient cells from inventory,
         let mut components = Vec::new();
 
-        inv.contains_ingredients(self)?
-            .into_iter()
-            .for_each(|(pos, n)| {
-                (0..n).for_each(|_| {
-                    let component = inv
-                        .take(pos, ability_map, msm)
-                        .expect("Expected item to exist in inventory");
-                    components.push(component);
-                })
-            });
+        // The optional inputs are only used if they are there on top of the required
+        // inputs
+        let improved_claims = if improve && !self.optional_inputs.is_empty() {
+            inv.contains_inputs(self.inputs().chain(self.optional_inputs()))
+                .ok()
+        } else {
+            None
+        };
+        let (claims, improved) = match improved_claims {
+            Some(claims) => (claims, true),
+            None => (inv.contains_ingredients(self)?, false),
+        };
+
+        claims.into_iter().for_each(|(pos, n)| {
+            (0..n).for_each(|_| {
+                let component = inv
+                    .take(pos, ability_map, msm)
+                    .expect("Expected item to exist in inventory");
+                components.push(component);
+            })
+        });
 
         for i in 0..self.output.1 {
-            let crafted_item =
+            let mut crafted_item =
                 Item::new_from_item_def(Arc::clone(&self.output.0), &components, ability_map, msm);
+            if improved {
+                crafted_item.set_quality_bonus(1);
+            }
             if let Err(item) = inv.push(crafted_item) {
                 return Ok(Some((item, self.output.1 - i)));
             }
@@ -62,6 +89,31 @@ impl Recipe {
             .iter()
             .map(|(item_def, amount)| (item_def, *amount))
     }
+
+    pub fn optional_inputs(&self) -> impl ExactSizeIterator<Item = (&RecipeInput, u32)> {
+        self.optional_inputs
+            .iter()
+            .map(|(item_def, amount)| (item_def, *amount))
+    }
+
+    /// How many times in a row the recipe can be crafted with the ingredients
+    /// in the inventory, at most `max`
+    pub fn max_crafts(&self, inv: &Inventory, max: u32) -> u32 {
+        self.inputs()
+            .filter(|(_, amount)| *amount > 0)
+            .map(|(input, amount)| {
+                let available = inv
+                    .slots()
+                    .flatten()
+                    .filter(|item| item.matches_recipe_input(input))
+                    .map(|item| item.amount())
+                    .sum::<u32>();
+                available / amount
+            })
+            .min()
+            .unwrap_or(max)
+            .min(max)
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -95,6 +147,11 @@ pub(crate) struct RawRecipe {
     pub(crate) inputs: Vec<(RawRecipeInput, u32)>,
     #[serde(default)]
     pub(crate) craft_sprite: Option<SpriteKind>,
+    /// How many seconds crafting the recipe once takes
+    #[serde(default)]
+    pub(crate) craft_time: f32,
+    #[serde(default)]
+    pub(crate) optional_inputs: Vec<(RawRecipeInput, u32)>,
 }
 
 #[derive(Clone, Deserialize)]
@@ -107,6 +164,15 @@ impl assets::Asset for RawRecipeBook {
     const EXTENSION: &'static str = "ron";
 }
 
+#[inline]
+fn load_recipe_input(spec: &(RawRecipeInput, u32)) -> Result<(RecipeInput, u32), assets::Error> {
+    let def = match &spec.0 {
+        RawRecipeInput::Item(name) => RecipeInput::Item(Arc::<ItemDef>::load_cloned(&name)?),
+        RawRecipeInput::Tag(tag) => RecipeInput::Tag(*tag),
+    };
+    Ok((def, spec.1))
+}
+
 impl assets::Compound for RecipeBook {
     fn load<S: assets::source::Source>(
         cache: &assets::AssetCache<S>,
@@ -117,19 +183,6 @@ impl assets::Compound for RecipeBook {
             let def = Arc::<ItemDef>::load_cloned(&spec.0)?;
             Ok((def, spec.1))
         }
-        #[inline]
-        fn load_recipe_input(
-            spec: &(RawRecipeInput, u32),
-        ) -> Result<(RecipeInput, u32), assets::Error> {
-            let def = match &spec.0 {
-                RawRecipeInput::Item(name) => {
-                    RecipeInput::Item(Arc::<ItemDef>::load_cloned(&name)?)
-                },
-                RawRecipeInput::Tag(tag) => RecipeInput::Tag(*tag),
-            };
-            Ok((def, spec.1))
-        }
-
         let mut raw = cache.load::<RawRecipeBook>(specifier)?.read().clone();
 
         // Avoid showing purple-question-box recipes until the assets are added
@@ -149,17 +202,25 @@ impl assets::Compound for RecipeBook {
                         output,
                         inputs,
                         craft_sprite,
+                        craft_time,
+                        optional_inputs,
                     },
                 )| {
                     let inputs = inputs
                         .iter()
                         .map(load_recipe_input)
                         .collect::<Result<Vec<_>, _>>()?;
+                    let optional_inputs = optional_inputs
+                        .iter()
+                        .map(load_recipe_input)
+                        .collect::<Result<Vec<_>, _>>()?;
                     let output = load_item_def(output)?;
                     Ok((name.clone(), Recipe {
                         output,
                         inputs,
                         craft_sprite: *craft_sprite,
+                        craft_time: Duration::from_secs_f32(craft_time.max(0.0)),
+                        optional_inputs,
                     }))
                 },
             )
@@ -172,3 +233,142 @@ impl assets::Compound for RecipeBook {
 pub fn default_recipe_book() -> AssetHandle<RecipeBook> {
     RecipeBook::load_expect("common.recipe_book")
 }
+
+/// Which worn out items a repair recipe can mend
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum RepairTarget {
+    /// Any weapon or tool of the given kind
+    Tool(ToolKind),
+    /// Any piece of armour
+    Armor,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RepairRecipe {
+    pub target: RepairTarget,
+    pub inputs: Vec<(RecipeInput, u32)>,
+    pub craft_sprite: Option<SpriteKind>,
+}
+
+impl RepairRecipe {
+    /// Whether this recipe can restore the durability of the given item
+    pub fn can_repair(&self, item: &Item) -> bool {
+        item.durability().is_some()
+            && match (&self.target, item.kind()) {
+                (RepairTarget::Tool(kind), ItemKind::Tool(tool)) => *kind == tool.kind,
+                (RepairTarget::Armor, ItemKind::Armor(_)) => true,
+                _ => false,
+            }
+    }
+
+    /// Use up the materials of the recipe to repair the item in the given
+    /// slot, returning a list of missing items on failure
+    pub fn perform(
+        &self,
+        inv: &mut Inventory,
+        slot: Slot,
+        ability_map: &AbilityMap,
+        msm: &MaterialStatManifest,
+    ) -> Result<(), Vec<(&RecipeInput, u32)>> {
+        let needs_repair = inv.get_slot(slot).map_or(false, |item| {
+            self.can_repair(item) && item.durability().map_or(false, |(cur, max)| cur < max)
+        });
+        if !needs_repair {
+            return Err(Vec::new());
+        }
+
+        let claims = inv.contains_inputs(self.inputs())?;
+        // Never use the item that is being repaired as its own material
+        if claims.keys().any(|pos| Slot::Inventory(*pos) == slot) {
+            return Err(Vec::new());
+        }
+
+        claims.into_iter().for_each(|(pos, n)| {
+            (0..n).for_each(|_| {
+                inv.take(pos, ability_map, msm)
+                    .expect("Expected item to exist in inventory");
+            })
+        });
+
+        if let Some(item) = inv.get_slot_mut(slot) {
+            item.repair();
+        }
+
+        Ok(())
+    }
+
+    pub fn inputs(&self) -> impl ExactSizeIterator<Item = (&RecipeInput, u32)> {
+        self.inputs
+            .iter()
+            .map(|(item_def, amount)| (item_def, *amount))
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RepairRecipeBook {
+    recipes: Vec<RepairRecipe>,
+}
+
+impl RepairRecipeBook {
+    /// Find the recipe that can repair the given item, if any
+    pub fn get(&self, item: &Item) -> Option<&RepairRecipe> {
+        self.recipes.iter().find(|recipe| recipe.can_repair(item))
+    }
+
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = &RepairRecipe> { self.recipes.iter() }
+}
+
+#[derive(Clone, Deserialize)]
+pub(crate) struct RawRepairRecipe {
+    pub(crate) target: RepairTarget,
+    pub(crate) inputs: Vec<(RawRecipeInput, u32)>,
+    #[serde(default)]
+    pub(crate) craft_sprite: Option<SpriteKind>,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(transparent)]
+pub(crate) struct RawRepairRecipeBook(pub(crate) Vec<RawRepairRecipe>);
+
+impl assets::Asset for RawRepairRecipeBook {
+    type Loader = assets::RonLoader;
+
+    const EXTENSION: &'static str = "ron";
+}
+
+impl assets::Compound for RepairRecipeBook {
+    fn load<S: assets::source::Source>(
+        cache: &assets::AssetCache<S>,
+        specifier: &str,
+    ) -> Result<Self, assets::Error> {
+        let raw = cache.load::<RawRepairRecipeBook>(specifier)?.read().clone();
+
+        let recipes = raw
+            .0
+            .iter()
+            .map(
+                |RawRepairRecipe {
+                     target,
+                     inputs,
+                     craft_sprite,
+                 }| {
+                    let inputs = inputs
+                        .iter()
+                        .map(load_recipe_input)
+                        .collect::<Result<Vec<_>, _>>()?;
+                    Ok(RepairRecipe {
+                        target: target.clone(),
+                        inputs,
+                        craft_sprite: *craft_sprite,
+                    })
+                },
+            )
+            .collect::<Result<_, assets::Error>>()?;
+
+        Ok(RepairRecipeBook { recipes })
+    }
+}
+
+pub fn default_repair_recipe_book() -> AssetHandle<RepairRecipeBook> {
+    RepairRecipeBook::load_expect("common.repair_recipe_book")
+}
@@ -2,7 +2,8 @@ use crate::{
     comp::{
         self, item::MaterialStatManifest, Beam, Body, CharacterState, Combo, ControlAction,
         Controller, ControllerInputs, Density, Energy, Health, InputAttr, InputKind, Inventory,
-        InventoryAction, Mass, Melee, Ori, PhysicsState, Pos, SkillSet, StateUpdate, Stats, Vel,
+        InventoryAction, Mass, Melee, MountState, Mounting, Ori, PhysicsState, Pos, SkillSet,
+        StateUpdate, Stats, Vel,
     },
     resources::DeltaTime,
     terrain::TerrainGrid,
@@ -98,6 +99,8 @@ pub struct JoinData<'a> {
     pub combo: &'a Combo,
     pub alignment: Option<&'a comp::Alignment>,
     pub terrain: &'a TerrainGrid,
+    pub mount_state: Option<&'a MountState>,
+    pub mounting: Option<&'a Mounting>,
 }
 
 type RestrictedMut<'a, C> = PairedStorage<
@@ -131,6 +134,8 @@ pub struct JoinStruct<'a> {
     pub combo: &'a Combo,
     pub alignment: Option<&'a comp::Alignment>,
     pub terrain: &'a TerrainGrid,
+    pub mount_state: Option<&'a MountState>,
+    pub mounting: Option<&'a Mounting>,
 }
 
 impl<'a> JoinData<'a> {
@@ -165,6 +170,8 @@ impl<'a> JoinData<'a> {
             combo: j.combo,
             alignment: j.alignment,
             terrain: j.terrain,
+            mount_state: j.mount_state,
+            mounting: j.mounting,
         }
     }
 }
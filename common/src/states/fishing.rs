@@ -0,0 +1,163 @@
+use crate::{
+    comp::{CharacterState, StateUpdate},
+    event::ServerEvent,
+    states::{
+        behavior::{CharacterBehavior, JoinData},
+        utils::*,
+    },
+    terrain::Block,
+    vol::ReadVol,
+};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use vek::*;
+
+/// Separated out to condense update portions of character state
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StaticData {
+    /// How long it takes to cast the line
+    pub cast_duration: Duration,
+    /// How long a fish stays on the hook before getting away
+    pub bite_window: Duration,
+    /// How long it takes to reel the line back in
+    pub reel_duration: Duration,
+    /// How far away the line lands
+    pub range: f32,
+    /// Miscellaneous information about the ability
+    pub ability_info: AbilityInfo,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Data {
+    /// Struct containing data that does not change over the course of the
+    /// character state
+    pub static_data: StaticData,
+    /// Timer for each stage
+    pub timer: Duration,
+    /// What section the character stage is in: casting the line (buildup),
+    /// waiting for a bite (charge) or reeling the line in (recover)
+    pub stage_section: StageSection,
+    /// Where the line landed in the water, once it was cast
+    pub bobber: Option<Vec3<f32>>,
+    /// When a fish bites while waiting, decided by the server from where and
+    /// when the line is in the water
+    pub bite: Option<Duration>,
+    /// Whether the input was let go of after casting, so that pressing it again
+    /// reels the line in
+    pub input_released: bool,
+    /// Whether a fish was on the hook when the line was reeled in
+    pub hooked: bool,
+}
+
+impl Data {
+    /// Whether a fish is on the hook right now
+    pub fn is_biting(&self) -> bool {
+        self.stage_section == StageSection::Charge
+            && self.bite.map_or(false, |bite| {
+                self.timer >= bite && self.timer < bite + self.static_data.bite_window
+            })
+    }
+}
+
+impl CharacterBehavior for Data {
+    fn behavior(&self, data: &JoinData) -> StateUpdate {
+        let mut update = StateUpdate::from(data);
+
+        handle_orientation(data, &mut update, 1.0);
+
+        // Walking away pulls the line out of the water
+        if data.inputs.move_dir.magnitude_squared() > 0.0 {
+            update.character = CharacterState::Wielding;
+            return update;
+        }
+
+        match self.stage_section {
+            StageSection::Buildup => {
+                if self.timer < self.static_data.cast_duration {
+                    // Casts the line
+                    update.character = CharacterState::Fishing(Data {
+                        timer: tick_attack_or_default(data, self.timer, None),
+                        ..*self
+                    });
+                } else {
+                    // The line lands where the character is looking, and can only be cast
+                    // into water
+                    let dir = data
+                        .inputs
+                        .look_dir
+                        .xy()
+                        .try_normalized()
+                        .unwrap_or_default();
+                    let above = data.pos.0
+                        + dir * self.static_data.range
+                        + Vec3::unit_z() * data.body.eye_height();
+                    let (dist, block) = data
+                        .terrain
+                        .ray(above, above - Vec3::unit_z() * 30.0)
+                        .until(|block: &Block| !block.is_air())
+                        .cast();
+                    match block {
+                        Ok(Some(block)) if block.is_liquid() => {
+                            update.character = CharacterState::Fishing(Data {
+                                timer: Duration::default(),
+                                stage_section: StageSection::Charge,
+                                bobber: Some(above - Vec3::unit_z() * dist),
+                                ..*self
+                            });
+                        },
+                        _ => update.character = CharacterState::Wielding,
+                    }
+                }
+            },
+            StageSection::Charge => {
+                let pressed = input_is_pressed(data, self.static_data.ability_info.input);
+                if pressed && self.input_released {
+                    // Reels the line in, with a fish if one is biting
+                    update.character = CharacterState::Fishing(Data {
+                        timer: Duration::default(),
+                        stage_section: StageSection::Recover,
+                        hooked: self.is_biting(),
+                        ..*self
+                    });
+                } else {
+                    let got_away = self.bite.map_or(false, |bite| {
+                        self.timer >= bite + self.static_data.bite_window
+                    });
+                    update.character = CharacterState::Fishing(Data {
+                        timer: tick_attack_or_default(data, self.timer, None),
+                        // Another fish may bite once this one got away
+                        bite: if got_away { None } else { self.bite },
+                        input_released: self.input_released || !pressed,
+                        ..*self
+                    });
+                }
+            },
+            StageSection::Recover => {
+                if self.timer < self.static_data.reel_duration {
+                    // Reels the line in
+                    update.character = CharacterState::Fishing(Data {
+                        timer: tick_attack_or_default(data, self.timer, None),
+                        ..*self
+                    });
+                } else {
+                    if let (true, Some(pos)) = (self.hooked, self.bobber) {
+                        update.server_events.push_front(ServerEvent::CatchFish {
+                            entity: data.entity,
+                            pos,
+                        });
+                    }
+                    // Done
+                    update.character = CharacterState::Wielding;
+                }
+            },
+            _ => {
+                // If it somehow ends up in an incorrect stage section
+                update.character = CharacterState::Wielding;
+            },
+        }
+
+        handle_dodge_input(data, &mut update);
+
+        update
+    }
+}
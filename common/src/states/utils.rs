@@ -3,11 +3,11 @@ use crate::{
     comp::{
         biped_large, biped_small,
         inventory::slot::EquipSlot,
-        item::{Hands, ItemKind, Tool, ToolKind},
+        item::{tool, Hands, ItemDesc, ItemKind, Tool, ToolKind},
         quadruped_low, quadruped_medium, quadruped_small,
         skills::{Skill, SwimSkill},
         theropod, Body, CharacterAbility, CharacterState, Density, InputAttr, InputKind,
-        InventoryAction, StateUpdate,
+        InventoryAction, MountState, MountStats, StateUpdate,
     },
     consts::{FRIC_GROUND, GRAVITY},
     event::{LocalEvent, ServerEvent},
@@ -224,8 +224,23 @@ impl Body {
     pub fn reverse_move_factor(&self) -> f32 { 0.45 }
 }
 
+/// Whether the character is riding a mount, which does the moving for it
+pub fn is_riding(data: &JoinData) -> bool { data.mounting.is_some() }
+
+/// Returns the mount stats of the character if it is carrying a rider
+fn ridden_stats(data: &JoinData) -> Option<MountStats> {
+    match data.mount_state {
+        Some(MountState::MountedBy(_)) => data.body.mount_stats(),
+        _ => None,
+    }
+}
+
 /// Handles updating `Components` to move player based on state of `JoinData`
 pub fn handle_move(data: &JoinData, update: &mut StateUpdate, efficiency: f32) {
+    if is_riding(data) {
+        return;
+    }
+
     let submersion = data
         .physics
         .in_liquid()
@@ -251,7 +266,10 @@ pub fn handle_move(data: &JoinData, update: &mut StateUpdate, efficiency: f32) {
 /// Updates components to move player as if theyre on ground or in air
 #[allow(clippy::assign_op_pattern)] // TODO: Pending review in #587
 fn basic_move(data: &JoinData, update: &mut StateUpdate, efficiency: f32) {
-    let efficiency = efficiency * data.stats.move_speed_modifier * data.stats.friction_modifier;
+    let efficiency = efficiency
+        * data.stats.move_speed_modifier
+        * data.stats.friction_modifier
+        * ridden_stats(data).map_or(1.0, |stats| stats.speed);
 
     let accel = if data.physics.on_ground.is_some() {
         data.body.base_accel()
@@ -292,6 +310,9 @@ fn basic_move(data: &JoinData, update: &mut StateUpdate, efficiency: f32) {
 
 /// Handles forced movement
 pub fn handle_forced_movement(data: &JoinData, update: &mut StateUpdate, movement: ForcedMovement) {
+    if is_riding(data) {
+        return;
+    }
     match movement {
         ForcedMovement::Forward { strength } => {
             let strength = strength * data.stats.move_speed_modifier * data.stats.friction_modifier;
@@ -334,6 +355,10 @@ pub fn handle_forced_movement(data: &JoinData, update: &mut StateUpdate, movemen
 }
 
 pub fn handle_orientation(data: &JoinData, update: &mut StateUpdate, efficiency: f32) {
+    // Riders face wherever their mount is facing
+    if is_riding(data) {
+        return;
+    }
     if let Some(dir) = (is_strafing(data, update) || update.character.is_attack())
         .then(|| data.inputs.look_dir.to_horizontal().unwrap_or_default())
         .or_else(|| Dir::from_unnormalized(data.inputs.move_dir.into()))
@@ -388,7 +413,10 @@ fn swim_move(data: &JoinData, update: &mut StateUpdate, efficiency: f32, submers
 
 /// Updates components to move entity as if it's flying
 pub fn fly_move(data: &JoinData, update: &mut StateUpdate, efficiency: f32) -> bool {
-    let efficiency = efficiency * data.stats.move_speed_modifier * data.stats.friction_modifier;
+    let efficiency = efficiency
+        * data.stats.move_speed_modifier
+        * data.stats.friction_modifier
+        * ridden_stats(data).map_or(1.0, |stats| stats.flight);
 
     let glider = match data.character {
         CharacterState::Glide(data) => Some(data),
@@ -500,13 +528,13 @@ pub fn attempt_wield(data: &JoinData, update: &mut StateUpdate) {
 
 /// Checks that player can `Sit` and updates `CharacterState` if so
 pub fn attempt_sit(data: &JoinData, update: &mut StateUpdate) {
-    if data.physics.on_ground.is_some() {
+    if data.physics.on_ground.is_some() && !is_riding(data) {
         update.character = CharacterState::Sit;
     }
 }
 
 pub fn attempt_dance(data: &JoinData, update: &mut StateUpdate) {
-    if data.physics.on_ground.is_some() && data.body.is_humanoid() {
+    if data.physics.on_ground.is_some() && data.body.is_humanoid() && !is_riding(data) {
         update.character = CharacterState::Dance;
     }
 }
@@ -518,7 +546,7 @@ pub fn attempt_talk(data: &JoinData, update: &mut StateUpdate) {
 }
 
 pub fn attempt_sneak(data: &JoinData, update: &mut StateUpdate) {
-    if data.physics.on_ground.is_some() && data.body.is_humanoid() {
+    if data.physics.on_ground.is_some() && data.body.is_humanoid() && !is_riding(data) {
         update.character = CharacterState::Sneak;
     }
 }
@@ -535,6 +563,7 @@ pub fn handle_climb(data: &JoinData, update: &mut StateUpdate) -> bool {
             .unwrap_or(false)
         //&& update.vel.0.z < 0.0
         && data.body.can_climb()
+        && !is_riding(data)
         && update.energy.current() > 100
     {
         update.character = CharacterState::Climb(climb::Data::create_adjusted_by_skills(data));
@@ -579,6 +608,7 @@ pub fn attempt_glide_wield(data: &JoinData, update: &mut StateUpdate) {
             .map(|depth| depth > 1.0)
             .unwrap_or(false)
         && data.body.is_humanoid()
+        && !is_riding(data)
     {
         update.character = CharacterState::GlideWield;
     }
@@ -586,6 +616,10 @@ pub fn attempt_glide_wield(data: &JoinData, update: &mut StateUpdate) {
 
 /// Checks that player can jump and sends jump event if so
 pub fn handle_jump(data: &JoinData, update: &mut StateUpdate, strength: f32) -> bool {
+    if is_riding(data) {
+        return false;
+    }
+    let strength = strength * ridden_stats(data).map_or(1.0, |stats| stats.jump);
     (input_is_pressed(data, InputKind::Jump) && data.physics.on_ground.is_some())
         .then(|| data.body.jump_impulse())
         .flatten()
@@ -710,7 +744,7 @@ pub fn handle_block_input(data: &JoinData, update: &mut StateUpdate) {
 /// Checks that player can perform a dodge, then
 /// attempts to perform their dodge ability
 pub fn handle_dodge_input(data: &JoinData, update: &mut StateUpdate) {
-    if input_is_pressed(data, InputKind::Roll) && data.body.is_humanoid() {
+    if input_is_pressed(data, InputKind::Roll) && data.body.is_humanoid() && !is_riding(data) {
         let ability = CharacterAbility::default_roll().adjusted_by_skills(&data.skill_set, None);
         if ability.requirements_paid(data, update) {
             update.character = CharacterState::from((
@@ -777,7 +811,11 @@ pub fn get_crit_data(data: &JoinData, ai: AbilityInfo) -> (f32, f32) {
         .and_then(|slot| data.inventory.equipped(slot))
         .and_then(|item| {
             if let ItemKind::Tool(tool) = item.kind() {
-                Some(tool.base_crit_chance(data.msm, item.components()))
+                Some(
+                    tool::Stats::from((data.msm, item.components(), tool))
+                        .with_modifiers(&item.all_modifiers())
+                        .crit_chance,
+                )
             } else {
                 None
             }
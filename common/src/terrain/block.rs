@@ -256,6 +256,8 @@ impl Block {
                 | SpriteKind::Forge
                 | SpriteKind::Loom
                 | SpriteKind::SpinningWheel
+                | SpriteKind::Strongbox
+                | SpriteKind::Mailbox
                 | SpriteKind::TanningRack => None,
                 _ => Some(0.25),
             }),
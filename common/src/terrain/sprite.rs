@@ -174,6 +174,10 @@ make_case_elim!(
         CeilingMushroom = 0x93,
         Orb = 0x94,
         EnsnaringVines = 0x95,
+        Strongbox = 0x96,
+        Mailbox = 0x97,
+        Seedling = 0x98,
+        Sprout = 0x99,
     }
 );
 
@@ -258,6 +262,8 @@ impl SpriteKind {
             | SpriteKind::Silver
             | SpriteKind::Gold => 0.6,
             SpriteKind::EnsnaringVines => 0.1,
+            SpriteKind::Strongbox => 1.09,
+            SpriteKind::Mailbox => 1.0,
             _ => return None,
         })
     }
@@ -303,6 +309,10 @@ impl SpriteKind {
             SpriteKind::RoundCactus => true,
             SpriteKind::ShortFlatCactus => true,
             SpriteKind::MedFlatCactus => true,
+            SpriteKind::Carrot => true,
+            SpriteKind::Tomato => true,
+            SpriteKind::Cabbage => true,
+            SpriteKind::Flax => true,
             _ => false,
         }
     }
@@ -407,6 +417,8 @@ impl SpriteKind {
                 | SpriteKind::SpinningWheel
                 | SpriteKind::TanningRack
                 | SpriteKind::Loom
+                | SpriteKind::Strongbox
+                | SpriteKind::Mailbox
         )
     }
 }
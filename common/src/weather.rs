@@ -0,0 +1,225 @@
+use crate::{grid::Grid, terrain::TerrainChunkSize, vol::RectVolSize};
+use serde::{Deserialize, Serialize};
+use vek::*;
+
+/// The number of chunks along each side of a weather cell.
+pub const CHUNKS_PER_CELL: u32 = 16;
+/// The number of blocks along each side of a weather cell.
+pub const CELL_SIZE: u32 = CHUNKS_PER_CELL * TerrainChunkSize::RECT_SIZE.x;
+
+/// The weather at a single location.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Weather {
+    /// Cloud cover, from 0 (clear skies) to 1 (overcast).
+    pub cloud: f32,
+    /// Intensity of rainfall, from 0 to 1.
+    pub rain: f32,
+    /// Intensity of snowfall, from 0 to 1.
+    pub snow: f32,
+    /// Density of fog, from 0 to 1.
+    pub fog: f32,
+    /// Wind velocity, in blocks per second.
+    pub wind: Vec2<f32>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum WeatherKind {
+    Clear,
+    Cloudy,
+    Foggy,
+    Rain,
+    Snow,
+    Storm,
+}
+
+impl Weather {
+    /// Winds faster than this (in blocks per second) turn heavy rain or snow
+    /// into a storm.
+    pub const STORM_WIND: f32 = 12.0;
+
+    pub fn lerp(from: &Self, to: &Self, t: f32) -> Self {
+        Self {
+            cloud: Lerp::lerp(from.cloud, to.cloud, t),
+            rain: Lerp::lerp(from.rain, to.rain, t),
+            snow: Lerp::lerp(from.snow, to.snow, t),
+            fog: Lerp::lerp(from.fog, to.fog, t),
+            wind: Lerp::lerp(from.wind, to.wind, t),
+        }
+    }
+
+    /// Total intensity of precipitation, whether rain or snow.
+    pub fn precipitation(&self) -> f32 { (self.rain + self.snow).min(1.0) }
+
+    pub fn get_kind(&self) -> WeatherKind {
+        let precipitation = self.precipitation();
+        if precipitation > 0.6 && self.wind.magnitude() > Self::STORM_WIND {
+            WeatherKind::Storm
+        } else if self.snow > 0.2 && self.snow >= self.rain {
+            WeatherKind::Snow
+        } else if self.rain > 0.2 {
+            WeatherKind::Rain
+        } else if self.fog > 0.4 {
+            WeatherKind::Foggy
+        } else if self.cloud > 0.5 {
+            WeatherKind::Cloudy
+        } else {
+            WeatherKind::Clear
+        }
+    }
+
+    /// How far one can see in this weather, as a fraction of the distance one
+    /// could see under clear skies.
+    pub fn visibility(&self) -> f32 {
+        (1.0 - self.fog * 0.7 - self.precipitation() * 0.3).clamp(0.2, 1.0)
+    }
+}
+
+/// A resource holding the current weather over the whole world, split into
+/// cells of [`CELL_SIZE`] blocks.
+///
+/// The server simulates the weather and regularly sends each client the part
+/// of the grid around it, as a [`WeatherWindow`].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WeatherGrid {
+    weather: Grid<Weather>,
+}
+
+/// A rectangular part of a [`WeatherGrid`].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WeatherWindow {
+    /// The cell in the corner of the window with the lowest coordinates.
+    min: Vec2<i32>,
+    weather: Grid<Weather>,
+}
+
+impl Default for WeatherGrid {
+    fn default() -> Self { Self::new(Vec2::zero()) }
+}
+
+impl WeatherGrid {
+    /// Create a grid of calm weather covering a world of the given size, in
+    /// chunks.
+    pub fn new(world_chunk_size: Vec2<u32>) -> Self {
+        let size = world_chunk_size.map(|e| ((e + CHUNKS_PER_CELL - 1) / CHUNKS_PER_CELL) as i32);
+        Self {
+            weather: Grid::new(size, Weather::default()),
+        }
+    }
+
+    pub fn size(&self) -> Vec2<i32> { self.weather.size() }
+
+    /// The cell containing the given world position.
+    pub fn cell_at(wpos: Vec2<f32>) -> Vec2<i32> {
+        wpos.map(|e| (e / CELL_SIZE as f32).floor() as i32)
+    }
+
+    pub fn get(&self, cell: Vec2<i32>) -> Option<&Weather> { self.weather.get(cell) }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Vec2<i32>, &Weather)> + '_ { self.weather.iter() }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Vec2<i32>, &mut Weather)> + '_ {
+        self.weather.iter_mut()
+    }
+
+    /// The cells at most `radius` cells away from the given cell, cut off at
+    /// the edges of the grid.
+    pub fn window(&self, center: Vec2<i32>, radius: i32) -> WeatherWindow {
+        let size = self.size();
+        let clamp = |v: Vec2<i32>| v.map2(size, |e, sz| e.clamp(0, sz.max(0)));
+        let min = clamp(center - radius);
+        let max = clamp(center + radius + 1);
+        WeatherWindow {
+            min,
+            weather: Grid::populate_from(max - min, |offs| self.weather[min + offs]),
+        }
+    }
+
+    /// Overwrite the cells covered by the window, ignoring any that lie
+    /// outside of the grid.
+    pub fn apply_window(&mut self, window: &WeatherWindow) {
+        for (offs, weather) in window.weather.iter() {
+            if let Some(cell) = self.weather.get_mut(window.min + offs) {
+                *cell = *weather;
+            }
+        }
+    }
+
+    /// The weather at the given world position, smoothly interpolated between
+    /// the centres of the surrounding cells.
+    pub fn get_interpolated(&self, wpos: Vec2<f32>) -> Weather {
+        let size = self.size();
+        if size.x <= 0 || size.y <= 0 {
+            return Weather::default();
+        }
+
+        let cpos = wpos / CELL_SIZE as f32 - 0.5;
+        let min = cpos.map(|e| e.floor());
+        let frac = cpos - min;
+        let get = |offs: Vec2<i32>| {
+            let cell = (min.map(|e| e as i32) + offs).map2(size, |e, sz| e.clamp(0, sz - 1));
+            self.weather[cell]
+        };
+
+        Weather::lerp(
+            &Weather::lerp(&get(Vec2::new(0, 0)), &get(Vec2::new(1, 0)), frac.x),
+            &Weather::lerp(&get(Vec2::new(0, 1)), &get(Vec2::new(1, 1)), frac.x),
+            frac.y,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interpolation_matches_cell_centres() {
+        let mut grid = WeatherGrid::new(Vec2::new(CHUNKS_PER_CELL * 2, CHUNKS_PER_CELL));
+        grid.iter_mut()
+            .for_each(|(cell, weather)| weather.rain = cell.x as f32);
+
+        let half = CELL_SIZE as f32 / 2.0;
+        assert_eq!(grid.get_interpolated(Vec2::new(half, half)).rain, 0.0);
+        assert_eq!(grid.get_interpolated(Vec2::new(half * 3.0, half)).rain, 1.0);
+        assert_eq!(grid.get_interpolated(Vec2::new(half * 2.0, half)).rain, 0.5);
+        // Positions outside of the world use the weather of the nearest cell
+        assert_eq!(grid.get_interpolated(Vec2::new(-1000.0, half)).rain, 0.0);
+    }
+
+    #[test]
+    fn windows_are_cut_off_at_the_edges() {
+        let mut grid = WeatherGrid::new(Vec2::new(CHUNKS_PER_CELL * 4, CHUNKS_PER_CELL * 4));
+        grid.iter_mut()
+            .for_each(|(cell, weather)| weather.rain = (cell.x + cell.y * 4) as f32);
+
+        let window = grid.window(Vec2::new(0, 3), 1);
+        assert_eq!(window.min, Vec2::new(0, 2));
+        assert_eq!(window.weather.size(), Vec2::new(2, 2));
+
+        let mut client_grid = WeatherGrid::new(Vec2::new(CHUNKS_PER_CELL * 4, CHUNKS_PER_CELL * 4));
+        client_grid.apply_window(&window);
+        for (cell, weather) in client_grid.iter() {
+            if cell.x <= 1 && cell.y >= 2 {
+                assert_eq!(weather, &grid.weather[cell]);
+            } else {
+                assert_eq!(weather, &Weather::default());
+            }
+        }
+
+        // Cells entirely outside of the grid give an empty window
+        assert_eq!(
+            grid.window(Vec2::new(-10, 0), 2).weather.size().product(),
+            0
+        );
+    }
+
+    #[test]
+    fn empty_grid_is_calm() {
+        let grid = WeatherGrid::default();
+        assert_eq!(
+            grid.get_interpolated(Vec2::new(100.0, 100.0)),
+            Weather::default()
+        );
+        assert_eq!(Weather::default().get_kind(), WeatherKind::Clear);
+    }
+}
@@ -187,11 +187,22 @@ impl State {
         ecs.register::<comp::InventoryUpdate>();
         ecs.register::<comp::Admin>();
         ecs.register::<comp::Waypoint>();
+        ecs.register::<comp::QuestLog>();
+        ecs.register::<comp::CraftingQueue>();
+        ecs.register::<comp::LoadoutPresets>();
+        ecs.register::<comp::Pet>();
+        ecs.register::<comp::Storage>();
+        ecs.register::<comp::Bank>();
+        ecs.register::<comp::StorageTarget>();
         ecs.register::<comp::Projectile>();
         ecs.register::<comp::Melee>();
         ecs.register::<comp::ItemDrop>();
         ecs.register::<comp::ChatMode>();
         ecs.register::<comp::Faction>();
+        ecs.register::<comp::Guild>();
+        ecs.register::<comp::PvpFlag>();
+        ecs.register::<comp::Duel>();
+        ecs.register::<comp::PvpZone>();
         ecs.register::<comp::invite::Invite>();
         ecs.register::<comp::invite::PendingInvites>();
         ecs.register::<comp::Beam>();
@@ -225,7 +236,9 @@ impl State {
         ecs.insert(SysMetrics::default());
         ecs.insert(PhysicsMetrics::default());
         ecs.insert(Trades::default());
+        ecs.insert(common::combat::PvpMode::default());
         ecs.insert(PlayerPhysicsSettings::default());
+        ecs.insert(common::weather::WeatherGrid::default());
 
         // Load plugins from asset directory
         #[cfg(feature = "plugins")]
@@ -1,12 +1,14 @@
 use common::{
+    combat::{HarmPolicy, PvpMode},
     comp::{
         aura::{AuraChange, AuraKey, AuraKind, AuraTarget},
         buff::{self, BuffCategory},
         group::Group,
-        Auras, BuffKind, Buffs, CharacterState, Health, Pos,
+        Alignment, Auras, BuffKind, Buffs, CharacterState, Duel, Health, Player, Pos, PvpFlag,
+        PvpZone,
     },
     event::{EventBus, ServerEvent},
-    resources::DeltaTime,
+    resources::{DeltaTime, Time},
     uid::{Uid, UidAllocator},
 };
 use common_ecs::{Job, Origin, Phase, System};
@@ -28,6 +30,13 @@ pub struct ReadData<'a> {
     healths: ReadStorage<'a, Health>,
     groups: ReadStorage<'a, Group>,
     uids: ReadStorage<'a, Uid>,
+    time: Read<'a, Time>,
+    players: ReadStorage<'a, Player>,
+    alignments: ReadStorage<'a, Alignment>,
+    pvp_mode: Read<'a, PvpMode>,
+    pvp_flags: ReadStorage<'a, PvpFlag>,
+    duels: ReadStorage<'a, Duel>,
+    pvp_zones: ReadStorage<'a, PvpZone>,
 }
 
 #[derive(Default)]
@@ -45,6 +54,20 @@ impl<'a> System<'a> for Sys {
 
     fn run(_job: &mut Job<Self>, (read_data, mut auras, mut buffs): Self::SystemData) {
         let mut server_emitter = read_data.server_bus.emitter();
+
+        let harm_policy = HarmPolicy {
+            mode: *read_data.pvp_mode,
+            time: read_data.time.0,
+            players: &read_data.players,
+            pvp_flags: &read_data.pvp_flags,
+            duels: &read_data.duels,
+            pvp_zones: &read_data.pvp_zones,
+            positions: &read_data.positions,
+            uids: &read_data.uids,
+            alignments: &read_data.alignments,
+            uid_allocator: &read_data.uid_allocator,
+        };
+
         let dt = read_data.dt.0;
 
         auras.set_event_emission(false);
@@ -134,6 +157,10 @@ impl<'a> System<'a> for Sys {
                                             Some(CharacterState::Sit)
                                         ) && health.current() < health.maximum()
                                     },
+                                    // Debuffs follow the same PvP rules as attacks
+                                    _ if !kind.is_buff() => {
+                                        harm_policy.may_harm(Some(entity), target)
+                                    },
                                     // Add other specific buff conditions here
                                     _ => true,
                                 };
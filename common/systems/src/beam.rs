@@ -1,9 +1,9 @@
 use common::{
-    combat::{AttackSource, AttackerInfo, TargetInfo},
+    combat::{AttackSource, AttackerInfo, HarmPolicy, PvpMode, TargetInfo},
     comp::{
         agent::{Sound, SoundKind},
-        Beam, BeamSegment, Body, CharacterState, Combo, Energy, Group, Health, HealthSource,
-        Inventory, Ori, Pos, Scale, Stats,
+        Alignment, Beam, BeamSegment, Body, CharacterState, Combo, Duel, Energy, Group, Health,
+        HealthSource, Inventory, Ori, Player, Pos, PvpFlag, PvpZone, Scale, Stats,
     },
     event::{EventBus, ServerEvent},
     outcome::Outcome,
@@ -44,6 +44,12 @@ pub struct ReadData<'a> {
     stats: ReadStorage<'a, Stats>,
     combos: ReadStorage<'a, Combo>,
     character_states: ReadStorage<'a, CharacterState>,
+    players: ReadStorage<'a, Player>,
+    alignments: ReadStorage<'a, Alignment>,
+    pvp_mode: Read<'a, PvpMode>,
+    pvp_flags: ReadStorage<'a, PvpFlag>,
+    duels: ReadStorage<'a, Duel>,
+    pvp_zones: ReadStorage<'a, PvpZone>,
 }
 
 /// This system is responsible for handling beams that heal or do damage
@@ -67,6 +73,19 @@ impl<'a> System<'a> for Sys {
     ) {
         let mut server_emitter = read_data.server_bus.emitter();
 
+        let harm_policy = HarmPolicy {
+            mode: *read_data.pvp_mode,
+            time: read_data.time.0,
+            players: &read_data.players,
+            pvp_flags: &read_data.pvp_flags,
+            duels: &read_data.duels,
+            pvp_zones: &read_data.pvp_zones,
+            positions: &read_data.positions,
+            uids: &read_data.uids,
+            alignments: &read_data.alignments,
+            uid_allocator: &read_data.uid_allocator,
+        };
+
         let time = read_data.time.0;
         let dt = read_data.dt.0;
 
@@ -218,6 +237,7 @@ impl<'a> System<'a> for Sys {
                         target_info,
                         ori.look_dir(),
                         false,
+                        harm_policy.may_harm(beam_owner, target),
                         1.0,
                         AttackSource::Beam,
                         |e| server_events.push(e),
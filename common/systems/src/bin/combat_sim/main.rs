@@ -0,0 +1,599 @@
+//! Simulates fights between two characters without terrain, running the same
+//! character behavior, melee, projectile, buff and stats systems as the game,
+//! and writes the outcome of every fight to a csv file. Used to balance
+//! abilities and items without having to play the game.
+//!
+//! Only the server events that matter for a fight are applied. Beams,
+//! shockwaves and explosions are not simulated, the fighters don't move and
+//! every projectile hits its target after flying in a straight line.
+#![deny(clippy::clone_on_ref_ptr)]
+#![feature(array_map)]
+
+use std::{error::Error, path::PathBuf, str::FromStr, time::Duration};
+use structopt::StructOpt;
+
+use common::{
+    comp::{
+        self,
+        buff::{BuffCategory, BuffChange},
+        humanoid,
+        skills::{GeneralSkill, Skill},
+        Body, Buffs, CharacterState, Combo, ControlAction, Controller, Energy, Health,
+        HealthSource, InputKind, Inventory, Item, Ori, PhysicsState, Poise, Pos, Projectile,
+        SkillSet, Stats, Vel,
+    },
+    event::{EventBus, ServerEvent},
+    outcome::Outcome,
+    resources::Time,
+    terrain::{Block, BlockKind},
+    uid::Uid,
+    util::Dir,
+    LoadoutBuilder, SkillSetBuilder,
+};
+use common_ecs::{dispatch, System};
+use common_net::sync::WorldSyncExt;
+use common_state::State;
+use rand::thread_rng;
+use specs::{Builder, DispatcherBuilder, Entity as EcsEntity, WorldExt};
+use vek::*;
+use veloren_common_systems::{buff, character_behavior, melee, projectile, stats};
+
+/// The input a fighter keeps using, "none" for a fighter that doesn't attack
+#[derive(Copy, Clone)]
+struct Input(Option<InputKind>);
+
+impl FromStr for Input {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Input(match s {
+            "none" => None,
+            "primary" => Some(InputKind::Primary),
+            "secondary" => Some(InputKind::Secondary),
+            s => match s
+                .strip_prefix("ability")
+                .and_then(|i| i.parse::<usize>().ok())
+            {
+                Some(i) if i > 0 => Some(InputKind::Ability(i - 1)),
+                _ => {
+                    return Err(format!(
+                        "Unknown input \"{}\", expected \"primary\", \"secondary\", \"ability1\", \
+                         \"ability2\", ... or \"none\"",
+                        s
+                    ));
+                },
+            },
+        }))
+    }
+}
+
+#[derive(StructOpt)]
+struct Cli {
+    /// Number of fights to simulate
+    #[structopt(long, default_value = "100")]
+    trials: u32,
+    /// Longest a fight may last, in seconds
+    #[structopt(long, default_value = "120")]
+    time_limit: f64,
+    /// Ticks simulated per second
+    #[structopt(long, default_value = "30")]
+    tps: u32,
+    /// Distance between the fighters, in blocks
+    #[structopt(long, default_value = "2")]
+    distance: f32,
+    /// File the results are written to
+    #[structopt(long, default_value = "combat_sim.csv", parse(from_os_str))]
+    output: PathBuf,
+    /// Loadout asset of fighter A
+    #[structopt(long, default_value = "common.loadout.default")]
+    a_loadout: String,
+    /// Weapon of fighter A, replacing the one of its loadout
+    #[structopt(long, default_value = "common.items.weapons.sword.starter")]
+    a_weapon: String,
+    /// Skill set asset of fighter A, no skills if not given
+    #[structopt(long)]
+    a_skillset: Option<String>,
+    /// Input fighter A keeps using: "primary", "secondary", "ability1", ... or
+    /// "none"
+    #[structopt(long, default_value = "primary")]
+    a_input: Input,
+    /// Loadout asset of fighter B
+    #[structopt(long, default_value = "common.loadout.default")]
+    b_loadout: String,
+    /// Weapon of fighter B, replacing the one of its loadout
+    #[structopt(long, default_value = "common.items.weapons.sword.starter")]
+    b_weapon: String,
+    /// Skill set asset of fighter B, no skills if not given
+    #[structopt(long)]
+    b_skillset: Option<String>,
+    /// Input fighter B keeps using: "primary", "secondary", "ability1", ... or
+    /// "none"
+    #[structopt(long, default_value = "primary")]
+    b_input: Input,
+}
+
+struct Fighter<'a> {
+    name: &'static str,
+    loadout: &'a str,
+    weapon: &'a str,
+    skillset: Option<&'a str>,
+    input: Option<InputKind>,
+}
+
+/// What a fighter did during one fight. Damage and energy are in the units
+/// shown in the HUD.
+#[derive(Default)]
+struct FighterResult {
+    damage_dealt: f64,
+    /// How often the fighter interrupted its opponent by breaking its poise
+    poise_breaks: u32,
+    energy_spent: f64,
+}
+
+struct TrialResult {
+    winner: Option<usize>,
+    duration: f64,
+    fighters: [FighterResult; 2],
+}
+
+/// A projectile on its way to the fighter it was shot at
+struct Flight {
+    projectile: EcsEntity,
+    target: EcsEntity,
+    arrives_at: f64,
+}
+
+fn add_combat_systems(dispatch_builder: &mut DispatcherBuilder) {
+    dispatch::<character_behavior::Sys>(dispatch_builder, &[]);
+    dispatch::<buff::Sys>(dispatch_builder, &[]);
+    dispatch::<stats::Sys>(dispatch_builder, &[&buff::Sys::sys_name()]);
+    dispatch::<projectile::Sys>(dispatch_builder, &[&character_behavior::Sys::sys_name()]);
+    dispatch::<melee::Sys>(dispatch_builder, &[&projectile::Sys::sys_name()]);
+}
+
+fn spawn_fighter(state: &mut State, fighter: &Fighter, pos: Vec3<f32>) -> EcsEntity {
+    let body = Body::Humanoid(humanoid::Body::random());
+    let loadout = LoadoutBuilder::new()
+        .with_asset_expect(fighter.loadout, &mut thread_rng())
+        .active_mainhand(Some(Item::new_from_asset_expect(fighter.weapon)))
+        .build();
+    let skill_set = fighter.skillset.map_or_else(SkillSet::default, |skillset| {
+        SkillSetBuilder::from_asset_expect(skillset).build()
+    });
+    let level = |skill| skill_set.skill_level(skill).unwrap_or(None).unwrap_or(0);
+    let health = Health::new(body, level(Skill::General(GeneralSkill::HealthIncrease)));
+    let energy = Energy::new(body, level(Skill::General(GeneralSkill::EnergyIncrease)));
+
+    state
+        .ecs_mut()
+        .create_entity_synced()
+        .with(Pos(pos))
+        .with(Vel(Vec3::zero()))
+        .with(Ori::default())
+        .with(body.mass())
+        .with(body.density())
+        .with(PhysicsState {
+            on_ground: Some(Block::new(BlockKind::Earth, Rgb::zero())),
+            ..PhysicsState::default()
+        })
+        .with(Controller::default())
+        .with(body)
+        .with(health)
+        .with(energy)
+        .with(Poise::new(body))
+        .with(Stats::new(fighter.name.to_owned()))
+        .with(skill_set)
+        .with(CharacterState::default())
+        .with(Inventory::new_with_loadout(loadout))
+        .with(Buffs::default())
+        .with(Combo::default())
+        .build()
+}
+
+/// Makes the fighter face its opponent and keep using its input
+fn drive(state: &State, entity: EcsEntity, opponent: EcsEntity, input: Option<InputKind>) {
+    let positions = state.ecs().read_storage::<Pos>();
+    let look_dir = positions
+        .get(entity)
+        .zip(positions.get(opponent))
+        .and_then(|(pos, opponent_pos)| Dir::from_unnormalized(opponent_pos.0 - pos.0))
+        .unwrap_or_default();
+    let wielding = state
+        .ecs()
+        .read_storage::<CharacterState>()
+        .get(entity)
+        .map_or(false, CharacterState::is_wield);
+    let target_entity = state.ecs().read_storage::<Uid>().get(opponent).copied();
+
+    if let Some(controller) = state.ecs().write_storage::<Controller>().get_mut(entity) {
+        controller.inputs.look_dir = look_dir;
+        if let Some(input) = input {
+            if !wielding {
+                controller.actions.push(ControlAction::Wield);
+            } else if !controller.queued_inputs.contains_key(&input) {
+                controller.actions.push(ControlAction::StartInput {
+                    input,
+                    target_entity,
+                    select_pos: None,
+                });
+            }
+        }
+    }
+}
+
+/// Lets the projectiles that reached their target touch it, so that the
+/// projectile system applies their effects
+fn land_projectiles(state: &State, flights: &mut Vec<Flight>) {
+    let time = state.get_time();
+    let uids = state.ecs().read_storage::<Uid>();
+    let mut positions = state.ecs().write_storage::<Pos>();
+    let mut physics_states = state.ecs().write_storage::<PhysicsState>();
+    flights.retain(|flight| {
+        if flight.arrives_at > time {
+            return true;
+        }
+        let target = uids.get(flight.target).copied();
+        let target_pos = positions.get(flight.target).copied();
+        if let (Some(target), Some(target_pos), Some(physics)) = (
+            target,
+            target_pos,
+            physics_states.get_mut(flight.projectile),
+        ) {
+            physics.touch_entities.insert(target);
+            if let Some(pos) = positions.get_mut(flight.projectile) {
+                *pos = target_pos;
+            }
+        }
+        false
+    });
+}
+
+fn shoot(
+    state: &mut State,
+    shooter: EcsEntity,
+    target: EcsEntity,
+    dir: Dir,
+    body: Body,
+    projectile: Projectile,
+    speed: f32,
+) -> Option<Flight> {
+    let positions = state.ecs().read_storage::<Pos>();
+    let mut pos = positions.get(shooter)?.0;
+    let target_pos = positions.get(target)?.0;
+    drop(positions);
+    pos.z += state
+        .ecs()
+        .read_storage::<Body>()
+        .get(shooter)
+        .map_or(0.0, Body::eye_height);
+    let arrives_at = state.get_time() + f64::from(pos.distance(target_pos) / speed.max(1.0));
+
+    let projectile = state
+        .ecs_mut()
+        .create_entity_synced()
+        .with(Pos(pos))
+        .with(Vel(*dir * speed))
+        .with(Ori::from(dir))
+        .with(body)
+        .with(PhysicsState::default())
+        .with(projectile)
+        .build();
+    Some(Flight {
+        projectile,
+        target,
+        arrives_at,
+    })
+}
+
+fn change_buffs(state: &State, entity: EcsEntity, buff_change: BuffChange) {
+    let mut buffs_all = state.ecs().write_storage::<Buffs>();
+    let mut buffs = match buffs_all.get_mut(entity) {
+        Some(buffs) => buffs,
+        None => return,
+    };
+    match buff_change {
+        BuffChange::Add(new_buff) => {
+            let immune = state
+                .ecs()
+                .read_storage::<Body>()
+                .get(entity)
+                .map_or(false, |body| body.immune_to(new_buff.kind));
+            if !immune {
+                buffs.insert(new_buff);
+            }
+        },
+        BuffChange::RemoveById(ids) => {
+            for id in ids {
+                buffs.remove(id);
+            }
+        },
+        BuffChange::RemoveByKind(kind) => buffs.remove_kind(kind),
+        BuffChange::RemoveFromController(kind) => {
+            if kind.is_buff() {
+                buffs.remove_kind(kind);
+            }
+        },
+        BuffChange::RemoveByCategory {
+            all_required,
+            any_required,
+            none_required,
+        } => {
+            let has = |cat_ids: &[BuffCategory], cat: &BuffCategory| cat_ids.contains(cat);
+            let ids_to_remove = buffs
+                .buffs
+                .iter()
+                .filter(|(_, buff)| {
+                    all_required.iter().all(|cat| has(&buff.cat_ids, cat))
+                        && (any_required.is_empty()
+                            || any_required.iter().any(|cat| has(&buff.cat_ids, cat)))
+                        && !none_required.iter().any(|cat| has(&buff.cat_ids, cat))
+                })
+                .map(|(id, _)| *id)
+                .collect::<Vec<_>>();
+            for id in ids_to_remove {
+                buffs.remove(id);
+            }
+        },
+    }
+}
+
+/// Applies the server events that matter for a fight, like the server would
+fn handle_event(
+    state: &mut State,
+    event: ServerEvent,
+    fighters: [EcsEntity; 2],
+    results: &mut [FighterResult; 2],
+    flights: &mut Vec<Flight>,
+) {
+    let ecs = state.ecs();
+    match event {
+        ServerEvent::Damage { entity, change } => {
+            if let HealthSource::Damage { by: Some(by), .. } = change.cause {
+                let uids = ecs.read_storage::<Uid>();
+                if let Some(i) = fighters.iter().position(|f| uids.get(*f) == Some(&by)) {
+                    if change.amount < 0 && fighters[i] != entity {
+                        results[i].damage_dealt += f64::from(-change.amount) / 10.0;
+                    }
+                }
+            }
+            if let Some(mut health) = ecs.write_storage::<Health>().get_mut(entity) {
+                health.change_by(change);
+            }
+        },
+        ServerEvent::PoiseChange {
+            entity,
+            change,
+            kb_dir,
+        } => {
+            let stunned = ecs
+                .read_storage::<CharacterState>()
+                .get(entity)
+                .map_or(false, CharacterState::is_stunned);
+            if !stunned {
+                if let Some(mut poise) = ecs.write_storage::<Poise>().get_mut(entity) {
+                    poise.change_by(change, kb_dir);
+                }
+            }
+        },
+        ServerEvent::EnergyChange { entity, change } => {
+            if let Some(mut energy) = ecs.write_storage::<Energy>().get_mut(entity) {
+                energy.change_by(change);
+            }
+        },
+        ServerEvent::ComboChange { entity, change } => {
+            let time = ecs.read_resource::<Time>().0;
+            if let Some(mut combo) = ecs.write_storage::<Combo>().get_mut(entity) {
+                combo.change_by(change, time);
+            }
+        },
+        ServerEvent::Buff {
+            entity,
+            buff_change,
+        } => change_buffs(state, entity, buff_change),
+        ServerEvent::Shoot {
+            entity,
+            dir,
+            body,
+            projectile,
+            speed,
+            ..
+        } => {
+            let target = if entity == fighters[0] {
+                fighters[1]
+            } else {
+                fighters[0]
+            };
+            if let Some(flight) = shoot(state, entity, target, dir, body, projectile, speed) {
+                flights.push(flight);
+            }
+        },
+        ServerEvent::Destroy { entity, .. } => {
+            if ecs.read_storage::<Projectile>().contains(entity) {
+                let _ = state.ecs_mut().delete_entity(entity);
+            }
+        },
+        _ => {},
+    }
+}
+
+fn simulate(state: &mut State, cli: &Cli, fighters: &[Fighter; 2]) -> TrialResult {
+    let entities = [
+        spawn_fighter(state, &fighters[0], Vec3::zero()),
+        spawn_fighter(state, &fighters[1], Vec3::unit_x() * cli.distance),
+    ];
+    let dt = Duration::from_secs_f64(1.0 / f64::from(cli.tps.max(1)));
+    let mut results = [FighterResult::default(), FighterResult::default()];
+    let mut flights = Vec::new();
+    let mut duration = 0.0;
+    let mut winner = None;
+
+    while duration < cli.time_limit {
+        for i in 0..2 {
+            drive(state, entities[i], entities[1 - i], fighters[i].input);
+        }
+        land_projectiles(state, &mut flights);
+
+        let energies = entities.map(|e| energy_of(state, e));
+        let stunned = entities.map(|e| is_stunned(state, e));
+
+        state.tick(dt, add_combat_systems, false);
+        let events = state
+            .ecs()
+            .read_resource::<EventBus<ServerEvent>>()
+            .recv_all();
+        for event in events {
+            handle_event(state, event, entities, &mut results, &mut flights);
+        }
+        state.ecs_mut().maintain();
+        state.ecs().write_resource::<Vec<Outcome>>().clear();
+        state.cleanup();
+        duration += dt.as_secs_f64();
+
+        for i in 0..2 {
+            let spent = energies[i] - energy_of(state, entities[i]);
+            if spent > 0.0 {
+                results[i].energy_spent += spent;
+            }
+            if !stunned[1 - i] && is_stunned(state, entities[1 - i]) {
+                results[i].poise_breaks += 1;
+            }
+        }
+
+        let alive = entities.map(|e| {
+            state
+                .ecs()
+                .read_storage::<Health>()
+                .get(e)
+                .map_or(false, |h| !h.should_die())
+        });
+        match alive {
+            [true, true] => {},
+            [true, false] => winner = Some(0),
+            [false, true] => winner = Some(1),
+            [false, false] => {},
+        }
+        if alive != [true, true] {
+            break;
+        }
+    }
+
+    state.ecs_mut().delete_all();
+    state.ecs_mut().maintain();
+
+    TrialResult {
+        winner,
+        duration,
+        fighters: results,
+    }
+}
+
+fn energy_of(state: &State, entity: EcsEntity) -> f64 {
+    state
+        .ecs()
+        .read_storage::<Energy>()
+        .get(entity)
+        .map_or(0.0, |e| f64::from(e.current()) / 10.0)
+}
+
+fn is_stunned(state: &State, entity: EcsEntity) -> bool {
+    state
+        .ecs()
+        .read_storage::<CharacterState>()
+        .get(entity)
+        .map_or(false, CharacterState::is_stunned)
+}
+
+fn run(cli: &Cli) -> Result<(), Box<dyn Error>> {
+    let fighters = [
+        Fighter {
+            name: "A",
+            loadout: &cli.a_loadout,
+            weapon: &cli.a_weapon,
+            skillset: cli.a_skillset.as_deref(),
+            input: cli.a_input.0,
+        },
+        Fighter {
+            name: "B",
+            loadout: &cli.b_loadout,
+            weapon: &cli.b_weapon,
+            skillset: cli.b_skillset.as_deref(),
+            input: cli.b_input.0,
+        },
+    ];
+
+    let mut state = State::server();
+    state
+        .ecs_mut()
+        .insert(comp::inventory::item::MaterialStatManifest::default());
+    state
+        .ecs_mut()
+        .insert(comp::inventory::item::tool::AbilityMap::default());
+
+    let mut wtr = csv::Writer::from_path(&cli.output)?;
+    wtr.write_record(&[
+        "Trial",
+        "Winner",
+        "Duration",
+        "A Damage",
+        "A DPS",
+        "A Poise Breaks",
+        "A Energy Spent",
+        "B Damage",
+        "B DPS",
+        "B Poise Breaks",
+        "B Energy Spent",
+    ])?;
+
+    let mut wins = [0; 2];
+    let mut time_to_kill = [0.0; 2];
+    for trial in 0..cli.trials {
+        let result = simulate(&mut state, cli, &fighters);
+        if let Some(winner) = result.winner {
+            wins[winner] += 1;
+            time_to_kill[winner] += result.duration;
+        }
+
+        let mut record = vec![
+            trial.to_string(),
+            result
+                .winner
+                .map_or("None", |i| fighters[i].name)
+                .to_owned(),
+            format!("{:.2}", result.duration),
+        ];
+        for fighter in &result.fighters {
+            record.push(format!("{:.1}", fighter.damage_dealt));
+            record.push(format!(
+                "{:.2}",
+                fighter.damage_dealt / result.duration.max(f64::EPSILON)
+            ));
+            record.push(fighter.poise_breaks.to_string());
+            record.push(format!("{:.1}", fighter.energy_spent));
+        }
+        wtr.write_record(&record)?;
+    }
+    wtr.flush()?;
+
+    for (i, fighter) in fighters.iter().enumerate() {
+        if wins[i] > 0 {
+            println!(
+                "Fighter {} won {} of {} fights, with an average time to kill of {:.2}s",
+                fighter.name,
+                wins[i],
+                cli.trials,
+                time_to_kill[i] / f64::from(wins[i])
+            );
+        } else {
+            println!("Fighter {} won none of {} fights", fighter.name, cli.trials);
+        }
+    }
+    println!("Results written to {}", cli.output.display());
+    Ok(())
+}
+
+fn main() {
+    let cli = Cli::from_args();
+    if let Err(e) = run(&cli) {
+        println!("Error: {}\n", e)
+    }
+}
@@ -5,19 +5,28 @@ use common::{
             Buffs,
         },
         fluid_dynamics::{Fluid, LiquidKind},
-        Energy, Health, HealthChange, HealthSource, Inventory, ModifierKind, PhysicsState, Stats,
+        Energy, Health, HealthChange, HealthSource, Inventory, ModifierKind, PhysicsState, Pos,
+        Stats,
     },
     event::{EventBus, ServerEvent},
     resources::DeltaTime,
-    terrain::SpriteKind,
+    terrain::{Block, SpriteKind, TerrainGrid},
+    vol::ReadVol,
+    weather::WeatherGrid,
     Damage, DamageSource,
 };
 use common_ecs::{Job, Origin, Phase, System};
 use hashbrown::HashMap;
 use specs::{
-    shred::ResourceId, Entities, Join, Read, ReadStorage, SystemData, World, WriteStorage,
+    shred::ResourceId, Entities, Join, Read, ReadExpect, ReadStorage, SystemData, World,
+    WriteStorage,
 };
 use std::time::Duration;
+use vek::*;
+
+/// Rain or snow heavier than this puts out fires on entities exposed to the
+/// sky.
+const EXTINGUISHING_PRECIPITATION: f32 = 0.5;
 
 #[derive(SystemData)]
 pub struct ReadData<'a> {
@@ -28,6 +37,9 @@ pub struct ReadData<'a> {
     healths: ReadStorage<'a, Health>,
     physics_states: ReadStorage<'a, PhysicsState>,
     energies: ReadStorage<'a, Energy>,
+    positions: ReadStorage<'a, Pos>,
+    terrain: ReadExpect<'a, TerrainGrid>,
+    weather: Read<'a, WeatherGrid>,
 }
 
 #[derive(Default)]
@@ -49,13 +61,14 @@ impl<'a> System<'a> for Sys {
         // Set to false to avoid spamming server
         buffs.set_event_emission(false);
         stats.set_event_emission(false);
-        for (entity, mut buff_comp, energy, mut stat, health, physics_state) in (
+        for (entity, mut buff_comp, energy, mut stat, health, physics_state, pos) in (
             &read_data.entities,
             &mut buffs,
             &read_data.energies,
             &mut stats,
             &read_data.healths,
             read_data.physics_states.maybe(),
+            read_data.positions.maybe(),
         )
             .join()
         {
@@ -109,6 +122,29 @@ impl<'a> System<'a> for Sys {
                 }
             }
 
+            // Heavy rain or snow puts out fires, unless sheltered from it
+            if let Some(pos) = pos {
+                if buff_comp.kinds.contains_key(&BuffKind::Burning)
+                    && read_data
+                        .weather
+                        .get_interpolated(pos.0.xy())
+                        .precipitation()
+                        > EXTINGUISHING_PRECIPITATION
+                    && read_data
+                        .terrain
+                        .ray(pos.0, pos.0 + Vec3::unit_z() * 64.0)
+                        .until(Block::is_opaque)
+                        .cast()
+                        .1
+                        .map_or(true, |b| b.is_none())
+                {
+                    server_emitter.emit(ServerEvent::Buff {
+                        entity,
+                        buff_change: BuffChange::RemoveByKind(BuffKind::Burning),
+                    });
+                }
+            }
+
             let (buff_comp_kinds, buff_comp_buffs): (
                 &HashMap<BuffKind, Vec<BuffId>>,
                 &mut HashMap<BuffId, Buff>,
@@ -1,13 +1,15 @@
 use specs::{
-    shred::ResourceId, Entities, Join, LazyUpdate, Read, ReadExpect, ReadStorage, SystemData,
-    World, Write, WriteStorage,
+    saveload::{Marker, MarkerAllocator},
+    shred::ResourceId,
+    Entities, Join, LazyUpdate, Read, ReadExpect, ReadStorage, SystemData, World, Write,
+    WriteStorage,
 };
 
 use common::{
     comp::{
         self, inventory::item::MaterialStatManifest, Beam, Body, CharacterState, Combo, Controller,
-        Density, Energy, Health, Inventory, Mass, Melee, Mounting, Ori, PhysicsState, Poise,
-        PoiseState, Pos, SkillSet, StateUpdate, Stats, Vel,
+        Density, Energy, Health, Inventory, Mass, Melee, MountState, Mounting, Ori, PhysicsState,
+        Poise, PoiseState, Pos, SkillSet, StateUpdate, Stats, Vel,
     },
     event::{EventBus, LocalEvent, ServerEvent},
     outcome::Outcome,
@@ -17,7 +19,7 @@ use common::{
         behavior::{CharacterBehavior, JoinData, JoinStruct},
     },
     terrain::TerrainGrid,
-    uid::Uid,
+    uid::{Uid, UidAllocator},
 };
 use common_ecs::{Job, Origin, Phase, System};
 use std::time::Duration;
@@ -55,6 +57,7 @@ pub struct ReadData<'a> {
     local_bus: Read<'a, EventBus<LocalEvent>>,
     dt: Read<'a, DeltaTime>,
     lazy_update: Read<'a, LazyUpdate>,
+    uid_allocator: Read<'a, UidAllocator>,
     healths: ReadStorage<'a, Health>,
     bodies: ReadStorage<'a, Body>,
     masses: ReadStorage<'a, Mass>,
@@ -63,6 +66,7 @@ pub struct ReadData<'a> {
     beams: ReadStorage<'a, Beam>,
     uids: ReadStorage<'a, Uid>,
     mountings: ReadStorage<'a, Mounting>,
+    mount_states: ReadStorage<'a, MountState>,
     stats: ReadStorage<'a, Stats>,
     skill_sets: ReadStorage<'a, SkillSet>,
     msm: Read<'a, MaterialStatManifest>,
@@ -283,6 +287,8 @@ impl<'a> System<'a> for Sys {
                 combo: &combo,
                 alignment: read_data.alignments.get(entity),
                 terrain: &read_data.terrain,
+                mount_state: read_data.mount_states.get(entity),
+                mounting: read_data.mountings.get(entity),
             };
 
             for action in actions {
@@ -332,6 +338,7 @@ impl<'a> System<'a> for Sys {
                     CharacterState::BasicSummon(data) => data.handle_event(&j, action),
                     CharacterState::SelfBuff(data) => data.handle_event(&j, action),
                     CharacterState::SpriteSummon(data) => data.handle_event(&j, action),
+                    CharacterState::Fishing(data) => data.handle_event(&j, action),
                 };
                 local_emitter.append(&mut state_update.local_events);
                 server_emitter.append(&mut state_update.server_events);
@@ -339,14 +346,21 @@ impl<'a> System<'a> for Sys {
             }
 
             // Mounted occurs after control actions have been handled
-            // If mounted, character state is controlled by mount
-            // TODO: Make mounting a state
-            if let Some(Mounting(_)) = read_data.mountings.get(entity) {
-                let sit_state = CharacterState::Sit {};
-                if join_struct.char_state.get_unchecked() != &sit_state {
-                    *join_struct.char_state.get_mut_unchecked() = sit_state;
+            // Only riders in the saddle of a creature can fight, passengers of ships and
+            // other mounts are kept sitting
+            if let Some(Mounting(mount)) = read_data.mountings.get(entity) {
+                let in_saddle = read_data
+                    .uid_allocator
+                    .retrieve_entity_internal(mount.id())
+                    .and_then(|mount| read_data.bodies.get(mount))
+                    .map_or(false, |body| body.mount_stats().is_some());
+                if !in_saddle {
+                    let sit_state = CharacterState::Sit {};
+                    if join_struct.char_state.get_unchecked() != &sit_state {
+                        *join_struct.char_state.get_mut_unchecked() = sit_state;
+                    }
+                    continue;
                 }
-                continue;
             }
 
             let j = JoinData::new(
@@ -388,6 +402,7 @@ impl<'a> System<'a> for Sys {
                 CharacterState::BasicSummon(data) => data.behavior(&j),
                 CharacterState::SelfBuff(data) => data.behavior(&j),
                 CharacterState::SpriteSummon(data) => data.behavior(&j),
+                CharacterState::Fishing(data) => data.behavior(&j),
             };
 
             local_emitter.append(&mut state_update.local_events);
@@ -88,6 +88,18 @@ impl<'a> System<'a> for Sys {
                     ControlEvent::GroupManip(manip) => {
                         server_emitter.emit(ServerEvent::GroupManip(entity, manip))
                     },
+                    ControlEvent::GuildManip(manip) => {
+                        server_emitter.emit(ServerEvent::GuildManip(entity, manip))
+                    },
+                    ControlEvent::StorageManip(manip) => {
+                        server_emitter.emit(ServerEvent::StorageManip(entity, manip))
+                    },
+                    ControlEvent::MailManip(manip) => {
+                        server_emitter.emit(ServerEvent::MailManip(entity, manip))
+                    },
+                    ControlEvent::BuildManip(manip) => {
+                        server_emitter.emit(ServerEvent::BuildManip(entity, manip))
+                    },
                     ControlEvent::Respawn => server_emitter.emit(ServerEvent::Respawn(entity)),
                     ControlEvent::Utterance(kind) => {
                         if let (Some(pos), Some(body)) = (
@@ -3,7 +3,7 @@
 
 mod aura;
 mod beam;
-mod buff;
+pub mod buff;
 pub mod character_behavior;
 pub mod controller;
 mod interpolation;
@@ -12,7 +12,7 @@ mod mount;
 pub mod phys;
 pub mod projectile;
 mod shockwave;
-mod stats;
+pub mod stats;
 
 // External
 use common_ecs::{dispatch, System};
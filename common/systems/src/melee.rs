@@ -1,14 +1,14 @@
 use common::{
-    combat::{AttackSource, AttackerInfo, TargetInfo},
+    combat::{AttackSource, AttackerInfo, HarmPolicy, PvpMode, TargetInfo},
     comp::{
         agent::{Sound, SoundKind},
-        Body, CharacterState, Combo, Energy, Group, Health, Inventory, Melee, Ori, Pos, Scale,
-        Stats,
+        Alignment, Body, CharacterState, Combo, Duel, Energy, Group, Health, Inventory, Melee, Ori,
+        Player, Pos, PvpFlag, PvpZone, Scale, Stats,
     },
     event::{EventBus, ServerEvent},
     outcome::Outcome,
     resources::Time,
-    uid::Uid,
+    uid::{Uid, UidAllocator},
     util::Dir,
     GroupTarget,
 };
@@ -22,6 +22,7 @@ use vek::*;
 pub struct ReadData<'a> {
     time: Read<'a, Time>,
     entities: Entities<'a>,
+    uid_allocator: Read<'a, UidAllocator>,
     uids: ReadStorage<'a, Uid>,
     positions: ReadStorage<'a, Pos>,
     orientations: ReadStorage<'a, Ori>,
@@ -35,6 +36,12 @@ pub struct ReadData<'a> {
     server_bus: Read<'a, EventBus<ServerEvent>>,
     stats: ReadStorage<'a, Stats>,
     combos: ReadStorage<'a, Combo>,
+    players: ReadStorage<'a, Player>,
+    alignments: ReadStorage<'a, Alignment>,
+    pvp_mode: Read<'a, PvpMode>,
+    pvp_flags: ReadStorage<'a, PvpFlag>,
+    duels: ReadStorage<'a, Duel>,
+    pvp_zones: ReadStorage<'a, PvpZone>,
 }
 
 /// This system is responsible for handling accepted inputs like moving or
@@ -55,6 +62,20 @@ impl<'a> System<'a> for Sys {
 
     fn run(_job: &mut Job<Self>, (read_data, mut melee_attacks, mut outcomes): Self::SystemData) {
         let mut server_emitter = read_data.server_bus.emitter();
+
+        let harm_policy = HarmPolicy {
+            mode: *read_data.pvp_mode,
+            time: read_data.time.0,
+            players: &read_data.players,
+            pvp_flags: &read_data.pvp_flags,
+            duels: &read_data.duels,
+            pvp_zones: &read_data.pvp_zones,
+            positions: &read_data.positions,
+            uids: &read_data.uids,
+            alignments: &read_data.alignments,
+            uid_allocator: &read_data.uid_allocator,
+        };
+
         // Attacks
         for (attacker, uid, pos, ori, melee_attack, body) in (
             &read_data.entities,
@@ -167,6 +188,7 @@ impl<'a> System<'a> for Sys {
                         target_info,
                         dir,
                         is_dodge,
+                        harm_policy.may_harm(Some(attacker), target),
                         1.0,
                         AttackSource::Melee,
                         |e| server_emitter.emit(e),
@@ -1,13 +1,12 @@
 use common::{
-    comp::{Body, Controller, MountState, Mounting, Ori, Pos, Vel},
+    comp::{Controller, MountState, Mounting},
     uid::UidAllocator,
 };
 use common_ecs::{Job, Origin, Phase, System};
 use specs::{
     saveload::{Marker, MarkerAllocator},
-    Entities, Join, Read, ReadStorage, WriteStorage,
+    Entities, Join, Read, WriteStorage,
 };
-use vek::*;
 
 /// This system is responsible for controlling mounts
 #[derive(Default)]
@@ -20,10 +19,6 @@ impl<'a> System<'a> for Sys {
         WriteStorage<'a, Controller>,
         WriteStorage<'a, MountState>,
         WriteStorage<'a, Mounting>,
-        WriteStorage<'a, Pos>,
-        WriteStorage<'a, Vel>,
-        WriteStorage<'a, Ori>,
-        ReadStorage<'a, Body>,
     );
 
     const NAME: &'static str = "mount";
@@ -38,46 +33,22 @@ impl<'a> System<'a> for Sys {
             mut controllers,
             mut mount_state,
             mut mountings,
-            mut positions,
-            mut velocities,
-            mut orientations,
-            bodies,
         ): Self::SystemData,
     ) {
         // Mounted entities.
-        for (entity, mut mount_states, body) in
-            (&entities, &mut mount_state.restrict_mut(), bodies.maybe()).join()
-        {
+        for (entity, mut mount_states) in (&entities, &mut mount_state.restrict_mut()).join() {
             match mount_states.get_unchecked() {
                 MountState::Unmounted => {},
                 MountState::MountedBy(mounter_uid) => {
-                    // Note: currently controller events are not passed through since none of them
-                    // are currently relevant to controlling the mounted entity
-                    if let Some((inputs, queued_inputs, mounter)) = uid_allocator
+                    // Only movement is passed on, abilities are used by the rider from the
+                    // saddle. The rider is moved along with the mount by the physics system.
+                    if let Some(controller) = uid_allocator
                         .retrieve_entity_internal(mounter_uid.id())
-                        .and_then(|mounter| {
-                            controllers
-                                .get(mounter)
-                                .map(|c| (c.inputs.clone(), c.queued_inputs.clone(), mounter))
-                        })
+                        .and_then(|mounter| controllers.get(mounter))
+                        .map(Controller::for_mount)
                     {
-                        // TODO: consider joining on these? (remember we can use .maybe())
-                        let pos = positions.get(entity).copied();
-                        let ori = orientations.get(entity).copied();
-                        let vel = velocities.get(entity).copied();
-                        if let (Some(pos), Some(ori), Some(vel)) = (pos, ori, vel) {
-                            let mounting_offset =
-                                body.map_or(Vec3::unit_z(), Body::mounting_offset);
-                            let _ = positions.insert(mounter, Pos(pos.0 + mounting_offset));
-                            let _ = orientations.insert(mounter, ori);
-                            let _ = velocities.insert(mounter, vel);
-                        }
-                        if let Some(controller) = controllers.get_mut(entity) {
-                            *controller = Controller {
-                                inputs,
-                                queued_inputs,
-                                ..Default::default()
-                            }
+                        if let Some(mount_controller) = controllers.get_mut(entity) {
+                            *mount_controller = controller;
                         }
                     } else {
                         *(mount_states.get_mut_unchecked()) = MountState::Unmounted;
@@ -11,14 +11,16 @@ use common::{
     resources::DeltaTime,
     states,
     terrain::{Block, TerrainGrid},
-    uid::Uid,
+    uid::{Uid, UidAllocator},
     util::{Projection, SpatialGrid},
     vol::{BaseVol, ReadVol},
+    weather::WeatherGrid,
 };
 use common_base::{prof_span, span};
 use common_ecs::{Job, Origin, ParMode, Phase, PhysicsMetrics, System};
 use rayon::iter::ParallelIterator;
 use specs::{
+    saveload::{Marker, MarkerAllocator},
     shred::{ResourceId, World},
     Entities, Entity, Join, ParJoin, Read, ReadExpect, ReadStorage, SystemData, Write, WriteExpect,
     WriteStorage,
@@ -109,6 +111,7 @@ pub struct Sys;
 pub struct PhysicsRead<'a> {
     entities: Entities<'a>,
     uids: ReadStorage<'a, Uid>,
+    uid_allocator: Read<'a, UidAllocator>,
     terrain: ReadExpect<'a, TerrainGrid>,
     dt: Read<'a, DeltaTime>,
     event_bus: Read<'a, EventBus<ServerEvent>>,
@@ -125,6 +128,7 @@ pub struct PhysicsRead<'a> {
     character_states: ReadStorage<'a, CharacterState>,
     densities: ReadStorage<'a, Density>,
     stats: ReadStorage<'a, Stats>,
+    weather: Read<'a, WeatherGrid>,
 }
 
 #[derive(SystemData)]
@@ -632,6 +636,14 @@ impl<'a> PhysicsData<'a> {
 
                                     _ => None,
                                 };
+                                // Gliders are carried by the wind and lifted by thermals
+                                let fluid = if wings.is_some() {
+                                    fluid.with_weather(
+                                        &read.weather.get_interpolated(pos.0.xy()),
+                                    )
+                                } else {
+                                    fluid
+                                };
                                 vel.0 = integrate_forces(
                                     &dt,
                                     *vel,
@@ -1177,6 +1189,38 @@ impl<'a> PhysicsData<'a> {
         });
     }
 
+    /// Riders don't take part in physics themselves, they are carried to
+    /// wherever their mount ended up
+    fn move_riders(&mut self) {
+        span!(_guard, "Move riders with their mounts");
+        let PhysicsData {
+            ref read,
+            ref mut write,
+        } = self;
+
+        for (rider, Mounting(mount_uid)) in (&read.entities, &read.mountings).join() {
+            let mount = match read.uid_allocator.retrieve_entity_internal(mount_uid.id()) {
+                Some(mount) => mount,
+                None => continue,
+            };
+            if let (Some(pos), Some(ori), Some(vel)) = (
+                write.positions.get(mount).copied(),
+                write.orientations.get(mount).copied(),
+                write.velocities.get(mount).copied(),
+            ) {
+                let mounting_offset = read
+                    .bodies
+                    .get(mount)
+                    .map_or(Vec3::unit_z(), Body::mounting_offset);
+                let _ = write
+                    .positions
+                    .insert(rider, Pos(pos.0 + ori.to_quat() * mounting_offset));
+                let _ = write.orientations.insert(rider, ori);
+                let _ = write.velocities.insert(rider, vel);
+            }
+        }
+    }
+
     fn update_cached_spatial_grid(&mut self) {
         span!(_guard, "Update cached spatial grid");
         let PhysicsData {
@@ -1234,6 +1278,7 @@ impl<'a> System<'a> for Sys {
 
         let voxel_collider_spatial_grid = physics_data.construct_voxel_collider_spatial_grid();
         physics_data.handle_movement_and_terrain(job, &voxel_collider_spatial_grid);
+        physics_data.move_riders();
 
         // Spatial grid used by other systems
         physics_data.update_cached_spatial_grid();
@@ -1,9 +1,10 @@
 use common::{
-    combat::{AttackSource, AttackerInfo, TargetInfo},
+    combat::{AttackSource, AttackerInfo, HarmPolicy, PvpMode, TargetInfo},
     comp::{
         agent::{Sound, SoundKind},
-        projectile, Body, CharacterState, Combo, Energy, Group, Health, HealthSource, Inventory,
-        Ori, PhysicsState, Pos, Projectile, Stats, Vel,
+        projectile, Alignment, Body, CharacterState, Combo, Duel, Energy, Group, Health,
+        HealthSource, Inventory, Ori, PhysicsState, Player, Pos, Projectile, PvpFlag, PvpZone,
+        Stats, Vel,
     },
     event::{EventBus, ServerEvent},
     outcome::Outcome,
@@ -40,6 +41,12 @@ pub struct ReadData<'a> {
     healths: ReadStorage<'a, Health>,
     bodies: ReadStorage<'a, Body>,
     character_states: ReadStorage<'a, CharacterState>,
+    players: ReadStorage<'a, Player>,
+    alignments: ReadStorage<'a, Alignment>,
+    pvp_mode: Read<'a, PvpMode>,
+    pvp_flags: ReadStorage<'a, PvpFlag>,
+    duels: ReadStorage<'a, Duel>,
+    pvp_zones: ReadStorage<'a, PvpZone>,
 }
 
 /// This system is responsible for handling projectile effect triggers
@@ -62,6 +69,20 @@ impl<'a> System<'a> for Sys {
         (read_data, mut orientations, mut projectiles, mut outcomes): Self::SystemData,
     ) {
         let mut server_emitter = read_data.server_bus.emitter();
+
+        let harm_policy = HarmPolicy {
+            mode: *read_data.pvp_mode,
+            time: read_data.time.0,
+            players: &read_data.players,
+            pvp_flags: &read_data.pvp_flags,
+            duels: &read_data.duels,
+            pvp_zones: &read_data.pvp_zones,
+            positions: &read_data.positions,
+            uids: &read_data.uids,
+            alignments: &read_data.alignments,
+            uid_allocator: &read_data.uid_allocator,
+        };
+
         // Attacks
         'projectile_loop: for (entity, pos, physics, vel, mut projectile) in (
             &read_data.entities,
@@ -169,6 +190,7 @@ impl<'a> System<'a> for Sys {
                                         target_info,
                                         dir,
                                         false,
+                                        harm_policy.may_harm(owner_entity, target),
                                         1.0,
                                         AttackSource::Projectile,
                                         |e| server_emitter.emit(e),
@@ -224,7 +224,8 @@ impl<'a> System<'a> for Sys {
                 | CharacterState::GlideWield { .. }
                 | CharacterState::Wielding { .. }
                 | CharacterState::Equipping { .. }
-                | CharacterState::Boost { .. } => {
+                | CharacterState::Boost { .. }
+                | CharacterState::Fishing { .. } => {
                     let res = {
                         let energy = energy.get_unchecked();
                         energy.current() < energy.maximum()
@@ -1,6 +1,7 @@
 use crate::persistence::character_updater::CharacterUpdater;
 use common::comp::{
-    inventory::loadout_builder::LoadoutBuilder, Body, Inventory, Item, SkillSet, Stats,
+    inventory::loadout_builder::LoadoutBuilder, Bank, Body, Inventory, Item, LoadoutPresets,
+    QuestLog, SkillSet, Stats,
 };
 use specs::{Entity, WriteExpect};
 
@@ -54,11 +55,17 @@ pub fn create_character(
         .expect("Inventory has at least 1 slot left!");
 
     let waypoint = None;
+    let quest_log = QuestLog::default();
+    let pets = Vec::new();
+    let bank = Bank::default();
+    let presets = LoadoutPresets::default();
 
     character_updater.create_character(
         entity,
         player_uuid,
         character_alias,
-        (body, stats, skill_set, inventory, waypoint),
+        (
+            body, stats, skill_set, inventory, waypoint, quest_log, pets, bank, presets,
+        ),
     );
 }
@@ -0,0 +1,159 @@
+use crate::data_dir;
+use common::{terrain::TerrainChunk, util::GIT_HASH};
+use common_net::msg::CompressedData;
+use std::{
+    collections::hash_map::DefaultHasher,
+    fs,
+    hash::{Hash, Hasher},
+    io,
+    path::{Path, PathBuf},
+};
+use tracing::{info, warn};
+use vek::*;
+use world::sim::FileOpts;
+
+const CHUNK_CACHE_DIR: &str = "chunk_cache";
+/// Written at the start of every cached chunk and bumped whenever the way
+/// chunks are stored changes. Local builds all share the same git hash, so the
+/// fingerprint of the cache alone doesn't tell their formats apart.
+const CHUNK_CACHE_VERSION: u8 = 1;
+
+/// Compressed copies of generated chunks, stored on disk so that they don't
+/// need to be generated again when they are next loaded.
+///
+/// Each world has its own directory, named after a fingerprint of everything
+/// that decides which chunks get generated: the world seed, the world file and
+/// the version of the server. Caches of any other world are deleted on
+/// startup, so stale chunks are never served.
+pub struct ChunkCache {
+    dir: PathBuf,
+}
+
+impl ChunkCache {
+    pub fn new(data_dir: &Path, world_seed: u32, map_file: Option<&FileOpts>) -> io::Result<Self> {
+        let root = data_dir.join(CHUNK_CACHE_DIR);
+        let dir = root.join(format!("{:016x}", Self::fingerprint(world_seed, map_file)));
+
+        if let Ok(entries) = fs::read_dir(&root) {
+            for entry in entries.flatten().filter(|entry| entry.path() != dir) {
+                match fs::remove_dir_all(entry.path()) {
+                    Ok(()) => info!("Removed outdated chunk cache {}", entry.path().display()),
+                    Err(e) => warn!(
+                        ?e,
+                        "Failed to remove outdated chunk cache {}",
+                        entry.path().display()
+                    ),
+                }
+            }
+        }
+
+        fs::create_dir_all(&dir)?;
+        info!("Caching generated chunks in {}", dir.display());
+        Ok(Self { dir })
+    }
+
+    fn fingerprint(world_seed: u32, map_file: Option<&FileOpts>) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        world_seed.hash(&mut hasher);
+        GIT_HASH.hash(&mut hasher);
+        match map_file {
+            // The world file could be replaced without anything else changing
+            Some(FileOpts::Load(path) | FileOpts::LoadLegacy(path)) => match fs::read(path) {
+                Ok(bytes) => bytes.hash(&mut hasher),
+                Err(_) => path.hash(&mut hasher),
+            },
+            opts => format!("{:?}", opts).hash(&mut hasher),
+        }
+        hasher.finish()
+    }
+
+    fn chunk_path(&self, key: Vec2<i32>) -> PathBuf {
+        self.dir.join(format!("{}_{}.bin", key.x, key.y))
+    }
+
+    /// Load the chunk with the given key, if it has been cached.
+    pub fn load(&self, key: Vec2<i32>) -> Option<TerrainChunk> {
+        let path = self.chunk_path(key);
+        let bytes = fs::read(&path).ok()?;
+        let chunk = Self::decode(&bytes);
+        if chunk.is_none() {
+            warn!(?key, "Discarding corrupt cached chunk");
+            let _ = fs::remove_file(&path);
+        }
+        chunk
+    }
+
+    pub fn store(&self, key: Vec2<i32>, chunk: &TerrainChunk) {
+        // Written atomically, so that a chunk that is only partially written is
+        // never loaded
+        if let Err(e) = data_dir::write_atomically(&self.chunk_path(key), &Self::encode(chunk)) {
+            warn!(?e, ?key, "Failed to cache chunk");
+        }
+    }
+
+    fn encode(chunk: &TerrainChunk) -> Vec<u8> {
+        let mut bytes = vec![CHUNK_CACHE_VERSION];
+        bytes.extend(CompressedData::compress(chunk, 1).to_bytes());
+        bytes
+    }
+
+    /// Inverse of [`ChunkCache::encode`], or `None` if the chunk was cached in
+    /// another format or is corrupt.
+    fn decode(bytes: &[u8]) -> Option<TerrainChunk> {
+        match bytes.split_first()? {
+            (&CHUNK_CACHE_VERSION, bytes) => CompressedData::<TerrainChunk>::from_bytes(bytes)
+                .and_then(|compressed| compressed.decompress()),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::{
+        terrain::{Block, BlockKind, SpriteKind, TerrainChunkMeta},
+        vol::{ReadVol, WriteVol},
+    };
+
+    fn chunk() -> TerrainChunk {
+        let mut chunk = TerrainChunk::new(
+            10,
+            Block::new(BlockKind::Rock, Rgb::new(100, 100, 100)),
+            Block::air(SpriteKind::Empty),
+            TerrainChunkMeta::void(),
+        );
+        chunk
+            .set(
+                Vec3::new(3, 4, 12),
+                Block::new(BlockKind::Wood, Rgb::new(80, 50, 20)),
+            )
+            .unwrap();
+        chunk
+    }
+
+    #[test]
+    fn cached_chunks_round_trip() {
+        let chunk = chunk();
+        let decoded = ChunkCache::decode(&ChunkCache::encode(&chunk)).unwrap();
+        for pos in &[
+            Vec3::new(3, 4, 12),
+            Vec3::new(0, 0, 0),
+            Vec3::new(5, 5, 11),
+            Vec3::new(5, 5, 40),
+        ] {
+            assert_eq!(decoded.get(*pos).ok(), chunk.get(*pos).ok());
+        }
+        assert_eq!(decoded.get_min_z(), chunk.get_min_z());
+    }
+
+    #[test]
+    fn chunks_from_other_versions_are_rejected() {
+        let mut bytes = ChunkCache::encode(&chunk());
+        bytes[0] = CHUNK_CACHE_VERSION.wrapping_add(1);
+        assert!(ChunkCache::decode(&bytes).is_none());
+        assert!(ChunkCache::decode(&[]).is_none());
+        // Corrupt data is rejected too
+        assert!(ChunkCache::decode(&[CHUNK_CACHE_VERSION, 1, 2, 3]).is_none());
+    }
+}
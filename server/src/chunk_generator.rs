@@ -1,6 +1,6 @@
-use crate::metrics::ChunkGenMetrics;
 #[cfg(not(feature = "worldgen"))]
 use crate::test_world::{IndexOwned, World};
+use crate::{chunk_cache::ChunkCache, metrics::ChunkGenMetrics};
 use common::{
     generation::ChunkSupplement, resources::TimeOfDay, slowjob::SlowJobPool, terrain::TerrainChunk,
 };
@@ -24,16 +24,18 @@ pub struct ChunkGenerator {
     chunk_rx: crossbeam_channel::Receiver<ChunkGenResult>,
     pending_chunks: HashMap<Vec2<i32>, Arc<AtomicBool>>,
     metrics: Arc<ChunkGenMetrics>,
+    cache: Option<Arc<ChunkCache>>,
 }
 impl ChunkGenerator {
     #[allow(clippy::new_without_default)] // TODO: Pending review in #587
-    pub fn new(metrics: ChunkGenMetrics) -> Self {
+    pub fn new(metrics: ChunkGenMetrics, cache: Option<ChunkCache>) -> Self {
         let (chunk_tx, chunk_rx) = crossbeam_channel::unbounded();
         Self {
             chunk_tx,
             chunk_rx,
             pending_chunks: HashMap::new(),
             metrics: Arc::new(metrics),
+            cache: cache.map(Arc::new),
         }
     }
 
@@ -54,12 +56,26 @@ impl ChunkGenerator {
         let cancel = Arc::new(AtomicBool::new(false));
         v.insert(Arc::clone(&cancel));
         let chunk_tx = self.chunk_tx.clone();
+        let cache = self.cache.clone();
         self.metrics.chunks_requested.inc();
         slowjob_pool.spawn("CHUNK_GENERATOR", move || {
             let index = index.as_index_ref();
-            let payload = world
-                .generate_chunk(index, key, || cancel.load(Ordering::Relaxed), Some(time))
-                .map_err(|_| entity);
+            // Only the terrain is cached, the entities in it are always generated anew
+            let payload = match cache.as_ref().and_then(|cache| cache.load(key)) {
+                Some(chunk) => {
+                    let supplement = world.generate_supplement(index, key, &chunk, Some(time));
+                    Ok((chunk, supplement))
+                },
+                None => world
+                    .generate_chunk(index, key, || cancel.load(Ordering::Relaxed), Some(time))
+                    .map(|(chunk, supplement)| {
+                        if let Some(cache) = &cache {
+                            cache.store(key, &chunk);
+                        }
+                        (chunk, supplement)
+                    })
+                    .map_err(|_| entity),
+            };
             let _ = chunk_tx.send((key, payload));
         });
     }
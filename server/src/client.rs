@@ -104,9 +104,17 @@ impl Client {
                     | ServerGeneral::InviteComplete { .. }
                     | ServerGeneral::ExitInGameSuccess
                     | ServerGeneral::InventoryUpdate(_, _)
+                    | ServerGeneral::QuestUpdate(_)
+                    | ServerGeneral::CraftingUpdate(_)
+                    | ServerGeneral::PresetUpdate(_)
+                    | ServerGeneral::StorageUpdate(_)
+                    | ServerGeneral::MailUpdate(_)
+                    | ServerGeneral::GuildUpdate(_)
                     | ServerGeneral::SetViewDistance(_)
                     | ServerGeneral::SiteEconomy(_)
+                    | ServerGeneral::WeatherUpdate(_)
                     | ServerGeneral::Outcomes(_)
+                    | ServerGeneral::CombatLog(_)
                     | ServerGeneral::Knockback(_)
                     | ServerGeneral::UpdatePendingTrade(_, _, _)
                     | ServerGeneral::FinishedTrade(_) => {
@@ -175,10 +183,18 @@ impl Client {
                     | ServerGeneral::InviteComplete { .. }
                     | ServerGeneral::ExitInGameSuccess
                     | ServerGeneral::InventoryUpdate(_, _)
+                    | ServerGeneral::QuestUpdate(_)
+                    | ServerGeneral::CraftingUpdate(_)
+                    | ServerGeneral::PresetUpdate(_)
+                    | ServerGeneral::StorageUpdate(_)
+                    | ServerGeneral::MailUpdate(_)
+                    | ServerGeneral::GuildUpdate(_)
                     | ServerGeneral::SetViewDistance(_)
                     | ServerGeneral::Outcomes(_)
+                    | ServerGeneral::CombatLog(_)
                     | ServerGeneral::Knockback(_)
                     | ServerGeneral::SiteEconomy(_)
+                    | ServerGeneral::WeatherUpdate(_)
                     | ServerGeneral::UpdatePendingTrade(_, _, _)
                     | ServerGeneral::FinishedTrade(_) => {
                         PreparedMsg::new(2, &g, &self.in_game_stream_params)
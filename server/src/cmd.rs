@@ -14,7 +14,9 @@ use authc::Uuid;
 use chrono::{NaiveTime, Timelike, Utc};
 use common::{
     assets,
+    character::CharacterId,
     cmd::{ChatCommand, BUFF_PACK, BUFF_PARSER},
+    combat::PvpMode,
     comp::{
         self,
         aura::{Aura, AuraKind, AuraTarget},
@@ -27,14 +29,14 @@ use common::{
     effect::Effect,
     event::{EventBus, ServerEvent},
     npc::{self, get_npc_name},
-    resources::{PlayerPhysicsSettings, TimeOfDay},
+    resources::{PlayerPhysicsSettings, Time, TimeOfDay},
     terrain::{Block, BlockKind, SpriteKind, TerrainChunkSize},
     uid::Uid,
     vol::RectVolSize,
     Damage, DamageKind, DamageSource, Explosion, LoadoutBuilder, RadiusEffect,
 };
 use common_net::{
-    msg::{DisconnectReason, Notification, PlayerListUpdate, ServerGeneral},
+    msg::{DisconnectReason, Notification, PlayerListUpdate, PresenceKind, ServerGeneral},
     sync::WorldSyncExt,
 };
 use common_state::{BuildAreaError, BuildAreas};
@@ -47,7 +49,10 @@ use vek::*;
 use wiring::{Circuit, Wire, WiringAction, WiringActionEffect, WiringElement};
 use world::util::Sampler;
 
-use crate::{client::Client, login_provider::LoginProvider, wiring};
+use crate::{
+    client::Client, guild::GuildManager, login_provider::LoginProvider, mail::PostOffice, pet,
+    presence::Presence, wiring,
+};
 use scan_fmt::{scan_fmt, scan_fmt_some};
 use tracing::{error, info, warn};
 
@@ -120,6 +125,7 @@ fn do_command(
         ChatCommand::DebugColumn => handle_debug_column,
         ChatCommand::DisconnectAllPlayers => handle_disconnect_all_players,
         ChatCommand::DropAll => handle_drop_all,
+        ChatCommand::Duel => handle_duel,
         ChatCommand::Dummy => handle_spawn_training_dummy,
         ChatCommand::Explosion => handle_explosion,
         ChatCommand::Faction => handle_faction,
@@ -130,9 +136,21 @@ fn do_command(
         ChatCommand::GroupKick => handle_group_kick,
         ChatCommand::GroupLeave => handle_group_leave,
         ChatCommand::GroupPromote => handle_group_promote,
+        ChatCommand::Guild => handle_guild,
+        ChatCommand::GuildCreate => handle_guild_create,
+        ChatCommand::GuildDemote => handle_guild_demote,
+        ChatCommand::GuildDisband => handle_guild_disband,
+        ChatCommand::GuildInvite => handle_guild_invite,
+        ChatCommand::GuildKick => handle_guild_kick,
+        ChatCommand::GuildLeave => handle_guild_leave,
+        ChatCommand::GuildPromote => handle_guild_promote,
         ChatCommand::Health => handle_health,
         ChatCommand::Help => handle_help,
         ChatCommand::Home => handle_home,
+        ChatCommand::House => handle_house,
+        ChatCommand::HouseAbandon => handle_house_abandon,
+        ChatCommand::HouseBuy => handle_house_buy,
+        ChatCommand::HouseWaypoint => handle_house_waypoint,
         ChatCommand::JoinFaction => handle_join_faction,
         ChatCommand::Jump => handle_jump,
         ChatCommand::Kick => handle_kick,
@@ -141,12 +159,15 @@ fn do_command(
         ChatCommand::Kit => handle_kit,
         ChatCommand::Lantern => handle_lantern,
         ChatCommand::Light => handle_light,
+        ChatCommand::MailLog => handle_mail_log,
         ChatCommand::MakeBlock => handle_make_block,
         ChatCommand::MakeSprite => handle_make_sprite,
         ChatCommand::Motd => handle_motd,
         ChatCommand::Object => handle_object,
         ChatCommand::PermitBuild => handle_permit_build,
+        ChatCommand::Pet => handle_pet,
         ChatCommand::Players => handle_players,
+        ChatCommand::Pvp => handle_pvp,
         ChatCommand::Region => handle_region,
         ChatCommand::RemoveLights => handle_remove_lights,
         ChatCommand::RevokeBuild => handle_revoke_build,
@@ -167,6 +188,7 @@ fn do_command(
         ChatCommand::Version => handle_version,
         ChatCommand::Waypoint => handle_waypoint,
         ChatCommand::Wiring => handle_spawn_wiring,
+        ChatCommand::Work => handle_work,
         ChatCommand::Whitelist => handle_whitelist,
         ChatCommand::World => handle_world,
     };
@@ -712,6 +734,60 @@ fn handle_home(
     insert_or_replace_component(server, target, comp::ForceUpdate, "target")
 }
 
+fn handle_house(
+    server: &mut Server,
+    client: EcsEntity,
+    target: EcsEntity,
+    _args: String,
+    _action: &ChatCommand,
+) -> CmdResult<()> {
+    let info = crate::events::housing::house_info(server.state.ecs(), target);
+    server.notify_client(client, ServerGeneral::Notification(info));
+    Ok(())
+}
+
+fn handle_house_abandon(
+    server: &mut Server,
+    client: EcsEntity,
+    target: EcsEntity,
+    _args: String,
+    _action: &ChatCommand,
+) -> CmdResult<()> {
+    let notification = crate::events::housing::abandon_house(server.state.ecs(), target);
+    server.notify_client(client, ServerGeneral::Notification(notification));
+    Ok(())
+}
+
+fn handle_house_buy(
+    server: &mut Server,
+    _client: EcsEntity,
+    target: EcsEntity,
+    _args: String,
+    _action: &ChatCommand,
+) -> CmdResult<()> {
+    if !server.settings().housing.enabled {
+        return Err("Houses can't be bought on this server.".into());
+    }
+    talk_to_closest_npc(
+        server,
+        target,
+        comp::dialogue::Subject::House,
+        "There is nobody close enough to buy a house from.",
+    )
+}
+
+fn handle_house_waypoint(
+    server: &mut Server,
+    _client: EcsEntity,
+    target: EcsEntity,
+    _args: String,
+    _action: &ChatCommand,
+) -> CmdResult<()> {
+    let notification = crate::events::housing::set_waypoint_to_house(server.state.ecs(), target);
+    server.notify_client(client, ServerGeneral::Notification(notification));
+    Ok(())
+}
+
 fn handle_kill(
     server: &mut Server,
     client: EcsEntity,
@@ -1249,13 +1325,33 @@ fn handle_safezone(
     args: String,
     action: &ChatCommand,
 ) -> CmdResult<()> {
-    let range = scan_fmt_some!(&args, &action.arg_fmt(), f32);
+    let (range, kind) = scan_fmt_some!(&args, &action.arg_fmt(), f32, String);
     let pos = position(server, target, "target")?;
-    server.state.create_safezone(range, pos).build();
+    let msg = match kind.as_deref() {
+        None | Some("safe") => {
+            server.state.create_safezone(range, pos).build();
+            "Spawned a safe zone"
+        },
+        Some("pvp") => {
+            server
+                .state
+                .create_pvp_zone(comp::PvpZoneKind::Pvp, range, pos)
+                .build();
+            "Spawned a PvP zone"
+        },
+        Some("no_pvp") => {
+            server
+                .state
+                .create_pvp_zone(comp::PvpZoneKind::NoPvp, range, pos)
+                .build();
+            "Spawned a no-PvP zone"
+        },
+        Some(_) => return Err(action.help_string()),
+    };
 
     server.notify_client(
         client,
-        ServerGeneral::server_msg(ChatType::CommandInfo, "Spawned a safe zone"),
+        ServerGeneral::server_msg(ChatType::CommandInfo, msg),
     );
     Ok(())
 }
@@ -1365,6 +1461,128 @@ fn handle_revoke_build_all(
     Ok(())
 }
 
+fn handle_pet(
+    server: &mut Server,
+    _client: EcsEntity,
+    target: EcsEntity,
+    args: String,
+    action: &ChatCommand,
+) -> CmdResult<()> {
+    // Pets won't be sent after anything further away than this
+    const MAX_ATTACK_DIST: f32 = 32.0;
+
+    let command =
+        scan_fmt_some!(&args, &action.arg_fmt(), String).ok_or_else(|| action.help_string())?;
+    let uid = uid(server, target, "target")?;
+    let pos = position(server, target, "target")?;
+    let pets = pet::pets_of(server.state.ecs(), uid);
+    if pets.is_empty() {
+        return Err("You don't have any pets.".to_string());
+    }
+
+    let ecs = server.state.ecs();
+    let positions = ecs.read_storage::<comp::Pos>();
+    let mut pet_comps = ecs.write_storage::<comp::Pet>();
+    let mut agents = ecs.write_storage::<comp::Agent>();
+    let (stay, new_target) = match command.as_str() {
+        "follow" => (false, None),
+        "stay" => (true, None),
+        "attack" => {
+            let look_dir = ecs
+                .read_storage::<comp::Ori>()
+                .get(target)
+                .map_or_else(Vec3::unit_y, |ori| *ori.look_dir());
+            let groups = ecs.read_storage::<comp::Group>();
+            let own_group = groups.get(target);
+            let (victim, _) = (
+                &ecs.entities(),
+                &positions,
+                &ecs.read_storage::<comp::Health>(),
+            )
+                .join()
+                .filter(|(entity, victim_pos, health)| {
+                    *entity != target
+                        && !health.is_dead
+                        && !pets.contains(entity)
+                        && (own_group.is_none() || groups.get(*entity) != own_group)
+                        && victim_pos.0.distance_squared(pos.0) < MAX_ATTACK_DIST.powi(2)
+                        // Roughly where the owner is looking
+                        && (victim_pos.0 - pos.0)
+                            .try_normalized()
+                            .map_or(false, |dir| dir.dot(look_dir) > 0.9)
+                })
+                .map(|(entity, victim_pos, _)| (entity, victim_pos.0.distance_squared(pos.0)))
+                .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(Ordering::Equal))
+                .ok_or_else(|| "There is nothing for your pets to attack there.".to_string())?;
+            (false, Some(victim))
+        },
+        _ => return Err(action.help_string()),
+    };
+
+    let time = ecs.read_resource::<Time>().0;
+    for pet in pets {
+        if let Some(pet_comp) = pet_comps.get_mut(pet) {
+            pet_comp.command = match positions.get(pet) {
+                Some(pet_pos) if stay => comp::PetCommand::Stay(pet_pos.0),
+                _ => comp::PetCommand::Follow,
+            };
+        }
+        if let Some(agent) = agents.get_mut(pet) {
+            // Without a target pets go back to their owner, or to where they were
+            // told to stay
+            agent.target = new_target.map(|victim| comp::agent::Target {
+                target: victim,
+                hostile: true,
+                selected_at: time,
+            });
+        }
+    }
+    Ok(())
+}
+
+fn handle_mail_log(
+    server: &mut Server,
+    client: EcsEntity,
+    _target: EcsEntity,
+    args: String,
+    action: &ChatCommand,
+) -> CmdResult<()> {
+    let character = scan_fmt_some!(&args, &action.arg_fmt(), String);
+    let post_office = server.state.ecs().read_resource::<PostOffice>();
+    let log = post_office
+        .log(character.as_deref())
+        .map(|entry| {
+            format!(
+                "\n[{}] #{} {} {} -> {}: {}",
+                entry.time,
+                entry.mail,
+                entry.action.as_str(),
+                entry.sender_alias,
+                entry.recipient_alias,
+                if entry.attachments.is_empty() {
+                    "no attachments"
+                } else {
+                    entry.attachments.as_str()
+                },
+            )
+        })
+        .collect::<String>();
+    drop(post_office);
+
+    server.notify_client(
+        client,
+        ServerGeneral::server_msg(
+            ChatType::CommandInfo,
+            if log.is_empty() {
+                "No letters since the server started.".to_owned()
+            } else {
+                format!("Recent letters:{}", log)
+            },
+        ),
+    );
+    Ok(())
+}
+
 fn handle_players(
     server: &mut Server,
     client: EcsEntity,
@@ -1393,6 +1611,88 @@ fn handle_players(
     Ok(())
 }
 
+fn handle_pvp(
+    server: &mut Server,
+    client: EcsEntity,
+    target: EcsEntity,
+    args: String,
+    action: &ChatCommand,
+) -> CmdResult<()> {
+    match *server.state.ecs().read_resource::<PvpMode>() {
+        PvpMode::Pve => return Err("PvP is disabled on this server.".into()),
+        PvpMode::Pvp => return Err("PvP is enabled for everyone on this server.".into()),
+        PvpMode::OptIn => {},
+    }
+    let time = server.state.ecs().read_resource::<Time>().0;
+    let mut pvp_flags = server.state.ecs().write_storage::<comp::PvpFlag>();
+    let flag = pvp_flags.get(target).copied();
+    let enabled = scan_fmt_some!(&args, &action.arg_fmt(), bool)
+        .unwrap_or_else(|| !flag.map_or(false, |f| f.enabled));
+
+    if flag.map_or(!enabled, |f| f.enabled == enabled) {
+        return Err(format!(
+            "PvP is already {}.",
+            if enabled { "enabled" } else { "disabled" }
+        ));
+    }
+    if let Some(flag) = flag.filter(|f| !f.can_toggle(time)) {
+        return Err(format!(
+            "You can change your PvP setting again in {:.0} seconds.",
+            flag.cooldown_left(time).ceil()
+        ));
+    }
+    pvp_flags
+        .insert(target, comp::PvpFlag {
+            enabled,
+            changed_at: time,
+        })
+        .map_err(|_| "Cannot change the PvP setting of the target.".to_string())?;
+    drop(pvp_flags);
+
+    server.notify_client(
+        client,
+        ServerGeneral::server_msg(
+            ChatType::CommandInfo,
+            if enabled {
+                "PvP enabled. Other players with PvP enabled can now attack you."
+            } else {
+                "PvP disabled."
+            },
+        ),
+    );
+    Ok(())
+}
+
+fn handle_duel(
+    server: &mut Server,
+    client: EcsEntity,
+    target: EcsEntity,
+    args: String,
+    action: &ChatCommand,
+) -> CmdResult<()> {
+    if let Some(target_alias) = scan_fmt_some!(&args, &action.arg_fmt(), String) {
+        let target_player = find_alias(server.state.ecs(), &target_alias)?.0;
+        let uid = uid(server, target_player, "player")?;
+
+        // Whether both players may duel is checked when handling the invite
+        server
+            .state
+            .mut_resource::<EventBus<ServerEvent>>()
+            .emit_now(ServerEvent::InitiateInvite(target, uid, InviteKind::Duel));
+
+        server.notify_client(
+            client,
+            ServerGeneral::server_msg(
+                ChatType::CommandInfo,
+                format!("Challenged {} to a duel.", target_alias),
+            ),
+        );
+        Ok(())
+    } else {
+        Err(action.help_string())
+    }
+}
+
 fn handle_build(
     server: &mut Server,
     client: EcsEntity,
@@ -2332,6 +2632,174 @@ fn handle_group_promote(
     }
 }
 
+fn handle_guild(
+    server: &mut Server,
+    client: EcsEntity,
+    target: EcsEntity,
+    msg: String,
+    _action: &ChatCommand,
+) -> CmdResult<()> {
+    no_sudo(client, target)?;
+
+    let guilds = server.state.ecs().read_storage::<comp::Guild>();
+    if let Some(comp::Guild(guild)) = guilds.get(target) {
+        let mode = comp::ChatMode::Guild(*guild);
+        drop(guilds);
+        insert_or_replace_component(server, target, mode.clone(), "target")?;
+        if !msg.is_empty() {
+            if let Some(uid) = server.state.ecs().read_storage().get(target) {
+                server.state.send_chat(mode.new_message(*uid, msg));
+            }
+        }
+        server.notify_client(target, ServerGeneral::ChatMode(mode));
+        Ok(())
+    } else {
+        Err("Please join or found a guild with /guild_create first".into())
+    }
+}
+
+fn handle_guild_create(
+    server: &mut Server,
+    _client: EcsEntity,
+    target: EcsEntity,
+    args: String,
+    action: &ChatCommand,
+) -> CmdResult<()> {
+    if let (Some(tag), Some(name)) = scan_fmt_some!(&args, &action.arg_fmt(), String, String) {
+        emit_guild_manip(server, target, comp::GuildManip::Create { name, tag });
+        Ok(())
+    } else {
+        Err(action.help_string())
+    }
+}
+
+fn handle_guild_invite(
+    server: &mut Server,
+    client: EcsEntity,
+    target: EcsEntity,
+    args: String,
+    action: &ChatCommand,
+) -> CmdResult<()> {
+    if let Some(target_alias) = scan_fmt_some!(&args, &action.arg_fmt(), String) {
+        let target_player = find_alias(server.state.ecs(), &target_alias)?.0;
+        let uid = uid(server, target_player, "player")?;
+
+        // Whether the target may invite is checked when handling the invite
+        server
+            .state
+            .mut_resource::<EventBus<ServerEvent>>()
+            .emit_now(ServerEvent::InitiateInvite(target, uid, InviteKind::Guild));
+
+        server.notify_client(
+            client,
+            ServerGeneral::server_msg(
+                ChatType::CommandInfo,
+                format!("Invited {} to the guild.", target_alias),
+            ),
+        );
+        Ok(())
+    } else {
+        Err(action.help_string())
+    }
+}
+
+fn handle_guild_kick(
+    server: &mut Server,
+    _client: EcsEntity,
+    target: EcsEntity,
+    args: String,
+    action: &ChatCommand,
+) -> CmdResult<()> {
+    let member = find_guild_member(server, target, &args, action)?;
+    emit_guild_manip(server, target, comp::GuildManip::Kick(member));
+    Ok(())
+}
+
+fn handle_guild_promote(
+    server: &mut Server,
+    _client: EcsEntity,
+    target: EcsEntity,
+    args: String,
+    action: &ChatCommand,
+) -> CmdResult<()> {
+    let member = find_guild_member(server, target, &args, action)?;
+    emit_guild_manip(server, target, comp::GuildManip::Promote(member));
+    Ok(())
+}
+
+fn handle_guild_demote(
+    server: &mut Server,
+    _client: EcsEntity,
+    target: EcsEntity,
+    args: String,
+    action: &ChatCommand,
+) -> CmdResult<()> {
+    let member = find_guild_member(server, target, &args, action)?;
+    emit_guild_manip(server, target, comp::GuildManip::Demote(member));
+    Ok(())
+}
+
+fn handle_guild_leave(
+    server: &mut Server,
+    _client: EcsEntity,
+    target: EcsEntity,
+    _args: String,
+    _action: &ChatCommand,
+) -> CmdResult<()> {
+    emit_guild_manip(server, target, comp::GuildManip::Leave);
+    Ok(())
+}
+
+fn handle_guild_disband(
+    server: &mut Server,
+    _client: EcsEntity,
+    target: EcsEntity,
+    _args: String,
+    _action: &ChatCommand,
+) -> CmdResult<()> {
+    emit_guild_manip(server, target, comp::GuildManip::Disband);
+    Ok(())
+}
+
+fn emit_guild_manip(server: &mut Server, target: EcsEntity, manip: comp::GuildManip) {
+    server
+        .state
+        .mut_resource::<EventBus<ServerEvent>>()
+        .emit_now(ServerEvent::GuildManip(target, manip));
+}
+
+/// Finds the member of the target's guild with the given character name.
+/// Members can be offline, so they are looked up by character rather than by
+/// player.
+fn find_guild_member(
+    server: &Server,
+    target: EcsEntity,
+    args: &str,
+    action: &ChatCommand,
+) -> CmdResult<CharacterId> {
+    let name =
+        scan_fmt_some!(args, &action.arg_fmt(), String).ok_or_else(|| action.help_string())?;
+    let ecs = server.state.ecs();
+    let character_id = match ecs.read_storage::<Presence>().get(target).map(|p| p.kind) {
+        Some(PresenceKind::Character(character_id)) => character_id,
+        _ => return Err("You must be playing a character to manage a guild.".into()),
+    };
+    let guild_manager = ecs.read_resource::<GuildManager>();
+    let post_office = ecs.read_resource::<PostOffice>();
+    let (_, data) = guild_manager
+        .guild_of(character_id)
+        .ok_or("You are not in a guild.")?;
+    data.members
+        .keys()
+        .copied()
+        .find(|member| {
+            post_office
+                .character_alias(*member)
+                .map_or(false, |alias| alias.eq_ignore_ascii_case(&name))
+        })
+        .ok_or_else(|| format!("There is no character called {} in your guild.", name))
+}
+
 fn handle_region(
     server: &mut Server,
     client: EcsEntity,
@@ -2372,6 +2840,54 @@ fn handle_say(
     Ok(())
 }
 
+fn handle_work(
+    server: &mut Server,
+    _client: EcsEntity,
+    target: EcsEntity,
+    _args: String,
+    _action: &ChatCommand,
+) -> CmdResult<()> {
+    talk_to_closest_npc(
+        server,
+        target,
+        comp::dialogue::Subject::Work,
+        "There is nobody close enough to ask for work.",
+    )
+}
+
+/// Asks the closest NPC that can speak about something
+fn talk_to_closest_npc(
+    server: &mut Server,
+    target: EcsEntity,
+    subject: comp::dialogue::Subject,
+    nobody_close: &str,
+) -> CmdResult<()> {
+    // NPCs further away than this won't hear the question
+    const MAX_TALK_DIST: f32 = 10.0;
+
+    let pos = position(server, target, "target")?;
+    let uid = uid(server, target, "target")?;
+    let ecs = server.state.ecs();
+    let positions = ecs.read_storage::<comp::Pos>();
+    let alignments = ecs.read_storage::<comp::Alignment>();
+    let mut agents = ecs.write_storage::<comp::Agent>();
+    let (_, agent) = (&ecs.entities(), &positions, &alignments, &mut agents)
+        .join()
+        .filter(|(entity, npc_pos, alignment, agent)| {
+            *entity != target
+                && matches!(alignment, comp::Alignment::Npc)
+                && agent.behavior.can(comp::BehaviorCapability::SPEAK)
+                && npc_pos.0.distance_squared(pos.0) < MAX_TALK_DIST.powi(2)
+        })
+        .map(|(_, npc_pos, _, agent)| (npc_pos.0.distance_squared(pos.0), agent))
+        .min_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap_or(Ordering::Equal))
+        .ok_or_else(|| nobody_close.to_string())?;
+    agent
+        .inbox
+        .push_back(comp::agent::AgentEvent::Talk(uid, subject));
+    Ok(())
+}
+
 fn handle_world(
     server: &mut Server,
     client: EcsEntity,
@@ -2595,6 +3111,12 @@ fn handle_skill_point(
             .write_storage::<comp::SkillSet>()
             .get_mut(player)
         {
+            // Data skill groups are unlocked once they first gain experience
+            if matches!(skill_tree, comp::skills::SkillGroupKind::Data(_))
+                && !skill_set.contains_skill_group(skill_tree)
+            {
+                skill_set.unlock_skill_group(skill_tree);
+            }
             skill_set.add_skill_points(skill_tree, sp);
             Ok(())
         } else {
@@ -2606,7 +3128,10 @@ fn handle_skill_point(
 }
 
 fn parse_skill_tree(skill_tree: &str) -> CmdResult<comp::skills::SkillGroupKind> {
-    use comp::{item::tool::ToolKind, skills::SkillGroupKind};
+    use comp::{item::tool::ToolKind, skill_tree::DataSkillGroup, skills::SkillGroupKind};
+    if let Some(group) = DataSkillGroup::from_id(skill_tree) {
+        return Ok(SkillGroupKind::Data(group));
+    }
     match skill_tree {
         "general" => Ok(SkillGroupKind::General),
         "sword" => Ok(SkillGroupKind::Weapon(ToolKind::Sword)),
@@ -1,4 +1,7 @@
-use std::path::{Path, PathBuf};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
 
 /// Used so that different server frontends can share the same server saves,
 /// etc.
@@ -11,3 +14,15 @@ pub struct DataDir {
 impl AsRef<Path> for DataDir {
     fn as_ref(&self) -> &Path { &self.path }
 }
+
+/// Writes a file in the data dir, creating the directories it is in if needed.
+/// The contents are written to a temporary file first, so that a crash while
+/// writing never leaves a partially written file behind.
+pub fn write_atomically(path: &Path, contents: &[u8]) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let tmp_path = path.with_extension("tmp");
+    fs::write(&tmp_path, contents)?;
+    fs::rename(&tmp_path, path)
+}
@@ -0,0 +1,253 @@
+use hashbrown::HashMap;
+use specs::{world::WorldExt, Builder, Entity as EcsEntity, World};
+use std::sync::Arc;
+use tracing::warn;
+use vek::*;
+
+use common::{
+    assets::AssetExt,
+    comp::{
+        self,
+        inventory::slot::{EquipSlot, Slot},
+        item::{ItemDef, ItemDesc},
+        preset::{COMBAT_COOLDOWN, MAX_PRESETS, MAX_PRESET_NAME_LENGTH},
+        skills::SkillGroupKind,
+        BuildManip, ChatType, Inventory, Item, LoadoutPreset, LoadoutPresets, SkillSet,
+    },
+    trade::Trades,
+};
+use common_net::{msg::ServerGeneral, sync::WorldSyncExt};
+
+use crate::{client::Client, state_ext::StateExt, Server};
+
+pub fn handle_build_manip(server: &mut Server, entity: EcsEntity, manip: BuildManip) {
+    let ecs = server.state.ecs();
+    let respec_cost = server.settings().respec_cost.clone();
+
+    let result = match manip {
+        BuildManip::Respec(skill_group_kind) => {
+            respec(ecs, entity, skill_group_kind, respec_cost.as_ref())
+        },
+        BuildManip::SavePreset(name) => save_preset(ecs, entity, name),
+        BuildManip::DeletePreset(name) => {
+            if let Some(presets) = ecs.write_storage::<LoadoutPresets>().get_mut(entity) {
+                if presets.remove(&name) {
+                    send_presets(ecs, entity, presets);
+                }
+            }
+            Ok(Vec::new())
+        },
+        BuildManip::ApplyPreset(name) => apply_preset(ecs, entity, &name, respec_cost.as_ref()),
+    };
+
+    match result {
+        Ok(leftovers) => drop_items(server, entity, leftovers),
+        Err(error) => inform(ecs, entity, error),
+    }
+}
+
+/// Refunds all the skills of a skill group, after taking the respec cost
+fn respec(
+    ecs: &World,
+    entity: EcsEntity,
+    skill_group_kind: SkillGroupKind,
+    respec_cost: Option<&(String, u32)>,
+) -> Result<Vec<Item>, String> {
+    let mut skill_sets = ecs.write_storage::<SkillSet>();
+    let can_respec = skill_sets.get(entity).map_or(false, |skill_set| {
+        skill_set.can_respec_skill_group(skill_group_kind)
+    });
+    if !can_respec {
+        return Err("There are no skills in that tree to refund.".to_owned());
+    }
+
+    pay_respec_cost(ecs, entity, respec_cost)?;
+    if let Some(mut skill_set) = skill_sets.get_mut(entity) {
+        skill_set.respec_skill_group(skill_group_kind);
+    }
+    Ok(Vec::new())
+}
+
+fn save_preset(ecs: &World, entity: EcsEntity, name: String) -> Result<Vec<Item>, String> {
+    let name = name.trim().to_owned();
+    if name.is_empty() || name.chars().count() > MAX_PRESET_NAME_LENGTH {
+        return Err(format!(
+            "Preset names must be between 1 and {} characters long.",
+            MAX_PRESET_NAME_LENGTH
+        ));
+    }
+
+    let skill_sets = ecs.read_storage::<SkillSet>();
+    let inventories = ecs.read_storage::<Inventory>();
+    let mut all_presets = ecs.write_storage::<LoadoutPresets>();
+    if let (Some(skill_set), Some(inventory), Some(presets)) = (
+        skill_sets.get(entity),
+        inventories.get(entity),
+        all_presets.get_mut(entity),
+    ) {
+        presets
+            .save(LoadoutPreset::new(name, skill_set, inventory))
+            .map_err(|_| format!("You can't keep more than {} presets.", MAX_PRESETS))?;
+        send_presets(ecs, entity, presets);
+    }
+    Ok(Vec::new())
+}
+
+/// Switches to the skills of a preset and equips the items of it that are in
+/// the inventory. Returns the items that had to be taken off but didn't fit in
+/// the inventory.
+fn apply_preset(
+    ecs: &World,
+    entity: EcsEntity,
+    name: &str,
+    respec_cost: Option<&(String, u32)>,
+) -> Result<Vec<Item>, String> {
+    let preset = match ecs
+        .read_storage::<LoadoutPresets>()
+        .get(entity)
+        .and_then(|presets| presets.get(name))
+    {
+        Some(preset) => preset.clone(),
+        None => return Ok(Vec::new()),
+    };
+
+    if in_combat(ecs, entity) {
+        return Err("You can't switch presets while in combat.".to_owned());
+    }
+    if let Some(uid) = ecs.uid_from_entity(entity) {
+        // Swapping equipment can mutate a trade the player is part of
+        if ecs.read_resource::<Trades>().in_immutable_trade(&uid) {
+            return Err("You can't switch presets while trading.".to_owned());
+        }
+    }
+
+    let mut skill_sets = ecs.write_storage::<SkillSet>();
+    let skills_differ = skill_sets.get(entity).map_or(false, |skill_set| {
+        skill_set.skills != preset.skills.iter().copied().collect::<HashMap<_, _>>()
+    });
+    // Changing skills refunds them first, so it costs as much as a respec
+    if skills_differ {
+        pay_respec_cost(ecs, entity, respec_cost)?;
+        if let Some(mut skill_set) = skill_sets.get_mut(entity) {
+            skill_set.apply_skills(&preset.skills);
+        }
+    }
+
+    let leftovers = ecs
+        .write_storage::<Inventory>()
+        .get_mut(entity)
+        .map(|inventory| equip(inventory, &preset.equipment))
+        .unwrap_or_default();
+    let _ = ecs.write_storage().insert(
+        entity,
+        comp::InventoryUpdate::new(comp::InventoryUpdateEvent::Swapped),
+    );
+    if let Some(uid) = ecs.uid_from_entity(entity) {
+        let mut trades = ecs.write_resource::<Trades>();
+        if trades.in_mutable_trade(&uid) {
+            trades.implicit_mutation_occurred(&uid);
+        }
+    }
+    Ok(leftovers)
+}
+
+/// Equips the items of a preset that can be found in the inventory
+fn equip(inventory: &mut Inventory, equipment: &[(EquipSlot, String)]) -> Vec<Item> {
+    let mut leftovers = Vec::new();
+    for (equip_slot, item_definition_id) in equipment {
+        if inventory.equipped(*equip_slot).map_or(false, |item| {
+            item.item_definition_id() == item_definition_id
+        }) {
+            continue;
+        }
+        let inv_slot = inventory.slots_with_id().find_map(|(inv_slot, slot)| {
+            slot.as_ref()
+                .filter(|item| item.item_definition_id() == item_definition_id)
+                .map(|_| inv_slot)
+        });
+        if let Some(inv_slot) = inv_slot {
+            if inventory.free_after_swap(*equip_slot, inv_slot) >= 0 {
+                leftovers
+                    .extend(inventory.swap(Slot::Inventory(inv_slot), Slot::Equip(*equip_slot)));
+            }
+        }
+    }
+    leftovers
+}
+
+/// Whether a character was hurt recently or is attacking
+fn in_combat(ecs: &World, entity: EcsEntity) -> bool {
+    let hurt = ecs
+        .read_storage::<comp::Health>()
+        .get(entity)
+        .map_or(false, |health| {
+            health.last_change.0 < COMBAT_COOLDOWN && health.last_change.1.amount < 0
+        });
+    let attacking = ecs
+        .read_storage::<comp::CharacterState>()
+        .get(entity)
+        .map_or(false, |character_state| character_state.is_attack());
+    hurt || attacking
+}
+
+fn pay_respec_cost(
+    ecs: &World,
+    entity: EcsEntity,
+    respec_cost: Option<&(String, u32)>,
+) -> Result<(), String> {
+    let (item, amount) = match respec_cost {
+        Some(cost) => cost,
+        None => return Ok(()),
+    };
+    let item_def = Arc::<ItemDef>::load_cloned(item).map_err(|e| {
+        warn!(?e, ?item, "Respec cost is not a valid item");
+        "Refunding skills is not possible on this server.".to_owned()
+    })?;
+    let paid = ecs
+        .write_storage::<Inventory>()
+        .get_mut(entity)
+        .map_or(false, |inventory| {
+            inventory.remove_item_amount(&item_def, *amount)
+        });
+    if paid {
+        let _ = ecs.write_storage().insert(
+            entity,
+            comp::InventoryUpdate::new(comp::InventoryUpdateEvent::Used),
+        );
+        Ok(())
+    } else {
+        Err(format!(
+            "Refunding skills costs {} {}.",
+            amount,
+            item_def.name()
+        ))
+    }
+}
+
+fn drop_items(server: &mut Server, entity: EcsEntity, items: Vec<Item>) {
+    let pos = match server.state.read_component_copied::<comp::Pos>(entity) {
+        Some(pos) => pos,
+        None => return,
+    };
+    for item in items {
+        server
+            .state
+            .create_object(Default::default(), comp::object::Body::Pouch)
+            .with(comp::Pos(pos.0 + Vec3::unit_z()))
+            .with(item)
+            .with(comp::Vel(Vec3::zero()))
+            .build();
+    }
+}
+
+fn send_presets(ecs: &World, entity: EcsEntity, presets: &LoadoutPresets) {
+    if let Some(client) = ecs.read_storage::<Client>().get(entity) {
+        client.send_fallible(ServerGeneral::PresetUpdate(presets.clone()));
+    }
+}
+
+fn inform(ecs: &World, entity: EcsEntity, message: String) {
+    if let Some(client) = ecs.read_storage::<Client>().get(entity) {
+        client.send_fallible(ServerGeneral::server_msg(ChatType::Meta, message));
+    }
+}
@@ -0,0 +1,52 @@
+use crate::Server;
+use common::{
+    combat_log::{CombatLogEntry, CombatLogKind},
+    comp::CharacterState,
+    resources::Time,
+    states::utils::AbilityInfo,
+    uid::Uid,
+};
+use common_net::sync::WorldSyncExt;
+use specs::{Entity as EcsEntity, World, WorldExt};
+
+/// Logs an attack, along with the ability the attacker was using when it landed
+pub fn handle_combat_log(
+    server: &Server,
+    source: Option<Uid>,
+    target: EcsEntity,
+    kind: CombatLogKind,
+) {
+    let ecs = server.state.ecs();
+    let ability = source
+        .and_then(|source| ecs.entity_from_uid(source.into()))
+        .and_then(|source| {
+            ecs.read_storage::<CharacterState>()
+                .get(source)
+                .and_then(CharacterState::ability_info)
+        });
+    log(ecs, source, target, ability, kind);
+}
+
+/// Adds an entry to the combat log, which is sent to the players who asked for
+/// it at the end of the tick
+pub fn log(
+    ecs: &World,
+    source: Option<Uid>,
+    target: EcsEntity,
+    ability: Option<AbilityInfo>,
+    kind: CombatLogKind,
+) {
+    let target = match ecs.read_storage::<Uid>().get(target) {
+        Some(uid) => *uid,
+        None => return,
+    };
+    let time = ecs.read_resource::<Time>().0;
+    ecs.write_resource::<Vec<CombatLogEntry>>()
+        .push(CombatLogEntry {
+            time,
+            source,
+            target,
+            ability,
+            kind,
+        });
+}
@@ -0,0 +1,99 @@
+use crate::client::Client;
+use common::{
+    comp::{pvp::DUEL_DURATION, ChatType, Duel, Player},
+    resources::Time,
+    uid::Uid,
+};
+use common_net::{msg::ServerGeneral, sync::WorldSyncExt};
+use specs::{Entity as EcsEntity, World, WorldExt};
+
+/// Whether `challenger` may challenge `opponent` to a duel, informing the
+/// challenger if they may not
+pub fn can_challenge(ecs: &World, challenger: EcsEntity, opponent: EcsEntity) -> bool {
+    let players = ecs.read_storage::<Player>();
+    if !players.contains(challenger) || !players.contains(opponent) {
+        inform(ecs, challenger, "Only players can duel.".to_owned());
+        return false;
+    }
+
+    let time = ecs.read_resource::<Time>().0;
+    let duels = ecs.read_storage::<Duel>();
+    let in_duel = |entity| duels.get(entity).map_or(false, |d| d.is_active(time));
+    if in_duel(challenger) {
+        inform(ecs, challenger, "You are already in a duel.".to_owned());
+        false
+    } else if in_duel(opponent) {
+        inform(
+            ecs,
+            challenger,
+            format!("{} is already in a duel.", name_of(ecs, opponent)),
+        );
+        false
+    } else {
+        true
+    }
+}
+
+/// Starts a duel after `opponent` accepted the challenge of `challenger`
+pub fn start_duel(ecs: &World, challenger: EcsEntity, opponent: EcsEntity) {
+    let uids = ecs.read_storage::<Uid>();
+    let (challenger_uid, opponent_uid) = match (uids.get(challenger), uids.get(opponent)) {
+        (Some(challenger_uid), Some(opponent_uid)) => (*challenger_uid, *opponent_uid),
+        _ => return,
+    };
+    let ends_at = ecs.read_resource::<Time>().0 + DUEL_DURATION;
+
+    let mut duels = ecs.write_storage::<Duel>();
+    let _ = duels.insert(challenger, Duel {
+        opponent: opponent_uid,
+        ends_at,
+    });
+    let _ = duels.insert(opponent, Duel {
+        opponent: challenger_uid,
+        ends_at,
+    });
+    drop(duels);
+
+    let message = format!(
+        "{} and {} started a duel. It ends when one of them is defeated, or after {} minutes.",
+        name_of(ecs, challenger),
+        name_of(ecs, opponent),
+        (DUEL_DURATION / 60.0).round(),
+    );
+    inform(ecs, challenger, message.clone());
+    inform(ecs, opponent, message);
+}
+
+/// Ends the duel of an entity that was defeated, if it was in one
+pub fn end_duel(ecs: &World, loser: EcsEntity) {
+    let time = ecs.read_resource::<Time>().0;
+    let duel = match ecs.write_storage::<Duel>().remove(loser) {
+        Some(duel) if duel.is_active(time) => duel,
+        _ => return,
+    };
+    let winner = match ecs.entity_from_uid(duel.opponent.into()) {
+        Some(winner) => winner,
+        None => return,
+    };
+    ecs.write_storage::<Duel>().remove(winner);
+
+    let message = format!(
+        "{} won the duel against {}.",
+        name_of(ecs, winner),
+        name_of(ecs, loser)
+    );
+    inform(ecs, winner, message.clone());
+    inform(ecs, loser, message);
+}
+
+fn name_of(ecs: &World, entity: EcsEntity) -> String {
+    ecs.read_storage::<Player>()
+        .get(entity)
+        .map_or_else(|| "Someone".to_owned(), |p| p.alias.clone())
+}
+
+fn inform(ecs: &World, entity: EcsEntity, message: String) {
+    if let Some(client) = ecs.read_storage::<Client>().get(entity) {
+        client.send_fallible(ServerGeneral::server_msg(ChatType::Meta, message));
+    }
+}
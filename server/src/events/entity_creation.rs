@@ -1,4 +1,4 @@
-use crate::{client::Client, sys, Server, StateExt};
+use crate::{client::Client, storage::StorageContainers, sys, Server, StateExt};
 use common::{
     character::CharacterId,
     comp::{
@@ -18,7 +18,7 @@ use common::{
     util::Dir,
 };
 use common_net::{msg::ServerGeneral, sync::WorldSyncExt};
-use specs::{Builder, Entity as EcsEntity, WorldExt};
+use specs::{Builder, Entity as EcsEntity, Join, WorldExt};
 use std::time::Duration;
 use vek::{Rgb, Vec3};
 
@@ -39,12 +39,19 @@ pub fn handle_loaded_character_data(
         comp::SkillSet,
         comp::Inventory,
         Option<comp::Waypoint>,
+        comp::QuestLog,
+        Vec<comp::pet::PetPersistenceData>,
+        comp::Bank,
+        comp::LoadoutPresets,
     ),
 ) {
     server
         .state
         .update_character_data(entity, loaded_components);
     sys::subscription::initialize_region_subscription(server.state.ecs(), entity);
+    super::mail::deliver_mail(server.state.ecs(), entity);
+    super::guild::load_guild(server.state.ecs(), entity);
+    super::housing::load_house(server.state.ecs(), entity);
 }
 
 #[allow(clippy::too_many_arguments)] // TODO: Pending review in #587
@@ -286,3 +293,31 @@ pub fn handle_create_waypoint(server: &mut Server, pos: Vec3<f32>) {
         ]))
         .build();
 }
+
+pub fn handle_create_storage(server: &mut Server, pos: Vec3<i32>) {
+    // The chest may still be around from the last time its chunk was loaded
+    if server
+        .state
+        .ecs()
+        .read_storage::<comp::Storage>()
+        .join()
+        .any(|storage| storage.pos == pos)
+    {
+        return;
+    }
+
+    server
+        .state
+        .ecs()
+        .write_resource::<StorageContainers>()
+        .create(pos);
+    server
+        .state
+        .create_object(
+            Pos(pos.map(|e| e as f32) + Vec3::new(0.5, 0.5, 0.0)),
+            comp::object::Body::ChestLight,
+        )
+        .with(comp::Storage { pos })
+        .with(comp::Mass(10_f32.powi(10)))
+        .build();
+}
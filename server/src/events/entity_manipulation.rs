@@ -3,20 +3,25 @@ use crate::{
     comp::{
         agent::{Agent, AgentEvent, Sound, SoundKind},
         biped_large, bird_large, quadruped_low, quadruped_medium, quadruped_small,
+        skill_tree::ExperienceSource,
         skills::SkillGroupKind,
         theropod, PhysicsState,
     },
+    events::{combat_log, duel, quest::count_quest_kill},
     rtsim::RtSim,
     Server, SpawnPoint, StateExt,
 };
 use common::{
     assets::AssetExt,
     combat,
+    combat_log::CombatLogKind,
     comp::{
         self, aura, buff,
         chat::{KillSource, KillType},
-        inventory::item::MaterialStatManifest,
-        object, Alignment, Body, CharacterState, Energy, EnergyChange, Group, Health, HealthChange,
+        inventory::item::{ItemDesc, MaterialStatManifest},
+        object,
+        slot::EquipSlot,
+        Alignment, Body, CharacterState, Energy, EnergyChange, Group, Health, HealthChange,
         HealthSource, Inventory, Player, Poise, PoiseChange, PoiseSource, Pos, SkillSet, Stats,
     },
     event::{EventBus, ServerEvent},
@@ -30,7 +35,10 @@ use common::{
     vol::ReadVol,
     Damage, DamageKind, DamageSource, Explosion, GroupTarget, RadiusEffect,
 };
-use common_net::{msg::ServerGeneral, sync::WorldSyncExt};
+use common_net::{
+    msg::{Notification, ServerGeneral},
+    sync::WorldSyncExt,
+};
 use common_state::BlockChange;
 use comp::chat::GenericChatMsg;
 use hashbrown::HashSet;
@@ -60,6 +68,27 @@ pub fn handle_damage(server: &Server, entity: EcsEntity, change: HealthChange) {
     if let Some(mut health) = ecs.write_storage::<Health>().get_mut(entity) {
         health.change_by(change);
     }
+    // Attacks are logged where they land, since only they know about crits and
+    // mitigation
+    let logged = match change.cause {
+        HealthSource::Heal { by } => Some((by, CombatLogKind::Heal {
+            amount: change.amount.max(0) as u32,
+        })),
+        HealthSource::Damage {
+            kind: DamageSource::Buff(buff),
+            by,
+        } => Some((by, CombatLogKind::Damage {
+            source: DamageSource::Buff(buff),
+            kind: None,
+            crit: false,
+            amount: (-change.amount).max(0) as u32,
+            mitigated: 0,
+        })),
+        _ => None,
+    };
+    if let Some((by, kind)) = logged.filter(|_| change.amount != 0) {
+        combat_log::log(ecs, by, entity, None, kind);
+    }
     // This if statement filters out anything under 5 damage, for DOT ticks
     // TODO: Find a better way to separate direct damage from DOT here
     if change.amount < -50 {
@@ -188,6 +217,8 @@ pub fn handle_destroy(server: &mut Server, entity: EcsEntity, cause: HealthSourc
         }
     }
 
+    duel::end_duel(state.ecs(), entity);
+
     // Give EXP to the killer if entity had stats
     (|| {
         let mut skill_set = state.ecs().write_storage::<SkillSet>();
@@ -309,6 +340,15 @@ pub fn handle_destroy(server: &mut Server, entity: EcsEntity, cause: HealthSourc
         }
     })();
 
+    // Count the kill towards the quests of the killer
+    if let HealthSource::Damage { by: Some(by), .. } = cause {
+        let killer = state.ecs().entity_from_uid(by.into());
+        let body = state.ecs().read_storage::<Body>().get(entity).copied();
+        if let (Some(killer), Some(body)) = (killer, body) {
+            count_quest_kill(state, killer, &body);
+        }
+    }
+
     let should_delete = if state
         .ecs()
         .write_storage::<Client>()
@@ -783,6 +823,26 @@ pub fn handle_explosion(server: &Server, pos: Vec3<f32>, explosion: Explosion, o
                 let energies = &ecs.read_storage::<comp::Energy>();
                 let combos = &ecs.read_storage::<comp::Combo>();
                 let inventories = &ecs.read_storage::<comp::Inventory>();
+                let players = &ecs.read_storage::<comp::Player>();
+                let pvp_flags = &ecs.read_storage::<comp::PvpFlag>();
+                let duels = &ecs.read_storage::<comp::Duel>();
+                let pvp_zones = &ecs.read_storage::<comp::PvpZone>();
+                let positions = &ecs.read_storage::<comp::Pos>();
+                let uids = &ecs.read_storage::<Uid>();
+                let alignments = &ecs.read_storage::<Alignment>();
+                let uid_allocator = &ecs.read_resource::<UidAllocator>();
+                let harm_policy = combat::HarmPolicy {
+                    mode: *ecs.read_resource::<combat::PvpMode>(),
+                    time: ecs.read_resource::<Time>().0,
+                    players,
+                    pvp_flags,
+                    duels,
+                    pvp_zones,
+                    positions,
+                    uids,
+                    alignments,
+                    uid_allocator,
+                };
                 for (
                     entity_b,
                     pos_b,
@@ -790,14 +850,14 @@ pub fn handle_explosion(server: &Server, pos: Vec3<f32>, explosion: Explosion, o
                     (body_b_maybe, stats_b_maybe, ori_b_maybe, char_state_b_maybe, uid_b),
                 ) in (
                     &ecs.entities(),
-                    &ecs.read_storage::<comp::Pos>(),
+                    positions,
                     &ecs.read_storage::<comp::Health>(),
                     (
                         ecs.read_storage::<comp::Body>().maybe(),
                         ecs.read_storage::<comp::Stats>().maybe(),
                         ecs.read_storage::<comp::Ori>().maybe(),
                         ecs.read_storage::<comp::CharacterState>().maybe(),
-                        &ecs.read_storage::<Uid>(),
+                        uids,
                     ),
                 )
                     .join()
@@ -857,6 +917,7 @@ pub fn handle_explosion(server: &Server, pos: Vec3<f32>, explosion: Explosion, o
                             target_info,
                             dir,
                             false,
+                            harm_policy.may_harm(owner_entity, entity_b),
                             strength,
                             combat::AttackSource::Explosion,
                             |e| server_eventbus.emit_now(e),
@@ -918,10 +979,16 @@ pub fn handle_buff(server: &mut Server, entity: EcsEntity, buff_change: buff::Bu
     let ecs = &server.state.ecs();
     let mut buffs_all = ecs.write_storage::<comp::Buffs>();
     let bodies = ecs.read_storage::<comp::Body>();
+    let mut logged = Vec::new();
     if let Some(mut buffs) = buffs_all.get_mut(entity) {
         use buff::BuffChange;
+        let kinds_before = buffs.kinds.keys().copied().collect::<HashSet<_>>();
+        let mut source = None;
         match buff_change {
             BuffChange::Add(new_buff) => {
+                if let buff::BuffSource::Character { by } = new_buff.source {
+                    source = Some(by);
+                }
                 if !bodies
                     .get(entity)
                     .map_or(false, |body| body.immune_to(new_buff.kind))
@@ -979,6 +1046,21 @@ pub fn handle_buff(server: &mut Server, entity: EcsEntity, buff_change: buff::Bu
                 }
             },
         }
+        let kinds_after = buffs.kinds.keys().copied().collect::<HashSet<_>>();
+        logged.extend(
+            kinds_after
+                .difference(&kinds_before)
+                .map(|kind| (source, CombatLogKind::BuffApplied(*kind))),
+        );
+        logged.extend(
+            kinds_before
+                .difference(&kinds_after)
+                .map(|kind| (None, CombatLogKind::BuffRemoved(*kind))),
+        );
+    }
+    drop(buffs_all);
+    for (source, kind) in logged {
+        combat_log::log(ecs, source, entity, None, kind);
     }
 }
 
@@ -989,7 +1071,7 @@ pub fn handle_energy_change(server: &Server, entity: EcsEntity, change: EnergyCh
     }
 }
 
-fn handle_exp_gain(
+pub fn handle_exp_gain(
     exp_reward: f32,
     inventory: &Inventory,
     skill_set: &mut SkillSet,
@@ -1022,6 +1104,7 @@ fn handle_exp_gain(
     add_tool_from_slot(EquipSlot::ActiveOffhand);
     add_tool_from_slot(EquipSlot::InactiveMainhand);
     add_tool_from_slot(EquipSlot::InactiveOffhand);
+    xp_pools.extend(skill_set.unlock_data_skill_groups(ExperienceSource::Combat));
     let num_pools = xp_pools.len() as f32;
     for pool in xp_pools.iter() {
         skill_set.change_experience(*pool, (exp_reward / num_pools).ceil() as i32);
@@ -1048,6 +1131,26 @@ pub fn handle_combo_change(server: &Server, entity: EcsEntity, change: i32) {
     }
 }
 
+pub fn handle_wear_equipment(server: &Server, entity: EcsEntity, slots: Vec<EquipSlot>) {
+    let ecs = &server.state.ecs();
+    let mut broken = Vec::new();
+    if let Some(mut inventory) = ecs.write_storage::<Inventory>().get_mut(entity) {
+        for slot in slots {
+            if let Some(item) = inventory.equipped_mut(slot) {
+                if item.wear(1) {
+                    broken.push(item.name().to_owned());
+                }
+            }
+        }
+    }
+
+    if let Some(client) = ecs.read_storage::<Client>().get(entity) {
+        for name in broken {
+            client.send_fallible(ServerGeneral::Notification(Notification::ItemBroken(name)));
+        }
+    }
+}
+
 pub fn handle_teleport_to(server: &Server, entity: EcsEntity, target: Uid, max_range: Option<f32>) {
     let ecs = &server.state.ecs();
     let mut positions = ecs.write_storage::<Pos>();
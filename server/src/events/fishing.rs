@@ -0,0 +1,51 @@
+use specs::{world::WorldExt, Builder, Entity as EcsEntity};
+use vek::*;
+
+use common::{
+    assets::AssetExt,
+    comp::{
+        self,
+        item::{tool::AbilityMap, MaterialStatManifest},
+        Inventory,
+    },
+    lottery::{LootSpec, Lottery},
+};
+
+use crate::{fishing, Server, StateExt};
+
+/// Gives the fish that was reeled in to the player who caught it, or drops it
+/// at their feet if their inventory is full
+pub fn handle_catch_fish(server: &mut Server, entity: EcsEntity, pos: Vec3<f32>) {
+    let loot_table = fishing::water_at(server.world(), pos).loot_table();
+    let item = Lottery::<LootSpec>::load_expect(loot_table)
+        .read()
+        .choose()
+        .to_item();
+
+    let state = server.state_mut();
+    let item_msg = item.duplicate(
+        &state.ecs().read_resource::<AbilityMap>(),
+        &state.ecs().read_resource::<MaterialStatManifest>(),
+    );
+    let leftover = match state.ecs().write_storage::<Inventory>().get_mut(entity) {
+        Some(inventory) => inventory.push(item).err(),
+        None => Some(item),
+    };
+    match leftover {
+        Some(item) => {
+            if let Some(pos) = state.read_component_copied::<comp::Pos>(entity) {
+                state
+                    .create_object(Default::default(), comp::object::Body::FishMeat)
+                    .with(pos)
+                    .with(item)
+                    .build();
+            }
+        },
+        None => {
+            let _ = state.ecs().write_storage().insert(
+                entity,
+                comp::InventoryUpdate::new(comp::InventoryUpdateEvent::Collected(item_msg)),
+            );
+        },
+    }
+}
@@ -0,0 +1,405 @@
+use hashbrown::HashMap;
+use specs::{world::WorldExt, Entity as EcsEntity, Join, World};
+
+use common::{
+    character::CharacterId,
+    comp::{
+        self,
+        guild::{GuildId, MAX_GUILD_NAME_LENGTH, MAX_GUILD_TAG_LENGTH, MIN_GUILD_TAG_LENGTH},
+        ChatMode, ChatType, GuildManip,
+    },
+    uid::Uid,
+};
+use common_net::msg::{PlayerListUpdate, PresenceKind, ServerGeneral};
+
+use super::{character_of, entity_of};
+use crate::{
+    alias_validator::AliasValidator, client::Client, guild::GuildManager, mail::PostOffice,
+    presence::Presence, Server,
+};
+
+pub fn handle_guild_manip(server: &mut Server, entity: EcsEntity, manip: GuildManip) {
+    let ecs = server.state.ecs();
+    let character_id = match character_of(ecs, entity) {
+        Some(character_id) => character_id,
+        None => return,
+    };
+
+    let result = match manip {
+        GuildManip::Create { name, tag } => create_guild(ecs, entity, character_id, name, tag),
+        GuildManip::Leave => leave_guild(ecs, entity, character_id),
+        GuildManip::Kick(target) => kick_member(ecs, character_id, target),
+        GuildManip::Promote(target) => change_rank(ecs, character_id, target, true),
+        GuildManip::Demote(target) => change_rank(ecs, character_id, target, false),
+        GuildManip::Disband => disband_guild(ecs, character_id),
+    };
+    if let Err(error) = result {
+        inform(ecs, entity, error);
+    }
+}
+
+/// Checks whether the inviter may invite the invitee into their guild,
+/// telling the inviter why not otherwise
+pub fn can_invite(ecs: &World, inviter: EcsEntity, invitee: EcsEntity) -> bool {
+    let result = (|| {
+        let guild_manager = ecs.read_resource::<GuildManager>();
+        let inviter_rank = character_of(ecs, inviter)
+            .and_then(|character_id| guild_manager.rank_of(character_id))
+            .ok_or("You are not in a guild.")?;
+        if !inviter_rank.can_invite() {
+            return Err("Only officers and the leader can invite new members.");
+        }
+        let invitee = character_of(ecs, invitee).ok_or("Only players can join a guild.")?;
+        if guild_manager.guild_of(invitee).is_some() {
+            return Err("That player is already in a guild.");
+        }
+        Ok(())
+    })();
+
+    match result {
+        Ok(()) => true,
+        Err(error) => {
+            inform(ecs, inviter, error.to_owned());
+            false
+        },
+    }
+}
+
+/// Adds the invitee to the inviter's guild, after they accepted the invite
+pub fn join_guild(ecs: &World, inviter: EcsEntity, invitee: EcsEntity) {
+    let (guild, name) = {
+        let mut guild_manager = ecs.write_resource::<GuildManager>();
+        let (guild, invitee_id) = match (character_of(ecs, inviter), character_of(ecs, invitee)) {
+            (Some(inviter_id), Some(invitee_id)) => {
+                match guild_manager.guild_of(inviter_id) {
+                    // The inviter may have left their guild since inviting
+                    Some((guild, _)) if guild_manager.guild_of(invitee_id).is_none() => {
+                        (guild, invitee_id)
+                    },
+                    _ => return,
+                }
+            },
+            _ => return,
+        };
+        guild_manager.add_member(guild, invitee_id);
+        (guild, name_of(ecs, invitee_id))
+    };
+
+    enter_guild(ecs, invitee, guild);
+    announce(ecs, guild, format!("{} has joined the guild.", name));
+}
+
+/// Marks a character as a member of their guild when they come online, and
+/// sends them its details
+pub fn load_guild(ecs: &World, entity: EcsEntity) {
+    let guild = character_of(ecs, entity).and_then(|character_id| {
+        ecs.read_resource::<GuildManager>()
+            .guild_of(character_id)
+            .map(|(guild, _)| guild)
+    });
+    if let Some(guild) = guild {
+        enter_guild(ecs, entity, guild);
+    }
+}
+
+/// Unmarks a character as a member of their guild when they go offline, so
+/// that the other members see them as offline
+pub fn unload_guild(ecs: &World, entity: EcsEntity) {
+    let guild = ecs.write_storage::<comp::Guild>().remove(entity);
+    if let Some(comp::Guild(guild)) = guild {
+        update_members(ecs, guild);
+    }
+}
+
+fn create_guild(
+    ecs: &World,
+    entity: EcsEntity,
+    character_id: CharacterId,
+    name: String,
+    tag: String,
+) -> Result<(), String> {
+    let name = name.trim().to_owned();
+    let tag = tag.trim().to_owned();
+    let guild = {
+        let mut guild_manager = ecs.write_resource::<GuildManager>();
+        if guild_manager.guild_of(character_id).is_some() {
+            return Err("You are already in a guild.".to_owned());
+        }
+        if name.is_empty() || name.chars().count() > MAX_GUILD_NAME_LENGTH {
+            return Err(format!(
+                "Guild names must be between 1 and {} characters long.",
+                MAX_GUILD_NAME_LENGTH
+            ));
+        }
+        let tag_length = tag.chars().count();
+        if tag_length < MIN_GUILD_TAG_LENGTH
+            || tag_length > MAX_GUILD_TAG_LENGTH
+            || !tag.chars().all(char::is_alphanumeric)
+        {
+            return Err(format!(
+                "Guild tags must be between {} and {} letters or digits long.",
+                MIN_GUILD_TAG_LENGTH, MAX_GUILD_TAG_LENGTH
+            ));
+        }
+        let alias_validator = ecs.read_resource::<AliasValidator>();
+        if alias_validator.validate(&name).is_err() || alias_validator.validate(&tag).is_err() {
+            return Err("That guild name or tag is not allowed.".to_owned());
+        }
+        if guild_manager.find(&name).is_some() || guild_manager.find(&tag).is_some() {
+            return Err("A guild with that name or tag already exists.".to_owned());
+        }
+        guild_manager.create(name.clone(), tag, character_id)
+    };
+
+    enter_guild(ecs, entity, guild);
+    announce(ecs, guild, format!("The guild {} has been founded.", name));
+    Ok(())
+}
+
+fn leave_guild(ecs: &World, entity: EcsEntity, character_id: CharacterId) -> Result<(), String> {
+    let guild = ecs
+        .write_resource::<GuildManager>()
+        .remove_member(character_id)
+        .ok_or_else(|| "You are not in a guild.".to_owned())?;
+
+    leave(ecs, entity);
+    inform(ecs, entity, "You have left your guild.".to_owned());
+    let name = name_of(ecs, character_id);
+    announce(ecs, guild, format!("{} has left the guild.", name));
+    update_members(ecs, guild);
+    Ok(())
+}
+
+/// Removes a deleted character from their guild, handing over leadership if
+/// they led it, so that no membership refers to a character that no longer
+/// exists
+pub fn remove_deleted_character(ecs: &World, character_id: CharacterId) {
+    let guild = ecs
+        .write_resource::<GuildManager>()
+        .delete_character(character_id);
+    if let Some(guild) = guild {
+        let name = name_of(ecs, character_id);
+        announce(ecs, guild, format!("{} has left the guild.", name));
+        update_members(ecs, guild);
+    }
+}
+
+fn kick_member(ecs: &World, character_id: CharacterId, target: CharacterId) -> Result<(), String> {
+    let guild = {
+        let mut guild_manager = ecs.write_resource::<GuildManager>();
+        let (guild, rank) = member_of_same_guild(&guild_manager, character_id, target)?;
+        if !rank.can_kick(guild_manager.rank_of(target).unwrap_or(rank)) {
+            return Err("You can only remove members of a lower rank than yours.".to_owned());
+        }
+        guild_manager.remove_member(target);
+        guild
+    };
+
+    if let Some(target_entity) = entity_of(ecs, target) {
+        leave(ecs, target_entity);
+        inform(
+            ecs,
+            target_entity,
+            "You have been removed from your guild.".to_owned(),
+        );
+    }
+    let name = name_of(ecs, target);
+    announce(
+        ecs,
+        guild,
+        format!("{} has been removed from the guild.", name),
+    );
+    update_members(ecs, guild);
+    Ok(())
+}
+
+fn change_rank(
+    ecs: &World,
+    character_id: CharacterId,
+    target: CharacterId,
+    promote: bool,
+) -> Result<(), String> {
+    let (guild, new_rank) = {
+        let mut guild_manager = ecs.write_resource::<GuildManager>();
+        let (guild, rank) = member_of_same_guild(&guild_manager, character_id, target)?;
+        let target_rank = guild_manager.rank_of(target).unwrap_or(rank);
+        let new_rank = if promote && rank.can_promote(target_rank) {
+            target_rank.promoted()
+        } else if !promote && rank.can_demote(target_rank) {
+            target_rank.demoted()
+        } else {
+            return Err("Only the leader can change the rank of officers and members.".to_owned());
+        };
+        guild_manager.set_rank(target, new_rank);
+        (guild, new_rank)
+    };
+
+    let name = name_of(ecs, target);
+    announce(
+        ecs,
+        guild,
+        format!("{} is now a guild {}.", name, new_rank.as_str()),
+    );
+    update_members(ecs, guild);
+    Ok(())
+}
+
+fn disband_guild(ecs: &World, character_id: CharacterId) -> Result<(), String> {
+    let (guild, members) = {
+        let mut guild_manager = ecs.write_resource::<GuildManager>();
+        let (guild, data) = guild_manager
+            .guild_of(character_id)
+            .ok_or_else(|| "You are not in a guild.".to_owned())?;
+        if !data
+            .members
+            .get(&character_id)
+            .map_or(false, |rank| rank.can_disband())
+        {
+            return Err("Only the leader can disband the guild.".to_owned());
+        }
+        (guild, guild_manager.disband(guild))
+    };
+
+    // Announce while the members are still marked as being in the guild
+    announce(ecs, guild, "The guild has been disbanded.".to_owned());
+    for member in members {
+        if let Some(entity) = entity_of(ecs, member) {
+            leave(ecs, entity);
+        }
+    }
+    Ok(())
+}
+
+/// Finds the guild both characters are in, along with the rank of the first
+fn member_of_same_guild(
+    guild_manager: &GuildManager,
+    character_id: CharacterId,
+    target: CharacterId,
+) -> Result<(GuildId, comp::GuildRank), String> {
+    let (guild, data) = guild_manager
+        .guild_of(character_id)
+        .ok_or_else(|| "You are not in a guild.".to_owned())?;
+    if character_id == target {
+        return Err("You can't do that to yourself.".to_owned());
+    }
+    match (data.members.get(&character_id), data.members.get(&target)) {
+        (Some(rank), Some(_)) => Ok((guild, *rank)),
+        _ => Err("That character is not in your guild.".to_owned()),
+    }
+}
+
+/// Marks an online character as a member of a guild, and updates everyone
+/// that needs to know
+fn enter_guild(ecs: &World, entity: EcsEntity, guild: GuildId) {
+    let _ = ecs
+        .write_storage::<comp::Guild>()
+        .insert(entity, comp::Guild(guild));
+    let tag = ecs
+        .read_resource::<GuildManager>()
+        .get(guild)
+        .map(|data| data.tag.clone());
+    set_tag(ecs, entity, tag);
+    update_members(ecs, guild);
+}
+
+/// Unmarks an online character as a member of their guild, after they left it
+fn leave(ecs: &World, entity: EcsEntity) {
+    ecs.write_storage::<comp::Guild>().remove(entity);
+    set_tag(ecs, entity, None);
+
+    let clients = ecs.read_storage::<Client>();
+    if let Some(client) = clients.get(entity) {
+        client.send_fallible(ServerGeneral::GuildUpdate(None));
+    }
+    // Guild chat would no longer reach anyone
+    let mut chat_modes = ecs.write_storage::<ChatMode>();
+    if let Some(mode) = chat_modes.get_mut(entity) {
+        if let ChatMode::Guild(_) = mode {
+            *mode = ChatMode::default();
+            if let Some(client) = clients.get(entity) {
+                client.send_fallible(ServerGeneral::ChatMode(mode.clone()));
+            }
+        }
+    }
+}
+
+/// The online members of a guild
+fn online_members(ecs: &World, guild: GuildId) -> HashMap<CharacterId, EcsEntity> {
+    (
+        &ecs.entities(),
+        &ecs.read_storage::<Presence>(),
+        &ecs.read_storage::<comp::Guild>(),
+    )
+        .join()
+        .filter(|(_, _, member)| member.0 == guild)
+        .filter_map(|(entity, presence, _)| match presence.kind {
+            PresenceKind::Character(character_id) => Some((character_id, entity)),
+            PresenceKind::Spectator => None,
+        })
+        .collect()
+}
+
+/// Sends the details of a guild to all of its online members
+fn update_members(ecs: &World, guild: GuildId) {
+    let online = online_members(ecs, guild);
+    let guild_manager = ecs.read_resource::<GuildManager>();
+    let post_office = ecs.read_resource::<PostOffice>();
+    let clients = ecs.read_storage::<Client>();
+    for (character_id, entity) in &online {
+        if let Some(client) = clients.get(*entity) {
+            client.send_fallible(ServerGeneral::GuildUpdate(guild_manager.info(
+                guild,
+                *character_id,
+                |member| post_office.character_alias(member).map(str::to_owned),
+                |member| online.contains_key(&member),
+            )));
+        }
+    }
+}
+
+/// Tells every player which guild tag to show next to a character
+fn set_tag(ecs: &World, entity: EcsEntity, tag: Option<String>) {
+    let uid = match ecs.read_storage::<Uid>().get(entity) {
+        Some(uid) => *uid,
+        None => return,
+    };
+    let mut msg = Some(ServerGeneral::PlayerListUpdate(PlayerListUpdate::GuildTag(
+        uid, tag,
+    )));
+    let mut lazy_msg = None;
+    for (client, _) in (
+        &ecs.read_storage::<Client>(),
+        &ecs.read_storage::<comp::Player>(),
+    )
+        .join()
+    {
+        if let Some(msg) = msg.take() {
+            lazy_msg = Some(client.prepare(msg));
+        }
+        lazy_msg.as_ref().map(|msg| client.send_prepared(msg));
+    }
+}
+
+fn announce(ecs: &World, guild: GuildId, message: String) {
+    let clients = ecs.read_storage::<Client>();
+    for entity in online_members(ecs, guild).values() {
+        if let Some(client) = clients.get(*entity) {
+            client.send_fallible(ServerGeneral::server_msg(
+                ChatType::GuildMeta(guild),
+                message.clone(),
+            ));
+        }
+    }
+}
+
+fn name_of(ecs: &World, character_id: CharacterId) -> String {
+    ecs.read_resource::<PostOffice>()
+        .character_alias(character_id)
+        .map_or_else(|| "Someone".to_owned(), str::to_owned)
+}
+
+fn inform(ecs: &World, entity: EcsEntity, message: String) {
+    if let Some(client) = ecs.read_storage::<Client>().get(entity) {
+        client.send_fallible(ServerGeneral::server_msg(ChatType::Meta, message));
+    }
+}
@@ -0,0 +1,263 @@
+use hashbrown::HashSet;
+use specs::{world::WorldExt, Entity as EcsEntity, World};
+use std::sync::Arc;
+use tracing::warn;
+use vek::*;
+
+use common::{
+    assets::AssetExt,
+    character::CharacterId,
+    comp::{self, item::ItemDef, Inventory, UnresolvedChatMsg},
+    resources::Time,
+    uid::Uid,
+};
+use common_net::msg::Notification;
+use common_state::BuildAreas;
+
+use super::character_of;
+use crate::{
+    housing::{HousingManager, SECONDS_PER_DAY},
+    mail::unix_time,
+    settings::Settings,
+    Server, StateExt,
+};
+
+/// Houses further away from the NPC selling them than this are not for sale
+/// in their settlement
+const MAX_SELLING_DIST: i32 = 300;
+
+const COINS: &str = "common.items.utility.coins";
+
+/// An NPC sells the closest house without an owner to the player it is
+/// talking to
+pub fn handle_sell_house(server: &mut Server, seller: EcsEntity, buyer: EcsEntity) {
+    let ecs = server.state.ecs();
+    let (seller_uid, buyer_uid) = {
+        let uids = ecs.read_storage::<Uid>();
+        match (uids.get(seller), uids.get(buyer)) {
+            (Some(seller_uid), Some(buyer_uid)) => (*seller_uid, *buyer_uid),
+            _ => return,
+        }
+    };
+
+    let reply = match sell_house(ecs, seller, buyer) {
+        Ok(reply) | Err(reply) => reply,
+    };
+    server.state.send_chat(UnresolvedChatMsg::npc_tell(
+        seller_uid,
+        buyer_uid,
+        reply.to_owned(),
+    ));
+}
+
+/// Sells a house to the buyer, returning the i18n key of what the seller
+/// replies
+fn sell_house(
+    ecs: &World,
+    seller: EcsEntity,
+    buyer: EcsEntity,
+) -> Result<&'static str, &'static str> {
+    let settings = ecs.read_resource::<Settings>().housing.clone();
+    if !settings.enabled {
+        return Err("npc.speech.house_none_for_sale");
+    }
+    let character_id = character_of(ecs, buyer).ok_or("npc.speech.house_cannot_own")?;
+    let seller_pos = ecs
+        .read_storage::<comp::Pos>()
+        .get(seller)
+        .map(|pos| pos.0.xy().map(|e| e as i32))
+        .ok_or("npc.speech.house_none_for_sale")?;
+
+    let mut housing = ecs.write_resource::<HousingManager>();
+    if housing.house_of(character_id).is_some() {
+        return Err("npc.speech.house_already_owned");
+    }
+    let pos = housing
+        .nearest_for_sale(seller_pos, MAX_SELLING_DIST)
+        .ok_or("npc.speech.house_all_owned")?;
+    // Claim the house before taking the money, so that the buyer never pays
+    // for a house they don't get
+    if !housing.buy(pos, character_id, unix_time()) {
+        return Err("npc.speech.house_all_owned");
+    }
+    if let Err(reply) = pay(ecs, buyer, settings.price) {
+        housing.abandon(character_id);
+        return Err(reply);
+    }
+    drop(housing);
+
+    enter_house(ecs, buyer, pos);
+    set_waypoint(ecs, buyer, pos);
+    Ok("npc.speech.house_sold")
+}
+
+fn pay(ecs: &World, entity: EcsEntity, price: u32) -> Result<(), &'static str> {
+    if price == 0 {
+        return Ok(());
+    }
+    let coins = Arc::<ItemDef>::load_cloned(COINS).map_err(|e| {
+        warn!(?e, "Could not load coins");
+        "npc.speech.house_payment_failed"
+    })?;
+    let paid = ecs
+        .write_storage::<Inventory>()
+        .get_mut(entity)
+        .map_or(false, |inventory| {
+            inventory.remove_item_amount(&coins, price)
+        });
+    if paid {
+        let _ = ecs.write_storage().insert(
+            entity,
+            comp::InventoryUpdate::new(comp::InventoryUpdateEvent::Used),
+        );
+        Ok(())
+    } else {
+        Err("npc.speech.house_too_expensive")
+    }
+}
+
+/// Lets a character build in their house when they come online
+pub fn load_house(ecs: &World, entity: EcsEntity) {
+    let pos = character_of(ecs, entity).and_then(|character_id| {
+        let mut housing = ecs.write_resource::<HousingManager>();
+        housing.seen(character_id, unix_time());
+        housing.house_of(character_id).map(|(pos, _)| pos)
+    });
+    if let Some(pos) = pos {
+        enter_house(ecs, entity, pos);
+    }
+}
+
+/// Notes when the owner of a house goes offline, which is how long they have
+/// been away from it
+pub fn unload_house(ecs: &World, entity: EcsEntity) {
+    if let Some(character_id) = character_of(ecs, entity) {
+        ecs.write_resource::<HousingManager>()
+            .seen(character_id, unix_time());
+    }
+}
+
+/// Puts the house of a player back on the market, returning what to notify
+/// them of
+pub fn abandon_house(ecs: &World, entity: EcsEntity) -> Notification {
+    let pos = character_of(ecs, entity)
+        .and_then(|character_id| ecs.write_resource::<HousingManager>().abandon(character_id));
+    match pos {
+        Some(pos) => {
+            remove_build_area(ecs, pos, Some(entity));
+            Notification::HouseAbandoned
+        },
+        None => no_house(ecs),
+    }
+}
+
+/// Puts the house of a deleted character back on the market, so that it isn't
+/// owned by a character that no longer exists
+pub fn remove_deleted_character(ecs: &World, character_id: CharacterId) {
+    let pos = ecs
+        .write_resource::<HousingManager>()
+        .delete_character(character_id);
+    if let Some(pos) = pos {
+        remove_build_area(ecs, pos, None);
+    }
+}
+
+/// Removes the build area of a house that lost its owner, and the permission
+/// of the owner to build in it if they are online
+pub fn remove_build_area(ecs: &World, pos: Vec2<i32>, owner: Option<EcsEntity>) {
+    let name = HousingManager::area_name(pos);
+    let mut build_areas = ecs.write_resource::<BuildAreas>();
+    if let (Some(owner), Some(id)) = (owner, build_areas.area_names().get(&name)) {
+        if let Some(can_build) = ecs.write_storage::<comp::CanBuild>().get_mut(owner) {
+            can_build.build_areas.remove(id);
+        }
+    }
+    let _ = build_areas.remove(&name);
+}
+
+/// Makes the house of a player their waypoint again, returning what to notify
+/// them of
+pub fn set_waypoint_to_house(ecs: &World, entity: EcsEntity) -> Notification {
+    let pos = character_of(ecs, entity).and_then(|character_id| {
+        ecs.read_resource::<HousingManager>()
+            .house_of(character_id)
+            .map(|(pos, _)| pos)
+    });
+    match pos {
+        Some(pos) => {
+            set_waypoint(ecs, entity, pos);
+            Notification::HouseWaypointSaved
+        },
+        None => no_house(ecs),
+    }
+}
+
+fn set_waypoint(ecs: &World, entity: EcsEntity, pos: Vec2<i32>) {
+    let waypoint = match ecs.read_resource::<HousingManager>().get(pos) {
+        Some(house) => house.waypoint(pos),
+        None => return,
+    };
+    let time = *ecs.read_resource::<Time>();
+    let _ = ecs
+        .write_storage()
+        .insert(entity, comp::Waypoint::new(waypoint, time));
+}
+
+/// Creates the build area of a house if it doesn't exist yet, and lets its
+/// owner build in it
+fn enter_house(ecs: &World, entity: EcsEntity, pos: Vec2<i32>) {
+    let volume = match ecs.read_resource::<HousingManager>().get(pos) {
+        Some(house) => house.volume,
+        None => return,
+    };
+    let name = HousingManager::area_name(pos);
+    let mut build_areas = ecs.write_resource::<BuildAreas>();
+    let id = match build_areas.area_names().get(&name) {
+        Some(id) => *id,
+        None => match build_areas.insert(name, volume) {
+            Ok(id) => id,
+            Err(_) => return,
+        },
+    };
+    if let Ok(entry) = ecs.write_storage::<comp::CanBuild>().entry(entity) {
+        entry
+            .or_insert(comp::CanBuild {
+                enabled: false,
+                build_areas: HashSet::new(),
+            })
+            .build_areas
+            .insert(id);
+    }
+}
+
+/// Describes the house of a player to them
+pub fn house_info(ecs: &World, entity: EcsEntity) -> Notification {
+    let character_id = match character_of(ecs, entity) {
+        Some(character_id) => character_id,
+        None => return Notification::CannotOwnHouse,
+    };
+    let settings = ecs.read_resource::<Settings>().housing.clone();
+    let owner = ecs
+        .read_resource::<HousingManager>()
+        .house_of(character_id)
+        .and_then(|(pos, house)| Some((pos, house.owner?)));
+    match owner {
+        Some((pos, owner)) => Notification::HouseInfo {
+            pos,
+            lease_days_left: settings.lease_days.map(|days| {
+                (owner.bought_at + u64::from(days) * SECONDS_PER_DAY).saturating_sub(unix_time())
+                    / SECONDS_PER_DAY
+            }),
+            evict_after_days: settings.evict_after_days_offline,
+        },
+        None => Notification::NoHouse {
+            price: settings.price,
+        },
+    }
+}
+
+fn no_house(ecs: &World) -> Notification {
+    Notification::NoHouse {
+        price: ecs.read_resource::<Settings>().housing.price,
+    }
+}
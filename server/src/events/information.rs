@@ -27,46 +27,43 @@ pub fn handle_site_info(server: &Server, entity: EcsEntity, id: u64) {
 
 #[cfg(feature = "worldgen")]
 pub fn handle_site_info(server: &Server, entity: EcsEntity, id: u64) {
-    let site_id = server.index.sites.recreate_id(id);
-    let info = if let Some(site_id) = site_id {
-        let site = server.index.sites.get(site_id);
+    let economy_sim = server
+        .state
+        .ecs()
+        .read_resource::<world::sim2::EconomySim>();
+    let info = if let Some(economy) = economy_sim.economy(id) {
         EconomyInfo {
             id,
-            population: site.economy.pop.floor() as u32,
-            stock: site
-                .economy
+            population: economy.pop.floor() as u32,
+            stock: economy
                 .stocks
                 .iter()
                 .map(|(g, a)| (Good::from(g), *a))
                 .collect(),
-            labor_values: site
-                .economy
+            labor_values: economy
                 .labor_values
                 .iter()
                 .filter_map(|(g, a)| a.map(|a| (Good::from(g), a)))
                 .collect(),
-            values: site
-                .economy
+            values: economy
                 .values
                 .iter()
                 .filter_map(|(g, a)| a.map(|a| (Good::from(g), a)))
                 .collect(),
-            labors: site.economy.labors.iter().map(|(_, a)| (*a)).collect(),
-            last_exports: site
-                .economy
+            labors: economy.labors.iter().map(|(_, a)| (*a)).collect(),
+            last_exports: economy
                 .last_exports
                 .iter()
                 .map(|(g, a)| (Good::from(g), *a))
                 .collect(),
-            resources: site
-                .economy
+            resources: economy
                 .natural_resources
                 .chunks_per_resource
                 .iter()
                 .map(|(g, a)| {
                     (
                         Good::from(g),
-                        ((*a) as f32) * site.economy.natural_resources.average_yield_per_chunk[g],
+                        ((*a) as f32) * economy.natural_resources.average_yield_per_chunk[g],
                     )
                 })
                 .collect(),
@@ -10,6 +10,7 @@ use common::{
         dialogue::Subject,
         inventory::slot::EquipSlot,
         item,
+        skill_tree::ExperienceSource,
         slot::Slot,
         tool::ToolKind,
         Inventory, Pos, SkillGroupKind,
@@ -77,6 +78,15 @@ pub fn handle_lantern(server: &mut Server, entity: EcsEntity, enable: bool) {
 
 pub fn handle_npc_interaction(server: &mut Server, interactor: EcsEntity, npc_entity: EcsEntity) {
     let state = server.state_mut();
+    if state
+        .ecs()
+        .read_storage::<comp::Storage>()
+        .get(npc_entity)
+        .is_some()
+    {
+        super::storage::open_container(state.ecs(), interactor, npc_entity);
+        return;
+    }
     if let Some(agent) = state
         .ecs()
         .write_storage::<comp::Agent>()
@@ -115,6 +125,22 @@ pub fn handle_mount(server: &mut Server, mounter: EcsEntity, mountee: EcsEntity)
                 state.ecs().uid_from_entity(mounter),
                 state.ecs().uid_from_entity(mountee),
             ) {
+                // Anyone can board a ship, but creatures only carry the one who tamed them
+                let can_ride = state
+                    .ecs()
+                    .read_storage::<comp::Body>()
+                    .get(mountee)
+                    .map_or(false, |body| {
+                        comp::pet::can_ride(
+                            body,
+                            state.ecs().read_storage::<comp::Alignment>().get(mountee),
+                            mounter_uid,
+                        )
+                    });
+                if !can_ride {
+                    return;
+                }
+
                 // We know the entities must exist to be able to look up their UIDs, so these
                 // are guaranteed to work; hence we can ignore possible errors here.
                 state.write_component_ignore_entity_dead(
@@ -189,6 +215,7 @@ pub fn handle_possess(server: &mut Server, possessor_uid: Uid, possesse_uid: Uid
                                 character: ecs.read_storage::<comp::Stats>().get(possesse).map(
                                     |s| common_net::msg::CharacterInfo {
                                         name: s.name.clone(),
+                                        guild_tag: None,
                                     },
                                 ),
                             },
@@ -327,14 +354,19 @@ pub fn handle_mine_block(
                             .0
                             .get(item.item_definition_id()),
                     ) {
-                        skillset.change_experience(SkillGroupKind::Weapon(tool), *exp_reward);
+                        let mut xp_pools = HashSet::from_iter(vec![SkillGroupKind::Weapon(tool)]);
+                        xp_pools
+                            .extend(skillset.unlock_data_skill_groups(ExperienceSource::Mining));
+                        for pool in xp_pools.iter() {
+                            skillset.change_experience(*pool, *exp_reward);
+                        }
                         state
                             .ecs()
                             .write_resource::<Vec<Outcome>>()
                             .push(Outcome::ExpChange {
                                 uid,
                                 exp: *exp_reward,
-                                xp_pools: HashSet::from_iter(vec![SkillGroupKind::Weapon(tool)]),
+                                xp_pools,
                             });
                     }
                     use common::comp::skills::{MiningSkill, Skill};
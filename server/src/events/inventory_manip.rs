@@ -1,16 +1,23 @@
+use hashbrown::HashSet;
 use rand::Rng;
 use specs::{join::Join, world::WorldExt, Builder, Entity as EcsEntity, WriteStorage};
+use std::time::Duration;
 use tracing::{debug, error, warn};
 use vek::{Rgb, Vec3};
 
 use common::{
     comp::{
         self,
+        crafting::{QueuedCraft, MAX_QUEUED_CRAFTS},
         item::{self, tool::AbilityMap, MaterialStatManifest},
+        skill_tree::{ExperienceSource, CRAFTING_EXPERIENCE},
         slot::{self, Slot},
     },
     consts::MAX_PICKUP_RANGE,
-    recipe::default_recipe_book,
+    event::{EventBus, ServerEvent},
+    farming::{self, CropManifest},
+    outcome::Outcome,
+    recipe::{default_recipe_book, default_repair_recipe_book},
     trade::Trades,
     uid::Uid,
     util::find_dist::{self, FindDist},
@@ -20,7 +27,7 @@ use common_net::{msg::ServerGeneral, sync::WorldSyncExt};
 use common_state::State;
 use comp::LightEmitter;
 
-use crate::{client::Client, Server, StateExt};
+use crate::{client::Client, farming::Crops, storage::StorageContainers, Server, StateExt};
 
 pub fn swap_lantern(
     storage: &mut WriteStorage<comp::LightEmitter>,
@@ -62,6 +69,8 @@ pub fn handle_inventory(server: &mut Server, entity: EcsEntity, manip: comp::Inv
 
     let mut dropped_items = Vec::new();
     let mut thrown_items = Vec::new();
+    let mut placed_storage = None;
+    let mut planted_crop = None;
 
     let get_cylinder = |state: &State, entity| {
         let ecs = state.ecs();
@@ -80,6 +89,32 @@ pub fn handle_inventory(server: &mut Server, entity: EcsEntity, manip: comp::Inv
         })
     };
 
+    // The sprite at the given position, if it is close enough to use for crafting
+    let nearby_sprite = |state: &State, entity, craft_sprite: Option<Vec3<i32>>| {
+        craft_sprite
+            .filter(|pos| {
+                let entity_cylinder = get_cylinder(state, entity);
+                if !within_pickup_range(entity_cylinder, || {
+                    Some(find_dist::Cube {
+                        min: pos.as_(),
+                        side_length: 1.0,
+                    })
+                }) {
+                    debug!(
+                        ?entity_cylinder,
+                        "Failed to use crafting station as not within range of required sprite, \
+                         sprite pos: {}",
+                        pos
+                    );
+                    false
+                } else {
+                    true
+                }
+            })
+            .and_then(|pos| state.terrain().get(pos).ok().copied())
+            .and_then(|block| block.get_sprite())
+    };
+
     let mut inventories = state.ecs().write_storage::<comp::Inventory>();
     let mut inventory = if let Some(inventory) = inventories.get_mut(entity) {
         inventory
@@ -313,16 +348,8 @@ pub fn handle_inventory(server: &mut Server, entity: EcsEntity, manip: comp::Inv
                                 let reinsert = if let Some(pos) =
                                     state.read_storage::<comp::Pos>().get(entity)
                                 {
-                                    if (
-                                        &state.read_storage::<comp::Alignment>(),
-                                        &state.read_storage::<comp::Agent>(),
-                                    )
-                                        .join()
-                                        .filter(|(alignment, _)| {
-                                            alignment == &&comp::Alignment::Owned(uid)
-                                        })
-                                        .count()
-                                        >= 3
+                                    if crate::pet::pets_of(state.ecs(), uid).len()
+                                        >= comp::pet::MAX_PETS
                                     {
                                         true
                                     } else if let Some(tameable_entity) = {
@@ -330,57 +357,23 @@ pub fn handle_inventory(server: &mut Server, entity: EcsEntity, manip: comp::Inv
                                             &state.ecs().entities(),
                                             &state.ecs().read_storage::<comp::Pos>(),
                                             &state.ecs().read_storage::<comp::Alignment>(),
+                                            &state.ecs().read_storage::<comp::Body>(),
                                         )
                                             .join()
-                                            .filter(|(_, wild_pos, _)| {
+                                            .filter(|(_, wild_pos, _, _)| {
                                                 wild_pos.0.distance_squared(pos.0) < 5.0f32.powi(2)
                                             })
-                                            .filter(|(_, _, alignment)| {
+                                            .filter(|(_, _, alignment, body)| {
                                                 alignment == &&comp::Alignment::Wild
+                                                    && comp::pet::is_tameable(body)
                                             })
-                                            .min_by_key(|(_, wild_pos, _)| {
+                                            .min_by_key(|(_, wild_pos, _, _)| {
                                                 (wild_pos.0.distance_squared(pos.0) * 100.0) as i32
                                             })
-                                            .map(|(entity, _, _)| entity);
+                                            .map(|(entity, _, _, _)| entity);
                                         nearest_tameable
                                     } {
-                                        let _ = state
-                                            .ecs()
-                                            .write_storage()
-                                            .insert(tameable_entity, comp::Alignment::Owned(uid));
-
-                                        // Add to group system
-                                        let clients = state.ecs().read_storage::<Client>();
-                                        let uids = state.ecs().read_storage::<Uid>();
-                                        let mut group_manager = state
-                                            .ecs()
-                                            .write_resource::<comp::group::GroupManager>(
-                                        );
-                                        group_manager.new_pet(
-                                            tameable_entity,
-                                            entity,
-                                            &mut state.ecs().write_storage(),
-                                            &state.ecs().entities(),
-                                            &state.ecs().read_storage(),
-                                            &uids,
-                                            &mut |entity, group_change| {
-                                                clients
-                                                    .get(entity)
-                                                    .and_then(|c| {
-                                                        group_change
-                                                            .try_map(|e| uids.get(e).copied())
-                                                            .map(|g| (g, c))
-                                                    })
-                                                    .map(|(g, c)| {
-                                                        c.send(ServerGeneral::GroupUpdate(g))
-                                                    });
-                                            },
-                                        );
-
-                                        let _ = state
-                                            .ecs()
-                                            .write_storage()
-                                            .insert(tameable_entity, comp::Agent::default());
+                                        crate::pet::tame_pet(state.ecs(), tameable_entity, entity);
                                         false
                                     } else {
                                         true
@@ -395,6 +388,63 @@ pub fn handle_inventory(server: &mut Server, entity: EcsEntity, manip: comp::Inv
 
                                 Some(comp::InventoryUpdateEvent::Used)
                             },
+                            ItemKind::Utility {
+                                kind: comp::item::Utility::StorageChest,
+                                ..
+                            } => {
+                                let block_pos = state
+                                    .read_storage::<comp::Pos>()
+                                    .get(entity)
+                                    .map(|pos| pos.0.map(|e| e.floor() as i32));
+                                // Only one chest fits in a block
+                                match block_pos.filter(|pos| {
+                                    state
+                                        .ecs()
+                                        .read_resource::<StorageContainers>()
+                                        .get(*pos)
+                                        .is_none()
+                                }) {
+                                    Some(pos) => {
+                                        placed_storage = Some(pos);
+                                        Some(comp::InventoryUpdateEvent::Used)
+                                    },
+                                    // The chest wasn't placed, so it wasn't used
+                                    None => {
+                                        let _ = inventory.insert_or_stack_at(slot, item);
+                                        None
+                                    },
+                                }
+                            },
+                            ItemKind::Utility {
+                                kind: comp::item::Utility::Seeds,
+                                ..
+                            } => {
+                                let block_pos = state
+                                    .read_storage::<comp::Pos>()
+                                    .get(entity)
+                                    .map(|pos| pos.0.map(|e| e.floor() as i32));
+                                let crop = CropManifest::load()
+                                    .read()
+                                    .crop_from_seeds(item.item_definition_id())
+                                    .map(|(crop, def)| (crop.to_owned(), def.sprite(0.0)));
+                                // Seeds are planted in an empty block on top of farmland
+                                match block_pos.zip(crop).filter(|(pos, _)| {
+                                    let terrain = state.terrain();
+                                    terrain.get(*pos).map_or(false, |block| {
+                                        !block.is_filled() && block.get_sprite().is_none()
+                                    }) && terrain
+                                        .get(*pos - Vec3::unit_z())
+                                        .map_or(false, farming::is_farmland)
+                                        && state.ecs().read_resource::<Crops>().get(*pos).is_none()
+                                }) {
+                                    Some(crop) => planted_crop = Some(crop),
+                                    None => {
+                                        let _ = inventory.insert_or_stack_at(slot, item);
+                                    },
+                                }
+
+                                Some(comp::InventoryUpdateEvent::Used)
+                            },
                             _ => {
                                 inventory.insert_or_stack_at(slot, item).expect(
                                     "slot was just vacated of item, so it definitely fits there.",
@@ -432,6 +482,22 @@ pub fn handle_inventory(server: &mut Server, entity: EcsEntity, manip: comp::Inv
 
             drop(inventories);
 
+            if let Some(pos) = placed_storage {
+                state
+                    .ecs()
+                    .read_resource::<EventBus<ServerEvent>>()
+                    .emit_now(ServerEvent::CreateStorage(pos));
+            }
+
+            if let Some((pos, (crop, sprite))) = planted_crop {
+                let block = state.terrain().get(pos).ok().copied();
+                if let Some(block) = block {
+                    if state.ecs().write_resource::<Crops>().plant(pos, crop) {
+                        state.set_block(pos, block.with_sprite(sprite));
+                    }
+                }
+            }
+
             if let Some(effects) = maybe_effect {
                 for effect in effects {
                     state.apply_effect(entity, effect, None);
@@ -585,44 +651,83 @@ pub fn handle_inventory(server: &mut Server, entity: EcsEntity, manip: comp::Inv
             drop(inventories);
         },
         comp::InventoryManip::CraftRecipe {
-            recipe,
+            recipe: recipe_name,
             craft_sprite,
+            amount,
+            improve,
         } => {
+            let recipe_book = default_recipe_book().read();
+            let recipe = recipe_book.get(&recipe_name).filter(|r| {
+                r.craft_sprite.map_or(true, |needed_sprite| {
+                    Some(needed_sprite) == nearby_sprite(state, entity, craft_sprite)
+                })
+            });
+            let mut craft_results = Vec::new();
+            let mut queued = None;
+            if let Some(recipe) = recipe {
+                if recipe.craft_time > Duration::default() {
+                    // Recipes that take time are queued, and the crafting system works on them
+                    // for as long as the entity stays at the crafting station
+                    if inventory.contains_ingredients(recipe).is_ok() {
+                        queued = Some(QueuedCraft {
+                            recipe: recipe_name.clone(),
+                            amount,
+                            craft_sprite,
+                            improve,
+                            craft_time: recipe.craft_time.as_secs_f32(),
+                        });
+                    }
+                } else {
+                    let ability_map = &state.ecs().read_resource::<AbilityMap>();
+                    let msm = &state.ecs().read_resource::<item::MaterialStatManifest>();
+                    for _ in 0..amount.min(MAX_QUEUED_CRAFTS) {
+                        match recipe.perform(&mut inventory, improve, ability_map, msm) {
+                            Ok(craft_result) => craft_results.push(craft_result),
+                            Err(_) => break,
+                        }
+                    }
+                }
+            }
+            drop(inventories);
+
+            if let Some(craft) = queued {
+                let queued = state
+                    .ecs()
+                    .write_storage::<comp::CraftingQueue>()
+                    .entry(entity)
+                    .map_or(false, |entry| {
+                        entry.or_insert_with(Default::default).push(craft)
+                    });
+                if queued {
+                    send_crafting_queue(state, entity);
+                }
+            }
+            finish_crafts(state, entity, craft_results, &mut dropped_items);
+        },
+        comp::InventoryManip::CancelCrafting => {
+            drop(inventories);
+            if let Some(crafting_queue) = state
+                .ecs()
+                .write_storage::<comp::CraftingQueue>()
+                .get_mut(entity)
+            {
+                crafting_queue.clear();
+            }
+            send_crafting_queue(state, entity);
+        },
+        comp::InventoryManip::FinishCraft(craft) => {
             let recipe_book = default_recipe_book().read();
             let craft_result = recipe_book
-                .get(&recipe)
+                .get(&craft.recipe)
                 .filter(|r| {
-                    if let Some(needed_sprite) = r.craft_sprite {
-                        let sprite = craft_sprite
-                            .filter(|pos| {
-                                let entity_cylinder = get_cylinder(state, entity);
-                                if !within_pickup_range(entity_cylinder, || {
-                                    Some(find_dist::Cube {
-                                        min: pos.as_(),
-                                        side_length: 1.0,
-                                    })
-                                }) {
-                                    debug!(
-                                        ?entity_cylinder,
-                                        "Failed to craft recipe as not within range of required \
-                                         sprite, sprite pos: {}",
-                                        pos
-                                    );
-                                    false
-                                } else {
-                                    true
-                                }
-                            })
-                            .and_then(|pos| state.terrain().get(pos).ok().copied())
-                            .and_then(|block| block.get_sprite());
-                        Some(needed_sprite) == sprite
-                    } else {
-                        true
-                    }
+                    r.craft_sprite.map_or(true, |needed_sprite| {
+                        Some(needed_sprite) == nearby_sprite(state, entity, craft.craft_sprite)
+                    })
                 })
                 .and_then(|r| {
                     r.perform(
                         &mut inventory,
+                        craft.improve,
                         &state.ecs().read_resource::<AbilityMap>(),
                         &state.ecs().read_resource::<item::MaterialStatManifest>(),
                     )
@@ -630,29 +735,67 @@ pub fn handle_inventory(server: &mut Server, entity: EcsEntity, manip: comp::Inv
                 });
             drop(inventories);
 
-            // FIXME: We should really require the drop and write to be atomic!
-            if craft_result.is_some() {
+            // Without the crafting station or the ingredients, the rest of the queue can't
+            // be crafted either
+            if craft_result.is_none() {
+                if let Some(crafting_queue) = state
+                    .ecs()
+                    .write_storage::<comp::CraftingQueue>()
+                    .get_mut(entity)
+                {
+                    crafting_queue.clear();
+                }
+            }
+            send_crafting_queue(state, entity);
+            finish_crafts(
+                state,
+                entity,
+                craft_result.into_iter().collect(),
+                &mut dropped_items,
+            );
+        },
+        comp::InventoryManip::RepairItem { slot, craft_sprite } => {
+            let repair_book = default_repair_recipe_book().read();
+            let repaired = inventory
+                .get_slot(slot)
+                .and_then(|item| repair_book.get(item))
+                .filter(|r| {
+                    r.craft_sprite.map_or(true, |needed_sprite| {
+                        Some(needed_sprite) == nearby_sprite(state, entity, craft_sprite)
+                    })
+                })
+                .map_or(false, |r| {
+                    r.perform(
+                        &mut inventory,
+                        slot,
+                        &state.ecs().read_resource::<AbilityMap>(),
+                        &state.ecs().read_resource::<item::MaterialStatManifest>(),
+                    )
+                    .is_ok()
+                });
+            drop(inventories);
+
+            if repaired {
                 let _ = state.ecs().write_storage().insert(
                     entity,
                     comp::InventoryUpdate::new(comp::InventoryUpdateEvent::Craft),
                 );
             }
+        },
+        comp::InventoryManip::SocketGem { gem, slot } => {
+            let socketed = inventory.socket_gem(
+                gem,
+                slot,
+                &state.ecs().read_resource::<AbilityMap>(),
+                &state.ecs().read_resource::<item::MaterialStatManifest>(),
+            );
+            drop(inventories);
 
-            // Drop the item if there wasn't enough space
-            if let Some(Some((item, amount))) = craft_result {
-                let ability_map = &state.ecs().read_resource::<AbilityMap>();
-                let msm = state.ecs().read_resource::<MaterialStatManifest>();
-                for _ in 0..amount {
-                    dropped_items.push((
-                        state
-                            .read_component_copied::<comp::Pos>(entity)
-                            .unwrap_or_default(),
-                        state
-                            .read_component_copied::<comp::Ori>(entity)
-                            .unwrap_or_default(),
-                        item.duplicate(ability_map, &msm),
-                    ));
-                }
+            if socketed {
+                let _ = state.ecs().write_storage().insert(
+                    entity,
+                    comp::InventoryUpdate::new(comp::InventoryUpdateEvent::Craft),
+                );
             }
         },
         comp::InventoryManip::Sort => {
@@ -761,6 +904,87 @@ fn within_pickup_range<S: FindDist<find_dist::Cylinder>>(
         .unwrap_or(false)
 }
 
+/// Lets the entity know about each craft it finished, and drops what didn't fit
+/// in its inventory
+fn finish_crafts(
+    state: &State,
+    entity: EcsEntity,
+    craft_results: Vec<Option<(item::Item, u32)>>,
+    dropped_items: &mut Vec<(comp::Pos, comp::Ori, item::Item)>,
+) {
+    if craft_results.is_empty() {
+        return;
+    }
+
+    // FIXME: We should really require the drop and write to be atomic!
+    let _ = state.ecs().write_storage().insert(
+        entity,
+        comp::InventoryUpdate::new(comp::InventoryUpdateEvent::Craft),
+    );
+
+    let ability_map = &state.ecs().read_resource::<AbilityMap>();
+    let msm = state.ecs().read_resource::<MaterialStatManifest>();
+    for craft_result in craft_results {
+        gain_crafting_experience(state, entity);
+
+        // Drop the item if there wasn't enough space
+        if let Some((item, amount)) = craft_result {
+            for _ in 0..amount {
+                dropped_items.push((
+                    state
+                        .read_component_copied::<comp::Pos>(entity)
+                        .unwrap_or_default(),
+                    state
+                        .read_component_copied::<comp::Ori>(entity)
+                        .unwrap_or_default(),
+                    item.duplicate(ability_map, &msm),
+                ));
+            }
+        }
+    }
+}
+
+/// Sends the crafts the entity is working on to its client
+fn send_crafting_queue(state: &State, entity: EcsEntity) {
+    if let (Some(client), Some(crafting_queue)) = (
+        state.ecs().read_storage::<Client>().get(entity),
+        state
+            .ecs()
+            .read_storage::<comp::CraftingQueue>()
+            .get(entity),
+    ) {
+        client.send_fallible(ServerGeneral::CraftingUpdate(crafting_queue.clone()));
+    }
+}
+
+/// Gives experience to the data skill groups that level from crafting
+fn gain_crafting_experience(state: &State, entity: EcsEntity) {
+    let mut skill_sets = state.ecs().write_storage::<comp::SkillSet>();
+    if let (Some(mut skill_set), Some(uid)) = (
+        skill_sets.get_mut(entity),
+        state.read_component_copied::<Uid>(entity),
+    ) {
+        let xp_pools = skill_set
+            .unlock_data_skill_groups(ExperienceSource::Crafting)
+            .into_iter()
+            .collect::<HashSet<_>>();
+        if xp_pools.is_empty() {
+            return;
+        }
+        for pool in xp_pools.iter() {
+            skill_set.change_experience(*pool, CRAFTING_EXPERIENCE);
+        }
+        state
+            .ecs()
+            .write_resource::<Vec<Outcome>>()
+            .push(Outcome::ExpChange {
+                uid,
+                exp: CRAFTING_EXPERIENCE,
+                xp_pools,
+            });
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use vek::Vec3;
@@ -1,4 +1,4 @@
-use super::group_manip;
+use super::{duel, group_manip, guild};
 use crate::{client::Client, Server};
 use common::{
     comp::{
@@ -74,6 +74,18 @@ pub fn handle_invite(
         }
     }
 
+    if let InviteKind::Guild = kind {
+        if !guild::can_invite(state.ecs(), inviter, invitee) {
+            return;
+        }
+    }
+
+    if let InviteKind::Duel = kind {
+        if !duel::can_challenge(state.ecs(), inviter, invitee) {
+            return;
+        }
+    }
+
     let mut agents = state.ecs().write_storage::<comp::Agent>();
     let mut invites = state.ecs().write_storage::<Invite>();
 
@@ -163,7 +175,6 @@ pub fn handle_invite_response(
 }
 
 pub fn handle_invite_accept(server: &mut Server, entity: specs::Entity) {
-    let index = server.index.clone();
     let state = server.state_mut();
     if let Some((inviter, kind)) = get_inviter_and_kind(entity, state) {
         handle_invite_answer(state, inviter, entity, InviteAnswer::Accepted, kind);
@@ -206,20 +217,23 @@ pub fn handle_invite_accept(server: &mut Server, entity: specs::Entity) {
                             .push_back(AgentEvent::TradeAccepted(invitee_uid));
                     }
                     #[cfg(feature = "worldgen")]
-                    let pricing = agents
-                        .get(inviter)
-                        .and_then(|a| {
-                            a.behavior
-                                .trade_site
-                                .and_then(|id| index.get_site_prices(id))
-                        })
-                        .or_else(|| {
-                            agents.get(entity).and_then(|a| {
+                    let pricing = {
+                        let economy = state.ecs().read_resource::<world::sim2::EconomySim>();
+                        agents
+                            .get(inviter)
+                            .and_then(|a| {
                                 a.behavior
                                     .trade_site
-                                    .and_then(|id| index.get_site_prices(id))
+                                    .and_then(|id| economy.get_site_prices(id))
+                            })
+                            .or_else(|| {
+                                agents.get(entity).and_then(|a| {
+                                    a.behavior
+                                        .trade_site
+                                        .and_then(|id| economy.get_site_prices(id))
+                                })
                             })
-                        });
+                    };
                     #[cfg(not(feature = "worldgen"))]
                     let pricing = None;
 
@@ -235,6 +249,8 @@ pub fn handle_invite_accept(server: &mut Server, entity: specs::Entity) {
                         .map(|c| c.send(ServerGeneral::UpdatePendingTrade(id, trade, pricing)));
                 }
             },
+            InviteKind::Guild => guild::join_guild(state.ecs(), inviter, entity),
+            InviteKind::Duel => duel::start_duel(state.ecs(), inviter, entity),
         }
     }
 }
@@ -0,0 +1,271 @@
+use hashbrown::HashSet;
+use specs::{world::WorldExt, Entity as EcsEntity, World};
+use vek::*;
+
+use common::{
+    character::CharacterId,
+    comp::{
+        self,
+        inventory::slot::InvSlotId,
+        item::{tool::AbilityMap, MaterialStatManifest},
+        ChatType, Inventory, MailManip,
+    },
+    mail::{Mail, MailId, MAX_ATTACHMENTS, MAX_MESSAGE_LENGTH},
+    terrain::SpriteKind,
+    trade::Trades,
+    vol::ReadVol,
+};
+use common_net::{msg::ServerGeneral, sync::WorldSyncExt};
+
+use super::{character_of, entity_of, storage::within_reach_of_block};
+use crate::{
+    client::Client,
+    mail::{unix_time, PostOffice},
+    Server,
+};
+
+pub fn handle_mail_manip(server: &mut Server, entity: EcsEntity, manip: MailManip) {
+    let ecs = server.state.ecs();
+    let character_id = match character_of(ecs, entity) {
+        Some(character_id) => character_id,
+        None => return,
+    };
+
+    match manip {
+        MailManip::Send {
+            mailbox,
+            recipient,
+            message,
+            attachments,
+        } => {
+            if at_mailbox(server, entity, mailbox) {
+                let result = send_mail(ecs, entity, character_id, &recipient, message, attachments);
+                inform(ecs, entity, result.unwrap_or_else(|error| error));
+            }
+        },
+        MailManip::Claim { mailbox, mail } => {
+            if at_mailbox(server, entity, mailbox) {
+                if let Err(error) = claim_mail(ecs, entity, character_id, mail) {
+                    inform(ecs, entity, error);
+                }
+            }
+        },
+    }
+}
+
+/// Sends a character the letters waiting for them when they come online, and
+/// tells them if there are any
+pub fn deliver_mail(ecs: &World, entity: EcsEntity) {
+    let character_id = match character_of(ecs, entity) {
+        Some(character_id) => character_id,
+        None => return,
+    };
+    let mailbox = ecs.read_resource::<PostOffice>().mailbox(character_id);
+    if !mailbox.is_empty() {
+        inform(
+            ecs,
+            entity,
+            format!(
+                "You have {} letter(s) waiting for you at the mailbox.",
+                mailbox.len()
+            ),
+        );
+    }
+    if let Some(client) = ecs.read_storage::<Client>().get(entity) {
+        client.send_fallible(ServerGeneral::MailUpdate(mailbox));
+    }
+}
+
+fn send_mail(
+    ecs: &World,
+    entity: EcsEntity,
+    sender: CharacterId,
+    recipient: &str,
+    message: String,
+    attachments: Vec<(InvSlotId, u32)>,
+) -> Result<String, String> {
+    let uid = ecs.uid_from_entity(entity).ok_or_else(String::new)?;
+    if ecs.read_resource::<Trades>().in_immutable_trade(&uid) {
+        return Err("You can't send letters while trading.".to_owned());
+    }
+    if message.chars().count() > MAX_MESSAGE_LENGTH {
+        return Err(format!(
+            "Letters can be at most {} characters long.",
+            MAX_MESSAGE_LENGTH
+        ));
+    }
+    if message.trim().is_empty() && attachments.is_empty() {
+        return Err("There is nothing to send.".to_owned());
+    }
+
+    let mut post_office = ecs.write_resource::<PostOffice>();
+    let (recipient, recipient_alias) = {
+        let mut found = post_office.find_characters(recipient);
+        match (found.next(), found.next()) {
+            (Some((id, _)), _) if id == sender => {
+                return Err("You can't send letters to yourself.".to_owned());
+            },
+            (Some((id, alias)), None) => (id, alias.to_owned()),
+            (Some(_), Some(_)) => {
+                return Err(format!(
+                    "There is more than one character called {}.",
+                    recipient
+                ));
+            },
+            (None, _) => return Err(format!("There is no character called {}.", recipient)),
+        }
+    };
+    let sender_alias = post_office
+        .character_alias(sender)
+        .map(str::to_owned)
+        .or_else(|| {
+            ecs.read_storage::<comp::Stats>()
+                .get(entity)
+                .map(|stats| stats.name.clone())
+        })
+        .unwrap_or_default();
+
+    let mut inventories = ecs.write_storage::<Inventory>();
+    let inventory = inventories.get_mut(entity).ok_or_else(String::new)?;
+
+    // Check every attachment before taking anything, so that a letter is either
+    // sent whole or not at all
+    let slots = attachments
+        .iter()
+        .map(|(slot, _)| *slot)
+        .collect::<HashSet<_>>();
+    if slots.len() != attachments.len() || attachments.len() > MAX_ATTACHMENTS {
+        return Err(format!(
+            "At most {} different items can be attached to a letter.",
+            MAX_ATTACHMENTS
+        ));
+    }
+    if attachments.iter().any(|(slot, quantity)| {
+        *quantity == 0
+            || inventory
+                .get(*slot)
+                .map_or(true, |item| item.amount() < *quantity)
+    }) {
+        return Err("Some of the attached items are no longer in your inventory.".to_owned());
+    }
+
+    let ability_map = ecs.read_resource::<AbilityMap>();
+    let msm = ecs.read_resource::<MaterialStatManifest>();
+    let mut attached = Inventory::new_with_capacity(MAX_ATTACHMENTS);
+    for (slot, quantity) in attachments {
+        // Take the items one by one, to benefit from Inventory's stack handling
+        for _ in 0..quantity {
+            if let Some(item) = inventory.take(slot, &ability_map, &msm) {
+                if let Err(item) = attached.push(item) {
+                    // Each attachment has its own slot, so this should never happen
+                    let _ = inventory.push(item);
+                }
+            }
+        }
+    }
+    drop(inventories);
+
+    post_office.send(Mail {
+        id: 0,
+        sender,
+        sender_alias: sender_alias.clone(),
+        recipient,
+        recipient_alias: recipient_alias.clone(),
+        message,
+        attachments: attached,
+        sent_at: unix_time(),
+        returned: false,
+    });
+
+    let _ = ecs.write_storage().insert(
+        entity,
+        comp::InventoryUpdate::new(comp::InventoryUpdateEvent::Gave),
+    );
+    let mut trades = ecs.write_resource::<Trades>();
+    if trades.in_mutable_trade(&uid) {
+        trades.implicit_mutation_occurred(&uid);
+    }
+
+    // Let the recipient know straight away if they are online
+    if let Some(recipient_entity) = entity_of(ecs, recipient) {
+        if let Some(client) = ecs.read_storage::<Client>().get(recipient_entity) {
+            client.send_fallible(ServerGeneral::MailUpdate(post_office.mailbox(recipient)));
+            client.send_fallible(ServerGeneral::server_msg(
+                ChatType::Meta,
+                format!("You have a new letter from {}.", sender_alias),
+            ));
+        }
+    }
+
+    Ok(format!("Your letter to {} has been sent.", recipient_alias))
+}
+
+fn claim_mail(
+    ecs: &World,
+    entity: EcsEntity,
+    character_id: CharacterId,
+    mail: MailId,
+) -> Result<(), String> {
+    let uid = ecs.uid_from_entity(entity).ok_or_else(String::new)?;
+    if ecs.read_resource::<Trades>().in_immutable_trade(&uid) {
+        return Err("You can't collect letters while trading.".to_owned());
+    }
+
+    let mut post_office = ecs.write_resource::<PostOffice>();
+    let attachment_count = match post_office.get(mail) {
+        Some(letter) if letter.recipient == character_id => letter.attachments.populated_slots(),
+        _ => return Err("That letter is not in your mailbox.".to_owned()),
+    };
+
+    let mut inventories = ecs.write_storage::<Inventory>();
+    let inventory = inventories.get_mut(entity).ok_or_else(String::new)?;
+    if inventory.capacity() - inventory.populated_slots() < attachment_count {
+        return Err(
+            "You don't have enough space in your inventory for the attachments.".to_owned(),
+        );
+    }
+
+    if let Some(mut letter) = post_office.claim(mail) {
+        for item in letter.attachments.drain() {
+            // There is room, as checked above
+            let _ = inventory.push(item);
+        }
+    }
+    drop(inventories);
+
+    let _ = ecs.write_storage().insert(
+        entity,
+        comp::InventoryUpdate::new(comp::InventoryUpdateEvent::Given),
+    );
+    let mut trades = ecs.write_resource::<Trades>();
+    if trades.in_mutable_trade(&uid) {
+        trades.implicit_mutation_occurred(&uid);
+    }
+
+    if let Some(client) = ecs.read_storage::<Client>().get(entity) {
+        client.send_fallible(ServerGeneral::MailUpdate(post_office.mailbox(character_id)));
+    }
+
+    Ok(())
+}
+
+fn at_mailbox(server: &Server, entity: EcsEntity, pos: Vec3<i32>) -> bool {
+    let is_mailbox = server
+        .state
+        .terrain()
+        .get(pos)
+        .ok()
+        .and_then(|block| block.get_sprite())
+        == Some(SpriteKind::Mailbox);
+
+    is_mailbox && within_reach_of_block(server.state.ecs(), entity, pos)
+}
+
+fn inform(ecs: &World, entity: EcsEntity, message: String) {
+    if message.is_empty() {
+        return;
+    }
+    if let Some(client) = ecs.read_storage::<Client>().get(entity) {
+        client.send_fallible(ServerGeneral::server_msg(ChatType::Meta, message));
+    }
+}
@@ -1,16 +1,26 @@
-use crate::{state_ext::StateExt, Server};
-use common::event::{EventBus, ServerEvent};
+use crate::{presence::Presence, state_ext::StateExt, Server};
+use build::handle_build_manip;
+use combat_log::handle_combat_log;
+use common::{
+    character::CharacterId,
+    event::{EventBus, ServerEvent},
+};
 use common_base::span;
+use common_net::msg::PresenceKind;
 use entity_creation::{
-    handle_beam, handle_create_npc, handle_create_ship, handle_create_waypoint,
-    handle_initialize_character, handle_loaded_character_data, handle_shockwave, handle_shoot,
+    handle_beam, handle_create_npc, handle_create_ship, handle_create_storage,
+    handle_create_waypoint, handle_initialize_character, handle_loaded_character_data,
+    handle_shockwave, handle_shoot,
 };
 use entity_manipulation::{
     handle_aura, handle_buff, handle_combo_change, handle_damage, handle_delete, handle_destroy,
     handle_energy_change, handle_explosion, handle_knockback, handle_land_on_ground, handle_poise,
-    handle_respawn, handle_teleport_to,
+    handle_respawn, handle_teleport_to, handle_wear_equipment,
 };
+use fishing::handle_catch_fish;
 use group_manip::handle_group;
+use guild::handle_guild_manip;
+use housing::handle_sell_house;
 use information::handle_site_info;
 use interaction::{
     handle_create_sprite, handle_lantern, handle_mine_block, handle_mount, handle_npc_interaction,
@@ -18,18 +28,30 @@ use interaction::{
 };
 use inventory_manip::handle_inventory;
 use invite::{handle_invite, handle_invite_response};
+use mail::handle_mail_manip;
 use player::{handle_client_disconnect, handle_exit_ingame};
-use specs::{Builder, Entity as EcsEntity, WorldExt};
+use quest::{handle_complete_quest, handle_offer_quest};
+use specs::{Builder, Entity as EcsEntity, Join, World, WorldExt};
+use storage::handle_storage_manip;
 use trade::{cancel_trade_for, handle_process_trade_action};
 
+mod build;
+mod combat_log;
+mod duel;
 mod entity_creation;
 mod entity_manipulation;
+mod fishing;
 mod group_manip;
+pub(crate) mod guild;
+pub(crate) mod housing;
 mod information;
 mod interaction;
 mod inventory_manip;
 mod invite;
+mod mail;
 mod player;
+mod quest;
+mod storage;
 mod trade;
 
 pub enum Event {
@@ -99,6 +121,12 @@ impl Server {
                 ServerEvent::Destroy { entity, cause } => handle_destroy(self, entity, cause),
                 ServerEvent::InventoryManip(entity, manip) => handle_inventory(self, entity, manip),
                 ServerEvent::GroupManip(entity, manip) => handle_group(self, entity, manip),
+                ServerEvent::GuildManip(entity, manip) => handle_guild_manip(self, entity, manip),
+                ServerEvent::StorageManip(entity, manip) => {
+                    handle_storage_manip(self, entity, manip)
+                },
+                ServerEvent::MailManip(entity, manip) => handle_mail_manip(self, entity, manip),
+                ServerEvent::BuildManip(entity, manip) => handle_build_manip(self, entity, manip),
                 ServerEvent::Respawn(entity) => handle_respawn(&self, entity),
                 ServerEvent::LandOnGround { entity, vel } => {
                     handle_land_on_ground(&self, entity, vel)
@@ -173,6 +201,7 @@ impl Server {
                     rtsim_entity,
                 } => handle_create_ship(self, pos, ship, mountable, agent, rtsim_entity),
                 ServerEvent::CreateWaypoint(pos) => handle_create_waypoint(self, pos),
+                ServerEvent::CreateStorage(pos) => handle_create_storage(self, pos),
                 ServerEvent::ClientDisconnect(entity, reason) => {
                     frontend_events.push(handle_client_disconnect(self, entity, reason, false))
                 },
@@ -208,6 +237,14 @@ impl Server {
                 ServerEvent::ComboChange { entity, change } => {
                     handle_combo_change(&self, entity, change)
                 },
+                ServerEvent::CombatLog {
+                    source,
+                    target,
+                    kind,
+                } => handle_combat_log(&self, source, target, kind),
+                ServerEvent::WearEquipment { entity, slots } => {
+                    handle_wear_equipment(&self, entity, slots)
+                },
                 ServerEvent::RequestSiteInfo { entity, id } => handle_site_info(&self, entity, id),
                 ServerEvent::MineBlock { entity, pos, tool } => {
                     handle_mine_block(self, entity, pos, tool)
@@ -224,6 +261,14 @@ impl Server {
                 ServerEvent::CreateSprite { pos, sprite } => {
                     handle_create_sprite(self, pos, sprite)
                 },
+                ServerEvent::OfferQuest { giver, player } => {
+                    handle_offer_quest(&self, giver, player)
+                },
+                ServerEvent::CompleteQuest { entity, template } => {
+                    handle_complete_quest(self, entity, template)
+                },
+                ServerEvent::SellHouse { seller, buyer } => handle_sell_house(self, seller, buyer),
+                ServerEvent::CatchFish { entity, pos } => handle_catch_fish(self, entity, pos),
             }
         }
 
@@ -243,3 +288,19 @@ impl Server {
         frontend_events
     }
 }
+
+/// The character an entity is playing, if any
+fn character_of(ecs: &World, entity: EcsEntity) -> Option<CharacterId> {
+    match ecs.read_storage::<Presence>().get(entity)?.kind {
+        PresenceKind::Character(character_id) => Some(character_id),
+        PresenceKind::Spectator => None,
+    }
+}
+
+/// The entity playing a character, if the character is online
+fn entity_of(ecs: &World, character_id: CharacterId) -> Option<EcsEntity> {
+    (&ecs.entities(), &ecs.read_storage::<Presence>())
+        .join()
+        .find(|(_, presence)| presence.kind == PresenceKind::Character(character_id))
+        .map(|(entity, _)| entity)
+}
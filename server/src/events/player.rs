@@ -1,11 +1,11 @@
 use super::Event;
 use crate::{
-    client::Client, metrics::PlayerMetrics, persistence::character_updater::CharacterUpdater,
+    client::Client, metrics::PlayerMetrics, persistence::character_updater::CharacterUpdater, pet,
     presence::Presence, state_ext::StateExt, Server,
 };
 use common::{
     comp,
-    comp::group,
+    comp::{group, pet::PetPersistenceData},
     uid::{Uid, UidAllocator},
 };
 use common_base::span;
@@ -23,6 +23,7 @@ pub fn handle_exit_ingame(server: &mut Server, entity: EcsEntity) {
     // Note: If other `ServerEvent`s are referring to this entity they will be
     // disrupted
 
+    let maybe_uid = state.read_component_copied::<Uid>(entity);
     let maybe_admin = state.ecs().write_storage::<comp::Admin>().remove(entity);
     let maybe_group = state
         .ecs()
@@ -84,7 +85,7 @@ pub fn handle_exit_ingame(server: &mut Server, entity: EcsEntity) {
     state.ecs().write_storage::<group::Group>().remove(entity);
 
     // Sync the player's character data to the database
-    let entity = persist_entity(state, entity);
+    let entity = persist_entity(state, entity, maybe_uid);
 
     // Delete old entity
     if let Err(e) = state.delete_entity_recorded(entity) {
@@ -178,7 +179,8 @@ pub fn handle_client_disconnect(
 
     // Sync the player's character data to the database
     if !skip_persistence {
-        entity = persist_entity(state, entity);
+        let maybe_uid = state.read_component_copied::<Uid>(entity);
+        entity = persist_entity(state, entity, maybe_uid);
     }
 
     // Delete client entity
@@ -194,11 +196,28 @@ pub fn handle_client_disconnect(
 // temporarily unable to log in during this period to avoid
 // the race condition of their login fetching their old data
 // and overwriting the data saved here.
-fn persist_entity(state: &mut State, entity: EcsEntity) -> EcsEntity {
-    if let (Some(presence), Some(skill_set), Some(inventory), mut character_updater) = (
+// The pets of the character are saved with it and leave the world together
+// with their owner.
+fn persist_entity(state: &mut State, entity: EcsEntity, uid: Option<Uid>) -> EcsEntity {
+    super::quest::fail_escort_quests(state.ecs(), entity, uid);
+    super::guild::unload_guild(state.ecs(), entity);
+    super::housing::unload_house(state.ecs(), entity);
+    let pets = uid.map_or_else(Vec::new, |uid| pet::pets_of(state.ecs(), uid));
+    if let (
+        Some(presence),
+        Some(skill_set),
+        Some(inventory),
+        quest_log,
+        bank,
+        presets,
+        mut character_updater,
+    ) = (
         state.read_storage::<Presence>().get(entity),
         state.read_storage::<comp::SkillSet>().get(entity),
         state.read_storage::<comp::Inventory>().get(entity),
+        state.read_storage::<comp::QuestLog>().get(entity),
+        state.read_storage::<comp::Bank>().get(entity),
+        state.read_storage::<comp::LoadoutPresets>().get(entity),
         state.ecs().fetch_mut::<CharacterUpdater>(),
     ) {
         match presence.kind {
@@ -208,15 +227,41 @@ fn persist_entity(state: &mut State, entity: EcsEntity) -> EcsEntity {
                     .read_storage::<common::comp::Waypoint>()
                     .get(entity)
                     .cloned();
+                let pet_data = {
+                    let bodies = state.ecs().read_storage::<comp::Body>();
+                    let stats = state.ecs().read_storage::<comp::Stats>();
+                    pets.iter()
+                        .filter_map(|pet| {
+                            Some(PetPersistenceData::new(
+                                stats.get(*pet)?,
+                                *bodies.get(*pet)?,
+                            ))
+                        })
+                        .collect()
+                };
 
                 character_updater.add_pending_logout_update(
                     char_id,
-                    (skill_set.clone(), inventory.clone(), waypoint),
+                    (
+                        skill_set.clone(),
+                        inventory.clone(),
+                        waypoint,
+                        quest_log.cloned().unwrap_or_default(),
+                        pet_data,
+                        bank.cloned().unwrap_or_default(),
+                        presets.cloned().unwrap_or_default(),
+                    ),
                 );
             },
             PresenceKind::Spectator => { /* Do nothing, spectators do not need persisting */ },
         };
     }
 
+    for pet in pets {
+        if let Err(e) = state.delete_entity_recorded(pet) {
+            error!(?e, ?pet, "Failed to delete pet of logged out character");
+        }
+    }
+
     entity
 }
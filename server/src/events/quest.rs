@@ -0,0 +1,274 @@
+use super::entity_manipulation::handle_exp_gain;
+use crate::{client::Client, Server, StateExt};
+use common::{
+    assets::AssetExt,
+    comp::{
+        self,
+        item::ItemDef,
+        quest::{Destination, Objective, Quest, QuestTemplate},
+        Alignment, Body, Inventory, Item, QuestLog, SkillSet, Stats, UnresolvedChatMsg,
+    },
+    outcome::Outcome,
+    uid::Uid,
+};
+use common_net::{
+    msg::{Notification, ServerGeneral},
+    sync::WorldSyncExt,
+};
+use common_state::State;
+use specs::{Builder, Entity as EcsEntity, WorldExt};
+use std::sync::Arc;
+use tracing::error;
+use vek::*;
+
+/// How many times to draw from the quest pool before giving up on finding a
+/// quest that the player doesn't already have
+const MAX_QUEST_DRAWS: usize = 5;
+
+pub fn handle_offer_quest(server: &Server, giver: EcsEntity, player: EcsEntity) {
+    let state = server.state();
+    let ecs = state.ecs();
+    let uids = ecs.read_storage::<Uid>();
+    let (giver_uid, player_uid) = match (uids.get(giver), uids.get(player)) {
+        (Some(giver_uid), Some(player_uid)) => (*giver_uid, *player_uid),
+        _ => return,
+    };
+    let (giver_name, giver_pos) = match (
+        ecs.read_storage::<Stats>().get(giver),
+        ecs.read_storage::<comp::Pos>().get(giver),
+    ) {
+        (Some(stats), Some(pos)) => (stats.name.clone(), pos.0),
+        _ => return,
+    };
+    // Only NPCs that aren't following anyone can be escorted
+    let can_escort = matches!(
+        ecs.read_storage::<Alignment>().get(giver),
+        Some(Alignment::Npc)
+    );
+    drop(uids);
+
+    let mut quest_logs = ecs.write_storage::<QuestLog>();
+    let quest_log = match quest_logs.get_mut(player) {
+        Some(quest_log) => quest_log,
+        None => return,
+    };
+
+    let quest = if quest_log.is_full() {
+        None
+    } else {
+        (0..MAX_QUEST_DRAWS)
+            .map(|_| QuestTemplate::choose())
+            .filter(|template| !quest_log.has_quest(template))
+            .find_map(|template| {
+                let objective = QuestTemplate::load_expect(&template)
+                    .read()
+                    .objective
+                    .clone();
+                if matches!(objective, Objective::Escort) && !can_escort {
+                    return None;
+                }
+                let destination = if objective.needs_destination() {
+                    Some(choose_destination(server, giver_pos.xy())?)
+                } else {
+                    None
+                };
+                Some(Quest {
+                    template,
+                    giver: giver_name.clone(),
+                    destination,
+                    escortee: matches!(objective, Objective::Escort).then(|| giver_uid),
+                    progress: 0,
+                })
+            })
+    };
+
+    let msg = match quest {
+        Some(quest) => {
+            if quest.escortee.is_some() {
+                if let Err(e) = ecs
+                    .write_storage()
+                    .insert(giver, Alignment::Owned(player_uid))
+                {
+                    error!(?e, "Failed to make escorted NPC follow the player");
+                }
+            }
+            quest_log.active.push(quest);
+            if let Some(client) = ecs.read_storage::<Client>().get(player) {
+                client.send_fallible(ServerGeneral::QuestUpdate(quest_log.clone()));
+            }
+            "npc.speech.quest_offered"
+        },
+        None if quest_log.is_full() => "npc.speech.quest_log_full",
+        None => "npc.speech.villager_no_work",
+    };
+    drop(quest_logs);
+
+    state.send_chat(UnresolvedChatMsg::npc_tell(
+        giver_uid,
+        player_uid,
+        msg.to_string(),
+    ));
+}
+
+/// Fail the escort quests of a character that leaves the world, since the
+/// escorted NPCs can't leave with it. The NPCs go back to their own business.
+pub fn fail_escort_quests(ecs: &specs::World, entity: EcsEntity, uid: Option<Uid>) {
+    let mut quest_logs = ecs.write_storage::<QuestLog>();
+    let mut alignments = ecs.write_storage::<Alignment>();
+    if let Some(quest_log) = quest_logs.get_mut(entity) {
+        quest_log.active.retain(|quest| {
+            let escortee = match quest.escortee {
+                Some(escortee) => escortee,
+                None => return true,
+            };
+            if let (Some(escortee), Some(uid)) = (ecs.entity_from_uid(escortee.into()), uid) {
+                if alignments.get(escortee) == Some(&Alignment::Owned(uid)) {
+                    let _ = alignments.insert(escortee, Alignment::Npc);
+                }
+            }
+            false
+        });
+    }
+}
+
+/// Choose a site near the given position for a quest to lead to
+#[cfg(feature = "worldgen")]
+fn choose_destination(server: &Server, near: Vec2<f32>) -> Option<Destination> {
+    use common::terrain::TerrainChunkSize;
+    use rand::seq::SliceRandom;
+
+    // Sites closer than this are most likely the one the quest giver is in
+    const MIN_DESTINATION_DIST: f32 = 512.0;
+    // Choose between this many of the closest sites, so that journeys stay short
+    const DESTINATION_CHOICES: usize = 3;
+
+    let mut sites = server
+        .world
+        .civs()
+        .sites()
+        .filter(|site| site.is_settlement())
+        .filter_map(|site| {
+            Some(Destination {
+                name: server.index.sites[site.site_tmp?].name().to_string(),
+                wpos: TerrainChunkSize::center_wpos(site.center),
+            })
+        })
+        .filter(|site| site.wpos.as_::<f32>().distance(near) > MIN_DESTINATION_DIST)
+        .collect::<Vec<_>>();
+    sites.sort_by_key(|site| site.wpos.as_::<f32>().distance_squared(near) as i64);
+    sites.truncate(DESTINATION_CHOICES);
+    sites.choose(&mut rand::thread_rng()).cloned()
+}
+
+#[cfg(not(feature = "worldgen"))]
+fn choose_destination(_server: &Server, _near: Vec2<f32>) -> Option<Destination> { None }
+
+/// Count a kill towards the kill objectives of the killer
+pub fn count_quest_kill(state: &State, killer: EcsEntity, body: &Body) {
+    if let Some(quest_log) = state.ecs().write_storage::<QuestLog>().get_mut(killer) {
+        if quest_log.count_kill(body) {
+            if let Some(client) = state.ecs().read_storage::<Client>().get(killer) {
+                client.send_fallible(ServerGeneral::QuestUpdate(quest_log.clone()));
+            }
+        }
+    }
+}
+
+pub fn handle_complete_quest(server: &mut Server, entity: EcsEntity, template: String) {
+    let state = server.state_mut();
+    let mut dropped_items = Vec::new();
+    {
+        let ecs = state.ecs();
+        let mut quest_logs = ecs.write_storage::<QuestLog>();
+        let mut inventories = ecs.write_storage::<Inventory>();
+        let (quest_log, mut inventory) =
+            match (quest_logs.get_mut(entity), inventories.get_mut(entity)) {
+                (Some(quest_log), Some(inventory)) => (quest_log, inventory),
+                _ => return,
+            };
+        let index = match quest_log
+            .active
+            .iter()
+            .position(|quest| quest.template == template && quest.is_complete())
+        {
+            Some(index) => index,
+            None => return,
+        };
+
+        // Hand over the items the quest asked for
+        if let Objective::Fetch { item, amount } | Objective::Deliver { item, amount } =
+            quest_log.active[index].objective()
+        {
+            let item_def = Arc::<ItemDef>::load_expect_cloned(&item);
+            if !inventory.remove_item_amount(&item_def, amount) {
+                return;
+            }
+        }
+
+        let quest = quest_log.active.remove(index);
+        quest_log.completed += 1;
+
+        // The escorted NPC goes back to its own business
+        if let Some(escortee) = quest
+            .escortee
+            .and_then(|uid| ecs.entity_from_uid(uid.into()))
+        {
+            let _ = ecs.write_storage().insert(escortee, Alignment::Npc);
+        }
+
+        let reward = quest.template().read().reward.clone();
+        for (item, amount) in reward.items.iter() {
+            let mut item = Item::new_from_asset_expect(item);
+            let items = if item.is_stackable() {
+                let _ = item.set_amount(*amount);
+                vec![item]
+            } else {
+                std::iter::repeat_with(|| Item::new_from_asset_expect(item.item_definition_id()))
+                    .take(*amount as usize)
+                    .collect()
+            };
+            dropped_items.extend(
+                items
+                    .into_iter()
+                    .filter_map(|item| inventory.push(item).err()),
+            );
+        }
+        if let (Some(mut skill_set), Some(uid)) = (
+            ecs.write_storage::<SkillSet>().get_mut(entity),
+            ecs.read_storage::<Uid>().get(entity),
+        ) {
+            if reward.exp > 0 {
+                handle_exp_gain(
+                    reward.exp as f32,
+                    &inventory,
+                    &mut skill_set,
+                    uid,
+                    &mut ecs.write_resource::<Vec<Outcome>>(),
+                );
+            }
+        }
+        let _ = ecs.write_storage().insert(
+            entity,
+            comp::InventoryUpdate::new(comp::InventoryUpdateEvent::Given),
+        );
+
+        if let Some(client) = ecs.read_storage::<Client>().get(entity) {
+            client.send_fallible(ServerGeneral::QuestUpdate(quest_log.clone()));
+            client.send_fallible(ServerGeneral::Notification(Notification::QuestComplete(
+                quest.title(),
+            )));
+        }
+    }
+
+    // Rewards that didn't fit into the inventory are dropped at the player's feet
+    if let Some(pos) = state.ecs().read_storage::<comp::Pos>().get(entity).copied() {
+        for item in dropped_items {
+            state
+                .create_object(Default::default(), comp::object::Body::Pouch)
+                .with(comp::Pos(pos.0 + Vec3::unit_z()))
+                .with(item)
+                .with(comp::Vel(Vec3::zero()))
+                .build();
+        }
+    }
+}
@@ -0,0 +1,221 @@
+use specs::{world::WorldExt, Entity as EcsEntity, Join, World};
+use tracing::warn;
+use vek::*;
+
+use common::{
+    comp::{self, inventory::slot::InvSlotId, Inventory, StorageManip, StorageTarget},
+    consts::MAX_PICKUP_RANGE,
+    terrain::SpriteKind,
+    trade::Trades,
+    uid::Uid,
+    util::find_dist::{self, FindDist},
+    vol::ReadVol,
+};
+use common_net::{msg::ServerGeneral, sync::WorldSyncExt};
+
+use crate::{client::Client, storage::StorageContainers, Server};
+
+/// Where the items of an open storage are kept
+#[derive(Copy, Clone)]
+enum StorageContents {
+    /// In the storage container at this position
+    Container(Vec3<i32>),
+    /// In the bank of the player
+    Bank,
+}
+
+pub fn handle_storage_manip(server: &mut Server, entity: EcsEntity, manip: StorageManip) {
+    let ecs = server.state.ecs();
+
+    match manip {
+        StorageManip::OpenBank(pos) => {
+            let at_strongbox = server
+                .state
+                .terrain()
+                .get(pos)
+                .ok()
+                .and_then(|block| block.get_sprite())
+                == Some(SpriteKind::Strongbox);
+
+            if at_strongbox && within_reach_of_block(ecs, entity, pos) {
+                if let Some(bank) = ecs.read_storage::<comp::Bank>().get(entity) {
+                    let _ = ecs.write_storage().insert(entity, StorageTarget::Bank(pos));
+                    send_contents(ecs, entity, Some(&bank.0));
+                }
+            }
+        },
+        StorageManip::Close => {
+            ecs.write_storage::<StorageTarget>().remove(entity);
+            send_contents(ecs, entity, None);
+        },
+        StorageManip::Deposit(slot) => move_item(ecs, entity, slot, true),
+        StorageManip::Withdraw(slot) => move_item(ecs, entity, slot, false),
+    }
+}
+
+/// Opens a storage container for a player that interacted with it
+pub fn open_container(ecs: &World, entity: EcsEntity, container: EcsEntity) {
+    let (container_uid, storage_pos) = match (
+        ecs.read_storage::<Uid>().get(container),
+        ecs.read_storage::<comp::Storage>().get(container),
+    ) {
+        (Some(uid), Some(storage)) => (*uid, storage.pos),
+        _ => return,
+    };
+
+    if within_reach_of_entity(ecs, entity, container) {
+        if let Some(contents) = ecs.read_resource::<StorageContainers>().get(storage_pos) {
+            let _ = ecs
+                .write_storage()
+                .insert(entity, StorageTarget::Container(container_uid));
+            send_contents(ecs, entity, Some(contents));
+        }
+    }
+}
+
+/// Moves an item between the inventory of a player and the storage they have
+/// open, in whichever direction is asked for. Whoever else is looking into the
+/// same container sees the change as well.
+fn move_item(ecs: &World, entity: EcsEntity, slot: InvSlotId, deposit: bool) {
+    let uid = match ecs.uid_from_entity(entity) {
+        Some(uid) => uid,
+        None => return,
+    };
+    // Moving items can mutate a trade the player is part of
+    if ecs.read_resource::<Trades>().in_immutable_trade(&uid) {
+        return;
+    }
+
+    let contents = match open_contents(ecs, entity) {
+        Some(contents) => contents,
+        None => {
+            // The storage went out of reach or no longer exists
+            ecs.write_storage::<StorageTarget>().remove(entity);
+            send_contents(ecs, entity, None);
+            return;
+        },
+    };
+
+    let mut inventories = ecs.write_storage::<comp::Inventory>();
+    let mut banks = ecs.write_storage::<comp::Bank>();
+    let mut storage_containers = ecs.write_resource::<StorageContainers>();
+
+    let inventory = match inventories.get_mut(entity) {
+        Some(inventory) => inventory,
+        None => return,
+    };
+    let storage = match contents {
+        StorageContents::Container(pos) => storage_containers.get_mut(pos),
+        StorageContents::Bank => banks.get_mut(entity).map(|bank| &mut bank.0),
+    };
+    let storage = match storage {
+        Some(storage) => storage,
+        None => {
+            warn!(?entity, "Open storage of player has no contents");
+            return;
+        },
+    };
+
+    let (from, to) = if deposit {
+        (inventory, storage)
+    } else {
+        (storage, inventory)
+    };
+    if let Some(item) = from.remove(slot) {
+        if let Err(item) = to.push(item) {
+            // There was no room, so the item stays where it was
+            let _ = from.insert_at(slot, item);
+            return;
+        }
+    } else {
+        return;
+    }
+
+    let _ = ecs.write_storage().insert(
+        entity,
+        comp::InventoryUpdate::new(comp::InventoryUpdateEvent::Swapped),
+    );
+
+    let mut trades = ecs.write_resource::<Trades>();
+    if trades.in_mutable_trade(&uid) {
+        trades.implicit_mutation_occurred(&uid);
+    }
+
+    match contents {
+        StorageContents::Container(pos) => {
+            let targets = ecs.read_storage::<StorageTarget>();
+            let containers = ecs.read_storage::<comp::Storage>();
+            let clients = ecs.read_storage::<Client>();
+            let viewers = (&ecs.entities(), &targets, &clients)
+                .join()
+                .filter(|(_, target, _)| match target {
+                    StorageTarget::Container(uid) => ecs
+                        .entity_from_uid(uid.0)
+                        .and_then(|container| containers.get(container))
+                        .map_or(false, |storage| storage.pos == pos),
+                    StorageTarget::Bank(_) => false,
+                });
+            for (_, _, client) in viewers {
+                client.send_fallible(ServerGeneral::StorageUpdate(
+                    storage_containers.get(pos).cloned(),
+                ));
+            }
+        },
+        StorageContents::Bank => {
+            if let Some(client) = ecs.read_storage::<Client>().get(entity) {
+                client.send_fallible(ServerGeneral::StorageUpdate(
+                    banks.get(entity).map(|bank| bank.0.clone()),
+                ));
+            }
+        },
+    }
+}
+
+/// Finds where the items of the storage a player has open are kept, as long
+/// as the storage is still within reach
+fn open_contents(ecs: &World, entity: EcsEntity) -> Option<StorageContents> {
+    match *ecs.read_storage::<StorageTarget>().get(entity)? {
+        StorageTarget::Container(uid) => {
+            let container = ecs.entity_from_uid(uid.0)?;
+            let storage = *ecs.read_storage::<comp::Storage>().get(container)?;
+            within_reach_of_entity(ecs, entity, container)
+                .then_some(StorageContents::Container(storage.pos))
+        },
+        StorageTarget::Bank(pos) => {
+            within_reach_of_block(ecs, entity, pos).then_some(StorageContents::Bank)
+        },
+    }
+}
+
+fn send_contents(ecs: &World, entity: EcsEntity, contents: Option<&Inventory>) {
+    if let Some(client) = ecs.read_storage::<Client>().get(entity) {
+        client.send_fallible(ServerGeneral::StorageUpdate(contents.cloned()));
+    }
+}
+
+fn cylinder_of(ecs: &World, entity: EcsEntity) -> Option<find_dist::Cylinder> {
+    Some(find_dist::Cylinder::from_components(
+        ecs.read_storage::<comp::Pos>().get(entity)?.0,
+        ecs.read_storage::<comp::Scale>().get(entity).copied(),
+        ecs.read_storage::<comp::Collider>().get(entity),
+        ecs.read_storage::<comp::CharacterState>().get(entity),
+    ))
+}
+
+fn within_reach_of_entity(ecs: &World, entity: EcsEntity, target: EcsEntity) -> bool {
+    match (cylinder_of(ecs, entity), cylinder_of(ecs, target)) {
+        (Some(cylinder), Some(target)) => cylinder.min_distance(target) < MAX_PICKUP_RANGE,
+        _ => false,
+    }
+}
+
+pub fn within_reach_of_block(ecs: &World, entity: EcsEntity, pos: Vec3<i32>) -> bool {
+    cylinder_of(ecs, entity).map_or(false, |cylinder| {
+        find_dist::Cube {
+            min: pos.as_(),
+            side_length: 1.0,
+        }
+        .min_distance(cylinder)
+            < MAX_PICKUP_RANGE
+    })
+}
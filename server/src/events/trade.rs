@@ -9,6 +9,11 @@ use common::{
     },
     trade::{PendingTrade, ReducedInventory, TradeAction, TradeId, TradeResult, Trades},
 };
+#[cfg(feature = "worldgen")]
+use common::{
+    comp::{inventory::trade_pricing::TradePricing, Item},
+    trade::Good,
+};
 use common_net::{
     msg::ServerGeneral,
     sync::{Uid, WorldSyncExt},
@@ -17,7 +22,8 @@ use hashbrown::hash_map::Entry;
 use specs::{world::WorldExt, Entity as EcsEntity};
 use std::cmp::Ordering;
 use tracing::{error, trace};
-use world::IndexOwned;
+#[cfg(feature = "worldgen")]
+use world::sim2::EconomySim;
 
 fn notify_agent_simple(
     mut agents: specs::WriteStorage<Agent>,
@@ -29,15 +35,16 @@ fn notify_agent_simple(
     }
 }
 
+#[cfg(feature = "worldgen")]
 fn notify_agent_prices(
     mut agents: specs::WriteStorage<Agent>,
-    index: &IndexOwned,
+    economy: &EconomySim,
     entity: EcsEntity,
     event: AgentEvent,
 ) {
     if let Some((Some(site_id), agent)) = agents.get_mut(entity).map(|a| (a.behavior.trade_site, a))
     {
-        let prices = index.get_site_prices(site_id);
+        let prices = economy.get_site_prices(site_id);
         if let AgentEvent::UpdatePendingTrade(boxval) = event {
             // Box<(tid, pend, _, inventories)>) = event {
             agent
@@ -108,6 +115,8 @@ pub fn handle_process_trade_action(
                     let mut inventories: [Option<ReducedInventory>; 2] = [None, None];
                     let mut prices = None;
                     let agents = server.state.ecs().read_storage::<Agent>();
+                    #[cfg(feature = "worldgen")]
+                    let economy = server.state.ecs().read_resource::<EconomySim>();
                     // sadly there is no map and collect on arrays
                     for i in 0..2 {
                         // parties.len()) {
@@ -127,7 +136,7 @@ pub fn handle_process_trade_action(
                                     agents
                                         .get(e)
                                         .and_then(|a| a.behavior.trade_site)
-                                        .and_then(|id| server.index.get_site_prices(id))
+                                        .and_then(|id| economy.get_site_prices(id))
                                 });
                             }
                         }
@@ -146,7 +155,7 @@ pub fn handle_process_trade_action(
                             #[cfg(feature = "worldgen")]
                             notify_agent_prices(
                                 server.state.ecs().write_storage::<Agent>(),
-                                &server.index,
+                                &economy,
                                 e,
                                 AgentEvent::UpdatePendingTrade(Box::new((
                                     trade_id,
@@ -268,6 +277,21 @@ fn commit_trade(ecs: &specs::World, trade: &PendingTrade) -> TradeResult {
             }
         }
     }
+    // The goods each party gave away, to be accounted for in the economy of any
+    // site that a party is trading on behalf of
+    #[cfg(feature = "worldgen")]
+    let goods = {
+        let goods = |items: &[Item]| {
+            items
+                .iter()
+                .map(|item| {
+                    let (good, factor) = TradePricing::get_material(item.item_definition_id());
+                    (good, factor * item.amount() as f32)
+                })
+                .collect::<Vec<(Good, f32)>>()
+        };
+        [goods(&items[0]), goods(&items[1])]
+    };
     for who in [0, 1].iter().cloned() {
         if let Err(leftovers) = inventories
             .get_mut(entities[1 - who])
@@ -282,5 +306,26 @@ fn commit_trade(ecs: &specs::World, trade: &PendingTrade) -> TradeResult {
             );
         }
     }
+    #[cfg(feature = "worldgen")]
+    {
+        let agents = ecs.read_storage::<Agent>();
+        let mut economy = ecs.write_resource::<EconomySim>();
+        for who in [0, 1].iter().cloned() {
+            if let Some(site_id) = agents
+                .get(entities[who])
+                .and_then(|a| a.behavior.trade_site)
+            {
+                // The site gains what the other party gave away, and loses what its merchant
+                // gave away
+                economy.record_trade(
+                    site_id,
+                    goods[1 - who]
+                        .iter()
+                        .copied()
+                        .chain(goods[who].iter().map(|(good, amount)| (*good, -amount))),
+                );
+            }
+        }
+    }
     TradeResult::Completed
 }
@@ -0,0 +1,169 @@
+use common::{
+    farming::CropDef,
+    terrain::{SpriteKind, TerrainChunkSize},
+    vol::RectVolSize,
+};
+use hashbrown::{HashMap, HashSet};
+use vek::*;
+
+/// How many in-game seconds crops grow for before all of them are saved, so
+/// that little of their growth between stages is lost if the server crashes
+const GROWTH_SAVE_INTERVAL: f64 = 3600.0;
+
+/// A crop planted by a player
+#[derive(Clone, Debug, PartialEq)]
+pub struct Crop {
+    /// Id of the crop in the crop manifest
+    pub kind: String,
+    /// How many in-game seconds the crop has been growing for
+    pub growth: f64,
+}
+
+/// Every crop planted in the world, kept under the block position of its
+/// sprite. Crops are loaded from the database when the server starts and keep
+/// growing whether or not their chunk is loaded, their sprites being placed
+/// whenever the chunk is loaded.
+#[derive(Default)]
+pub struct Crops {
+    crops: HashMap<Vec3<i32>, Crop>,
+    /// Crops that were planted, removed or reached a new stage of growth since
+    /// they were last saved
+    modified: HashSet<Vec3<i32>>,
+    /// The time of day when the crops last grew
+    pub last_time_of_day: Option<f64>,
+    /// How many in-game seconds the crops grew for since all of them were last
+    /// saved
+    unsaved_growth: f64,
+}
+
+impl Crops {
+    pub fn new(crops: impl IntoIterator<Item = (Vec3<i32>, Crop)>) -> Self {
+        Self {
+            crops: crops.into_iter().collect(),
+            ..Default::default()
+        }
+    }
+
+    pub fn get(&self, pos: Vec3<i32>) -> Option<&Crop> { self.crops.get(&pos) }
+
+    /// Plants a crop at the given position unless there already is one.
+    /// Returns whether the crop was planted.
+    pub fn plant(&mut self, pos: Vec3<i32>, kind: String) -> bool {
+        if self.crops.contains_key(&pos) {
+            return false;
+        }
+        self.crops.insert(pos, Crop { kind, growth: 0.0 });
+        self.modified.insert(pos);
+        true
+    }
+
+    /// Removes the crop at the given position, such as when it was harvested
+    pub fn remove(&mut self, pos: Vec3<i32>) -> Option<Crop> {
+        let crop = self.crops.remove(&pos)?;
+        self.modified.insert(pos);
+        Some(crop)
+    }
+
+    /// Grows the crop at the given position for `dt` in-game seconds. Returns
+    /// the sprite of its new stage if it grew into one.
+    pub fn grow(&mut self, pos: Vec3<i32>, dt: f64, def: &CropDef) -> Option<SpriteKind> {
+        let crop = self.crops.get_mut(&pos)?;
+        let stage = def.stage(crop.growth);
+        crop.growth += dt.max(0.0);
+        if def.stage(crop.growth) != stage {
+            self.modified.insert(pos);
+            Some(def.sprite(crop.growth))
+        } else {
+            None
+        }
+    }
+
+    /// Notes that the crops grew for `dt` in-game seconds, marking all of them
+    /// to be saved once they grew for long enough since they were last saved
+    pub fn note_growth(&mut self, dt: f64) {
+        self.unsaved_growth += dt.max(0.0);
+        if self.unsaved_growth >= GROWTH_SAVE_INTERVAL {
+            self.save_growth();
+        }
+    }
+
+    /// Marks every crop to be saved along with its current growth, such as when
+    /// the server shuts down
+    pub fn save_growth(&mut self) {
+        self.unsaved_growth = 0.0;
+        self.modified.extend(self.crops.keys().copied());
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = Vec3<i32>> + '_ { self.crops.keys().copied() }
+
+    /// The crops within the given chunk
+    pub fn in_chunk(&self, key: Vec2<i32>) -> impl Iterator<Item = (Vec3<i32>, &Crop)> + '_ {
+        self.crops
+            .iter()
+            .map(|(pos, crop)| (*pos, crop))
+            .filter(move |(pos, _)| {
+                pos.xy()
+                    .map2(TerrainChunkSize::RECT_SIZE, |e, sz| e.div_euclid(sz as i32))
+                    == key
+            })
+    }
+
+    /// Takes the crops that changed since this was last called, to be saved.
+    /// Crops that were removed come without a crop.
+    pub fn take_modified(&mut self) -> Vec<(Vec3<i32>, Option<Crop>)> {
+        let crops = &self.crops;
+        self.modified
+            .drain()
+            .map(|pos| (pos, crops.get(&pos).cloned()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_new_stages_are_saved() {
+        let def = CropDef {
+            seeds: String::new(),
+            stages: vec![SpriteKind::Seedling, SpriteKind::Sprout, SpriteKind::Carrot],
+            stage_duration: 100.0,
+            harvest: String::new(),
+        };
+        let pos = Vec3::new(1, 2, 3);
+        let mut crops = Crops::default();
+        assert!(crops.plant(pos, "carrot".to_owned()));
+        assert!(!crops.plant(pos, "tomato".to_owned()));
+        assert_eq!(crops.take_modified().len(), 1);
+
+        assert_eq!(crops.grow(pos, 60.0, &def), None);
+        assert!(crops.take_modified().is_empty());
+        assert_eq!(crops.grow(pos, 60.0, &def), Some(SpriteKind::Sprout));
+        assert_eq!(
+            crops.take_modified()[0].1.as_ref().map(|c| c.growth),
+            Some(120.0)
+        );
+
+        crops.remove(pos);
+        assert_eq!(crops.take_modified(), vec![(pos, None)]);
+    }
+
+    #[test]
+    fn growth_is_saved_periodically() {
+        let pos = Vec3::new(1, 2, 3);
+        let mut crops = Crops::new(vec![(pos, Crop {
+            kind: "carrot".to_owned(),
+            growth: 10.0,
+        })]);
+        crops.note_growth(GROWTH_SAVE_INTERVAL / 2.0);
+        assert!(crops.take_modified().is_empty());
+        crops.note_growth(GROWTH_SAVE_INTERVAL / 2.0);
+        assert_eq!(crops.take_modified().len(), 1);
+        crops.note_growth(GROWTH_SAVE_INTERVAL / 2.0);
+        assert!(crops.take_modified().is_empty());
+
+        crops.save_growth();
+        assert_eq!(crops.take_modified().len(), 1);
+    }
+}
@@ -0,0 +1,103 @@
+#[cfg(not(feature = "worldgen"))]
+use crate::test_world::World;
+use common::terrain::BiomeKind;
+use rand::Rng;
+use std::time::Duration;
+use vek::*;
+#[cfg(feature = "worldgen")] use world::World;
+
+const SECONDS_PER_DAY: f64 = 24.0 * 60.0 * 60.0;
+
+/// The kind of water a fishing line is cast into, which decides how often fish
+/// bite and what can be caught
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FishingWater {
+    Ocean,
+    Lake,
+    River,
+}
+
+impl FishingWater {
+    /// The water of a biome, where rivers are anything that isn't an ocean or
+    /// a lake
+    pub fn from_biome(biome: BiomeKind) -> Self {
+        match biome {
+            BiomeKind::Ocean => FishingWater::Ocean,
+            BiomeKind::Lake => FishingWater::Lake,
+            _ => FishingWater::River,
+        }
+    }
+
+    pub fn loot_table(&self) -> &'static str {
+        match self {
+            FishingWater::Ocean => "common.loot_tables.fishing.ocean",
+            FishingWater::Lake => "common.loot_tables.fishing.lake",
+            FishingWater::River => "common.loot_tables.fishing.river",
+        }
+    }
+
+    /// How many seconds it takes for a fish to bite on average, at the given
+    /// time of day. Fish bite most around dawn and dusk, and least in the
+    /// middle of the day.
+    pub fn mean_bite_secs(&self, time_of_day: f64) -> f32 {
+        let base = match self {
+            FishingWater::Ocean => 20.0,
+            FishingWater::Lake => 15.0,
+            FishingWater::River => 12.0,
+        };
+        let hour = time_of_day.rem_euclid(SECONDS_PER_DAY) / 3600.0;
+        let time_factor = match hour as u32 {
+            5..=7 | 18..=20 => 0.6,
+            8..=17 => 1.5,
+            _ => 1.0,
+        };
+        base * time_factor
+    }
+
+    /// Picks how long it takes for the next fish to bite, at the given time of
+    /// day
+    pub fn bite_after(&self, time_of_day: f64, rng: &mut impl Rng) -> Duration {
+        let mean = self.mean_bite_secs(time_of_day);
+        // Bites are exponentially distributed, but never instant nor endless
+        let wait = -mean * (1.0 - rng.gen::<f32>()).ln();
+        Duration::from_secs_f32(wait.clamp(2.0, mean * 4.0))
+    }
+}
+
+/// The kind of water at a position, from the biome the world simulation gives
+/// it
+pub fn water_at(world: &World, pos: Vec3<f32>) -> FishingWater {
+    #[cfg(feature = "worldgen")]
+    {
+        FishingWater::from_biome(
+            world
+                .sim()
+                .get_wpos(pos.xy().map(|e| e as i32))
+                .map(|chunk| chunk.get_biome())
+                .unwrap_or_default(),
+        )
+    }
+    #[cfg(not(feature = "worldgen"))]
+    {
+        let _ = (world, pos);
+        FishingWater::River
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fish_bite_more_at_dusk() {
+        let hour = 3600.0;
+        for water in [FishingWater::Ocean, FishingWater::Lake, FishingWater::River].iter() {
+            assert!(water.mean_bite_secs(19.0 * hour) < water.mean_bite_secs(12.0 * hour));
+            // Days wrap around
+            assert_eq!(
+                water.mean_bite_secs(6.0 * hour),
+                water.mean_bite_secs(30.0 * hour)
+            );
+        }
+    }
+}
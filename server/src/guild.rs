@@ -0,0 +1,326 @@
+use common::{
+    character::CharacterId,
+    comp::guild::{GuildId, GuildInfo, GuildMember, GuildRank},
+};
+use hashbrown::HashMap;
+
+/// A change to the guilds that is yet to be saved
+#[derive(Clone, Debug)]
+pub enum GuildChange {
+    Create {
+        guild: GuildId,
+        name: String,
+        tag: String,
+    },
+    Disband(GuildId),
+    SetMember {
+        character_id: CharacterId,
+        guild: GuildId,
+        rank: GuildRank,
+    },
+    RemoveMember(CharacterId),
+}
+
+pub struct GuildData {
+    pub name: String,
+    pub tag: String,
+    pub members: HashMap<CharacterId, GuildRank>,
+}
+
+/// Every guild and its members, including members who are offline. Guilds
+/// are loaded from the database when the server starts, and changes are saved
+/// by the persistence system.
+#[derive(Default)]
+pub struct GuildManager {
+    guilds: HashMap<GuildId, GuildData>,
+    members: HashMap<CharacterId, GuildId>,
+    next_id: GuildId,
+    changes: Vec<GuildChange>,
+}
+
+impl GuildManager {
+    pub fn new(
+        guilds: impl IntoIterator<Item = (GuildId, String, String)>,
+        members: impl IntoIterator<Item = (CharacterId, GuildId, GuildRank)>,
+    ) -> Self {
+        let mut manager = Self {
+            guilds: guilds
+                .into_iter()
+                .map(|(id, name, tag)| {
+                    (id, GuildData {
+                        name,
+                        tag,
+                        members: HashMap::new(),
+                    })
+                })
+                .collect(),
+            ..Default::default()
+        };
+        manager.next_id = manager.guilds.keys().max().map_or(1, |id| id + 1);
+        for (character_id, guild, rank) in members {
+            if let Some(data) = manager.guilds.get_mut(&guild) {
+                data.members.insert(character_id, rank);
+                manager.members.insert(character_id, guild);
+            }
+        }
+
+        // Guilds whose members were all deleted are disbanded, and guilds whose
+        // leader was deleted get a new one
+        let guilds = manager.guilds.keys().copied().collect::<Vec<_>>();
+        for guild in guilds {
+            if manager.guilds[&guild].members.is_empty() {
+                manager.disband(guild);
+            } else {
+                manager.ensure_leader(guild);
+            }
+        }
+        manager
+    }
+
+    pub fn get(&self, guild: GuildId) -> Option<&GuildData> { self.guilds.get(&guild) }
+
+    pub fn guild_of(&self, character_id: CharacterId) -> Option<(GuildId, &GuildData)> {
+        let guild = *self.members.get(&character_id)?;
+        Some((guild, self.guilds.get(&guild)?))
+    }
+
+    pub fn rank_of(&self, character_id: CharacterId) -> Option<GuildRank> {
+        self.guild_of(character_id)
+            .and_then(|(_, data)| data.members.get(&character_id).copied())
+    }
+
+    /// Finds the guild with the given name or tag, ignoring case
+    pub fn find(&self, name_or_tag: &str) -> Option<GuildId> {
+        self.guilds
+            .iter()
+            .find(|(_, data)| {
+                data.name.eq_ignore_ascii_case(name_or_tag)
+                    || data.tag.eq_ignore_ascii_case(name_or_tag)
+            })
+            .map(|(id, _)| *id)
+    }
+
+    /// Founds a guild with the given character as its leader
+    pub fn create(&mut self, name: String, tag: String, leader: CharacterId) -> GuildId {
+        let guild = self.next_id;
+        self.next_id += 1;
+        self.changes.push(GuildChange::Create {
+            guild,
+            name: name.clone(),
+            tag: tag.clone(),
+        });
+        self.guilds.insert(guild, GuildData {
+            name,
+            tag,
+            members: HashMap::new(),
+        });
+        self.set_member(guild, leader, GuildRank::Leader);
+        guild
+    }
+
+    pub fn add_member(&mut self, guild: GuildId, character_id: CharacterId) {
+        if self.guilds.contains_key(&guild) && !self.members.contains_key(&character_id) {
+            self.set_member(guild, character_id, GuildRank::Member);
+        }
+    }
+
+    /// Changes the rank of a member. Making someone the leader demotes the
+    /// previous leader.
+    pub fn set_rank(&mut self, character_id: CharacterId, rank: GuildRank) {
+        let guild = match self.members.get(&character_id) {
+            Some(guild) => *guild,
+            None => return,
+        };
+        if rank == GuildRank::Leader {
+            if let Some(leader) = self.leader(guild) {
+                self.set_member(guild, leader, GuildRank::Officer);
+            }
+        }
+        self.set_member(guild, character_id, rank);
+    }
+
+    /// Removes a member from their guild, disbanding it if they were the last
+    /// one. Returns the guild they were in.
+    pub fn remove_member(&mut self, character_id: CharacterId) -> Option<GuildId> {
+        let guild = self.members.remove(&character_id)?;
+        self.changes.push(GuildChange::RemoveMember(character_id));
+        let empty = self.guilds.get_mut(&guild).map_or(true, |data| {
+            data.members.remove(&character_id);
+            data.members.is_empty()
+        });
+        if empty {
+            self.disband(guild);
+        } else {
+            self.ensure_leader(guild);
+        }
+        Some(guild)
+    }
+
+    /// Removes a character that was deleted from their guild. Unsaved changes
+    /// that would make them a member again are dropped, since the database
+    /// no longer has the character they refer to.
+    pub fn delete_character(&mut self, character_id: CharacterId) -> Option<GuildId> {
+        self.changes.retain(|change| {
+            !matches!(change, GuildChange::SetMember { character_id: id, .. } if *id == character_id)
+        });
+        self.remove_member(character_id)
+    }
+
+    /// Disbands a guild, returning its former members
+    pub fn disband(&mut self, guild: GuildId) -> Vec<CharacterId> {
+        let members = self
+            .guilds
+            .remove(&guild)
+            .map(|data| data.members.keys().copied().collect::<Vec<_>>())
+            .unwrap_or_default();
+        for character_id in &members {
+            self.members.remove(character_id);
+        }
+        self.changes.push(GuildChange::Disband(guild));
+        members
+    }
+
+    /// Takes the changes made since this was last called, to be saved
+    pub fn take_changes(&mut self) -> Vec<GuildChange> { std::mem::take(&mut self.changes) }
+
+    /// Describes a guild to one of its members
+    pub fn info(
+        &self,
+        guild: GuildId,
+        viewer: CharacterId,
+        name_of: impl Fn(CharacterId) -> Option<String>,
+        is_online: impl Fn(CharacterId) -> bool,
+    ) -> Option<GuildInfo> {
+        let data = self.guilds.get(&guild)?;
+        let mut members = data
+            .members
+            .iter()
+            .map(|(character_id, rank)| GuildMember {
+                character_id: *character_id,
+                name: name_of(*character_id).unwrap_or_default(),
+                rank: *rank,
+                online: is_online(*character_id),
+            })
+            .collect::<Vec<_>>();
+        members.sort_by(|a, b| b.rank.cmp(&a.rank).then_with(|| a.name.cmp(&b.name)));
+        Some(GuildInfo {
+            id: guild,
+            name: data.name.clone(),
+            tag: data.tag.clone(),
+            rank: *data.members.get(&viewer)?,
+            members,
+        })
+    }
+
+    fn leader(&self, guild: GuildId) -> Option<CharacterId> {
+        self.guilds.get(&guild).and_then(|data| {
+            data.members
+                .iter()
+                .find(|(_, rank)| **rank == GuildRank::Leader)
+                .map(|(character_id, _)| *character_id)
+        })
+    }
+
+    /// Makes the highest ranked member the leader if the guild has none. The
+    /// longest standing character wins ties.
+    fn ensure_leader(&mut self, guild: GuildId) {
+        if self.leader(guild).is_some() {
+            return;
+        }
+        if let Some(successor) = self.guilds.get(&guild).and_then(|data| {
+            data.members
+                .iter()
+                .max_by(|(a_id, a_rank), (b_id, b_rank)| {
+                    a_rank.cmp(b_rank).then_with(|| b_id.cmp(a_id))
+                })
+                .map(|(character_id, _)| *character_id)
+        }) {
+            self.set_member(guild, successor, GuildRank::Leader);
+        }
+    }
+
+    fn set_member(&mut self, guild: GuildId, character_id: CharacterId, rank: GuildRank) {
+        if let Some(data) = self.guilds.get_mut(&guild) {
+            data.members.insert(character_id, rank);
+            self.members.insert(character_id, guild);
+            self.changes.push(GuildChange::SetMember {
+                character_id,
+                guild,
+                rank,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn leader_leaving_hands_over_leadership() {
+        let mut guilds = GuildManager::default();
+        let guild = guilds.create("Wanderers".to_owned(), "WND".to_owned(), 1);
+        guilds.add_member(guild, 2);
+        guilds.add_member(guild, 3);
+        guilds.set_rank(3, GuildRank::Officer);
+
+        assert_eq!(guilds.remove_member(1), Some(guild));
+        assert_eq!(guilds.rank_of(3), Some(GuildRank::Leader));
+        assert_eq!(guilds.rank_of(2), Some(GuildRank::Member));
+    }
+
+    #[test]
+    fn deleted_leader_is_replaced() {
+        let mut guilds = GuildManager::default();
+        let guild = guilds.create("Wanderers".to_owned(), "WND".to_owned(), 1);
+        guilds.add_member(guild, 2);
+
+        assert_eq!(guilds.delete_character(1), Some(guild));
+        assert_eq!(guilds.rank_of(1), None);
+        assert_eq!(guilds.rank_of(2), Some(GuildRank::Leader));
+        assert!(!guilds.take_changes().iter().any(|change| matches!(
+            change,
+            GuildChange::SetMember {
+                character_id: 1,
+                ..
+            }
+        )));
+    }
+
+    #[test]
+    fn promoting_to_leader_demotes_the_leader() {
+        let mut guilds = GuildManager::default();
+        let guild = guilds.create("Wanderers".to_owned(), "WND".to_owned(), 1);
+        guilds.add_member(guild, 2);
+        guilds.set_rank(2, GuildRank::Leader);
+
+        assert_eq!(guilds.rank_of(1), Some(GuildRank::Officer));
+        assert_eq!(guilds.rank_of(2), Some(GuildRank::Leader));
+    }
+
+    #[test]
+    fn last_member_leaving_disbands() {
+        let mut guilds = GuildManager::default();
+        let guild = guilds.create("Wanderers".to_owned(), "WND".to_owned(), 1);
+        guilds.remove_member(1);
+
+        assert!(guilds.get(guild).is_none());
+        assert_eq!(guilds.find("wnd"), None);
+    }
+
+    #[test]
+    fn loading_repairs_leaderless_guilds() {
+        let guilds = GuildManager::new(
+            vec![
+                (4, "Wanderers".to_owned(), "WND".to_owned()),
+                (5, "Empty".to_owned(), "EMP".to_owned()),
+            ],
+            vec![(1, 4, GuildRank::Member), (2, 4, GuildRank::Member)],
+        );
+
+        assert_eq!(guilds.rank_of(1), Some(GuildRank::Leader));
+        assert_eq!(guilds.rank_of(2), Some(GuildRank::Member));
+        assert!(guilds.get(5).is_none());
+        assert_eq!(guilds.next_id, 6);
+    }
+}
@@ -0,0 +1,335 @@
+use common::character::CharacterId;
+use hashbrown::HashMap;
+use serde::{Deserialize, Serialize};
+use vek::*;
+use world::site2::HouseForSale;
+
+pub const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// How players may own houses on the server
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HousingSettings {
+    /// Whether town NPCs sell houses at all
+    pub enabled: bool,
+    /// How many coins a house costs
+    pub price: u32,
+    /// How many days a house belongs to its buyer before it goes back on the
+    /// market, if houses are only leased
+    pub lease_days: Option<u32>,
+    /// How many days the owner of a house may stay offline before they are
+    /// evicted, if they can be evicted at all
+    pub evict_after_days_offline: Option<u32>,
+}
+
+impl Default for HousingSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            price: 2000,
+            lease_days: None,
+            evict_after_days_offline: Some(30),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HouseOwner {
+    pub character_id: CharacterId,
+    /// Unix time at which the house was bought
+    pub bought_at: u64,
+    /// Unix time at which the owner last logged in or out
+    pub last_seen: u64,
+}
+
+/// A change to the owners of houses that is yet to be saved
+#[derive(Clone, Debug)]
+pub enum HousingChange {
+    SetOwner { house: Vec2<i32>, owner: HouseOwner },
+    RemoveOwner(Vec2<i32>),
+}
+
+pub struct House {
+    /// The blocks that belong to the house, which become a build area of its
+    /// owner
+    pub volume: Aabb<i32>,
+    /// The height of the ground floor
+    pub floor: i32,
+    pub owner: Option<HouseOwner>,
+}
+
+impl House {
+    /// Where the owner of the house respawns
+    pub fn waypoint(&self, pos: Vec2<i32>) -> Vec3<f32> {
+        pos.with_z(self.floor).map(|e| e as f32) + Vec3::new(0.5, 0.5, 0.0)
+    }
+}
+
+/// The houses that players can buy, identified by the position of their plot,
+/// and who owns them. The houses are found in the sites of the world when the
+/// server starts and their owners are loaded from the database, and changes are
+/// saved by the persistence system.
+#[derive(Default)]
+pub struct HousingManager {
+    houses: HashMap<Vec2<i32>, House>,
+    owned: HashMap<CharacterId, Vec2<i32>>,
+    changes: Vec<HousingChange>,
+}
+
+impl HousingManager {
+    pub fn new(
+        houses: impl IntoIterator<Item = HouseForSale>,
+        owners: impl IntoIterator<Item = (Vec2<i32>, HouseOwner)>,
+    ) -> Self {
+        let mut manager = Self {
+            houses: houses
+                .into_iter()
+                .map(|house| {
+                    (house.pos, House {
+                        volume: house.volume,
+                        floor: house.floor,
+                        owner: None,
+                    })
+                })
+                .collect(),
+            ..Default::default()
+        };
+        for (pos, owner) in owners {
+            match manager.houses.get_mut(&pos) {
+                Some(house) if !manager.owned.contains_key(&owner.character_id) => {
+                    house.owner = Some(owner);
+                    manager.owned.insert(owner.character_id, pos);
+                },
+                // The world changed since the house was bought, or the owner
+                // somehow has two houses
+                _ => manager.changes.push(HousingChange::RemoveOwner(pos)),
+            }
+        }
+        manager
+    }
+
+    /// The name of the build area that covers a house
+    pub fn area_name(pos: Vec2<i32>) -> String { format!("house_{}_{}", pos.x, pos.y) }
+
+    pub fn get(&self, pos: Vec2<i32>) -> Option<&House> { self.houses.get(&pos) }
+
+    pub fn house_of(&self, character_id: CharacterId) -> Option<(Vec2<i32>, &House)> {
+        let pos = *self.owned.get(&character_id)?;
+        Some((pos, self.houses.get(&pos)?))
+    }
+
+    /// The houses that have an owner
+    pub fn owned_houses(&self) -> impl Iterator<Item = (Vec2<i32>, &House)> + '_ {
+        self.owned
+            .values()
+            .filter_map(move |pos| Some((*pos, self.houses.get(pos)?)))
+    }
+
+    /// The closest house without an owner within `max_dist` of a position
+    pub fn nearest_for_sale(&self, pos: Vec2<i32>, max_dist: i32) -> Option<Vec2<i32>> {
+        self.houses
+            .iter()
+            .filter(|(_, house)| house.owner.is_none())
+            .map(|(house_pos, _)| (house_pos.distance_squared(pos), *house_pos))
+            .filter(|(dist_sqr, _)| *dist_sqr <= max_dist.pow(2))
+            .min()
+            .map(|(_, house_pos)| house_pos)
+    }
+
+    /// Makes a character the owner of a house, if it is for sale and they
+    /// don't own one yet. Returns whether the house was bought.
+    pub fn buy(&mut self, pos: Vec2<i32>, character_id: CharacterId, now: u64) -> bool {
+        if self.owned.contains_key(&character_id) {
+            return false;
+        }
+        match self.houses.get_mut(&pos) {
+            Some(house) if house.owner.is_none() => {
+                let owner = HouseOwner {
+                    character_id,
+                    bought_at: now,
+                    last_seen: now,
+                };
+                house.owner = Some(owner);
+                self.owned.insert(character_id, pos);
+                self.changes
+                    .push(HousingChange::SetOwner { house: pos, owner });
+                true
+            },
+            _ => false,
+        }
+    }
+
+    /// Records that the owner of a house is active, so that they are not
+    /// evicted
+    pub fn seen(&mut self, character_id: CharacterId, now: u64) {
+        if let Some(pos) = self.owned.get(&character_id) {
+            if let Some(owner) = self
+                .houses
+                .get_mut(pos)
+                .and_then(|house| house.owner.as_mut())
+            {
+                owner.last_seen = now;
+                self.changes.push(HousingChange::SetOwner {
+                    house: *pos,
+                    owner: *owner,
+                });
+            }
+        }
+    }
+
+    /// Puts the house of a character back on the market, returning where it
+    /// is
+    pub fn abandon(&mut self, character_id: CharacterId) -> Option<Vec2<i32>> {
+        let pos = self.owned.remove(&character_id)?;
+        if let Some(house) = self.houses.get_mut(&pos) {
+            house.owner = None;
+        }
+        self.changes.push(HousingChange::RemoveOwner(pos));
+        Some(pos)
+    }
+
+    /// Puts the house of a deleted character back on the market, dropping
+    /// unsaved changes that would make them its owner again
+    pub fn delete_character(&mut self, character_id: CharacterId) -> Option<Vec2<i32>> {
+        self.changes.retain(|change| match change {
+            HousingChange::SetOwner { owner, .. } => owner.character_id != character_id,
+            HousingChange::RemoveOwner(_) => true,
+        });
+        self.abandon(character_id)
+    }
+
+    /// Takes away the houses whose lease ran out, and those of owners who
+    /// have been offline for too long. Returns the houses and their former
+    /// owners.
+    pub fn evict_expired(
+        &mut self,
+        now: u64,
+        settings: &HousingSettings,
+        is_online: impl Fn(CharacterId) -> bool,
+    ) -> Vec<(Vec2<i32>, CharacterId)> {
+        let days_since = |time: u64| now.saturating_sub(time) / SECONDS_PER_DAY;
+        let expired = self
+            .owned_houses()
+            .filter_map(|(_, house)| house.owner)
+            .filter(|owner| {
+                let lease_over = settings
+                    .lease_days
+                    .map_or(false, |days| days_since(owner.bought_at) >= u64::from(days));
+                // Owners who are online are still around, however long ago they
+                // logged in
+                let away_too_long = !is_online(owner.character_id)
+                    && settings
+                        .evict_after_days_offline
+                        .map_or(false, |days| days_since(owner.last_seen) >= u64::from(days));
+                lease_over || away_too_long
+            })
+            .map(|owner| owner.character_id)
+            .collect::<Vec<_>>();
+        expired
+            .into_iter()
+            .filter_map(|character_id| Some((self.abandon(character_id)?, character_id)))
+            .collect()
+    }
+
+    /// Takes the changes made since this was last called, to be saved
+    pub fn take_changes(&mut self) -> Vec<HousingChange> { std::mem::take(&mut self.changes) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: u64 = SECONDS_PER_DAY;
+
+    fn manager() -> HousingManager {
+        HousingManager::new(
+            vec![
+                HouseForSale {
+                    pos: Vec2::new(0, 0),
+                    volume: Aabb::new_empty(Vec3::zero()),
+                    floor: 0,
+                },
+                HouseForSale {
+                    pos: Vec2::new(100, 0),
+                    volume: Aabb::new_empty(Vec3::zero()),
+                    floor: 0,
+                },
+            ],
+            Vec::new(),
+        )
+    }
+
+    #[test]
+    fn one_house_per_character() {
+        let mut housing = manager();
+        assert_eq!(
+            housing.nearest_for_sale(Vec2::new(80, 0), 50),
+            Some(Vec2::new(100, 0))
+        );
+        assert!(housing.buy(Vec2::new(100, 0), 1, 0));
+        assert!(!housing.buy(Vec2::new(0, 0), 1, 0));
+        assert!(!housing.buy(Vec2::new(100, 0), 2, 0));
+        assert_eq!(housing.nearest_for_sale(Vec2::new(80, 0), 50), None);
+
+        assert_eq!(housing.abandon(1), Some(Vec2::new(100, 0)));
+        assert!(housing.buy(Vec2::new(100, 0), 2, 0));
+    }
+
+    #[test]
+    fn deleted_owners_lose_their_house() {
+        let mut housing = manager();
+        housing.buy(Vec2::new(0, 0), 1, 0);
+        housing.seen(1, DAY);
+
+        assert_eq!(housing.delete_character(1), Some(Vec2::new(0, 0)));
+        assert!(housing.house_of(1).is_none());
+        assert!(housing.get(Vec2::new(0, 0)).unwrap().owner.is_none());
+        assert!(
+            !housing
+                .take_changes()
+                .iter()
+                .any(|change| matches!(change, HousingChange::SetOwner { .. }))
+        );
+    }
+
+    #[test]
+    fn inactive_owners_are_evicted() {
+        let settings = HousingSettings {
+            evict_after_days_offline: Some(30),
+            ..Default::default()
+        };
+        let mut housing = manager();
+        housing.buy(Vec2::new(0, 0), 1, 0);
+        housing.buy(Vec2::new(100, 0), 2, 0);
+        housing.seen(2, 20 * DAY);
+
+        assert!(
+            housing
+                .evict_expired(30 * DAY, &settings, |_| true)
+                .is_empty()
+        );
+        assert_eq!(housing.evict_expired(30 * DAY, &settings, |_| false), vec![
+            (Vec2::new(0, 0), 1)
+        ]);
+        assert!(housing.house_of(2).is_some());
+    }
+
+    #[test]
+    fn leases_run_out_while_online() {
+        let settings = HousingSettings {
+            lease_days: Some(7),
+            evict_after_days_offline: None,
+            ..Default::default()
+        };
+        let mut housing = manager();
+        housing.buy(Vec2::new(0, 0), 1, 0);
+
+        assert!(
+            housing
+                .evict_expired(6 * DAY, &settings, |_| true)
+                .is_empty()
+        );
+        assert_eq!(housing.evict_expired(7 * DAY, &settings, |_| true).len(), 1);
+        assert!(housing.house_of(1).is_none());
+    }
+}
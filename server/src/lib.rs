@@ -14,6 +14,7 @@
 
 pub mod alias_validator;
 mod character_creator;
+mod chunk_cache;
 pub mod chunk_generator;
 pub mod client;
 pub mod cmd;
@@ -21,16 +22,24 @@ pub mod connection_handler;
 mod data_dir;
 pub mod error;
 pub mod events;
+pub mod farming;
+pub mod fishing;
+pub mod guild;
+pub mod housing;
 pub mod input;
 pub mod login_provider;
+pub mod mail;
 pub mod metrics;
 pub mod persistence;
+pub mod pet;
 pub mod presence;
 pub mod rtsim;
 pub mod settings;
 pub mod state_ext;
+pub mod storage;
 pub mod sys;
 #[cfg(not(feature = "worldgen"))] mod test_world;
+#[cfg(feature = "worldgen")] pub mod weather;
 pub mod wiring;
 
 // Reexports
@@ -60,6 +69,7 @@ use common::grid::Grid;
 use common::{
     assets::AssetExt,
     cmd::ChatCommand,
+    combat_log::CombatLogEntry,
     comp,
     comp::{item::MaterialStatManifest, CharacterAbility},
     event::{EventBus, ServerEvent},
@@ -74,7 +84,8 @@ use common::{
 use common_ecs::run_now;
 use common_net::{
     msg::{
-        ClientType, DisconnectReason, ServerGeneral, ServerInfo, ServerInit, ServerMsg, WorldMapMsg,
+        ClientType, DisconnectReason, PresenceKind, ServerGeneral, ServerInfo, ServerInit,
+        ServerMsg, WorldMapMsg,
     },
     sync::WorldSyncExt,
 };
@@ -179,6 +190,14 @@ impl Server {
         debug!("Running DB migrations...");
         persistence::run_migrations(&database_settings);
 
+        // Storage containers and crops are loaded up front since their sprites are
+        // placed as soon as the chunks they are in are generated
+        let storage_containers = persistence::load_storage_containers(&database_settings)?;
+        let crops = persistence::load_crops(&database_settings)?;
+        let (mail, character_aliases, last_mail_id) = persistence::load_mail(&database_settings)?;
+        let (guilds, guild_members) = persistence::load_guilds(&database_settings)?;
+        let house_owners = persistence::load_house_owners(&database_settings)?;
+
         let database_settings = Arc::new(RwLock::new(database_settings));
 
         let registry = Arc::new(Registry::new());
@@ -191,11 +210,13 @@ impl Server {
 
         let mut state = State::server();
         state.ecs_mut().insert(settings.clone());
+        state.ecs_mut().insert(settings.pvp_mode);
         state.ecs_mut().insert(editable_settings);
         state.ecs_mut().insert(DataDir {
             path: data_dir.to_owned(),
         });
         state.ecs_mut().insert(EventBus::<ServerEvent>::default());
+        state.ecs_mut().insert(Vec::<CombatLogEntry>::new());
         state.ecs_mut().insert(LoginProvider::new(
             settings.auth_server_address.clone(),
             Arc::clone(&runtime),
@@ -215,10 +236,32 @@ impl Server {
             .ecs_mut()
             .write_resource::<SlowJobPool>()
             .configure("CHUNK_GENERATOR", |n| n / 2 + n / 4);
+        let chunk_cache = if settings.chunk_cache {
+            chunk_cache::ChunkCache::new(data_dir, settings.world_seed, settings.map_file.as_ref())
+                .map_err(|e| {
+                    error!(
+                        ?e,
+                        "Failed to create chunk cache, chunks will not be cached"
+                    )
+                })
+                .ok()
+        } else {
+            None
+        };
         state
             .ecs_mut()
-            .insert(ChunkGenerator::new(chunk_gen_metrics));
+            .insert(ChunkGenerator::new(chunk_gen_metrics, chunk_cache));
 
+        state
+            .ecs_mut()
+            .insert(storage::StorageContainers::new(storage_containers));
+        state.ecs_mut().insert(farming::Crops::new(crops));
+        state
+            .ecs_mut()
+            .insert(mail::PostOffice::new(mail, character_aliases, last_mail_id));
+        state
+            .ecs_mut()
+            .insert(guild::GuildManager::new(guilds, guild_members));
         state.ecs_mut().insert(CharacterUpdater::new(
             Arc::<RwLock<DatabaseSettings>>::clone(&database_settings),
         )?);
@@ -239,6 +282,22 @@ impl Server {
         state
             .ecs_mut()
             .insert(sys::PersistenceScheduler::every(Duration::from_secs(10)));
+        state
+            .ecs_mut()
+            .insert(sys::QuestScheduler::every(Duration::from_secs(1)));
+        state
+            .ecs_mut()
+            .insert(sys::FarmingScheduler::every(Duration::from_secs(5)));
+        state
+            .ecs_mut()
+            .insert(sys::MailScheduler::every(Duration::from_secs(60)));
+        state
+            .ecs_mut()
+            .insert(sys::HousingScheduler::every(Duration::from_secs(60)));
+        #[cfg(feature = "worldgen")]
+        state
+            .ecs_mut()
+            .insert(sys::EconomyScheduler::every(Duration::from_secs(300)));
 
         // Server-only components
         state.ecs_mut().register::<RegionSubscription>();
@@ -371,11 +430,51 @@ impl Server {
                 .expect("The initial insert should always work.");
         }
 
+        // Find the houses that settlements reserve for players to buy, and let their
+        // owners build in them
+        {
+            #[cfg(feature = "worldgen")]
+            let houses = world
+                .civs()
+                .sites()
+                .filter_map(|site| match &index.sites[site.site_tmp?].kind {
+                    world::site::SiteKind::Refactor(site2) => {
+                        Some(site2.houses_for_sale().collect::<Vec<_>>())
+                    },
+                    _ => None,
+                })
+                .flatten()
+                .collect::<Vec<_>>();
+            #[cfg(not(feature = "worldgen"))]
+            let houses = Vec::new();
+
+            let housing_manager = housing::HousingManager::new(houses, house_owners);
+            let mut build_areas = state.ecs().write_resource::<BuildAreas>();
+            for (pos, house) in housing_manager.owned_houses() {
+                if let Err(name) =
+                    build_areas.insert(housing::HousingManager::area_name(pos), house.volume)
+                {
+                    warn!(?name, "Build area of house is already taken");
+                }
+            }
+            drop(build_areas);
+            state.ecs_mut().insert(housing_manager);
+        }
+
         // Insert the world into the ECS (todo: Maybe not an Arc?)
         let world = Arc::new(world);
         state.ecs_mut().insert(Arc::clone(&world));
         state.ecs_mut().insert(index.clone());
 
+        // Carry on simulating the economies of sites from where the last run of the
+        // server left off
+        #[cfg(feature = "worldgen")]
+        {
+            let mut economy = world::sim2::EconomySim::new(&index);
+            sys::economy::load(&mut economy, data_dir, settings.world_seed);
+            state.ecs_mut().insert(economy);
+        }
+
         // Set starting time for the server.
         state.ecs_mut().write_resource::<TimeOfDay>().0 = settings.start_time;
 
@@ -442,6 +541,9 @@ impl Server {
         #[cfg(not(feature = "worldgen"))]
         rtsim::init(&mut state);
 
+        #[cfg(feature = "worldgen")]
+        weather::init(&mut state, &world);
+
         let this = Self {
             state,
             world,
@@ -569,6 +671,8 @@ impl Server {
                 sys::add_server_systems(dispatcher_builder);
                 #[cfg(feature = "worldgen")]
                 rtsim::add_server_systems(dispatcher_builder);
+                #[cfg(feature = "worldgen")]
+                weather::add_server_systems(dispatcher_builder);
             },
             false,
         );
@@ -697,6 +801,15 @@ impl Server {
                 },
                 CharacterLoaderResponseKind::CharacterCreation(result) => match result {
                     Ok((character_id, list)) => {
+                        if let Some(item) = list
+                            .iter()
+                            .find(|item| item.character.id == Some(character_id))
+                        {
+                            self.state
+                                .ecs()
+                                .write_resource::<mail::PostOffice>()
+                                .add_character(character_id, item.character.alias.clone());
+                        }
                         self.notify_client(
                             query_result.entity,
                             ServerGeneral::CharacterListUpdate(list),
@@ -711,6 +824,41 @@ impl Server {
                         ServerGeneral::CharacterActionError(error.to_string()),
                     ),
                 },
+                CharacterLoaderResponseKind::CharacterDeletion(result) => match result {
+                    Ok((character_id, list)) => {
+                        // Deleted characters leave their guild, which may need a new leader
+                        events::guild::remove_deleted_character(self.state.ecs(), character_id);
+                        // Their house goes back on the market
+                        events::housing::remove_deleted_character(self.state.ecs(), character_id);
+                        // Letters waiting for the character go back to their senders
+                        let mut post_office = self.state.ecs().write_resource::<mail::PostOffice>();
+                        let returned = post_office.delete_character(character_id);
+                        if !returned.is_empty() {
+                            for (presence, client) in (
+                                &self.state.ecs().read_storage::<Presence>(),
+                                &self.state.ecs().read_storage::<Client>(),
+                            )
+                                .join()
+                            {
+                                if let PresenceKind::Character(id) = presence.kind {
+                                    if returned.contains(&id) {
+                                        client.send_fallible(ServerGeneral::MailUpdate(
+                                            post_office.mailbox(id),
+                                        ));
+                                    }
+                                }
+                            }
+                        }
+                        self.notify_client(
+                            query_result.entity,
+                            ServerGeneral::CharacterListUpdate(list),
+                        );
+                    },
+                    Err(error) => self.notify_client(
+                        query_result.entity,
+                        ServerGeneral::CharacterActionError(error.to_string()),
+                    ),
+                },
                 CharacterLoaderResponseKind::CharacterData(result) => {
                     let message = match *result {
                         Ok(character_data) => ServerEvent::UpdateCharacterData {
@@ -1197,6 +1345,35 @@ impl Drop for Server {
         self.metrics_shutdown.notify_one();
         self.state
             .notify_players(ServerGeneral::Disconnect(DisconnectReason::Shutdown));
+
+        // Crops are otherwise only saved when they reach a new stage, so their growth
+        // since then is saved before the character updater finishes its work
+        let crops = {
+            let mut crops = self.state.ecs().write_resource::<farming::Crops>();
+            crops.save_growth();
+            crops.take_modified()
+        };
+        self.state
+            .ecs()
+            .write_resource::<CharacterUpdater>()
+            .update_crops(crops);
+
+        #[cfg(feature = "worldgen")]
+        sys::economy::save(
+            &self.state.ecs().read_resource::<world::sim2::EconomySim>(),
+            &self.data_dir().path,
+            self.settings().world_seed,
+        );
+        #[cfg(feature = "worldgen")]
+        rtsim::save(
+            &self.state.ecs().read_resource::<rtsim::RtSim>(),
+            self.state
+                .ecs()
+                .read_resource::<common::resources::Time>()
+                .0,
+            &self.data_dir().path,
+            self.settings().world_seed,
+        );
     }
 }
 
@@ -0,0 +1,260 @@
+use common::{
+    character::CharacterId,
+    mail::{Mail, MailId},
+};
+use hashbrown::HashMap;
+use std::{
+    collections::VecDeque,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tracing::info;
+
+/// What happened to a letter
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum MailAction {
+    Sent,
+    Returned,
+    Claimed,
+    /// The letter could not be delivered or returned because the characters
+    /// involved were deleted, so it was thrown away along with its attachments
+    Discarded,
+}
+
+impl MailAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MailAction::Sent => "sent",
+            MailAction::Returned => "returned",
+            MailAction::Claimed => "claimed",
+            MailAction::Discarded => "discarded",
+        }
+    }
+}
+
+/// A change to a letter that is yet to be saved. Each change is also written
+/// to the mail log, so that moderators can trace where items went.
+#[derive(Clone, Debug)]
+pub struct MailChange {
+    pub action: MailAction,
+    pub mail: Mail,
+}
+
+/// A line of the mail log
+#[derive(Clone, Debug)]
+pub struct MailLogEntry {
+    pub time: u64,
+    pub action: MailAction,
+    pub mail: MailId,
+    pub sender_alias: String,
+    pub recipient_alias: String,
+    pub attachments: String,
+}
+
+/// How many lines of the mail log are kept in memory for `/mail_log`
+const MAIL_LOG_LENGTH: usize = 100;
+
+/// Every letter that is yet to be collected, along with the names of all
+/// characters so that letters can be addressed to characters that are not
+/// online. Letters are loaded from the database when the server starts, and
+/// changes are saved together with the characters involved so that items are
+/// never both in a letter and in an inventory.
+#[derive(Default)]
+pub struct PostOffice {
+    mail: HashMap<MailId, Mail>,
+    next_id: MailId,
+    characters: HashMap<CharacterId, String>,
+    changes: Vec<MailChange>,
+    log: VecDeque<MailLogEntry>,
+}
+
+impl PostOffice {
+    pub fn new(
+        mail: impl IntoIterator<Item = Mail>,
+        characters: impl IntoIterator<Item = (CharacterId, String)>,
+        last_mail_id: MailId,
+    ) -> Self {
+        let mail = mail
+            .into_iter()
+            .map(|mail| (mail.id, mail))
+            .collect::<HashMap<_, _>>();
+        Self {
+            next_id: mail.keys().copied().fold(last_mail_id, MailId::max) + 1,
+            mail,
+            characters: characters.into_iter().collect(),
+            changes: Vec::new(),
+            log: VecDeque::new(),
+        }
+    }
+
+    /// Makes a newly created character known so that it can receive letters
+    pub fn add_character(&mut self, character_id: CharacterId, alias: String) {
+        self.characters.insert(character_id, alias);
+    }
+
+    /// Forgets a deleted character. Letters waiting for them go back to their
+    /// senders, and letters that can't be returned are discarded. Returns the
+    /// characters whose mailboxes changed.
+    pub fn delete_character(&mut self, character_id: CharacterId) -> Vec<CharacterId> {
+        self.characters.remove(&character_id);
+
+        let undeliverable = self
+            .mail
+            .values()
+            .filter(|mail| mail.recipient == character_id)
+            .map(|mail| mail.id)
+            .collect::<Vec<_>>();
+
+        let mut changed = Vec::new();
+        for id in undeliverable {
+            if let Some(mail) = self.mail.remove(&id) {
+                if !mail.returned && self.characters.contains_key(&mail.sender) {
+                    changed.push(mail.sender);
+                    self.return_to_sender(mail, unix_time());
+                } else {
+                    self.record(MailAction::Discarded, &mail);
+                }
+            }
+        }
+        changed
+    }
+
+    pub fn character_alias(&self, character_id: CharacterId) -> Option<&str> {
+        self.characters.get(&character_id).map(String::as_str)
+    }
+
+    /// Finds the characters with the given name, ignoring case
+    pub fn find_characters<'a>(
+        &'a self,
+        alias: &'a str,
+    ) -> impl Iterator<Item = (CharacterId, &'a str)> + 'a {
+        self.characters
+            .iter()
+            .filter(move |(_, a)| a.eq_ignore_ascii_case(alias))
+            .map(|(id, a)| (*id, a.as_str()))
+    }
+
+    pub fn get(&self, id: MailId) -> Option<&Mail> { self.mail.get(&id) }
+
+    /// The letters waiting for a character, oldest first
+    pub fn mailbox(&self, character_id: CharacterId) -> Vec<Mail> {
+        let mut mailbox = self
+            .mail
+            .values()
+            .filter(|mail| mail.recipient == character_id)
+            .cloned()
+            .collect::<Vec<_>>();
+        mailbox.sort_by_key(|mail| (mail.sent_at, mail.id));
+        mailbox
+    }
+
+    /// Posts a letter, giving it an id
+    pub fn send(&mut self, mut mail: Mail) {
+        mail.id = self.next_id;
+        self.next_id += 1;
+        self.record(MailAction::Sent, &mail);
+        self.mail.insert(mail.id, mail);
+    }
+
+    /// Takes a letter out of the post so that its recipient can collect it
+    pub fn claim(&mut self, id: MailId) -> Option<Mail> {
+        let mail = self.mail.remove(&id)?;
+        self.record(MailAction::Claimed, &mail);
+        Some(mail)
+    }
+
+    /// Sends letters that were not collected in time back to their senders,
+    /// returning the characters whose mailboxes changed
+    pub fn return_expired(&mut self, now: u64) -> Vec<CharacterId> {
+        let expired = self
+            .mail
+            .values()
+            .filter(|mail| mail.is_expired(now))
+            .map(|mail| mail.id)
+            .collect::<Vec<_>>();
+
+        let mut changed = Vec::new();
+        for id in expired {
+            if let Some(mail) = self.mail.remove(&id) {
+                changed.push(mail.recipient);
+                // The sender may have been deleted since they sent the letter
+                if self.characters.contains_key(&mail.sender) {
+                    changed.push(mail.sender);
+                    self.return_to_sender(mail, now);
+                } else {
+                    self.record(MailAction::Discarded, &mail);
+                }
+            }
+        }
+        changed
+    }
+
+    fn return_to_sender(&mut self, mut mail: Mail, now: u64) {
+        std::mem::swap(&mut mail.sender, &mut mail.recipient);
+        std::mem::swap(&mut mail.sender_alias, &mut mail.recipient_alias);
+        mail.sent_at = now;
+        mail.returned = true;
+        self.record(MailAction::Returned, &mail);
+        self.mail.insert(mail.id, mail);
+    }
+
+    /// Takes the changes made since this was last called, to be saved
+    pub fn take_changes(&mut self) -> Vec<MailChange> { std::mem::take(&mut self.changes) }
+
+    /// The most recent lines of the mail log that involve the given character,
+    /// or all of them
+    pub fn log<'a>(
+        &'a self,
+        alias: Option<&'a str>,
+    ) -> impl Iterator<Item = &'a MailLogEntry> + 'a {
+        self.log.iter().filter(move |entry| {
+            alias.map_or(true, |alias| {
+                entry.sender_alias.eq_ignore_ascii_case(alias)
+                    || entry.recipient_alias.eq_ignore_ascii_case(alias)
+            })
+        })
+    }
+
+    fn record(&mut self, action: MailAction, mail: &Mail) {
+        let entry = MailLogEntry {
+            time: unix_time(),
+            action,
+            mail: mail.id,
+            sender_alias: mail.sender_alias.clone(),
+            recipient_alias: mail.recipient_alias.clone(),
+            attachments: describe_attachments(mail),
+        };
+        info!(
+            mail = entry.mail,
+            action = action.as_str(),
+            sender = %entry.sender_alias,
+            recipient = %entry.recipient_alias,
+            attachments = %entry.attachments,
+            "Mail"
+        );
+        if self.log.len() >= MAIL_LOG_LENGTH {
+            self.log.pop_front();
+        }
+        self.log.push_back(entry);
+        self.changes.push(MailChange {
+            action,
+            mail: mail.clone(),
+        });
+    }
+}
+
+/// Lists the items attached to a letter, e.g. `3x common.items.utility.coins`
+pub fn describe_attachments(mail: &Mail) -> String {
+    mail.attachments
+        .slots()
+        .flatten()
+        .map(|item| format!("{}x {}", item.amount(), item.item_definition_id()))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// The current time in seconds since the unix epoch
+pub fn unix_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
@@ -0,0 +1,3 @@
+-- Add a 'quests' column to the 'character' table, holding the JSON encoded quest log of
+-- each character
+ALTER TABLE character ADD COLUMN quests TEXT NULL;
@@ -0,0 +1,3 @@
+-- Add a 'pets' column to the 'character' table, holding the JSON encoded pets that
+-- belong to each character
+ALTER TABLE character ADD COLUMN pets TEXT NULL;
@@ -0,0 +1,3 @@
+-- Add a 'durability_lost' column to the 'item' table, tracking how worn down each
+-- item with durability is
+ALTER TABLE item ADD COLUMN durability_lost INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,26 @@
+-- Letters waiting to be collected. The items attached to each letter are kept in the
+-- 'item' table, under a pseudo container of the world named after the letter.
+CREATE TABLE mail
+(
+    mail_id                INTEGER NOT NULL
+        PRIMARY KEY,
+    sender_character_id    INTEGER NOT NULL,
+    recipient_character_id INTEGER NOT NULL,
+    message                TEXT NOT NULL,
+    sent_at                INTEGER NOT NULL,
+    returned               INTEGER NOT NULL DEFAULT 0
+);
+
+-- A record of every letter that was sent, returned or collected, along with its
+-- attachments, for moderators tracing duplicated items
+CREATE TABLE mail_log
+(
+    mail_log_id            INTEGER NOT NULL
+        PRIMARY KEY AUTOINCREMENT,
+    mail_id                INTEGER NOT NULL,
+    action                 TEXT NOT NULL,
+    sender_character_id    INTEGER NOT NULL,
+    recipient_character_id INTEGER NOT NULL,
+    attachments            TEXT NOT NULL,
+    logged_at              INTEGER NOT NULL
+);
@@ -0,0 +1,20 @@
+-- Player guilds. Each guild has a unique name and a short tag shown next to the
+-- names of its members.
+CREATE TABLE guild
+(
+    guild_id INTEGER NOT NULL
+        PRIMARY KEY,
+    name     TEXT NOT NULL,
+    tag      TEXT NOT NULL
+);
+
+-- The guild each character belongs to, and their rank within it
+CREATE TABLE guild_member
+(
+    character_id INTEGER NOT NULL
+        PRIMARY KEY
+        REFERENCES character(character_id),
+    guild_id     INTEGER NOT NULL
+        REFERENCES guild(guild_id),
+    rank         TEXT NOT NULL
+);
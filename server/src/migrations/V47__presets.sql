@@ -0,0 +1,3 @@
+-- Add a 'presets' column to the 'character' table, holding the JSON encoded skill and
+-- equipment presets of each character
+ALTER TABLE character ADD COLUMN presets TEXT NULL;
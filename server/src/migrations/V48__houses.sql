@@ -0,0 +1,13 @@
+-- The owners of the houses that players can buy. Houses are identified by the
+-- position of their plot in the world.
+CREATE TABLE house
+(
+    house_x      INTEGER NOT NULL,
+    house_y      INTEGER NOT NULL,
+    character_id INTEGER NOT NULL
+        UNIQUE
+        REFERENCES character(character_id),
+    bought_at    INTEGER NOT NULL,
+    last_seen    INTEGER NOT NULL,
+    PRIMARY KEY (house_x, house_y)
+);
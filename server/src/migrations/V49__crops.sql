@@ -0,0 +1,12 @@
+-- The crops that players planted, kept under the block position of their
+-- sprite. Growth is in in-game seconds and only saved when a crop reaches a new
+-- stage.
+CREATE TABLE crop
+(
+    pos_x  INTEGER NOT NULL,
+    pos_y  INTEGER NOT NULL,
+    pos_z  INTEGER NOT NULL,
+    crop   TEXT    NOT NULL,
+    growth REAL    NOT NULL,
+    PRIMARY KEY (pos_x, pos_y, pos_z)
+);
@@ -0,0 +1,3 @@
+-- Add a 'quality_bonus' column to the 'item' table, tracking how many steps of
+-- quality above its definition each crafted item is
+ALTER TABLE item ADD COLUMN quality_bonus INTEGER NOT NULL DEFAULT 0;
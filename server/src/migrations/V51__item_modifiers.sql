@@ -0,0 +1,3 @@
+-- Add a 'properties' column to the 'item' table, holding the modifiers rolled
+-- for each item and the gems socketed into it as JSON
+ALTER TABLE item ADD COLUMN properties TEXT NULL;
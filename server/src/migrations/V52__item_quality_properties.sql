@@ -0,0 +1,43 @@
+-- Move the quality bonus of crafted items into their JSON properties, so that
+-- all per-item data is saved in one place
+UPDATE  item
+SET     properties = json_set(COALESCE(properties, '{"modifiers":[],"sockets":[]}'),
+                              '$.quality_bonus',
+                              quality_bonus)
+WHERE   quality_bonus > 0;
+
+-- Rebuild the 'item' table without the 'quality_bonus' column, which SQLite
+-- can't drop otherwise
+CREATE TABLE _item_new
+(
+    item_id                  INTEGER NOT NULL
+        PRIMARY KEY
+        REFERENCES entity(entity_id),
+    parent_container_item_id INTEGER NOT NULL
+        REFERENCES item(item_id),
+    item_definition_id       TEXT NOT NULL,
+    stack_size               INTEGER NOT NULL,
+    position                 TEXT NOT NULL,
+    durability_lost          INTEGER NOT NULL DEFAULT 0,
+    properties               TEXT NULL
+);
+
+INSERT
+INTO    _item_new
+SELECT  item_id,
+        parent_container_item_id,
+        item_definition_id,
+        stack_size,
+        position,
+        durability_lost,
+        properties
+FROM    item;
+
+DROP TABLE item;
+ALTER TABLE _item_new RENAME TO item;
+
+CREATE UNIQUE INDEX idx_parent_container_item_id_position
+    ON item(parent_container_item_id, position);
+
+CREATE INDEX idx_item_definition_id
+    ON item(item_definition_id);
@@ -9,26 +9,44 @@ extern crate rusqlite;
 use super::{error::PersistenceError, models::*};
 use crate::{
     comp,
-    comp::Inventory,
+    comp::{
+        pet::PetPersistenceData,
+        storage::{BANK_SLOTS, CONTAINER_SLOTS},
+        Inventory,
+    },
+    mail::{describe_attachments, MailAction, MailChange},
     persistence::{
         character::conversions::{
             convert_body_from_database, convert_body_to_database_json,
             convert_character_from_database, convert_inventory_from_database_items,
             convert_items_to_database_items, convert_loadout_from_database_items,
-            convert_skill_groups_to_database, convert_skill_set_from_database,
-            convert_skills_to_database, convert_stats_from_database,
-            convert_waypoint_from_database_json, convert_waypoint_to_database_json,
+            convert_mail_position_to_database, convert_pets_from_database_json,
+            convert_pets_to_database_json, convert_presets_from_database_json,
+            convert_presets_to_database_json, convert_quest_log_from_database_json,
+            convert_quest_log_to_database_json, convert_skill_groups_to_database,
+            convert_skill_set_from_database, convert_skills_to_database,
+            convert_stats_from_database, convert_storage_from_database_items,
+            convert_storage_position_from_database, convert_storage_position_to_database,
+            convert_waypoint_from_database_json, convert_waypoint_to_database_json, ItemModelPair,
+        },
+        character_loader::{
+            CharacterCreationResult, CharacterDataResult, CharacterDeletionResult,
+            CharacterListResult,
         },
-        character_loader::{CharacterCreationResult, CharacterDataResult, CharacterListResult},
         error::PersistenceError::DatabaseError,
         PersistedComponents,
     },
 };
-use common::character::{CharacterId, CharacterItem, MAX_CHARACTERS_PER_PLAYER};
+use common::{
+    character::{CharacterId, CharacterItem, MAX_CHARACTERS_PER_PLAYER},
+    mail::{Mail, MailId, MAX_ATTACHMENTS},
+};
 use core::ops::Range;
+use hashbrown::HashMap;
 use rusqlite::{types::Value, Connection, ToSql, Transaction, NO_PARAMS};
 use std::{collections::VecDeque, rc::Rc};
 use tracing::{error, trace, warn};
+use vek::*;
 
 /// Private module for very tightly coupled database conversion methods.  In
 /// general, these have many invariants that need to be maintained when they're
@@ -40,8 +58,12 @@ pub(crate) type EntityId = i64;
 const CHARACTER_PSEUDO_CONTAINER_DEF_ID: &str = "veloren.core.pseudo_containers.character";
 const INVENTORY_PSEUDO_CONTAINER_DEF_ID: &str = "veloren.core.pseudo_containers.inventory";
 const LOADOUT_PSEUDO_CONTAINER_DEF_ID: &str = "veloren.core.pseudo_containers.loadout";
+const BANK_PSEUDO_CONTAINER_DEF_ID: &str = "veloren.core.pseudo_containers.bank";
+const STORAGE_PSEUDO_CONTAINER_DEF_ID: &str = "veloren.core.pseudo_containers.storage";
+const MAIL_PSEUDO_CONTAINER_DEF_ID: &str = "veloren.core.pseudo_containers.mail";
 const INVENTORY_PSEUDO_CONTAINER_POSITION: &str = "inventory";
 const LOADOUT_PSEUDO_CONTAINER_POSITION: &str = "loadout";
+const BANK_PSEUDO_CONTAINER_POSITION: &str = "bank";
 const WORLD_PSEUDO_CONTAINER_ID: EntityId = 1;
 
 #[derive(Clone, Copy)]
@@ -64,7 +86,9 @@ pub fn load_items_bfs(connection: &Connection, root: i64) -> Result<Vec<Item>, P
                 parent_container_item_id,
                 item_definition_id,
                 stack_size,
-                position
+                position,
+                durability_lost,
+                properties
         FROM    item
         WHERE   parent_container_item_id = ?1")?;
 
@@ -77,6 +101,8 @@ pub fn load_items_bfs(connection: &Connection, root: i64) -> Result<Vec<Item>, P
                     item_definition_id: row.get(2)?,
                     stack_size: row.get(3)?,
                     position: row.get(4)?,
+                    durability_lost: row.get(5)?,
+                    properties: row.get(6)?,
                 })
             })?
             .filter_map(Result::ok)
@@ -102,6 +128,16 @@ pub fn load_character_data(
     let character_containers = get_pseudo_containers(connection, char_id)?;
     let inventory_items = load_items_bfs(connection, character_containers.inventory_container_id)?;
     let loadout_items = load_items_bfs(connection, character_containers.loadout_container_id)?;
+    let bank = match find_pseudo_container_id(connection, char_id, BANK_PSEUDO_CONTAINER_POSITION)?
+    {
+        Some(bank_container_id) => comp::Bank(convert_storage_from_database_items(
+            bank_container_id,
+            &load_items_bfs(connection, bank_container_id)?,
+            BANK_SLOTS,
+        )?),
+        // Characters created before banks existed get one the next time they are saved
+        None => comp::Bank::default(),
+    };
 
     #[rustfmt::skip]
     let mut stmt = connection.prepare_cached("
@@ -109,7 +145,10 @@ pub fn load_character_data(
                 c.alias,
                 c.waypoint,
                 b.variant,
-                b.body_data
+                b.body_data,
+                c.quests,
+                c.pets,
+                c.presets
         FROM    character c
         JOIN    body b ON (c.character_id = b.body_id)
         WHERE   c.player_uuid = ?1
@@ -124,6 +163,9 @@ pub fn load_character_data(
                 player_uuid: requesting_player_uuid,
                 alias: row.get(1)?,
                 waypoint: row.get(2)?,
+                quests: row.get(5)?,
+                pets: row.get(6)?,
+                presets: row.get(7)?,
             };
 
             let body_data = Body {
@@ -150,6 +192,51 @@ pub fn load_character_data(
         }
     });
 
+    let char_quest_log = character_data
+        .quests
+        .as_ref()
+        .and_then(|x| match convert_quest_log_from_database_json(&x) {
+            Ok(quest_log) => Some(quest_log),
+            Err(e) => {
+                warn!(
+                    "Error reading quest log from database for character ID {}, error: {}",
+                    char_id, e
+                );
+                None
+            },
+        })
+        .unwrap_or_default();
+
+    let char_pets = character_data
+        .pets
+        .as_ref()
+        .and_then(|x| match convert_pets_from_database_json(&x) {
+            Ok(pets) => Some(pets),
+            Err(e) => {
+                warn!(
+                    "Error reading pets from database for character ID {}, error: {}",
+                    char_id, e
+                );
+                None
+            },
+        })
+        .unwrap_or_default();
+
+    let char_presets = character_data
+        .presets
+        .as_ref()
+        .and_then(|x| match convert_presets_from_database_json(&x) {
+            Ok(presets) => Some(presets),
+            Err(e) => {
+                warn!(
+                    "Error reading presets from database for character ID {}, error: {}",
+                    char_id, e
+                );
+                None
+            },
+        })
+        .unwrap_or_default();
+
     #[rustfmt::skip]
     let mut stmt = connection.prepare_cached("
         SELECT  skill,
@@ -203,6 +290,10 @@ pub fn load_character_data(
             &loadout_items,
         )?,
         char_waypoint,
+        char_quest_log,
+        char_pets,
+        bank,
+        char_presets,
     ))
 }
 
@@ -232,6 +323,9 @@ pub fn load_character_list(player_uuid_: &str, connection: &Connection) -> Chara
                     alias: row.get(1)?,
                     player_uuid: player_uuid_.to_owned(),
                     waypoint: None, // Not used for character select
+                    quests: None,   // Not used for character select
+                    pets: None,     // Not used for character select
+                    presets: None,  // Not used for character select
                 })
             })?
             .map(|x| x.unwrap())
@@ -292,15 +386,17 @@ pub fn create_character(
 ) -> CharacterCreationResult {
     check_character_limit(uuid, connection)?;
 
-    let (body, _stats, skill_set, inventory, waypoint) = persisted_components;
+    let (body, _stats, skill_set, inventory, waypoint, _quest_log, _pets, _bank, _presets) =
+        persisted_components;
 
-    // Fetch new entity IDs for character, inventory and loadout
-    let mut new_entity_ids = get_new_entity_ids(connection, |next_id| next_id + 3)?;
+    // Fetch new entity IDs for character, inventory, loadout and bank
+    let mut new_entity_ids = get_new_entity_ids(connection, |next_id| next_id + 4)?;
 
     // Create pseudo-container items for character
     let character_id = new_entity_ids.next().unwrap();
     let inventory_container_id = new_entity_ids.next().unwrap();
     let loadout_container_id = new_entity_ids.next().unwrap();
+    let bank_container_id = new_entity_ids.next().unwrap();
 
     let pseudo_containers = vec![
         Item {
@@ -309,6 +405,8 @@ pub fn create_character(
             parent_container_item_id: WORLD_PSEUDO_CONTAINER_ID,
             item_definition_id: CHARACTER_PSEUDO_CONTAINER_DEF_ID.to_owned(),
             position: character_id.to_string(),
+            durability_lost: 0,
+            properties: None,
         },
         Item {
             stack_size: 1,
@@ -316,6 +414,8 @@ pub fn create_character(
             parent_container_item_id: character_id,
             item_definition_id: INVENTORY_PSEUDO_CONTAINER_DEF_ID.to_owned(),
             position: INVENTORY_PSEUDO_CONTAINER_POSITION.to_owned(),
+            durability_lost: 0,
+            properties: None,
         },
         Item {
             stack_size: 1,
@@ -323,6 +423,17 @@ pub fn create_character(
             parent_container_item_id: character_id,
             item_definition_id: LOADOUT_PSEUDO_CONTAINER_DEF_ID.to_owned(),
             position: LOADOUT_PSEUDO_CONTAINER_POSITION.to_owned(),
+            durability_lost: 0,
+            properties: None,
+        },
+        Item {
+            stack_size: 1,
+            item_id: bank_container_id,
+            parent_container_item_id: character_id,
+            item_definition_id: BANK_PSEUDO_CONTAINER_DEF_ID.to_owned(),
+            position: BANK_PSEUDO_CONTAINER_POSITION.to_owned(),
+            durability_lost: 0,
+            properties: None,
         },
     ];
 
@@ -332,8 +443,9 @@ pub fn create_character(
                           parent_container_item_id,
                           item_definition_id,
                           stack_size,
-                          position)
-        VALUES (?1, ?2, ?3, ?4, ?5)",
+                          position,
+                          durability_lost)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
     )?;
 
     for pseudo_container in pseudo_containers {
@@ -343,6 +455,7 @@ pub fn create_character(
             &pseudo_container.item_definition_id,
             &pseudo_container.stack_size,
             &pseudo_container.position,
+            &pseudo_container.durability_lost,
         ])?;
     }
 
@@ -420,8 +533,10 @@ pub fn create_character(
                           parent_container_item_id,
                           item_definition_id,
                           stack_size,
-                          position)
-        VALUES (?1, ?2, ?3, ?4, ?5)",
+                          position,
+                          durability_lost,
+                          properties)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
     )?;
 
     for item in inserts {
@@ -431,6 +546,8 @@ pub fn create_character(
             &item.model.item_definition_id,
             &item.model.stack_size,
             &item.model.position,
+            &item.model.durability_lost,
+            &item.model.properties,
         ])?;
     }
     drop(stmt);
@@ -438,12 +555,13 @@ pub fn create_character(
     load_character_list(uuid, connection).map(|list| (character_id, list))
 }
 
-/// Delete a character. Returns the updated character list.
+/// Delete a character. Returns the id of the deleted character and the
+/// updated character list.
 pub fn delete_character(
     requesting_player_uuid: &str,
     char_id: CharacterId,
     connection: &mut Transaction,
-) -> CharacterListResult {
+) -> CharacterDeletionResult {
     #[rustfmt::skip]
     let mut stmt = connection.prepare_cached("
         SELECT  COUNT(1)
@@ -484,6 +602,12 @@ pub fn delete_character(
     stmt.execute(&[&char_id])?;
     drop(stmt);
 
+    // Leave the character's guild
+    super::guild::delete_guild_member(char_id, connection)?;
+
+    // Give up the character's house
+    super::house::delete_house_owner(char_id, connection)?;
+
     // Delete character
     let mut stmt = connection.prepare_cached(
         "
@@ -538,7 +662,7 @@ pub fn delete_character(
         )));
     }
 
-    load_character_list(requesting_player_uuid, connection)
+    load_character_list(requesting_player_uuid, connection).map(|list| (char_id, list))
 }
 
 /// Before creating a character, we ensure that the limit on the number of
@@ -668,41 +792,223 @@ fn get_pseudo_container_id(
     }
 }
 
+/// Looks up a pseudo-container that may not exist yet, such as the bank of a
+/// character created before banks were added
+fn find_pseudo_container_id(
+    connection: &Connection,
+    parent_container_id: EntityId,
+    pseudo_container_position: &str,
+) -> Result<Option<EntityId>, PersistenceError> {
+    #[rustfmt::skip]
+    let mut stmt = connection.prepare_cached("\
+        SELECT  item_id
+        FROM    item
+        WHERE   parent_container_item_id = ?1
+        AND     position = ?2",
+    )?;
+
+    match stmt.query_row(
+        &[
+            &parent_container_id as &dyn ToSql,
+            &pseudo_container_position,
+        ],
+        |row| row.get(0),
+    ) {
+        Ok(id) => Ok(Some(id)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(DatabaseError(e)),
+    }
+}
+
+fn get_or_create_pseudo_container_id(
+    connection: &mut Transaction,
+    parent_container_id: EntityId,
+    pseudo_container_def_id: &str,
+    pseudo_container_position: &str,
+) -> Result<EntityId, PersistenceError> {
+    if let Some(id) =
+        find_pseudo_container_id(connection, parent_container_id, pseudo_container_position)?
+    {
+        return Ok(id);
+    }
+
+    let container_id = get_new_entity_ids(connection, |next_id| next_id + 1)?.start;
+
+    #[rustfmt::skip]
+    let mut stmt = connection.prepare_cached("
+        INSERT INTO item (item_id,
+                          parent_container_item_id,
+                          item_definition_id,
+                          stack_size,
+                          position,
+                          durability_lost)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+    )?;
+
+    stmt.execute(&[
+        &container_id as &dyn ToSql,
+        &parent_container_id,
+        &pseudo_container_def_id,
+        &1,
+        &pseudo_container_position,
+        &0,
+    ])?;
+
+    Ok(container_id)
+}
+
 pub fn update(
     char_id: CharacterId,
     char_skill_set: comp::SkillSet,
     inventory: comp::Inventory,
     char_waypoint: Option<comp::Waypoint>,
+    char_quest_log: comp::QuestLog,
+    char_pets: Vec<PetPersistenceData>,
+    bank: comp::Bank,
+    char_presets: comp::LoadoutPresets,
     connection: &mut Transaction,
 ) -> Result<(), PersistenceError> {
     let pseudo_containers = get_pseudo_containers(connection, char_id)?;
+    let bank_container_id = get_or_create_pseudo_container_id(
+        connection,
+        char_id,
+        BANK_PSEUDO_CONTAINER_DEF_ID,
+        BANK_PSEUDO_CONTAINER_POSITION,
+    )?;
 
     let mut upserts = Vec::new();
 
     // First, get all the entity IDs for any new items, and identify which
     // slots to upsert and which ones to delete.
     get_new_entity_ids(connection, |mut next_id| {
-        let upserts_ = convert_items_to_database_items(
+        let mut upserts_ = convert_items_to_database_items(
             pseudo_containers.loadout_container_id,
             &inventory,
             pseudo_containers.inventory_container_id,
             &mut next_id,
         );
+        // The bank has no loadout, so all of its items go in the one container
+        upserts_.extend(convert_items_to_database_items(
+            bank_container_id,
+            &bank.0,
+            bank_container_id,
+            &mut next_id,
+        ));
         upserts = upserts_;
         next_id
     })?;
 
-    // Next, delete any slots we aren't upserting.
-    trace!("Deleting items for character_id {}", char_id);
-    let mut existing_item_ids: Vec<_> = vec![
-        Value::from(pseudo_containers.inventory_container_id),
-        Value::from(pseudo_containers.loadout_container_id),
-    ];
-    for it in load_items_bfs(connection, pseudo_containers.inventory_container_id)? {
-        existing_item_ids.push(Value::from(it.item_id));
+    trace!("Updating items for character_id {}", char_id);
+    replace_container_items(
+        connection,
+        &[
+            pseudo_containers.inventory_container_id,
+            pseudo_containers.loadout_container_id,
+            bank_container_id,
+        ],
+        upserts,
+    )?;
+
+    let db_skill_groups = convert_skill_groups_to_database(char_id, char_skill_set.skill_groups);
+
+    #[rustfmt::skip]
+    let mut stmt =  connection.prepare_cached("
+        REPLACE
+        INTO    skill_group (entity_id,
+                             skill_group_kind,
+                             exp,
+                             available_sp,
+                             earned_sp)
+        VALUES (?1, ?2, ?3, ?4, ?5)")?;
+
+    for skill_group in db_skill_groups {
+        stmt.execute(&[
+            &skill_group.entity_id as &dyn ToSql,
+            &skill_group.skill_group_kind,
+            &skill_group.exp,
+            &skill_group.available_sp,
+            &skill_group.earned_sp,
+        ])?;
     }
-    for it in load_items_bfs(connection, pseudo_containers.loadout_container_id)? {
-        existing_item_ids.push(Value::from(it.item_id));
+
+    let db_skills = convert_skills_to_database(char_id, char_skill_set.skills);
+
+    let known_skills = Rc::new(
+        db_skills
+            .iter()
+            .map(|x| Value::from(x.skill.clone()))
+            .collect::<Vec<Value>>(),
+    );
+
+    #[rustfmt::skip]
+    let mut stmt = connection.prepare_cached("
+        DELETE
+        FROM    skill
+        WHERE   entity_id = ?1
+        AND     skill NOT IN rarray(?2)")?;
+
+    let delete_count = stmt.execute(&[&char_id as &dyn ToSql, &known_skills])?;
+    trace!("Deleted {} skills", delete_count);
+
+    #[rustfmt::skip]
+    let mut stmt =  connection.prepare_cached("
+        REPLACE 
+        INTO    skill (entity_id,
+                       skill,
+                       level)
+        VALUES (?1, ?2, ?3)")?;
+
+    for skill in db_skills {
+        stmt.execute(&[&skill.entity_id as &dyn ToSql, &skill.skill, &skill.level])?;
+    }
+
+    let db_waypoint = convert_waypoint_to_database_json(char_waypoint);
+    let db_quests = convert_quest_log_to_database_json(&char_quest_log)?;
+    let db_pets = convert_pets_to_database_json(&char_pets)?;
+    let db_presets = convert_presets_to_database_json(&char_presets)?;
+
+    #[rustfmt::skip]
+    let mut stmt =  connection.prepare_cached("
+        UPDATE  character
+        SET     waypoint = ?1,
+                quests = ?2,
+                pets = ?3,
+                presets = ?4
+        WHERE   character_id = ?5
+    ")?;
+
+    let character_count = stmt.execute(&[
+        &db_waypoint as &dyn ToSql,
+        &db_quests,
+        &db_pets,
+        &db_presets,
+        &char_id,
+    ])?;
+
+    if character_count != 1 {
+        return Err(PersistenceError::OtherError(format!(
+            "Error updating character table for char_id {}",
+            char_id
+        )));
+    }
+
+    Ok(())
+}
+
+/// Deletes the items in the given containers that are not among the upserts,
+/// then writes the upserts. Only items still stored under these containers
+/// are deleted, so an item moved elsewhere (e.g. from a character's inventory
+/// into a storage chest) is kept for the save of the container it went to.
+fn replace_container_items(
+    connection: &mut Transaction,
+    container_ids: &[EntityId],
+    upserts: Vec<ItemModelPair>,
+) -> Result<(), PersistenceError> {
+    let mut existing_item_ids: Vec<_> = container_ids.iter().copied().map(Value::from).collect();
+    for container_id in container_ids {
+        for it in load_items_bfs(connection, *container_id)? {
+            existing_item_ids.push(Value::from(it.item_id));
+        }
     }
 
     let non_upserted_items = upserts
@@ -733,11 +1039,7 @@ pub fn update(
                 (model_pair.model, model_pair.comp)
             })
             .unzip();
-        trace!(
-            "Upserting items {:?} for character_id {}",
-            upserted_items,
-            char_id
-        );
+        trace!("Upserting items {:?}", upserted_items);
 
         // When moving inventory items around, foreign key constraints on
         // `parent_container_item_id` can be temporarily violated by one
@@ -755,8 +1057,10 @@ pub fn update(
                           parent_container_item_id,
                           item_definition_id,
                           stack_size,
-                          position)
-            VALUES  (?1, ?2, ?3, ?4, ?5)")?;
+                          position,
+                          durability_lost,
+                          properties)
+            VALUES  (?1, ?2, ?3, ?4, ?5, ?6, ?7)")?;
 
         for item in upserted_items.iter() {
             stmt.execute(&[
@@ -765,79 +1069,283 @@ pub fn update(
                 &item.item_definition_id,
                 &item.stack_size,
                 &item.position,
+                &item.durability_lost,
+                &item.properties,
             ])?;
         }
     }
 
-    let db_skill_groups = convert_skill_groups_to_database(char_id, char_skill_set.skill_groups);
+    Ok(())
+}
 
+/// Loads the contents of every storage container placed in the world
+pub fn load_storage_containers(
+    connection: &Connection,
+) -> Result<Vec<(Vec3<i32>, Inventory)>, PersistenceError> {
     #[rustfmt::skip]
-    let mut stmt =  connection.prepare_cached("
-        REPLACE
-        INTO    skill_group (entity_id,
-                             skill_group_kind,
-                             exp,
-                             available_sp,
-                             earned_sp)
-        VALUES (?1, ?2, ?3, ?4, ?5)")?;
+    let mut stmt = connection.prepare_cached("
+        SELECT  item_id,
+                position
+        FROM    item
+        WHERE   parent_container_item_id = ?1
+        AND     item_definition_id = ?2")?;
+
+    let containers = stmt
+        .query_map(
+            &[
+                &WORLD_PSEUDO_CONTAINER_ID as &dyn ToSql,
+                &STORAGE_PSEUDO_CONTAINER_DEF_ID,
+            ],
+            |row| Ok((row.get::<_, EntityId>(0)?, row.get::<_, String>(1)?)),
+        )?
+        .filter_map(Result::ok)
+        .collect::<Vec<_>>();
+
+    containers
+        .into_iter()
+        .map(|(container_id, position)| {
+            let items = load_items_bfs(connection, container_id)?;
+            Ok((
+                convert_storage_position_from_database(&position)?,
+                convert_storage_from_database_items(container_id, &items, CONTAINER_SLOTS)?,
+            ))
+        })
+        .collect()
+}
 
-    for skill_group in db_skill_groups {
-        stmt.execute(&[
-            &skill_group.entity_id as &dyn ToSql,
-            &skill_group.skill_group_kind,
-            &skill_group.exp,
-            &skill_group.available_sp,
-            &skill_group.earned_sp,
-        ])?;
+/// Saves the contents of storage containers in the world, creating the
+/// pseudo-containers of any that were placed since the last save
+pub fn update_storage_containers(
+    containers: Vec<(Vec3<i32>, Inventory)>,
+    connection: &mut Transaction,
+) -> Result<(), PersistenceError> {
+    for (pos, inventory) in containers {
+        let container_id = get_or_create_pseudo_container_id(
+            connection,
+            WORLD_PSEUDO_CONTAINER_ID,
+            STORAGE_PSEUDO_CONTAINER_DEF_ID,
+            &convert_storage_position_to_database(pos),
+        )?;
+
+        let mut upserts = Vec::new();
+        get_new_entity_ids(connection, |mut next_id| {
+            upserts = convert_items_to_database_items(
+                container_id,
+                &inventory,
+                container_id,
+                &mut next_id,
+            );
+            next_id
+        })?;
+
+        trace!("Updating items for storage container at {:?}", pos);
+        replace_container_items(connection, &[container_id], upserts)?;
     }
 
-    let db_skills = convert_skills_to_database(char_id, char_skill_set.skills);
+    Ok(())
+}
 
-    let known_skills = Rc::new(
-        db_skills
-            .iter()
-            .map(|x| Value::from(x.skill.clone()))
-            .collect::<Vec<Value>>(),
-    );
+/// Loads the names of all characters, so that letters can be addressed to
+/// characters that are not online
+pub fn load_character_aliases(
+    connection: &Connection,
+) -> Result<Vec<(CharacterId, String)>, PersistenceError> {
+    #[rustfmt::skip]
+    let mut stmt = connection.prepare_cached("
+        SELECT  character_id,
+                alias
+        FROM    character")?;
+
+    let aliases = stmt
+        .query_map(NO_PARAMS, |row| Ok((row.get(0)?, row.get(1)?)))?
+        .filter_map(Result::ok)
+        .collect();
 
+    Ok(aliases)
+}
+
+/// Finds the highest id any letter ever had, including those that were
+/// already collected and only remain in the mail log, so that ids are never
+/// reused
+pub fn load_last_mail_id(connection: &Connection) -> Result<MailId, PersistenceError> {
     #[rustfmt::skip]
     let mut stmt = connection.prepare_cached("
-        DELETE
-        FROM    skill
-        WHERE   entity_id = ?1
-        AND     skill NOT IN rarray(?2)")?;
+        SELECT  MAX(mail_id)
+        FROM    (SELECT mail_id FROM mail
+                 UNION ALL
+                 SELECT mail_id FROM mail_log)")?;
 
-    let delete_count = stmt.execute(&[&char_id as &dyn ToSql, &known_skills])?;
-    trace!("Deleted {} skills", delete_count);
+    let last_id = stmt.query_row(NO_PARAMS, |row| row.get::<_, Option<i64>>(0))?;
+    Ok(last_id.map_or(0, |id| id as MailId))
+}
 
+/// Loads every letter that is yet to be collected
+pub fn load_mail(
+    connection: &Connection,
+    aliases: &HashMap<CharacterId, String>,
+) -> Result<Vec<Mail>, PersistenceError> {
     #[rustfmt::skip]
-    let mut stmt =  connection.prepare_cached("
-        REPLACE 
-        INTO    skill (entity_id,
-                       skill,
-                       level)
-        VALUES (?1, ?2, ?3)")?;
+    let mut stmt = connection.prepare_cached("
+        SELECT  mail_id,
+                sender_character_id,
+                recipient_character_id,
+                message,
+                sent_at,
+                returned
+        FROM    mail")?;
+
+    let rows = stmt
+        .query_map(NO_PARAMS, |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, CharacterId>(1)?,
+                row.get::<_, CharacterId>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, i64>(4)?,
+                row.get::<_, bool>(5)?,
+            ))
+        })?
+        .filter_map(Result::ok)
+        .collect::<Vec<_>>();
 
-    for skill in db_skills {
-        stmt.execute(&[&skill.entity_id as &dyn ToSql, &skill.skill, &skill.level])?;
+    rows.into_iter()
+        .map(|(mail_id, sender, recipient, message, sent_at, returned)| {
+            let mail_id = mail_id as u64;
+            let attachments = match find_pseudo_container_id(
+                connection,
+                WORLD_PSEUDO_CONTAINER_ID,
+                &convert_mail_position_to_database(mail_id),
+            )? {
+                Some(container_id) => convert_storage_from_database_items(
+                    container_id,
+                    &load_items_bfs(connection, container_id)?,
+                    MAX_ATTACHMENTS,
+                )?,
+                None => Inventory::new_with_capacity(MAX_ATTACHMENTS),
+            };
+            let alias = |character_id| aliases.get(&character_id).cloned().unwrap_or_default();
+
+            Ok(Mail {
+                id: mail_id,
+                sender,
+                sender_alias: alias(sender),
+                recipient,
+                recipient_alias: alias(recipient),
+                message,
+                attachments,
+                sent_at: sent_at as u64,
+                returned,
+            })
+        })
+        .collect()
+}
+
+/// Saves changes to letters and writes them to the mail log. This runs in the
+/// same transaction as the update of the characters that sent or collected
+/// the letters, so their items are never saved both in a letter and in an
+/// inventory.
+pub fn update_mail(
+    changes: Vec<MailChange>,
+    connection: &mut Transaction,
+) -> Result<(), PersistenceError> {
+    #[rustfmt::skip]
+    let mut stmt = connection.prepare_cached("
+        INSERT
+        INTO    mail_log (mail_id,
+                          action,
+                          sender_character_id,
+                          recipient_character_id,
+                          attachments,
+                          logged_at)
+        VALUES  (?1, ?2, ?3, ?4, ?5, strftime('%s', 'now'))")?;
+
+    for change in changes.iter() {
+        stmt.execute(&[
+            &(change.mail.id as i64) as &dyn ToSql,
+            &change.action.as_str(),
+            &change.mail.sender,
+            &change.mail.recipient,
+            &describe_attachments(&change.mail),
+        ])?;
     }
+    drop(stmt);
 
-    let db_waypoint = convert_waypoint_to_database_json(char_waypoint);
+    // Only the latest state of each letter needs saving
+    let mut latest = HashMap::new();
+    for change in changes {
+        latest.insert(change.mail.id, change);
+    }
 
-    #[rustfmt::skip]
-    let mut stmt =  connection.prepare_cached("
-        UPDATE  character
-        SET     waypoint = ?1
-        WHERE   character_id = ?2
-    ")?;
+    for (mail_id, MailChange { action, mail }) in latest {
+        let position = convert_mail_position_to_database(mail_id);
+        match action {
+            MailAction::Sent | MailAction::Returned => {
+                #[rustfmt::skip]
+                let mut stmt = connection.prepare_cached("
+                    REPLACE
+                    INTO    mail (mail_id,
+                                  sender_character_id,
+                                  recipient_character_id,
+                                  message,
+                                  sent_at,
+                                  returned)
+                    VALUES  (?1, ?2, ?3, ?4, ?5, ?6)")?;
+                stmt.execute(&[
+                    &(mail_id as i64) as &dyn ToSql,
+                    &mail.sender,
+                    &mail.recipient,
+                    &mail.message,
+                    &(mail.sent_at as i64),
+                    &mail.returned,
+                ])?;
+                drop(stmt);
+
+                let container_id = get_or_create_pseudo_container_id(
+                    connection,
+                    WORLD_PSEUDO_CONTAINER_ID,
+                    MAIL_PSEUDO_CONTAINER_DEF_ID,
+                    &position,
+                )?;
+
+                let mut upserts = Vec::new();
+                get_new_entity_ids(connection, |mut next_id| {
+                    upserts = convert_items_to_database_items(
+                        container_id,
+                        &mail.attachments,
+                        container_id,
+                        &mut next_id,
+                    );
+                    next_id
+                })?;
 
-    let waypoint_count = stmt.execute(&[&db_waypoint as &dyn ToSql, &char_id])?;
+                trace!("Updating attachments of mail {}", mail_id);
+                replace_container_items(connection, &[container_id], upserts)?;
+            },
+            MailAction::Claimed | MailAction::Discarded => {
+                if let Some(container_id) =
+                    find_pseudo_container_id(connection, WORLD_PSEUDO_CONTAINER_ID, &position)?
+                {
+                    // The attachments of claimed letters now belong to the recipient, so only
+                    // items that somehow remained in the letter are deleted here. Discarded
+                    // letters lose their attachments along with them.
+                    replace_container_items(connection, &[container_id], Vec::new())?;
+
+                    #[rustfmt::skip]
+                    let mut stmt = connection.prepare_cached("
+                        DELETE
+                        FROM    item
+                        WHERE   item_id = ?1")?;
+                    stmt.execute(&[container_id])?;
+                }
 
-    if waypoint_count != 1 {
-        return Err(PersistenceError::OtherError(format!(
-            "Error updating character table for char_id {}",
-            char_id
-        )));
+                #[rustfmt::skip]
+                let mut stmt = connection.prepare_cached("
+                    DELETE
+                    FROM    mail
+                    WHERE   mail_id = ?1")?;
+                stmt.execute(&[mail_id as i64])?;
+            },
+        }
     }
 
     Ok(())
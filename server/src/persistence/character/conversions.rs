@@ -8,6 +8,7 @@ use crate::persistence::{
     json_models::{self, CharacterPosition, HumanoidBody},
 };
 use common::{
+    assets::AssetExt,
     character::CharacterId,
     comp::{
         inventory::{
@@ -16,15 +17,18 @@ use common::{
             loadout_builder::LoadoutBuilder,
             slot::InvSlotId,
         },
+        pet::PetPersistenceData,
         skills, Body as CompBody, Waypoint, *,
     },
+    mail::MailId,
     resources::Time,
 };
 use core::{convert::TryFrom, num::NonZeroU64};
 use hashbrown::HashMap;
 use lazy_static::lazy_static;
 use std::{collections::VecDeque, sync::Arc};
-use tracing::trace;
+use tracing::{trace, warn};
+use vek::*;
 
 #[derive(Debug)]
 pub struct ItemModelPair {
@@ -175,6 +179,8 @@ pub fn convert_items_to_database_items(
                     } else {
                         1
                     },
+                    durability_lost: item.durability_lost() as i32,
+                    properties: convert_item_properties_to_database_json(item),
                 },
                 // Continue to remember the atomic, in case we detect an error later and want
                 // to roll back to preserve liveness.
@@ -188,6 +194,37 @@ pub fn convert_items_to_database_items(
     upserts
 }
 
+/// Items without crafted quality, modifiers or gems don't store any properties
+fn convert_item_properties_to_database_json(item: &common::comp::Item) -> Option<String> {
+    if item.quality_bonus() == 0
+        && item.modifiers().is_empty()
+        && item.sockets().iter().all(Option::is_none)
+    {
+        return None;
+    }
+    serde_json::to_string(&json_models::ItemProperties::from(item)).ok()
+}
+
+/// Properties that can't be read, such as modifiers that no longer exist, are
+/// dropped rather than failing to load the item
+fn apply_item_properties_from_database_json(item: &mut common::comp::Item, db_item: &Item) {
+    let properties = match &db_item.properties {
+        Some(properties) => properties,
+        None => return,
+    };
+    match serde_json::de::from_str::<json_models::ItemProperties>(properties) {
+        Ok(properties) => {
+            item.set_quality_bonus(properties.quality_bonus);
+            item.set_modifiers(properties.modifiers, &ABILITY_MAP, &MATERIAL_STATS_MANIFEST);
+            item.set_sockets(properties.sockets, &ABILITY_MAP, &MATERIAL_STATS_MANIFEST);
+        },
+        Err(err) => warn!(
+            "Error de-serializing properties of item {}: {} err: {}",
+            db_item.item_id, properties, err
+        ),
+    }
+}
+
 pub fn convert_body_to_database_json(body: &CompBody) -> Result<String, PersistenceError> {
     let json_model = match body {
         common::comp::Body::Humanoid(humanoid_body) => HumanoidBody::from(humanoid_body),
@@ -229,6 +266,138 @@ pub fn convert_waypoint_from_database_json(position: &str) -> Result<Waypoint, P
     Ok(Waypoint::new(character_position.waypoint, Time(0.0)))
 }
 
+/// Storage containers in the world are kept under the world pseudo-container,
+/// with the block position of the container as their position
+pub fn convert_storage_position_to_database(pos: Vec3<i32>) -> String {
+    format!("storage_{}_{}_{}", pos.x, pos.y, pos.z)
+}
+
+pub fn convert_storage_position_from_database(
+    position: &str,
+) -> Result<Vec3<i32>, PersistenceError> {
+    let error = || {
+        PersistenceError::ConversionError(format!(
+            "Error parsing storage container position: {}",
+            position
+        ))
+    };
+    let coords = position
+        .strip_prefix("storage_")
+        .ok_or_else(error)?
+        .split('_')
+        .map(|coord| coord.parse::<i32>().map_err(|_| error()))
+        .collect::<Result<Vec<_>, _>>()?;
+    match coords.as_slice() {
+        [x, y, z] => Ok(Vec3::new(*x, *y, *z)),
+        _ => Err(error()),
+    }
+}
+
+/// The attachments of each letter are kept under a pseudo-container of the
+/// world named after the letter
+pub fn convert_mail_position_to_database(mail_id: MailId) -> String { format!("mail_{}", mail_id) }
+
+pub fn convert_quest_log_to_database_json(
+    quest_log: &QuestLog,
+) -> Result<String, PersistenceError> {
+    serde_json::to_string(&json_models::QuestLog::from(quest_log))
+        .map_err(PersistenceError::SerializationError)
+}
+
+pub fn convert_quest_log_from_database_json(quests: &str) -> Result<QuestLog, PersistenceError> {
+    let quest_log = serde_json::de::from_str::<json_models::QuestLog>(quests).map_err(|err| {
+        PersistenceError::ConversionError(format!(
+            "Error de-serializing quest log: {} err: {}",
+            quests, err
+        ))
+    })?;
+    Ok(QuestLog {
+        active: quest_log
+            .quests
+            .into_iter()
+            // Quests whose template has since been removed can't be completed
+            .filter(|quest| quest::QuestTemplate::load(&quest.template).is_ok())
+            .map(|quest| quest::Quest {
+                template: quest.template,
+                giver: quest.giver,
+                destination: quest.destination.map(|destination| quest::Destination {
+                    name: destination.name,
+                    wpos: destination.wpos,
+                }),
+                escortee: None,
+                progress: quest.progress,
+            })
+            .collect(),
+        completed: quest_log.completed,
+    })
+}
+
+pub fn convert_pets_to_database_json(
+    pets: &[PetPersistenceData],
+) -> Result<String, PersistenceError> {
+    serde_json::to_string(&pets.iter().map(json_models::Pet::from).collect::<Vec<_>>())
+        .map_err(PersistenceError::SerializationError)
+}
+
+pub fn convert_pets_from_database_json(
+    pets: &str,
+) -> Result<Vec<PetPersistenceData>, PersistenceError> {
+    let pets = serde_json::de::from_str::<Vec<json_models::Pet>>(pets).map_err(|err| {
+        PersistenceError::ConversionError(format!(
+            "Error de-serializing pets: {} err: {}",
+            pets, err
+        ))
+    })?;
+    Ok(pets
+        .into_iter()
+        .map(|pet| PetPersistenceData {
+            name: pet.name,
+            body: pet.body,
+        })
+        .collect())
+}
+
+pub fn convert_presets_to_database_json(
+    presets: &LoadoutPresets,
+) -> Result<String, PersistenceError> {
+    serde_json::to_string(
+        &presets
+            .presets
+            .iter()
+            .map(json_models::LoadoutPreset::from)
+            .collect::<Vec<_>>(),
+    )
+    .map_err(PersistenceError::SerializationError)
+}
+
+pub fn convert_presets_from_database_json(
+    presets: &str,
+) -> Result<LoadoutPresets, PersistenceError> {
+    let db_presets =
+        serde_json::de::from_str::<Vec<json_models::LoadoutPreset>>(presets).map_err(|err| {
+            PersistenceError::ConversionError(format!(
+                "Error de-serializing presets: {} err: {}",
+                presets, err
+            ))
+        })?;
+    Ok(LoadoutPresets {
+        presets: db_presets
+            .into_iter()
+            .map(|preset| LoadoutPreset {
+                name: preset.name,
+                skills: preset
+                    .skills
+                    .iter()
+                    .filter_map(|(skill, level)| {
+                        json_models::db_string_to_skill(skill).map(|skill| (skill, *level))
+                    })
+                    .collect(),
+                equipment: preset.equipment,
+            })
+            .collect(),
+    })
+}
+
 /// Properly-recursive items (currently modular weapons) occupy the same
 /// inventory slot as their parent. The caller is responsible for ensuring that
 /// inventory_items and loadout_items are topologically sorted (i.e. forall i,
@@ -248,6 +417,29 @@ pub fn convert_inventory_from_database_items(
     //
     let loadout = convert_loadout_from_database_items(loadout_container_id, loadout_items)?;
     let mut inventory = Inventory::new_with_loadout(loadout);
+    insert_database_items(&mut inventory, inventory_container_id, inventory_items)?;
+
+    Ok(inventory)
+}
+
+/// Loads a storage container, such as a bank or a chest in the world, which
+/// holds items in a fixed number of slots and has no loadout
+pub fn convert_storage_from_database_items(
+    container_id: i64,
+    items: &[Item],
+    capacity: usize,
+) -> Result<Inventory, PersistenceError> {
+    let mut inventory = Inventory::new_with_capacity(capacity);
+    insert_database_items(&mut inventory, container_id, items)?;
+
+    Ok(inventory)
+}
+
+fn insert_database_items(
+    inventory: &mut Inventory,
+    inventory_container_id: i64,
+    inventory_items: &[Item],
+) -> Result<(), PersistenceError> {
     let mut item_indices = HashMap::new();
 
     for (i, db_item) in inventory_items.iter().enumerate() {
@@ -280,6 +472,10 @@ pub fn convert_inventory_from_database_items(
             })?;
         }
 
+        // Durability, crafted quality, modifiers and sockets
+        item.set_durability_lost(u32::try_from(db_item.durability_lost).unwrap_or(0));
+        apply_item_properties_from_database_json(&mut item, db_item);
+
         // Insert item into inventory
 
         // Slot position
@@ -334,7 +530,7 @@ pub fn convert_inventory_from_database_items(
         }
     }
 
-    Ok(inventory)
+    Ok(())
 }
 
 pub fn convert_loadout_from_database_items(
@@ -348,7 +544,7 @@ pub fn convert_loadout_from_database_items(
     for (i, db_item) in database_items.iter().enumerate() {
         item_indices.insert(db_item.item_id, i);
 
-        let item = get_item_from_asset(db_item.item_definition_id.as_str())?;
+        let mut item = get_item_from_asset(db_item.item_definition_id.as_str())?;
 
         // NOTE: item id is currently *unique*, so we can store the ID safely.
         let comp = item.get_item_id_for_database();
@@ -356,6 +552,9 @@ pub fn convert_loadout_from_database_items(
             |_| PersistenceError::ConversionError("Item with zero item_id".to_owned()),
         )?));
 
+        item.set_durability_lost(u32::try_from(db_item.durability_lost).unwrap_or(0));
+        apply_item_properties_from_database_json(&mut item, db_item);
+
         let convert_error = |err| match err {
             LoadoutError::InvalidPersistenceKey => PersistenceError::ConversionError(format!(
                 "Invalid persistence key: {}",
@@ -466,7 +665,17 @@ fn get_item_from_asset(item_definition_id: &str) -> Result<common::comp::Item, P
 fn convert_skill_groups_from_database(skill_groups: &[SkillGroup]) -> Vec<skills::SkillGroup> {
     let mut new_skill_groups = Vec::new();
     for skill_group in skill_groups.iter() {
-        let skill_group_kind = json_models::db_string_to_skill_group(&skill_group.skill_group_kind);
+        let skill_group_kind =
+            match json_models::db_string_to_skill_group(&skill_group.skill_group_kind) {
+                Some(skill_group_kind) => skill_group_kind,
+                None => {
+                    warn!(
+                        "Skipping skill group {} that is no longer declared",
+                        skill_group.skill_group_kind
+                    );
+                    continue;
+                },
+            };
         let new_skill_group = skills::SkillGroup {
             skill_group_kind,
             exp: skill_group.exp as u16,
@@ -481,8 +690,11 @@ fn convert_skill_groups_from_database(skill_groups: &[SkillGroup]) -> Vec<skills
 fn convert_skills_from_database(skills: &[Skill]) -> HashMap<skills::Skill, Option<u16>> {
     let mut new_skills = HashMap::new();
     for skill in skills.iter() {
-        let new_skill = json_models::db_string_to_skill(&skill.skill);
-        new_skills.insert(new_skill, skill.level.map(|l| l as u16));
+        if let Some(new_skill) = json_models::db_string_to_skill(&skill.skill) {
+            new_skills.insert(new_skill, skill.level.map(|l| l as u16));
+        } else {
+            warn!("Skipping skill {} that is no longer declared", skill.skill);
+        }
     }
     new_skills
 }
@@ -516,3 +728,34 @@ pub fn convert_skills_to_database(
         })
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quality_bonus_survives_saving() {
+        let mut item =
+            common::comp::Item::new_from_asset_expect("common.items.weapons.hammer_1h.iron-1");
+        item.set_quality_bonus(2);
+        let mut storage = Inventory::new_with_capacity(1);
+        assert!(storage.push(item).is_ok());
+
+        let mut next_id = 3;
+        let db_items = convert_items_to_database_items(1, &storage, 2, &mut next_id)
+            .into_iter()
+            .map(|pair| pair.model)
+            .collect::<Vec<_>>();
+        let loaded = convert_storage_from_database_items(2, &db_items, 1)
+            .expect("Failed to load the saved item");
+
+        assert_eq!(
+            loaded
+                .slots()
+                .flatten()
+                .map(common::comp::Item::quality_bonus)
+                .collect::<Vec<_>>(),
+            vec![2]
+        );
+    }
+}
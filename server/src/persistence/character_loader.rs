@@ -12,6 +12,8 @@ use tracing::error;
 pub(crate) type CharacterListResult = Result<Vec<CharacterItem>, PersistenceError>;
 pub(crate) type CharacterCreationResult =
     Result<(CharacterId, Vec<CharacterItem>), PersistenceError>;
+pub(crate) type CharacterDeletionResult =
+    Result<(CharacterId, Vec<CharacterItem>), PersistenceError>;
 pub(crate) type CharacterDataResult = Result<PersistedComponents, PersistenceError>;
 type CharacterLoaderRequest = (specs::Entity, CharacterLoaderRequestKind);
 
@@ -33,6 +35,7 @@ pub enum CharacterLoaderResponseKind {
     CharacterList(CharacterListResult),
     CharacterData(Box<CharacterDataResult>),
     CharacterCreation(CharacterCreationResult),
+    CharacterDeletion(CharacterDeletionResult),
 }
 
 /// Common message format dispatched in response to an update request
@@ -49,6 +52,7 @@ impl CharacterLoaderResponse {
             CharacterLoaderResponseKind::CharacterData(box Err(_))
                 | CharacterLoaderResponseKind::CharacterList(Err(_))
                 | CharacterLoaderResponseKind::CharacterCreation(Err(_))
+                | CharacterLoaderResponseKind::CharacterDeletion(Err(_))
         )
     }
 }
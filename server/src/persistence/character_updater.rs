@@ -1,5 +1,5 @@
-use crate::comp;
-use common::character::CharacterId;
+use crate::{comp, farming::Crop, guild::GuildChange, housing::HousingChange, mail::MailChange};
+use common::{character::CharacterId, comp::pet::PetPersistenceData};
 
 use crate::persistence::{
     character_loader::{CharacterLoaderResponse, CharacterLoaderResponseKind},
@@ -17,12 +17,28 @@ use std::{
     },
 };
 use tracing::{debug, error, info, trace, warn};
+use vek::*;
 
-pub type CharacterUpdateData = (comp::SkillSet, comp::Inventory, Option<comp::Waypoint>);
+pub type CharacterUpdateData = (
+    comp::SkillSet,
+    comp::Inventory,
+    Option<comp::Waypoint>,
+    comp::QuestLog,
+    Vec<PetPersistenceData>,
+    comp::Bank,
+    comp::LoadoutPresets,
+);
 
 #[allow(clippy::large_enum_variant)]
 pub enum CharacterUpdaterEvent {
-    BatchUpdate(Vec<(CharacterId, CharacterUpdateData)>),
+    BatchUpdate(
+        Vec<(CharacterId, CharacterUpdateData)>,
+        Vec<MailChange>,
+        Vec<(Vec3<i32>, comp::Inventory)>,
+    ),
+    UpdateGuilds(Vec<GuildChange>),
+    UpdateHouses(Vec<HousingChange>),
+    UpdateCrops(Vec<(Vec3<i32>, Option<Crop>)>),
     CreateCharacter {
         entity: Entity,
         player_uuid: String,
@@ -69,7 +85,7 @@ impl CharacterUpdater {
                     establish_connection(&*settings.read().unwrap(), ConnectionMode::ReadWrite);
                 while let Ok(updates) = update_rx.recv() {
                     match updates {
-                        CharacterUpdaterEvent::BatchUpdate(updates) => {
+                        CharacterUpdaterEvent::BatchUpdate(updates, mail, containers) => {
                             if disconnect_all_clients_requested_clone.load(Ordering::Relaxed) {
                                 debug!(
                                     "Skipping persistence due to pending disconnection of all \
@@ -78,7 +94,9 @@ impl CharacterUpdater {
                                 continue;
                             }
                             conn.update_log_mode(&settings);
-                            if let Err(e) = execute_batch_update(updates, &mut conn) {
+                            if let Err(e) =
+                                execute_batch_update(updates, mail, containers, &mut conn)
+                            {
                                 error!(
                                     "Error during character batch update, disconnecting all \
                                      clients to avoid loss of data integrity. Error: {:?}",
@@ -88,6 +106,42 @@ impl CharacterUpdater {
                                     .store(true, Ordering::Relaxed);
                             };
                         },
+                        CharacterUpdaterEvent::UpdateGuilds(changes) => {
+                            if disconnect_all_clients_requested_clone.load(Ordering::Relaxed) {
+                                debug!(
+                                    "Skipping guild persistence due to pending disconnection of \
+                                     all clients"
+                                );
+                                continue;
+                            }
+                            if let Err(e) = execute_guild_update(changes, &mut conn) {
+                                error!(?e, "Error during guild update");
+                            }
+                        },
+                        CharacterUpdaterEvent::UpdateHouses(changes) => {
+                            if disconnect_all_clients_requested_clone.load(Ordering::Relaxed) {
+                                debug!(
+                                    "Skipping house persistence due to pending disconnection of \
+                                     all clients"
+                                );
+                                continue;
+                            }
+                            if let Err(e) = execute_house_update(changes, &mut conn) {
+                                error!(?e, "Error during house update");
+                            }
+                        },
+                        CharacterUpdaterEvent::UpdateCrops(crops) => {
+                            if disconnect_all_clients_requested_clone.load(Ordering::Relaxed) {
+                                debug!(
+                                    "Skipping crop persistence due to pending disconnection of \
+                                     all clients"
+                                );
+                                continue;
+                            }
+                            if let Err(e) = execute_crop_update(crops, &mut conn) {
+                                error!(?e, "Error during crop update");
+                            }
+                        },
                         CharacterUpdaterEvent::CreateCharacter {
                             entity,
                             character_alias,
@@ -250,7 +304,9 @@ impl CharacterUpdater {
         }
     }
 
-    /// Updates a collection of characters based on their id and components
+    /// Updates a collection of characters based on their id and components,
+    /// along with any letters they sent or collected and the storage
+    /// containers in the world that changed since they were last saved
     pub fn batch_update<'a>(
         &mut self,
         updates: impl Iterator<
@@ -259,16 +315,32 @@ impl CharacterUpdater {
                 &'a comp::SkillSet,
                 &'a comp::Inventory,
                 Option<&'a comp::Waypoint>,
+                Option<&'a comp::QuestLog>,
+                Vec<PetPersistenceData>,
+                Option<&'a comp::Bank>,
+                Option<&'a comp::LoadoutPresets>,
             ),
         >,
+        mail: Vec<MailChange>,
+        containers: Vec<(Vec3<i32>, comp::Inventory)>,
     ) {
         let updates = updates
-            .map(|(character_id, skill_set, inventory, waypoint)| {
-                (
-                    character_id,
-                    (skill_set.clone(), inventory.clone(), waypoint.cloned()),
-                )
-            })
+            .map(
+                |(character_id, skill_set, inventory, waypoint, quest_log, pets, bank, presets)| {
+                    (
+                        character_id,
+                        (
+                            skill_set.clone(),
+                            inventory.clone(),
+                            waypoint.cloned(),
+                            quest_log.cloned().unwrap_or_default(),
+                            pets,
+                            bank.cloned().unwrap_or_default(),
+                            presets.cloned().unwrap_or_default(),
+                        ),
+                    )
+                },
+            )
             .chain(self.pending_logout_updates.drain())
             .collect::<Vec<_>>();
 
@@ -276,12 +348,64 @@ impl CharacterUpdater {
             .update_tx
             .as_ref()
             .unwrap()
-            .send(CharacterUpdaterEvent::BatchUpdate(updates))
+            .send(CharacterUpdaterEvent::BatchUpdate(
+                updates, mail, containers,
+            ))
         {
             error!(?e, "Could not send stats updates");
         }
     }
 
+    /// Saves the changes made to guilds since they were last saved
+    pub fn update_guilds(&mut self, changes: Vec<GuildChange>) {
+        if changes.is_empty() {
+            return;
+        }
+
+        if let Err(e) = self
+            .update_tx
+            .as_ref()
+            .unwrap()
+            .send(CharacterUpdaterEvent::UpdateGuilds(changes))
+        {
+            error!(?e, "Could not send guild updates");
+        }
+    }
+
+    /// Saves the changes made to the owners of houses since they were last
+    /// saved
+    pub fn update_houses(&mut self, changes: Vec<HousingChange>) {
+        if changes.is_empty() {
+            return;
+        }
+
+        if let Err(e) = self
+            .update_tx
+            .as_ref()
+            .unwrap()
+            .send(CharacterUpdaterEvent::UpdateHouses(changes))
+        {
+            error!(?e, "Could not send house updates");
+        }
+    }
+
+    /// Saves the crops that were planted, removed or grew into a new stage
+    /// since they were last saved
+    pub fn update_crops(&mut self, crops: Vec<(Vec3<i32>, Option<Crop>)>) {
+        if crops.is_empty() {
+            return;
+        }
+
+        if let Err(e) = self
+            .update_tx
+            .as_ref()
+            .unwrap()
+            .send(CharacterUpdaterEvent::UpdateCrops(crops))
+        {
+            error!(?e, "Could not send crop updates");
+        }
+    }
+
     /// Indicates to the batch update thread that a requested disconnection of
     /// all clients has been processed
     pub fn disconnected_success(&mut self) {
@@ -301,22 +425,75 @@ impl CharacterUpdater {
 
 fn execute_batch_update(
     updates: Vec<(CharacterId, CharacterUpdateData)>,
+    mail: Vec<MailChange>,
+    containers: Vec<(Vec3<i32>, comp::Inventory)>,
     connection: &mut VelorenConnection,
 ) -> Result<(), PersistenceError> {
     let mut transaction = connection.connection.transaction()?;
     transaction.set_drop_behavior(DropBehavior::Rollback);
     trace!("Transaction started for character batch update");
-    updates
-        .into_iter()
-        .try_for_each(|(character_id, (stats, inventory, waypoint))| {
-            super::character::update(character_id, stats, inventory, waypoint, &mut transaction)
-        })?;
+    updates.into_iter().try_for_each(
+        |(character_id, (stats, inventory, waypoint, quest_log, pets, bank, presets))| {
+            super::character::update(
+                character_id,
+                stats,
+                inventory,
+                waypoint,
+                quest_log,
+                pets,
+                bank,
+                presets,
+                &mut transaction,
+            )
+        },
+    )?;
+    super::character::update_mail(mail, &mut transaction)?;
+    super::character::update_storage_containers(containers, &mut transaction)?;
     transaction.commit()?;
 
     trace!("Commit for character batch update completed");
     Ok(())
 }
 
+fn execute_guild_update(
+    changes: Vec<GuildChange>,
+    connection: &mut VelorenConnection,
+) -> Result<(), PersistenceError> {
+    let mut transaction = connection.connection.transaction()?;
+    transaction.set_drop_behavior(DropBehavior::Rollback);
+    super::guild::update_guilds(changes, &mut transaction)?;
+    transaction.commit()?;
+
+    trace!("Commit for guild update completed");
+    Ok(())
+}
+
+fn execute_house_update(
+    changes: Vec<HousingChange>,
+    connection: &mut VelorenConnection,
+) -> Result<(), PersistenceError> {
+    let mut transaction = connection.connection.transaction()?;
+    transaction.set_drop_behavior(DropBehavior::Rollback);
+    super::house::update_houses(changes, &mut transaction)?;
+    transaction.commit()?;
+
+    trace!("Commit for house update completed");
+    Ok(())
+}
+
+fn execute_crop_update(
+    crops: Vec<(Vec3<i32>, Option<Crop>)>,
+    connection: &mut VelorenConnection,
+) -> Result<(), PersistenceError> {
+    let mut transaction = connection.connection.transaction()?;
+    transaction.set_drop_behavior(DropBehavior::Rollback);
+    super::farming::update_crops(crops, &mut transaction)?;
+    transaction.commit()?;
+
+    trace!("Commit for crop update completed");
+    Ok(())
+}
+
 fn execute_character_create(
     entity: Entity,
     alias: String,
@@ -353,7 +530,7 @@ fn execute_character_delete(
 
     let response = CharacterLoaderResponse {
         entity,
-        result: CharacterLoaderResponseKind::CharacterList(super::character::delete_character(
+        result: CharacterLoaderResponseKind::CharacterDeletion(super::character::delete_character(
             requesting_player_uuid,
             character_id,
             &mut transaction,
@@ -0,0 +1,71 @@
+//! Database operations related to crops planted by players
+
+use super::error::PersistenceError;
+use crate::farming::Crop;
+use rusqlite::{Connection, ToSql, Transaction, NO_PARAMS};
+use vek::*;
+
+/// Loads every crop planted in the world
+pub fn load_crops(connection: &Connection) -> Result<Vec<(Vec3<i32>, Crop)>, PersistenceError> {
+    #[rustfmt::skip]
+    let mut stmt = connection.prepare_cached("
+        SELECT  pos_x,
+                pos_y,
+                pos_z,
+                crop,
+                growth
+        FROM    crop")?;
+
+    let crops = stmt
+        .query_map(NO_PARAMS, |row| {
+            Ok((Vec3::new(row.get(0)?, row.get(1)?, row.get(2)?), Crop {
+                kind: row.get(3)?,
+                growth: row.get(4)?,
+            }))
+        })?
+        .filter_map(Result::ok)
+        .collect();
+
+    Ok(crops)
+}
+
+/// Saves crops that were planted or grew, and deletes those that are gone
+pub fn update_crops(
+    crops: Vec<(Vec3<i32>, Option<Crop>)>,
+    connection: &mut Transaction,
+) -> Result<(), PersistenceError> {
+    for (pos, crop) in crops {
+        match crop {
+            Some(crop) => {
+                #[rustfmt::skip]
+                let mut stmt = connection.prepare_cached("
+                    REPLACE
+                    INTO    crop (pos_x,
+                                  pos_y,
+                                  pos_z,
+                                  crop,
+                                  growth)
+                    VALUES  (?1, ?2, ?3, ?4, ?5)")?;
+                stmt.execute(&[
+                    &pos.x as &dyn ToSql,
+                    &pos.y,
+                    &pos.z,
+                    &crop.kind,
+                    &crop.growth,
+                ])?;
+            },
+            None => {
+                #[rustfmt::skip]
+                let mut stmt = connection.prepare_cached("
+                    DELETE
+                    FROM    crop
+                    WHERE   pos_x = ?1
+                    AND     pos_y = ?2
+                    AND     pos_z = ?3")?;
+                stmt.execute(&[pos.x, pos.y, pos.z])?;
+            },
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,150 @@
+//! Database operations related to guilds
+
+use super::error::PersistenceError;
+use crate::guild::GuildChange;
+use common::{
+    character::CharacterId,
+    comp::guild::{GuildId, GuildRank},
+};
+use rusqlite::{Connection, ToSql, Transaction, NO_PARAMS};
+use tracing::warn;
+
+/// Loads every guild, followed by the members of all guilds
+#[allow(clippy::type_complexity)]
+pub fn load_guilds(
+    connection: &Connection,
+) -> Result<
+    (
+        Vec<(GuildId, String, String)>,
+        Vec<(CharacterId, GuildId, GuildRank)>,
+    ),
+    PersistenceError,
+> {
+    #[rustfmt::skip]
+    let mut stmt = connection.prepare_cached("
+        SELECT  guild_id,
+                name,
+                tag
+        FROM    guild")?;
+
+    let guilds = stmt
+        .query_map(NO_PARAMS, |row| {
+            Ok((row.get::<_, i64>(0)? as GuildId, row.get(1)?, row.get(2)?))
+        })?
+        .filter_map(Result::ok)
+        .collect();
+    drop(stmt);
+
+    #[rustfmt::skip]
+    let mut stmt = connection.prepare_cached("
+        SELECT  character_id,
+                guild_id,
+                rank
+        FROM    guild_member")?;
+
+    let members = stmt
+        .query_map(NO_PARAMS, |row| {
+            Ok((
+                row.get::<_, CharacterId>(0)?,
+                row.get::<_, i64>(1)? as GuildId,
+                row.get::<_, String>(2)?,
+            ))
+        })?
+        .filter_map(Result::ok)
+        .filter_map(
+            |(character_id, guild, rank)| match convert_rank_from_database(&rank) {
+                Some(rank) => Some((character_id, guild, rank)),
+                None => {
+                    warn!(
+                        ?character_id,
+                        ?rank,
+                        "Ignoring guild member with unknown rank"
+                    );
+                    None
+                },
+            },
+        )
+        .collect();
+
+    Ok((guilds, members))
+}
+
+/// Saves changes to guilds, in the order they were made
+pub fn update_guilds(
+    changes: Vec<GuildChange>,
+    connection: &mut Transaction,
+) -> Result<(), PersistenceError> {
+    for change in changes {
+        match change {
+            GuildChange::Create { guild, name, tag } => {
+                #[rustfmt::skip]
+                let mut stmt = connection.prepare_cached("
+                    INSERT
+                    INTO    guild (guild_id,
+                                   name,
+                                   tag)
+                    VALUES  (?1, ?2, ?3)")?;
+                stmt.execute(&[&(guild as i64) as &dyn ToSql, &name, &tag])?;
+            },
+            GuildChange::Disband(guild) => {
+                #[rustfmt::skip]
+                let mut stmt = connection.prepare_cached("
+                    DELETE
+                    FROM    guild_member
+                    WHERE   guild_id = ?1")?;
+                stmt.execute(&[guild as i64])?;
+                drop(stmt);
+
+                #[rustfmt::skip]
+                let mut stmt = connection.prepare_cached("
+                    DELETE
+                    FROM    guild
+                    WHERE   guild_id = ?1")?;
+                stmt.execute(&[guild as i64])?;
+            },
+            GuildChange::SetMember {
+                character_id,
+                guild,
+                rank,
+            } => {
+                #[rustfmt::skip]
+                let mut stmt = connection.prepare_cached("
+                    REPLACE
+                    INTO    guild_member (character_id,
+                                          guild_id,
+                                          rank)
+                    VALUES  (?1, ?2, ?3)")?;
+                stmt.execute(&[&character_id as &dyn ToSql, &(guild as i64), &rank.as_str()])?;
+            },
+            GuildChange::RemoveMember(character_id) => {
+                delete_guild_member(character_id, connection)?;
+            },
+        }
+    }
+
+    Ok(())
+}
+
+/// Removes a character from their guild, such as when they are deleted
+pub fn delete_guild_member(
+    character_id: CharacterId,
+    connection: &Connection,
+) -> Result<(), PersistenceError> {
+    #[rustfmt::skip]
+    let mut stmt = connection.prepare_cached("
+        DELETE
+        FROM    guild_member
+        WHERE   character_id = ?1")?;
+    stmt.execute(&[character_id])?;
+
+    Ok(())
+}
+
+fn convert_rank_from_database(rank: &str) -> Option<GuildRank> {
+    match rank {
+        "member" => Some(GuildRank::Member),
+        "officer" => Some(GuildRank::Officer),
+        "leader" => Some(GuildRank::Leader),
+        _ => None,
+    }
+}
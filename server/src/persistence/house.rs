@@ -0,0 +1,89 @@
+//! Database operations related to player houses
+
+use super::error::PersistenceError;
+use crate::housing::{HouseOwner, HousingChange};
+use common::character::CharacterId;
+use rusqlite::{Connection, ToSql, Transaction, NO_PARAMS};
+use vek::*;
+
+/// Loads the owner of every house that has one
+pub fn load_house_owners(
+    connection: &Connection,
+) -> Result<Vec<(Vec2<i32>, HouseOwner)>, PersistenceError> {
+    #[rustfmt::skip]
+    let mut stmt = connection.prepare_cached("
+        SELECT  house_x,
+                house_y,
+                character_id,
+                bought_at,
+                last_seen
+        FROM    house")?;
+
+    let owners = stmt
+        .query_map(NO_PARAMS, |row| {
+            Ok((Vec2::new(row.get(0)?, row.get(1)?), HouseOwner {
+                character_id: row.get(2)?,
+                bought_at: row.get::<_, i64>(3)? as u64,
+                last_seen: row.get::<_, i64>(4)? as u64,
+            }))
+        })?
+        .filter_map(Result::ok)
+        .collect();
+
+    Ok(owners)
+}
+
+/// Saves changes to the owners of houses, in the order they were made
+pub fn update_houses(
+    changes: Vec<HousingChange>,
+    connection: &mut Transaction,
+) -> Result<(), PersistenceError> {
+    for change in changes {
+        match change {
+            HousingChange::SetOwner { house, owner } => {
+                #[rustfmt::skip]
+                let mut stmt = connection.prepare_cached("
+                    REPLACE
+                    INTO    house (house_x,
+                                   house_y,
+                                   character_id,
+                                   bought_at,
+                                   last_seen)
+                    VALUES  (?1, ?2, ?3, ?4, ?5)")?;
+                stmt.execute(&[
+                    &house.x as &dyn ToSql,
+                    &house.y,
+                    &owner.character_id,
+                    &(owner.bought_at as i64),
+                    &(owner.last_seen as i64),
+                ])?;
+            },
+            HousingChange::RemoveOwner(house) => {
+                #[rustfmt::skip]
+                let mut stmt = connection.prepare_cached("
+                    DELETE
+                    FROM    house
+                    WHERE   house_x = ?1
+                    AND     house_y = ?2")?;
+                stmt.execute(&[house.x, house.y])?;
+            },
+        }
+    }
+
+    Ok(())
+}
+
+/// Gives up the house of a character, such as when they are deleted
+pub fn delete_house_owner(
+    character_id: CharacterId,
+    connection: &Connection,
+) -> Result<(), PersistenceError> {
+    #[rustfmt::skip]
+    let mut stmt = connection.prepare_cached("
+        DELETE
+        FROM    house
+        WHERE   character_id = ?1")?;
+    stmt.execute(&[character_id])?;
+
+    Ok(())
+}
@@ -1,6 +1,6 @@
 use common::comp;
 use serde::{Deserialize, Serialize};
-use vek::Vec3;
+use vek::{Vec2, Vec3};
 
 #[derive(Serialize, Deserialize)]
 pub struct HumanoidBody {
@@ -36,6 +36,112 @@ pub struct CharacterPosition {
     pub waypoint: Vec3<f32>,
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct QuestLog {
+    pub quests: Vec<Quest>,
+    pub completed: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Quest {
+    pub template: String,
+    pub giver: String,
+    pub destination: Option<QuestDestination>,
+    pub progress: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct QuestDestination {
+    pub name: String,
+    pub wpos: Vec2<i32>,
+}
+
+impl From<&comp::QuestLog> for QuestLog {
+    fn from(quest_log: &comp::QuestLog) -> Self {
+        QuestLog {
+            quests: quest_log
+                .active
+                .iter()
+                // Escort quests are failed when the character leaves the world, so any that are
+                // still active are those of characters that are online. The escorted NPC can't be
+                // restored after a restart, so they aren't saved.
+                .filter(|quest| quest.escortee.is_none())
+                .map(|quest| Quest {
+                    template: quest.template.clone(),
+                    giver: quest.giver.clone(),
+                    destination: quest
+                        .destination
+                        .as_ref()
+                        .map(|destination| QuestDestination {
+                            name: destination.name.clone(),
+                            wpos: destination.wpos,
+                        }),
+                    progress: quest.progress,
+                })
+                .collect(),
+            completed: quest_log.completed,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Pet {
+    pub name: String,
+    pub body: comp::Body,
+}
+
+impl From<&comp::pet::PetPersistenceData> for Pet {
+    fn from(pet: &comp::pet::PetPersistenceData) -> Self {
+        Pet {
+            name: pet.name.clone(),
+            body: pet.body,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct LoadoutPreset {
+    pub name: String,
+    /// Skills are named the same way as in the skill table
+    pub skills: Vec<(String, Option<u16>)>,
+    pub equipment: Vec<(comp::slot::EquipSlot, String)>,
+}
+
+impl From<&comp::LoadoutPreset> for LoadoutPreset {
+    fn from(preset: &comp::LoadoutPreset) -> Self {
+        LoadoutPreset {
+            name: preset.name.clone(),
+            skills: preset
+                .skills
+                .iter()
+                .map(|(skill, level)| (skill_to_db_string(*skill), *level))
+                .collect(),
+            equipment: preset.equipment.clone(),
+        }
+    }
+}
+
+/// The per-item data that isn't part of an item's definition: how many steps
+/// of quality it was crafted above its definition, its modifiers and the gems
+/// socketed into it
+#[derive(Serialize, Deserialize)]
+pub struct ItemProperties {
+    #[serde(default)]
+    pub quality_bonus: u8,
+    pub modifiers: Vec<comp::item::ItemModifier>,
+    pub sockets: Vec<Option<String>>,
+}
+
+impl From<&comp::Item> for ItemProperties {
+    fn from(item: &comp::Item) -> Self {
+        ItemProperties {
+            quality_bonus: item.quality_bonus(),
+            modifiers: item.modifiers().to_vec(),
+            sockets: item.sockets().to_vec(),
+        }
+    }
+}
+
 pub fn skill_to_db_string(skill: comp::skills::Skill) -> String {
     use comp::{
         item::tool::ToolKind,
@@ -150,24 +256,33 @@ pub fn skill_to_db_string(skill: comp::skills::Skill) -> String {
         | UnlockGroup(SkillGroupKind::Weapon(ToolKind::Debug))
         | UnlockGroup(SkillGroupKind::Weapon(ToolKind::Farming))
         | UnlockGroup(SkillGroupKind::Weapon(ToolKind::Pick))
+        | UnlockGroup(SkillGroupKind::Weapon(ToolKind::Fishing))
         | UnlockGroup(SkillGroupKind::Weapon(ToolKind::Empty))
         | UnlockGroup(SkillGroupKind::Weapon(ToolKind::Natural))
-        | UnlockGroup(SkillGroupKind::General) => {
+        | UnlockGroup(SkillGroupKind::General)
+        | UnlockGroup(SkillGroupKind::Data(_)) => {
             panic!("Tried to add unsupported skill to database: {:?}", skill)
         },
+        Data(skill) => return format!("Data {}", skill.id()),
     };
     skill_string.to_string()
 }
 
-pub fn db_string_to_skill(skill_string: &str) -> comp::skills::Skill {
+/// Returns `None` for skills of the data skill trees that are no longer
+/// declared in the assets
+pub fn db_string_to_skill(skill_string: &str) -> Option<comp::skills::Skill> {
     use comp::{
         item::tool::ToolKind,
+        skill_tree::DataSkill,
         skills::{
             AxeSkill, BowSkill, ClimbSkill, GeneralSkill, HammerSkill, MiningSkill, RollSkill,
             SceptreSkill, Skill::*, SkillGroupKind, StaffSkill, SwimSkill, SwordSkill,
         },
     };
-    match skill_string {
+    if let Some(id) = skill_string.strip_prefix("Data ") {
+        return DataSkill::from_id(id).map(Data);
+    }
+    Some(match skill_string {
         "General HealthIncrease" => General(GeneralSkill::HealthIncrease),
         "General EnergyIncrease" => General(GeneralSkill::EnergyIncrease),
         "Sword InterruptingAttacks" => Sword(SwordSkill::InterruptingAttacks),
@@ -273,7 +388,7 @@ pub fn db_string_to_skill(skill_string: &str) -> comp::skills::Skill {
                 skill_string
             )
         },
-    }
+    })
 }
 
 pub fn skill_group_to_db_string(skill_group: comp::skills::SkillGroupKind) -> String {
@@ -292,18 +407,25 @@ pub fn skill_group_to_db_string(skill_group: comp::skills::SkillGroupKind) -> St
         | Weapon(ToolKind::Spear)
         | Weapon(ToolKind::Debug)
         | Weapon(ToolKind::Farming)
+        | Weapon(ToolKind::Fishing)
         | Weapon(ToolKind::Empty)
         | Weapon(ToolKind::Natural) => panic!(
             "Tried to add unsupported skill group to database: {:?}",
             skill_group
         ),
+        Data(group) => return format!("Data {}", group.id()),
     };
     skill_group_string.to_string()
 }
 
-pub fn db_string_to_skill_group(skill_group_string: &str) -> comp::skills::SkillGroupKind {
-    use comp::{item::tool::ToolKind, skills::SkillGroupKind::*};
-    match skill_group_string {
+/// Returns `None` for skill groups of the data skill trees that are no longer
+/// declared in the assets
+pub fn db_string_to_skill_group(skill_group_string: &str) -> Option<comp::skills::SkillGroupKind> {
+    use comp::{item::tool::ToolKind, skill_tree::DataSkillGroup, skills::SkillGroupKind::*};
+    if let Some(id) = skill_group_string.strip_prefix("Data ") {
+        return DataSkillGroup::from_id(id).map(Data);
+    }
+    Some(match skill_group_string {
         "General" => General,
         "Weapon Sword" => Weapon(ToolKind::Sword),
         "Weapon Axe" => Weapon(ToolKind::Axe),
@@ -316,5 +438,5 @@ pub fn db_string_to_skill_group(skill_group_string: &str) -> comp::skills::Skill
             "Tried to convert an unsupported string from the database: {}",
             skill_group_string
         ),
-    }
+    })
 }
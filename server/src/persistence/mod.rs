@@ -5,12 +5,25 @@ pub mod character_loader;
 pub mod character_updater;
 mod diesel_to_rusqlite;
 pub mod error;
+pub(in crate::persistence) mod farming;
+pub(in crate::persistence) mod guild;
+pub(in crate::persistence) mod house;
 mod json_models;
 mod models;
 
-use common::comp;
+use crate::{farming::Crop, housing::HouseOwner};
+use common::{
+    character::CharacterId,
+    comp::{
+        self,
+        guild::{GuildId, GuildRank},
+        pet::PetPersistenceData,
+    },
+    mail::{Mail, MailId},
+};
+use hashbrown::HashMap;
 use refinery::Report;
-use rusqlite::{Connection, OpenFlags};
+use rusqlite::{Connection, OpenFlags, NO_PARAMS};
 use std::{
     fs,
     ops::Deref,
@@ -18,7 +31,8 @@ use std::{
     sync::{Arc, RwLock},
     time::Duration,
 };
-use tracing::info;
+use tracing::{error, info};
+use vek::*;
 
 /// A tuple of the components that are persisted to the DB for each character
 pub type PersistedComponents = (
@@ -27,6 +41,10 @@ pub type PersistedComponents = (
     comp::SkillSet,
     comp::Inventory,
     Option<comp::Waypoint>,
+    comp::QuestLog,
+    Vec<PetPersistenceData>,
+    comp::Bank,
+    comp::LoadoutPresets,
 );
 
 // See: https://docs.rs/refinery/0.5.0/refinery/macro.embed_migrations.html
@@ -154,6 +172,14 @@ pub fn run_migrations(settings: &DatabaseSettings) {
     diesel_to_rusqlite::migrate_from_diesel(&mut conn)
         .expect("One-time migration from Diesel to Refinery failed");
 
+    // Some migrations rebuild tables that other tables refer to, which SQLite
+    // only allows while foreign keys are disabled. They can't be disabled within
+    // the transaction each migration runs in, so they are disabled for all
+    // migrations and checked once they ran.
+    conn.connection
+        .pragma_update(None, "foreign_keys", &"OFF")
+        .expect("Failed to set foreign_keys PRAGMA");
+
     // If migrations fail to run, the server cannot start since the database will
     // not be in the required state.
     let report: Report = embedded::migrations::runner()
@@ -161,10 +187,80 @@ pub fn run_migrations(settings: &DatabaseSettings) {
         .run(&mut conn.connection)
         .expect("Database migrations failed, server startup aborted");
 
+    // Databases from before foreign keys were enforced may already violate them,
+    // so this doesn't keep the server from starting
+    let foreign_keys_violated = conn
+        .connection
+        .prepare("PRAGMA foreign_key_check")
+        .and_then(|mut stmt| stmt.exists(NO_PARAMS))
+        .expect("Failed to check foreign keys after running migrations");
+    if foreign_keys_violated {
+        error!("The database violates foreign keys after running migrations");
+    }
+    conn.connection
+        .pragma_update(None, "foreign_keys", &"ON")
+        .expect("Failed to set foreign_keys PRAGMA");
+
     let applied_migrations = report.applied_migrations().len();
     info!("Applied {} database migrations", applied_migrations);
 }
 
+/// Loads the contents of every storage container in the world. This is
+/// executed during server startup, before any chunks are generated.
+pub fn load_storage_containers(
+    settings: &DatabaseSettings,
+) -> Result<Vec<(Vec3<i32>, comp::Inventory)>, error::PersistenceError> {
+    let conn = establish_connection(settings, ConnectionMode::ReadOnly);
+    character::load_storage_containers(&conn.connection)
+}
+
+/// Loads every letter that is yet to be collected, along with the names of all
+/// characters and the highest id a letter ever had
+#[allow(clippy::type_complexity)]
+pub fn load_mail(
+    settings: &DatabaseSettings,
+) -> Result<(Vec<Mail>, HashMap<CharacterId, String>, MailId), error::PersistenceError> {
+    let conn = establish_connection(settings, ConnectionMode::ReadOnly);
+    let aliases = character::load_character_aliases(&conn.connection)?
+        .into_iter()
+        .collect::<HashMap<_, _>>();
+    let mail = character::load_mail(&conn.connection, &aliases)?;
+    let last_mail_id = character::load_last_mail_id(&conn.connection)?;
+    Ok((mail, aliases, last_mail_id))
+}
+
+/// Loads every guild and the members of all guilds
+#[allow(clippy::type_complexity)]
+pub fn load_guilds(
+    settings: &DatabaseSettings,
+) -> Result<
+    (
+        Vec<(GuildId, String, String)>,
+        Vec<(CharacterId, GuildId, GuildRank)>,
+    ),
+    error::PersistenceError,
+> {
+    let conn = establish_connection(settings, ConnectionMode::ReadOnly);
+    guild::load_guilds(&conn.connection)
+}
+
+/// Loads the owner of every house that has one
+pub fn load_house_owners(
+    settings: &DatabaseSettings,
+) -> Result<Vec<(Vec2<i32>, HouseOwner)>, error::PersistenceError> {
+    let conn = establish_connection(settings, ConnectionMode::ReadOnly);
+    house::load_house_owners(&conn.connection)
+}
+
+/// Loads every crop planted in the world. This is executed during server
+/// startup, before any chunks are generated.
+pub fn load_crops(
+    settings: &DatabaseSettings,
+) -> Result<Vec<(Vec3<i32>, Crop)>, error::PersistenceError> {
+    let conn = establish_connection(settings, ConnectionMode::ReadOnly);
+    farming::load_crops(&conn.connection)
+}
+
 // These callbacks use info logging because they are never enabled by default,
 // only when explicitly turned on via CLI arguments or interactive CLI commands.
 // Setting them to anything other than info would remove the ability to get SQL
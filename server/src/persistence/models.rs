@@ -3,6 +3,9 @@ pub struct Character {
     pub player_uuid: String,
     pub alias: String,
     pub waypoint: Option<String>,
+    pub quests: Option<String>,
+    pub pets: Option<String>,
+    pub presets: Option<String>,
 }
 
 #[derive(Debug)]
@@ -12,6 +15,8 @@ pub struct Item {
     pub item_definition_id: String,
     pub stack_size: i32,
     pub position: String,
+    pub durability_lost: i32,
+    pub properties: Option<String>,
 }
 
 pub struct Body {
@@ -0,0 +1,81 @@
+use crate::client::Client;
+use common::{
+    comp::{self, group::GroupManager, Alignment, Pet},
+    uid::Uid,
+};
+use common_net::msg::ServerGeneral;
+use specs::{Entity, Join, World, WorldExt};
+
+/// Makes a creature the pet of the given owner and adds it to the owner's
+/// group. This is used both when a wild creature is tamed and when the pets of
+/// a character are restored as it logs in.
+pub fn tame_pet(ecs: &World, pet_entity: Entity, owner: Entity) {
+    let owner_uid = match ecs.read_storage::<Uid>().get(owner) {
+        Some(uid) => *uid,
+        None => return,
+    };
+
+    let _ = ecs
+        .write_storage()
+        .insert(pet_entity, Alignment::Owned(owner_uid));
+    let _ = ecs.write_storage().insert(pet_entity, Pet::default());
+    // Forget whatever the creature was up to while it was wild
+    let _ = ecs
+        .write_storage()
+        .insert(pet_entity, comp::Agent::default());
+    // Creatures that can be ridden may now carry their owner
+    if ecs
+        .read_storage::<comp::Body>()
+        .get(pet_entity)
+        .map_or(false, |body| body.mount_stats().is_some())
+        && ecs
+            .read_storage::<comp::MountState>()
+            .get(pet_entity)
+            .is_none()
+    {
+        let _ = ecs
+            .write_storage()
+            .insert(pet_entity, comp::MountState::Unmounted);
+    }
+
+    // Add to group system
+    let clients = ecs.read_storage::<Client>();
+    let uids = ecs.read_storage::<Uid>();
+    let mut group_manager = ecs.write_resource::<GroupManager>();
+    group_manager.new_pet(
+        pet_entity,
+        owner,
+        &mut ecs.write_storage(),
+        &ecs.entities(),
+        &ecs.read_storage(),
+        &uids,
+        &mut |entity, group_change| {
+            clients
+                .get(entity)
+                .and_then(|c| {
+                    group_change
+                        .try_map(|e| uids.get(e).copied())
+                        .map(|g| (g, c))
+                })
+                .map(|(g, c)| {
+                    c.send_fallible(ServerGeneral::GroupUpdate(g));
+                });
+        },
+    );
+}
+
+/// The living pets of the character with the given uid
+pub fn pets_of(ecs: &World, owner_uid: Uid) -> Vec<Entity> {
+    (
+        &ecs.entities(),
+        &ecs.read_storage::<Alignment>(),
+        &ecs.read_storage::<Pet>(),
+        &ecs.read_storage::<comp::Health>(),
+    )
+        .join()
+        .filter(|(_, alignment, _, health)| {
+            **alignment == Alignment::Owned(owner_uid) && !health.is_dead
+        })
+        .map(|(entity, _, _, _)| entity)
+        .collect()
+}
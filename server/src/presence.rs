@@ -10,6 +10,8 @@ pub struct Presence {
     pub view_distance: u32,
     pub kind: PresenceKind,
     pub lossy_terrain_compression: bool,
+    /// Whether the client wants the combat log of its player and their group
+    pub combat_log: bool,
 }
 
 impl Presence {
@@ -18,6 +20,7 @@ impl Presence {
             view_distance,
             kind,
             lossy_terrain_compression: false,
+            combat_log: false,
         }
     }
 }
@@ -1,16 +1,25 @@
 use super::*;
 use common::{
-    comp::inventory::loadout_builder::LoadoutBuilder,
-    resources::Time,
+    comp::{
+        inventory::{
+            loadout_builder::LoadoutBuilder, slot::ArmorSlot, trade_pricing::TradePricing,
+        },
+        Item,
+    },
+    resources::{Time, TimeOfDay},
     rtsim::{Memory, MemoryItem},
     store::Id,
     terrain::TerrainGrid,
+    time::DayPeriod,
+    trade::{Good, SiteId},
 };
 use rand_distr::{Distribution, Normal};
 use std::f32::consts::PI;
 use tracing::warn;
 use world::{
     civ::{Site, Track},
+    sim2::EconomySim,
+    site::SiteKind,
     util::RandomPerm,
     IndexRef, World,
 };
@@ -21,21 +30,46 @@ pub struct Entity {
     pub seed: u32,
     pub last_tick: u64,
     pub controller: RtSimController,
+    pub role: Role,
 
     pub brain: Brain,
 }
 
+/// What an rtsim entity does with its life
+#[derive(Clone, Debug)]
+pub enum Role {
+    /// Wanders from site to site
+    Traveller,
+    /// Travels between sites like a traveller, carrying goods from the economy
+    /// of one site to the next
+    Merchant { cargo: Vec<(Good, f32)> },
+    /// Lives in a house of a site, and spends the day working and meeting
+    /// people there
+    Resident { site_id: Id<Site>, home: Vec2<i32> },
+}
+
 const PERM_SPECIES: u32 = 0;
 const PERM_BODY: u32 = 1;
 const PERM_LOADOUT: u32 = 2;
 const PERM_LEVEL: u32 = 3;
 const PERM_GENUS: u32 = 4;
+const PERM_SCHEDULE: u32 = 5;
+
+/// How many units of goods a merchant can carry
+const MERCHANT_CAPACITY: f32 = 100.0;
+/// How fast residents walk around their site
+const RESIDENT_SPEED_FACTOR: f32 = 0.5;
 
 impl Entity {
     pub fn rng(&self, perm: u32) -> impl Rng { RandomPerm::new(self.seed + perm) }
 
     pub fn get_body(&self) -> comp::Body {
-        match self.rng(PERM_GENUS).gen::<f32>() {
+        // Merchants and residents are always people
+        let genus = match self.role {
+            Role::Traveller => self.rng(PERM_GENUS).gen::<f32>(),
+            Role::Merchant { .. } | Role::Resident { .. } => 1.0,
+        };
+        match genus {
             // we want 5% airships, 45% birds, 50% humans
             x if x < 0.05 => comp::ship::Body::random_with(&mut self.rng(PERM_BODY)).into(),
             x if x < 0.45 => {
@@ -78,15 +112,127 @@ impl Entity {
     pub fn get_loadout(&self) -> comp::inventory::loadout::Loadout {
         let mut rng = self.rng(PERM_LOADOUT);
 
-        LoadoutBuilder::from_asset_expect("common.loadout.world.traveler", Some(&mut rng))
+        let loadout = match self.role {
+            Role::Traveller => "common.loadout.world.traveler",
+            Role::Merchant { .. } => "common.loadout.village.merchant",
+            Role::Resident { .. } => "common.loadout.village.villager",
+        };
+        LoadoutBuilder::from_asset_expect(loadout, Some(&mut rng))
             .bag(
                 comp::inventory::slot::ArmorSlot::Bag1,
                 Some(comp::inventory::loadout_builder::make_potion_bag(100)),
             )
+            .bag(ArmorSlot::Bag2, self.get_cargo_bag())
             .build()
     }
 
-    pub fn tick(&mut self, time: &Time, terrain: &TerrainGrid, world: &World, index: &IndexRef) {
+    /// A bag with items standing in for the goods that a merchant carries
+    fn get_cargo_bag(&self) -> Option<Item> {
+        let cargo = match &self.role {
+            Role::Merchant { cargo } if !cargo.is_empty() => cargo,
+            _ => return None,
+        };
+        let mut bag = Item::new_from_asset_expect("common.items.armor.misc.bag.reliable_backpack");
+        for (slot, (good, amount)) in bag.slots_mut().iter_mut().zip(cargo) {
+            if let Some(item_id) = TradePricing::random_item(*good, *amount / 10.0, true) {
+                let mut item = Item::new_from_asset_expect(&item_id);
+                let _ = item.set_amount((*amount as u32).clamp(1, 16.min(item.max_amount())));
+                *slot = Some(item);
+            }
+        }
+        Some(bag)
+    }
+
+    /// What a merchant leaves behind when they are robbed
+    pub fn get_drop_item(&self) -> Option<Item> {
+        match &self.role {
+            Role::Merchant { cargo } => cargo
+                .iter()
+                .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
+                .and_then(|(good, amount)| TradePricing::random_item(*good, *amount, true))
+                .map(|item_id| Item::new_from_asset_expect(&item_id)),
+            _ => None,
+        }
+    }
+
+    /// The site whose prices a merchant trades at
+    pub fn get_trade_site(&self, world: &World) -> Option<SiteId> {
+        match self.role {
+            Role::Merchant { .. } => self
+                .brain
+                .last_visited
+                .and_then(|site_id| world.civs().sites[site_id].site_tmp)
+                .map(|site_id| site_id.id()),
+            _ => None,
+        }
+    }
+
+    /// Residents go home for the night, spend the day at one of the workplaces
+    /// of their site, and meet at a plaza in the evening
+    fn tick_resident(
+        &mut self,
+        site_id: Id<Site>,
+        home: Vec2<i32>,
+        time_of_day: &TimeOfDay,
+        terrain: &TerrainGrid,
+        world: &World,
+        index: &IndexRef,
+    ) {
+        let site = world.civs().sites[site_id]
+            .site_tmp
+            .map(|id| &index.sites[id]);
+        let site2 = site.and_then(|site| match &site.kind {
+            SiteKind::Refactor(site2) => Some(site2),
+            _ => None,
+        });
+        // Each resident always goes to the same places
+        let mut rng = self.rng(PERM_SCHEDULE);
+        let mut choose = |positions: Vec<Vec2<i32>>| positions.choose(&mut rng).copied();
+        let destination = site2
+            .and_then(|site2| match DayPeriod::from(time_of_day.0) {
+                DayPeriod::Night => None,
+                DayPeriod::Morning | DayPeriod::Noon => choose(site2.workplaces().collect())
+                    .or_else(|| choose(site2.plaza_positions().collect())),
+                DayPeriod::Evening => choose(site2.plaza_positions().collect()),
+            })
+            .unwrap_or(home);
+
+        let alt = world.sim().get_alt_approx(destination).unwrap_or(0.0) as i32;
+        let travel_to = terrain
+            .find_space(destination.with_z(alt))
+            .map(|e| e as f32)
+            + Vec3::new(0.5, 0.5, 0.0);
+        let site_name = site.map_or_else(String::new, |site| site.name().to_string());
+        self.controller.travel_to = Some((travel_to, site_name));
+        self.controller.speed_factor = RESIDENT_SPEED_FACTOR;
+    }
+
+    pub fn tick(
+        &mut self,
+        time: &Time,
+        time_of_day: &TimeOfDay,
+        terrain: &TerrainGrid,
+        world: &World,
+        index: &IndexRef,
+        economy: Option<&mut EconomySim>,
+    ) {
+        if let Role::Resident { site_id, home } = self.role {
+            self.tick_resident(site_id, home, time_of_day, terrain, world, index);
+            self.forget_memories(time);
+            return;
+        }
+
+        // Merchants sell the goods they brought along to the sites they arrive at, and
+        // take on what those sites can spare
+        if let (Role::Merchant { cargo }, Travel::InSite { site_id }) =
+            (&mut self.role, &self.brain.route)
+        {
+            if let (Some(economy), Some(site)) = (economy, world.civs().sites[*site_id].site_tmp) {
+                economy.record_trade(site.id(), cargo.drain(..));
+                *cargo = economy.export_goods(site.id(), MERCHANT_CAPACITY);
+            }
+        }
+
         self.brain.route = match self.brain.route.clone() {
             Travel::Lost => {
                 match self.get_body() {
@@ -443,7 +589,10 @@ impl Entity {
             },
         };
 
-        // Forget old memories
+        self.forget_memories(time);
+    }
+
+    fn forget_memories(&mut self, time: &Time) {
         self.brain
             .memories
             .retain(|memory| memory.time_to_forget > time.0);
@@ -451,7 +600,7 @@ impl Entity {
 }
 
 #[derive(Clone, Debug)]
-enum Travel {
+pub(super) enum Travel {
     // The initial state all entities start in, and a fallback for when a state has stopped making
     // sense. Non humanoids will always revert to this state after reaching their goal since the
     // current site they are in doesn't change their behavior.
@@ -490,11 +639,11 @@ impl Default for Travel {
 
 #[derive(Default)]
 pub struct Brain {
-    begin: Option<Id<Site>>,
-    tgt: Option<Id<Site>>,
-    route: Travel,
-    last_visited: Option<Id<Site>>,
-    memories: Vec<Memory>,
+    pub(super) begin: Option<Id<Site>>,
+    pub(super) tgt: Option<Id<Site>>,
+    pub(super) route: Travel,
+    pub(super) last_visited: Option<Id<Site>>,
+    pub(super) memories: Vec<Memory>,
 }
 
 impl Brain {
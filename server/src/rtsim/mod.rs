@@ -3,10 +3,12 @@
 mod chunks;
 mod entity;
 mod load_chunks;
+mod save;
 mod tick;
 mod unload_chunks;
 
 use self::chunks::Chunks;
+use crate::sys::SysScheduler;
 use common::{
     comp,
     rtsim::{Memory, RtSimController, RtSimEntity, RtSimId},
@@ -18,9 +20,18 @@ use common_state::State;
 use rand::prelude::*;
 use slab::Slab;
 use specs::{DispatcherBuilder, WorldExt};
+use std::time::Duration;
 use vek::*;
 
-pub use self::entity::Entity;
+pub use self::{
+    entity::{Entity, Role},
+    save::save,
+};
+
+/// How often the population is saved to disk
+const SAVE_INTERVAL: Duration = Duration::from_secs(5 * 60);
+/// How many merchants each settlement sends out into the world
+const MERCHANTS_PER_SITE: usize = 2;
 
 pub struct RtSim {
     tick: u64,
@@ -96,6 +107,8 @@ pub fn add_server_systems(dispatch_builder: &mut DispatcherBuilder) {
         &load_chunks::Sys::sys_name(),
         &unload_chunks::Sys::sys_name(),
     ]);
+    #[cfg(feature = "worldgen")]
+    dispatch::<save::Sys>(dispatch_builder, &[&tick::Sys::sys_name()]);
 }
 
 pub fn init(state: &mut State, #[cfg(feature = "worldgen")] world: &world::World) {
@@ -104,26 +117,117 @@ pub fn init(state: &mut State, #[cfg(feature = "worldgen")] world: &world::World
     #[cfg(not(feature = "worldgen"))]
     let mut rtsim = RtSim::new(Vec2::new(40, 40));
 
+    // Carry on with the population from the last run of the server, if there was
+    // one
+    #[cfg(feature = "worldgen")]
+    let restored = {
+        let data_dir = state.ecs().read_resource::<crate::data_dir::DataDir>();
+        save::load(
+            &mut rtsim,
+            world,
+            state.ecs().read_resource::<common::resources::Time>().0,
+            &data_dir.path,
+            state
+                .ecs()
+                .read_resource::<crate::settings::Settings>()
+                .world_seed,
+        )
+    };
+    #[cfg(not(feature = "worldgen"))]
+    let restored = false;
+
+    // Fill the roles that nobody has, which is all of them unless the population
+    // was restored, in which case those who died since are replaced
     #[cfg(feature = "worldgen")]
-    for _ in 0..world.sim().get_size().product() / 400 {
-        let pos = rtsim
-            .chunks
-            .size()
-            .map2(TerrainChunk::RECT_SIZE, |sz, chunk_sz| {
-                thread_rng().gen_range(0..sz * chunk_sz) as i32
+    {
+        let travellers = rtsim
+            .entities
+            .iter()
+            .filter(|(_, entity)| matches!(entity.role, Role::Traveller))
+            .count();
+        let merchants = rtsim
+            .entities
+            .iter()
+            .filter(|(_, entity)| matches!(entity.role, Role::Merchant { .. }))
+            .count();
+        let homes = rtsim
+            .entities
+            .iter()
+            .filter_map(|(_, entity)| match entity.role {
+                Role::Resident { home, .. } => Some(home),
+                _ => None,
+            })
+            .collect::<hashbrown::HashSet<_>>();
+
+        for _ in travellers..(world.sim().get_size().product() / 400) as usize {
+            let pos = rtsim
+                .chunks
+                .size()
+                .map2(TerrainChunk::RECT_SIZE, |sz, chunk_sz| {
+                    thread_rng().gen_range(0..sz * chunk_sz) as i32
+                });
+
+            rtsim.entities.insert(Entity {
+                is_loaded: false,
+                pos: Vec3::from(pos.map(|e| e as f32)),
+                seed: thread_rng().gen(),
+                controller: RtSimController::default(),
+                role: Role::Traveller,
+                last_tick: 0,
+                brain: Default::default(),
             });
+        }
 
-        rtsim.entities.insert(Entity {
-            is_loaded: false,
-            pos: Vec3::from(pos.map(|e| e as f32)),
-            seed: thread_rng().gen(),
-            controller: RtSimController::default(),
-            last_tick: 0,
-            brain: Default::default(),
-        });
+        // Every settlement sends out merchants, and the houses of new-style towns have
+        // people living in them
+        let index = state.ecs().read_resource::<world::IndexOwned>();
+        let settlements = world
+            .civs()
+            .sites
+            .iter()
+            .filter(|(_, site)| site.is_settlement())
+            .collect::<Vec<_>>();
+        let mut missing_merchants =
+            (settlements.len() * MERCHANTS_PER_SITE).saturating_sub(merchants);
+        for (site_id, site) in settlements {
+            let center = TerrainChunk::RECT_SIZE.map(|e| e as i32) * site.center;
+            let houses = match site.site_tmp.map(|id| &index.sites[id].kind) {
+                Some(world::site::SiteKind::Refactor(site2)) => site2.houses().collect(),
+                _ => Vec::new(),
+            };
+            let new_merchants = missing_merchants.min(MERCHANTS_PER_SITE);
+            missing_merchants -= new_merchants;
+            let roles = (0..new_merchants)
+                .map(|_| (center, Role::Merchant { cargo: Vec::new() }))
+                .chain(
+                    houses
+                        .into_iter()
+                        .filter(|home| !homes.contains(home))
+                        .map(|home| (home, Role::Resident { site_id, home })),
+                );
+            for (wpos, role) in roles {
+                let alt = world.sim().get_alt_approx(wpos).unwrap_or(0.0);
+                rtsim.entities.insert(Entity {
+                    is_loaded: false,
+                    pos: wpos.map(|e| e as f32).with_z(alt),
+                    seed: thread_rng().gen(),
+                    controller: RtSimController::default(),
+                    role,
+                    last_tick: 0,
+                    brain: Default::default(),
+                });
+            }
+        }
     }
 
     state.ecs_mut().insert(rtsim);
     state.ecs_mut().register::<RtSimEntity>();
-    tracing::info!("Initiated real-time world simulation");
+    state
+        .ecs_mut()
+        .insert(SysScheduler::<save::Sys>::every(SAVE_INTERVAL));
+    if restored {
+        tracing::info!("Restored real-time world simulation");
+    } else {
+        tracing::info!("Initiated real-time world simulation");
+    }
 }
@@ -0,0 +1,299 @@
+use super::{
+    entity::{Brain, Travel},
+    Entity, Role, RtSim,
+};
+use crate::{
+    data_dir::{self, DataDir},
+    settings::Settings,
+    sys::SysScheduler,
+};
+use common::{
+    resources::Time,
+    rtsim::{Memory, RtSimController},
+    trade::Good,
+};
+use common_ecs::{Job, Origin, Phase, System};
+use serde::{Deserialize, Serialize};
+use specs::{Read, ReadExpect, Write};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use tracing::{debug, error, warn};
+use vek::*;
+use world::World;
+
+const RTSIM_DIR: &str = "rtsim";
+
+/// What is kept of the simulated population between runs of the server
+#[derive(Serialize, Deserialize)]
+pub struct RtSimSave {
+    entities: Vec<EntitySave>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct EntitySave {
+    pos: Vec3<f32>,
+    seed: u32,
+    #[serde(default)]
+    role: RoleSave,
+    begin: Option<u64>,
+    tgt: Option<u64>,
+    last_visited: Option<u64>,
+    route: RouteSave,
+    /// Memories are saved with the time that is left until they are forgotten,
+    /// because the server clock starts over after a restart
+    memories: Vec<Memory>,
+}
+
+/// Mirrors `Role`, with sites referred to by their index
+#[derive(Serialize, Deserialize)]
+enum RoleSave {
+    Traveller,
+    Merchant { cargo: Vec<(Good, f32)> },
+    Resident { site_id: u64, home: Vec2<i32> },
+}
+
+impl Default for RoleSave {
+    fn default() -> Self { RoleSave::Traveller }
+}
+
+/// Mirrors `Travel`, with sites and tracks referred to by their index
+#[derive(Serialize, Deserialize)]
+enum RouteSave {
+    Lost,
+    InSite {
+        site_id: u64,
+    },
+    Direct {
+        target_id: u64,
+    },
+    CustomPath {
+        target_id: u64,
+        path: Vec<Vec2<i32>>,
+        progress: usize,
+    },
+    Path {
+        target_id: u64,
+        track_id: u64,
+        progress: usize,
+        reversed: bool,
+    },
+}
+
+impl RtSimSave {
+    fn new(rtsim: &RtSim, time: f64) -> Self {
+        let entities = rtsim
+            .entities
+            .iter()
+            .map(|(_, entity)| EntitySave {
+                pos: entity.pos,
+                seed: entity.seed,
+                role: match &entity.role {
+                    Role::Traveller => RoleSave::Traveller,
+                    Role::Merchant { cargo } => RoleSave::Merchant {
+                        cargo: cargo.clone(),
+                    },
+                    Role::Resident { site_id, home } => RoleSave::Resident {
+                        site_id: site_id.id(),
+                        home: *home,
+                    },
+                },
+                begin: entity.brain.begin.map(|id| id.id()),
+                tgt: entity.brain.tgt.map(|id| id.id()),
+                last_visited: entity.brain.last_visited.map(|id| id.id()),
+                route: match &entity.brain.route {
+                    Travel::Lost => RouteSave::Lost,
+                    Travel::InSite { site_id } => RouteSave::InSite {
+                        site_id: site_id.id(),
+                    },
+                    Travel::Direct { target_id } => RouteSave::Direct {
+                        target_id: target_id.id(),
+                    },
+                    Travel::CustomPath {
+                        target_id,
+                        path,
+                        progress,
+                    } => RouteSave::CustomPath {
+                        target_id: target_id.id(),
+                        path: path.clone(),
+                        progress: *progress,
+                    },
+                    Travel::Path {
+                        target_id,
+                        track_id,
+                        progress,
+                        reversed,
+                    } => RouteSave::Path {
+                        target_id: target_id.id(),
+                        track_id: track_id.id(),
+                        progress: *progress,
+                        reversed: *reversed,
+                    },
+                },
+                memories: entity
+                    .brain
+                    .memories
+                    .iter()
+                    .filter(|memory| memory.time_to_forget > time)
+                    .map(|memory| Memory {
+                        item: memory.item.clone(),
+                        time_to_forget: memory.time_to_forget - time,
+                    })
+                    .collect(),
+            })
+            .collect();
+        Self { entities }
+    }
+
+    /// Recreate the saved entities. Sites and tracks that no longer exist in
+    /// the world are forgotten, and entities with a broken route get lost.
+    fn entities<'a>(&'a self, world: &'a World, time: f64) -> impl Iterator<Item = Entity> + 'a {
+        let civs = world.civs();
+        self.entities.iter().map(move |save| {
+            let route = match &save.route {
+                RouteSave::Lost => None,
+                RouteSave::InSite { site_id } => civs
+                    .sites
+                    .recreate_id(*site_id)
+                    .map(|site_id| Travel::InSite { site_id }),
+                RouteSave::Direct { target_id } => civs
+                    .sites
+                    .recreate_id(*target_id)
+                    .map(|target_id| Travel::Direct { target_id }),
+                RouteSave::CustomPath {
+                    target_id,
+                    path,
+                    progress,
+                } => civs
+                    .sites
+                    .recreate_id(*target_id)
+                    .map(|target_id| Travel::CustomPath {
+                        target_id,
+                        path: path.clone(),
+                        progress: *progress,
+                    }),
+                RouteSave::Path {
+                    target_id,
+                    track_id,
+                    progress,
+                    reversed,
+                } => civs
+                    .sites
+                    .recreate_id(*target_id)
+                    .zip(civs.tracks.recreate_id(*track_id))
+                    .map(|(target_id, track_id)| Travel::Path {
+                        target_id,
+                        track_id,
+                        progress: *progress,
+                        reversed: *reversed,
+                    }),
+            };
+            let role = match &save.role {
+                RoleSave::Traveller => Some(Role::Traveller),
+                RoleSave::Merchant { cargo } => Some(Role::Merchant {
+                    cargo: cargo.clone(),
+                }),
+                RoleSave::Resident { site_id, home } => {
+                    civs.sites
+                        .recreate_id(*site_id)
+                        .map(|site_id| Role::Resident {
+                            site_id,
+                            home: *home,
+                        })
+                },
+            };
+            Entity {
+                is_loaded: false,
+                pos: save.pos,
+                seed: save.seed,
+                controller: RtSimController::default(),
+                // Residents whose site is gone have to make a living travelling
+                role: role.unwrap_or(Role::Traveller),
+                last_tick: 0,
+                brain: Brain {
+                    begin: save.begin.and_then(|id| civs.sites.recreate_id(id)),
+                    tgt: save.tgt.and_then(|id| civs.sites.recreate_id(id)),
+                    route: route.unwrap_or_default(),
+                    last_visited: save.last_visited.and_then(|id| civs.sites.recreate_id(id)),
+                    memories: save
+                        .memories
+                        .iter()
+                        .map(|memory| Memory {
+                            item: memory.item.clone(),
+                            time_to_forget: memory.time_to_forget + time,
+                        })
+                        .collect(),
+                },
+            }
+        })
+    }
+}
+
+/// This system periodically saves the rtsim population to disk, so that it
+/// carries on from where it was when the server restarts
+#[derive(Default)]
+pub struct Sys;
+impl<'a> System<'a> for Sys {
+    type SystemData = (
+        ReadExpect<'a, RtSim>,
+        Read<'a, Time>,
+        Read<'a, Settings>,
+        ReadExpect<'a, DataDir>,
+        Write<'a, SysScheduler<Self>>,
+    );
+
+    const NAME: &'static str = "rtsim::save";
+    const ORIGIN: Origin = Origin::Server;
+    const PHASE: Phase = Phase::Create;
+
+    fn run(
+        _job: &mut Job<Self>,
+        (rtsim, time, settings, data_dir, mut scheduler): Self::SystemData,
+    ) {
+        if scheduler.should_run() {
+            save(&rtsim, time.0, &data_dir.path, settings.world_seed);
+            debug!("Saved rtsim population");
+        }
+    }
+}
+
+fn save_path(data_dir: &Path, world_seed: u32) -> PathBuf {
+    data_dir.join(RTSIM_DIR).join(format!("{}.ron", world_seed))
+}
+
+/// Restore the population saved for the world with the given seed. Returns
+/// `false` if there is nothing to restore and a new population has to be
+/// generated.
+pub fn load(rtsim: &mut RtSim, world: &World, time: f64, data_dir: &Path, world_seed: u32) -> bool {
+    let path = save_path(data_dir, world_seed);
+    let file = match fs::File::open(&path) {
+        Ok(file) => file,
+        Err(_) => return false,
+    };
+    match ron::de::from_reader::<_, RtSimSave>(file) {
+        Ok(save) => {
+            for entity in save.entities(world, time) {
+                rtsim.entities.insert(entity);
+            }
+            true
+        },
+        Err(e) => {
+            warn!(
+                ?e,
+                "Failed to parse rtsim save file {}, generating a new population",
+                path.display()
+            );
+            false
+        },
+    }
+}
+
+pub fn save(rtsim: &RtSim, time: f64, data_dir: &Path, world_seed: u32) {
+    let path = save_path(data_dir, world_seed);
+    let ron = ron::ser::to_string(&RtSimSave::new(rtsim, time))
+        .expect("Failed to serialize rtsim population");
+    if let Err(e) = data_dir::write_atomically(&path, ron.as_bytes()) {
+        error!(?e, "Failed to save rtsim population to {}", path.display());
+    }
+}
@@ -4,12 +4,13 @@ use super::*;
 use common::{
     comp::{self, inventory::loadout_builder::LoadoutBuilder, Behavior, BehaviorCapability},
     event::{EventBus, ServerEvent},
-    resources::{DeltaTime, Time},
+    resources::{DeltaTime, Time, TimeOfDay},
     terrain::TerrainGrid,
 };
 use common_ecs::{Job, Origin, Phase, System};
-use specs::{Join, Read, ReadExpect, ReadStorage, WriteExpect, WriteStorage};
+use specs::{Join, Read, ReadExpect, ReadStorage, Write, WriteExpect, WriteStorage};
 use std::sync::Arc;
+use world::sim2::EconomySim;
 
 const ENTITY_TICK_PERIOD: u64 = 30;
 
@@ -20,8 +21,10 @@ impl<'a> System<'a> for Sys {
     type SystemData = (
         Read<'a, Time>,
         Read<'a, DeltaTime>,
+        Read<'a, TimeOfDay>,
         Read<'a, EventBus<ServerEvent>>,
         WriteExpect<'a, RtSim>,
+        Option<Write<'a, EconomySim>>,
         ReadExpect<'a, TerrainGrid>,
         ReadExpect<'a, Arc<world::World>>,
         ReadExpect<'a, world::IndexOwned>,
@@ -39,8 +42,10 @@ impl<'a> System<'a> for Sys {
         (
             time,
             dt,
+            time_of_day,
             server_event_bus,
             mut rtsim,
+            mut economy,
             terrain,
             world,
             index,
@@ -68,14 +73,15 @@ impl<'a> System<'a> for Sys {
             } else {
                 // Simulate behaviour
                 if let Some(travel_to) = &entity.controller.travel_to {
-                    // Move towards target at approximate character speed
+                    // Move towards target at approximate character speed, without overshooting
+                    let to_target = travel_to.0.xy() - entity.pos.xy();
+                    let step = entity.get_body().max_speed_approx()
+                        * entity.controller.speed_factor
+                        * dt.0;
                     entity.pos += Vec3::from(
-                        (travel_to.0.xy() - entity.pos.xy())
-                            .try_normalized()
-                            .unwrap_or_else(Vec2::zero)
-                            * entity.get_body().max_speed_approx()
-                            * entity.controller.speed_factor,
-                    ) * dt.0;
+                        to_target.try_normalized().unwrap_or_else(Vec2::zero)
+                            * step.min(to_target.magnitude()),
+                    );
                 }
 
                 if let Some(alt) = world
@@ -88,7 +94,14 @@ impl<'a> System<'a> for Sys {
 
             // Tick entity AI
             if entity.last_tick + ENTITY_TICK_PERIOD <= rtsim.tick {
-                entity.tick(&time, &terrain, &world, &index.as_index_ref());
+                entity.tick(
+                    &time,
+                    &time_of_day,
+                    &terrain,
+                    &world,
+                    &index.as_index_ref(),
+                    economy.as_deref_mut(),
+                );
                 entity.last_tick = rtsim.tick;
             }
         }
@@ -108,6 +121,7 @@ impl<'a> System<'a> for Sys {
                 &body,
                 if matches!(body, comp::Body::Humanoid(_)) {
                     Behavior::from(BehaviorCapability::SPEAK)
+                        .with_trade_site(entity.get_trade_site(&world))
                 } else {
                     Behavior::default()
                 },
@@ -148,7 +162,7 @@ impl<'a> System<'a> for Sys {
                         comp::Body::Ship(_) => comp::Scale(comp::ship::AIRSHIP_SCALE),
                         _ => comp::Scale(1.0),
                     },
-                    drop_item: None,
+                    drop_item: entity.get_drop_item(),
                     home_chunk: None,
                     rtsim_entity,
                     projectile: None,
@@ -13,7 +13,9 @@ pub use banlist::{
 pub use server_description::ServerDescription;
 pub use whitelist::{Whitelist, WhitelistInfo, WhitelistRecord};
 
+use crate::housing::HousingSettings;
 use chrono::Utc;
+use common::combat::PvpMode;
 use core::time::Duration;
 use portpicker::pick_unused_port;
 use serde::{Deserialize, Serialize};
@@ -48,7 +50,8 @@ pub struct Settings {
     pub quic_files: Option<X509FilePair>,
     pub max_players: usize,
     pub world_seed: u32,
-    //pub pvp_enabled: bool,
+    /// Whether players can hurt each other outside of duels and PvP zones
+    pub pvp_mode: PvpMode,
     pub server_name: String,
     pub start_time: f64,
     /// When set to None, loads the default map file (if available); otherwise,
@@ -61,6 +64,14 @@ pub struct Settings {
     pub spawn_town: Option<String>,
     pub safe_spawn: bool,
     pub max_player_for_kill_broadcast: Option<usize>,
+    /// Whether to keep generated chunks on disk, so that they don't have to be
+    /// generated again
+    pub chunk_cache: bool,
+    /// The item definition id and amount of the item that refunding a whole
+    /// skill group costs, if it costs anything
+    pub respec_cost: Option<(String, u32)>,
+    /// Whether players can buy houses, what they cost and when they lose them
+    pub housing: HousingSettings,
 }
 
 impl Default for Settings {
@@ -71,6 +82,7 @@ impl Default for Settings {
             auth_server_address: Some("https://auth.veloren.net".into()),
             quic_files: None,
             world_seed: DEFAULT_WORLD_SEED,
+            pvp_mode: PvpMode::default(),
             server_name: "Veloren Alpha".into(),
             max_players: 100,
             start_time: 9.0 * 3600.0,
@@ -82,6 +94,9 @@ impl Default for Settings {
             spawn_town: None,
             safe_spawn: true,
             max_player_for_kill_broadcast: None,
+            chunk_cache: false,
+            respec_cost: None,
+            housing: HousingSettings::default(),
         }
     }
 }
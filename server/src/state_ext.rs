@@ -1,5 +1,5 @@
 use crate::{
-    client::Client, persistence::PersistedComponents, presence::Presence, settings::Settings,
+    client::Client, persistence::PersistedComponents, pet, presence::Presence, settings::Settings,
     sys::sentinel::DeletedEntities, wiring, SpawnPoint,
 };
 use common::{
@@ -7,6 +7,7 @@ use common::{
     combat,
     comp::{
         self,
+        inventory::loadout_builder::LoadoutBuilder,
         skills::{GeneralSkill, Skill},
         Group, Inventory,
     },
@@ -76,6 +77,13 @@ pub trait StateExt {
     ) -> EcsEntityBuilder;
     /// Creates a safezone
     fn create_safezone(&mut self, range: Option<f32>, pos: comp::Pos) -> EcsEntityBuilder;
+    /// Creates a zone that overrides whether players can hurt each other
+    fn create_pvp_zone(
+        &mut self,
+        kind: comp::PvpZoneKind,
+        range: Option<f32>,
+        pos: comp::Pos,
+    ) -> EcsEntityBuilder;
     fn create_wiring(
         &mut self,
         pos: comp::Pos,
@@ -366,6 +374,21 @@ impl StateExt for State {
             )]))
     }
 
+    fn create_pvp_zone(
+        &mut self,
+        kind: comp::PvpZoneKind,
+        range: Option<f32>,
+        pos: comp::Pos,
+    ) -> EcsEntityBuilder {
+        self.ecs_mut()
+            .create_entity_synced()
+            .with(pos)
+            .with(comp::PvpZone {
+                kind,
+                radius: range.unwrap_or(100.0),
+            })
+    }
+
     fn create_wiring(
         &mut self,
         pos: comp::Pos,
@@ -481,13 +504,15 @@ impl StateExt for State {
     }
 
     fn update_character_data(&mut self, entity: EcsEntity, components: PersistedComponents) {
-        let (body, stats, skill_set, inventory, waypoint) = components;
+        let (body, stats, skill_set, inventory, waypoint, quest_log, pets, bank, presets) =
+            components;
 
         if let Some(player_uid) = self.read_component_copied::<Uid>(entity) {
             // Notify clients of a player list update
             self.notify_players(ServerGeneral::PlayerListUpdate(
                 PlayerListUpdate::SelectedCharacter(player_uid, CharacterInfo {
                     name: String::from(&stats.name),
+                    guild_tag: None,
                 }),
             ));
 
@@ -523,6 +548,15 @@ impl StateExt for State {
                 entity,
                 comp::InventoryUpdate::new(comp::InventoryUpdateEvent::default()),
             );
+            if let Some(client) = self.ecs().read_storage::<Client>().get(entity) {
+                client.send_fallible(ServerGeneral::QuestUpdate(quest_log.clone()));
+            }
+            self.write_component_ignore_entity_dead(entity, quest_log);
+            self.write_component_ignore_entity_dead(entity, bank);
+            if let Some(client) = self.ecs().read_storage::<Client>().get(entity) {
+                client.send_fallible(ServerGeneral::PresetUpdate(presets.clone()));
+            }
+            self.write_component_ignore_entity_dead(entity, presets);
 
             if let Some(waypoint) = waypoint {
                 self.write_component_ignore_entity_dead(entity, waypoint);
@@ -530,11 +564,33 @@ impl StateExt for State {
                 self.write_component_ignore_entity_dead(entity, comp::Vel(Vec3::zero()));
                 self.write_component_ignore_entity_dead(entity, comp::ForceUpdate);
             }
+
+            // Bring back the pets the character had when it logged out
+            if let Some(pos) = self.read_component_copied::<comp::Pos>(entity) {
+                for pet in pets {
+                    let inventory = Inventory::new_with_loadout(
+                        LoadoutBuilder::from_default(&pet.body).build(),
+                    );
+                    let pet_entity = self
+                        .create_npc(
+                            pos,
+                            comp::Stats::new(pet.name),
+                            comp::SkillSet::default(),
+                            Some(comp::Health::new(pet.body, 0)),
+                            comp::Poise::new(pet.body),
+                            inventory,
+                            pet.body,
+                        )
+                        .with(comp::MountState::Unmounted)
+                        .build();
+                    pet::tame_pet(self.ecs(), pet_entity, entity);
+                }
+            }
         }
     }
 
     /// Send the chat message to the proper players. Say and region are limited
-    /// by location. Faction and group are limited by component.
+    /// by location. Faction, group and guild are limited by component.
     fn send_chat(&self, msg: comp::UnresolvedChatMsg) {
         let ecs = self.ecs();
         let is_within =
@@ -710,6 +766,38 @@ impl StateExt for State {
             comp::ChatType::GroupMeta(g) => {
                 send_to_group(g, ecs, &resolved_msg);
             },
+            comp::ChatType::Guild(from, g) => {
+                let is_member = (
+                    &ecs.read_storage::<Uid>(),
+                    &ecs.read_storage::<comp::Guild>(),
+                )
+                    .join()
+                    .any(|(uid, guild)| uid == from && guild.0 == *g);
+                if !is_member {
+                    // guild not found, reply with command error
+                    let reply = comp::ChatMsg {
+                        chat_type: comp::ChatType::CommandError,
+                        message: "You are using guild chat but do not belong to a guild. Use \
+                                  /world or /region to change chat."
+                            .into(),
+                    };
+
+                    if let Some((client, _)) =
+                        (&ecs.read_storage::<Client>(), &ecs.read_storage::<Uid>())
+                            .join()
+                            .find(|(_, uid)| *uid == from)
+                    {
+                        client.send_fallible(ServerGeneral::ChatMsg(reply));
+                    }
+                    return;
+                }
+                send_to_guild(*g, ecs, &resolved_msg);
+            },
+            comp::ChatType::GuildMeta(g) => {
+                send_to_guild(*g, ecs, &resolved_msg);
+            },
+            // Only created by clients from the combat log stream
+            comp::ChatType::CombatLog => {},
         }
     }
 
@@ -808,6 +896,19 @@ impl StateExt for State {
     }
 }
 
+fn send_to_guild(g: comp::guild::GuildId, ecs: &specs::World, msg: &comp::ChatMsg) {
+    for (client, guild) in (
+        &ecs.read_storage::<Client>(),
+        &ecs.read_storage::<comp::Guild>(),
+    )
+        .join()
+    {
+        if guild.0 == g {
+            client.send_fallible(ServerGeneral::ChatMsg(msg.clone()));
+        }
+    }
+}
+
 fn send_to_group(g: &comp::Group, ecs: &specs::World, msg: &comp::ChatMsg) {
     for (client, group) in (
         &ecs.read_storage::<Client>(),
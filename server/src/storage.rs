@@ -0,0 +1,65 @@
+use common::{
+    comp::{storage::CONTAINER_SLOTS, Inventory},
+    terrain::TerrainChunkSize,
+    vol::RectVolSize,
+};
+use hashbrown::{HashMap, HashSet};
+use vek::*;
+
+/// The contents of every storage container placed in the world, kept under
+/// the block position of the container. Containers are loaded from the
+/// database when the server starts and their chests are spawned whenever the
+/// chunk they are in is loaded.
+#[derive(Default)]
+pub struct StorageContainers {
+    containers: HashMap<Vec3<i32>, Inventory>,
+    /// Containers that changed since they were last saved
+    modified: HashSet<Vec3<i32>>,
+}
+
+impl StorageContainers {
+    pub fn new(containers: impl IntoIterator<Item = (Vec3<i32>, Inventory)>) -> Self {
+        Self {
+            containers: containers.into_iter().collect(),
+            modified: HashSet::new(),
+        }
+    }
+
+    pub fn get(&self, pos: Vec3<i32>) -> Option<&Inventory> { self.containers.get(&pos) }
+
+    /// Gets a container to change its contents, marking it to be saved
+    pub fn get_mut(&mut self, pos: Vec3<i32>) -> Option<&mut Inventory> {
+        let container = self.containers.get_mut(&pos)?;
+        self.modified.insert(pos);
+        Some(container)
+    }
+
+    /// Adds an empty container at the given position unless there already is
+    /// one
+    pub fn create(&mut self, pos: Vec3<i32>) {
+        if !self.containers.contains_key(&pos) {
+            self.containers
+                .insert(pos, Inventory::new_with_capacity(CONTAINER_SLOTS));
+            self.modified.insert(pos);
+        }
+    }
+
+    /// The positions of the containers within the given chunk
+    pub fn in_chunk(&self, key: Vec2<i32>) -> impl Iterator<Item = Vec3<i32>> + '_ {
+        self.containers.keys().copied().filter(move |pos| {
+            pos.xy()
+                .map2(TerrainChunkSize::RECT_SIZE, |e, sz| e.div_euclid(sz as i32))
+                == key
+        })
+    }
+
+    /// Takes the containers that changed since this was last called, to be
+    /// saved
+    pub fn take_modified(&mut self) -> Vec<(Vec3<i32>, Inventory)> {
+        let containers = &self.containers;
+        self.modified
+            .drain()
+            .filter_map(|pos| Some((pos, containers.get(&pos)?.clone())))
+            .collect()
+    }
+}
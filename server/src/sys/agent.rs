@@ -2,6 +2,7 @@
 
 use crate::rtsim::{Entity as RtSimData, RtSim};
 use common::{
+    assets::AssetHandle,
     comp::{
         self,
         agent::{
@@ -20,8 +21,8 @@ use common::{
         skills::{AxeSkill, BowSkill, HammerSkill, Skill, StaffSkill, SwordSkill},
         Agent, Alignment, BehaviorCapability, BehaviorState, Body, CharacterAbility,
         CharacterState, ControlAction, ControlEvent, Controller, Energy, Health, HealthChange,
-        InputKind, Inventory, InventoryAction, LightEmitter, MountState, Ori, PhysicsState, Pos,
-        Scale, SkillSet, Stats, UnresolvedChatMsg, UtteranceKind, Vel,
+        InputKind, Inventory, InventoryAction, LightEmitter, MountState, Ori, Pet, PetCommand,
+        PhysicsState, Pos, Scale, SkillSet, Stats, UnresolvedChatMsg, UtteranceKind, Vel,
     },
     consts::GRAVITY,
     effect::{BuffEffect, Effect},
@@ -36,11 +37,13 @@ use common::{
     uid::{Uid, UidAllocator},
     util::Dir,
     vol::ReadVol,
+    weather::WeatherGrid,
 };
 use common_base::prof_span;
 use common_ecs::{Job, Origin, ParMode, Phase, System};
 use rand::{thread_rng, Rng};
 use rayon::iter::ParallelIterator;
+use serde::Deserialize;
 use specs::{
     saveload::{Marker, MarkerAllocator},
     shred::ResourceId,
@@ -48,8 +51,11 @@ use specs::{
     Write, WriteExpect, WriteStorage,
 };
 use std::{f32::consts::PI, sync::Arc, time::Duration};
+use tactic::{TacticDef, TacticManifest};
 use vek::*;
 
+mod tactic;
+
 struct AgentData<'a> {
     entity: &'a EcsEntity,
     rtsim_entity: Option<&'a RtSimData>,
@@ -91,7 +97,9 @@ impl AttackData {
     fn in_min_range(&self) -> bool { self.dist_sqrd < self.min_attack_dist.powi(2) }
 }
 
-#[derive(Eq, PartialEq)]
+/// How an agent fights, decided by the weapon it wields. Agents wielding items
+/// with a custom ability spec use the tactic the tactic manifest lists for it.
+#[derive(Clone, Deserialize)]
 pub enum Tactic {
     Melee,
     Axe,
@@ -100,15 +108,8 @@ pub enum Tactic {
     Bow,
     Staff,
     StoneGolem,
-    CircleCharge { radius: u32, circle_time: u32 },
     QuadLowRanged,
-    TailSlap,
-    QuadLowQuick,
-    QuadLowBasic,
-    QuadLowBeam,
     QuadMedJump,
-    QuadMedBasic,
-    Theropod,
     Turret,
     FixedTurret,
     RotatingTurret,
@@ -123,6 +124,9 @@ pub enum Tactic {
     Yeti,
     Tornado,
     Harvester,
+    /// A tactic described by a `TacticDef` asset
+    #[serde(skip)]
+    Data(AssetHandle<TacticDef>),
 }
 
 #[derive(SystemData)]
@@ -148,6 +152,7 @@ pub struct ReadData<'a> {
     groups: ReadStorage<'a, group::Group>,
     terrain: ReadExpect<'a, TerrainGrid>,
     alignments: ReadStorage<'a, Alignment>,
+    pets: ReadStorage<'a, Pet>,
     bodies: ReadStorage<'a, Body>,
     mount_states: ReadStorage<'a, MountState>,
     time_of_day: Read<'a, TimeOfDay>,
@@ -156,6 +161,7 @@ pub struct ReadData<'a> {
     world: ReadExpect<'a, Arc<world::World>>,
     rtsim_entities: ReadStorage<'a, RtSimEntity>,
     buffs: ReadStorage<'a, Buffs>,
+    weather: Read<'a, WeatherGrid>,
 }
 
 // This is 3.1 to last longer than the last damage timer (3.0 seconds)
@@ -168,6 +174,7 @@ const MAX_FLEE_DIST: f32 = 20.0;
 const SEARCH_DIST: f32 = 48.0;
 const SNEAK_COEFFICIENT: f32 = 0.25;
 const AVG_FOLLOW_DIST: f32 = 6.0;
+const MAX_STAY_DIST: f32 = 2.0;
 const RETARGETING_THRESHOLD_SECONDS: f64 = 10.0;
 const HEALING_ITEM_THRESHOLD: f32 = 0.5;
 const DEFAULT_ATTACK_RANGE: f32 = 2.0;
@@ -387,7 +394,19 @@ impl<'a> System<'a> for Sys {
                                     );
                                 // Target is something worth following methinks
                                 } else if let Some(Alignment::Owned(_)) = data.alignment {
-                                    if let Some(tgt_pos) = read_data.positions.get(target) {
+                                    // Pets that were told to stay keep to their spot
+                                    // instead of following their owner
+                                    if let Some(PetCommand::Stay(stay_pos)) =
+                                        read_data.pets.get(entity).map(|pet| pet.command)
+                                    {
+                                        data.stay(
+                                            agent,
+                                            controller,
+                                            &read_data,
+                                            &mut event_emitter,
+                                            stay_pos,
+                                        );
+                                    } else if let Some(tgt_pos) = read_data.positions.get(target) {
                                         let dist_sqrd = pos.0.distance_squared(tgt_pos.0);
                                         // If really far away drop everything and follow
                                         if dist_sqrd > (2.0 * MAX_FOLLOW_DIST).powi(2) {
@@ -1231,7 +1250,41 @@ impl<'a> AgentData<'a> {
                                         ));
                                     }
                                 },
-                                Subject::Work => {},
+                                Subject::Work => {
+                                    // NPCs simulated by rtsim only need help once they are
+                                    // in a bad mood, others always have some work to hand out
+                                    let needs_help =
+                                        self.rtsim_entity.map_or(true, |rtsim_entity| {
+                                            matches!(
+                                                rtsim_entity
+                                                    .brain
+                                                    .get_mood()
+                                                    .map(|memory| &memory.item),
+                                                Some(MemoryItem::Mood {
+                                                    state: MoodState::Bad(_),
+                                                })
+                                            )
+                                        });
+                                    if needs_help {
+                                        event_emitter.emit(ServerEvent::OfferQuest {
+                                            giver: *self.entity,
+                                            player: target,
+                                        });
+                                    } else {
+                                        event_emitter.emit(ServerEvent::Chat(
+                                            UnresolvedChatMsg::npc(
+                                                *self.uid,
+                                                "npc.speech.villager_no_work".to_string(),
+                                            ),
+                                        ));
+                                    }
+                                },
+                                Subject::House => {
+                                    event_emitter.emit(ServerEvent::SellHouse {
+                                        seller: *self.entity,
+                                        buyer: target,
+                                    });
+                                },
                             }
                         }
                     }
@@ -1508,6 +1561,12 @@ impl<'a> AgentData<'a> {
     ) {
         agent.action_state.timer = 0.0;
 
+        // Fog, rain and snow make it harder to spot targets
+        let visibility = read_data
+            .weather
+            .get_interpolated(self.pos.0.xy())
+            .visibility();
+
         // Search area
         let target = self.cached_spatial_grid.0
             .in_circle_aabr(self.pos.0.xy(), SEARCH_DIST)
@@ -1522,7 +1581,7 @@ impl<'a> AgentData<'a> {
                     })
             })
             .filter(|(e, e_pos, e_health, e_stats, e_inventory, e_alignment, char_state)| {
-                let mut search_dist = SEARCH_DIST;
+                let mut search_dist = SEARCH_DIST * visibility;
                 let mut listen_dist = MAX_LISTEN_DIST;
                 if char_state.map_or(false, |c_s| c_s.is_stealthy()) {
                     // TODO: make sneak more effective based on a stat like e_stats.fitness
@@ -1626,46 +1685,8 @@ impl<'a> AgentData<'a> {
             .map(|item| {
                 if let Some(ability_spec) = item.ability_spec() {
                     match ability_spec {
-                        AbilitySpec::Custom(spec) => match spec.as_str() {
-                            "Axe Simple" | "Sword Simple" => Tactic::Sword,
-                            "Staff Simple" => Tactic::Staff,
-                            "Bow Simple" => Tactic::Bow,
-                            "Stone Golem" => Tactic::StoneGolem,
-                            "Quad Med Quick" => Tactic::CircleCharge {
-                                radius: 3,
-                                circle_time: 2,
-                            },
-                            "Quad Med Jump" => Tactic::QuadMedJump,
-                            "Quad Med Charge" => Tactic::CircleCharge {
-                                radius: 12,
-                                circle_time: 1,
-                            },
-                            "Quad Med Basic" => Tactic::QuadMedBasic,
-                            "Asp" | "Maneater" => Tactic::QuadLowRanged,
-                            "Quad Low Breathe" | "Quad Low Beam" | "Basilisk" => {
-                                Tactic::QuadLowBeam
-                            },
-                            "Quad Low Tail" => Tactic::TailSlap,
-                            "Quad Low Quick" => Tactic::QuadLowQuick,
-                            "Quad Low Basic" => Tactic::QuadLowBasic,
-                            "Theropod Basic" | "Theropod Bird" => Tactic::Theropod,
-                            "Theropod Charge" => Tactic::CircleCharge {
-                                radius: 6,
-                                circle_time: 1,
-                            },
-                            "Turret" => Tactic::Turret,
-                            "Haniwa Sentry" => Tactic::RotatingTurret,
-                            "Bird Large Breathe" => Tactic::BirdLargeBreathe,
-                            "Bird Large Fire" => Tactic::BirdLargeFire,
-                            "Bird Large Basic" => Tactic::BirdLargeBasic,
-                            "Mindflayer" => Tactic::Mindflayer,
-                            "Minotaur" => Tactic::Minotaur,
-                            "Clay Golem" => Tactic::ClayGolem,
-                            "Tidal Warrior" => Tactic::TidalWarrior,
-                            "Tidal Totem" => Tactic::RadialTurret,
-                            "Yeti" => Tactic::Yeti,
-                            "Harvester" => Tactic::Harvester,
-                            _ => Tactic::Melee,
+                        AbilitySpec::Custom(spec) => {
+                            TacticManifest::tactic_for(spec).unwrap_or(Tactic::Melee)
                         },
                         AbilitySpec::Tool(tool_kind) => tool_tactic(*tool_kind),
                     }
@@ -1697,7 +1718,7 @@ impl<'a> AgentData<'a> {
                    // of the target and not the ground around the target
                    // For the ranged it is to shoot at the feet and not
                    // the head to get splash damage
-                   if tactic == Tactic::QuadMedJump {
+                   if matches!(tactic, Tactic::QuadMedJump) {
                        1.0
                    } else if matches!(tactic, Tactic::QuadLowRanged) {
                        -1.0
@@ -1822,18 +1843,6 @@ impl<'a> AgentData<'a> {
                 &tgt_data,
                 &read_data,
             ),
-            Tactic::CircleCharge {
-                radius,
-                circle_time,
-            } => self.handle_circle_charge_attack(
-                agent,
-                controller,
-                &attack_data,
-                &tgt_data,
-                &read_data,
-                radius,
-                circle_time,
-            ),
             Tactic::QuadLowRanged => self.handle_quadlow_ranged_attack(
                 agent,
                 controller,
@@ -1841,23 +1850,6 @@ impl<'a> AgentData<'a> {
                 &tgt_data,
                 &read_data,
             ),
-            Tactic::TailSlap => {
-                self.handle_tail_slap_attack(agent, controller, &attack_data, &tgt_data, &read_data)
-            },
-            Tactic::QuadLowQuick => self.handle_quadlow_quick_attack(
-                agent,
-                controller,
-                &attack_data,
-                &tgt_data,
-                &read_data,
-            ),
-            Tactic::QuadLowBasic => self.handle_quadlow_basic_attack(
-                agent,
-                controller,
-                &attack_data,
-                &tgt_data,
-                &read_data,
-            ),
             Tactic::QuadMedJump => self.handle_quadmed_jump_attack(
                 agent,
                 controller,
@@ -1865,23 +1857,6 @@ impl<'a> AgentData<'a> {
                 &tgt_data,
                 &read_data,
             ),
-            Tactic::QuadMedBasic => self.handle_quadmed_basic_attack(
-                agent,
-                controller,
-                &attack_data,
-                &tgt_data,
-                &read_data,
-            ),
-            Tactic::QuadLowBeam => self.handle_quadlow_beam_attack(
-                agent,
-                controller,
-                &attack_data,
-                &tgt_data,
-                &read_data,
-            ),
-            Tactic::Theropod => {
-                self.handle_theropod_attack(agent, controller, &attack_data, &tgt_data, &read_data)
-            },
             Tactic::Turret => {
                 self.handle_turret_attack(agent, controller, &attack_data, &tgt_data, &read_data)
             },
@@ -1959,6 +1934,14 @@ impl<'a> AgentData<'a> {
             Tactic::Harvester => {
                 self.handle_harvester_attack(agent, controller, &attack_data, &tgt_data, &read_data)
             },
+            Tactic::Data(tactic) => self.handle_data_tactic(
+                agent,
+                controller,
+                &attack_data,
+                &tgt_data,
+                &read_data,
+                &tactic.read(),
+            ),
         }
     }
 
@@ -2475,95 +2458,6 @@ impl<'a> AgentData<'a> {
     }
 
     #[allow(clippy::too_many_arguments)]
-    fn handle_circle_charge_attack(
-        &self,
-        agent: &mut Agent,
-        controller: &mut Controller,
-        attack_data: &AttackData,
-        tgt_data: &TargetData,
-        read_data: &ReadData,
-        radius: u32,
-        circle_time: u32,
-    ) {
-        if attack_data.in_min_range() && thread_rng().gen_bool(0.5) {
-            controller.inputs.move_dir = Vec2::zero();
-            controller
-                .actions
-                .push(ControlAction::basic_input(InputKind::Primary));
-        } else if attack_data.dist_sqrd < (radius as f32 * attack_data.min_attack_dist).powi(2) {
-            controller.inputs.move_dir = (self.pos.0 - tgt_data.pos.0)
-                .xy()
-                .try_normalized()
-                .unwrap_or_else(Vec2::unit_y);
-        } else if attack_data.dist_sqrd
-            < ((radius as f32 + 1.0) * attack_data.min_attack_dist).powi(2)
-            && attack_data.dist_sqrd > (radius as f32 * attack_data.min_attack_dist).powi(2)
-        {
-            if agent.action_state.timer < circle_time as f32 {
-                let move_dir = (tgt_data.pos.0 - self.pos.0)
-                    .xy()
-                    .rotated_z(0.47 * PI)
-                    .try_normalized()
-                    .unwrap_or_else(Vec2::unit_y);
-                let obstacle_left = read_data
-                    .terrain
-                    .ray(
-                        self.pos.0 + Vec3::unit_z(),
-                        self.pos.0 + move_dir.with_z(0.0) * 2.0 + Vec3::unit_z(),
-                    )
-                    .until(Block::is_solid)
-                    .cast()
-                    .1
-                    .map_or(true, |b| b.is_some());
-                if obstacle_left {
-                    agent.action_state.timer = circle_time as f32;
-                }
-                controller.inputs.move_dir = move_dir;
-                agent.action_state.timer += read_data.dt.0;
-            } else if agent.action_state.timer < circle_time as f32 + 0.5 {
-                controller
-                    .actions
-                    .push(ControlAction::basic_input(InputKind::Secondary));
-                agent.action_state.timer += read_data.dt.0;
-            } else if agent.action_state.timer < 2.0 * circle_time as f32 + 0.5 {
-                let move_dir = (tgt_data.pos.0 - self.pos.0)
-                    .xy()
-                    .rotated_z(-0.47 * PI)
-                    .try_normalized()
-                    .unwrap_or_else(Vec2::unit_y);
-                let obstacle_right = read_data
-                    .terrain
-                    .ray(
-                        self.pos.0 + Vec3::unit_z(),
-                        self.pos.0 + move_dir.with_z(0.0) * 2.0 + Vec3::unit_z(),
-                    )
-                    .until(Block::is_solid)
-                    .cast()
-                    .1
-                    .map_or(true, |b| b.is_some());
-                if obstacle_right {
-                    agent.action_state.timer = 2.0 * circle_time as f32 + 0.5;
-                }
-                controller.inputs.move_dir = move_dir;
-                agent.action_state.timer += read_data.dt.0;
-            } else if agent.action_state.timer < 2.0 * circle_time as f32 + 1.0 {
-                if agent.action_state.timer < 2.0 * circle_time as f32 {
-                    agent.action_state.timer = 2.0 * circle_time as f32;
-                }
-                controller
-                    .actions
-                    .push(ControlAction::basic_input(InputKind::Secondary));
-                agent.action_state.timer += read_data.dt.0;
-            } else {
-                agent.action_state.timer = 0.0;
-            }
-        } else if attack_data.dist_sqrd < MAX_PATH_DIST.powi(2) {
-            self.path_toward_target(agent, controller, tgt_data, read_data, true, None);
-        } else {
-            self.path_toward_target(agent, controller, tgt_data, read_data, false, None);
-        }
-    }
-
     fn handle_quadlow_ranged_attack(
         &self,
         agent: &mut Agent,
@@ -2639,111 +2533,6 @@ impl<'a> AgentData<'a> {
         }
     }
 
-    fn handle_tail_slap_attack(
-        &self,
-        agent: &mut Agent,
-        controller: &mut Controller,
-        attack_data: &AttackData,
-        tgt_data: &TargetData,
-        read_data: &ReadData,
-    ) {
-        if attack_data.angle < 90.0
-            && attack_data.dist_sqrd < (1.5 * attack_data.min_attack_dist).powi(2)
-        {
-            if agent.action_state.timer > 4.0 {
-                controller
-                    .actions
-                    .push(ControlAction::CancelInput(InputKind::Primary));
-                agent.action_state.timer = 0.0;
-            } else if agent.action_state.timer > 1.0 {
-                controller
-                    .actions
-                    .push(ControlAction::basic_input(InputKind::Primary));
-                agent.action_state.timer += read_data.dt.0;
-            } else {
-                controller
-                    .actions
-                    .push(ControlAction::basic_input(InputKind::Secondary));
-                agent.action_state.timer += read_data.dt.0;
-            }
-            controller.inputs.move_dir = (tgt_data.pos.0 - self.pos.0)
-                .xy()
-                .try_normalized()
-                .unwrap_or_else(Vec2::unit_y)
-                * 0.1;
-        } else if attack_data.dist_sqrd < MAX_PATH_DIST.powi(2) {
-            self.path_toward_target(agent, controller, tgt_data, read_data, true, None);
-        } else {
-            self.path_toward_target(agent, controller, tgt_data, read_data, false, None);
-        }
-    }
-
-    fn handle_quadlow_quick_attack(
-        &self,
-        agent: &mut Agent,
-        controller: &mut Controller,
-        attack_data: &AttackData,
-        tgt_data: &TargetData,
-        read_data: &ReadData,
-    ) {
-        if attack_data.angle < 90.0
-            && attack_data.dist_sqrd < (1.5 * attack_data.min_attack_dist).powi(2)
-        {
-            controller.inputs.move_dir = Vec2::zero();
-            controller
-                .actions
-                .push(ControlAction::basic_input(InputKind::Secondary));
-        } else if attack_data.dist_sqrd < (3.0 * attack_data.min_attack_dist).powi(2)
-            && attack_data.dist_sqrd > (2.0 * attack_data.min_attack_dist).powi(2)
-            && attack_data.angle < 90.0
-        {
-            controller
-                .actions
-                .push(ControlAction::basic_input(InputKind::Primary));
-            controller.inputs.move_dir = (tgt_data.pos.0 - self.pos.0)
-                .xy()
-                .rotated_z(-0.47 * PI)
-                .try_normalized()
-                .unwrap_or_else(Vec2::unit_y);
-        } else if attack_data.dist_sqrd < MAX_PATH_DIST.powi(2) {
-            self.path_toward_target(agent, controller, tgt_data, read_data, true, None);
-        } else {
-            self.path_toward_target(agent, controller, tgt_data, read_data, false, None);
-        }
-    }
-
-    fn handle_quadlow_basic_attack(
-        &self,
-        agent: &mut Agent,
-        controller: &mut Controller,
-        attack_data: &AttackData,
-        tgt_data: &TargetData,
-        read_data: &ReadData,
-    ) {
-        if attack_data.angle < 70.0
-            && attack_data.dist_sqrd < (1.3 * attack_data.min_attack_dist).powi(2)
-        {
-            controller.inputs.move_dir = Vec2::zero();
-            if agent.action_state.timer > 5.0 {
-                agent.action_state.timer = 0.0;
-            } else if agent.action_state.timer > 2.0 {
-                controller
-                    .actions
-                    .push(ControlAction::basic_input(InputKind::Secondary));
-                agent.action_state.timer += read_data.dt.0;
-            } else {
-                controller
-                    .actions
-                    .push(ControlAction::basic_input(InputKind::Primary));
-                agent.action_state.timer += read_data.dt.0;
-            }
-        } else if attack_data.dist_sqrd < MAX_PATH_DIST.powi(2) {
-            self.path_toward_target(agent, controller, tgt_data, read_data, true, None);
-        } else {
-            self.path_toward_target(agent, controller, tgt_data, read_data, false, None);
-        }
-    }
-
     fn handle_quadmed_jump_attack(
         &self,
         agent: &mut Agent,
@@ -2784,109 +2573,6 @@ impl<'a> AgentData<'a> {
         }
     }
 
-    fn handle_quadmed_basic_attack(
-        &self,
-        agent: &mut Agent,
-        controller: &mut Controller,
-        attack_data: &AttackData,
-        tgt_data: &TargetData,
-        read_data: &ReadData,
-    ) {
-        if attack_data.angle < 90.0 && attack_data.in_min_range() {
-            controller.inputs.move_dir = Vec2::zero();
-            if agent.action_state.timer < 2.0 {
-                controller
-                    .actions
-                    .push(ControlAction::basic_input(InputKind::Secondary));
-                agent.action_state.timer += read_data.dt.0;
-            } else if agent.action_state.timer < 3.0 {
-                controller
-                    .actions
-                    .push(ControlAction::basic_input(InputKind::Primary));
-                agent.action_state.timer += read_data.dt.0;
-            } else {
-                agent.action_state.timer = 0.0;
-            }
-        } else if attack_data.dist_sqrd < MAX_PATH_DIST.powi(2) {
-            self.path_toward_target(agent, controller, tgt_data, read_data, true, None);
-        } else {
-            self.path_toward_target(agent, controller, tgt_data, read_data, false, None);
-        }
-    }
-
-    fn handle_quadlow_beam_attack(
-        &self,
-        agent: &mut Agent,
-        controller: &mut Controller,
-        attack_data: &AttackData,
-        tgt_data: &TargetData,
-        read_data: &ReadData,
-    ) {
-        if attack_data.angle < 90.0
-            && attack_data.dist_sqrd < (2.5 * attack_data.min_attack_dist).powi(2)
-        {
-            controller.inputs.move_dir = Vec2::zero();
-            controller
-                .actions
-                .push(ControlAction::basic_input(InputKind::Secondary));
-        } else if attack_data.dist_sqrd < (7.0 * attack_data.min_attack_dist).powi(2)
-            && attack_data.angle < 15.0
-        {
-            if agent.action_state.timer < 2.0 {
-                controller.inputs.move_dir = (tgt_data.pos.0 - self.pos.0)
-                    .xy()
-                    .rotated_z(0.47 * PI)
-                    .try_normalized()
-                    .unwrap_or_else(Vec2::unit_y);
-                controller
-                    .actions
-                    .push(ControlAction::basic_input(InputKind::Primary));
-                agent.action_state.timer += read_data.dt.0;
-            } else if agent.action_state.timer < 4.0 && attack_data.angle < 15.0 {
-                controller.inputs.move_dir = (tgt_data.pos.0 - self.pos.0)
-                    .xy()
-                    .rotated_z(-0.47 * PI)
-                    .try_normalized()
-                    .unwrap_or_else(Vec2::unit_y);
-                controller
-                    .actions
-                    .push(ControlAction::basic_input(InputKind::Primary));
-                agent.action_state.timer += read_data.dt.0;
-            } else if agent.action_state.timer < 6.0 && attack_data.angle < 15.0 {
-                controller
-                    .actions
-                    .push(ControlAction::basic_input(InputKind::Ability(0)));
-                agent.action_state.timer += read_data.dt.0;
-            } else {
-                agent.action_state.timer = 0.0;
-            }
-        } else if attack_data.dist_sqrd < MAX_PATH_DIST.powi(2) {
-            self.path_toward_target(agent, controller, tgt_data, read_data, true, None);
-        } else {
-            self.path_toward_target(agent, controller, tgt_data, read_data, false, None);
-        }
-    }
-
-    fn handle_theropod_attack(
-        &self,
-        agent: &mut Agent,
-        controller: &mut Controller,
-        attack_data: &AttackData,
-        tgt_data: &TargetData,
-        read_data: &ReadData,
-    ) {
-        if attack_data.angle < 90.0 && attack_data.in_min_range() {
-            controller.inputs.move_dir = Vec2::zero();
-            controller
-                .actions
-                .push(ControlAction::basic_input(InputKind::Primary));
-        } else if attack_data.dist_sqrd < MAX_PATH_DIST.powi(2) {
-            self.path_toward_target(agent, controller, tgt_data, read_data, true, None);
-        } else {
-            self.path_toward_target(agent, controller, tgt_data, read_data, false, None);
-        }
-    }
-
     fn handle_turret_attack(
         &self,
         agent: &mut Agent,
@@ -3807,6 +3493,34 @@ impl<'a> AgentData<'a> {
         }
     }
 
+    fn stay(
+        &self,
+        agent: &mut Agent,
+        controller: &mut Controller,
+        read_data: &ReadData,
+        event_emitter: &mut Emitter<'_, ServerEvent>,
+        stay_pos: Vec3<f32>,
+    ) {
+        if self.pos.0.distance_squared(stay_pos) > MAX_STAY_DIST.powi(2) {
+            if let Some((bearing, speed)) = agent.chaser.chase(
+                &*read_data.terrain,
+                self.pos.0,
+                self.vel.0,
+                stay_pos,
+                self.traversal_config,
+            ) {
+                controller.inputs.move_dir =
+                    bearing.xy().try_normalized().unwrap_or_else(Vec2::zero) * speed;
+                self.jump_if(controller, bearing.z > 1.5);
+                controller.inputs.move_z = bearing.z;
+            }
+        } else if thread_rng().gen::<f32>() < 0.1 {
+            self.choose_target(agent, controller, read_data, event_emitter);
+        } else {
+            controller.inputs.move_dir = Vec2::zero();
+        }
+    }
+
     fn handle_elevated_awareness(
         &self,
         agent: &mut Agent,
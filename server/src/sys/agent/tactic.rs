@@ -0,0 +1,407 @@
+//! Combat tactics described as data.
+//!
+//! A tactic is a list of behaviours in order of priority. Each behaviour has
+//! conditions that decide when it can be started, and a sequence of timed
+//! steps that say which inputs to press and how to move while it is performed.
+//! Tactics are loaded from `server.tactics.*`, and the manifest at
+//! `server.manifests.tactics` decides which ability specs use which tactic,
+//! whether described as data or built into the agent code.
+
+use super::{
+    aim_projectile, can_see_tgt, AgentData, AttackData, ReadData, Tactic, TargetData, MAX_PATH_DIST,
+};
+use common::{
+    assets::{self, AssetExt, AssetHandle},
+    comp::{Agent, ControlAction, Controller, InputKind},
+    terrain::Block,
+    vol::ReadVol,
+};
+use hashbrown::HashMap;
+use lazy_static::lazy_static;
+use rand::{thread_rng, Rng};
+use serde::Deserialize;
+use std::f32::consts::PI;
+use vek::*;
+
+/// Maps custom ability specs to the tactic used by agents wielding them
+#[derive(Deserialize)]
+pub struct TacticManifest(HashMap<String, TacticSpec>);
+
+#[derive(Deserialize)]
+enum TacticSpec {
+    /// A tactic built into the agent code, for creatures such as bosses whose
+    /// way of fighting is too involved to describe as data
+    Builtin(Tactic),
+    /// Asset specifier of a `TacticDef`
+    Data(String),
+}
+
+impl assets::Asset for TacticManifest {
+    type Loader = assets::RonLoader;
+
+    const EXTENSION: &'static str = "ron";
+}
+
+lazy_static! {
+    static ref TACTIC_MANIFEST: AssetHandle<TacticManifest> =
+        TacticManifest::load_expect("server.manifests.tactics");
+}
+
+impl TacticManifest {
+    /// The tactic used by agents wielding an item with the given ability spec
+    pub fn tactic_for(ability_spec: &str) -> Option<Tactic> {
+        TACTIC_MANIFEST
+            .read()
+            .0
+            .get(ability_spec)
+            .map(|tactic| match tactic {
+                TacticSpec::Builtin(tactic) => tactic.clone(),
+                TacticSpec::Data(specifier) => Tactic::Data(TacticDef::load_expect(specifier)),
+            })
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct TacticDef {
+    /// Tried in order, the first behaviour that can be started is performed
+    pub behaviours: Vec<Behaviour>,
+}
+
+impl assets::Asset for TacticDef {
+    type Loader = assets::RonLoader;
+
+    const EXTENSION: &'static str = "ron";
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Behaviour {
+    #[serde(default)]
+    pub conditions: Vec<Condition>,
+    /// Minimum time in seconds between two starts of this behaviour
+    #[serde(default)]
+    pub cooldown: f64,
+    #[serde(default)]
+    pub aim: Aim,
+    /// How to move during steps that don't specify their own movement
+    #[serde(default)]
+    pub movement: Movement,
+    /// Performed one after the other. A behaviour without steps, or whose
+    /// steps take no time, keeps being performed while its conditions hold.
+    #[serde(default)]
+    pub steps: Vec<Step>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Step {
+    #[serde(default)]
+    pub duration: f32,
+    /// Inputs that are held during the step
+    #[serde(default)]
+    pub inputs: Vec<InputKind>,
+    #[serde(default)]
+    pub movement: Option<Movement>,
+}
+
+/// Conditions on the distance and direction to the target are checked for as
+/// long as the behaviour is performed, the others only when it is started.
+#[derive(Clone, Debug, Deserialize)]
+pub enum Condition {
+    /// Distance to the target, in multiples of the minimum attack distance
+    Range { min: f32, max: f32 },
+    /// The target is at most this many degrees away from where the agent is
+    /// looking
+    Angle(f32),
+    /// Nothing blocks the line of sight to the target
+    CanSeeTarget,
+    /// The agent has at least this much energy
+    Energy(u32),
+    /// The agent's health is at most this fraction of its maximum
+    HealthBelow(f32),
+    /// Chance for the behaviour to be started when it is considered
+    Chance(f64),
+}
+
+#[derive(Copy, Clone, Debug, Deserialize)]
+pub enum Aim {
+    /// Look straight at the eyes of the target
+    Direct,
+    /// Aim so that a projectile with the given speed arcs onto the target,
+    /// offset vertically from its eyes
+    Projectile {
+        speed: f32,
+        #[serde(default)]
+        height: f32,
+    },
+}
+
+impl Default for Aim {
+    fn default() -> Self { Aim::Direct }
+}
+
+#[derive(Copy, Clone, Debug, Deserialize)]
+pub enum Movement {
+    /// Stay in place
+    Stand,
+    /// Follow a path to the target
+    Chase,
+    /// Move straight towards the target at the given fraction of full speed
+    Advance(f32),
+    /// Move straight away from the target
+    Retreat,
+    /// Run around the target. The step ends early when the way is blocked.
+    Circle { clockwise: bool },
+    /// Stay between the given distances from the target, in multiples of the
+    /// minimum attack distance
+    Kite { min: f32, max: f32 },
+}
+
+impl Default for Movement {
+    fn default() -> Self { Movement::Stand }
+}
+
+impl Behaviour {
+    fn duration(&self) -> f32 { self.steps.iter().map(|step| step.duration).sum() }
+
+    fn step_at(&self, time: f32) -> Option<(&Step, f32)> {
+        let mut end = 0.0;
+        for step in self.steps.iter() {
+            end += step.duration;
+            if time < end {
+                return Some((step, end));
+            }
+        }
+        self.steps.last().map(|step| (step, end))
+    }
+
+    fn inputs(&self) -> impl Iterator<Item = InputKind> + '_ {
+        self.steps
+            .iter()
+            .flat_map(|step| step.inputs.iter().copied())
+    }
+}
+
+impl Condition {
+    fn is_continuous(&self) -> bool {
+        matches!(
+            self,
+            Condition::Range { .. } | Condition::Angle(_) | Condition::CanSeeTarget
+        )
+    }
+}
+
+impl<'a> AgentData<'a> {
+    fn meets(
+        &self,
+        condition: &Condition,
+        attack_data: &AttackData,
+        tgt_data: &TargetData,
+        read_data: &ReadData,
+    ) -> bool {
+        match condition {
+            Condition::Range { min, max } => {
+                attack_data.dist_sqrd >= (min * attack_data.min_attack_dist).powi(2)
+                    && attack_data.dist_sqrd < (max * attack_data.min_attack_dist).powi(2)
+            },
+            Condition::Angle(angle) => attack_data.angle < *angle,
+            Condition::CanSeeTarget => can_see_tgt(
+                &*read_data.terrain,
+                self.pos,
+                tgt_data.pos,
+                attack_data.dist_sqrd,
+            ),
+            Condition::Energy(energy) => self.energy.current() >= *energy,
+            Condition::HealthBelow(fraction) => self
+                .health
+                .map_or(false, |health| health.fraction() <= *fraction),
+            Condition::Chance(chance) => thread_rng().gen_bool(chance.clamp(0.0, 1.0)),
+        }
+    }
+
+    pub(super) fn handle_data_tactic(
+        &self,
+        agent: &mut Agent,
+        controller: &mut Controller,
+        attack_data: &AttackData,
+        tgt_data: &TargetData,
+        read_data: &ReadData,
+        tactic: &TacticDef,
+    ) {
+        let action_state = &mut agent.action_state;
+        action_state
+            .behaviour_started
+            .resize(tactic.behaviours.len(), f64::NEG_INFINITY);
+
+        // Keep performing the current behaviour until its steps are done or the
+        // target is no longer where it needs to be
+        let previous = action_state.behaviour;
+        let continued = previous.filter(|index| {
+            tactic.behaviours.get(*index).map_or(false, |behaviour| {
+                action_state.timer < behaviour.duration()
+                    && behaviour
+                        .conditions
+                        .iter()
+                        .filter(|condition| condition.is_continuous())
+                        .all(|condition| self.meets(condition, attack_data, tgt_data, read_data))
+            })
+        });
+        let current = continued.or_else(|| {
+            let index = tactic
+                .behaviours
+                .iter()
+                .enumerate()
+                .position(|(index, behaviour)| {
+                    read_data.time.0 - action_state.behaviour_started[index] >= behaviour.cooldown
+                        && behaviour.conditions.iter().all(|condition| {
+                            self.meets(condition, attack_data, tgt_data, read_data)
+                        })
+                })?;
+            action_state.timer = 0.0;
+            action_state.behaviour_started[index] = read_data.time.0;
+            Some(index)
+        });
+        action_state.behaviour = current;
+
+        // Let go of whatever the previous behaviour was holding once it is
+        // interrupted or its steps are done. Behaviours without a duration keep
+        // holding their inputs for as long as they are performed.
+        if let Some((index, released)) = previous
+            .filter(|_| continued.is_none())
+            .and_then(|index| Some((index, tactic.behaviours.get(index)?)))
+        {
+            if current != Some(index) || released.duration() > 0.0 {
+                for input in released.inputs() {
+                    controller.actions.push(ControlAction::CancelInput(input));
+                }
+            }
+        }
+
+        let behaviour = match current.and_then(|index| tactic.behaviours.get(index)) {
+            Some(behaviour) => behaviour,
+            None => {
+                self.tactic_move(
+                    agent,
+                    controller,
+                    attack_data,
+                    tgt_data,
+                    read_data,
+                    Movement::Chase,
+                );
+                return;
+            },
+        };
+
+        if let Aim::Projectile { speed, height } = behaviour.aim {
+            let eye_offset = self.body.map_or(0.0, |b| b.eye_height());
+            let tgt_eye_offset = tgt_data.body.map_or(0.0, |b| b.eye_height()) + height;
+            if let Some(dir) = aim_projectile(
+                speed,
+                self.pos.0 + Vec3::unit_z() * eye_offset,
+                tgt_data.pos.0 + Vec3::unit_z() * tgt_eye_offset,
+            ) {
+                controller.inputs.look_dir = dir;
+            }
+        }
+
+        let (inputs, movement, step_end) = match behaviour.step_at(agent.action_state.timer) {
+            Some((step, step_end)) => (
+                step.inputs.as_slice(),
+                step.movement.unwrap_or(behaviour.movement),
+                step_end,
+            ),
+            None => (&[][..], behaviour.movement, 0.0),
+        };
+        for input in inputs {
+            controller.actions.push(ControlAction::basic_input(*input));
+        }
+        let blocked = self.tactic_move(
+            agent,
+            controller,
+            attack_data,
+            tgt_data,
+            read_data,
+            movement,
+        );
+        agent.action_state.timer = if blocked {
+            step_end
+        } else {
+            agent.action_state.timer + read_data.dt.0
+        };
+    }
+
+    /// Returns whether the movement was blocked by terrain
+    fn tactic_move(
+        &self,
+        agent: &mut Agent,
+        controller: &mut Controller,
+        attack_data: &AttackData,
+        tgt_data: &TargetData,
+        read_data: &ReadData,
+        movement: Movement,
+    ) -> bool {
+        let to_tgt = (tgt_data.pos.0 - self.pos.0)
+            .xy()
+            .try_normalized()
+            .unwrap_or_else(Vec2::unit_y);
+        match movement {
+            Movement::Stand => controller.inputs.move_dir = Vec2::zero(),
+            Movement::Chase => {
+                self.path_toward_target(
+                    agent,
+                    controller,
+                    tgt_data,
+                    read_data,
+                    attack_data.dist_sqrd < MAX_PATH_DIST.powi(2),
+                    None,
+                );
+            },
+            Movement::Advance(speed) => controller.inputs.move_dir = to_tgt * speed,
+            Movement::Retreat => controller.inputs.move_dir = -to_tgt,
+            Movement::Circle { clockwise } => {
+                let move_dir = to_tgt.rotated_z(if clockwise { -0.47 } else { 0.47 } * PI);
+                controller.inputs.move_dir = move_dir;
+                return read_data
+                    .terrain
+                    .ray(
+                        self.pos.0 + Vec3::unit_z(),
+                        self.pos.0 + move_dir.with_z(0.0) * 2.0 + Vec3::unit_z(),
+                    )
+                    .until(Block::is_solid)
+                    .cast()
+                    .1
+                    .map_or(true, |b| b.is_some());
+            },
+            Movement::Kite { min, max } => {
+                if attack_data.dist_sqrd < (min * attack_data.min_attack_dist).powi(2) {
+                    controller.inputs.move_dir = -to_tgt;
+                } else if attack_data.dist_sqrd > (max * attack_data.min_attack_dist).powi(2) {
+                    return self.tactic_move(
+                        agent,
+                        controller,
+                        attack_data,
+                        tgt_data,
+                        read_data,
+                        Movement::Chase,
+                    );
+                } else {
+                    controller.inputs.move_dir = Vec2::zero();
+                }
+            },
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_all_tactics_load() {
+        for tactic in TACTIC_MANIFEST.read().0.values() {
+            if let TacticSpec::Data(specifier) = tactic {
+                let tactic = TacticDef::load_expect_cloned(specifier);
+                assert!(!tactic.behaviours.is_empty());
+            }
+        }
+    }
+}
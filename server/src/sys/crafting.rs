@@ -0,0 +1,89 @@
+use crate::client::Client;
+use common::{
+    comp::{CharacterState, Collider, CraftingQueue, InventoryManip, Pos, Scale},
+    consts::MAX_PICKUP_RANGE,
+    event::{EventBus, ServerEvent},
+    resources::DeltaTime,
+    util::find_dist::{self, FindDist},
+};
+use common_ecs::{Job, Origin, Phase, System};
+use common_net::msg::ServerGeneral;
+use specs::{Entities, Join, Read, ReadStorage, WriteStorage};
+
+/// This system works on the crafts queued up by entities, for as long as they
+/// stay near the crafting station. Each finished craft is handed over as an
+/// `InventoryManip::FinishCraft`, which uses up its ingredients.
+#[derive(Default)]
+pub struct Sys;
+impl<'a> System<'a> for Sys {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        Entities<'a>,
+        Read<'a, DeltaTime>,
+        Read<'a, EventBus<ServerEvent>>,
+        ReadStorage<'a, Pos>,
+        ReadStorage<'a, Scale>,
+        ReadStorage<'a, Collider>,
+        ReadStorage<'a, CharacterState>,
+        ReadStorage<'a, Client>,
+        WriteStorage<'a, CraftingQueue>,
+    );
+
+    const NAME: &'static str = "crafting";
+    const ORIGIN: Origin = Origin::Server;
+    const PHASE: Phase = Phase::Create;
+
+    fn run(
+        _job: &mut Job<Self>,
+        (
+            entities,
+            dt,
+            server_event_bus,
+            positions,
+            scales,
+            colliders,
+            char_states,
+            clients,
+            mut crafting_queues,
+        ): Self::SystemData,
+    ) {
+        let mut server_emitter = server_event_bus.emitter();
+
+        for (entity, pos, crafting_queue) in (&entities, &positions, &mut crafting_queues).join() {
+            let craft_sprite = match crafting_queue.current() {
+                Some(craft) => craft.craft_sprite,
+                None => continue,
+            };
+
+            // Walking away from the crafting station stops crafting
+            let near_station = craft_sprite.map_or(true, |sprite_pos| {
+                let entity_cylinder = find_dist::Cylinder::from_components(
+                    pos.0,
+                    scales.get(entity).copied(),
+                    colliders.get(entity),
+                    char_states.get(entity),
+                );
+                find_dist::Cube {
+                    min: sprite_pos.as_(),
+                    side_length: 1.0,
+                }
+                .min_distance(entity_cylinder)
+                    < MAX_PICKUP_RANGE
+            });
+            if !near_station {
+                crafting_queue.clear();
+                if let Some(client) = clients.get(entity) {
+                    client.send_fallible(ServerGeneral::CraftingUpdate(crafting_queue.clone()));
+                }
+                continue;
+            }
+
+            if let Some(craft) = crafting_queue.tick(dt.0) {
+                server_emitter.emit(ServerEvent::InventoryManip(
+                    entity,
+                    InventoryManip::FinishCraft(craft),
+                ));
+            }
+        }
+    }
+}
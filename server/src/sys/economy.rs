@@ -0,0 +1,73 @@
+use crate::{
+    data_dir::{self, DataDir},
+    settings::Settings,
+    sys::SysScheduler,
+};
+use common_ecs::{Job, Origin, Phase, System};
+use specs::{Read, ReadExpect, Write, WriteExpect};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use tracing::{debug, error, warn};
+use world::sim2::{EconomySave, EconomySim};
+
+const ECONOMY_DIR: &str = "economy";
+
+/// This system advances the economies of all sites and periodically saves them
+/// to disk, so that prices react to trade with players and survive restarts
+#[derive(Default)]
+pub struct Sys;
+impl<'a> System<'a> for Sys {
+    type SystemData = (
+        WriteExpect<'a, EconomySim>,
+        Read<'a, Settings>,
+        ReadExpect<'a, DataDir>,
+        Write<'a, SysScheduler<Self>>,
+    );
+
+    const NAME: &'static str = "economy";
+    const ORIGIN: Origin = Origin::Server;
+    const PHASE: Phase = Phase::Create;
+
+    fn run(
+        _job: &mut Job<Self>,
+        (mut economy, settings, data_dir, mut scheduler): Self::SystemData,
+    ) {
+        if scheduler.should_run() {
+            economy.tick();
+            debug!(time = economy.time(), "Advanced site economies");
+            save(&economy, &data_dir.path, settings.world_seed);
+        }
+    }
+}
+
+fn save_path(data_dir: &Path, world_seed: u32) -> PathBuf {
+    data_dir
+        .join(ECONOMY_DIR)
+        .join(format!("{}.ron", world_seed))
+}
+
+/// Restore the economies saved for the world with the given seed, if any
+pub fn load(economy: &mut EconomySim, data_dir: &Path, world_seed: u32) {
+    let path = save_path(data_dir, world_seed);
+    if let Ok(file) = fs::File::open(&path) {
+        match ron::de::from_reader::<_, EconomySave>(file) {
+            Ok(save) => economy.load(&save),
+            Err(e) => warn!(
+                ?e,
+                "Failed to parse economy save file {}, starting from the generated economy",
+                path.display()
+            ),
+        }
+    }
+}
+
+pub fn save(economy: &EconomySim, data_dir: &Path, world_seed: u32) {
+    let path = save_path(data_dir, world_seed);
+    let ron = ron::ser::to_string_pretty(&economy.save(), ron::ser::PrettyConfig::default())
+        .expect("Failed to serialize economy");
+    if let Err(e) = data_dir::write_atomically(&path, ron.as_bytes()) {
+        error!(?e, "Failed to save economy to {}", path.display());
+    }
+}
@@ -5,7 +5,8 @@ use crate::{
     Tick,
 };
 use common::{
-    comp::{Collider, ForceUpdate, Inventory, InventoryUpdate, Last, Ori, Player, Pos, Vel},
+    combat_log::CombatLogEntry,
+    comp::{Collider, ForceUpdate, Group, Inventory, InventoryUpdate, Last, Ori, Player, Pos, Vel},
     outcome::Outcome,
     region::{Event as RegionEvent, RegionMap},
     resources::{PlayerPhysicsSettings, TimeOfDay},
@@ -15,6 +16,7 @@ use common::{
 };
 use common_ecs::{Job, Origin, Phase, System};
 use common_net::{msg::ServerGeneral, sync::CompSyncPackage};
+use hashbrown::HashMap;
 use itertools::Either;
 use specs::{Entities, Join, Read, ReadExpect, ReadStorage, Write, WriteStorage};
 use vek::*;
@@ -45,6 +47,8 @@ impl<'a> System<'a> for Sys {
         WriteStorage<'a, InventoryUpdate>,
         Write<'a, DeletedEntities>,
         Write<'a, Vec<Outcome>>,
+        Write<'a, Vec<CombatLogEntry>>,
+        ReadStorage<'a, Group>,
         Read<'a, PlayerPhysicsSettings>,
         ReadStorage<'a, Player>,
         TrackedComps<'a>,
@@ -78,6 +82,8 @@ impl<'a> System<'a> for Sys {
             mut inventory_updates,
             mut deleted_entities,
             mut outcomes,
+            mut combat_log,
+            groups,
             player_physics_settings,
             players,
             tracked_comps,
@@ -349,6 +355,35 @@ impl<'a> System<'a> for Sys {
         }
         outcomes.clear();
 
+        // Sync the combat log to the clients that asked for it. Players see what
+        // happens to and is done by themselves and the members of their group.
+        if !combat_log.is_empty() {
+            let group_of = (&uids, &groups)
+                .join()
+                .map(|(uid, group)| (*uid, *group))
+                .collect::<HashMap<_, _>>();
+            for (presence, uid, client) in (&presences, &uids, &clients).join() {
+                if !presence.combat_log {
+                    continue;
+                }
+                let group = group_of.get(uid);
+                let is_relevant =
+                    |other: &Uid| other == uid || (group.is_some() && group_of.get(other) == group);
+                let entries = combat_log
+                    .iter()
+                    .filter(|entry| {
+                        is_relevant(&entry.target)
+                            || entry.source.as_ref().map_or(false, is_relevant)
+                    })
+                    .cloned()
+                    .collect::<Vec<_>>();
+                if !entries.is_empty() {
+                    client.send_fallible(ServerGeneral::CombatLog(entries));
+                }
+            }
+        }
+        combat_log.clear();
+
         // Remove all force flags.
         force_updates.clear();
         inventory_updates.clear();
@@ -0,0 +1,73 @@
+use crate::{farming::Crops, sys::SysScheduler};
+use common::{farming::CropManifest, resources::TimeOfDay, terrain::TerrainGrid, vol::ReadVol};
+use common_ecs::{Job, Origin, Phase, System};
+use common_state::BlockChange;
+use specs::{Read, ReadExpect, Write};
+
+/// This system grows the crops planted by players as in-game time passes,
+/// whether or not their chunk is loaded, and forgets crops whose sprite is gone
+/// from a loaded chunk, such as when they were harvested.
+#[derive(Default)]
+pub struct Sys;
+impl<'a> System<'a> for Sys {
+    type SystemData = (
+        Read<'a, TimeOfDay>,
+        ReadExpect<'a, TerrainGrid>,
+        Write<'a, BlockChange>,
+        Write<'a, Crops>,
+        Write<'a, SysScheduler<Self>>,
+    );
+
+    const NAME: &'static str = "farming";
+    const ORIGIN: Origin = Origin::Server;
+    const PHASE: Phase = Phase::Create;
+
+    fn run(
+        _job: &mut Job<Self>,
+        (time_of_day, terrain, mut block_change, mut crops, mut scheduler): Self::SystemData,
+    ) {
+        if !scheduler.should_run() {
+            return;
+        }
+
+        // Time going backwards, such as when an admin sets the time of day, doesn't
+        // shrink crops
+        let dt = crops
+            .last_time_of_day
+            .map_or(0.0, |last| (time_of_day.0 - last).max(0.0));
+        crops.last_time_of_day = Some(time_of_day.0);
+
+        let crop_manifest = CropManifest::load().read();
+        for pos in crops.positions().collect::<Vec<_>>() {
+            let (def, growth) = match crops
+                .get(pos)
+                .and_then(|crop| Some((crop_manifest.0.get(&crop.kind)?, crop.growth)))
+            {
+                Some(crop) => crop,
+                // The crop was removed from the manifest
+                None => {
+                    crops.remove(pos);
+                    continue;
+                },
+            };
+
+            let block = terrain.get(pos).ok().copied();
+            // Crops that were just planted might not have their sprite yet
+            if growth > 0.0 {
+                if let Some(block) = block {
+                    if block.get_sprite() != Some(def.sprite(growth)) {
+                        crops.remove(pos);
+                        continue;
+                    }
+                }
+            }
+
+            if let Some(sprite) = crops.grow(pos, dt, def) {
+                if let Some(block) = block {
+                    block_change.set(pos, block.with_sprite(sprite));
+                }
+            }
+        }
+        crops.note_growth(dt);
+    }
+}
@@ -0,0 +1,50 @@
+use crate::fishing;
+#[cfg(not(feature = "worldgen"))]
+use crate::test_world::World;
+use common::{comp::CharacterState, resources::TimeOfDay, states::utils::StageSection};
+use common_ecs::{Job, Origin, Phase, System};
+use specs::{Entities, Join, Read, ReadExpect, WriteStorage};
+use std::sync::Arc;
+#[cfg(feature = "worldgen")] use world::World;
+
+/// This system decides when a fish bites on the lines cast into water, from
+/// the kind of water the line is in and the time of day
+#[derive(Default)]
+pub struct Sys;
+impl<'a> System<'a> for Sys {
+    type SystemData = (
+        Entities<'a>,
+        Read<'a, TimeOfDay>,
+        ReadExpect<'a, Arc<World>>,
+        WriteStorage<'a, CharacterState>,
+    );
+
+    const NAME: &'static str = "fishing";
+    const ORIGIN: Origin = Origin::Server;
+    const PHASE: Phase = Phase::Create;
+
+    fn run(
+        _job: &mut Job<Self>,
+        (entities, time_of_day, world, mut char_states): Self::SystemData,
+    ) {
+        let waiting = (&entities, &char_states)
+            .join()
+            .filter_map(|(entity, char_state)| match char_state {
+                CharacterState::Fishing(data)
+                    if data.stage_section == StageSection::Charge && data.bite.is_none() =>
+                {
+                    Some((entity, data.bobber?))
+                },
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        let mut rng = rand::thread_rng();
+        for (entity, bobber) in waiting {
+            let bite_after = fishing::water_at(&world, bobber).bite_after(time_of_day.0, &mut rng);
+            if let Some(CharacterState::Fishing(data)) = char_states.get_mut(entity) {
+                data.bite = Some(data.timer + bite_after);
+            }
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use crate::{
+    client::Client, housing::HousingManager, mail::unix_time, presence::Presence,
+    settings::Settings, sys::SysScheduler,
+};
+use common_ecs::{Job, Origin, Phase, System};
+use common_net::msg::{Notification, PresenceKind, ServerGeneral};
+use common_state::BuildAreas;
+use hashbrown::HashSet;
+use specs::{Join, ReadExpect, ReadStorage, Write};
+
+/// This system puts houses whose lease ran out or whose owners have been away
+/// for too long back on the market
+#[derive(Default)]
+pub struct Sys;
+
+impl<'a> System<'a> for Sys {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        ReadStorage<'a, Presence>,
+        ReadStorage<'a, Client>,
+        Write<'a, HousingManager>,
+        Write<'a, BuildAreas>,
+        ReadExpect<'a, Settings>,
+        Write<'a, SysScheduler<Self>>,
+    );
+
+    const NAME: &'static str = "housing";
+    const ORIGIN: Origin = Origin::Server;
+    const PHASE: Phase = Phase::Create;
+
+    fn run(
+        _job: &mut Job<Self>,
+        (
+            presences,
+            clients,
+            mut housing,
+            mut build_areas,
+            settings,
+            mut scheduler,
+        ): Self::SystemData,
+    ) {
+        if scheduler.should_run() {
+            let online = (&presences)
+                .join()
+                .filter_map(|presence| match presence.kind {
+                    PresenceKind::Character(id) => Some(id),
+                    PresenceKind::Spectator => None,
+                })
+                .collect::<HashSet<_>>();
+            let evicted =
+                housing.evict_expired(unix_time(), &settings.housing, |id| online.contains(&id));
+            if !evicted.is_empty() {
+                for (pos, _) in &evicted {
+                    let _ = build_areas.remove(&HousingManager::area_name(*pos));
+                }
+                for (presence, client) in (&presences, &clients).join() {
+                    if let PresenceKind::Character(id) = presence.kind {
+                        if evicted.iter().any(|(_, owner)| *owner == id) {
+                            client.send_fallible(ServerGeneral::Notification(
+                                Notification::HouseLost,
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use crate::{
+    client::Client,
+    mail::{unix_time, PostOffice},
+    presence::Presence,
+    sys::SysScheduler,
+};
+use common_ecs::{Job, Origin, Phase, System};
+use common_net::msg::{PresenceKind, ServerGeneral};
+use specs::{Join, ReadStorage, Write};
+
+/// This system sends letters that were not collected in time back to their
+/// senders
+#[derive(Default)]
+pub struct Sys;
+
+impl<'a> System<'a> for Sys {
+    type SystemData = (
+        ReadStorage<'a, Presence>,
+        ReadStorage<'a, Client>,
+        Write<'a, PostOffice>,
+        Write<'a, SysScheduler<Self>>,
+    );
+
+    const NAME: &'static str = "mail";
+    const ORIGIN: Origin = Origin::Server;
+    const PHASE: Phase = Phase::Create;
+
+    fn run(
+        _job: &mut Job<Self>,
+        (presences, clients, mut post_office, mut scheduler): Self::SystemData,
+    ) {
+        if scheduler.should_run() {
+            let returned = post_office.return_expired(unix_time());
+            if !returned.is_empty() {
+                for (presence, client) in (&presences, &clients).join() {
+                    if let PresenceKind::Character(id) = presence.kind {
+                        if returned.contains(&id) {
+                            client
+                                .send_fallible(ServerGeneral::MailUpdate(post_office.mailbox(id)));
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,10 +1,17 @@
 pub mod agent;
+pub mod crafting;
+#[cfg(feature = "worldgen")] pub mod economy;
 pub mod entity_sync;
+pub mod farming;
+pub mod fishing;
+pub mod housing;
 pub mod invite_timeout;
+pub mod mail;
 pub mod metrics;
 pub mod msg;
 pub mod object;
 pub mod persistence;
+pub mod quest;
 pub mod sentinel;
 pub mod subscription;
 pub mod terrain;
@@ -21,6 +28,12 @@ use std::{
 };
 
 pub type PersistenceScheduler = SysScheduler<persistence::Sys>;
+pub type QuestScheduler = SysScheduler<quest::Sys>;
+pub type FarmingScheduler = SysScheduler<farming::Sys>;
+pub type MailScheduler = SysScheduler<mail::Sys>;
+pub type HousingScheduler = SysScheduler<housing::Sys>;
+#[cfg(feature = "worldgen")]
+pub type EconomyScheduler = SysScheduler<economy::Sys>;
 
 pub fn add_server_systems(dispatch_builder: &mut DispatcherBuilder) {
     dispatch::<melee::Sys>(dispatch_builder, &[&projectile::Sys::sys_name()]);
@@ -30,6 +43,14 @@ pub fn add_server_systems(dispatch_builder: &mut DispatcherBuilder) {
     dispatch::<waypoint::Sys>(dispatch_builder, &[]);
     dispatch::<invite_timeout::Sys>(dispatch_builder, &[]);
     dispatch::<persistence::Sys>(dispatch_builder, &[]);
+    dispatch::<mail::Sys>(dispatch_builder, &[]);
+    dispatch::<housing::Sys>(dispatch_builder, &[]);
+    dispatch::<quest::Sys>(dispatch_builder, &[]);
+    dispatch::<farming::Sys>(dispatch_builder, &[]);
+    dispatch::<fishing::Sys>(dispatch_builder, &[]);
+    dispatch::<crafting::Sys>(dispatch_builder, &[]);
+    #[cfg(feature = "worldgen")]
+    dispatch::<economy::Sys>(dispatch_builder, &[]);
     dispatch::<object::Sys>(dispatch_builder, &[]);
     dispatch::<wiring::Sys>(dispatch_builder, &[]);
 }
@@ -258,6 +258,9 @@ impl Sys {
             } => {
                 presence.lossy_terrain_compression = lossy_terrain_compression;
             },
+            ClientGeneral::RequestCombatLog { enabled } => {
+                presence.combat_log = enabled;
+            },
             _ => tracing::error!("not a client_in_game msg"),
         }
         Ok(())
@@ -1,11 +1,12 @@
 use crate::{
     client::Client,
+    guild::GuildManager,
     login_provider::{LoginProvider, PendingLogin},
     metrics::PlayerMetrics,
     EditableSettings,
 };
 use common::{
-    comp::{Admin, Player, Stats},
+    comp::{Admin, Guild, Player, Stats},
     event::{EventBus, ServerEvent},
     uid::{Uid, UidAllocator},
 };
@@ -52,6 +53,8 @@ impl<'a> System<'a> for Sys {
         WriteStorage<'a, Admin>,
         ReadExpect<'a, EditableSettings>,
         Read<'a, EventBus<ServerEvent>>,
+        ReadStorage<'a, Guild>,
+        Read<'a, GuildManager>,
     );
 
     const NAME: &'static str = "msg::register";
@@ -75,18 +78,29 @@ impl<'a> System<'a> for Sys {
             mut admins,
             editable_settings,
             server_event_bus,
+            guilds,
+            guild_manager,
         ): Self::SystemData,
     ) {
         // Player list to send new players.
-        let player_list = (&uids, &players, stats.maybe(), admins.maybe())
+        let player_list = (
+            &uids,
+            &players,
+            stats.maybe(),
+            admins.maybe(),
+            guilds.maybe(),
+        )
             .join()
-            .map(|(uid, player, stats, admin)| {
+            .map(|(uid, player, stats, admin, guild)| {
                 (*uid, PlayerInfo {
                     is_online: true,
                     is_moderator: admin.is_some(),
                     player_alias: player.alias.clone(),
                     character: stats.map(|stats| CharacterInfo {
                         name: stats.name.clone(),
+                        guild_tag: guild
+                            .and_then(|guild| guild_manager.get(guild.0))
+                            .map(|data| data.tag.clone()),
                     }),
                 })
             })
@@ -1,7 +1,18 @@
-use crate::{persistence::character_updater, presence::Presence, sys::SysScheduler};
-use common::comp::{Inventory, SkillSet, Waypoint};
+use crate::{
+    farming::Crops, guild::GuildManager, housing::HousingManager, mail::PostOffice,
+    persistence::character_updater, presence::Presence, storage::StorageContainers,
+    sys::SysScheduler,
+};
+use common::{
+    comp::{
+        pet::PetPersistenceData, Alignment, Bank, Body, Health, Inventory, LoadoutPresets, Pet,
+        QuestLog, SkillSet, Stats, Waypoint,
+    },
+    uid::Uid,
+};
 use common_ecs::{Job, Origin, Phase, System};
 use common_net::msg::PresenceKind;
+use hashbrown::HashMap;
 use specs::{Join, ReadStorage, Write, WriteExpect};
 
 #[derive(Default)]
@@ -11,10 +22,25 @@ impl<'a> System<'a> for Sys {
     #[allow(clippy::type_complexity)]
     type SystemData = (
         ReadStorage<'a, Presence>,
+        ReadStorage<'a, Uid>,
         ReadStorage<'a, SkillSet>,
         ReadStorage<'a, Inventory>,
         ReadStorage<'a, Waypoint>,
+        ReadStorage<'a, QuestLog>,
+        ReadStorage<'a, Bank>,
+        ReadStorage<'a, LoadoutPresets>,
+        (
+            ReadStorage<'a, Pet>,
+            ReadStorage<'a, Alignment>,
+            ReadStorage<'a, Body>,
+            ReadStorage<'a, Stats>,
+            ReadStorage<'a, Health>,
+        ),
         WriteExpect<'a, character_updater::CharacterUpdater>,
+        (Write<'a, StorageContainers>, Write<'a, Crops>),
+        Write<'a, PostOffice>,
+        Write<'a, GuildManager>,
+        Write<'a, HousingManager>,
         Write<'a, SysScheduler<Self>>,
     );
 
@@ -26,31 +52,80 @@ impl<'a> System<'a> for Sys {
         _job: &mut Job<Self>,
         (
             presences,
+            uids,
             player_skill_set,
             player_inventories,
             player_waypoint,
+            player_quest_logs,
+            player_banks,
+            player_presets,
+            (pets, alignments, bodies, stats, healths),
             mut updater,
+            (mut storage_containers, mut crops),
+            mut post_office,
+            mut guild_manager,
+            mut housing,
             mut scheduler,
         ): Self::SystemData,
     ) {
         if scheduler.should_run() {
+            let mut pets_by_owner = HashMap::<Uid, Vec<PetPersistenceData>>::new();
+            for (_, alignment, body, stats, health) in
+                (&pets, &alignments, &bodies, &stats, &healths).join()
+            {
+                if let (Alignment::Owned(owner), false) = (alignment, health.is_dead) {
+                    pets_by_owner
+                        .entry(*owner)
+                        .or_default()
+                        .push(PetPersistenceData::new(stats, *body));
+                }
+            }
+
             updater.batch_update(
                 (
                     &presences,
+                    &uids,
                     &player_skill_set,
                     &player_inventories,
                     player_waypoint.maybe(),
+                    player_quest_logs.maybe(),
+                    player_banks.maybe(),
+                    player_presets.maybe(),
                 )
                     .join()
                     .filter_map(
-                        |(presence, skill_set, inventory, waypoint)| match presence.kind {
-                            PresenceKind::Character(id) => {
-                                Some((id, skill_set, inventory, waypoint))
-                            },
-                            PresenceKind::Spectator => None,
+                        |(
+                            presence,
+                            uid,
+                            skill_set,
+                            inventory,
+                            waypoint,
+                            quest_log,
+                            bank,
+                            presets,
+                        )| {
+                            match presence.kind {
+                                PresenceKind::Character(id) => Some((
+                                    id,
+                                    skill_set,
+                                    inventory,
+                                    waypoint,
+                                    quest_log,
+                                    pets_by_owner.remove(uid).unwrap_or_default(),
+                                    bank,
+                                    presets,
+                                )),
+                                PresenceKind::Spectator => None,
+                            }
                         },
                     ),
+                post_office.take_changes(),
+                storage_containers.take_modified(),
             );
+
+            updater.update_guilds(guild_manager.take_changes());
+            updater.update_houses(housing.take_changes());
+            updater.update_crops(crops.take_modified());
         }
     }
 }
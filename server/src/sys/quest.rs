@@ -0,0 +1,156 @@
+use crate::{client::Client, sys::SysScheduler};
+use common::{
+    assets::AssetExt,
+    comp::{
+        self,
+        item::ItemDef,
+        quest::{Objective, DESTINATION_RADIUS},
+        Alignment, Health, Inventory, Pos, QuestLog,
+    },
+    event::{EventBus, ServerEvent},
+    uid::{Uid, UidAllocator},
+};
+use common_ecs::{Job, Origin, Phase, System};
+use common_net::msg::{Notification, ServerGeneral};
+use specs::{saveload::MarkerAllocator, Entities, Join, Read, ReadStorage, Write, WriteStorage};
+use std::sync::Arc;
+use vek::*;
+
+/// This system keeps track of the quest objectives that aren't driven by
+/// events: collecting and delivering items, and escorting NPCs. Quests whose
+/// objective has been met are handed in through `ServerEvent::CompleteQuest`.
+#[derive(Default)]
+pub struct Sys;
+impl<'a> System<'a> for Sys {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        Entities<'a>,
+        Read<'a, EventBus<ServerEvent>>,
+        Read<'a, UidAllocator>,
+        ReadStorage<'a, Uid>,
+        ReadStorage<'a, Pos>,
+        ReadStorage<'a, Inventory>,
+        ReadStorage<'a, Health>,
+        ReadStorage<'a, Client>,
+        WriteStorage<'a, QuestLog>,
+        WriteStorage<'a, Alignment>,
+        Write<'a, SysScheduler<Self>>,
+    );
+
+    const NAME: &'static str = "quest";
+    const ORIGIN: Origin = Origin::Server;
+    const PHASE: Phase = Phase::Create;
+
+    fn run(
+        _job: &mut Job<Self>,
+        (
+            entities,
+            server_event_bus,
+            uid_allocator,
+            uids,
+            positions,
+            inventories,
+            healths,
+            clients,
+            mut quest_logs,
+            mut alignments,
+            mut scheduler,
+        ): Self::SystemData,
+    ) {
+        if !scheduler.should_run() {
+            return;
+        }
+        let mut server_emitter = server_event_bus.emitter();
+
+        for (entity, uid, pos, inventory, client, quest_log) in (
+            &entities,
+            &uids,
+            &positions,
+            &inventories,
+            &clients,
+            &mut quest_logs,
+        )
+            .join()
+        {
+            let reached = |destination: Option<&comp::quest::Destination>, pos: Vec3<f32>| {
+                destination.map_or(false, |destination| {
+                    destination.wpos.as_::<f32>().distance_squared(pos.xy())
+                        < DESTINATION_RADIUS.powi(2)
+                })
+            };
+            let count_items =
+                |item: &str| inventory.item_count(&Arc::<ItemDef>::load_expect_cloned(item)) as u32;
+
+            let mut changed = false;
+            let mut failed = Vec::new();
+            for quest in quest_log.active.iter_mut() {
+                let progress = match quest.objective() {
+                    Objective::Fetch { item, amount } => count_items(&item).min(amount),
+                    Objective::Deliver { item, amount } => {
+                        (reached(quest.destination.as_ref(), pos.0) && count_items(&item) >= amount)
+                            as u32
+                    },
+                    Objective::Escort => {
+                        let escortee = quest
+                            .escortee
+                            .and_then(|escortee| {
+                                uid_allocator.retrieve_entity_internal(escortee.id())
+                            })
+                            .filter(|escortee| {
+                                healths
+                                    .get(*escortee)
+                                    .map_or(false, |health| !health.is_dead)
+                            });
+                        match escortee.and_then(|escortee| positions.get(escortee)) {
+                            Some(escortee_pos) => {
+                                reached(quest.destination.as_ref(), escortee_pos.0) as u32
+                            },
+                            None => {
+                                failed.push(quest.template.clone());
+                                continue;
+                            },
+                        }
+                    },
+                    Objective::Kill { .. } => quest.progress,
+                };
+                if progress != quest.progress {
+                    quest.progress = progress;
+                    changed = true;
+                }
+                if quest.is_complete() {
+                    server_emitter.emit(ServerEvent::CompleteQuest {
+                        entity,
+                        template: quest.template.clone(),
+                    });
+                }
+            }
+
+            for template in failed {
+                if let Some(index) = quest_log
+                    .active
+                    .iter()
+                    .position(|quest| quest.template == template)
+                {
+                    let quest = quest_log.active.remove(index);
+                    // A surviving escortee stops following the player
+                    if let Some(escortee) = quest
+                        .escortee
+                        .and_then(|escortee| uid_allocator.retrieve_entity_internal(escortee.id()))
+                    {
+                        if alignments.get(escortee) == Some(&Alignment::Owned(*uid)) {
+                            let _ = alignments.insert(escortee, Alignment::Npc);
+                        }
+                    }
+                    client.send_fallible(ServerGeneral::Notification(Notification::QuestFailed(
+                        quest.title(),
+                    )));
+                    changed = true;
+                }
+            }
+
+            if changed {
+                client.send_fallible(ServerGeneral::QuestUpdate(quest_log.clone()));
+            }
+        }
+    }
+}
@@ -1,13 +1,16 @@
 use crate::{
-    chunk_generator::ChunkGenerator, client::Client, metrics::NetworkRequestMetrics,
-    presence::Presence, rtsim::RtSim, settings::Settings, SpawnPoint, Tick,
+    chunk_generator::ChunkGenerator, client::Client, farming::Crops,
+    metrics::NetworkRequestMetrics, presence::Presence, rtsim::RtSim, settings::Settings,
+    storage::StorageContainers, SpawnPoint, Tick,
 };
 use common::{
     comp::{self, agent, bird_medium, Alignment, BehaviorCapability, Pos},
     event::{EventBus, ServerEvent},
+    farming::CropManifest,
     generation::{get_npc_name, EntityInfo},
     npc::NPC_NAMES,
     terrain::TerrainGrid,
+    vol::{ReadVol, WriteVol},
     LoadoutBuilder, SkillSetBuilder,
 };
 use common_ecs::{Job, Origin, Phase, System};
@@ -93,6 +96,7 @@ impl<'a> System<'a> for Sys {
         WriteExpect<'a, TerrainGrid>,
         Write<'a, TerrainChanges>,
         WriteExpect<'a, RtSim>,
+        (Read<'a, StorageContainers>, Read<'a, Crops>),
         ReadStorage<'a, Pos>,
         ReadStorage<'a, Presence>,
         ReadStorage<'a, Client>,
@@ -114,6 +118,7 @@ impl<'a> System<'a> for Sys {
             mut terrain,
             mut terrain_changes,
             mut rtsim,
+            (storage_containers, crops),
             positions,
             presences,
             clients,
@@ -125,7 +130,7 @@ impl<'a> System<'a> for Sys {
         // Also, send the chunk data to anybody that is close by.
         let mut new_chunks = Vec::new();
         'insert_terrain_chunks: while let Some((key, res)) = chunk_generator.recv_new_chunk() {
-            let (chunk, supplement) = match res {
+            let (mut chunk, supplement) = match res {
                 Ok((chunk, supplement)) => (chunk, supplement),
                 Err(Some(entity)) => {
                     if let Some(client) = clients.get(entity) {
@@ -141,6 +146,19 @@ impl<'a> System<'a> for Sys {
                 },
             };
 
+            // Bring back the crops that were planted in this chunk, at their current stage
+            // of growth
+            let crop_manifest = CropManifest::load().read();
+            for (pos, crop) in crops.in_chunk(key) {
+                if let Some(def) = crop_manifest.0.get(&crop.kind) {
+                    let offs = TerrainGrid::chunk_offs(pos);
+                    if let Ok(block) = chunk.get(offs).map(|block| *block) {
+                        let _ = chunk.set(offs, block.with_sprite(def.sprite(crop.growth)));
+                    }
+                }
+            }
+            drop(crop_manifest);
+
             // Arcify the chunk
             let chunk = Arc::new(chunk);
 
@@ -154,6 +172,10 @@ impl<'a> System<'a> for Sys {
             } else {
                 terrain_changes.new_chunks.insert(key);
                 rtsim.hook_load_chunk(key);
+                // Bring back the storage chests that were placed in this chunk
+                for pos in storage_containers.in_chunk(key) {
+                    server_emitter.emit(ServerEvent::CreateStorage(pos));
+                }
             }
 
             // Handle chunk supplement
@@ -172,6 +194,13 @@ impl<'a> System<'a> for Sys {
                     continue;
                 }
 
+                if entity.is_storage {
+                    server_emitter.emit(ServerEvent::CreateStorage(
+                        entity.pos.map(|e| e.floor() as i32),
+                    ));
+                    continue;
+                }
+
                 let mut body = entity.body;
                 let name = entity.name.unwrap_or_else(|| "Unnamed".to_string());
                 let alignment = entity.alignment;
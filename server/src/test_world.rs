@@ -71,4 +71,14 @@ impl World {
             supplement,
         ))
     }
+
+    pub fn generate_supplement(
+        &self,
+        _index: IndexRef,
+        _chunk_pos: Vec2<i32>,
+        _chunk: &TerrainChunk,
+        _time: Option<TimeOfDay>,
+    ) -> ChunkSupplement {
+        ChunkSupplement::default()
+    }
 }
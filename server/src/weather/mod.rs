@@ -0,0 +1,34 @@
+mod sim;
+mod tick;
+
+use common::weather::WeatherGrid;
+use common_ecs::{dispatch, System};
+use common_state::State;
+use specs::DispatcherBuilder;
+use std::time::Duration;
+
+pub use self::sim::WeatherSim;
+
+/// How often the weather is advanced and sent to clients.
+const WEATHER_DT: Duration = Duration::from_secs(5);
+/// How many cells around each client the weather is sent for. Clients only
+/// look the weather up around the camera, so the rest of the world can wait
+/// until they get closer.
+const WEATHER_SYNC_RADIUS: i32 = 2;
+
+pub fn add_server_systems(dispatch_builder: &mut DispatcherBuilder) {
+    dispatch::<tick::Sys>(dispatch_builder, &[]);
+}
+
+pub fn init(state: &mut State, world: &world::World) {
+    let sim = WeatherSim::new(world);
+    let mut grid = WeatherGrid::new(world.sim().get_size());
+    sim.update(&mut grid);
+
+    state.ecs_mut().insert(grid);
+    state.ecs_mut().insert(sim);
+    state
+        .ecs_mut()
+        .insert(crate::sys::SysScheduler::<tick::Sys>::every(WEATHER_DT));
+    tracing::info!("Initiated weather simulation");
+}
@@ -0,0 +1,115 @@
+use common::{
+    grid::Grid,
+    weather::{Weather, WeatherGrid, CELL_SIZE, CHUNKS_PER_CELL},
+};
+use vek::*;
+use world::{
+    util::{FastNoise, Sampler},
+    World,
+};
+
+/// Seconds it takes for the weather over a cell to change noticeably.
+const WEATHER_PERIOD: f64 = 600.0;
+/// Scale of weather fronts, in cells.
+const FRONT_SCALE: f64 = 6.0;
+/// The wind that weather fronts drift across the world with, in blocks per
+/// second.
+const PREVAILING_WIND: Vec2<f32> = Vec2 { x: 4.0, y: 1.5 };
+/// Precipitation falls as snow rather than rain below this temperature.
+const FREEZING_TEMP: f32 = -0.4;
+
+/// The long-term climate of a weather cell, averaged from the chunks within it.
+#[derive(Copy, Clone, Default)]
+struct Climate {
+    humidity: f32,
+    temp: f32,
+}
+
+pub struct WeatherSim {
+    climate: Grid<Climate>,
+    front_nz: FastNoise,
+    fog_nz: FastNoise,
+    wind_nz: FastNoise,
+    time: f64,
+}
+
+impl WeatherSim {
+    pub fn new(world: &World) -> Self {
+        let sim = world.sim();
+        let size = WeatherGrid::new(sim.get_size()).size();
+        let climate = Grid::populate_from(size, |cell| {
+            let min = cell * CHUNKS_PER_CELL as i32;
+            let chunks = (0..CHUNKS_PER_CELL as i32)
+                .flat_map(|x| (0..CHUNKS_PER_CELL as i32).map(move |y| Vec2::new(x, y)))
+                .filter_map(|offs| sim.get(min + offs))
+                .collect::<Vec<_>>();
+            if chunks.is_empty() {
+                return Climate::default();
+            }
+            let n = chunks.len() as f32;
+            Climate {
+                humidity: chunks.iter().map(|chunk| chunk.humidity).sum::<f32>() / n,
+                temp: chunks.iter().map(|chunk| chunk.temp).sum::<f32>() / n,
+            }
+        });
+
+        Self {
+            climate,
+            front_nz: FastNoise::new(sim.seed),
+            fog_nz: FastNoise::new(sim.seed.wrapping_add(1)),
+            wind_nz: FastNoise::new(sim.seed.wrapping_add(2)),
+            time: 0.0,
+        }
+    }
+
+    /// Advance the weather by the given number of seconds.
+    pub fn tick(&mut self, grid: &mut WeatherGrid, dt: f64) {
+        self.time += dt;
+        self.update(grid);
+    }
+
+    /// Recompute the weather in every cell for the current time.
+    pub fn update(&self, grid: &mut WeatherGrid) {
+        for (cell, weather) in grid.iter_mut() {
+            *weather = self.weather_at(cell);
+        }
+    }
+
+    fn weather_at(&self, cell: Vec2<i32>) -> Weather {
+        let climate = self.climate.get(cell).copied().unwrap_or_default();
+        let t = self.time / WEATHER_PERIOD;
+
+        // Weather fronts drift across the world with the prevailing wind
+        let drift = PREVAILING_WIND.map(|e| e as f64) * self.time / CELL_SIZE as f64;
+        let pos = (cell.map(|e| e as f64) - drift) / FRONT_SCALE;
+        let front = self.front_nz.get(pos.with_z(t)) * 0.5 + 0.5;
+
+        // Humid regions are cloudy more often and dry regions almost never are
+        let cloud = ((front - 0.7 + climate.humidity * 0.8) * 1.5).clamp(0.0, 1.0);
+        let precipitation = ((cloud - 0.55) * 2.5).clamp(0.0, 1.0);
+        let (rain, snow) = if climate.temp < FREEZING_TEMP {
+            (0.0, precipitation)
+        } else {
+            (precipitation, 0.0)
+        };
+
+        // Storms bring strong winds, blowing in varying directions
+        let wind_dir = self.wind_nz.get((pos * 2.0).with_z(t)) * std::f32::consts::PI;
+        let wind = PREVAILING_WIND
+            + Vec2::new(wind_dir.cos(), wind_dir.sin()) * (2.0 + front.powi(3) * 14.0);
+
+        // Fog gathers in calm, humid places when it isn't raining
+        let fog_nz = self.fog_nz.get((pos * 3.0).with_z(t * 2.0)) * 0.5 + 0.5;
+        let fog = ((climate.humidity - 0.4) * 2.0 * fog_nz - wind.magnitude() / 30.0)
+            .clamp(0.0, 1.0)
+            * (1.0 - precipitation);
+
+        Weather {
+            cloud,
+            rain,
+            snow,
+            fog,
+            wind,
+        }
+    }
+}
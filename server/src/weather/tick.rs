@@ -0,0 +1,41 @@
+use super::{WeatherSim, WEATHER_DT, WEATHER_SYNC_RADIUS};
+use crate::{client::Client, presence::Presence, sys::SysScheduler};
+use common::{comp::Pos, weather::WeatherGrid};
+use common_ecs::{Job, Origin, Phase, System};
+use common_net::msg::{CompressedData, ServerGeneral};
+use specs::{Join, ReadStorage, Write, WriteExpect};
+
+/// This system advances the weather and sends all clients in game the weather
+/// around them
+#[derive(Default)]
+pub struct Sys;
+impl<'a> System<'a> for Sys {
+    type SystemData = (
+        WriteExpect<'a, WeatherSim>,
+        WriteExpect<'a, WeatherGrid>,
+        Write<'a, SysScheduler<Self>>,
+        ReadStorage<'a, Presence>,
+        ReadStorage<'a, Client>,
+        ReadStorage<'a, Pos>,
+    );
+
+    const NAME: &'static str = "weather::tick";
+    const ORIGIN: Origin = Origin::Server;
+    const PHASE: Phase = Phase::Create;
+
+    fn run(
+        _job: &mut Job<Self>,
+        (mut sim, mut grid, mut scheduler, presences, clients, positions): Self::SystemData,
+    ) {
+        if scheduler.should_run() {
+            sim.tick(&mut grid, WEATHER_DT.as_secs_f64());
+
+            for (_, client, pos) in (&presences, &clients, &positions).join() {
+                let window = grid.window(WeatherGrid::cell_at(pos.0.xy()), WEATHER_SYNC_RADIUS);
+                client.send_fallible(ServerGeneral::WeatherUpdate(CompressedData::compress(
+                    &window, 1,
+                )));
+            }
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use super::{
+    super::{vek::*, Animation},
+    CharacterSkeleton, SkeletonAttr,
+};
+use common::{states::utils::StageSection, util::Dir};
+
+pub struct FishingAnimation;
+
+impl Animation for FishingAnimation {
+    type Dependency<'a> = (Option<StageSection>, bool, f32, Dir);
+    type Skeleton = CharacterSkeleton;
+
+    #[cfg(feature = "use-dyn-lib")]
+    const UPDATE_FN: &'static [u8] = b"character_fishing\0";
+
+    #[cfg_attr(feature = "be-dyn-lib", export_name = "character_fishing")]
+    #[allow(clippy::approx_constant)] // TODO: Pending review in #587
+    fn update_skeleton_inner<'a>(
+        skeleton: &Self::Skeleton,
+        (stage_section, biting, global_time, look_dir): Self::Dependency<'a>,
+        anim_time: f32,
+        rate: &mut f32,
+        _s_a: &SkeletonAttr,
+    ) -> Self::Skeleton {
+        *rate = 1.0;
+        let mut next = (*skeleton).clone();
+
+        // The rod is raised behind the head to cast, held out over the water while
+        // waiting and pulled back up while reeling in
+        let (move1, move2, move3) = match stage_section {
+            Some(StageSection::Buildup) => (anim_time.powf(0.5), 0.0, 0.0),
+            Some(StageSection::Charge) => (1.0, 1.0, 0.0),
+            Some(StageSection::Recover) => (1.0, 1.0, anim_time.powf(0.25)),
+            _ => (0.0, 0.0, 0.0),
+        };
+        let windup = (move1 * std::f32::consts::PI).sin();
+        let bob = (global_time * 1.5).sin() * move2 * (1.0 - move3);
+        // A fish tugging on the line jerks the rod tip down
+        let tug = if biting {
+            (global_time * 18.0).sin().abs() * 0.2
+        } else {
+            0.0
+        };
+        let reel = (global_time * 14.0).sin() * move3;
+
+        next.head.orientation = Quaternion::rotation_x(-0.2 + look_dir.z * 0.7 + move2 * -0.2);
+
+        next.hand_l.position = Vec3::new(9.0, 1.0, 1.0);
+        next.hand_l.orientation = Quaternion::rotation_x(1.57);
+        next.hand_r.position = Vec3::new(9.0 + reel * 1.0, 1.0 + reel * 1.0, 11.0);
+        next.hand_r.orientation = Quaternion::rotation_x(1.57 + reel * 0.3);
+        next.main.position = Vec3::new(7.5, 7.5, 13.2);
+        next.main.orientation = Quaternion::rotation_y(3.14);
+
+        next.control.position = Vec3::new(-11.0, 1.8 + move2 * 3.0, 4.0 + windup * 4.0);
+        next.control.orientation =
+            Quaternion::rotation_x(windup * -1.2 + move2 * 0.8 + bob * 0.05 - tug + move3 * -0.6)
+                * Quaternion::rotation_y(0.6 + windup * -0.4)
+                * Quaternion::rotation_z(move2 * -0.3);
+
+        next
+    }
+}
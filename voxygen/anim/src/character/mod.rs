@@ -7,11 +7,13 @@ pub mod climb;
 pub mod dance;
 pub mod dash;
 pub mod equip;
+pub mod fishing;
 pub mod glidewield;
 pub mod gliding;
 pub mod idle;
 pub mod jump;
 pub mod leapmelee;
+pub mod mount;
 pub mod repeater;
 pub mod roll;
 pub mod run;
@@ -33,8 +35,9 @@ pub mod wield;
 pub use self::{
     alpha::AlphaAnimation, beam::BeamAnimation, beta::BetaAnimation, block::BlockAnimation,
     chargeswing::ChargeswingAnimation, climb::ClimbAnimation, dance::DanceAnimation,
-    dash::DashAnimation, equip::EquipAnimation, glidewield::GlideWieldAnimation,
-    gliding::GlidingAnimation, idle::IdleAnimation, jump::JumpAnimation, leapmelee::LeapAnimation,
+    dash::DashAnimation, equip::EquipAnimation, fishing::FishingAnimation,
+    glidewield::GlideWieldAnimation, gliding::GlidingAnimation, idle::IdleAnimation,
+    jump::JumpAnimation, leapmelee::LeapAnimation, mount::MountAnimation,
     repeater::RepeaterAnimation, roll::RollAnimation, run::RunAnimation,
     shockwave::ShockwaveAnimation, shoot::ShootAnimation, sit::SitAnimation, sneak::SneakAnimation,
     spin::SpinAnimation, spinmelee::SpinMeleeAnimation, staggered::StaggeredAnimation,
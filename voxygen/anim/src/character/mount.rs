@@ -0,0 +1,115 @@
+use super::{
+    super::{vek::*, Animation},
+    CharacterSkeleton, SkeletonAttr,
+};
+use std::{f32::consts::PI, ops::Mul};
+
+pub struct MountAnimation;
+
+type MountAnimationDependency = (Vec3<f32>, f32, f32);
+
+impl Animation for MountAnimation {
+    type Dependency<'a> = MountAnimationDependency;
+    type Skeleton = CharacterSkeleton;
+
+    #[cfg(feature = "use-dyn-lib")]
+    const UPDATE_FN: &'static [u8] = b"character_mount\0";
+
+    #[cfg_attr(feature = "be-dyn-lib", export_name = "character_mount")]
+    fn update_skeleton_inner<'a>(
+        skeleton: &Self::Skeleton,
+        (velocity, global_time, acc_vel): Self::Dependency<'a>,
+        anim_time: f32,
+        _rate: &mut f32,
+        s_a: &SkeletonAttr,
+    ) -> Self::Skeleton {
+        let mut next = (*skeleton).clone();
+
+        let speed = Vec2::<f32>::from(velocity).magnitude().min(15.0);
+        let slow = (anim_time * 1.0).sin();
+        // Bob along with the gait of the mount
+        let gait = (acc_vel * 1.2).sin() * (speed / 15.0);
+        let gaitb = (acc_vel * 1.2 + PI / 2.0).sin() * (speed / 15.0);
+
+        let head_look = Vec2::new(
+            (global_time * 0.05 + anim_time / 15.0)
+                .floor()
+                .mul(7331.0)
+                .sin()
+                * 0.25,
+            (global_time * 0.05 + anim_time / 15.0)
+                .floor()
+                .mul(1337.0)
+                .sin()
+                * 0.125,
+        );
+        next.head.position = Vec3::new(0.0, s_a.head.0, s_a.head.1 + slow * 0.1);
+        next.head.orientation = Quaternion::rotation_z(head_look.x)
+            * Quaternion::rotation_x(head_look.y.abs() + gait * -0.05);
+
+        next.chest.position = Vec3::new(0.0, s_a.chest.0, s_a.chest.1 + gait * 0.6);
+        next.chest.orientation = Quaternion::rotation_x(speed * 0.01 + gaitb * 0.05);
+
+        next.belt.position = Vec3::new(0.0, s_a.belt.0, s_a.belt.1);
+        next.belt.orientation = Quaternion::rotation_x(speed * -0.005);
+
+        next.back.position = Vec3::new(0.0, s_a.back.0, s_a.back.1);
+
+        next.shorts.position = Vec3::new(0.0, s_a.shorts.0 + 1.0, s_a.shorts.1);
+        next.shorts.orientation = Quaternion::rotation_x(0.3);
+
+        // Hands hold on to the reins
+        next.hand_l.position = Vec3::new(
+            -s_a.hand.0 + 2.0,
+            s_a.hand.1 + 5.0,
+            s_a.hand.2 + 2.0 + gait * 0.5,
+        );
+        next.hand_l.orientation = Quaternion::rotation_x(1.2) * Quaternion::rotation_y(-0.3);
+
+        next.hand_r.position = Vec3::new(
+            s_a.hand.0 - 2.0,
+            s_a.hand.1 + 5.0,
+            s_a.hand.2 + 2.0 + gait * 0.5,
+        );
+        next.hand_r.orientation = Quaternion::rotation_x(1.2) * Quaternion::rotation_y(0.3);
+
+        // Legs straddle the back of the mount
+        next.foot_l.position = Vec3::new(-s_a.foot.0 - 2.5, 3.0 + s_a.foot.1, 4.0 + s_a.foot.2);
+        next.foot_l.orientation =
+            Quaternion::rotation_x(0.8 + gaitb * 0.1) * Quaternion::rotation_y(-0.4);
+
+        next.foot_r.position = Vec3::new(s_a.foot.0 + 2.5, 3.0 + s_a.foot.1, 4.0 + s_a.foot.2);
+        next.foot_r.orientation =
+            Quaternion::rotation_x(0.8 + gaitb * 0.1) * Quaternion::rotation_y(0.4);
+
+        next.shoulder_l.position = Vec3::new(-s_a.shoulder.0, s_a.shoulder.1, s_a.shoulder.2);
+        next.shoulder_l.orientation = Quaternion::rotation_x(0.0);
+
+        next.shoulder_r.position = Vec3::new(s_a.shoulder.0, s_a.shoulder.1, s_a.shoulder.2);
+        next.shoulder_r.orientation = Quaternion::rotation_x(0.0);
+
+        next.torso.position = Vec3::new(0.0, 0.0, gait.abs() * 0.1) * s_a.scaler;
+
+        if skeleton.holding_lantern {
+            next.hand_r.position = Vec3::new(
+                s_a.hand.0 + 1.0 - head_look.x * 8.0,
+                s_a.hand.1 + 5.0 + head_look.x * 6.0,
+                s_a.hand.2 + 9.0 + head_look.y * 6.0,
+            );
+            next.hand_r.orientation = Quaternion::rotation_x(2.25)
+                * Quaternion::rotation_z(0.9)
+                * Quaternion::rotation_y(head_look.x * 3.0)
+                * Quaternion::rotation_x(head_look.y * 3.0);
+
+            let fast = (anim_time * 5.0).sin();
+            let fast2 = (anim_time * 4.5 + 8.0).sin();
+
+            next.lantern.position = Vec3::new(-0.5, -0.5, -2.5);
+            next.lantern.orientation = next.hand_r.orientation.inverse()
+                * Quaternion::rotation_x(fast * 0.1 + gait * 0.1)
+                * Quaternion::rotation_y(fast2 * 0.1);
+        }
+
+        next
+    }
+}
@@ -1,5 +1,5 @@
 use super::{
-    img_ids::Imgs, ChatTab, ERROR_COLOR, FACTION_COLOR, GROUP_COLOR, INFO_COLOR, KILL_COLOR,
+    img_ids::Imgs, ChatTab, COMBAT_LOG_COLOR, ERROR_COLOR, FACTION_COLOR, GROUP_COLOR, GUILD_COLOR, INFO_COLOR, KILL_COLOR,
     OFFLINE_COLOR, ONLINE_COLOR, REGION_COLOR, SAY_COLOR, TELL_COLOR, TEXT_COLOR, WORLD_COLOR,
 };
 use crate::{i18n::Localization, settings::chat::MAX_CHAT_TABS, ui::fonts::Fonts, GlobalState};
@@ -429,6 +429,13 @@ impl<'a> Widget for Chat<'a> {
                         }
                     }
                 }
+                // NPCs talk in localization keys, which fall back to the message itself
+                if let ChatType::NpcSay(_, r) | ChatType::NpcTell(_, _, r) = &message.chat_type {
+                    message.message = self
+                        .localized_strings
+                        .get_variation(&message.message, *r)
+                        .to_string();
+                }
                 message.message = self.client.format_message(&message, show_char_name);
                 message
             })
@@ -723,6 +730,7 @@ fn render_chat_mode(chat_mode: &ChatMode, imgs: &Imgs) -> (Color, conrod_core::i
         ChatMode::Region => (REGION_COLOR, imgs.chat_region_small),
         ChatMode::Faction(_) => (FACTION_COLOR, imgs.chat_faction_small),
         ChatMode::Group(_) => (GROUP_COLOR, imgs.chat_group_small),
+        ChatMode::Guild(_) => (GUILD_COLOR, imgs.chat_faction_small),
         ChatMode::Tell(_) => (TELL_COLOR, imgs.chat_tell_small),
     }
 }
@@ -736,17 +744,20 @@ fn render_chat_line(chat_type: &ChatType<String>, imgs: &Imgs) -> (Color, conrod
         ChatType::CommandInfo => (INFO_COLOR, imgs.chat_command_info_small),
         ChatType::GroupMeta(_) => (GROUP_COLOR, imgs.chat_group_small),
         ChatType::FactionMeta(_) => (FACTION_COLOR, imgs.chat_faction_small),
+        ChatType::GuildMeta(_) => (GUILD_COLOR, imgs.chat_faction_small),
         ChatType::Kill(_, _) => (KILL_COLOR, imgs.chat_kill_small),
         ChatType::Tell(_from, _to) => (TELL_COLOR, imgs.chat_tell_small),
         ChatType::Say(_uid) => (SAY_COLOR, imgs.chat_say_small),
         ChatType::Group(_uid, _s) => (GROUP_COLOR, imgs.chat_group_small),
         ChatType::Faction(_uid, _s) => (FACTION_COLOR, imgs.chat_faction_small),
+        ChatType::Guild(_uid, _guild) => (GUILD_COLOR, imgs.chat_faction_small),
         ChatType::Region(_uid) => (REGION_COLOR, imgs.chat_region_small),
         ChatType::World(_uid) => (WORLD_COLOR, imgs.chat_world_small),
         ChatType::Npc(_uid, _r) => panic!("NPCs can't talk!"), // Should be filtered by hud/mod.rs
         ChatType::NpcSay(_uid, _r) => (SAY_COLOR, imgs.chat_say_small),
         ChatType::NpcTell(_from, _to, _r) => (TELL_COLOR, imgs.chat_tell_small),
         ChatType::Meta => (INFO_COLOR, imgs.chat_command_info_small),
+        ChatType::CombatLog => (COMBAT_LOG_COLOR, imgs.chat_kill_small),
     }
 }
 
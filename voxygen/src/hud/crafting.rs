@@ -15,9 +15,11 @@ use client::{self, Client};
 use common::{
     assets::AssetExt,
     comp::{
+        crafting::MAX_QUEUED_CRAFTS,
         item::{
             ItemDef, ItemDesc, ItemKind, ItemTag, MaterialStatManifest, Quality, TagExampleInfo,
         },
+        slot::Slot,
         Inventory,
     },
     recipe::RecipeInput,
@@ -54,6 +56,13 @@ widget_ids! {
         align_ing,
         scrollbar_ing,
         btn_craft,
+        btn_craft_all,
+        btn_improve,
+        btn_repair,
+        btn_cancel_craft,
+        craft_progress_bg,
+        craft_progress_bar,
+        craft_progress_txt,
         recipe_list_btns[],
         recipe_list_labels[],
         recipe_list_quality_indicators[],
@@ -77,7 +86,13 @@ widget_ids! {
 }
 
 pub enum Event {
-    CraftRecipe(String),
+    CraftRecipe {
+        recipe: String,
+        amount: u32,
+        improve: bool,
+    },
+    CancelCrafting,
+    RepairItem(Slot),
     ChangeCraftingTab(CraftingTab),
     Close,
     Focus(widget::Id),
@@ -216,6 +231,8 @@ impl CraftingTab {
 pub struct State {
     ids: Ids,
     selected_recipe: Option<String>,
+    /// Whether to use the optional ingredients of recipes
+    improve: bool,
 }
 
 impl<'a> Widget for Crafting<'a> {
@@ -227,6 +244,7 @@ impl<'a> Widget for Crafting<'a> {
         State {
             ids: Ids::new(id_gen),
             selected_recipe: None,
+            improve: false,
         }
     }
 
@@ -593,7 +611,49 @@ impl<'a> Widget for Crafting<'a> {
                 .set(state.ids.btn_craft, ui)
                 .was_clicked()
             {
-                events.push(Event::CraftRecipe(recipe_name.clone()));
+                events.push(Event::CraftRecipe {
+                    recipe: recipe_name.clone(),
+                    amount: 1,
+                    improve: state.improve,
+                });
+            }
+
+            // Craft all button, queues up as many crafts as there are ingredients for
+            let max_crafts = if can_perform {
+                recipe.max_crafts(self.inventory, MAX_QUEUED_CRAFTS)
+            } else {
+                0
+            };
+            let can_craft_all = max_crafts > 1;
+            if Button::image(self.imgs.button)
+                .w_h(60.0, 25.0)
+                .hover_image(
+                    can_craft_all
+                        .then_some(self.imgs.button_hover)
+                        .unwrap_or(self.imgs.button),
+                )
+                .press_image(
+                    can_craft_all
+                        .then_some(self.imgs.button_press)
+                        .unwrap_or(self.imgs.button),
+                )
+                .label(&format!("x{}", max_crafts))
+                .label_y(conrod_core::position::Relative::Scalar(1.0))
+                .label_color(can_craft_all.then_some(TEXT_COLOR).unwrap_or(TEXT_GRAY_COLOR))
+                .label_font_size(self.fonts.cyri.scale(12))
+                .label_font_id(self.fonts.cyri.conrod_id)
+                .image_color(can_craft_all.then_some(TEXT_COLOR).unwrap_or(TEXT_GRAY_COLOR))
+                .right_from(state.ids.btn_craft, 5.0)
+                .parent(state.ids.window_frame)
+                .set(state.ids.btn_craft_all, ui)
+                .was_clicked()
+                && can_craft_all
+            {
+                events.push(Event::CraftRecipe {
+                    recipe: recipe_name.clone(),
+                    amount: max_crafts,
+                    improve: state.improve,
+                });
             }
 
             // Output Image Frame
@@ -901,6 +961,49 @@ impl<'a> Widget for Crafting<'a> {
                         .set(state.ids.ingredients[i], ui);
                 }
             }
+
+            // Optional ingredients, used when the toggle is on to improve the quality of
+            // what is crafted
+            if !recipe.optional_inputs.is_empty() {
+                let optional = recipe
+                    .optional_inputs()
+                    .map(|(input, amount)| {
+                        let name = match input {
+                            RecipeInput::Item(item_def) => item_def.name().to_string(),
+                            RecipeInput::Tag(tag) => format!("Any {}", tag.name()),
+                        };
+                        format!("{}x {}", amount, name)
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let label = format!(
+                    "[{}] {}",
+                    if state.improve { "x" } else { " " },
+                    self.localized_strings
+                        .get("hud.crafting.improve")
+                        .replace("{ingredients}", &optional)
+                );
+                let improve_btn = Button::image(self.imgs.nothing)
+                    .w_h(260.0, 20.0)
+                    .label(&label)
+                    .label_color(TEXT_COLOR)
+                    .label_font_size(self.fonts.cyri.scale(12))
+                    .label_font_id(self.fonts.cyri.conrod_id)
+                    .label_x(conrod_core::position::Relative::Place(
+                        conrod_core::position::Place::Start(Some(2.0)),
+                    ));
+                let improve_btn = match recipe.inputs().len() {
+                    0 => improve_btn.down_from(state.ids.ingredients_txt, 10.0),
+                    n => improve_btn.down_from(state.ids.ingredient_frame[n - 1], 10.0),
+                };
+                if improve_btn
+                    .align_left_of(state.ids.ingredients_txt)
+                    .set(state.ids.btn_improve, ui)
+                    .was_clicked()
+                {
+                    state.update(|s| s.improve = !s.improve);
+                }
+            }
         }
 
         // Search / Title Recipes
@@ -968,6 +1071,81 @@ impl<'a> Widget for Crafting<'a> {
             .rgba(0.33, 0.33, 0.33, 1.0)
             .set(state.ids.scrollbar_ing, ui);
 
+        // Progress of the crafts being worked on
+        if let Some(crafting_queue) = self.client.crafting_queue() {
+            if let Some(craft) = crafting_queue.current() {
+                let name = self
+                    .client
+                    .recipe_book()
+                    .get(&craft.recipe)
+                    .map_or(craft.recipe.as_str(), |recipe| recipe.output.0.name());
+                Rectangle::fill_with([300.0, 14.0], color::rgba(0.0, 0.0, 0.0, 0.7))
+                    .mid_bottom_with_margin_on(state.ids.window, -20.0)
+                    .set(state.ids.craft_progress_bg, ui);
+                Rectangle::fill_with(
+                    [296.0 * f64::from(crafting_queue.progress()), 10.0],
+                    UI_HIGHLIGHT_0,
+                )
+                .top_left_with_margins_on(state.ids.craft_progress_bg, 2.0, 2.0)
+                .set(state.ids.craft_progress_bar, ui);
+                Text::new(
+                    &self
+                        .localized_strings
+                        .get("hud.crafting.progress")
+                        .replace("{item}", name)
+                        .replace("{remaining}", &crafting_queue.remaining().to_string()),
+                )
+                .middle_of(state.ids.craft_progress_bg)
+                .font_id(self.fonts.cyri.conrod_id)
+                .font_size(self.fonts.cyri.scale(10))
+                .color(TEXT_COLOR)
+                .set(state.ids.craft_progress_txt, ui);
+                if Button::image(self.imgs.close_button)
+                    .w_h(16.0, 16.0)
+                    .hover_image(self.imgs.close_button_hover)
+                    .press_image(self.imgs.close_button_press)
+                    .right_from(state.ids.craft_progress_bg, 4.0)
+                    .set(state.ids.btn_cancel_craft, ui)
+                    .was_clicked()
+                {
+                    events.push(Event::CancelCrafting);
+                }
+            }
+        }
+
+        // Repair button, only available at crafting stations
+        if let Some((_, sprite)) = self.show.craft_sprite {
+            let repairable = self.client.repairable_item(Some(sprite));
+            let can_repair = repairable.is_some();
+            if Button::image(self.imgs.button)
+                .w_h(105.0, 25.0)
+                .hover_image(
+                    can_repair
+                        .then_some(self.imgs.button_hover)
+                        .unwrap_or(self.imgs.button),
+                )
+                .press_image(
+                    can_repair
+                        .then_some(self.imgs.button_press)
+                        .unwrap_or(self.imgs.button),
+                )
+                .label(&self.localized_strings.get("hud.crafting.repair"))
+                .label_y(conrod_core::position::Relative::Scalar(1.0))
+                .label_color(can_repair.then_some(TEXT_COLOR).unwrap_or(TEXT_GRAY_COLOR))
+                .label_font_size(self.fonts.cyri.scale(12))
+                .label_font_id(self.fonts.cyri.conrod_id)
+                .image_color(can_repair.then_some(TEXT_COLOR).unwrap_or(TEXT_GRAY_COLOR))
+                .mid_bottom_with_margin_on(state.ids.align_rec, -31.0)
+                .parent(state.ids.window_frame)
+                .set(state.ids.btn_repair, ui)
+                .was_clicked()
+            {
+                if let Some(slot) = repairable {
+                    events.push(Event::RepairItem(slot));
+                }
+            }
+        }
+
         events
     }
 }
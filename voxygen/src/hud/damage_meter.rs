@@ -0,0 +1,395 @@
+use super::{get_buff_title, img_ids::Imgs, util::tool_kind_text, TEXT_COLOR, UI_HIGHLIGHT_0};
+use crate::{i18n::Localization, ui::fonts::Fonts};
+use client::Client;
+use common::{
+    combat_log::{CombatLogEntry, CombatLogKind},
+    comp::{InputKind, Stats},
+    states::utils::AbilityInfo,
+    uid::Uid,
+    DamageKind, DamageSource,
+};
+use common_net::sync::WorldSyncExt;
+use conrod_core::{
+    color,
+    position::Relative,
+    widget::{self, Button, Rectangle, Text},
+    widget_ids, Color, Colorable, Labelable, Positionable, Sizeable, Widget, WidgetCommon,
+};
+use hashbrown::HashMap;
+
+/// How long the group has to stop fighting for the next hit to start a new
+/// fight, in seconds
+const FIGHT_TIMEOUT: f64 = 20.0;
+/// How many members of the group are listed at most
+const MAX_ROWS: usize = 10;
+const WIDTH: f64 = 260.0;
+const ROW_HEIGHT: f64 = 20.0;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum MeterMode {
+    Damage,
+    Healing,
+}
+
+impl Default for MeterMode {
+    fn default() -> Self { Self::Damage }
+}
+
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Totals {
+    pub damage: u32,
+    pub healing: u32,
+}
+
+/// Damage and healing done by the player and their group during the current
+/// fight
+#[derive(Default)]
+pub struct MeterData {
+    /// When the current fight started and when the last hit of it landed
+    fight: Option<(f64, f64)>,
+    totals: HashMap<Uid, Totals>,
+}
+
+impl MeterData {
+    /// Counts an entry of the combat log that was caused by the player or a
+    /// member of their group
+    pub fn record(&mut self, entry: &CombatLogEntry) {
+        let source = match entry.source {
+            Some(source) => source,
+            None => return,
+        };
+        let (damage, healing) = (entry.kind.damage(), entry.kind.healing());
+        if damage == 0 && healing == 0 {
+            return;
+        }
+
+        let start = match self.fight {
+            Some((start, last)) if entry.time - last < FIGHT_TIMEOUT => start,
+            _ => {
+                self.totals.clear();
+                entry.time
+            },
+        };
+        self.fight = Some((start, entry.time));
+        let totals = self.totals.entry(source).or_default();
+        totals.damage += damage;
+        totals.healing += healing;
+    }
+
+    pub fn reset(&mut self) { *self = Self::default(); }
+
+    /// How long the current fight has lasted, in seconds. Never less than a
+    /// second, so that a single hit doesn't show an absurd rate.
+    pub fn duration(&self) -> f64 { self.fight.map_or(1.0, |(start, last)| (last - start).max(1.0)) }
+
+    /// The totals of each member of the group, highest first
+    fn ranking(&self, mode: MeterMode) -> Vec<(Uid, u32)> {
+        let mut ranking = self
+            .totals
+            .iter()
+            .map(|(uid, totals)| {
+                (*uid, match mode {
+                    MeterMode::Damage => totals.damage,
+                    MeterMode::Healing => totals.healing,
+                })
+            })
+            .filter(|(_, total)| *total > 0)
+            .collect::<Vec<_>>();
+        ranking.sort_by(|a, b| b.1.cmp(&a.1));
+        ranking
+    }
+}
+
+/// The name of an entity, or of the player controlling it
+pub fn name_of(client: &Client, uid: Uid, i18n: &Localization) -> String {
+    let ecs = client.state().ecs();
+    ecs.entity_from_uid(uid.0)
+        .and_then(|entity| ecs.read_storage::<Stats>().get(entity).map(|s| s.name.clone()))
+        .or_else(|| {
+            client
+                .player_list()
+                .get(&uid)
+                .map(|player| player.player_alias.clone())
+        })
+        .unwrap_or_else(|| i18n.get("hud.combat_log.someone").to_owned())
+}
+
+fn ability_text(ability: &AbilityInfo, i18n: &Localization) -> String {
+    let input = match ability.input {
+        InputKind::Primary => i18n.get("gameinput.primary").to_owned(),
+        InputKind::Secondary => i18n.get("gameinput.secondary").to_owned(),
+        InputKind::Block => i18n.get("gameinput.block").to_owned(),
+        InputKind::Ability(n) => i18n
+            .get("hud.combat_log.ability_n")
+            .replace("{n}", &(n + 1).to_string()),
+        InputKind::Roll => i18n.get("gameinput.roll").to_owned(),
+        InputKind::Jump => i18n.get("gameinput.jump").to_owned(),
+        InputKind::Fly => i18n.get("gameinput.fly").to_owned(),
+    };
+    match ability.tool {
+        Some(tool) => i18n
+            .get("hud.combat_log.ability")
+            .replace("{tool}", tool_kind_text(tool, i18n))
+            .replace("{input}", &input),
+        None => input,
+    }
+}
+
+fn damage_kind_text(kind: DamageKind, i18n: &Localization) -> &str {
+    match kind {
+        DamageKind::Piercing => i18n.get("hud.combat_log.piercing"),
+        DamageKind::Slashing => i18n.get("hud.combat_log.slashing"),
+        DamageKind::Crushing => i18n.get("hud.combat_log.crushing"),
+        DamageKind::Energy => i18n.get("hud.combat_log.energy"),
+    }
+}
+
+/// Describes an entry of the combat log as a line of chat
+pub fn describe_entry(client: &Client, entry: &CombatLogEntry, i18n: &Localization) -> String {
+    let source = entry.source.map_or_else(
+        || i18n.get("hud.combat_log.someone").to_owned(),
+        |source| name_of(client, source, i18n),
+    );
+    let target = name_of(client, entry.target, i18n);
+    let mut text = match entry.kind {
+        CombatLogKind::Damage {
+            source: damage_source,
+            kind,
+            crit,
+            amount,
+            mitigated,
+        } => match kind {
+            Some(kind) => i18n
+                .get(if crit {
+                    "hud.combat_log.crit"
+                } else {
+                    "hud.combat_log.damage"
+                })
+                .replace("{kind}", damage_kind_text(kind, i18n))
+                .replace("{mitigated}", &mitigated.to_string()),
+            None => {
+                let buff = match damage_source {
+                    DamageSource::Buff(buff) => get_buff_title(buff, i18n),
+                    _ => i18n.get("hud.combat_log.someone"),
+                };
+                i18n.get("hud.combat_log.damage_over_time")
+                    .replace("{buff}", buff)
+            },
+        }
+        .replace("{amount}", &amount.to_string()),
+        CombatLogKind::Heal { amount } => i18n
+            .get("hud.combat_log.heal")
+            .replace("{amount}", &amount.to_string()),
+        CombatLogKind::BuffApplied(buff) => i18n
+            .get("hud.combat_log.buff_applied")
+            .replace("{buff}", get_buff_title(buff, i18n)),
+        CombatLogKind::BuffRemoved(buff) => i18n
+            .get("hud.combat_log.buff_removed")
+            .replace("{buff}", get_buff_title(buff, i18n)),
+    }
+    .replace("{source}", &source)
+    .replace("{target}", &target);
+    if let Some(ability) = &entry.ability {
+        text.push_str(&format!(" [{}]", ability_text(ability, i18n)));
+    }
+    text
+}
+
+/// Shortens large numbers, e.g. 12345 to 12.3k
+fn abbreviate(value: f64) -> String {
+    if value >= 1_000_000.0 {
+        format!("{:.1}m", value / 1_000_000.0)
+    } else if value >= 1_000.0 {
+        format!("{:.1}k", value / 1_000.0)
+    } else {
+        format!("{:.0}", value)
+    }
+}
+
+widget_ids! {
+    pub struct Ids {
+        bg,
+        title,
+        close,
+        mode_button,
+        reset_button,
+        empty,
+        bars[],
+        names[],
+        totals[],
+    }
+}
+
+pub enum Event {
+    Close,
+    Reset,
+    SetMode(MeterMode),
+}
+
+/// Shows how much damage or healing each member of the group did during the
+/// current fight
+#[derive(WidgetCommon)]
+pub struct DamageMeter<'a> {
+    client: &'a Client,
+    imgs: &'a Imgs,
+    fonts: &'a Fonts,
+    #[conrod(common_builder)]
+    common: widget::CommonBuilder,
+    localized_strings: &'a Localization,
+    data: &'a MeterData,
+    mode: MeterMode,
+}
+
+impl<'a> DamageMeter<'a> {
+    pub fn new(
+        client: &'a Client,
+        imgs: &'a Imgs,
+        fonts: &'a Fonts,
+        localized_strings: &'a Localization,
+        data: &'a MeterData,
+        mode: MeterMode,
+    ) -> Self {
+        Self {
+            client,
+            imgs,
+            fonts,
+            common: widget::CommonBuilder::default(),
+            localized_strings,
+            data,
+            mode,
+        }
+    }
+}
+
+impl<'a> Widget for DamageMeter<'a> {
+    type Event = Vec<Event>;
+    type State = Ids;
+    type Style = ();
+
+    fn init_state(&self, id_gen: widget::id::Generator) -> Self::State { Ids::new(id_gen) }
+
+    fn style(&self) -> Self::Style {}
+
+    fn update(self, args: widget::UpdateArgs<Self>) -> Self::Event {
+        common_base::prof_span!("DamageMeter::update");
+        let widget::UpdateArgs { state, ui, .. } = args;
+        let i18n = &self.localized_strings;
+        let mut events = Vec::new();
+
+        let ranking = self.data.ranking(self.mode);
+        let rows = ranking.len().min(MAX_ROWS);
+        if state.bars.len() < rows {
+            state.update(|s| {
+                s.bars.resize(rows, &mut ui.widget_id_generator());
+                s.names.resize(rows, &mut ui.widget_id_generator());
+                s.totals.resize(rows, &mut ui.widget_id_generator());
+            });
+        }
+
+        // Background
+        Rectangle::fill_with(
+            [WIDTH, 60.0 + rows.max(1) as f64 * ROW_HEIGHT],
+            color::rgba(0.0, 0.0, 0.0, 0.6),
+        )
+        .mid_right_with_margin_on(ui.window, 5.0)
+        .set(state.bg, ui);
+
+        Text::new(&i18n.get("hud.combat_log.meter"))
+            .top_left_with_margins_on(state.bg, 6.0, 8.0)
+            .font_id(self.fonts.cyri.conrod_id)
+            .font_size(self.fonts.cyri.scale(16))
+            .color(TEXT_COLOR)
+            .set(state.title, ui);
+
+        if Button::image(self.imgs.close_btn)
+            .w_h(24.0, 25.0)
+            .hover_image(self.imgs.close_btn_hover)
+            .press_image(self.imgs.close_btn_press)
+            .top_right_with_margins_on(state.bg, 0.0, 0.0)
+            .set(state.close, ui)
+            .was_clicked()
+        {
+            events.push(Event::Close);
+        }
+
+        // Switches between damage and healing
+        let (mode_label, next_mode) = match self.mode {
+            MeterMode::Damage => ("hud.combat_log.meter_damage", MeterMode::Healing),
+            MeterMode::Healing => ("hud.combat_log.meter_healing", MeterMode::Damage),
+        };
+        if Button::image(self.imgs.button)
+            .w_h(90.0, 20.0)
+            .hover_image(self.imgs.button_hover)
+            .press_image(self.imgs.button_press)
+            .top_left_with_margins_on(state.bg, 30.0, 8.0)
+            .label(&i18n.get(mode_label))
+            .label_font_size(self.fonts.cyri.scale(12))
+            .label_color(TEXT_COLOR)
+            .label_font_id(self.fonts.cyri.conrod_id)
+            .label_y(Relative::Scalar(2.0))
+            .set(state.mode_button, ui)
+            .was_clicked()
+        {
+            events.push(Event::SetMode(next_mode));
+        }
+        if Button::image(self.imgs.button)
+            .w_h(90.0, 20.0)
+            .hover_image(self.imgs.button_hover)
+            .press_image(self.imgs.button_press)
+            .right_from(state.mode_button, 6.0)
+            .label(&i18n.get("hud.combat_log.meter_reset"))
+            .label_font_size(self.fonts.cyri.scale(12))
+            .label_color(TEXT_COLOR)
+            .label_font_id(self.fonts.cyri.conrod_id)
+            .label_y(Relative::Scalar(2.0))
+            .set(state.reset_button, ui)
+            .was_clicked()
+        {
+            events.push(Event::Reset);
+        }
+
+        if ranking.is_empty() {
+            Text::new(&i18n.get("hud.combat_log.meter_empty"))
+                .top_left_with_margins_on(state.bg, 56.0, 8.0)
+                .font_id(self.fonts.cyri.conrod_id)
+                .font_size(self.fonts.cyri.scale(12))
+                .color(TEXT_COLOR)
+                .set(state.empty, ui);
+            return events;
+        }
+
+        let best = ranking[0].1.max(1) as f64;
+        let duration = self.data.duration();
+        for (i, (uid, total)) in ranking.iter().take(MAX_ROWS).enumerate() {
+            let top = 56.0 + i as f64 * ROW_HEIGHT;
+            Rectangle::fill_with(
+                [(WIDTH - 16.0) * *total as f64 / best, ROW_HEIGHT - 2.0],
+                match self.mode {
+                    MeterMode::Damage => Color::Rgba(0.6, 0.15, 0.1, 0.8),
+                    MeterMode::Healing => Color::Rgba(0.15, 0.5, 0.15, 0.8),
+                },
+            )
+            .top_left_with_margins_on(state.bg, top, 8.0)
+            .set(state.bars[i], ui);
+            Text::new(&name_of(self.client, *uid, i18n))
+                .mid_left_with_margin_on(state.bars[i], 4.0)
+                .font_id(self.fonts.cyri.conrod_id)
+                .font_size(self.fonts.cyri.scale(12))
+                .color(TEXT_COLOR)
+                .set(state.names[i], ui);
+            Text::new(
+                &i18n
+                    .get("hud.combat_log.meter_row")
+                    .replace("{total}", &abbreviate(*total as f64))
+                    .replace("{rate}", &abbreviate(*total as f64 / duration)),
+            )
+            .top_right_with_margins_on(state.bg, top + 2.0, 12.0)
+            .font_id(self.fonts.cyri.conrod_id)
+            .font_size(self.fonts.cyri.scale(12))
+            .color(UI_HIGHLIGHT_0)
+            .set(state.totals[i], ui);
+        }
+
+        events
+    }
+}
@@ -10,15 +10,18 @@ use crate::{
 use conrod_core::{
     color,
     image::Id,
-    widget::{self, button, Button, Image, Rectangle, Text},
+    position::Relative,
+    widget::{self, button, Button, Image, Rectangle, Text, TextEdit},
     widget_ids, Color, Colorable, Labelable, Positionable, Sizeable, UiCell, Widget, WidgetCommon,
 };
 
 use client::{self, Client};
 use common::comp::{
     item::tool::ToolKind,
+    preset::MAX_PRESET_NAME_LENGTH,
+    skill_tree::DATA_SKILL_TREES,
     skills::{self, Skill},
-    SkillSet,
+    LoadoutPresets, QuestLog, SkillSet,
 };
 
 widget_ids! {
@@ -38,6 +41,18 @@ widget_ids! {
         tree_title_txt,
         lock_imgs[],
         available_pts_txt,
+        quest_log_title,
+        quest_log_txt,
+        quests_completed_txt,
+        respec_btn,
+        presets_title,
+        preset_name_bg,
+        preset_name,
+        preset_save_btn,
+        no_presets_txt,
+        preset_txts[],
+        preset_apply_btns[],
+        preset_delete_btns[],
         weapon_imgs[],
         weapon_btns[],
         skills_top_l_align,
@@ -48,6 +63,7 @@ widget_ids! {
         skills_top_r[],
         skills_bot_l[],
         skills_bot_r[],
+        data_skills[],
         sword_render,
         skill_sword_combo_0,
         skill_sword_combo_1,
@@ -179,7 +195,7 @@ widget_ids! {
 #[derive(WidgetCommon)]
 pub struct Diary<'a> {
     show: &'a Show,
-    _client: &'a Client,
+    client: &'a Client,
     skill_set: &'a SkillSet,
     imgs: &'a Imgs,
     item_imgs: &'a ItemImgs,
@@ -200,7 +216,7 @@ pub struct Diary<'a> {
 impl<'a> Diary<'a> {
     pub fn new(
         show: &'a Show,
-        _client: &'a Client,
+        client: &'a Client,
         skill_set: &'a SkillSet,
         imgs: &'a Imgs,
         item_imgs: &'a ItemImgs,
@@ -212,7 +228,7 @@ impl<'a> Diary<'a> {
     ) -> Self {
         Self {
             show,
-            _client,
+            client,
             skill_set,
             imgs,
             item_imgs,
@@ -232,6 +248,9 @@ impl<'a> Diary<'a> {
 
 pub type SelectedSkillTree = skills::SkillGroupKind;
 
+/// How many skills of a data skill tree fit in each of the four rectangles
+const DATA_SKILLS_PER_RECTANGLE: usize = 9;
+
 const TREES: [&str; 8] = [
     "General Combat",
     "Sword",
@@ -247,6 +266,11 @@ pub enum Event {
     Close,
     ChangeSkillTree(SelectedSkillTree),
     UnlockSkill(Skill),
+    Respec(SelectedSkillTree),
+    SetPresetName(String),
+    SavePreset,
+    ApplyPreset(String),
+    DeletePreset(String),
 }
 
 impl<'a> Widget for Diary<'a> {
@@ -335,57 +359,76 @@ impl<'a> Widget for Diary<'a> {
         // Skill Trees
 
         // Skill Tree Selection
+        // The trees declared in the data skill trees come after the ones in code
+        let trees = TREES
+            .iter()
+            .map(|name| (name.to_string(), skill_tree_from_str(name)))
+            .chain(
+                DATA_SKILL_TREES
+                    .sorted_groups()
+                    .into_iter()
+                    .map(|group| {
+                        (
+                            self.localized_strings.get(&group.def().title).to_owned(),
+                            Some(SelectedSkillTree::Data(group)),
+                        )
+                    }),
+            )
+            .collect::<Vec<_>>();
         state.update(|s| {
             s.weapon_btns
-                .resize(TREES.len(), &mut ui.widget_id_generator())
+                .resize(trees.len(), &mut ui.widget_id_generator())
         });
         state.update(|s| {
             s.weapon_imgs
-                .resize(TREES.len(), &mut ui.widget_id_generator())
+                .resize(trees.len(), &mut ui.widget_id_generator())
         });
         state.update(|s| {
             s.lock_imgs
-                .resize(TREES.len(), &mut ui.widget_id_generator())
+                .resize(trees.len(), &mut ui.widget_id_generator())
         });
-        for i in TREES.iter().copied().enumerate() {
-            let locked = !skill_tree_from_str(i.1)
-                .map_or(false, |st| self.skill_set.contains_skill_group(st));
+        for (i, (name, tree)) in trees.iter().enumerate() {
+            let locked = !tree.map_or(false, |st| {
+                // Data skill trees are available to everyone, even before they gain experience
+                matches!(st, SelectedSkillTree::Data(_)) || self.skill_set.contains_skill_group(st)
+            });
 
             // Background weapon image
-            let img = Image::new(match i.1 {
-                "General Combat" => self.imgs.swords_crossed,
-                "Sword" => self.imgs.sword,
-                "Hammer" => self.imgs.hammer,
-                "Axe" => self.imgs.axe,
-                "Sceptre" => self.imgs.sceptre,
-                "Bow" => self.imgs.bow,
-                "Fire Staff" => self.imgs.staff,
-                "Mining" => self.imgs.mining,
+            let img = Image::new(match (name.as_str(), tree) {
+                ("General Combat", _) => self.imgs.swords_crossed,
+                ("Sword", _) => self.imgs.sword,
+                ("Hammer", _) => self.imgs.hammer,
+                ("Axe", _) => self.imgs.axe,
+                ("Sceptre", _) => self.imgs.sceptre,
+                ("Bow", _) => self.imgs.bow,
+                ("Fire Staff", _) => self.imgs.staff,
+                ("Mining", _) => self.imgs.mining,
+                (_, Some(SelectedSkillTree::Data(_))) => self.imgs.spellbook_button,
                 _ => self.imgs.nothing,
             });
 
-            let img = if i.0 == 0 {
+            let img = if i == 0 {
                 img.top_left_with_margins_on(state.content_align, 10.0, 5.0)
             } else {
-                img.down_from(state.weapon_btns[i.0 - 1], 5.0)
+                img.down_from(state.weapon_btns[i - 1], 5.0)
             };
             let tooltip_txt = if !locked {
                 ""
             } else {
                 &self.localized_strings.get("hud.skill.not_unlocked")
             };
-            img.w_h(50.0, 50.0).set(state.weapon_imgs[i.0], ui);
+            img.w_h(50.0, 50.0).set(state.weapon_imgs[i], ui);
             // Lock Image
             if locked {
                 Image::new(self.imgs.lock)
                     .w_h(50.0, 50.0)
-                    .middle_of(state.weapon_imgs[i.0])
-                    .graphics_for(state.weapon_imgs[i.0])
+                    .middle_of(state.weapon_imgs[i])
+                    .graphics_for(state.weapon_imgs[i])
                     .color(Some(Color::Rgba(1.0, 1.0, 1.0, 0.8)))
-                    .set(state.lock_imgs[i.0], ui);
+                    .set(state.lock_imgs[i], ui);
             }
             // Weapon icons
-            let available_pts = skill_tree_from_str(i.1)
+            let available_pts = tree
                 .map(|st| {
                     (
                         st,
@@ -396,27 +439,25 @@ impl<'a> Widget for Diary<'a> {
                 .map_or(false, |(st, a_pts, e_pts)| {
                     a_pts > 0 && (e_pts - a_pts) < st.total_skill_point_cost()
                 });
-            if Button::image(
-                if skill_tree_from_str(i.1).map_or(false, |st| st == *sel_tab || available_pts) {
-                    self.imgs.wpn_icon_border_pressed
-                } else {
-                    self.imgs.wpn_icon_border
-                },
-            )
+            if Button::image(if tree.map_or(false, |st| st == *sel_tab || available_pts) {
+                self.imgs.wpn_icon_border_pressed
+            } else {
+                self.imgs.wpn_icon_border
+            })
             .w_h(50.0, 50.0)
-            .hover_image(match skill_tree_from_str(i.1).map(|st| st == *sel_tab) {
+            .hover_image(match tree.map(|st| st == *sel_tab) {
                 Some(true) => self.imgs.wpn_icon_border_pressed,
                 Some(false) => self.imgs.wpn_icon_border_mo,
                 None => self.imgs.wpn_icon_border,
             })
-            .press_image(match skill_tree_from_str(i.1).map(|st| st == *sel_tab) {
+            .press_image(match tree.map(|st| st == *sel_tab) {
                 Some(true) => self.imgs.wpn_icon_border_pressed,
                 Some(false) => self.imgs.wpn_icon_border_press,
                 None => self.imgs.wpn_icon_border,
             })
-            .middle_of(state.weapon_imgs[i.0])
+            .middle_of(state.weapon_imgs[i])
             .image_color(
-                if skill_tree_from_str(i.1).map_or(false, |st| st != *sel_tab && available_pts) {
+                if tree.map_or(false, |st| st != *sel_tab && available_pts) {
                     Color::Rgba(0.92, 0.76, 0.0, frame_ani)
                 } else {
                     TEXT_COLOR
@@ -424,15 +465,15 @@ impl<'a> Widget for Diary<'a> {
             )
             .with_tooltip(
                 self.tooltip_manager,
-                i.1,
+                name,
                 &tooltip_txt,
                 &diary_tooltip,
                 TEXT_COLOR,
             )
-            .set(state.weapon_btns[i.0], ui)
+            .set(state.weapon_btns[i], ui)
             .was_clicked()
             {
-                events.push(skill_tree_from_str(i.1).map_or(Event::Close, Event::ChangeSkillTree))
+                events.push(tree.map_or(Event::Close, Event::ChangeSkillTree))
             }
         }
         // Exp Bars and Rank Display
@@ -473,6 +514,30 @@ impl<'a> Widget for Diary<'a> {
         }
         self.create_new_text(&rank_txt, state.exp_bar_frame, 5.0, 28, TEXT_COLOR)
             .set(state.exp_bar_rank, ui);
+        // Respec
+        if self.skill_set.can_respec_skill_group(*sel_tab)
+            && Button::image(self.imgs.button)
+                .w_h(31.0 * 3.0, 12.0 * 2.0)
+                .hover_image(self.imgs.button_hover)
+                .press_image(self.imgs.button_press)
+                .right_from(state.exp_bar_bg, 10.0)
+                .label(&self.localized_strings.get("hud.diary.respec"))
+                .label_font_size(self.fonts.cyri.scale(14))
+                .label_color(TEXT_COLOR)
+                .label_font_id(self.fonts.cyri.conrod_id)
+                .label_y(Relative::Scalar(2.0))
+                .with_tooltip(
+                    self.tooltip_manager,
+                    &self.localized_strings.get("hud.diary.respec"),
+                    &self.localized_strings.get("hud.diary.respec_desc"),
+                    &diary_tooltip,
+                    TEXT_COLOR,
+                )
+                .set(state.respec_btn, ui)
+                .was_clicked()
+        {
+            events.push(Event::Respec(*sel_tab));
+        }
         self.create_new_text(
             &self
                 .localized_strings
@@ -511,10 +576,162 @@ impl<'a> Widget for Diary<'a> {
             SelectedSkillTree::Weapon(ToolKind::Pick) => {
                 self.localized_strings.get("common.tool.mining")
             },
+            SelectedSkillTree::Data(group) => self.localized_strings.get(&group.def().title),
             _ => "Unknown",
         };
         self.create_new_text(&tree_title, state.content_align, 2.0, 34, TEXT_COLOR)
             .set(state.tree_title_txt, ui);
+        // Quest Log
+        let quest_log = self.client.current::<QuestLog>().unwrap_or_default();
+        Text::new(&self.localized_strings.get("hud.diary.quests"))
+            .top_right_with_margins_on(state.content_align, 10.0, 10.0)
+            .w(180.0)
+            .font_id(self.fonts.cyri.conrod_id)
+            .font_size(self.fonts.cyri.scale(22))
+            .color(TEXT_COLOR)
+            .set(state.quest_log_title, ui);
+        let quest_log_txt = if quest_log.active.is_empty() {
+            self.localized_strings
+                .get("hud.diary.no_quests")
+                .to_string()
+        } else {
+            quest_log
+                .active
+                .iter()
+                .map(|quest| {
+                    format!(
+                        "{} ({}/{})\n{}",
+                        quest.title(),
+                        quest.progress,
+                        quest.objective().required(),
+                        quest.description()
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n\n")
+        };
+        Text::new(&quest_log_txt)
+            .down_from(state.quest_log_title, 10.0)
+            .w(180.0)
+            .font_id(self.fonts.cyri.conrod_id)
+            .font_size(self.fonts.cyri.scale(13))
+            .color(TEXT_COLOR)
+            .set(state.quest_log_txt, ui);
+        Text::new(
+            &self
+                .localized_strings
+                .get("hud.diary.quests_completed")
+                .replace("{number}", &quest_log.completed.to_string()),
+        )
+        .down_from(state.quest_log_txt, 15.0)
+        .w(180.0)
+        .font_id(self.fonts.cyri.conrod_id)
+        .font_size(self.fonts.cyri.scale(13))
+        .color(TEXT_COLOR)
+        .set(state.quests_completed_txt, ui);
+        // Presets
+        let presets = self.client.current::<LoadoutPresets>().unwrap_or_default();
+        Text::new(&self.localized_strings.get("hud.diary.presets"))
+            .down_from(state.quests_completed_txt, 25.0)
+            .w(180.0)
+            .font_id(self.fonts.cyri.conrod_id)
+            .font_size(self.fonts.cyri.scale(22))
+            .color(TEXT_COLOR)
+            .set(state.presets_title, ui);
+        state.update(|s| {
+            s.preset_txts
+                .resize(presets.presets.len(), &mut ui.widget_id_generator());
+            s.preset_apply_btns
+                .resize(presets.presets.len(), &mut ui.widget_id_generator());
+            s.preset_delete_btns
+                .resize(presets.presets.len(), &mut ui.widget_id_generator());
+        });
+        if presets.presets.is_empty() {
+            Text::new(&self.localized_strings.get("hud.diary.no_presets"))
+                .down_from(state.presets_title, 10.0)
+                .w(180.0)
+                .font_id(self.fonts.cyri.conrod_id)
+                .font_size(self.fonts.cyri.scale(13))
+                .color(TEXT_COLOR)
+                .set(state.no_presets_txt, ui);
+        }
+        for (i, preset) in presets.presets.iter().enumerate() {
+            let txt = Text::new(&preset.name)
+                .w(80.0)
+                .font_id(self.fonts.cyri.conrod_id)
+                .font_size(self.fonts.cyri.scale(13))
+                .color(TEXT_COLOR);
+            if i == 0 {
+                txt.down_from(state.presets_title, 12.0)
+            } else {
+                txt.down_from(state.preset_txts[i - 1], 12.0)
+            }
+            .set(state.preset_txts[i], ui);
+            if Button::image(self.imgs.button)
+                .w_h(45.0, 20.0)
+                .hover_image(self.imgs.button_hover)
+                .press_image(self.imgs.button_press)
+                .right_from(state.preset_txts[i], 5.0)
+                .label(&self.localized_strings.get("hud.diary.preset_apply"))
+                .label_font_size(self.fonts.cyri.scale(11))
+                .label_color(TEXT_COLOR)
+                .label_font_id(self.fonts.cyri.conrod_id)
+                .label_y(Relative::Scalar(1.0))
+                .set(state.preset_apply_btns[i], ui)
+                .was_clicked()
+            {
+                events.push(Event::ApplyPreset(preset.name.clone()));
+            }
+            if Button::image(self.imgs.button)
+                .w_h(45.0, 20.0)
+                .hover_image(self.imgs.button_hover)
+                .press_image(self.imgs.button_press)
+                .right_from(state.preset_apply_btns[i], 5.0)
+                .label(&self.localized_strings.get("hud.diary.preset_delete"))
+                .label_font_size(self.fonts.cyri.scale(11))
+                .label_color(TEXT_COLOR)
+                .label_font_id(self.fonts.cyri.conrod_id)
+                .label_y(Relative::Scalar(1.0))
+                .set(state.preset_delete_btns[i], ui)
+                .was_clicked()
+            {
+                events.push(Event::DeletePreset(preset.name.clone()));
+            }
+        }
+        let preset_name_bg = Rectangle::fill([125.0, 22.0]).hsla(0.0, 0.0, 0.0, 0.7);
+        match state.preset_txts.last() {
+            Some(last) => preset_name_bg.down_from(*last, 15.0),
+            None => preset_name_bg.down_from(state.no_presets_txt, 15.0),
+        }
+        .set(state.preset_name_bg, ui);
+        if let Some(name) = TextEdit::new(&self.show.preset_name)
+            .middle_of(state.preset_name_bg)
+            .padded_w_of(state.preset_name_bg, 4.0)
+            .h(20.0)
+            .font_id(self.fonts.cyri.conrod_id)
+            .font_size(self.fonts.cyri.scale(13))
+            .color(TEXT_COLOR)
+            .set(state.preset_name, ui)
+        {
+            events.push(Event::SetPresetName(
+                name.chars().take(MAX_PRESET_NAME_LENGTH).collect(),
+            ));
+        }
+        if Button::image(self.imgs.button)
+            .w_h(50.0, 22.0)
+            .hover_image(self.imgs.button_hover)
+            .press_image(self.imgs.button_press)
+            .right_from(state.preset_name_bg, 5.0)
+            .label(&self.localized_strings.get("hud.diary.preset_save"))
+            .label_font_size(self.fonts.cyri.scale(11))
+            .label_color(TEXT_COLOR)
+            .label_font_id(self.fonts.cyri.conrod_id)
+            .label_y(Relative::Scalar(1.0))
+            .set(state.preset_save_btn, ui)
+            .was_clicked()
+        {
+            events.push(Event::SavePreset);
+        }
         // Skill Trees
         // Alignment Placing
         let x = 200.0;
@@ -532,6 +749,17 @@ impl<'a> Widget for Diary<'a> {
         Rectangle::fill_with([124.0 * 2.0, 124.0 * 2.0], color::TRANSPARENT)
             .bottom_right_with_margins_on(state.content_align, y, x)
             .set(state.skills_bot_r_align, ui);
+        // Skills of data skill trees fill the rectangles in order
+        let data_skills = match sel_tab {
+            SelectedSkillTree::Data(group) => group.def().skills.as_slice(),
+            _ => &[][..],
+        };
+        let data_skills_in = |rectangle: usize| {
+            data_skills
+                .len()
+                .saturating_sub(rectangle * DATA_SKILLS_PER_RECTANGLE)
+                .min(DATA_SKILLS_PER_RECTANGLE)
+        };
         // Number of skills per rectangle per weapon, start counting at 0
         // Maximum of 9 skills/8 indices
         let skills_top_l = match sel_tab {
@@ -543,7 +771,7 @@ impl<'a> Widget for Diary<'a> {
             SelectedSkillTree::Weapon(ToolKind::Staff) => 4,
             SelectedSkillTree::Weapon(ToolKind::Sceptre) => 5,
             SelectedSkillTree::Weapon(ToolKind::Pick) => 4,
-            _ => 0,
+            _ => data_skills_in(0),
         };
         let skills_top_r = match sel_tab {
             SelectedSkillTree::General => 6,
@@ -553,7 +781,7 @@ impl<'a> Widget for Diary<'a> {
             SelectedSkillTree::Weapon(ToolKind::Bow) => 4,
             SelectedSkillTree::Weapon(ToolKind::Staff) => 5,
             SelectedSkillTree::Weapon(ToolKind::Sceptre) => 4,
-            _ => 0,
+            _ => data_skills_in(1),
         };
         let skills_bot_l = match sel_tab {
             SelectedSkillTree::General => 4,
@@ -563,13 +791,13 @@ impl<'a> Widget for Diary<'a> {
             SelectedSkillTree::Weapon(ToolKind::Bow) => 5,
             SelectedSkillTree::Weapon(ToolKind::Staff) => 5,
             SelectedSkillTree::Weapon(ToolKind::Sceptre) => 5,
-            _ => 0,
+            _ => data_skills_in(2),
         };
         let skills_bot_r = match sel_tab {
             SelectedSkillTree::General => 5,
             SelectedSkillTree::Weapon(ToolKind::Sword) => 1,
             SelectedSkillTree::Weapon(ToolKind::Bow) => 1,
-            _ => 0,
+            _ => data_skills_in(3),
         };
         // Update widget id array len
         state.update(|s| {
@@ -588,6 +816,10 @@ impl<'a> Widget for Diary<'a> {
             s.skills_bot_r
                 .resize(skills_bot_r, &mut ui.widget_id_generator())
         });
+        state.update(|s| {
+            s.data_skills
+                .resize(data_skills.len(), &mut ui.widget_id_generator())
+        });
         // Create Background Images to place skill icons on them later
         // Create central skill first, others around it:
         //
@@ -2047,6 +2279,47 @@ impl<'a> Widget for Diary<'a> {
                     &diary_tooltip,
                 );
             },
+            SelectedSkillTree::Data(_) => {
+                let rectangles = [
+                    &state.skills_top_l,
+                    &state.skills_top_r,
+                    &state.skills_bot_l,
+                    &state.skills_bot_r,
+                ];
+                for (i, data_skill) in data_skills
+                    .iter()
+                    .enumerate()
+                    .take(rectangles.len() * DATA_SKILLS_PER_RECTANGLE)
+                {
+                    let def = data_skill.def();
+                    let skill = Skill::Data(*data_skill);
+                    if create_skill_button(
+                        self.imgs.spellbook_button,
+                        rectangles[i / DATA_SKILLS_PER_RECTANGLE][i % DATA_SKILLS_PER_RECTANGLE],
+                        &self.skill_set,
+                        skill,
+                        self.fonts,
+                        &get_skill_label(skill, &self.skill_set),
+                    )
+                    .with_tooltip(
+                        self.tooltip_manager,
+                        &self.localized_strings.get(&def.title),
+                        &add_sp_cost_tooltip(
+                            &self.localized_strings.get(&def.desc),
+                            skill,
+                            &self.skill_set,
+                            &self.localized_strings,
+                        ),
+                        &diary_tooltip,
+                        TEXT_COLOR,
+                    )
+                    .set(state.data_skills[i], ui)
+                    .was_clicked()
+                    {
+                        events.push(Event::UnlockSkill(skill));
+                    }
+                }
+            },
             _ => {},
         }
 
@@ -0,0 +1,351 @@
+use super::{img_ids::Imgs, TEXT_COLOR, TEXT_GRAY_COLOR, UI_HIGHLIGHT_0, UI_MAIN};
+use crate::{i18n::Localization, ui::fonts::Fonts};
+use client::Client;
+use common::{
+    comp::inventory::slot::InvSlotId,
+    mail::{Mail, MailId, MAX_ATTACHMENTS, MAX_MESSAGE_LENGTH},
+};
+use conrod_core::{
+    color,
+    position::{Dimension, Place, Relative},
+    widget::{self, Button, Image, List, Rectangle, Text, TextEdit},
+    widget_ids, Color, Colorable, Labelable, Positionable, Sizeable, Widget, WidgetCommon,
+};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+widget_ids! {
+    pub struct Ids {
+        bg,
+        bg_frame,
+        close,
+        title,
+        title_bg,
+        letters_title,
+        letters_align,
+        letters,
+        no_letters,
+        letter_texts[],
+        claim_buttons[],
+        recipient_label,
+        recipient_bg,
+        recipient,
+        message_bg,
+        message,
+        attachments_title,
+        attachments_hint,
+        attachments[],
+        send_button,
+    }
+}
+
+/// The letter being written at a mailbox
+#[derive(Default)]
+pub struct MailDraft {
+    pub recipient: String,
+    pub message: String,
+    pub attachments: Vec<(InvSlotId, u32)>,
+}
+
+impl MailDraft {
+    /// Attaches the items in an inventory slot, unless they already are
+    pub fn attach(&mut self, slot: InvSlotId, amount: u32) {
+        if self.attachments.len() < MAX_ATTACHMENTS
+            && !self.attachments.iter().any(|(s, _)| *s == slot)
+        {
+            self.attachments.push((slot, amount));
+        }
+    }
+}
+
+pub enum Event {
+    Close,
+    SetRecipient(String),
+    SetMessage(String),
+    Detach(usize),
+    Send,
+    Claim(MailId),
+}
+
+#[derive(WidgetCommon)]
+pub struct Mailbox<'a> {
+    client: &'a Client,
+    imgs: &'a Imgs,
+    fonts: &'a Fonts,
+    #[conrod(common_builder)]
+    common: widget::CommonBuilder,
+    localized_strings: &'a Localization,
+    draft: &'a MailDraft,
+}
+
+impl<'a> Mailbox<'a> {
+    pub fn new(
+        client: &'a Client,
+        imgs: &'a Imgs,
+        fonts: &'a Fonts,
+        localized_strings: &'a Localization,
+        draft: &'a MailDraft,
+    ) -> Self {
+        Self {
+            client,
+            imgs,
+            fonts,
+            common: widget::CommonBuilder::default(),
+            localized_strings,
+            draft,
+        }
+    }
+
+    /// Describes a letter in the list of letters
+    fn describe(&self, mail: &Mail, now: u64) -> String {
+        let i18n = &self.localized_strings;
+        let mut text = if mail.returned {
+            i18n.get("hud.mail.returned_from")
+                .replace("{name}", &mail.sender_alias)
+        } else {
+            i18n.get("hud.mail.from").replace("{name}", &mail.sender_alias)
+        };
+        if !mail.message.is_empty() {
+            text.push('\n');
+            text.push_str(&mail.message);
+        }
+        for item in mail.attachments.slots().flatten() {
+            text.push_str(&format!("\n  {}x {}", item.amount(), item.name()));
+        }
+        if let Some(expires_at) = mail.expires_at() {
+            let days = (expires_at.saturating_sub(now) + 86399) / 86400;
+            text.push('\n');
+            text.push_str(
+                &i18n
+                    .get("hud.mail.returns_in")
+                    .replace("{days}", &days.to_string()),
+            );
+        }
+        text
+    }
+}
+
+impl<'a> Widget for Mailbox<'a> {
+    type Event = Vec<Event>;
+    type State = Ids;
+    type Style = ();
+
+    fn init_state(&self, id_gen: widget::id::Generator) -> Self::State { Ids::new(id_gen) }
+
+    fn style(&self) -> Self::Style {}
+
+    fn update(self, args: widget::UpdateArgs<Self>) -> Self::Event {
+        common_base::prof_span!("Mailbox::update");
+        let widget::UpdateArgs { state, ui, .. } = args;
+        let i18n = &self.localized_strings;
+        let mut events = Vec::new();
+
+        let letters = self.client.mail();
+        if state.letter_texts.len() < letters.len() {
+            state.update(|s| {
+                s.letter_texts
+                    .resize(letters.len(), &mut ui.widget_id_generator());
+                s.claim_buttons
+                    .resize(letters.len(), &mut ui.widget_id_generator());
+            });
+        }
+        if state.attachments.len() < MAX_ATTACHMENTS {
+            state.update(|s| {
+                s.attachments
+                    .resize(MAX_ATTACHMENTS, &mut ui.widget_id_generator());
+            });
+        }
+
+        // Background
+        Image::new(self.imgs.inv_bg_bag)
+            .w_h(424.0, 708.0)
+            .bottom_left_with_margins_on(ui.window, 60.0, 5.0)
+            .color(Some(UI_MAIN))
+            .set(state.bg, ui);
+        Image::new(self.imgs.inv_frame_bag)
+            .w_h(424.0, 708.0)
+            .middle_of(state.bg)
+            .color(Some(UI_HIGHLIGHT_0))
+            .set(state.bg_frame, ui);
+        if Button::image(self.imgs.close_btn)
+            .w_h(24.0, 25.0)
+            .hover_image(self.imgs.close_btn_hover)
+            .press_image(self.imgs.close_btn_press)
+            .top_right_with_margins_on(state.bg, 0.0, 0.0)
+            .set(state.close, ui)
+            .was_clicked()
+        {
+            events.push(Event::Close);
+        }
+
+        // Title
+        Text::new(&i18n.get("hud.mail.mailbox"))
+            .mid_top_with_margin_on(state.bg_frame, 9.0)
+            .font_id(self.fonts.cyri.conrod_id)
+            .font_size(self.fonts.cyri.scale(20))
+            .color(Color::Rgba(0.0, 0.0, 0.0, 1.0))
+            .set(state.title_bg, ui);
+        Text::new(&i18n.get("hud.mail.mailbox"))
+            .top_left_with_margins_on(state.title_bg, 2.0, 2.0)
+            .font_id(self.fonts.cyri.conrod_id)
+            .font_size(self.fonts.cyri.scale(20))
+            .color(TEXT_COLOR)
+            .set(state.title, ui);
+
+        // Letters waiting to be collected
+        Text::new(&i18n.get("hud.mail.letters"))
+            .top_left_with_margins_on(state.bg_frame, 50.0, 22.0)
+            .font_id(self.fonts.cyri.conrod_id)
+            .font_size(self.fonts.cyri.scale(16))
+            .color(TEXT_COLOR)
+            .set(state.letters_title, ui);
+        Rectangle::fill_with([380.0, 280.0], color::TRANSPARENT)
+            .down_from(state.letters_title, 8.0)
+            .set(state.letters_align, ui);
+        if letters.is_empty() {
+            Text::new(&i18n.get("hud.mail.no_letters"))
+                .top_left_with_margins_on(state.letters_align, 0.0, 0.0)
+                .font_id(self.fonts.cyri.conrod_id)
+                .font_size(self.fonts.cyri.scale(14))
+                .color(TEXT_GRAY_COLOR)
+                .set(state.no_letters, ui);
+        } else {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |duration| duration.as_secs());
+            let (mut items, _) = List::flow_down(letters.len())
+                .middle_of(state.letters_align)
+                .w_h(380.0, 280.0)
+                .scroll_kids_vertically()
+                .set(state.letters, ui);
+            while let Some(item) = items.next(ui) {
+                let letter = &letters[item.i];
+                let text = Text::new(&self.describe(letter, now))
+                    .font_id(self.fonts.cyri.conrod_id)
+                    .font_size(self.fonts.cyri.scale(14))
+                    .color(TEXT_COLOR)
+                    .w(280.0)
+                    .line_spacing(2.0);
+                let height = match text.get_y_dimension(ui) {
+                    Dimension::Absolute(y) => y + 10.0,
+                    _ => 0.0,
+                };
+                item.set(text.h(height.max(34.0)), ui);
+                if Button::image(self.imgs.button)
+                    .w_h(90.0, 24.0)
+                    .hover_image(self.imgs.button_hover)
+                    .press_image(self.imgs.button_press)
+                    .top_right_with_margins_on(item.widget_id, 0.0, -95.0)
+                    .label(&i18n.get("hud.mail.collect"))
+                    .label_font_size(self.fonts.cyri.scale(12))
+                    .label_color(TEXT_COLOR)
+                    .label_font_id(self.fonts.cyri.conrod_id)
+                    .label_y(Relative::Scalar(2.0))
+                    .parent(state.letters_align)
+                    .set(state.claim_buttons[item.i], ui)
+                    .was_clicked()
+                {
+                    events.push(Event::Claim(letter.id));
+                }
+            }
+        }
+
+        // The letter being written
+        Text::new(&i18n.get("hud.mail.to"))
+            .top_left_with_margins_on(state.bg_frame, 375.0, 22.0)
+            .font_id(self.fonts.cyri.conrod_id)
+            .font_size(self.fonts.cyri.scale(14))
+            .color(TEXT_COLOR)
+            .set(state.recipient_label, ui);
+        Rectangle::fill([320.0, 22.0])
+            .right_from(state.recipient_label, 10.0)
+            .hsla(0.0, 0.0, 0.0, 0.7)
+            .set(state.recipient_bg, ui);
+        if let Some(recipient) = TextEdit::new(&self.draft.recipient)
+            .middle_of(state.recipient_bg)
+            .padded_w_of(state.recipient_bg, 4.0)
+            .h(20.0)
+            .font_id(self.fonts.cyri.conrod_id)
+            .font_size(self.fonts.cyri.scale(14))
+            .color(TEXT_COLOR)
+            .set(state.recipient, ui)
+        {
+            events.push(Event::SetRecipient(recipient));
+        }
+        Rectangle::fill([380.0, 100.0])
+            .top_left_with_margins_on(state.bg_frame, 405.0, 22.0)
+            .hsla(0.0, 0.0, 0.0, 0.7)
+            .set(state.message_bg, ui);
+        if let Some(message) = TextEdit::new(&self.draft.message)
+            .top_left_with_margins_on(state.message_bg, 2.0, 4.0)
+            .w_h(372.0, 96.0)
+            .wrap_by_word()
+            .font_id(self.fonts.cyri.conrod_id)
+            .font_size(self.fonts.cyri.scale(14))
+            .color(TEXT_COLOR)
+            .set(state.message, ui)
+        {
+            events.push(Event::SetMessage(
+                message.chars().take(MAX_MESSAGE_LENGTH).collect(),
+            ));
+        }
+
+        Text::new(&i18n.get("hud.mail.attachments"))
+            .down_from(state.message_bg, 10.0)
+            .font_id(self.fonts.cyri.conrod_id)
+            .font_size(self.fonts.cyri.scale(14))
+            .color(TEXT_COLOR)
+            .set(state.attachments_title, ui);
+        if self.draft.attachments.is_empty() {
+            Text::new(&i18n.get("hud.mail.attach_hint"))
+                .down_from(state.attachments_title, 6.0)
+                .font_id(self.fonts.cyri.conrod_id)
+                .font_size(self.fonts.cyri.scale(12))
+                .color(TEXT_GRAY_COLOR)
+                .set(state.attachments_hint, ui);
+        }
+        let inventories = self.client.inventories();
+        let inventory = inventories.get(self.client.entity());
+        for (i, (slot, amount)) in self.draft.attachments.iter().enumerate() {
+            let name = inventory
+                .and_then(|inventory| inventory.get(*slot))
+                .map_or("?", |item| item.name());
+            // Two columns of attachments, clicking one removes it
+            let button = Button::new()
+                .w_h(185.0, 20.0)
+                .color(color::TRANSPARENT)
+                .label(&format!("{}x {}", amount, name))
+                .label_font_size(self.fonts.cyri.scale(12))
+                .label_color(TEXT_COLOR)
+                .label_font_id(self.fonts.cyri.conrod_id)
+                .label_x(Relative::Place(Place::Start(None)));
+            let button = if i == 0 {
+                button.down_from(state.attachments_title, 6.0)
+            } else if i % 2 == 1 {
+                button.right_from(state.attachments[i - 1], 10.0)
+            } else {
+                button.down_from(state.attachments[i - 2], 2.0)
+            };
+            if button.set(state.attachments[i], ui).was_clicked() {
+                events.push(Event::Detach(i));
+            }
+        }
+
+        if Button::image(self.imgs.button)
+            .w_h(31.0 * 5.0, 12.0 * 2.0)
+            .hover_image(self.imgs.button_hover)
+            .press_image(self.imgs.button_press)
+            .mid_bottom_with_margin_on(state.bg_frame, 60.0)
+            .label(&i18n.get("hud.mail.send"))
+            .label_font_size(self.fonts.cyri.scale(14))
+            .label_color(TEXT_COLOR)
+            .label_font_id(self.fonts.cyri.conrod_id)
+            .label_y(Relative::Scalar(2.0))
+            .set(state.send_button, ui)
+            .was_clicked()
+        {
+            events.push(Event::Send);
+        }
+
+        events
+    }
+}
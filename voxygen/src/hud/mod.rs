@@ -3,6 +3,7 @@ mod buffs;
 mod buttons;
 mod chat;
 mod crafting;
+mod damage_meter;
 mod diary;
 mod esc_menu;
 mod group;
@@ -10,6 +11,7 @@ mod hotbar;
 pub mod img_ids;
 pub mod item_imgs;
 mod loot_scroller;
+mod mailbox;
 mod map;
 mod minimap;
 mod overhead;
@@ -20,6 +22,7 @@ mod settings_window;
 mod skillbar;
 mod slots;
 mod social;
+mod storage;
 mod trade;
 pub mod util;
 
@@ -35,12 +38,14 @@ use buttons::Buttons;
 use chat::Chat;
 use chrono::NaiveTime;
 use crafting::Crafting;
+use damage_meter::{DamageMeter, MeterData, MeterMode};
 use diary::{Diary, SelectedSkillTree};
 use esc_menu::EscMenu;
 use group::Group;
 use img_ids::Imgs;
 use item_imgs::ItemImgs;
 use loot_scroller::LootScroller;
+use mailbox::{MailDraft, Mailbox};
 use map::Map;
 use minimap::{MiniMap, VoxelMinimap};
 use popup::Popup;
@@ -48,7 +53,8 @@ use prompt_dialog::PromptDialog;
 use serde::{Deserialize, Serialize};
 use settings_window::{SettingsTab, SettingsWindow};
 use skillbar::Skillbar;
-use social::Social;
+use social::{Social, SocialTab};
+use storage::Storage;
 use trade::Trade;
 
 use crate::{
@@ -72,6 +78,7 @@ use crate::{
 use client::Client;
 use common::{
     combat,
+    combat_log::CombatLogEntry,
     comp::{
         self,
         inventory::trade_pricing::TradePricing,
@@ -80,6 +87,7 @@ use common::{
         BuffData, BuffKind, Item,
     },
     consts::MAX_PICKUP_RANGE,
+    mail::MailId,
     outcome::Outcome,
     slowjob::SlowJobPool,
     terrain::{SpriteKind, TerrainChunk},
@@ -160,10 +168,14 @@ const SAY_COLOR: Color = Color::Rgba(1.0, 0.8, 0.8, 1.0);
 const GROUP_COLOR: Color = Color::Rgba(0.47, 0.84, 1.0, 1.0);
 /// Color for factional chat
 const FACTION_COLOR: Color = Color::Rgba(0.24, 1.0, 0.48, 1.0);
+/// Color for guild chat
+const GUILD_COLOR: Color = Color::Rgba(1.0, 0.82, 0.36, 1.0);
 /// Color for regional chat
 const REGION_COLOR: Color = Color::Rgba(0.8, 1.0, 0.8, 1.0);
 /// Color for death messagesw
 const KILL_COLOR: Color = Color::Rgba(1.0, 0.17, 0.17, 1.0);
+/// Color for the combat log
+const COMBAT_LOG_COLOR: Color = Color::Rgba(1.0, 0.62, 0.42, 1.0);
 /// Color for global messages
 const WORLD_COLOR: Color = Color::Rgba(0.95, 1.0, 0.95, 1.0);
 
@@ -273,6 +285,9 @@ widget_ids! {
         prompt_dialog,
         bag,
         trade,
+        storage,
+        mailbox,
+        damage_meter,
         social,
         quest,
         diary,
@@ -398,6 +413,17 @@ pub enum Event {
     SortInventory,
     ChangeHotbarState(Box<HotbarState>),
     TradeAction(TradeAction),
+    CloseStorage,
+    DepositItem(comp::slot::InvSlotId),
+    WithdrawItem(comp::slot::InvSlotId),
+    CloseMailbox,
+    SendMail {
+        recipient: String,
+        message: String,
+        attachments: Vec<(comp::slot::InvSlotId, u32)>,
+    },
+    ClaimMail(MailId),
+    RequestCombatLog(bool),
     Ability3(bool),
     Ability4(bool),
     Logout,
@@ -406,6 +432,17 @@ pub enum Event {
     CraftRecipe {
         recipe: String,
         craft_sprite: Option<(Vec3<i32>, SpriteKind)>,
+        amount: u32,
+        improve: bool,
+    },
+    CancelCrafting,
+    RepairItem {
+        slot: comp::slot::Slot,
+        craft_sprite: Option<(Vec3<i32>, SpriteKind)>,
+    },
+    SocketGem {
+        gem: comp::slot::InvSlotId,
+        slot: comp::slot::Slot,
     },
     InviteMember(Uid),
     AcceptInvite,
@@ -413,8 +450,11 @@ pub enum Event {
     KickMember(Uid),
     LeaveGroup,
     AssignLeader(Uid),
+    InviteToGuild(Uid),
+    ManageGuild(comp::GuildManip),
     RemoveBuff(BuffKind),
     UnlockSkill(Skill),
+    ManageBuild(comp::BuildManip),
     RequestSiteInfo(SiteId),
 
     SettingsChange(SettingsChange),
@@ -519,6 +559,8 @@ pub struct Show {
     bag: bool,
     bag_inv: bool,
     trade: bool,
+    storage: bool,
+    mailbox: bool,
     social: bool,
     diary: bool,
     group: bool,
@@ -533,7 +575,12 @@ pub struct Show {
     crafting_tab: CraftingTab,
     crafting_search_key: Option<String>,
     craft_sprite: Option<(Vec3<i32>, SpriteKind)>,
+    mail_draft: MailDraft,
+    preset_name: String,
+    damage_meter: bool,
+    meter_mode: MeterMode,
     social_search_key: Option<String>,
+    social_tab: SocialTab,
     want_grab: bool,
     stats: bool,
     free_look: bool,
@@ -565,6 +612,24 @@ impl Show {
         }
     }
 
+    fn storage(&mut self, open: bool) {
+        if !self.esc_menu {
+            self.bag = open;
+            self.storage = open;
+            self.map = false;
+            self.want_grab = !open;
+        }
+    }
+
+    fn mailbox(&mut self, open: bool) {
+        if !self.esc_menu {
+            self.bag = open;
+            self.mailbox = open;
+            self.map = false;
+            self.want_grab = !open;
+        }
+    }
+
     fn map(&mut self, open: bool) {
         if !self.esc_menu {
             self.map = open;
@@ -641,6 +706,10 @@ impl Show {
 
     fn toggle_trade(&mut self) { self.trade(!self.trade); }
 
+    fn toggle_storage(&mut self) { self.storage(!self.storage); }
+
+    fn toggle_mailbox(&mut self) { self.mailbox(!self.mailbox); }
+
     fn toggle_map(&mut self) { self.map(!self.map) }
 
     fn toggle_social(&mut self) { self.social(!self.social); }
@@ -676,6 +745,8 @@ impl Show {
     fn toggle_windows(&mut self, global_state: &mut GlobalState) {
         if self.bag
             || self.trade
+            || self.storage
+            || self.mailbox
             || self.esc_menu
             || self.map
             || self.social
@@ -687,6 +758,8 @@ impl Show {
         {
             self.bag = false;
             self.trade = false;
+            self.storage = false;
+            self.mailbox = false;
             self.esc_menu = false;
             self.help = false;
             self.intro = false;
@@ -735,11 +808,15 @@ impl Show {
         self.social_search_key = search_key;
     }
 
+    fn social_tab(&mut self, tab: SocialTab) { self.social_tab = tab; }
+
     /// If all of the menus are closed, adjusts coordinates of cursor to center
     /// of screen
     fn toggle_cursor_on_menu_close(&self, global_state: &mut GlobalState, ui: &mut Ui) {
         if !self.bag
             && !self.trade
+            && !self.storage
+            && !self.mailbox
             && !self.esc_menu
             && !self.map
             && !self.social
@@ -798,6 +875,10 @@ pub struct Hud {
     failed_entity_pickups: HashMap<EcsEntity, f32>,
     new_loot_messages: VecDeque<LootMessage>,
     new_messages: VecDeque<comp::ChatMsg>,
+    new_combat_log: VecDeque<CombatLogEntry>,
+    combat_meter: MeterData,
+    /// Whether the server was asked to send the combat log
+    combat_log_requested: bool,
     new_notifications: VecDeque<Notification>,
     speech_bubbles: HashMap<Uid, comp::SpeechBubble>,
     pub show: Show,
@@ -884,6 +965,9 @@ impl Hud {
             failed_entity_pickups: HashMap::default(),
             new_loot_messages: VecDeque::new(),
             new_messages: VecDeque::new(),
+            new_combat_log: VecDeque::new(),
+            combat_meter: MeterData::default(),
+            combat_log_requested: false,
             new_notifications: VecDeque::new(),
             speech_bubbles: HashMap::new(),
             //intro: false,
@@ -895,6 +979,8 @@ impl Hud {
                 bag: false,
                 bag_inv: false,
                 trade: false,
+                storage: false,
+                mailbox: false,
                 esc_menu: false,
                 open_windows: Windows::None,
                 map: false,
@@ -910,7 +996,12 @@ impl Hud {
                 crafting_tab: CraftingTab::All,
                 crafting_search_key: None,
                 craft_sprite: None,
+                mail_draft: MailDraft::default(),
+                preset_name: String::new(),
+                damage_meter: false,
+                meter_mode: MeterMode::default(),
                 social_search_key: None,
+                social_tab: SocialTab::Online,
                 want_grab: true,
                 ingame: true,
                 stats: false,
@@ -1004,6 +1095,16 @@ impl Hud {
                 self.show.toggle_trade();
             }
 
+            if (client.storage().is_some() && !self.show.storage)
+                || (client.storage().is_none() && self.show.storage)
+            {
+                self.show.toggle_storage();
+            }
+
+            if client.mailbox().is_some() != self.show.mailbox {
+                self.show.toggle_mailbox();
+            }
+
             //self.input = client.read_storage::<comp::ControllerInputs>();
             if let Some(health) = healths.get(me) {
                 // Hurt Frame
@@ -1378,6 +1479,7 @@ impl Hud {
                             Weapon(ToolKind::Bow) => &i18n.get("common.weapons.bow"),
                             Weapon(ToolKind::Staff) => &i18n.get("common.weapons.staff"),
                             Weapon(ToolKind::Pick) => &i18n.get("common.tool.mining"),
+                            SkillGroupKind::Data(group) => &i18n.get(&group.def().title),
                             _ => "Unknown",
                         };
                         Text::new(skill)
@@ -1403,6 +1505,7 @@ impl Hud {
                             Weapon(ToolKind::Bow) => self.imgs.bow,
                             Weapon(ToolKind::Staff) => self.imgs.staff,
                             Weapon(ToolKind::Pick) => self.imgs.mining,
+                            SkillGroupKind::Data(_) => self.imgs.spellbook_button,
                             _ => self.imgs.swords_crossed,
                         })
                         .w_h(20.0, 20.0)
@@ -1668,6 +1771,10 @@ impl Hud {
 
                         let info = display_overhead_info.then(|| overhead::Info {
                             name: &stats.name,
+                            guild_tag: player
+                                .and_then(|_| client.player_list().get(uid))
+                                .and_then(|player_info| player_info.character.as_ref())
+                                .and_then(|character| character.guild_tag.as_deref()),
                             health,
                             buffs,
                             energy,
@@ -2621,6 +2728,108 @@ impl Hud {
             }
         }
 
+        // Storage window
+        if self.show.storage {
+            if let Some(storage::Event::Close) = Storage::new(
+                client,
+                &self.imgs,
+                &self.item_imgs,
+                &self.fonts,
+                &self.rot_imgs,
+                item_tooltip_manager,
+                &mut self.slot_manager,
+                i18n,
+                &msm,
+                self.pulse,
+            )
+            .set(self.ids.storage, ui_widgets)
+            {
+                self.show.storage(false);
+                events.push(Event::CloseStorage);
+            }
+        }
+
+        // Mailbox window
+        if self.show.mailbox {
+            for event in Mailbox::new(client, &self.imgs, &self.fonts, i18n, &self.show.mail_draft)
+                .set(self.ids.mailbox, ui_widgets)
+            {
+                match event {
+                    mailbox::Event::Close => {
+                        self.show.mailbox(false);
+                        events.push(Event::CloseMailbox);
+                    },
+                    mailbox::Event::SetRecipient(recipient) => {
+                        self.show.mail_draft.recipient = recipient;
+                    },
+                    mailbox::Event::SetMessage(message) => {
+                        self.show.mail_draft.message = message;
+                    },
+                    mailbox::Event::Detach(i) => {
+                        if i < self.show.mail_draft.attachments.len() {
+                            self.show.mail_draft.attachments.remove(i);
+                        }
+                    },
+                    mailbox::Event::Send => {
+                        let draft = std::mem::take(&mut self.show.mail_draft);
+                        events.push(Event::SendMail {
+                            recipient: draft.recipient,
+                            message: draft.message,
+                            attachments: draft.attachments,
+                        });
+                    },
+                    mailbox::Event::Claim(mail) => events.push(Event::ClaimMail(mail)),
+                }
+            }
+        }
+
+        // Combat log
+        let shows_combat_log = global_state
+            .settings
+            .chat
+            .chat_tabs
+            .iter()
+            .any(|tab| tab.filter.combat_log);
+        let own_uid = client.uid();
+        for entry in self.new_combat_log.drain(..) {
+            if entry.source.map_or(false, |source| {
+                Some(source) == own_uid || client.group_members().contains_key(&source)
+            }) {
+                self.combat_meter.record(&entry);
+            }
+            if shows_combat_log {
+                self.new_messages.push_back(
+                    comp::ChatType::CombatLog
+                        .chat_msg(damage_meter::describe_entry(client, &entry, i18n)),
+                );
+            }
+        }
+        let wants_combat_log = shows_combat_log || self.show.damage_meter;
+        if wants_combat_log != self.combat_log_requested {
+            self.combat_log_requested = wants_combat_log;
+            events.push(Event::RequestCombatLog(wants_combat_log));
+        }
+
+        // Damage meter
+        if self.show.damage_meter {
+            for event in DamageMeter::new(
+                client,
+                &self.imgs,
+                &self.fonts,
+                i18n,
+                &self.combat_meter,
+                self.show.meter_mode,
+            )
+            .set(self.ids.damage_meter, ui_widgets)
+            {
+                match event {
+                    damage_meter::Event::Close => self.show.damage_meter = false,
+                    damage_meter::Event::Reset => self.combat_meter.reset(),
+                    damage_meter::Event::SetMode(mode) => self.show.meter_mode = mode,
+                }
+            }
+        }
+
         // Buffs
         let ecs = client.state().ecs();
         let entity = client.entity();
@@ -2671,10 +2880,25 @@ impl Hud {
                 .set(self.ids.crafting_window, ui_widgets)
                 {
                     match event {
-                        crafting::Event::CraftRecipe(recipe) => {
+                        crafting::Event::CraftRecipe {
+                            recipe,
+                            amount,
+                            improve,
+                        } => {
                             events.push(Event::CraftRecipe {
                                 recipe,
                                 craft_sprite: self.show.craft_sprite,
+                                amount,
+                                improve,
+                            });
+                        },
+                        crafting::Event::CancelCrafting => {
+                            events.push(Event::CancelCrafting);
+                        },
+                        crafting::Event::RepairItem(slot) => {
+                            events.push(Event::RepairItem {
+                                slot,
+                                craft_sprite: self.show.craft_sprite,
                             });
                         },
                         crafting::Event::Close => {
@@ -2859,6 +3083,11 @@ impl Hud {
                         social::Event::SearchPlayers(search_key) => {
                             self.show.search_social_players(search_key)
                         },
+                        social::Event::ChangeTab(tab) => self.show.social_tab(tab),
+                        social::Event::GuildInvite(uid) => events.push(Event::InviteToGuild(uid)),
+                        social::Event::GuildManip(manip) => {
+                            events.push(Event::ManageGuild(manip))
+                        },
                     }
                 }
             }
@@ -2893,6 +3122,20 @@ impl Hud {
                             self.show.open_skill_tree(tree_sel)
                         },
                         diary::Event::UnlockSkill(skill) => events.push(Event::UnlockSkill(skill)),
+                        diary::Event::Respec(tree) => {
+                            events.push(Event::ManageBuild(comp::BuildManip::Respec(tree)))
+                        },
+                        diary::Event::SetPresetName(name) => self.show.preset_name = name,
+                        diary::Event::SavePreset => {
+                            let name = std::mem::take(&mut self.show.preset_name);
+                            events.push(Event::ManageBuild(comp::BuildManip::SavePreset(name)))
+                        },
+                        diary::Event::ApplyPreset(name) => {
+                            events.push(Event::ManageBuild(comp::BuildManip::ApplyPreset(name)))
+                        },
+                        diary::Event::DeletePreset(name) => {
+                            events.push(Event::ManageBuild(comp::BuildManip::DeletePreset(name)))
+                        },
                     }
                 }
             }
@@ -3062,8 +3305,23 @@ impl Hud {
             };
             match event {
                 slot::Event::Dragged(a, b) => {
-                    // Swap between slots
-                    if let (Some(a), Some(b)) = (to_slot(a), to_slot(b)) {
+                    // Dragging a gem onto an item with a free socket sockets it
+                    let socket = match (a, to_slot(b)) {
+                        (
+                            Inventory(InventorySlot {
+                                slot: gem, ours: true, ..
+                            }),
+                            Some(slot),
+                        ) => inventories
+                            .get(entity)
+                            .filter(|inv| inv.can_socket(gem, slot))
+                            .map(|_| (gem, slot)),
+                        _ => None,
+                    };
+                    if let Some((gem, slot)) = socket {
+                        events.push(Event::SocketGem { gem, slot });
+                    } else if let (Some(a), Some(b)) = (to_slot(a), to_slot(b)) {
+                        // Swap between slots
                         events.push(Event::SwapSlots {
                             slot_a: a,
                             slot_b: b,
@@ -3081,6 +3339,15 @@ impl Hud {
                     } else if let (Hotbar(a), Hotbar(b)) = (a, b) {
                         self.hotbar.swap(a, b);
                         events.push(Event::ChangeHotbarState(Box::new(self.hotbar.to_owned())));
+                    } else if let (Inventory(i), Inventory(s)) = (a, b) {
+                        // Move items in and out of an open storage
+                        if self.show.storage && i.ours != s.ours {
+                            events.push(if i.ours {
+                                Event::DepositItem(i.slot)
+                            } else {
+                                Event::WithdrawItem(i.slot)
+                            });
+                        }
                     } else if let (Inventory(i), Trade(t)) = (a, b) {
                         if i.ours == t.ours {
                             if let Some(inventory) = inventories.get(t.entity) {
@@ -3173,11 +3440,29 @@ impl Hud {
                 },
                 slot::Event::Used(from) => {
                     // Item used (selected and then clicked again)
-                    if let Some(from) = to_slot(from) {
+                    if let (Inventory(i), true) = (from, self.show.mailbox) {
+                        // Attach the item to the letter being written
+                        if let Some(amount) = inventories
+                            .get(client.entity())
+                            .filter(|_| i.ours)
+                            .and_then(|inventory| i.amount(inventory))
+                        {
+                            self.show.mail_draft.attach(i.slot, amount);
+                        }
+                    } else if let Some(from) = to_slot(from) {
                         events.push(Event::UseSlot {
                             slot: from,
                             bypass_dialog: false,
                         });
+                    } else if let (
+                        Inventory(InventorySlot {
+                            slot, ours: false, ..
+                        }),
+                        true,
+                    ) = (from, self.show.storage)
+                    {
+                        // Take the item out of the open storage
+                        events.push(Event::WithdrawItem(slot));
                     } else if let Hotbar(h) = from {
                         // Used from hotbar
                         self.hotbar.get(h).map(|s| {
@@ -3338,6 +3623,10 @@ impl Hud {
 
     pub fn new_message(&mut self, msg: comp::ChatMsg) { self.new_messages.push_back(msg); }
 
+    pub fn new_combat_log_entry(&mut self, entry: CombatLogEntry) {
+        self.new_combat_log.push_back(entry);
+    }
+
     pub fn new_notification(&mut self, msg: Notification) { self.new_notifications.push_back(msg); }
 
     pub fn set_scaling_mode(&mut self, scale_mode: ScaleMode) {
@@ -3500,6 +3789,12 @@ impl Hud {
                     self.ui.focus_widget(None);
                 } else if self.show.trade {
                     self.events.push(Event::TradeAction(TradeAction::Decline));
+                } else if self.show.storage {
+                    self.show.storage(false);
+                    self.events.push(Event::CloseStorage);
+                } else if self.show.mailbox {
+                    self.show.mailbox(false);
+                    self.events.push(Event::CloseMailbox);
                 } else {
                     // Close windows on esc
                     self.show.toggle_windows(global_state);
@@ -3532,6 +3827,10 @@ impl Hud {
                         self.show.toggle_crafting();
                         true
                     },
+                    GameInput::DamageMeter if state => {
+                        self.show.damage_meter = !self.show.damage_meter;
+                        true
+                    },
                     GameInput::Spellbook if state => {
                         self.show.toggle_spell();
                         true
@@ -3651,6 +3950,16 @@ impl Hud {
                 }
         });
 
+        // Close the mailbox when walking away from it
+        if let Some(pos) = client.mailbox() {
+            if client.position().map_or(true, |player_pos| {
+                pos.map(|e| e as f32 + 0.5).distance(player_pos) > MAX_PICKUP_RANGE + 1.0
+            }) {
+                self.show.mailbox(false);
+                self.events.push(Event::CloseMailbox);
+            }
+        }
+
         // Optimization: skip maintaining UI when it's off.
         if !self.show.ui {
             return std::mem::take(&mut self.events);
@@ -1,5 +1,6 @@
 use super::{
     cr_color, img_ids::Imgs, DEFAULT_NPC, ENEMY_HP_COLOR, FACTION_COLOR, GROUP_COLOR, GROUP_MEMBER,
+    GUILD_COLOR,
     HP_COLOR, LOW_HP_COLOR, QUALITY_EPIC, REGION_COLOR, SAY_COLOR, STAMINA_COLOR, TELL_COLOR,
     TEXT_BG, TEXT_COLOR,
 };
@@ -59,6 +60,8 @@ widget_ids! {
 #[derive(Clone, Copy)]
 pub struct Info<'a> {
     pub name: &'a str,
+    /// The tag of the guild a player's character belongs to
+    pub guild_tag: Option<&'a str>,
     pub health: Option<&'a Health>,
     pub buffs: &'a Buffs,
     pub energy: Option<&'a Energy>,
@@ -181,12 +184,17 @@ impl<'a> Widget for Overhead<'a> {
         const MANA_BAR_Y: f64 = MANA_BAR_HEIGHT / 2.0;
         if let Some(Info {
             name,
+            guild_tag,
             health,
             buffs,
             energy,
             combat_rating,
         }) = self.info
         {
+            let name = match guild_tag {
+                Some(tag) => format!("[{}] {}", tag, name),
+                None => name.to_owned(),
+            };
             // Used to set healthbar colours based on hp_percentage
             let hp_percentage = health.map_or(100.0, |h| {
                 h.current() as f64 / h.base_max().max(h.maximum()) as f64 * 100.0
@@ -287,7 +295,7 @@ impl<'a> Widget for Overhead<'a> {
                     });
             }
             // Name
-            Text::new(name)
+            Text::new(&name)
                 //Text::new(&format!("{} [{:?}]", name, combat_rating)) // <- Uncomment to debug combat ratings
                 .font_id(self.fonts.cyri.conrod_id)
                 .font_size(font_size)
@@ -295,7 +303,7 @@ impl<'a> Widget for Overhead<'a> {
                 .x_y(-1.0, name_y)
                 .parent(id)
                 .set(state.ids.name_bg, ui);
-            Text::new(name)
+            Text::new(&name)
                 //Text::new(&format!("{} [{:?}]", name, combat_rating)) // <- Uncomment to debug combat ratings
                 .font_id(self.fonts.cyri.conrod_id)
                 .font_size(font_size)
@@ -606,6 +614,7 @@ fn bubble_color(bubble: &SpeechBubble, dark_mode: bool) -> (Color, Color) {
         SpeechBubbleType::Region => REGION_COLOR,
         SpeechBubbleType::Group => GROUP_COLOR,
         SpeechBubbleType::Faction => FACTION_COLOR,
+        SpeechBubbleType::Guild => GUILD_COLOR,
         SpeechBubbleType::World
         | SpeechBubbleType::Quest
         | SpeechBubbleType::Trade
@@ -626,6 +635,7 @@ fn bubble_icon(sb: &SpeechBubble, imgs: &Imgs) -> conrod_core::image::Id {
         SpeechBubbleType::Region => imgs.chat_region_small,
         SpeechBubbleType::Group => imgs.chat_group_small,
         SpeechBubbleType::Faction => imgs.chat_faction_small,
+        SpeechBubbleType::Guild => imgs.chat_faction_small,
         SpeechBubbleType::World => imgs.chat_world_small,
         SpeechBubbleType::Quest => imgs.nothing, // TODO not implemented
         SpeechBubbleType::Trade => imgs.nothing, // TODO not implemented
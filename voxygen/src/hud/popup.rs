@@ -131,6 +131,92 @@ impl<'a> Widget for Popup<'a> {
                         s.infos.push_back(text.to_string());
                     });
                 },
+                Notification::QuestComplete(title) | Notification::QuestFailed(title) => {
+                    state.update(|s| {
+                        if s.infos.is_empty() {
+                            s.last_info_update = Instant::now();
+                        }
+                        let key = match notification {
+                            Notification::QuestComplete(_) => "hud.quest_complete",
+                            _ => "hud.quest_failed",
+                        };
+                        let text = self.i18n.get(key).replace("{title}", title);
+                        s.infos.push_back(text);
+                    });
+                },
+                Notification::ItemBroken(item) => {
+                    state.update(|s| {
+                        if s.infos.is_empty() {
+                            s.last_info_update = Instant::now();
+                        }
+                        let text = self.i18n.get("hud.item_broken").replace("{item}", item);
+                        s.infos.push_back(text);
+                    });
+                },
+                Notification::HouseInfo {
+                    pos,
+                    lease_days_left,
+                    evict_after_days,
+                } => {
+                    state.update(|s| {
+                        if s.infos.is_empty() {
+                            s.last_info_update = Instant::now();
+                        }
+                        let mut text = self
+                            .i18n
+                            .get("hud.house_info")
+                            .replace("{x}", &pos.x.to_string())
+                            .replace("{y}", &pos.y.to_string());
+                        if let Some(days) = lease_days_left {
+                            text.push(' ');
+                            text.push_str(
+                                &self
+                                    .i18n
+                                    .get("hud.house_lease")
+                                    .replace("{days}", &days.to_string()),
+                            );
+                        }
+                        if let Some(days) = evict_after_days {
+                            text.push(' ');
+                            text.push_str(
+                                &self
+                                    .i18n
+                                    .get("hud.house_eviction")
+                                    .replace("{days}", &days.to_string()),
+                            );
+                        }
+                        s.infos.push_back(text);
+                    });
+                },
+                Notification::NoHouse { price } => {
+                    state.update(|s| {
+                        if s.infos.is_empty() {
+                            s.last_info_update = Instant::now();
+                        }
+                        let text = self
+                            .i18n
+                            .get("hud.house_none")
+                            .replace("{price}", &price.to_string());
+                        s.infos.push_back(text);
+                    });
+                },
+                Notification::CannotOwnHouse
+                | Notification::HouseWaypointSaved
+                | Notification::HouseAbandoned
+                | Notification::HouseLost => {
+                    state.update(|s| {
+                        if s.infos.is_empty() {
+                            s.last_info_update = Instant::now();
+                        }
+                        let key = match notification {
+                            Notification::CannotOwnHouse => "hud.house_cannot_own",
+                            Notification::HouseWaypointSaved => "hud.house_waypoint_saved",
+                            Notification::HouseAbandoned => "hud.house_abandoned",
+                            _ => "hud.house_lost",
+                        };
+                        s.infos.push_back(self.i18n.get(key).to_string());
+                    });
+                },
             }
         }
 
@@ -67,6 +67,9 @@ widget_ids! {
 
         text_death,
         list_death,
+
+        btn_combat_log,
+        text_combat_log,
     }
 }
 
@@ -648,6 +651,19 @@ impl<'a> Widget for Chat<'a> {
                 }
             }
 
+            //Combat log
+            if chat_tab.filter.combat_log
+                != create_toggle(chat_tab.filter.combat_log, true)
+                    .down_from(state.ids.list_death, 20.0)
+                    .set(state.ids.btn_combat_log, ui)
+            {
+                updated_chat_tab.filter.combat_log = !chat_tab.filter.combat_log;
+            }
+
+            create_toggle_text(&self.localized_strings.get("hud.settings.combat_log"), true)
+                .right_from(state.ids.btn_combat_log, 5.0)
+                .set(state.ids.text_combat_log, ui);
+
             if chat_tab != &updated_chat_tab {
                 //insert to front to avoid errors where the tab is moved or removed
                 events.insert(0, Event::ChatChange(ChatTabUpdate(index, updated_chat_tab)));
@@ -8,11 +8,16 @@ use crate::{
     ui::{fonts::Fonts, ImageFrame, Tooltip, TooltipManager, Tooltipable},
 };
 use client::{self, Client};
-use common::{comp::group, uid::Uid};
+use common::{
+    character::CharacterId,
+    comp::{group, GuildManip},
+    uid::Uid,
+};
 use conrod_core::{
     color,
-    widget::{self, Button, Image, Rectangle, Scrollbar, Text, TextEdit},
-    widget_ids, Color, Colorable, Labelable, Positionable, Sizeable, Widget, WidgetCommon,
+    widget::{self, Button, Image, Rectangle, Scrollbar, State as ConrodState, Text, TextEdit},
+    widget_ids, Color, Colorable, Labelable, Positionable, Sizeable, UiCell, Widget,
+    WidgetCommon,
 };
 use itertools::Itertools;
 use std::time::Instant;
@@ -35,14 +40,31 @@ widget_ids! {
         player_search_input,
         player_search_input_bg,
         player_search_input_overlay,
+        tab_button,
+        no_guild_txt,
+        guild_title,
+        guild_align,
+        guild_scrollbar,
+        guild_members[],
+        promote_button,
+        demote_button,
+        kick_button,
+        leave_button,
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SocialTab {
+    Online,
+    Guild,
+}
+
 pub struct State {
     ids: Ids,
     // Holds the time when selection is made since this selection can be overridden
     // by selecting an entity in-game
     selected_uid: Option<(Uid, Instant)>,
+    selected_member: Option<CharacterId>,
 }
 
 #[derive(WidgetCommon)]
@@ -91,6 +113,9 @@ pub enum Event {
     Invite(Uid),
     Focus(widget::Id),
     SearchPlayers(Option<String>),
+    ChangeTab(SocialTab),
+    GuildInvite(Uid),
+    GuildManip(GuildManip),
 }
 
 impl<'a> Widget for Social<'a> {
@@ -102,6 +127,7 @@ impl<'a> Widget for Social<'a> {
         Self::State {
             ids: Ids::new(id_gen),
             selected_uid: None,
+            selected_member: None,
         }
     }
 
@@ -171,6 +197,33 @@ impl<'a> Widget for Social<'a> {
             .color(TEXT_COLOR)
             .set(state.ids.title, ui);
 
+        // Switch between the online players and the guild
+        if Button::image(self.imgs.button)
+            .w_h(70.0, 26.0)
+            .bottom_left_with_margins_on(state.ids.frame, 9.0, 92.0)
+            .hover_image(self.imgs.button_hover)
+            .press_image(self.imgs.button_press)
+            .label(&self.localized_strings.get(match self.show.social_tab {
+                SocialTab::Online => "hud.social.guild",
+                SocialTab::Guild => "hud.social.players",
+            }))
+            .label_y(conrod_core::position::Relative::Scalar(3.0))
+            .label_color(TEXT_COLOR)
+            .label_font_size(self.fonts.cyri.scale(15))
+            .label_font_id(self.fonts.cyri.conrod_id)
+            .set(state.ids.tab_button, ui)
+            .was_clicked()
+        {
+            events.push(Event::ChangeTab(match self.show.social_tab {
+                SocialTab::Online => SocialTab::Guild,
+                SocialTab::Guild => SocialTab::Online,
+            }));
+        }
+        if let SocialTab::Guild = self.show.social_tab {
+            self.guild_tab(state, ui, &mut events);
+            return events;
+        }
+
         let players = self
             .client
             .player_list()
@@ -444,3 +497,245 @@ impl<'a> Widget for Social<'a> {
         events
     }
 }
+
+impl<'a> Social<'a> {
+    fn guild_tab(
+        &self,
+        state: &mut ConrodState<'_, State>,
+        ui: &mut UiCell<'_>,
+        events: &mut Vec<Event>,
+    ) {
+        let guild = match self.client.guild() {
+            Some(guild) => guild,
+            None => {
+                Text::new(&self.localized_strings.get("hud.social.no_guild"))
+                    .top_left_with_margins_on(state.ids.frame, 54.0, 10.0)
+                    .w(260.0)
+                    .font_id(self.fonts.cyri.conrod_id)
+                    .font_size(self.fonts.cyri.scale(14))
+                    .color(TEXT_COLOR)
+                    .set(state.ids.no_guild_txt, ui);
+                return;
+            },
+        };
+
+        Text::new(&format!("[{}] {}", guild.tag, guild.name))
+            .top_left_with_margins_on(state.ids.frame, 52.0, 10.0)
+            .font_id(self.fonts.cyri.conrod_id)
+            .font_size(self.fonts.cyri.scale(16))
+            .color(TEXT_COLOR)
+            .set(state.ids.guild_title, ui);
+
+        // Members, online ones first within each rank
+        Rectangle::fill_with([270.0, 290.0], color::TRANSPARENT)
+            .mid_top_with_margin_on(state.ids.frame, 74.0)
+            .scroll_kids_vertically()
+            .set(state.ids.guild_align, ui);
+        Scrollbar::y_axis(state.ids.guild_align)
+            .thickness(4.0)
+            .color(Color::Rgba(0.79, 1.09, 1.09, 0.0))
+            .set(state.ids.guild_scrollbar, ui);
+        if state.ids.guild_members.len() < guild.members.len() {
+            state.update(|s| {
+                s.ids
+                    .guild_members
+                    .resize(guild.members.len(), &mut ui.widget_id_generator())
+            })
+        };
+        let members = guild
+            .members
+            .iter()
+            .sorted_by_key(|member| (std::cmp::Reverse(member.rank), !member.online))
+            .collect_vec();
+        for (i, member) in members.iter().enumerate() {
+            let selected = state.selected_member == Some(member.character_id);
+            let button = Button::image(if selected {
+                self.imgs.selection
+            } else {
+                self.imgs.nothing
+            })
+            .hover_image(if selected {
+                self.imgs.selection
+            } else {
+                self.imgs.selection_hover
+            })
+            .press_image(if selected {
+                self.imgs.selection
+            } else {
+                self.imgs.selection_press
+            })
+            .w_h(260.0, 20.0);
+            let button = if i == 0 {
+                button.mid_top_with_margin_on(state.ids.guild_align, 1.0)
+            } else {
+                button.down_from(state.ids.guild_members[i - 1], 1.0)
+            };
+            if button
+                .label(&format!(
+                    "{} ({})",
+                    member.name,
+                    self.localized_strings
+                        .get(&format!("hud.social.guild.{}", member.rank.as_str()))
+                ))
+                .label_font_size(self.fonts.cyri.scale(14))
+                .label_y(conrod_core::position::Relative::Scalar(1.0))
+                .label_font_id(self.fonts.cyri.conrod_id)
+                .label_color(if member.online {
+                    TEXT_COLOR
+                } else {
+                    TEXT_COLOR_3
+                })
+                .set(state.ids.guild_members[i], ui)
+                .was_clicked()
+            {
+                state.update(|s| s.selected_member = Some(member.character_id));
+            }
+        }
+
+        // Online Text
+        Text::new(&self.localized_strings.get("hud.social.online"))
+            .bottom_left_with_margins_on(state.ids.frame, 18.0, 10.0)
+            .font_id(self.fonts.cyri.conrod_id)
+            .font_size(self.fonts.cyri.scale(14))
+            .color(TEXT_COLOR)
+            .set(state.ids.online_txt, ui);
+        Text::new(
+            &guild
+                .members
+                .iter()
+                .filter(|member| member.online)
+                .count()
+                .to_string(),
+        )
+        .right_from(state.ids.online_txt, 5.0)
+        .font_id(self.fonts.cyri.conrod_id)
+        .font_size(self.fonts.cyri.scale(14))
+        .color(TEXT_COLOR)
+        .set(state.ids.online_no, ui);
+
+        // Rank changes only go through if the server agrees, the buttons are
+        // disabled when they would not
+        let target = state
+            .selected_member
+            .and_then(|selected| members.iter().find(|m| m.character_id == selected));
+        let actions = vec![
+            (
+                state.ids.promote_button,
+                "hud.social.guild.promote",
+                target
+                    .filter(|member| guild.rank.can_promote(member.rank))
+                    .map(|member| GuildManip::Promote(member.character_id)),
+            ),
+            (
+                state.ids.demote_button,
+                "hud.social.guild.demote",
+                target
+                    .filter(|member| guild.rank.can_demote(member.rank))
+                    .map(|member| GuildManip::Demote(member.character_id)),
+            ),
+            (
+                state.ids.kick_button,
+                "hud.social.guild.kick",
+                target
+                    .filter(|member| guild.rank.can_kick(member.rank))
+                    .map(|member| GuildManip::Kick(member.character_id)),
+            ),
+            (
+                state.ids.leave_button,
+                "hud.social.guild.leave",
+                Some(GuildManip::Leave),
+            ),
+        ];
+        for (i, (id, key, manip)) in actions.into_iter().enumerate() {
+            let enabled = manip.is_some();
+            if Button::image(self.imgs.button)
+                .w_h(64.0, 26.0)
+                .bottom_left_with_margins_on(state.ids.frame, 44.0, 7.0 + i as f64 * 67.0)
+                .hover_image(if enabled {
+                    self.imgs.button_hover
+                } else {
+                    self.imgs.button
+                })
+                .press_image(if enabled {
+                    self.imgs.button_press
+                } else {
+                    self.imgs.button
+                })
+                .label(&self.localized_strings.get(key))
+                .label_y(conrod_core::position::Relative::Scalar(3.0))
+                .label_color(if enabled { TEXT_COLOR } else { TEXT_COLOR_3 })
+                .image_color(if enabled { TEXT_COLOR } else { TEXT_COLOR_3 })
+                .label_font_size(self.fonts.cyri.scale(13))
+                .label_font_id(self.fonts.cyri.conrod_id)
+                .set(id, ui)
+                .was_clicked()
+            {
+                if let Some(manip) = manip {
+                    events.push(Event::GuildManip(manip));
+                    state.update(|s| s.selected_member = None);
+                }
+            }
+        }
+
+        // Invite the player selected in the list of online players or in the
+        // world, as long as they are not in a guild yet
+        let selected_to_invite = guild
+            .rank
+            .can_invite()
+            .then(|| {
+                state
+                    .selected_uid
+                    .as_ref()
+                    .map(|(s, _)| *s)
+                    .or_else(|| {
+                        self.selected_entity
+                            .and_then(|s| self.client.state().read_component_copied(s.0))
+                    })
+                    .filter(|selected| {
+                        self.client
+                            .player_list()
+                            .get(selected)
+                            .and_then(|player| {
+                                player.character.as_ref().filter(|_| player.is_online)
+                            })
+                            .map_or(false, |character| character.guild_tag.is_none())
+                    })
+            })
+            .flatten();
+        if Button::image(self.imgs.button)
+            .w_h(106.0, 26.0)
+            .bottom_right_with_margins_on(state.ids.frame, 9.0, 7.0)
+            .hover_image(if selected_to_invite.is_some() {
+                self.imgs.button_hover
+            } else {
+                self.imgs.button
+            })
+            .press_image(if selected_to_invite.is_some() {
+                self.imgs.button_press
+            } else {
+                self.imgs.button
+            })
+            .label(&self.localized_strings.get("hud.group.invite"))
+            .label_y(conrod_core::position::Relative::Scalar(3.0))
+            .label_color(if selected_to_invite.is_some() {
+                TEXT_COLOR
+            } else {
+                TEXT_COLOR_3
+            })
+            .image_color(if selected_to_invite.is_some() {
+                TEXT_COLOR
+            } else {
+                TEXT_COLOR_3
+            })
+            .label_font_size(self.fonts.cyri.scale(15))
+            .label_font_id(self.fonts.cyri.conrod_id)
+            .set(state.ids.invite_button, ui)
+            .was_clicked()
+        {
+            if let Some(uid) = selected_to_invite {
+                events.push(Event::GuildInvite(uid));
+                state.update(|s| s.selected_uid = None);
+            }
+        }
+    }
+}
@@ -0,0 +1,170 @@
+use super::{
+    img_ids::{Imgs, ImgsRot},
+    item_imgs::ItemImgs,
+    slots::SlotManager,
+    TEXT_COLOR,
+};
+use crate::{
+    hud::bag::{BackgroundIds, InventoryScroller},
+    i18n::Localization,
+    ui::{fonts::Fonts, ImageFrame, ItemTooltip, ItemTooltipManager, ItemTooltipable},
+};
+use client::Client;
+use common::comp::inventory::item::MaterialStatManifest;
+use conrod_core::{
+    widget::{self, Button},
+    widget_ids, Color, Positionable, Sizeable, Widget, WidgetCommon,
+};
+
+pub struct State {
+    ids: Ids,
+    bg_ids: BackgroundIds,
+}
+
+widget_ids! {
+    pub struct Ids {
+        storage_close,
+        inventory_scroller,
+    }
+}
+
+pub enum Event {
+    Close,
+}
+
+#[derive(WidgetCommon)]
+pub struct Storage<'a> {
+    client: &'a Client,
+    imgs: &'a Imgs,
+    item_imgs: &'a ItemImgs,
+    fonts: &'a Fonts,
+    rot_imgs: &'a ImgsRot,
+    item_tooltip_manager: &'a mut ItemTooltipManager,
+    #[conrod(common_builder)]
+    common: widget::CommonBuilder,
+    slot_manager: &'a mut SlotManager,
+    localized_strings: &'a Localization,
+    msm: &'a MaterialStatManifest,
+    pulse: f32,
+}
+
+impl<'a> Storage<'a> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        client: &'a Client,
+        imgs: &'a Imgs,
+        item_imgs: &'a ItemImgs,
+        fonts: &'a Fonts,
+        rot_imgs: &'a ImgsRot,
+        item_tooltip_manager: &'a mut ItemTooltipManager,
+        slot_manager: &'a mut SlotManager,
+        localized_strings: &'a Localization,
+        msm: &'a MaterialStatManifest,
+        pulse: f32,
+    ) -> Self {
+        Self {
+            client,
+            imgs,
+            item_imgs,
+            fonts,
+            rot_imgs,
+            item_tooltip_manager,
+            common: widget::CommonBuilder::default(),
+            slot_manager,
+            localized_strings,
+            msm,
+            pulse,
+        }
+    }
+}
+
+impl<'a> Widget for Storage<'a> {
+    type Event = Option<Event>;
+    type State = State;
+    type Style = ();
+
+    fn init_state(&self, mut id_gen: widget::id::Generator) -> Self::State {
+        State {
+            bg_ids: BackgroundIds {
+                bg: id_gen.next(),
+                bg_frame: id_gen.next(),
+            },
+            ids: Ids::new(id_gen),
+        }
+    }
+
+    fn style(&self) -> Self::Style {}
+
+    fn update(self, args: widget::UpdateArgs<Self>) -> Self::Event {
+        common_base::prof_span!("Storage::update");
+        let widget::UpdateArgs { state, ui, .. } = args;
+
+        let inventory = match self.client.storage() {
+            Some(inventory) => inventory,
+            None => return Some(Event::Close),
+        };
+
+        // Tooltips
+        let item_tooltip = ItemTooltip::new(
+            {
+                // Edge images [t, b, r, l]
+                // Corner images [tr, tl, br, bl]
+                let edge = &self.rot_imgs.tt_side;
+                let corner = &self.rot_imgs.tt_corner;
+                ImageFrame::new(
+                    [edge.cw180, edge.none, edge.cw270, edge.cw90],
+                    [corner.none, corner.cw270, corner.cw90, corner.cw180],
+                    Color::Rgba(0.08, 0.07, 0.04, 1.0),
+                    5.0,
+                )
+            },
+            self.client,
+            self.imgs,
+            self.item_imgs,
+            self.pulse,
+            self.msm,
+            self.localized_strings,
+        )
+        .title_font_size(self.fonts.cyri.scale(20))
+        .parent(ui.window)
+        .desc_font_size(self.fonts.cyri.scale(12))
+        .font_id(self.fonts.cyri.conrod_id)
+        .desc_text_color(TEXT_COLOR);
+
+        // The storage is shown like the inventory of someone else, so dragging
+        // between it and our own inventory moves items in or out
+        InventoryScroller::new(
+            self.client,
+            self.imgs,
+            self.item_imgs,
+            self.fonts,
+            self.item_tooltip_manager,
+            self.slot_manager,
+            self.pulse,
+            self.localized_strings,
+            false,
+            true,
+            false,
+            &item_tooltip,
+            self.localized_strings.get("hud.storage.storage").to_string(),
+            self.client.entity(),
+            false,
+            inventory,
+            &state.bg_ids,
+        )
+        .set(state.ids.inventory_scroller, ui);
+
+        if Button::image(self.imgs.close_btn)
+            .w_h(24.0, 25.0)
+            .hover_image(self.imgs.close_btn_hover)
+            .press_image(self.imgs.close_btn_press)
+            .top_right_with_margins_on(state.bg_ids.bg, 0.0, 0.0)
+            .set(state.ids.storage_close, ui)
+            .was_clicked()
+        {
+            Some(Event::Close)
+        } else {
+            None
+        }
+    }
+}
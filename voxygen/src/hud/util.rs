@@ -1,19 +1,22 @@
 use common::{
+    assets::AssetExt,
+    combat::CombatEffect,
     comp::{
         inventory::trade_pricing::TradePricing,
         item::{
             armor::{Armor, ArmorKind, Protection},
             tool::{Hands, StatKind, Stats, Tool, ToolKind},
-            Item, ItemKind, MaterialKind, MaterialStatManifest, ModularComponent,
+            GemManifest, Item, ItemDef, ItemKind, ItemModifier, MaterialKind,
+            MaterialStatManifest, ModularComponent,
         },
         BuffKind,
     },
     effect::Effect,
     trade::{Good, SitePrices},
 };
-use std::{borrow::Cow, fmt::Write};
+use std::{borrow::Cow, fmt::Write, sync::Arc};
 
-use crate::i18n::Localization;
+use crate::{hud::get_buff_title, i18n::Localization};
 
 pub fn price_desc(
     prices: &Option<SitePrices>,
@@ -59,7 +62,7 @@ pub fn kind_text<'a>(kind: &ItemKind, i18n: &'a Localization) -> Cow<'a, str> {
         ItemKind::Armor(armor) => Cow::Borrowed(armor_kind(&armor, &i18n)),
         ItemKind::Tool(tool) => Cow::Owned(format!(
             "{} ({})",
-            tool_kind(&tool, i18n),
+            tool_kind_text(tool.kind, i18n),
             tool_hands(&tool, i18n)
         )),
         ItemKind::ModularComponent(_mc) => Cow::Borrowed(i18n.get("common.bag.shoulders")),
@@ -173,6 +176,82 @@ pub fn consumable_desc(effects: &[Effect], i18n: &Localization) -> String {
     description
 }
 
+pub fn modifier_desc(modifier: &ItemModifier, i18n: &Localization) -> String {
+    match modifier {
+        ItemModifier::Power(power) => {
+            format!("{} : +{:.0}%", i18n.get("common.stats.power"), power * 100.0)
+        },
+        ItemModifier::Speed(speed) => {
+            format!("{} : +{:.0}%", i18n.get("common.stats.speed"), speed * 100.0)
+        },
+        ItemModifier::CritChance(crit_chance) => format!(
+            "{} : +{:.1}%",
+            i18n.get("common.stats.crit_chance"),
+            crit_chance * 100.0
+        ),
+        ItemModifier::EnergyReward(energy) => format!(
+            "{} : +{:.1}",
+            i18n.get("common.stats.energy_reward"),
+            energy * 0.1
+        ),
+        ItemModifier::OnHit(effect) => {
+            let effect = match effect {
+                CombatEffect::Buff(buff) => Cow::Borrowed(get_buff_title(buff.kind, i18n)),
+                CombatEffect::Lifesteal(lifesteal) => Cow::Owned(format!(
+                    "{} {:.0}%",
+                    i18n.get("common.stats.lifesteal"),
+                    lifesteal * 100.0
+                )),
+                CombatEffect::Poise(poise) => Cow::Owned(format!(
+                    "{} {:.1}",
+                    i18n.get("common.stats.poise"),
+                    poise * 0.1
+                )),
+                CombatEffect::EnergyReward(energy) => Cow::Owned(format!(
+                    "{} {:.1}",
+                    i18n.get("common.stats.energy_reward"),
+                    energy * 0.1
+                )),
+                CombatEffect::Heal(heal) => Cow::Owned(format!(
+                    "{} {:.1}",
+                    i18n.get("buff.title.heal"),
+                    heal * 0.1
+                )),
+                CombatEffect::Knockback(_) => Cow::Borrowed(i18n.get("common.stats.knockback")),
+                CombatEffect::Combo(combo) => Cow::Owned(format!(
+                    "{} {}",
+                    i18n.get("common.stats.combo"),
+                    combo
+                )),
+            };
+            format!("{} : {}", i18n.get("common.stats.on_hit"), effect)
+        },
+    }
+}
+
+/// Describes a socket of an item, along with the modifier of the gem in it
+pub fn socket_desc(gem: Option<&str>, i18n: &Localization) -> String {
+    match gem {
+        Some(gem) => {
+            let name = Arc::<ItemDef>::load_expect_cloned(gem).name.clone();
+            match GemManifest::load().read().modifier(gem) {
+                Some(modifier) => format!(
+                    "{} : {} ({})",
+                    i18n.get("common.stats.socket"),
+                    name,
+                    modifier_desc(&modifier, i18n)
+                ),
+                None => format!("{} : {}", i18n.get("common.stats.socket"), name),
+            }
+        },
+        None => format!(
+            "{} : {}",
+            i18n.get("common.stats.socket"),
+            i18n.get("common.stats.empty_socket")
+        ),
+    }
+}
+
 // Armor
 fn armor_kind<'a>(armor: &Armor, i18n: &'a Localization) -> &'a str {
     let kind = match armor.kind {
@@ -193,8 +272,8 @@ fn armor_kind<'a>(armor: &Armor, i18n: &'a Localization) -> &'a str {
 }
 
 // Tool
-fn tool_kind<'a>(tool: &Tool, i18n: &'a Localization) -> &'a str {
-    let kind = match tool.kind {
+pub fn tool_kind_text(kind: ToolKind, i18n: &Localization) -> &str {
+    let kind = match kind {
         ToolKind::Sword => i18n.get("common.weapons.sword"),
         ToolKind::Axe => i18n.get("common.weapons.axe"),
         ToolKind::Hammer => i18n.get("common.weapons.hammer"),
@@ -208,6 +287,7 @@ fn tool_kind<'a>(tool: &Tool, i18n: &'a Localization) -> &'a str {
         ToolKind::Debug => i18n.get("common.tool.debug"),
         ToolKind::Farming => i18n.get("common.tool.farming"),
         ToolKind::Pick => i18n.get("common.tool.pick"),
+        ToolKind::Fishing => i18n.get("common.tool.fishing"),
         ToolKind::Empty => i18n.get("common.empty"),
     };
     kind
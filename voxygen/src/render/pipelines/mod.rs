@@ -15,7 +15,7 @@ pub mod ui;
 use super::{Consts, Texture};
 use crate::scene::camera::CameraMode;
 use bytemuck::{Pod, Zeroable};
-use common::terrain::BlockKind;
+use common::{terrain::BlockKind, weather::Weather};
 use vek::*;
 
 // TODO: auto insert these into shaders
@@ -58,6 +58,8 @@ pub struct Globals {
     medium: [u32; 4],
     select_pos: [i32; 4],
     gamma_exposure: [f32; 4],
+    /// Cloud cover, rain, snow and fog at the camera's position.
+    weather: [f32; 4],
     ambiance: f32,
     cam_mode: u32,
     sprite_render_distance: f32,
@@ -104,6 +106,7 @@ impl Globals {
         ambiance: f32,
         cam_mode: CameraMode,
         sprite_render_distance: f32,
+        weather: Weather,
     ) -> Self {
         Self {
             view_mat: view_mat.into_col_arrays(),
@@ -143,6 +146,7 @@ impl Globals {
                 .unwrap_or(Vec4::zero())
                 .into_array(),
             gamma_exposure: [gamma, exposure, 0.0, 0.0],
+            weather: [weather.cloud, weather.rain, weather.snow, weather.fog],
             ambiance,
             cam_mode: cam_mode as u32,
             sprite_render_distance,
@@ -190,6 +194,7 @@ impl Default for Globals {
             1.0,
             CameraMode::ThirdPerson,
             250.0,
+            Weather::default(),
         )
     }
 }
@@ -31,7 +31,7 @@ use common::{
         inventory::slot::EquipSlot,
         item::{Hands, ItemKind, ToolKind},
         Body, CharacterState, Controller, Health, Inventory, Item, Last, LightAnimation,
-        LightEmitter, Ori, PhysicsState, PoiseState, Pos, Scale, Vel,
+        LightEmitter, Mounting, Ori, PhysicsState, PoiseState, Pos, Scale, Vel,
     },
     resources::DeltaTime,
     states::utils::StageSection,
@@ -40,6 +40,7 @@ use common::{
     vol::RectRasterableVol,
 };
 use common_base::span;
+use common_net::sync::WorldSyncExt;
 use common_state::State;
 use core::{
     borrow::Borrow,
@@ -596,6 +597,7 @@ impl FigureMgr {
                 inventory,
                 item,
                 light_emitter,
+                mounting,
             ),
         ) in (
             &ecs.entities(),
@@ -612,6 +614,7 @@ impl FigureMgr {
             ecs.read_storage::<Inventory>().maybe(),
             ecs.read_storage::<Item>().maybe(),
             ecs.read_storage::<LightEmitter>().maybe(),
+            ecs.read_storage::<Mounting>().maybe(),
         )
             .join()
             .enumerate()
@@ -799,11 +802,34 @@ impl FigureMgr {
                         state.state_time = 0.0;
                     }
 
+                    // Riders straddle creatures, but sit down when aboard a ship
+                    let mount_body = mounting
+                        .and_then(|Mounting(uid)| ecs.entity_from_uid(uid.0))
+                        .and_then(|mount| ecs.read_storage::<Body>().get(mount).copied());
+
                     let target_base = match (
                         physics.on_ground.is_some(),
                         rel_vel.magnitude_squared() > MOVING_THRESHOLD_SQR, // Moving
                         physics.in_liquid().is_some(),                      // In water
                     ) {
+                        // Riding a creature
+                        _ if mount_body.map_or(false, |body| body.mount_stats().is_some()) => {
+                            anim::character::MountAnimation::update_skeleton(
+                                &CharacterSkeleton::new(holding_lantern),
+                                (rel_vel, time, state.acc_vel),
+                                state.state_time,
+                                &mut state_animation_rate,
+                                skeleton_attr,
+                            )
+                        },
+                        // Aboard a ship
+                        _ if mount_body.is_some() => anim::character::SitAnimation::update_skeleton(
+                            &CharacterSkeleton::new(holding_lantern),
+                            (active_tool_kind, second_tool_kind, time),
+                            state.state_time,
+                            &mut state_animation_rate,
+                            skeleton_attr,
+                        ),
                         // Standing
                         (true, false, false) => anim::character::StandAnimation::update_skeleton(
                             &CharacterSkeleton::new(holding_lantern),
@@ -1440,6 +1466,25 @@ impl FigureMgr {
                                 skeleton_attr,
                             )
                         },
+                        CharacterState::Fishing(s) => {
+                            let stage_time = s.timer.as_secs_f32();
+                            let stage_progress = match s.stage_section {
+                                StageSection::Buildup => {
+                                    stage_time / s.static_data.cast_duration.as_secs_f32()
+                                },
+                                StageSection::Recover => {
+                                    stage_time / s.static_data.reel_duration.as_secs_f32()
+                                },
+                                _ => stage_time,
+                            };
+                            anim::character::FishingAnimation::update_skeleton(
+                                &target_base,
+                                (Some(s.stage_section), s.is_biting(), time, look_dir),
+                                stage_progress,
+                                &mut state_animation_rate,
+                                skeleton_attr,
+                            )
+                        },
                         CharacterState::Talk => anim::character::TalkAnimation::update_skeleton(
                             &target_base,
                             (
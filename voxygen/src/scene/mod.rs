@@ -32,6 +32,7 @@ use common::{
     resources::DeltaTime,
     terrain::{BlockKind, TerrainChunk},
     vol::ReadVol,
+    weather::WeatherGrid,
 };
 use common_base::{prof_span, span};
 use common_state::State;
@@ -651,6 +652,11 @@ impl Scene {
             scene_data.ambiance,
             self.camera.get_mode(),
             scene_data.sprite_render_distance as f32 - 20.0,
+            scene_data
+                .state
+                .ecs()
+                .read_resource::<WeatherGrid>()
+                .get_interpolated((cam_pos + focus_off).xy()),
         )]);
         renderer.update_clouds_locals(CloudsLocals::new(proj_mat_inv, view_mat_inv));
         renderer.update_postprocess_locals(PostProcessLocals::new(proj_mat_inv, view_mat_inv));
@@ -19,6 +19,7 @@ use common::{
     states::{self, utils::StageSection},
     terrain::TerrainChunk,
     vol::{RectRasterableVol, SizedVol},
+    weather::WeatherGrid,
 };
 use common_base::span;
 use hashbrown::HashMap;
@@ -277,6 +278,7 @@ impl ParticleMgr {
             self.maintain_shockwave_particles(scene_data);
             self.maintain_aura_particles(scene_data);
             self.maintain_buff_particles(scene_data);
+            self.maintain_weather_particles(scene_data);
 
             self.upload_particles(renderer);
         } else {
@@ -341,6 +343,63 @@ impl ParticleMgr {
         }
     }
 
+    fn maintain_weather_particles(&mut self, scene_data: &SceneData) {
+        span!(
+            _guard,
+            "weather_particles",
+            "ParticleMgr::maintain_weather_particles"
+        );
+        // Rain and snow only fall around the player, since they couldn't be seen
+        // from further away anyway
+        const RADIUS: f32 = 40.0;
+        const RAIN_SPEED: f32 = 30.0;
+        const FALL_HEIGHT: f32 = 40.0;
+
+        let ecs = scene_data.state.ecs();
+        let player_pos = match ecs.read_storage::<Pos>().get(scene_data.player_entity) {
+            Some(pos) => pos.0,
+            None => return,
+        };
+        let weather = ecs
+            .read_resource::<WeatherGrid>()
+            .get_interpolated(player_pos.xy());
+        let time = scene_data.state.get_time();
+        let mut rng = thread_rng();
+
+        for _ in 0..self.scheduler.heartbeats(Duration::from_millis(10)) {
+            let spawn_pos = |rng: &mut ThreadRng| {
+                player_pos
+                    + Vec3::new(
+                        rng.gen_range(-RADIUS..RADIUS),
+                        rng.gen_range(-RADIUS..RADIUS),
+                        FALL_HEIGHT * rng.gen_range(0.5..1.0),
+                    )
+            };
+
+            for _ in 0..(weather.rain * 12.0).round() as usize {
+                let start = spawn_pos(&mut rng);
+                let fall_time = (start.z - player_pos.z + FALL_HEIGHT * 0.5) / RAIN_SPEED;
+                let end = start + (weather.wind * fall_time).with_z(-RAIN_SPEED * fall_time);
+                self.particles.push(Particle::new_directed(
+                    Duration::from_secs_f32(fall_time),
+                    time,
+                    ParticleMode::Rain,
+                    start,
+                    end,
+                ));
+            }
+
+            if rng.gen::<f32>() < weather.snow {
+                self.particles.push(Particle::new(
+                    Duration::from_secs(15),
+                    time,
+                    ParticleMode::Snow,
+                    spawn_pos(&mut rng),
+                ));
+            }
+        }
+    }
+
     fn maintain_campfirelit_particles(
         &mut self,
         scene_data: &SceneData,
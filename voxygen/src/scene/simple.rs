@@ -27,6 +27,7 @@ use common::{
     slowjob::SlowJobPool,
     terrain::BlockKind,
     vol::{BaseVol, ReadVol},
+    weather::Weather,
 };
 use vek::*;
 use winit::event::MouseButton;
@@ -262,6 +263,7 @@ impl Scene {
             scene_data.ambiance,
             self.camera.get_mode(),
             250.0,
+            Weather::default(),
         )]);
 
         self.figure_model_cache
@@ -11,6 +11,8 @@ use vek::*;
 pub enum Interaction {
     Collect,
     Craft(CraftingTab),
+    Bank,
+    Mailbox,
 }
 
 #[derive(Default)]
@@ -142,6 +144,10 @@ impl BlocksOfInterest {
                         Some(SpriteKind::CookingPot) => {
                             interactables.push((pos, Interaction::Craft(CraftingTab::Food)))
                         },
+                        Some(SpriteKind::Strongbox) => interactables.push((pos, Interaction::Bank)),
+                        Some(SpriteKind::Mailbox) => {
+                            interactables.push((pos, Interaction::Mailbox))
+                        },
                         _ => {},
                     },
                 }
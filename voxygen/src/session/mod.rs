@@ -159,6 +159,8 @@ impl SessionState {
                     let kind_str = match kind {
                         InviteKind::Group => "Group",
                         InviteKind::Trade => "Trade",
+                        InviteKind::Guild => "Guild",
+                        InviteKind::Duel => "Duel",
                     };
                     let target_name = match client.player_list().get(&target) {
                         Some(info) => info.player_alias.clone(),
@@ -252,6 +254,7 @@ impl SessionState {
                     global_state.settings.save_to_file_warn();
                 },
                 client::Event::Outcome(outcome) => outcomes.push(outcome),
+                client::Event::CombatLog(entry) => self.hud.new_combat_log_entry(entry),
                 client::Event::CharacterCreated(_) => {},
                 client::Event::CharacterError(error) => {
                     global_state.client_error = Some(error);
@@ -624,13 +627,16 @@ impl PlayState for SessionState {
                                                 .state()
                                                 .ecs()
                                                 .read_storage::<comp::MountState>(),
+                                            &client.state().ecs().read_storage::<comp::Body>(),
                                         )
                                             .join()
-                                            .filter(|(entity, _, mount_state)| {
+                                            .filter(|(entity, _, mount_state, body)| {
                                                 *entity != client.entity()
                                                     && **mount_state == comp::MountState::Unmounted
+                                                    && (matches!(body, comp::Body::Ship(_))
+                                                        || body.mount_stats().is_some())
                                             })
-                                            .map(|(entity, pos, _)| {
+                                            .map(|(entity, pos, _, _)| {
                                                 (entity, player_pos.0.distance_squared(pos.0))
                                             })
                                             .filter(|(_, dist_sqr)| {
@@ -662,6 +668,8 @@ impl PlayState for SessionState {
                                                             block.get_sprite().map(|s| (pos, s)),
                                                         )
                                                     },
+                                                    Interaction::Bank => client.open_bank(pos),
+                                                    Interaction::Mailbox => client.open_mailbox(pos),
                                                 }
                                             },
                                             Interactable::Entity(entity) => {
@@ -1284,6 +1292,33 @@ impl PlayState for SessionState {
                         let mut client = self.client.borrow_mut();
                         client.perform_trade_action(action);
                     },
+                    HudEvent::CloseStorage => {
+                        self.client.borrow_mut().close_storage();
+                    },
+                    HudEvent::DepositItem(slot) => {
+                        self.client.borrow_mut().deposit_item(slot);
+                    },
+                    HudEvent::WithdrawItem(slot) => {
+                        self.client.borrow_mut().withdraw_item(slot);
+                    },
+                    HudEvent::CloseMailbox => {
+                        self.client.borrow_mut().close_mailbox();
+                    },
+                    HudEvent::SendMail {
+                        recipient,
+                        message,
+                        attachments,
+                    } => {
+                        self.client
+                            .borrow_mut()
+                            .send_mail(recipient, message, attachments);
+                    },
+                    HudEvent::ClaimMail(mail) => {
+                        self.client.borrow_mut().claim_mail(mail);
+                    },
+                    HudEvent::RequestCombatLog(enabled) => {
+                        self.client.borrow_mut().request_combat_log(enabled);
+                    },
                     HudEvent::Ability3(state) => {
                         let mut client = self.client.borrow_mut();
                         client.handle_input(
@@ -1311,12 +1346,34 @@ impl PlayState for SessionState {
                     HudEvent::CraftRecipe {
                         recipe,
                         craft_sprite,
+                        amount,
+                        improve,
                     } => {
-                        self.client.borrow_mut().craft_recipe(&recipe, craft_sprite);
+                        self.client
+                            .borrow_mut()
+                            .craft_recipe(&recipe, craft_sprite, amount, improve);
+                    },
+                    HudEvent::CancelCrafting => {
+                        self.client.borrow_mut().cancel_crafting();
+                    },
+                    HudEvent::RepairItem { slot, craft_sprite } => {
+                        self.client.borrow_mut().repair_item(slot, craft_sprite);
+                    },
+                    HudEvent::SocketGem { gem, slot } => {
+                        self.client.borrow_mut().socket_gem(gem, slot);
                     },
                     HudEvent::InviteMember(uid) => {
                         self.client.borrow_mut().send_invite(uid, InviteKind::Group);
                     },
+                    HudEvent::InviteToGuild(uid) => {
+                        self.client.borrow_mut().send_invite(uid, InviteKind::Guild);
+                    },
+                    HudEvent::ManageGuild(manip) => {
+                        self.client.borrow_mut().guild_manip(manip);
+                    },
+                    HudEvent::ManageBuild(manip) => {
+                        self.client.borrow_mut().build_manip(manip);
+                    },
                     HudEvent::AcceptInvite => {
                         self.client.borrow_mut().accept_invite();
                     },
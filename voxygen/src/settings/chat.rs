@@ -23,6 +23,9 @@ pub struct ChatFilter {
     //deaths
     pub death_all: bool,
     pub death_group: bool,
+    //combat log
+    #[serde(default)]
+    pub combat_log: bool,
 }
 impl ChatFilter {
     pub fn satisfies(&self, chat_msg: &ChatMsg, group_members: &HashSet<&Uid>) -> bool {
@@ -34,16 +37,19 @@ impl ChatFilter {
             ChatType::Kill(_, u) => self.death_all || self.death_group && group_members.contains(u),
             ChatType::GroupMeta(_) => true,   //todo
             ChatType::FactionMeta(_) => true, //todo
+            ChatType::GuildMeta(_) => true,
             ChatType::Tell(..) => true,
             ChatType::Say(_) => self.message_all || self.message_say,
             ChatType::Group(..) => self.message_all || self.message_group,
             ChatType::Faction(..) => self.message_all || self.message_faction,
+            ChatType::Guild(..) => self.message_all || self.message_faction,
             ChatType::Region(_) => self.message_all || self.message_region,
             ChatType::World(_) => self.message_all || self.message_world,
             ChatType::Npc(..) => true,
             ChatType::NpcSay(..) => true,
             ChatType::NpcTell(..) => true,
             ChatType::Meta => true,
+            ChatType::CombatLog => self.combat_log,
         }
     }
 }
@@ -62,6 +68,8 @@ impl Default for ChatFilter {
 
             death_all: false,
             death_group: true,
+
+            combat_log: false,
         }
     }
 }
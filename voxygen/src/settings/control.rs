@@ -138,6 +138,7 @@ impl ControlSettings {
             GameInput::Trade => KeyMouse::Key(VirtualKeyCode::R),
             GameInput::Social => KeyMouse::Key(VirtualKeyCode::O),
             GameInput::Crafting => KeyMouse::Key(VirtualKeyCode::C),
+            GameInput::DamageMeter => KeyMouse::Key(VirtualKeyCode::F7),
             GameInput::Spellbook => KeyMouse::Key(VirtualKeyCode::P),
             GameInput::Settings => KeyMouse::Key(VirtualKeyCode::N),
             GameInput::Help => KeyMouse::Key(VirtualKeyCode::F1),
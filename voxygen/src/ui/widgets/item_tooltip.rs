@@ -256,6 +256,11 @@ const V_PAD_STATS: f64 = 6.0;
 const IMAGE_W_FRAC: f64 = 0.3;
 // Item icon size
 const ICON_SIZE: [f64; 2] = [64.0, 64.0];
+/// Color of the modifiers of an item and the gems socketed into it
+const MODIFIER_COLOR: Color = Color::Rgba(0.55, 0.8, 1.0, 1.0);
+
+/// How many lines the modifiers and sockets of an item take up
+fn modifier_count(item: &dyn ItemDesc) -> usize { item.modifiers().len() + item.sockets().len() }
 
 /// A widget for displaying tooltips
 #[derive(Clone, WidgetCommon)]
@@ -444,7 +449,10 @@ impl<'a> Widget for ItemTooltip<'a> {
             if item.num_slots() != 0 && !is_bag {
                 count += 1
             }
-            count as usize
+            if item.durability().is_some() {
+                count += 1
+            }
+            count as usize + modifier_count(item)
         }
 
         let i18n = &self.localized_strings;
@@ -577,10 +585,15 @@ impl<'a> Widget for ItemTooltip<'a> {
         // Stats
         match item.kind() {
             ItemKind::Tool(tool) => {
-                let power = tool.base_power(self.msm, item.components()) * 10.0;
-                let speed = tool.base_speed(self.msm, item.components());
-                let poise_str = tool.base_poise_strength(self.msm, item.components()) * 10.0;
-                let crit_chance = tool.base_crit_chance(self.msm, item.components()) * 100.0;
+                let stats = tool
+                    .stats
+                    .resolve_stats(self.msm, item.components())
+                    .clamp_speed()
+                    .with_modifiers(&item.all_modifiers());
+                let power = stats.power * 10.0;
+                let speed = stats.speed;
+                let poise_str = stats.poise_strength * 10.0;
+                let crit_chance = stats.crit_chance * 100.0;
                 let combat_rating = combat::weapon_rating(&item, self.msm) * 10.0;
 
                 // Combat Rating
@@ -658,14 +671,12 @@ impl<'a> Widget for ItemTooltip<'a> {
 
                 if let Some(equipped_item) = equip_slot.cloned().next() {
                     if let ItemKind::Tool(equipped_tool) = equipped_item.kind() {
-                        let tool_stats = tool
-                            .stats
-                            .resolve_stats(self.msm, item.components())
-                            .clamp_speed();
+                        let tool_stats = stats;
                         let equipped_tool_stats = equipped_tool
                             .stats
                             .resolve_stats(self.msm, equipped_item.components())
-                            .clamp_speed();
+                            .clamp_speed()
+                            .with_modifiers(&equipped_item.all_modifiers());
                         let diff = tool_stats - equipped_tool_stats;
                         let power_diff =
                             util::comparison(tool_stats.power, equipped_tool_stats.power);
@@ -964,6 +975,77 @@ impl<'a> Widget for ItemTooltip<'a> {
             _ => (),
         }
 
+        // Durability
+        if let Some((durability, max_durability)) = item.durability() {
+            let index = stats_count(item) - modifier_count(item) - 1;
+            widget::Text::new(&if durability == 0 {
+                format!(
+                    "{} : {}",
+                    i18n.get("common.stats.durability"),
+                    i18n.get("common.stats.broken")
+                )
+            } else {
+                format!(
+                    "{} : {}/{}",
+                    i18n.get("common.stats.durability"),
+                    durability,
+                    max_durability
+                )
+            })
+            .x_align_to(state.ids.item_frame, conrod_core::position::Align::Start)
+            .graphics_for(id)
+            .parent(id)
+            .with_style(self.style.desc)
+            .color(if durability == 0 {
+                conrod_core::color::RED
+            } else {
+                text_color
+            })
+            .down_from(
+                if index > 0 {
+                    state.ids.stats[index - 1]
+                } else {
+                    state.ids.item_frame
+                },
+                V_PAD_STATS,
+            )
+            .set(state.ids.stats[index], ui);
+        }
+
+        // Modifiers and sockets
+        let modifier_lines = item
+            .modifiers()
+            .iter()
+            .map(|modifier| (util::modifier_desc(modifier, i18n), true))
+            .chain(
+                item.sockets()
+                    .iter()
+                    .map(|gem| (util::socket_desc(gem.as_deref(), i18n), gem.is_some())),
+            );
+        let first_index = stats_count(item) - modifier_count(item);
+        for (i, (text, active)) in modifier_lines.enumerate() {
+            let index = first_index + i;
+            widget::Text::new(&text)
+                .x_align_to(state.ids.item_frame, conrod_core::position::Align::Start)
+                .graphics_for(id)
+                .parent(id)
+                .with_style(self.style.desc)
+                .color(if active {
+                    MODIFIER_COLOR
+                } else {
+                    conrod_core::color::GREY
+                })
+                .down_from(
+                    if index > 0 {
+                        state.ids.stats[index - 1]
+                    } else {
+                        state.ids.item_frame
+                    },
+                    V_PAD_STATS,
+                )
+                .set(state.ids.stats[index], ui);
+        }
+
         // Description
         if !desc.is_empty() {
             widget::Text::new(&format!("\"{}\"", &desc))
@@ -1061,7 +1143,10 @@ impl<'a> Widget for ItemTooltip<'a> {
             if item.num_slots() != 0 && !is_bag {
                 count += 1
             }
-            count as usize
+            if item.durability().is_some() {
+                count += 1
+            }
+            count as usize + modifier_count(item)
         }
 
         let item = &self.item;
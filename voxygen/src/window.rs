@@ -57,6 +57,7 @@ pub enum GameInput {
     Trade,
     Social,
     Crafting,
+    DamageMeter,
     Spellbook,
     Settings,
     ToggleInterface,
@@ -114,6 +115,7 @@ impl GameInput {
             GameInput::Trade => "gameinput.trade",
             GameInput::Social => "gameinput.social",
             GameInput::Crafting => "gameinput.crafting",
+            GameInput::DamageMeter => "gameinput.damagemeter",
             GameInput::Spellbook => "gameinput.spellbook",
             GameInput::Settings => "gameinput.settings",
             GameInput::ToggleInterface => "gameinput.toggleinterface",
@@ -179,6 +181,7 @@ impl GameInput {
             GameInput::Trade,
             GameInput::Social,
             GameInput::Crafting,
+            GameInput::DamageMeter,
             GameInput::Spellbook,
             GameInput::Settings,
             GameInput::ToggleInterface,
@@ -1031,6 +1031,14 @@ impl<'a> Sampler<'a> for ColumnGen<'a> {
         let path = sim.get_nearest_path(wpos);
         let cave = sim.get_nearest_cave(wpos);
 
+        let (sites_allow_trees, sites_allow_wildlife) = sim_chunk
+            .sites
+            .iter()
+            .map(|site| index.sites[*site].spawn_rules(wpos))
+            .fold((true, true), |(trees, wildlife), rules| {
+                (trees && rules.trees, wildlife && rules.wildlife)
+            });
+
         Some(ColumnSample {
             alt,
             riverless_alt,
@@ -1053,11 +1061,7 @@ impl<'a> Sampler<'a> for ColumnGen<'a> {
             // No growing directly on bedrock.
             // And, no growing on sites that don't want them TODO: More precise than this when we
             // apply trees as a post-processing layer
-            tree_density: if sim_chunk
-                .sites
-                .iter()
-                .all(|site| index.sites[*site].spawn_rules(wpos).trees)
-            {
+            tree_density: if sites_allow_trees {
                 Lerp::lerp(0.0, tree_density, alt.sub(2.0).sub(basement).mul(0.5))
             } else {
                 0.0
@@ -1068,7 +1072,12 @@ impl<'a> Sampler<'a> for ColumnGen<'a> {
             rock,
             temp,
             humidity,
-            spawn_rate,
+            // No wild creatures on sites that don't want them
+            spawn_rate: if sites_allow_wildlife {
+                spawn_rate
+            } else {
+                0.0
+            },
             stone_col,
             water_dist,
             gradient,
@@ -43,6 +43,7 @@ pub use column::ColumnSample;
 pub use index::{IndexOwned, IndexRef};
 
 use crate::{
+    block::ZCache,
     column::ColumnGen,
     index::Index,
     site::SiteKind,
@@ -344,14 +345,6 @@ impl World {
             }
         }
 
-        let sample_get = |offs| {
-            zcache_grid
-                .get(grid_border + offs)
-                .map(Option::as_ref)
-                .flatten()
-                .map(|zc| &zc.sample)
-        };
-
         // Only use for rng affecting dynamic elements like chests and entities!
         let mut dynamic_rng = rand::thread_rng();
 
@@ -380,6 +373,85 @@ impl World {
             .iter()
             .for_each(|site| index.sites[*site].apply_to(&mut canvas, &mut dynamic_rng));
 
+        let supplement = self.generate_supplement_with(
+            index,
+            chunk_pos,
+            &chunk,
+            sim_chunk,
+            &zcache_grid,
+            grid_border,
+            &mut dynamic_rng,
+            time,
+        );
+
+        // Finally, defragment to minimize space consumption.
+        chunk.defragment();
+
+        Ok((chunk, supplement))
+    }
+
+    /// Generate the entities that populate an already generated chunk (such as
+    /// one loaded from a cache) without generating its terrain again.
+    pub fn generate_supplement(
+        &self,
+        index: IndexRef,
+        chunk_pos: Vec2<i32>,
+        chunk: &TerrainChunk,
+        time: Option<TimeOfDay>,
+    ) -> ChunkSupplement {
+        let sim_chunk = match self
+            .sim
+            .get_base_z(chunk_pos)
+            .and_then(|_| self.sim.get(chunk_pos))
+        {
+            Some(sim_chunk) => sim_chunk,
+            None => return ChunkSupplement::default(),
+        };
+
+        // Unlike the terrain, which needs a border around the chunk for the
+        // structures that reach into it, the supplement only samples the columns
+        // of the chunk itself
+        let mut sampler = self.sample_blocks();
+        let chunk_wpos2d = chunk_pos * TerrainChunkSize::RECT_SIZE.map(|e| e as i32);
+        let grid_border = 0;
+        let zcache_grid =
+            Grid::populate_from(TerrainChunkSize::RECT_SIZE.map(|e| e as i32), |offs| {
+                sampler.get_z_cache(chunk_wpos2d + offs, index)
+            });
+
+        self.generate_supplement_with(
+            index,
+            chunk_pos,
+            chunk,
+            sim_chunk,
+            &zcache_grid,
+            grid_border,
+            &mut rand::thread_rng(),
+            time,
+        )
+    }
+
+    fn generate_supplement_with(
+        &self,
+        index: IndexRef,
+        chunk_pos: Vec2<i32>,
+        chunk: &TerrainChunk,
+        sim_chunk: &sim::SimChunk,
+        zcache_grid: &Grid<Option<ZCache>>,
+        grid_border: i32,
+        // Only use for rng affecting dynamic elements like chests and entities!
+        dynamic_rng: &mut rand::rngs::ThreadRng,
+        time: Option<TimeOfDay>,
+    ) -> ChunkSupplement {
+        let chunk_wpos2d = chunk_pos * TerrainChunkSize::RECT_SIZE.map(|e| e as i32);
+        let sample_get = |offs| {
+            zcache_grid
+                .get(grid_border + offs)
+                .map(Option::as_ref)
+                .flatten()
+                .map(|zc| &zc.sample)
+        };
+
         let gen_entity_pos = |dynamic_rng: &mut rand::rngs::ThreadRng| {
             let lpos2d = TerrainChunkSize::RECT_SIZE
                 .map(|sz| dynamic_rng.gen::<u32>().rem_euclid(sz) as i32);
@@ -396,30 +468,28 @@ impl World {
             (Vec3::from(chunk_wpos2d) + lpos).map(|e: i32| e as f32) + 0.5
         };
 
-        let mut supplement = ChunkSupplement {
-            entities: Vec::new(),
-        };
+        let mut supplement = ChunkSupplement::default();
 
         if sim_chunk.contains_waypoint {
-            supplement.add_entity(EntityInfo::at(gen_entity_pos(&mut dynamic_rng)).into_waypoint());
+            supplement.add_entity(EntityInfo::at(gen_entity_pos(dynamic_rng)).into_waypoint());
         }
 
         // Apply layer supplement
         layer::apply_caves_supplement(
-            &mut dynamic_rng,
+            dynamic_rng,
             chunk_wpos2d,
             sample_get,
-            &chunk,
+            chunk,
             index,
             &mut supplement,
         );
 
         // Apply layer supplement
         layer::wildlife::apply_wildlife_supplement(
-            &mut dynamic_rng,
+            dynamic_rng,
             chunk_wpos2d,
             sample_get,
-            &chunk,
+            chunk,
             index,
             sim_chunk,
             &mut supplement,
@@ -429,7 +499,7 @@ impl World {
         // Apply site supplementary information
         sim_chunk.sites.iter().for_each(|site| {
             index.sites[*site].apply_supplement(
-                &mut dynamic_rng,
+                dynamic_rng,
                 chunk_wpos2d,
                 sample_get,
                 &mut supplement,
@@ -437,9 +507,6 @@ impl World {
             )
         });
 
-        // Finally, defragment to minimize space consumption.
-        chunk.defragment();
-
-        Ok((chunk, supplement))
+        supplement
     }
 }
@@ -274,7 +274,7 @@ impl<V: Copy + Default> LaborMap<V> {
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct AreaResources {
     pub resource_sum: GoodMap<f32>,
     pub resource_chunks: GoodMap<f32>,
@@ -291,7 +291,7 @@ impl Default for AreaResources {
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct NaturalResources {
     // resources per distance, we should increase labor cost for far resources
     pub per_area: Vec<AreaResources>,
@@ -369,13 +369,13 @@ impl Default for Labor {
     fn default() -> Self { *DUMMY_LABOR }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct TradeOrder {
     pub customer: Id<Site>,
     pub amount: GoodMap<f32>, // positive for orders, negative for exchange
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct TradeDelivery {
     pub supplier: Id<Site>,
     pub amount: GoodMap<f32>, // positive for orders, negative for exchange
@@ -383,7 +383,7 @@ pub struct TradeDelivery {
     pub supply: GoodMap<f32>, // maximum amount available, at the time of interaction
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct TradeInformation {
     pub orders: DHashMap<Id<Site>, Vec<TradeOrder>>, // per provider
     pub deliveries: DHashMap<Id<Site>, Vec<TradeDelivery>>, // per receiver
@@ -398,7 +398,7 @@ impl Default for TradeInformation {
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct NeighborInformation {
     pub id: Id<Site>,
     pub travel_distance: usize,
@@ -408,7 +408,7 @@ pub struct NeighborInformation {
     pub last_supplies: GoodMap<f32>,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Economy {
     // Population
     pub pop: f32,
@@ -440,6 +440,22 @@ pub struct Economy {
     pub neighbors: Vec<NeighborInformation>,
 }
 
+/// The parts of an [`Economy`] that change while it is being simulated. The
+/// rest (natural resources, neighbors) is recomputed whenever the world is
+/// generated, so this is everything that has to be stored to resume the
+/// simulation later on.
+///
+/// Goods and labors are stored by name rather than by index so that snapshots
+/// survive changes to the list of goods or professions.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct EconomySnapshot {
+    pub pop: f32,
+    pub stocks: Vec<(Good, f32)>,
+    pub values: Vec<(Good, f32)>,
+    pub labor_values: Vec<(Good, f32)>,
+    pub labors: Vec<(String, f32)>,
+}
+
 impl Default for Economy {
     fn default() -> Self {
         let coin_index: GoodIndex = GoodIndex::try_from(Coin).unwrap_or_default();
@@ -609,6 +625,65 @@ impl Economy {
             },
         }
     }
+
+    /// Add goods that were traded outside of the simulation (e.g. sold to the
+    /// site's merchants by a player). Negative amounts remove goods from the
+    /// stock.
+    pub fn add_stock(&mut self, good: Good, amount: f32) {
+        if let Ok(good) = GoodIndex::try_from(good) {
+            self.stocks[good] = (self.stocks[good] + amount).max(0.0);
+            self.unconsumed_stock[good] = (self.unconsumed_stock[good] + amount).max(0.0);
+        }
+    }
+
+    pub fn snapshot(&self) -> EconomySnapshot {
+        let goods = |map: &GoodMap<f32>| map.iter().map(|(g, a)| (Good::from(g), *a)).collect();
+        let values = |map: &GoodMap<Option<f32>>| {
+            map.iter()
+                .filter_map(|(g, v)| Some((Good::from(g), (*v)?)))
+                .collect()
+        };
+        EconomySnapshot {
+            pop: self.pop,
+            stocks: goods(&self.stocks),
+            values: values(&self.values),
+            labor_values: values(&self.labor_values),
+            labors: self
+                .labors
+                .iter()
+                .map(|(l, a)| (LABOR[l.into_usize()].name.clone(), *a))
+                .collect(),
+        }
+    }
+
+    /// Resume from a snapshot taken with [`Economy::snapshot`]. Goods and
+    /// labors that no longer exist are ignored.
+    pub fn restore(&mut self, snapshot: &EconomySnapshot) {
+        let goods = |list: &[(Good, f32)]| {
+            list.iter()
+                .filter_map(|(g, a)| Some((GoodIndex::try_from(*g).ok()?, *a)))
+                .collect::<Vec<_>>()
+        };
+        self.pop = snapshot.pop;
+        self.stocks = GoodMap::from_list(&goods(&snapshot.stocks), 0.0);
+        self.values = GoodMap::from_iter(
+            goods(&snapshot.values)
+                .into_iter()
+                .map(|(g, v)| (g, Some(v))),
+            None,
+        );
+        self.labor_values = GoodMap::from_iter(
+            goods(&snapshot.labor_values)
+                .into_iter()
+                .map(|(g, v)| (g, Some(v))),
+            None,
+        );
+        for (name, amount) in snapshot.labors.iter() {
+            if let Some(labor) = Labor::list().find(|l| &LABOR[l.into_usize()].name == name) {
+                self.labors[labor] = *amount;
+            }
+        }
+    }
 }
 
 pub fn good_list() -> impl Iterator<Item = GoodIndex> {
@@ -27,10 +27,17 @@ pub struct Colors {
 
 pub struct SpawnRules {
     pub trees: bool,
+    /// Whether wild creatures may spawn here.
+    pub wildlife: bool,
 }
 
 impl Default for SpawnRules {
-    fn default() -> Self { Self { trees: true } }
+    fn default() -> Self {
+        Self {
+            trees: true,
+            wildlife: true,
+        }
+    }
 }
 
 pub struct Site {
@@ -518,6 +518,18 @@ impl Archetype for House {
                             // Ceiling
                             return floor;
                         }
+                    } else if !attr.storey_fill.has_lower()
+                        && profile.y == 1
+                        && center_offset == Vec2::zero()
+                    {
+                        // The middle of every open workshop holds a bank or a mailbox
+                        let station = if self.noise.get(Vec3::zero()) % 2 == 0 {
+                            SpriteKind::Strongbox
+                        } else {
+                            SpriteKind::Mailbox
+                        };
+
+                        return BlockMask::new(Block::air(station).with_ori(end_ori).unwrap(), 1);
                     } else if !attr.storey_fill.has_lower()
                         && center_offset.sum() % 2 == 0
                         && profile.y == 1
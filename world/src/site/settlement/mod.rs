@@ -970,6 +970,22 @@ impl Settlement {
 
                     supplement.add_entity(entity);
                 }
+
+                // Storage chests stand at fixed spots around town so that the same chest, and
+                // whatever was left in it, is found again each time the chunk is loaded
+                if matches!(sample.plot, Some(Plot::Town { .. }))
+                    && RandomField::new(self.seed + 2)
+                        .chance(Vec3::from(wpos2d), 1.0 / (50.0 * 80.0))
+                {
+                    supplement.add_entity(
+                        EntityInfo::at(Vec3::new(
+                            wpos2d.x as f32,
+                            wpos2d.y as f32,
+                            col_sample.alt.floor(),
+                        ))
+                        .into_storage(),
+                    );
+                }
             }
         }
     }
@@ -14,6 +14,7 @@ use crate::{
 };
 use common::{
     astar::Astar,
+    farming,
     lottery::Lottery,
     spiral::Spiral2d,
     store::{Id, Store},
@@ -33,7 +34,18 @@ use vek::*;
 /// likely to produce entirely different outcomes if some detail of a generation
 /// algorithm changes slightly. This is generally good and makes worldgen code
 /// easier to maintain and less liable to breaking changes.
-fn reseed(rng: &mut impl Rng) -> impl Rng { ChaChaRng::from_seed(rng.gen::<[u8; 32]>()) }
+fn reseed(rng: &mut impl Rng) -> ChaChaRng { ChaChaRng::from_seed(rng.gen::<[u8; 32]>()) }
+
+/// A house that players can buy
+#[derive(Clone, Debug)]
+pub struct HouseForSale {
+    /// The position of the plot of the house, which identifies it
+    pub pos: Vec2<i32>,
+    /// The blocks that belong to the house
+    pub volume: Aabb<i32>,
+    /// The height of the ground floor
+    pub floor: i32,
+}
 
 #[derive(Default)]
 pub struct Site {
@@ -42,6 +54,7 @@ pub struct Site {
     plots: Store<Plot>,
     plazas: Vec<Id<Plot>>,
     roads: Vec<Id<Plot>>,
+    town_wall: Option<Id<Plot>>,
 }
 
 impl Site {
@@ -63,6 +76,10 @@ impl Site {
                 self.wpos_tile(wpos + rpos * tile::TILE_SIZE as i32)
                     .is_empty()
             }),
+            wildlife: match self.town_wall.map(|p| &self.plot(p).kind) {
+                Some(PlotKind::TownWall(wall)) => !wall.contains(self.wpos_tile_pos(wpos)),
+                _ => true,
+            },
         }
     }
 
@@ -80,6 +97,49 @@ impl Site {
 
     pub fn create_plot(&mut self, plot: Plot) -> Id<Plot> { self.plots.insert(plot) }
 
+    fn plot_positions(
+        &self,
+        f: impl Fn(&PlotKind) -> bool + 'static,
+    ) -> impl Iterator<Item = Vec2<i32>> + '_ {
+        self.plots
+            .values()
+            .filter(move |plot| f(&plot.kind))
+            .map(move |plot| self.tile_center_wpos(plot.root_tile))
+    }
+
+    /// The positions of the houses that people of the site live in
+    pub fn houses(&self) -> impl Iterator<Item = Vec2<i32>> + '_ {
+        self.plots
+            .values()
+            .filter(|plot| matches!(plot.kind, PlotKind::House(_)) && !plot.is_for_sale())
+            .map(move |plot| self.tile_center_wpos(plot.root_tile))
+    }
+
+    /// The houses of the site that are reserved for players to buy
+    pub fn houses_for_sale(&self) -> impl Iterator<Item = HouseForSale> + '_ {
+        self.plots
+            .values()
+            .filter(|plot| plot.is_for_sale())
+            .filter_map(move |plot| match &plot.kind {
+                PlotKind::House(house) => Some(HouseForSale {
+                    pos: self.tile_center_wpos(plot.root_tile),
+                    volume: house.volume(),
+                    floor: house.floor(),
+                }),
+                _ => None,
+            })
+    }
+
+    /// The positions of the farms and workshops that people of the site work at
+    pub fn workplaces(&self) -> impl Iterator<Item = Vec2<i32>> + '_ {
+        self.plot_positions(|kind| matches!(kind, PlotKind::Farm(_) | PlotKind::Workshop(_)))
+    }
+
+    /// The positions of the plazas of the site
+    pub fn plaza_positions(&self) -> impl Iterator<Item = Vec2<i32>> + '_ {
+        self.plot_positions(|kind| matches!(kind, PlotKind::Plaza))
+    }
+
     pub fn blit_aabr(&mut self, aabr: Aabr<i32>, tile: Tile) {
         for y in 0..aabr.size().h {
             for x in 0..aabr.size().w {
@@ -168,19 +228,31 @@ impl Site {
             self.tiles
                 .grow_aabr(center, area_range.clone(), min_dims)
                 .ok()
-                .filter(|aabr| {
-                    (aabr.min.x..aabr.max.x)
-                        .any(|x| self.tiles.get(Vec2::new(x, aabr.min.y - 1)).is_road())
-                        || (aabr.min.x..aabr.max.x)
-                            .any(|x| self.tiles.get(Vec2::new(x, aabr.max.y)).is_road())
-                        || (aabr.min.y..aabr.max.y)
-                            .any(|y| self.tiles.get(Vec2::new(aabr.min.x - 1, y)).is_road())
-                        || (aabr.min.y..aabr.max.y)
-                            .any(|y| self.tiles.get(Vec2::new(aabr.max.x, y)).is_road())
-                })
+                .filter(|aabr| self.road_side(*aabr).is_some())
         })
     }
 
+    /// Find a side of the given tile area that borders a road, if any.
+    pub fn road_side(&self, aabr: Aabr<i32>) -> Option<Ori> {
+        if (aabr.min.x..aabr.max.x).any(|x| self.tiles.get(Vec2::new(x, aabr.max.y)).is_road()) {
+            Some(Ori::North)
+        } else if (aabr.min.y..aabr.max.y)
+            .any(|y| self.tiles.get(Vec2::new(aabr.max.x, y)).is_road())
+        {
+            Some(Ori::East)
+        } else if (aabr.min.x..aabr.max.x)
+            .any(|x| self.tiles.get(Vec2::new(x, aabr.min.y - 1)).is_road())
+        {
+            Some(Ori::South)
+        } else if (aabr.min.y..aabr.max.y)
+            .any(|y| self.tiles.get(Vec2::new(aabr.min.x - 1, y)).is_road())
+        {
+            Some(Ori::West)
+        } else {
+            None
+        }
+    }
+
     pub fn find_roadside_aabr(
         &mut self,
         rng: &mut impl Rng,
@@ -258,6 +330,71 @@ impl Site {
         plaza
     }
 
+    /// Enclose the site in a wall, extending the outermost roads on each side
+    /// through it to form gates.
+    pub fn make_town_wall(&mut self, land: &Land, rng: &mut impl Rng) -> Option<Id<Plot>> {
+        // Leave room for a street between the outermost plots and the wall
+        let aabr = Aabr {
+            min: self.tiles.bounds.min - 2,
+            max: self.tiles.bounds.max + 3,
+        };
+        if aabr.min.reduce_min() <= -(tile::TILE_RADIUS as i32) + 2
+            || aabr.max.reduce_max() >= tile::TILE_RADIUS as i32 - 2
+        {
+            return None;
+        }
+
+        for &ori in [Ori::North, Ori::East, Ori::South, Ori::West].iter() {
+            let dir = ori.dir();
+            let from = if let Some(from) = self
+                .roads
+                .iter()
+                .filter_map(|&road| match &self.plot(road).kind {
+                    PlotKind::Road(path) => Some(path.nodes().iter().copied()),
+                    _ => None,
+                })
+                .flatten()
+                .max_by_key(|tile| tile.dot(dir))
+            {
+                from
+            } else {
+                continue;
+            };
+            // Keep gates away from the corners of the wall
+            let gate_x = from.x.max(aabr.min.x + 2).min(aabr.max.x - 3);
+            let gate_y = from.y.max(aabr.min.y + 2).min(aabr.max.y - 3);
+            let to = match ori {
+                Ori::North => Vec2::new(gate_x, aabr.max.y + 1),
+                Ori::East => Vec2::new(aabr.max.x + 1, gate_y),
+                Ori::South => Vec2::new(gate_x, aabr.min.y - 2),
+                Ori::West => Vec2::new(aabr.min.x - 2, gate_y),
+            };
+            self.create_road(land, rng, from, to, 1);
+        }
+
+        let plot = self.create_plot(Plot {
+            kind: PlotKind::TownWall(plot::TownWall::generate(land, self, aabr)),
+            root_tile: aabr.center(),
+            tiles: plot::TownWall::ring(aabr).collect(),
+            seed: rng.gen(),
+        });
+
+        for tile in plot::TownWall::ring(aabr) {
+            let kind = match &self.tiles.get(tile).kind {
+                TileKind::Hazard(HazardKind::Water) => continue,
+                TileKind::Road { .. } => TileKind::Gate,
+                _ => plot::TownWall::tile_kind_at(aabr, tile),
+            };
+            self.tiles.set(tile, Tile {
+                kind,
+                plot: Some(plot),
+            });
+        }
+
+        self.town_wall = Some(plot);
+        Some(plot)
+    }
+
     pub fn demarcate_obstacles(&mut self, land: &Land) {
         const SEARCH_RADIUS: u32 = 96;
 
@@ -277,6 +414,11 @@ impl Site {
 
     pub fn generate(land: &Land, rng: &mut impl Rng, origin: Vec2<i32>) -> Self {
         let mut rng = reseed(rng);
+        // Building templates are chosen from a stream of their own, so that plots
+        // for which the built-in generator is chosen come out the same as without
+        // any templates
+        let mut template_rng = rng.clone();
+        template_rng.set_stream(1);
 
         let mut site = Site {
             origin,
@@ -287,7 +429,7 @@ impl Site {
 
         site.make_plaza(land, &mut rng);
 
-        let build_chance = Lottery::from(vec![(64.0, 1), (5.0, 2), (8.0, 3), (0.75, 4)]);
+        let build_chance = Lottery::from(vec![(64.0, 1), (5.0, 2), (8.0, 3), (0.75, 4), (6.0, 5)]);
 
         let mut castles = 0;
 
@@ -303,14 +445,29 @@ impl Site {
                             Extent2::broadcast(size),
                         )
                     }) {
+                        let kind =
+                            plot::choose_template(plot::TemplateKind::House, &mut template_rng)
+                                .and_then(|template| {
+                                    plot::Template::generate(
+                                        land,
+                                        &mut reseed(&mut template_rng),
+                                        &site,
+                                        aabr,
+                                        template,
+                                    )
+                                })
+                                .map(PlotKind::Template)
+                                .unwrap_or_else(|| {
+                                    PlotKind::House(plot::House::generate(
+                                        land,
+                                        &mut reseed(&mut rng),
+                                        &site,
+                                        door_tile,
+                                        aabr,
+                                    ))
+                                });
                         let plot = site.create_plot(Plot {
-                            kind: PlotKind::House(plot::House::generate(
-                                land,
-                                &mut reseed(&mut rng),
-                                &site,
-                                door_tile,
-                                aabr,
-                            )),
+                            kind,
                             root_tile: aabr.center(),
                             tiles: aabr_tiles(aabr).collect(),
                             seed: rng.gen(),
@@ -371,15 +528,48 @@ impl Site {
                             site.tiles.grow_organic(&mut rng, center, 12..64).ok()
                         })
                     })
-                    .map(|(tiles, _)| {
+                    .map(|(tiles, root_tile)| {
+                        let plot = site.create_plot(Plot {
+                            kind: PlotKind::Farm(plot::Farm::generate(
+                                &mut reseed(&mut rng),
+                                &site,
+                                root_tile,
+                            )),
+                            root_tile,
+                            tiles: tiles.clone(),
+                            seed: rng.gen(),
+                        });
                         for tile in tiles {
                             site.tiles.set(tile, Tile {
                                 kind: TileKind::Field,
-                                plot: None,
+                                plot: Some(plot),
                             });
                         }
                     });
                 },
+                // Workshop
+                5 => {
+                    if let Some((aabr, _)) = attempt(16, || {
+                        site.find_roadside_aabr(&mut rng, 4..9, Extent2::new(2, 2))
+                    }) {
+                        let plot = site.create_plot(Plot {
+                            kind: PlotKind::Workshop(plot::Workshop::generate(
+                                land,
+                                &mut reseed(&mut rng),
+                                &site,
+                                aabr,
+                            )),
+                            root_tile: aabr.center(),
+                            tiles: aabr_tiles(aabr).collect(),
+                            seed: rng.gen(),
+                        });
+
+                        site.blit_aabr(aabr, Tile {
+                            kind: TileKind::Building,
+                            plot: Some(plot),
+                        });
+                    }
+                },
                 // Castle
                 4 if castles < 1 => {
                     if let Some((aabr, _entrance_tile)) = attempt(10, || {
@@ -522,6 +712,8 @@ impl Site {
             }
         }
 
+        site.make_town_wall(land, &mut rng);
+
         site
     }
 
@@ -597,70 +789,71 @@ impl Site {
 
     pub fn render(&self, canvas: &mut Canvas, dynamic_rng: &mut impl Rng) {
         canvas.foreach_col(|canvas, wpos2d, col| {
-
             let tpos = self.wpos_tile_pos(wpos2d);
-            let near_roads = SQUARE_9
-                .iter()
-                .filter_map(|rpos| {
-                    let tile = self.tiles.get(tpos + rpos);
-                    if let TileKind::Road { a, b, w } = &tile.kind {
-                        if let Some(PlotKind::Road(path)) = tile.plot.map(|p| &self.plot(p).kind) {
-                            Some((LineSegment2 {
-                                start: self.tile_center_wpos(path.nodes()[*a as usize]).map(|e| e as f32),
-                                end: self.tile_center_wpos(path.nodes()[*b as usize]).map(|e| e as f32),
-                            }, *w))
-                        } else {
-                            None
-                        }
+            let near_roads = SQUARE_9.iter().filter_map(|rpos| {
+                let tile = self.tiles.get(tpos + rpos);
+                if let TileKind::Road { a, b, w } = &tile.kind {
+                    if let Some(PlotKind::Road(path)) = tile.plot.map(|p| &self.plot(p).kind) {
+                        Some((
+                            LineSegment2 {
+                                start: self
+                                    .tile_center_wpos(path.nodes()[*a as usize])
+                                    .map(|e| e as f32),
+                                end: self
+                                    .tile_center_wpos(path.nodes()[*b as usize])
+                                    .map(|e| e as f32),
+                            },
+                            *w,
+                        ))
                     } else {
                         None
                     }
-                });
+                } else {
+                    None
+                }
+            });
 
             let wpos2df = wpos2d.map(|e| e as f32);
-            let dist  = near_roads
+            let dist = near_roads
                 .map(|(line, w)| (line.distance_to_point(wpos2df) - w as f32 * 2.0).max(0.0))
                 .min_by_key(|d| (*d * 100.0) as i32);
 
             if dist.map_or(false, |d| d <= 0.75) {
                 let alt = canvas.col(wpos2d).map_or(0, |col| col.alt as i32);
-                (-6..4).for_each(|z| canvas.map(
-                    Vec3::new(wpos2d.x, wpos2d.y, alt + z),
-                    |b| if z >= 0 {
-                        if b.is_filled() {
-                            Block::empty()
+                (-6..4).for_each(|z| {
+                    canvas.map(Vec3::new(wpos2d.x, wpos2d.y, alt + z), |b| {
+                        if z >= 0 {
+                            if b.is_filled() {
+                                Block::empty()
+                            } else {
+                                b.with_sprite(SpriteKind::Empty)
+                            }
                         } else {
-                            b.with_sprite(SpriteKind::Empty)
+                            Block::new(BlockKind::Rock, Rgb::new(55, 45, 50))
                         }
-                    } else {
-                        Block::new(BlockKind::Rock, Rgb::new(55, 45, 50))
-                    },
-                ));
+                    })
+                });
             }
 
             let tile = self.wpos_tile(wpos2d);
             let seed = tile.plot.map_or(0, |p| self.plot(p).seed);
+
             #[allow(clippy::single_match)]
             match tile.kind {
-                TileKind::Field /*| TileKind::Road*/ => (-4..5).for_each(|z| canvas.map(
-                    Vec3::new(wpos2d.x, wpos2d.y, col.alt as i32 + z),
-                    |b| if [
-                        BlockKind::Grass,
-                        BlockKind::Earth,
-                        BlockKind::Sand,
-                        BlockKind::Snow,
-                        BlockKind::Rock,
-                    ]
-                    .contains(&b.kind()) {
-                        match tile.kind {
-                            TileKind::Field => Block::new(BlockKind::Earth, Rgb::new(40, 5 + (seed % 32) as u8, 0)),
-                            TileKind::Road { .. } => Block::new(BlockKind::Rock, Rgb::new(55, 45, 65)),
-                            _ => unreachable!(),
-                        }
-                    } else {
-                        b.with_sprite(SpriteKind::Empty)
-                    },
-                )),
+                TileKind::Field /*| TileKind::Road*/ => {
+                    let sprite = match tile.plot.map(|p| &self.plot(p).kind) {
+                        Some(PlotKind::Farm(farm)) => farm.sprite_at(wpos2d),
+                        _ => None,
+                    };
+                    let mut blocks = [Block::empty(); 9];
+                    for (block, z) in blocks.iter_mut().zip(-4..5) {
+                        *block = canvas.get(wpos2d.with_z(col.alt as i32 + z));
+                    }
+                    render_field_column(&mut blocks, seed, sprite);
+                    for (block, z) in blocks.iter().zip(-4..5) {
+                        canvas.set(wpos2d.with_z(col.alt as i32 + z), *block);
+                    }
+                },
                 // TileKind::Building => {
                 //     let base_alt = tile.plot.map(|p| self.plot(p)).map_or(col.alt as i32, |p| p.base_alt);
                 //     for z in base_alt - 12..base_alt + 16 {
@@ -710,11 +903,18 @@ impl Site {
             let (prim_tree, fills) = match &self.plots[plot].kind {
                 PlotKind::House(house) => house.render_collect(self),
                 PlotKind::Castle(castle) => castle.render_collect(self),
+                PlotKind::Template(template) => template.render_collect(self),
+                PlotKind::Workshop(workshop) => workshop.render_collect(self),
+                PlotKind::TownWall(town_wall) => town_wall.render_collect(self),
                 _ => continue,
             };
 
+            let chunk_aabr = canvas.area();
             for (prim, fill) in fills {
-                let aabb = fill.get_bounds(&prim_tree, prim);
+                // Only sample the part of each fill that lies within this chunk
+                let mut aabb = fill.get_bounds(&prim_tree, prim);
+                aabb.min = Vec2::max(aabb.min.xy(), chunk_aabr.min).with_z(aabb.min.z);
+                aabb.max = Vec2::min(aabb.max.xy(), chunk_aabr.max).with_z(aabb.max.z);
 
                 for x in aabb.min.x..aabb.max.x {
                     for y in aabb.min.y..aabb.max.y {
@@ -734,6 +934,34 @@ impl Site {
 
 pub fn test_site() -> Site { Site::generate(&Land::empty(), &mut thread_rng(), Vec2::zero()) }
 
+/// Turns a column of a field into tilled earth, from 4 blocks below its
+/// altitude to 4 above, and plants the given sprite on the first empty block
+/// above the new surface.
+fn render_field_column(blocks: &mut [Block], seed: u32, sprite: Option<SpriteKind>) {
+    for block in blocks.iter_mut() {
+        *block = if [
+            BlockKind::Grass,
+            BlockKind::Earth,
+            BlockKind::Sand,
+            BlockKind::Snow,
+            BlockKind::Rock,
+        ]
+        .contains(&block.kind())
+        {
+            farming::tilled_earth(seed)
+        } else {
+            block.with_sprite(SpriteKind::Empty)
+        };
+    }
+
+    // Crops are planted after the field is cleared, so that they aren't cleared too
+    if let Some(sprite) = sprite {
+        if let Some(block) = blocks.iter_mut().find(|block| !block.is_filled()) {
+            *block = block.with_sprite(sprite);
+        }
+    }
+}
+
 fn wpos_is_hazard(land: &Land, wpos: Vec2<i32>) -> Option<HazardKind> {
     if land
         .get_chunk_at(wpos)
@@ -754,3 +982,28 @@ pub fn aabr_tiles(aabr: Aabr<i32>) -> impl Iterator<Item = Vec2<i32>> {
 }
 
 pub struct Plaza {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crops_survive_field_rendering() {
+        let mut blocks = [Block::empty(); 9];
+        for block in &mut blocks[..5] {
+            *block = Block::new(BlockKind::Grass, Rgb::new(0, 100, 0));
+        }
+        // Wild grass growing on the surface is cleared to make room for the crop
+        blocks[5] = blocks[5].with_sprite(SpriteKind::LongGrass);
+
+        render_field_column(&mut blocks, 0, Some(SpriteKind::Corn));
+
+        assert!(blocks[..5].iter().all(|b| b.kind() == BlockKind::Earth));
+        assert_eq!(blocks[5].get_sprite(), Some(SpriteKind::Corn));
+        assert!(
+            blocks[6..]
+                .iter()
+                .all(|b| b.get_sprite() == Some(SpriteKind::Empty))
+        );
+    }
+}
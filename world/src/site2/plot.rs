@@ -1,7 +1,18 @@
 mod castle;
+mod farm;
 mod house;
+mod template;
+mod town_wall;
+mod workshop;
 
-pub use self::{castle::Castle, house::House};
+pub use self::{
+    castle::Castle,
+    farm::Farm,
+    house::House,
+    template::{choose_template, Template, TemplateKind},
+    town_wall::TownWall,
+    workshop::Workshop,
+};
 
 use super::*;
 use crate::util::DHashSet;
@@ -15,7 +26,16 @@ pub struct Plot {
     pub(crate) seed: u32,
 }
 
+/// One in this many houses is reserved for players to buy instead of having
+/// people live in it
+const HOUSES_PER_HOUSE_FOR_SALE: u32 = 4;
+
 impl Plot {
+    /// Whether the plot is a house that is reserved for players to buy
+    pub fn is_for_sale(&self) -> bool {
+        matches!(self.kind, PlotKind::House(_)) && self.seed % HOUSES_PER_HOUSE_FOR_SALE == 0
+    }
+
     pub fn find_bounds(&self) -> Aabr<i32> {
         self.tiles
             .iter()
@@ -29,5 +49,9 @@ pub enum PlotKind {
     House(House),
     Plaza,
     Castle(Castle),
+    Template(Template),
     Road(Path<Vec2<i32>>),
+    Farm(Farm),
+    Workshop(Workshop),
+    TownWall(TownWall),
 }
@@ -0,0 +1,52 @@
+use super::*;
+use common::terrain::SpriteKind;
+use rand::prelude::*;
+use vek::*;
+
+/// A field of crops, planted in rows.
+pub struct Farm {
+    crop: SpriteKind,
+    rows_along_x: bool,
+    scarecrow: Vec2<i32>,
+}
+
+impl Farm {
+    pub fn generate(rng: &mut impl Rng, site: &Site, root_tile: Vec2<i32>) -> Self {
+        const CROPS: [SpriteKind; 9] = [
+            SpriteKind::WheatYellow,
+            SpriteKind::WheatGreen,
+            SpriteKind::Corn,
+            SpriteKind::Cabbage,
+            SpriteKind::Carrot,
+            SpriteKind::Tomato,
+            SpriteKind::Radish,
+            SpriteKind::Turnip,
+            SpriteKind::Flax,
+        ];
+
+        Self {
+            crop: *CROPS.choose(rng).unwrap(),
+            rows_along_x: rng.gen(),
+            scarecrow: site.tile_center_wpos(root_tile),
+        }
+    }
+
+    /// The sprite that should be placed on the surface of the field at the
+    /// given position, if any.
+    pub fn sprite_at(&self, wpos2d: Vec2<i32>) -> Option<SpriteKind> {
+        let across = if self.rows_along_x {
+            wpos2d.y
+        } else {
+            wpos2d.x
+        };
+
+        if wpos2d == self.scarecrow {
+            Some(SpriteKind::Scarecrow)
+        } else if across.rem_euclid(3) == 0 {
+            // Furrow between rows
+            None
+        } else {
+            Some(self.crop)
+        }
+    }
+}
@@ -4,6 +4,13 @@ use common::terrain::{Block, BlockKind, SpriteKind};
 use rand::prelude::*;
 use vek::*;
 
+/// Height of each storey of a house
+const STOREY: i32 = 5;
+/// Depth of the foundations below the ground floor
+const FOUNDATIONS: i32 = 12;
+/// How far the roof overhangs the walls
+const ROOF_LIP: i32 = 2;
+
 pub struct House {
     _door_tile: Vec2<i32>,
     tile_aabr: Aabr<i32>,
@@ -44,6 +51,28 @@ impl House {
             },
         }
     }
+
+    /// The height of the ground floor
+    pub fn floor(&self) -> i32 { self.alt }
+
+    /// The volume the house takes up, from its foundations to the tip of its
+    /// roof
+    pub fn volume(&self) -> Aabb<i32> {
+        Aabb {
+            min: (self.bounds.min - ROOF_LIP).with_z(self.alt - FOUNDATIONS),
+            max: (self.bounds.max + ROOF_LIP)
+                .with_z(self.alt + STOREY * self.levels as i32 + self.roof_height()),
+        }
+    }
+
+    fn roof_height(&self) -> i32 {
+        (self.bounds.min - self.bounds.max)
+            .map(|e| e.abs())
+            .reduce_min()
+            / 2
+            + ROOF_LIP
+            + 1
+    }
 }
 
 impl Structure for House {
@@ -53,9 +82,9 @@ impl Structure for House {
         mut prim: F,
         mut fill: G,
     ) {
-        let storey = 5;
+        let storey = STOREY;
         let roof = storey * self.levels as i32;
-        let foundations = 12;
+        let foundations = FOUNDATIONS;
 
         // Walls
         let inner = prim(Primitive::Aabb(Aabb {
@@ -156,13 +185,8 @@ impl Structure for House {
             );
         }
 
-        let roof_lip = 2;
-        let roof_height = (self.bounds.min - self.bounds.max)
-            .map(|e| e.abs())
-            .reduce_min()
-            / 2
-            + roof_lip
-            + 1;
+        let roof_lip = ROOF_LIP;
+        let roof_height = self.roof_height();
 
         // Roof
         fill(
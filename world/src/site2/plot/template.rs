@@ -0,0 +1,612 @@
+use super::*;
+use crate::{
+    util::{RandomField, Sampler},
+    Land,
+};
+use common::{
+    assets::{self, AssetExt, AssetHandle},
+    terrain::{Block, BlockKind, SpriteKind},
+};
+use hashbrown::HashMap;
+use rand::prelude::*;
+use serde::Deserialize;
+use std::fmt;
+use tracing::warn;
+use vek::*;
+
+/// How many times a `Repeat` may repeat its body, so that a template can't make
+/// a plot take forever to generate
+const MAX_REPEATS: i64 = 256;
+
+/// The kinds of plot that may be generated from a [`BuildingTemplate`] rather
+/// than from a hard-coded generator.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Deserialize)]
+pub enum TemplateKind {
+    House,
+}
+
+#[derive(Deserialize)]
+struct TemplateChoices {
+    /// Relative weight of the hard-coded generator for this kind of plot.
+    builtin: f32,
+    /// Asset specifiers of templates, with their relative weights.
+    templates: Vec<(f32, String)>,
+}
+
+#[derive(Deserialize)]
+struct TemplateManifest(HashMap<TemplateKind, TemplateChoices>);
+
+impl assets::Asset for TemplateManifest {
+    type Loader = assets::RonLoader;
+
+    const EXTENSION: &'static str = "ron";
+}
+
+/// Choose a template for a plot of the given kind, or `None` if the built-in
+/// generator should be used instead.
+pub fn choose_template(
+    kind: TemplateKind,
+    rng: &mut impl Rng,
+) -> Option<AssetHandle<BuildingTemplate>> {
+    let manifest = TemplateManifest::load_expect("world.site2.manifest").read();
+    let choices = manifest.0.get(&kind)?;
+    let total = choices.builtin + choices.templates.iter().map(|(w, _)| w).sum::<f32>();
+    let mut x = rng.gen::<f32>() * total - choices.builtin;
+    for (weight, specifier) in &choices.templates {
+        if x < 0.0 {
+            break;
+        }
+        x -= weight;
+        if x < 0.0 {
+            return BuildingTemplate::load(specifier)
+                .map_err(|err| warn!(?err, ?specifier, "Failed to load building template"))
+                .ok();
+        }
+    }
+    None
+}
+
+/// An integer expression, evaluated against the variables bound for a plot.
+///
+/// The following variables are always bound:
+/// - `min_x`, `min_y`, `max_x`, `max_y`: the bounds of the plot in world
+///   coordinates
+/// - `width`, `length`: the size of the plot along the x and y axes
+/// - `alt`: the altitude of the ground floor
+/// - `levels`: the number of storeys, chosen from the template's `levels`
+/// - `ori`: the side of the plot facing a road (0 = north, 1 = east, 2 = south,
+///   3 = west)
+/// - `door_x`, `door_y`: the middle of the side of the plot facing a road
+/// - `tile_size`: the size of a site tile, in blocks
+/// - `seed`: the seed of the plot
+#[derive(Clone, Debug, Deserialize)]
+pub enum Expr {
+    Const(i32),
+    Var(String),
+    Add(Box<Expr>, Box<Expr>),
+    Sub(Box<Expr>, Box<Expr>),
+    Mul(Box<Expr>, Box<Expr>),
+    Div(Box<Expr>, Box<Expr>),
+    Min(Box<Expr>, Box<Expr>),
+    Max(Box<Expr>, Box<Expr>),
+    /// Pick one of the options using the first expression as an index (modulo
+    /// the number of options).
+    Choose(Box<Expr>, Vec<Expr>),
+    /// A value in `0..n`, derived from the plot seed and the given salt.
+    Rand(u32, Box<Expr>),
+}
+
+pub type Vec2Expr = (Expr, Expr);
+pub type Vec3Expr = (Expr, Expr, Expr);
+
+/// A description of a [`Primitive`], in terms of [`Expr`]s.
+#[derive(Clone, Debug, Deserialize)]
+pub enum PrimitiveSpec {
+    Empty,
+
+    // Shapes
+    Aabb {
+        min: Vec3Expr,
+        max: Vec3Expr,
+    },
+    Pyramid {
+        min: Vec3Expr,
+        max: Vec3Expr,
+        inset: Expr,
+    },
+    Cylinder {
+        min: Vec3Expr,
+        max: Vec3Expr,
+    },
+    Cone {
+        min: Vec3Expr,
+        max: Vec3Expr,
+    },
+    Sphere {
+        min: Vec3Expr,
+        max: Vec3Expr,
+    },
+    Plane {
+        min: Vec2Expr,
+        max: Vec2Expr,
+        origin: Vec3Expr,
+        gradient: (f32, f32),
+    },
+
+    // Combinators
+    And(Box<PrimitiveSpec>, Box<PrimitiveSpec>),
+    Or(Box<PrimitiveSpec>, Box<PrimitiveSpec>),
+    Xor(Box<PrimitiveSpec>, Box<PrimitiveSpec>),
+    Diff(Box<PrimitiveSpec>, Box<PrimitiveSpec>),
+    // Operators
+    /// Rotate by a matrix, given as rows.
+    Rotate(
+        Box<PrimitiveSpec>,
+        ((i32, i32, i32), (i32, i32, i32), (i32, i32, i32)),
+    ),
+    /// The union of `body` for each value of `var` in `from..to`, advancing by
+    /// `step` each time, at most 256 times.
+    Repeat {
+        var: String,
+        from: Expr,
+        to: Expr,
+        step: Expr,
+        body: Box<PrimitiveSpec>,
+    },
+    /// A primitive previously declared in the template's `prims`.
+    Named(String),
+}
+
+/// A description of a [`Fill`].
+#[derive(Clone, Debug, Deserialize)]
+pub enum FillSpec {
+    Air,
+    Block(BlockKind, (u8, u8, u8)),
+    Brick(BlockKind, (u8, u8, u8), u8),
+    Sprite(SpriteKind, u8),
+    /// A block of the given kind, coloured with the roof colour chosen for the
+    /// plot.
+    Roof(BlockKind),
+}
+
+/// A building described as a tree of primitives and fills, loaded from
+/// `assets/world/site2/building`.
+#[derive(Clone, Debug, Deserialize)]
+pub struct BuildingTemplate {
+    /// Range of the number of storeys (upper bound exclusive).
+    levels: (u32, u32),
+    roof_colors: Vec<(u8, u8, u8)>,
+    /// Additional variables, evaluated in order. Later variables may refer to
+    /// earlier ones.
+    vars: Vec<(String, Expr)>,
+    /// Primitives that may be referred to with `Named` by later primitives
+    /// and fills.
+    prims: Vec<(String, PrimitiveSpec)>,
+    /// Fills, applied in order.
+    fills: Vec<(PrimitiveSpec, FillSpec)>,
+}
+
+impl assets::Asset for BuildingTemplate {
+    type Loader = assets::RonLoader;
+
+    const EXTENSION: &'static str = "ron";
+}
+
+#[derive(Debug)]
+pub enum TemplateError {
+    UnknownVar(String),
+    UnknownPrimitive(String),
+    DivideByZero,
+    /// `Choose` was given no options to choose from
+    EmptyChoice,
+    /// `Rand` was asked for a value in an empty range
+    EmptyRange(i32),
+    /// `Repeat` was given a step that would never reach the end
+    NonPositiveStep(i32),
+    /// `Repeat` would repeat its body more often than allowed
+    TooManyRepeats(i64),
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::UnknownVar(name) => write!(f, "unknown variable '{}'", name),
+            Self::UnknownPrimitive(name) => write!(f, "unknown primitive '{}'", name),
+            Self::DivideByZero => write!(f, "division by zero"),
+            Self::EmptyChoice => write!(f, "choice between no options"),
+            Self::EmptyRange(n) => write!(f, "random value in empty range 0..{}", n),
+            Self::NonPositiveStep(step) => write!(f, "repeat with non-positive step {}", step),
+            Self::TooManyRepeats(count) => write!(
+                f,
+                "repeat {} times, more than the limit of {}",
+                count, MAX_REPEATS
+            ),
+        }
+    }
+}
+
+/// Variable bindings, searched from the most recent binding backwards so that
+/// inner bindings shadow outer ones.
+struct Env {
+    seed: u32,
+    vars: Vec<(String, i32)>,
+}
+
+impl Env {
+    fn get(&self, name: &str) -> Result<i32, TemplateError> {
+        self.vars
+            .iter()
+            .rev()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| *v)
+            .ok_or_else(|| TemplateError::UnknownVar(name.to_string()))
+    }
+}
+
+impl Expr {
+    fn eval(&self, env: &Env) -> Result<i32, TemplateError> {
+        Ok(match self {
+            Expr::Const(x) => *x,
+            Expr::Var(name) => env.get(name)?,
+            Expr::Add(a, b) => a.eval(env)?.wrapping_add(b.eval(env)?),
+            Expr::Sub(a, b) => a.eval(env)?.wrapping_sub(b.eval(env)?),
+            Expr::Mul(a, b) => a.eval(env)?.wrapping_mul(b.eval(env)?),
+            Expr::Div(a, b) => a
+                .eval(env)?
+                .checked_div(b.eval(env)?)
+                .ok_or(TemplateError::DivideByZero)?,
+            Expr::Min(a, b) => a.eval(env)?.min(b.eval(env)?),
+            Expr::Max(a, b) => a.eval(env)?.max(b.eval(env)?),
+            Expr::Choose(idx, options) => {
+                if options.is_empty() {
+                    return Err(TemplateError::EmptyChoice);
+                }
+                options[idx.eval(env)?.rem_euclid(options.len() as i32) as usize].eval(env)?
+            },
+            Expr::Rand(salt, n) => {
+                let n = n.eval(env)?;
+                if n <= 0 {
+                    return Err(TemplateError::EmptyRange(n));
+                }
+                (RandomField::new(env.seed).get(Vec3::new(*salt as i32, 0, 0)) % n as u32) as i32
+            },
+        })
+    }
+}
+
+fn eval2(v: &Vec2Expr, env: &Env) -> Result<Vec2<i32>, TemplateError> {
+    Ok(Vec2::new(v.0.eval(env)?, v.1.eval(env)?))
+}
+
+fn eval3(v: &Vec3Expr, env: &Env) -> Result<Vec3<i32>, TemplateError> {
+    Ok(Vec3::new(v.0.eval(env)?, v.1.eval(env)?, v.2.eval(env)?))
+}
+
+impl PrimitiveSpec {
+    fn build(
+        &self,
+        env: &mut Env,
+        named: &HashMap<String, Id<Primitive>>,
+        prim: &mut dyn FnMut(Primitive) -> Id<Primitive>,
+    ) -> Result<Id<Primitive>, TemplateError> {
+        let aabb = |min: &Vec3Expr, max: &Vec3Expr, env: &Env| -> Result<_, TemplateError> {
+            Ok(Aabb {
+                min: eval3(min, env)?,
+                max: eval3(max, env)?,
+            })
+        };
+
+        Ok(match self {
+            PrimitiveSpec::Empty => prim(Primitive::Empty),
+            PrimitiveSpec::Aabb { min, max } => prim(Primitive::Aabb(aabb(min, max, env)?)),
+            PrimitiveSpec::Pyramid { min, max, inset } => prim(Primitive::Pyramid {
+                aabb: aabb(min, max, env)?,
+                inset: inset.eval(env)?,
+            }),
+            PrimitiveSpec::Cylinder { min, max } => prim(Primitive::Cylinder(aabb(min, max, env)?)),
+            PrimitiveSpec::Cone { min, max } => prim(Primitive::Cone(aabb(min, max, env)?)),
+            PrimitiveSpec::Sphere { min, max } => prim(Primitive::Sphere(aabb(min, max, env)?)),
+            PrimitiveSpec::Plane {
+                min,
+                max,
+                origin,
+                gradient,
+            } => prim(Primitive::Plane(
+                Aabr {
+                    min: eval2(min, env)?,
+                    max: eval2(max, env)?,
+                },
+                eval3(origin, env)?,
+                Vec2::new(gradient.0, gradient.1),
+            )),
+            PrimitiveSpec::And(a, b) => {
+                let (a, b) = (a.build(env, named, prim)?, b.build(env, named, prim)?);
+                prim(Primitive::And(a, b))
+            },
+            PrimitiveSpec::Or(a, b) => {
+                let (a, b) = (a.build(env, named, prim)?, b.build(env, named, prim)?);
+                prim(Primitive::Or(a, b))
+            },
+            PrimitiveSpec::Xor(a, b) => {
+                let (a, b) = (a.build(env, named, prim)?, b.build(env, named, prim)?);
+                prim(Primitive::Xor(a, b))
+            },
+            PrimitiveSpec::Diff(a, b) => {
+                let (a, b) = (a.build(env, named, prim)?, b.build(env, named, prim)?);
+                prim(Primitive::Diff(a, b))
+            },
+            PrimitiveSpec::Rotate(a, (r0, r1, r2)) => {
+                let a = a.build(env, named, prim)?;
+                prim(Primitive::Rotate(
+                    a,
+                    Mat3::new(r0.0, r0.1, r0.2, r1.0, r1.1, r1.2, r2.0, r2.1, r2.2),
+                ))
+            },
+            PrimitiveSpec::Repeat {
+                var,
+                from,
+                to,
+                step,
+                body,
+            } => {
+                let (from, to, step) = (from.eval(env)?, to.eval(env)?, step.eval(env)?);
+                if step <= 0 {
+                    return Err(TemplateError::NonPositiveStep(step));
+                }
+                let count =
+                    (i64::from(to) - i64::from(from) + i64::from(step) - 1) / i64::from(step);
+                if count > MAX_REPEATS {
+                    return Err(TemplateError::TooManyRepeats(count));
+                }
+                let mut union = prim(Primitive::Empty);
+                for i in (from..to).step_by(step as usize) {
+                    env.vars.push((var.clone(), i));
+                    let item = body.build(env, named, prim);
+                    env.vars.pop();
+                    union = prim(Primitive::Or(union, item?));
+                }
+                union
+            },
+            PrimitiveSpec::Named(name) => *named
+                .get(name)
+                .ok_or_else(|| TemplateError::UnknownPrimitive(name.clone()))?,
+        })
+    }
+}
+
+impl FillSpec {
+    fn to_fill(&self, roof_color: Rgb<u8>) -> Fill {
+        let rgb = |(r, g, b): (u8, u8, u8)| Rgb::new(r, g, b);
+        match self {
+            FillSpec::Air => Fill::Block(Block::empty()),
+            FillSpec::Block(kind, col) => Fill::Block(Block::new(*kind, rgb(*col))),
+            FillSpec::Brick(kind, col, range) => Fill::Brick(*kind, rgb(*col), *range),
+            FillSpec::Sprite(kind, ori) => Fill::Block(
+                Block::air(*kind)
+                    .with_ori(*ori)
+                    .unwrap_or_else(|| Block::air(*kind)),
+            ),
+            FillSpec::Roof(kind) => Fill::Block(Block::new(*kind, roof_color)),
+        }
+    }
+}
+
+impl BuildingTemplate {
+    fn render_inner(
+        &self,
+        env: &mut Env,
+        roof_color: Rgb<u8>,
+        prim: &mut dyn FnMut(Primitive) -> Id<Primitive>,
+        fill: &mut dyn FnMut(Id<Primitive>, Fill),
+    ) -> Result<(), TemplateError> {
+        for (name, expr) in &self.vars {
+            let value = expr.eval(env)?;
+            env.vars.push((name.clone(), value));
+        }
+
+        let mut named = HashMap::new();
+        for (name, spec) in &self.prims {
+            let id = spec.build(env, &named, prim)?;
+            named.insert(name.clone(), id);
+        }
+
+        for (spec, fill_spec) in &self.fills {
+            let id = spec.build(env, &named, prim)?;
+            fill(id, fill_spec.to_fill(roof_color));
+        }
+
+        Ok(())
+    }
+}
+
+/// A plot whose structure is generated from a [`BuildingTemplate`].
+pub struct Template {
+    template: AssetHandle<BuildingTemplate>,
+    seed: u32,
+    vars: Vec<(String, i32)>,
+    roof_color: Rgb<u8>,
+}
+
+impl Template {
+    /// Bind the template's variables for the given plot, returning `None` if
+    /// the template could not be evaluated.
+    pub fn generate(
+        land: &Land,
+        rng: &mut impl Rng,
+        site: &Site,
+        tile_aabr: Aabr<i32>,
+        template: AssetHandle<BuildingTemplate>,
+    ) -> Option<Self> {
+        let spec = template.read();
+        let bounds = Aabr {
+            min: site.tile_wpos(tile_aabr.min),
+            max: site.tile_wpos(tile_aabr.max),
+        };
+        let center = bounds.center();
+        let ori = site.road_side(tile_aabr).unwrap_or(Ori::South);
+        let door = match ori {
+            Ori::North => Vec2::new(center.x, bounds.max.y),
+            Ori::East => Vec2::new(bounds.max.x, center.y),
+            Ori::South => Vec2::new(center.x, bounds.min.y),
+            Ori::West => Vec2::new(bounds.min.x, center.y),
+        };
+        let seed = rng.gen();
+        let levels = rng.gen_range(spec.levels.0..spec.levels.1.max(spec.levels.0 + 1));
+
+        let vars = vec![
+            ("min_x".to_string(), bounds.min.x),
+            ("min_y".to_string(), bounds.min.y),
+            ("max_x".to_string(), bounds.max.x),
+            ("max_y".to_string(), bounds.max.y),
+            ("width".to_string(), bounds.size().w),
+            ("length".to_string(), bounds.size().h),
+            (
+                "alt".to_string(),
+                land.get_alt_approx(site.tile_center_wpos(tile_aabr.center())) as i32 + 2,
+            ),
+            ("levels".to_string(), levels as i32),
+            ("ori".to_string(), ori as u8 as i32),
+            ("door_x".to_string(), door.x),
+            ("door_y".to_string(), door.y),
+            ("tile_size".to_string(), TILE_SIZE as i32),
+            ("seed".to_string(), seed as i32),
+        ];
+        let roof_color = spec
+            .roof_colors
+            .choose(rng)
+            .map_or(Rgb::new(80, 80, 80), |&(r, g, b)| Rgb::new(r, g, b));
+
+        let this = Self {
+            template,
+            seed,
+            vars,
+            roof_color,
+        };
+
+        // Evaluate the template once up front so that broken templates are reported
+        // during generation rather than every time a chunk is rendered.
+        let mut store = Store::default();
+        match this.render_with(&spec, &mut |p| store.insert(p), &mut |_, _| {}) {
+            Ok(()) => Some(this),
+            Err(err) => {
+                warn!(%err, "Failed to evaluate building template");
+                None
+            },
+        }
+    }
+
+    fn render_with(
+        &self,
+        spec: &BuildingTemplate,
+        prim: &mut dyn FnMut(Primitive) -> Id<Primitive>,
+        fill: &mut dyn FnMut(Id<Primitive>, Fill),
+    ) -> Result<(), TemplateError> {
+        let mut env = Env {
+            seed: self.seed,
+            vars: self.vars.clone(),
+        };
+        spec.render_inner(&mut env, self.roof_color, prim, fill)
+    }
+}
+
+impl Structure for Template {
+    fn render<F: FnMut(Primitive) -> Id<Primitive>, G: FnMut(Id<Primitive>, Fill)>(
+        &self,
+        _site: &Site,
+        mut prim: F,
+        mut fill: G,
+    ) {
+        // The template may have been hot-reloaded since generation, so errors are
+        // still possible here.
+        if let Err(err) = self.render_with(&self.template.read(), &mut prim, &mut fill) {
+            warn!(%err, "Failed to evaluate building template");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_building_templates() {
+        let manifest = TemplateManifest::load_expect("world.site2.manifest").read();
+        for choices in manifest.0.values() {
+            for (_, specifier) in &choices.templates {
+                let spec = BuildingTemplate::load_expect(specifier).read();
+                let size = TILE_SIZE as i32 * 3;
+                let mut env = Env {
+                    seed: 0,
+                    vars: vec![
+                        ("min_x".to_string(), 0),
+                        ("min_y".to_string(), 0),
+                        ("max_x".to_string(), size),
+                        ("max_y".to_string(), size),
+                        ("width".to_string(), size),
+                        ("length".to_string(), size),
+                        ("alt".to_string(), 0),
+                        ("levels".to_string(), spec.levels.0 as i32),
+                        ("ori".to_string(), 0),
+                        ("door_x".to_string(), size / 2),
+                        ("door_y".to_string(), size),
+                        ("tile_size".to_string(), TILE_SIZE as i32),
+                        ("seed".to_string(), 0),
+                    ],
+                };
+                let mut store = Store::default();
+                spec.render_inner(
+                    &mut env,
+                    Rgb::zero(),
+                    &mut |p| store.insert(p),
+                    &mut |_, _| {},
+                )
+                .unwrap_or_else(|err| panic!("Invalid template {}: {}", specifier, err));
+            }
+        }
+    }
+
+    #[test]
+    fn test_invalid_expressions() {
+        let env = Env {
+            seed: 0,
+            vars: Vec::new(),
+        };
+        assert!(matches!(
+            Expr::Choose(Box::new(Expr::Const(0)), Vec::new()).eval(&env),
+            Err(TemplateError::EmptyChoice)
+        ));
+        assert!(matches!(
+            Expr::Rand(0, Box::new(Expr::Const(0))).eval(&env),
+            Err(TemplateError::EmptyRange(0))
+        ));
+        assert!(matches!(
+            Expr::Div(Box::new(Expr::Const(1)), Box::new(Expr::Const(0))).eval(&env),
+            Err(TemplateError::DivideByZero)
+        ));
+    }
+
+    #[test]
+    fn test_repeat_limit() {
+        let repeat = |to| PrimitiveSpec::Repeat {
+            var: "i".to_string(),
+            from: Expr::Const(0),
+            to: Expr::Const(to),
+            step: Expr::Const(1),
+            body: Box::new(PrimitiveSpec::Empty),
+        };
+        let mut env = Env {
+            seed: 0,
+            vars: Vec::new(),
+        };
+        let mut store = Store::default();
+        assert!(
+            repeat(MAX_REPEATS as i32)
+                .build(&mut env, &HashMap::new(), &mut |p| store.insert(p))
+                .is_ok()
+        );
+        assert!(matches!(
+            repeat(i32::MAX).build(&mut env, &HashMap::new(), &mut |p| store.insert(p)),
+            Err(TemplateError::TooManyRepeats(_))
+        ));
+    }
+}
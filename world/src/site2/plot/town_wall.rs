@@ -0,0 +1,180 @@
+use super::*;
+use crate::Land;
+use common::terrain::{Block, BlockKind};
+use vek::*;
+
+/// Tiles between consecutive towers along a straight stretch of wall.
+const TOWER_SPACING: i32 = 8;
+
+/// A defensive wall that encloses a site, with towers at its corners and at
+/// intervals along it, and gates wherever a road crosses it.
+pub struct TownWall {
+    /// The area enclosed by the wall, including the wall itself.
+    tile_aabr: Aabr<i32>,
+    /// The tiles that make up the wall, along with the altitude of the ground
+    /// beneath them.
+    tiles: Vec<(Vec2<i32>, i32)>,
+}
+
+impl TownWall {
+    pub fn generate(land: &Land, site: &Site, tile_aabr: Aabr<i32>) -> Self {
+        Self {
+            tile_aabr,
+            tiles: Self::ring(tile_aabr)
+                .map(|tile| {
+                    let alt = land.get_alt_approx(site.tile_center_wpos(tile)) as i32;
+                    (tile, alt)
+                })
+                .collect(),
+        }
+    }
+
+    /// Whether the given tile is within the area enclosed by the wall.
+    pub fn contains(&self, tile: Vec2<i32>) -> bool {
+        (self.tile_aabr.min.x..self.tile_aabr.max.x).contains(&tile.x)
+            && (self.tile_aabr.min.y..self.tile_aabr.max.y).contains(&tile.y)
+    }
+
+    /// The tiles along the inside edge of the given (exclusive) area.
+    pub fn ring(aabr: Aabr<i32>) -> impl Iterator<Item = Vec2<i32>> {
+        let (min, max) = (aabr.min, aabr.max - 1);
+        (min.x..max.x)
+            .map(move |x| Vec2::new(x, min.y))
+            .chain((min.y..max.y).map(move |y| Vec2::new(max.x, y)))
+            .chain((min.x + 1..=max.x).map(move |x| Vec2::new(x, max.y)))
+            .chain((min.y + 1..=max.y).map(move |y| Vec2::new(min.x, y)))
+    }
+
+    /// The kind of tile that the wall should have at the given position along
+    /// the ring, ignoring gates.
+    pub fn tile_kind_at(aabr: Aabr<i32>, tile: Vec2<i32>) -> TileKind {
+        let (min, max) = (aabr.min, aabr.max - 1);
+        let on_x_side = tile.y == min.y || tile.y == max.y;
+        let on_y_side = tile.x == min.x || tile.x == max.x;
+        if on_x_side && on_y_side {
+            TileKind::Tower(RoofKind::Pyramid)
+        } else if on_x_side {
+            if (tile.x - min.x) % TOWER_SPACING == 0 {
+                TileKind::Tower(RoofKind::Parapet)
+            } else {
+                TileKind::Wall(Ori::East)
+            }
+        } else if (tile.y - min.y) % TOWER_SPACING == 0 {
+            TileKind::Tower(RoofKind::Parapet)
+        } else {
+            TileKind::Wall(Ori::North)
+        }
+    }
+
+    /// The direction along the wall at the given tile.
+    fn dir_at(&self, tile: Vec2<i32>) -> Vec2<i32> {
+        if tile.y == self.tile_aabr.min.y || tile.y == self.tile_aabr.max.y - 1 {
+            Ori::East.dir()
+        } else {
+            Ori::North.dir()
+        }
+    }
+}
+
+impl Structure for TownWall {
+    fn render<F: FnMut(Primitive) -> Id<Primitive>, G: FnMut(Id<Primitive>, Fill)>(
+        &self,
+        site: &Site,
+        mut prim: F,
+        mut fill: G,
+    ) {
+        let wall_height = 12;
+        let wall_thickness = 2;
+        let foundations = 8;
+        let gate_height = 7;
+        let tower_height = 6;
+        let ts = TILE_SIZE as i32;
+        let wall_rgb = Rgb::new(60, 60, 65);
+
+        for &(tile, alt) in &self.tiles {
+            let wpos = site.tile_wpos(tile);
+            let dir = self.dir_at(tile);
+            // Offset of the wall from the edge of the tile, across the wall
+            let across = dir.yx();
+            let inset = (ts - wall_thickness) / 2;
+
+            match site.tiles.get(tile).kind {
+                TileKind::Wall(_) | TileKind::Gate => {
+                    let body = prim(Primitive::Aabb(Aabb {
+                        min: (wpos + across * inset).with_z(alt - foundations),
+                        max: (wpos + dir * ts + across * (inset + wall_thickness))
+                            .with_z(alt + wall_height),
+                    }));
+                    fill(body, Fill::Brick(BlockKind::Rock, wall_rgb, 12));
+
+                    // Walkway and crenellations
+                    let walkway = prim(Primitive::Aabb(Aabb {
+                        min: (wpos + across * (inset - 1)).with_z(alt + wall_height - 1),
+                        max: (wpos + dir * ts + across * (inset + wall_thickness + 1))
+                            .with_z(alt + wall_height),
+                    }));
+                    fill(walkway, Fill::Brick(BlockKind::Rock, wall_rgb, 12));
+                    for i in (0..ts).step_by(2) {
+                        for side in [inset - 1, inset + wall_thickness].iter() {
+                            let merlon = prim(Primitive::Aabb(Aabb {
+                                min: (wpos + dir * i + across * *side).with_z(alt + wall_height),
+                                max: (wpos + dir * (i + 1) + across * (*side + 1))
+                                    .with_z(alt + wall_height + 1),
+                            }));
+                            fill(merlon, Fill::Brick(BlockKind::Rock, wall_rgb, 12));
+                        }
+                    }
+
+                    if site.tiles.get(tile).kind == TileKind::Gate {
+                        let opening = prim(Primitive::Aabb(Aabb {
+                            min: (wpos + dir + across * (inset - 1)).with_z(alt),
+                            max: (wpos + dir * (ts - 1) + across * (inset + wall_thickness + 1))
+                                .with_z(alt + gate_height),
+                        }));
+                        fill(opening, Fill::Block(Block::empty()));
+                    }
+                },
+                TileKind::Tower(roof) => {
+                    let top = alt + wall_height + tower_height;
+                    let tower = prim(Primitive::Aabb(Aabb {
+                        min: wpos.with_z(alt - foundations),
+                        max: (wpos + ts).with_z(top),
+                    }));
+                    fill(tower, Fill::Brick(BlockKind::Rock, wall_rgb, 12));
+
+                    match roof {
+                        RoofKind::Pyramid => {
+                            let roof_lip = 1;
+                            let roof_height = ts / 2 + roof_lip + 1;
+                            fill(
+                                prim(Primitive::Pyramid {
+                                    aabb: Aabb {
+                                        min: (wpos - roof_lip).with_z(top),
+                                        max: (wpos + ts + roof_lip).with_z(top + roof_height),
+                                    },
+                                    inset: roof_height,
+                                }),
+                                Fill::Brick(BlockKind::Wood, Rgb::new(40, 5, 11), 10),
+                            );
+                        },
+                        RoofKind::Parapet => {
+                            let outer = prim(Primitive::Aabb(Aabb {
+                                min: (wpos - 1).with_z(top),
+                                max: (wpos + ts + 1).with_z(top + 2),
+                            }));
+                            let inner = prim(Primitive::Aabb(Aabb {
+                                min: wpos.with_z(top),
+                                max: (wpos + ts).with_z(top + 2),
+                            }));
+                            fill(
+                                prim(Primitive::Xor(outer, inner)),
+                                Fill::Brick(BlockKind::Rock, wall_rgb, 12),
+                            );
+                        },
+                    }
+                },
+                _ => {},
+            }
+        }
+    }
+}
@@ -0,0 +1,143 @@
+use super::*;
+use crate::Land;
+use common::terrain::{Block, BlockKind, SpriteKind};
+use rand::prelude::*;
+use vek::*;
+
+/// An open-sided, roofed workshop containing crafting stations.
+pub struct Workshop {
+    bounds: Aabr<i32>,
+    alt: i32,
+    stations: Vec<SpriteKind>,
+    roof_color: Rgb<u8>,
+}
+
+impl Workshop {
+    pub fn generate(land: &Land, rng: &mut impl Rng, site: &Site, tile_aabr: Aabr<i32>) -> Self {
+        let mut stations = vec![
+            SpriteKind::CraftingBench,
+            SpriteKind::Forge,
+            SpriteKind::Anvil,
+            SpriteKind::Cauldron,
+            SpriteKind::CookingPot,
+            SpriteKind::Loom,
+            SpriteKind::SpinningWheel,
+            SpriteKind::TanningRack,
+        ];
+        stations.shuffle(rng);
+        // Placed first so that every town has a bank and a mailbox, however small the
+        // workshop
+        stations.insert(0, SpriteKind::Strongbox);
+        stations.insert(1, SpriteKind::Mailbox);
+
+        Self {
+            bounds: Aabr {
+                min: site.tile_wpos(tile_aabr.min),
+                max: site.tile_wpos(tile_aabr.max),
+            },
+            alt: land.get_alt_approx(site.tile_center_wpos(tile_aabr.center())) as i32 + 1,
+            stations,
+            roof_color: {
+                let colors = [
+                    Rgb::new(60, 35, 20),
+                    Rgb::new(45, 28, 21),
+                    Rgb::new(55, 45, 11),
+                ];
+                *colors.choose(rng).unwrap()
+            },
+        }
+    }
+}
+
+impl Structure for Workshop {
+    fn render<F: FnMut(Primitive) -> Id<Primitive>, G: FnMut(Id<Primitive>, Fill)>(
+        &self,
+        _site: &Site,
+        mut prim: F,
+        mut fill: G,
+    ) {
+        let height = 6;
+        let foundations = 12;
+        let ts = TILE_SIZE as i32;
+
+        // Foundations and floor
+        fill(
+            prim(Primitive::Aabb(Aabb {
+                min: self.bounds.min.with_z(self.alt - foundations),
+                max: (self.bounds.max + 1).with_z(self.alt),
+            })),
+            Fill::Block(Block::new(BlockKind::Rock, Rgb::new(31, 33, 32))),
+        );
+        fill(
+            prim(Primitive::Aabb(Aabb {
+                min: self.bounds.min.with_z(self.alt),
+                max: (self.bounds.max + 1).with_z(self.alt + height),
+            })),
+            Fill::Block(Block::empty()),
+        );
+
+        // Posts at the corners of every tile along the edge
+        let mut posts = prim(Primitive::Empty);
+        for x in (self.bounds.min.x..=self.bounds.max.x).step_by(ts as usize) {
+            for &y in [self.bounds.min.y, self.bounds.max.y].iter() {
+                let post = prim(Primitive::Aabb(Aabb {
+                    min: Vec3::new(x, y, self.alt),
+                    max: Vec3::new(x + 1, y + 1, self.alt + height),
+                }));
+                posts = prim(Primitive::Or(posts, post));
+            }
+        }
+        for y in (self.bounds.min.y..=self.bounds.max.y).step_by(ts as usize) {
+            for &x in [self.bounds.min.x, self.bounds.max.x].iter() {
+                let post = prim(Primitive::Aabb(Aabb {
+                    min: Vec3::new(x, y, self.alt),
+                    max: Vec3::new(x + 1, y + 1, self.alt + height),
+                }));
+                posts = prim(Primitive::Or(posts, post));
+            }
+        }
+        fill(
+            posts,
+            Fill::Block(Block::new(BlockKind::Wood, Rgb::new(55, 25, 8))),
+        );
+
+        // Roof
+        let roof_lip = 1;
+        let roof_height = (self.bounds.max - self.bounds.min).reduce_min() / 2 + roof_lip + 1;
+        fill(
+            prim(Primitive::Pyramid {
+                aabb: Aabb {
+                    min: (self.bounds.min - roof_lip).with_z(self.alt + height),
+                    max: (self.bounds.max + 1 + roof_lip).with_z(self.alt + height + roof_height),
+                },
+                inset: roof_height,
+            }),
+            Fill::Block(Block::new(BlockKind::Wood, self.roof_color)),
+        );
+
+        // Crafting stations, spaced out along the middle of the longer axis
+        let size = self.bounds.size();
+        let (dir, len, start) = if size.w >= size.h {
+            (
+                Vec2::unit_x(),
+                size.w,
+                Vec2::new(self.bounds.min.x + 2, self.bounds.center().y),
+            )
+        } else {
+            (
+                Vec2::unit_y(),
+                size.h,
+                Vec2::new(self.bounds.center().x, self.bounds.min.y + 2),
+            )
+        };
+        for (i, station) in (0..len - 2).step_by(3).zip(self.stations.iter()) {
+            fill(
+                prim(Primitive::Aabb(Aabb {
+                    min: (start + dir * i).with_z(self.alt),
+                    max: (start + dir * i + 1).with_z(self.alt + 1),
+                })),
+                Fill::Block(Block::air(*station)),
+            );
+        }
+    }
+}
@@ -212,7 +212,11 @@ impl Tile {
     pub fn is_obstacle(&self) -> bool {
         matches!(
             self.kind,
-            TileKind::Hazard(_) | TileKind::Building | TileKind::Castle | TileKind::Wall(_)
+            TileKind::Hazard(_)
+                | TileKind::Building
+                | TileKind::Castle
+                | TileKind::Wall(_)
+                | TileKind::Tower(_)
         )
     }
 }